@@ -5,29 +5,234 @@ export interface PtzPosition {
   zoom: number;
 }
 
+/** Camera white-balance mode, with explicit gains for manual balance. */
+export type WhiteBalanceSetting =
+  | "Auto"
+  | "Indoor"
+  | "Outdoor"
+  | "OnePush"
+  | { Manual: { red_gain: number; blue_gain: number } };
+
+/** Pan/tilt adjustment produced by a click in the video frame. */
+export interface ClickDelta {
+  pan_delta: number;
+  tilt_delta: number;
+}
+
+/** Last-known reachability of an endpoint, from the commands sent to it. */
+export type ConnectionStatus =
+  | "Unknown"
+  | "Ok"
+  | { Failed: { since: number; reason: string } };
+
+/** A command sent to a camera, as kept in the command log. */
+export interface CommandLogEntry {
+  /** Unix time in milliseconds. */
+  timestamp: number;
+  endpoint_id: string;
+  /** Command name and arguments, e.g. "move_absolute(0.5, 0.1, 0.0)". */
+  command: string;
+  /** Null when the command succeeded. */
+  error: string | null;
+}
+
+/** A camera command that can be previewed without sending it. */
+export type PtzCommand =
+  | { command: "move_absolute"; pan: number; tilt: number; zoom: number }
+  | { command: "zoom_to"; zoom: number }
+  | { command: "recall_preset"; index: number }
+  | { command: "store_preset"; index: number };
+
+/** One request a command would put on the wire. */
+export type EncodedRequest =
+  /** Protocol payload as space-separated hex bytes, without transport framing. */
+  | { kind: "bytes"; hex: string }
+  | { kind: "http"; method: string; url: string; body?: string | null };
+
+/** What a command would send to the active camera. */
+export interface CommandPreview {
+  endpoint_id: string;
+  /** The command as it would appear in the command log. */
+  command: string;
+  requests: EncodedRequest[];
+}
+
+/** How a camera carries out relative pan/tilt moves. */
+export type RelativeMode = "NativeSpeed" | "ViaAbsolute";
+
+/** How a VISCA camera carries out absolute moves. */
+export type AbsoluteStrategy = "AbsolutePosition" | "NearestNativePreset";
+
+/** Tally lamp state following the switcher's program/preview bus. */
+export type TallyState = "Off" | "Preview" | "Program";
+
+/** Who made a camera and what it is, as reported by the camera itself. */
+export interface CameraInfo {
+  vendor: string;
+  model: string;
+  firmware: string | null;
+}
+
+/** What a camera's protocol can do, so unsupported controls can be hidden. */
+export interface Capabilities {
+  supports_absolute: boolean;
+  supports_position: boolean;
+  supports_continuous: boolean;
+  supports_continuous_zoom: boolean;
+  supports_focus: boolean;
+  supports_focus_position: boolean;
+  supports_exposure: boolean;
+  supports_white_balance: boolean;
+  supports_image_flip: boolean;
+  supports_menu: boolean;
+  supports_tally: boolean;
+  supports_ndi_name: boolean;
+  supports_picture_profile: boolean;
+  supports_native_presets: boolean;
+  supports_power: boolean;
+  max_preset_slots: number;
+}
+
+/** Cursor movement in a camera's on-screen menu. */
+export type MenuDirection = "Up" | "Down" | "Left" | "Right";
+
 /** Supported PTZ protocols. */
-export type PtzProtocol = "Ndi" | "Visca" | "PanasonicAw" | "BirdDogRest" | "Simulated";
+export type PtzProtocol =
+  | "Ndi"
+  | "Visca"
+  | "PanasonicAw"
+  | "BirdDogRest"
+  | "PelcoD"
+  | "Onvif"
+  | "Simulated";
+
+/** Divisors mapping raw camera position units to the normalized range. */
+export interface PositionScale {
+  pan_max: number;
+  tilt_max: number;
+  zoom_max: number;
+}
+
+/** Raw VISCA pan/tilt range of a camera model. */
+export interface ViscaCalibration {
+  pan_min: number;
+  pan_max: number;
+  tilt_min: number;
+  tilt_max: number;
+}
+
+/** Software pan/tilt limits in normalized units. */
+export interface PtzLimits {
+  pan_min: number;
+  pan_max: number;
+  tilt_min: number;
+  tilt_max: number;
+}
 
 /** Protocol-specific connection configuration. */
 export type ProtocolConfig =
-  | { type: "Ndi" }
-  | { type: "Visca"; host: string; port: number }
+  | { type: "Ndi"; source_name?: string }
+  | {
+      type: "Visca";
+      host: string;
+      port: number;
+      limits?: PtzLimits;
+      /** Tally lamp opcode bytes after `81 01`; omitted = `7E 01 0A`. */
+      tally_opcode?: [number, number, number] | null;
+      /** Gamma select opcode bytes after `81 01`; omitted = `04 5B`. */
+      gamma_opcode?: [number, number] | null;
+      /** Picture profile opcode bytes after `81 01`; omitted = `7E 04 5F`. */
+      picture_profile_opcode?: [number, number, number] | null;
+      /** Raw full-tele zoom position; omitted = `0x4000`. */
+      zoom_max?: number | null;
+      /** Raw full-tele position with digital zoom; omitted = optical-only zoom. */
+      digital_zoom_max?: number | null;
+      /** Digital zoom on/off opcode bytes after `81 01`; omitted = `04 06`. */
+      digital_zoom_opcode?: [number, number] | null;
+      /** Raw pan/tilt range; omitted = the Sony range. */
+      calibration?: ViscaCalibration | null;
+      /** Send zoom only after pan/tilt reports completion in absolute moves. */
+      wait_for_completion?: boolean;
+      /** Local IP to send from; omitted = let the OS choose. */
+      bind_address?: string | null;
+      /** Absolute moves recall the nearest native preset instead; omitted = `AbsolutePosition`. */
+      absolute_strategy?: AbsoluteStrategy;
+    }
   | {
       type: "PanasonicAw";
       host: string;
       port: number;
       username?: string;
       password?: string;
+      move_speed?: number;
+      /** Top of the APS speed table: 0x29 on extended-range firmware; omitted = 0x1D. */
+      move_speed_max?: number | null;
+      relative_mode?: RelativeMode;
+    }
+  | {
+      type: "BirdDogRest";
+      host: string;
+      port: number;
+      username?: string;
+      password?: string;
+      position_scale?: PositionScale;
+    }
+  | { type: "PelcoD"; host: string; port: number; address: number }
+  | {
+      type: "Onvif";
+      host: string;
+      port: number;
+      username?: string;
+      password?: string;
     }
-  | { type: "BirdDogRest"; host: string; port: number }
   | { type: "Simulated" };
 
-/** A camera endpoint for PTZ control. */
+/** A camera endpoint for PTZ control; payload of `endpoint-created` and `endpoint-updated`. */
 export interface CameraEndpoint {
   id: string;
   name: string;
   protocol: PtzProtocol;
   config: ProtocolConfig;
+  /** Tag color as lowercase `#rrggbb`; `#rgb` and CSS names are accepted. */
+  color?: string | null;
+  /** Icon name shown next to the endpoint. */
+  icon?: string | null;
+}
+
+/** Health of a camera endpoint as seen by the health monitor. */
+export type EndpointStatus = "Connected" | "Degraded" | "Disconnected";
+
+/** Last-known status of an endpoint; payload of `endpoint-status-changed`. */
+export interface EndpointStatusReport {
+  endpoint_id: string;
+  status: EndpointStatus;
+}
+
+/** Outcome of an endpoint connection test. */
+/** Live state of a VISCA link, for troubleshooting flaky cameras. */
+export interface ViscaDiagnostics {
+  /** Sequence number the next command will carry. */
+  sequence: number;
+  /** Whether a socket to the camera is open. */
+  connected: boolean;
+  /** Last datagram received from the camera, header included, as hex. */
+  last_reply_hex?: string | null;
+}
+
+export interface ConnectionTestResult {
+  ok: boolean;
+  /** Round-trip time of the test, in milliseconds. */
+  latency_ms: number;
+  /** Camera model or firmware, if the camera reported one. */
+  model?: string | null;
+  /** Human-readable summary for display. */
+  message: string;
+}
+
+/** Connection test outcome for one endpoint; `test_all_endpoints` returns one per endpoint. */
+export interface EndpointTestResult {
+  endpoint_id: string;
+  result: ConnectionTestResult;
 }
 
 /** A single preset definition. */
@@ -38,6 +243,45 @@ export interface Preset {
   tilt: number;
   zoom: number;
   color: string;
+  /** Camera preset memory slot used for recall, if bound. */
+  native_slot?: number;
+  /** Focus applied on recall (0 = nearest, 1 = infinity). */
+  focus?: number | null;
+  /** White balance applied on recall. */
+  white_balance?: WhiteBalanceSetting | null;
+  /** Keyboard shortcut that recalls the preset, e.g. "F1" or "Ctrl+1". */
+  hotkey?: string | null;
+  /** Preview frame from when the preset was captured, as base64 JPEG. */
+  thumbnail?: string | null;
+}
+
+/** Whether a preset change can be undone or redone. */
+export interface UndoState {
+  can_undo: boolean;
+  can_redo: boolean;
+}
+
+/** Fields for a new preset in a batch create. */
+export interface PresetInput {
+  name: string;
+  pan: number;
+  tilt: number;
+  zoom: number;
+  color?: string;
+  hotkey?: string | null;
+}
+
+/** An item of a batch operation that was not applied. */
+export interface BatchFailure {
+  /** Position of the item in the request. */
+  index: number;
+  error: string;
+}
+
+/** Outcome of a batch operation: the items applied and those rejected. */
+export interface BatchResult<T> {
+  succeeded: T[];
+  failed: BatchFailure[];
 }
 
 /** A named collection of presets for a camera setup. */
@@ -47,6 +291,49 @@ export interface PresetProfile {
   camera_fov_degrees: number;
   endpoint_id?: string;
   presets: Preset[];
+  tour?: TourStep[];
+  /** Host-side interpolation for preset recall; omitted = move directly. */
+  transition?: PresetTransition;
+  /** Preset the home command recalls; omitted = the camera's own home. */
+  home_preset_id?: string | null;
+  /** How zoom narrows the FOV for click-to-move; omitted = linear. */
+  lens_curve?: LensCurve;
+  /** The head's travel, for converting degrees; omitted = scale by the FOV. */
+  mechanical_range?: MechanicalRange | null;
+  /** Soft pan/tilt limits in degrees for absolute and relative moves. */
+  limits_degrees?: DegreeLimits | null;
+}
+
+/** How far a head pans and tilts either side of center, in degrees. */
+export interface MechanicalRange {
+  pan_degrees: number;
+  tilt_degrees: number;
+}
+
+/** Software pan/tilt limits in degrees from center; right and up are positive. */
+export interface DegreeLimits {
+  pan_min: number;
+  pan_max: number;
+  tilt_min: number;
+  tilt_max: number;
+}
+
+/** Zoom to magnification mapping; `optical` is the lens's zoom ratio, reached geometrically. */
+export type LensCurve = "linear" | { optical: number };
+
+/** Easing curve for interpolated preset transitions. */
+export type Easing = "Linear" | "EaseInOut";
+
+/** Smooth preset recall settings for a profile. */
+export interface PresetTransition {
+  duration_ms: number;
+  easing?: Easing;
+}
+
+/** One stop on a preset tour. */
+export interface TourStep {
+  preset_id: string;
+  dwell_ms: number;
 }
 
 /** Application mode. */
@@ -66,6 +353,64 @@ export interface AppSettings {
   camera_fov_degrees: number;
   active_profile_id?: string;
   video_source?: VideoSourceConfig;
+  position_poll_interval_ms?: number;
+  profile_backup_count?: number;
+  gamepad?: GamepadSettings;
+  /** Fail PTZ commands when no endpoint is active. */
+  strict_commands?: boolean;
+  /** Unlock advanced tools such as raw protocol commands. */
+  developer_mode?: boolean;
+  /** Most jog commands sent to one camera per second; 0 removes the cap. */
+  command_rate_limit?: number;
+  /** Shaping applied to pan, tilt and zoom jog velocities. */
+  response_curve?: ResponseCurve | null;
+  /** How long discovered NDI sources are reused, in ms; 0 disables the cache. */
+  ndi_source_cache_ttl_ms?: number;
+  /** How long a jog runs without a command or keepalive before the camera stops, in ms; 0 never stops. */
+  deadman_window_ms?: number;
+  /** Drive time of a relative nudge on cameras that nudge by time, scaled by the nudge's size. */
+  nudge_dwell?: NudgeDwell;
+}
+
+/** Shortest and longest drive time of a relative nudge, in ms. */
+export interface NudgeDwell {
+  min_ms: number;
+  max_ms: number;
+}
+
+/** Jog input to velocity mapping; `exponent` raises the input's magnitude to that power. */
+export type ResponseCurve = "linear" | "squared" | "cubic" | { exponent: number };
+
+/** Gamepad stick and trigger tuning. */
+export interface GamepadSettings {
+  /** Axis values below this magnitude count as centred (0 to 0.9). */
+  deadzone: number;
+  invert_pan: boolean;
+  invert_tilt: boolean;
+  invert_zoom: boolean;
+}
+
+/** Stick velocities from the gamepad before the response curve, -1 to 1 each. */
+export interface GamepadVelocity {
+  pan: number;
+  tilt: number;
+  zoom: number;
+}
+
+/** A rotated profiles.json backup; index 1 is the most recent. */
+export interface ProfileBackup {
+  index: number;
+  modified_ms: number;
+  profile_count: number;
+}
+
+/** A data file that failed to load at startup; the store started from defaults. */
+export interface LoadWarning {
+  /** e.g. `profiles.json`. */
+  file: string;
+  /** Where the unreadable file was moved, if it could be kept. */
+  preserved_as: string | null;
+  error: string;
 }
 
 /** NDI source descriptor. */
@@ -88,3 +433,50 @@ export interface VideoSource {
   deviceId?: string;
   ndiName?: string;
 }
+
+/** How imported items with an existing id are handled. */
+export type CollisionStrategy = "Skip" | "Overwrite" | "Rename";
+
+/** Per-kind outcome of a config bundle import. */
+export interface MergeCounts {
+  added: number;
+  overwritten: number;
+  renamed: number;
+  skipped: number;
+}
+
+/** Result of importing a config bundle. */
+export interface ImportSummary {
+  endpoints: MergeCounts;
+  profiles: MergeCounts;
+  settings_applied: boolean;
+}
+
+/** A command sent to the WebSocket control server at `/control`. */
+export type WsControlCommand =
+  | { type: "move_absolute"; pan: number; tilt: number; zoom: number }
+  | { type: "move_relative"; pan_delta: number; tilt_delta: number }
+  | { type: "continuous_move"; pan_speed: number; tilt_speed: number }
+  | { type: "keepalive" }
+  | { type: "zoom"; zoom: number }
+  | { type: "stop" }
+  | { type: "home" }
+  | { type: "recall_preset"; number: number }
+  | { type: "get_position" };
+
+/** A request to the WebSocket control server; `id` is echoed in the reply. */
+export interface WsControlRequest {
+  id?: number;
+  command: WsControlCommand;
+}
+
+/** A message from the WebSocket control server. */
+export type WsServerMessage =
+  | {
+      type: "response";
+      id: number | null;
+      ok: boolean;
+      error?: string;
+      position?: PtzPosition;
+    }
+  | { type: "position"; position: PtzPosition };
@@ -1,3 +1,64 @@
 fn main() {
+    #[cfg(feature = "ndi")]
+    ndi::generate_bindings();
+
     tauri_build::build();
 }
+
+#[cfg(feature = "ndi")]
+mod ndi {
+    use std::env;
+    use std::path::PathBuf;
+
+    /// Generate bindings from the NDI SDK headers and link its runtime library.
+    pub fn generate_bindings() {
+        println!("cargo:rerun-if-env-changed=NDI_SDK_DIR");
+        let sdk_dir = PathBuf::from(
+            env::var("NDI_SDK_DIR")
+                .expect("NDI_SDK_DIR must point at the NDI SDK when the `ndi` feature is enabled"),
+        );
+
+        let target_os = env::var("CARGO_CFG_TARGET_OS").unwrap_or_default();
+        let target_arch = env::var("CARGO_CFG_TARGET_ARCH").unwrap_or_default();
+        let (include_dir, lib_dir, lib_name) = match target_os.as_str() {
+            "windows" => (
+                sdk_dir.join("Include"),
+                sdk_dir.join("Lib").join("x64"),
+                "Processing.NDI.Lib.x64",
+            ),
+            "macos" => (
+                sdk_dir.join("include"),
+                sdk_dir.join("lib").join("macOS"),
+                "ndi",
+            ),
+            _ => (
+                sdk_dir.join("include"),
+                sdk_dir
+                    .join("lib")
+                    .join(format!("{}-linux-gnu", target_arch)),
+                "ndi",
+            ),
+        };
+
+        println!("cargo:rustc-link-search=native={}", lib_dir.display());
+        println!("cargo:rustc-link-lib=dylib={}", lib_name);
+
+        let header = include_dir.join("Processing.NDI.Lib.h");
+        println!("cargo:rerun-if-changed={}", header.display());
+
+        let bindings = bindgen::Builder::default()
+            .header(header.to_string_lossy())
+            .clang_arg(format!("-I{}", include_dir.display()))
+            .allowlist_function("NDIlib_.*")
+            .allowlist_type("NDIlib_.*")
+            .allowlist_var("NDIlib_.*")
+            .prepend_enum_name(false)
+            .generate()
+            .expect("Failed to generate NDI SDK bindings");
+
+        let out_dir = PathBuf::from(env::var("OUT_DIR").expect("OUT_DIR not set"));
+        bindings
+            .write_to_file(out_dir.join("ndi_bindings.rs"))
+            .expect("Failed to write NDI SDK bindings");
+    }
+}
@@ -1,81 +1,229 @@
 use crate::ptz::controller::{PtzController, PtzError};
-use crate::ptz::types::PtzPosition;
+use crate::ptz::preview::{EncodedRequest, PtzCommand};
+use crate::ptz::retry::{map_request_error, RetryPolicy};
+use crate::ptz::timeout;
+use crate::ptz::types::{
+    CameraInfo, Capabilities, ExposureMode, PositionScale, PtzPosition, TallyState,
+    WhiteBalanceSetting,
+};
 use async_trait::async_trait;
+use tokio::sync::{Mutex, OnceCell};
 
 /// BirdDog REST API client for BirdDog PTZ cameras.
 /// Uses HTTP POST/GET requests to the BirdDog API (default port 8080).
 pub struct BirdDogClient {
     base_url: String,
     client: reqwest::Client,
+    retry_policy: RetryPolicy,
+    /// Username and password for firmware that requires a `/login` session.
+    credentials: Option<(String, String)>,
+    /// Session token from the last successful `/login`.
+    session_token: Mutex<Option<String>>,
+    /// Raw position range, configured or detected on first position query.
+    position_scale: Mutex<Option<PositionScale>>,
+    /// Model and firmware from `/about`, fetched on first `identify`.
+    info: OnceCell<Option<CameraInfo>>,
 }
 
 impl BirdDogClient {
     pub fn new(host: &str, port: u16) -> Result<Self, PtzError> {
-        crate::ptz::types::validate_host(host).map_err(PtzError::ConnectionFailed)?;
+        crate::ptz::controller::validate_address(host, port)?;
         Ok(Self {
-            base_url: format!("http://{}:{}", host, port),
+            base_url: format!("http://{}", crate::ptz::types::host_with_port(host, port)),
             client: reqwest::Client::new(),
+            retry_policy: RetryPolicy::default(),
+            credentials: None,
+            session_token: Mutex::new(None),
+            position_scale: Mutex::new(None),
+            info: OnceCell::new(),
         })
     }
 
+    /// Use a fixed raw position range instead of auto-detecting it.
+    pub fn with_position_scale(self, scale: Option<PositionScale>) -> Self {
+        Self {
+            position_scale: Mutex::new(scale),
+            ..self
+        }
+    }
+
+    /// Return the position scale, detecting it from `/capabilities` on first use.
+    /// Cameras that do not report ranges are assumed to return normalized values.
+    async fn position_scale(&self) -> PositionScale {
+        let mut scale = self.position_scale.lock().await;
+        if let Some(scale) = *scale {
+            return scale;
+        }
+        match self.get_json("capabilities").await {
+            Ok(caps) => {
+                let detected = parse_position_scale(&caps);
+                *scale = Some(detected);
+                detected
+            }
+            // Not cached, so the next position query detects it again
+            Err(e) => {
+                log::warn!("BirdDog capabilities query failed, assuming normalized positions: {e}");
+                PositionScale::default()
+            }
+        }
+    }
+
+    /// Set the retry policy for transient network failures.
+    pub fn with_retry_policy(mut self, policy: RetryPolicy) -> Self {
+        self.retry_policy = policy;
+        self
+    }
+
+    /// Authenticate with the camera. Only applied when a password is given,
+    /// since older firmware exposes the API without a login.
+    pub fn with_credentials(mut self, username: Option<String>, password: Option<String>) -> Self {
+        self.credentials = password.map(|p| (username.unwrap_or_else(|| "admin".to_string()), p));
+        self
+    }
+
+    pub fn has_credentials(&self) -> bool {
+        self.credentials.is_some()
+    }
+
+    /// Return the cached session token, performing the `/login` handshake if needed.
+    async fn session_token(&self) -> Result<Option<String>, PtzError> {
+        let Some((username, password)) = &self.credentials else {
+            return Ok(None);
+        };
+
+        let mut token = self.session_token.lock().await;
+        if token.is_none() {
+            let url = format!("{}/login", self.base_url);
+            let response = self
+                .client
+                .post(&url)
+                .json(&serde_json::json!({
+                    "username": username,
+                    "password": password
+                }))
+                .timeout(timeout::or_default(timeout::HTTP_TIMEOUT))
+                .send()
+                .await
+                .map_err(map_request_error)?;
+            check_auth_status(response.status())?;
+
+            let body: serde_json::Value = response
+                .json()
+                .await
+                .map_err(|e| PtzError::CommandFailed(e.to_string()))?;
+            *token = Some(parse_login_token(&body)?);
+        }
+        Ok(token.clone())
+    }
+
     async fn post_json(
         &self,
         endpoint: &str,
         body: serde_json::Value,
     ) -> Result<serde_json::Value, PtzError> {
-        let url = format!("{}/{}", self.base_url, endpoint);
-        let response = self
-            .client
-            .post(&url)
-            .json(&body)
-            .timeout(std::time::Duration::from_secs(5))
-            .send()
+        self.send_json(reqwest::Method::POST, endpoint, Some(&body))
             .await
-            .map_err(|e| PtzError::ConnectionFailed(e.to_string()))?;
-
-        let json = response
-            .json()
-            .await
-            .map_err(|e| PtzError::CommandFailed(e.to_string()))?;
-
-        Ok(json)
     }
 
     async fn get_json(&self, endpoint: &str) -> Result<serde_json::Value, PtzError> {
+        self.send_json(reqwest::Method::GET, endpoint, None).await
+    }
+
+    async fn send_json(
+        &self,
+        method: reqwest::Method,
+        endpoint: &str,
+        body: Option<&serde_json::Value>,
+    ) -> Result<serde_json::Value, PtzError> {
         let url = format!("{}/{}", self.base_url, endpoint);
-        let response = self
-            .client
-            .get(&url)
-            .timeout(std::time::Duration::from_secs(5))
-            .send()
+        self.retry_policy
+            .run(|| async {
+                match self.send_once(&method, &url, body).await {
+                    // The session may have expired: log in once more, and
+                    // give up if the camera still refuses
+                    Err(PtzError::AuthFailed(_)) if self.has_credentials() => {
+                        self.send_once(&method, &url, body).await
+                    }
+                    result => result,
+                }
+            })
             .await
-            .map_err(|e| PtzError::ConnectionFailed(e.to_string()))?;
+    }
+
+    async fn send_once(
+        &self,
+        method: &reqwest::Method,
+        url: &str,
+        body: Option<&serde_json::Value>,
+    ) -> Result<serde_json::Value, PtzError> {
+        let mut request = self
+            .client
+            .request(method.clone(), url)
+            .timeout(timeout::or_default(timeout::HTTP_TIMEOUT));
+        if let Some(body) = body {
+            request = request.json(body);
+        }
+        if let Some(token) = self.session_token().await? {
+            request = request.bearer_auth(token);
+        }
+
+        let response = request.send().await.map_err(map_request_error)?;
+        if let Err(e) = check_auth_status(response.status()) {
+            // Drop the token so the next attempt logs in again
+            *self.session_token.lock().await = None;
+            return Err(e);
+        }
 
-        let json = response
+        response
             .json()
             .await
-            .map_err(|e| PtzError::CommandFailed(e.to_string()))?;
+            .map_err(|e| PtzError::CommandFailed(e.to_string()))
+    }
+}
 
-        Ok(json)
+/// Reject 401/403 responses with an error explaining that credentials are
+/// needed. Not retryable: resending the same credentials won't help.
+fn check_auth_status(status: reqwest::StatusCode) -> Result<(), PtzError> {
+    if status == reqwest::StatusCode::UNAUTHORIZED || status == reqwest::StatusCode::FORBIDDEN {
+        return Err(PtzError::AuthFailed(format!(
+            "BirdDog authentication required (HTTP {}): check the endpoint username and password",
+            status.as_u16()
+        )));
     }
+    Ok(())
+}
+
+/// Extract the session token from a `/login` response body.
+fn parse_login_token(body: &serde_json::Value) -> Result<String, PtzError> {
+    body["token"]
+        .as_str()
+        .filter(|t| !t.is_empty())
+        .map(str::to_string)
+        .ok_or_else(|| {
+            PtzError::AuthFailed(
+                "BirdDog authentication required: login response contained no token".to_string(),
+            )
+        })
 }
 
 #[async_trait]
 impl PtzController for BirdDogClient {
     async fn move_absolute(&self, pan: f64, tilt: f64, zoom: f64) -> Result<(), PtzError> {
-        self.post_json(
-            "ptz",
-            serde_json::json!({
-                "pan": pan,
-                "tilt": tilt,
-                "zoom": zoom,
-                "mode": "absolute"
-            }),
-        )
-        .await?;
+        let (endpoint, body) = command_body(&PtzCommand::MoveAbsolute { pan, tilt, zoom });
+        self.post_json(endpoint, body).await?;
         Ok(())
     }
 
+    /// The session token, if any, is left out.
+    fn encode(&self, command: &PtzCommand) -> Result<Vec<EncodedRequest>, PtzError> {
+        let (endpoint, body) = command_body(command);
+        Ok(vec![EncodedRequest::Http {
+            method: "POST".to_string(),
+            url: format!("{}/{}", self.base_url, endpoint),
+            body: Some(body.to_string()),
+        }])
+    }
+
     async fn move_relative(&self, pan_delta: f64, tilt_delta: f64) -> Result<(), PtzError> {
         self.post_json(
             "ptz",
@@ -90,46 +238,31 @@ impl PtzController for BirdDogClient {
     }
 
     async fn zoom_to(&self, zoom: f64) -> Result<(), PtzError> {
-        self.post_json(
-            "ptz",
-            serde_json::json!({
-                "zoom": zoom,
-                "mode": "absolute"
-            }),
-        )
-        .await?;
+        let (endpoint, body) = command_body(&PtzCommand::ZoomTo { zoom });
+        self.post_json(endpoint, body).await?;
         Ok(())
     }
 
     async fn recall_preset(&self, preset_index: u8) -> Result<(), PtzError> {
-        self.post_json(
-            "recall",
-            serde_json::json!({
-                "preset": preset_index
-            }),
-        )
-        .await?;
+        let (endpoint, body) = command_body(&PtzCommand::RecallPreset {
+            index: preset_index,
+        });
+        self.post_json(endpoint, body).await?;
         Ok(())
     }
 
     async fn store_preset(&self, preset_index: u8) -> Result<(), PtzError> {
-        self.post_json(
-            "store",
-            serde_json::json!({
-                "preset": preset_index
-            }),
-        )
-        .await?;
+        let (endpoint, body) = command_body(&PtzCommand::StorePreset {
+            index: preset_index,
+        });
+        self.post_json(endpoint, body).await?;
         Ok(())
     }
 
     async fn get_position(&self) -> Result<PtzPosition, PtzError> {
         let response = self.get_json("ptz/position").await?;
-        Ok(PtzPosition {
-            pan: response["pan"].as_f64().unwrap_or(0.0),
-            tilt: response["tilt"].as_f64().unwrap_or(0.0),
-            zoom: response["zoom"].as_f64().unwrap_or(0.0),
-        })
+        let scale = self.position_scale().await;
+        Ok(parse_position(&response, &scale))
     }
 
     async fn test_connection(&self) -> Result<(), PtzError> {
@@ -137,29 +270,701 @@ impl PtzController for BirdDogClient {
         Ok(())
     }
 
+    async fn probe(&self) -> Result<Option<String>, PtzError> {
+        let about = self.get_json("about").await?;
+        Ok(parse_about_model(&about))
+    }
+
+    async fn identify(&self) -> Result<Option<CameraInfo>, PtzError> {
+        let info = self
+            .info
+            .get_or_try_init(|| async {
+                let about = self.get_json("about").await?;
+                Ok::<_, PtzError>(parse_about_info(&about))
+            })
+            .await?;
+        Ok(info.clone())
+    }
+
+    /// Start continuous pan/tilt movement.
+    ///
+    /// `POST /ptz` with `{"mode": "continuous", "pan": <-1..1>, "tilt": <-1..1>}`,
+    /// where the values are signed velocities (negative = left/down).
     async fn continuous_move(&self, pan_speed: f64, tilt_speed: f64) -> Result<(), PtzError> {
-        self.post_json(
-            "ptz",
-            serde_json::json!({
-                "pan": pan_speed,
-                "tilt": tilt_speed,
-                "mode": "velocity"
-            }),
-        )
-        .await?;
+        self.post_json("ptz", continuous_move_body(pan_speed, tilt_speed))
+            .await?;
         Ok(())
     }
 
+    /// Stop pan/tilt movement.
+    ///
+    /// `POST /ptz` with `{"mode": "continuous", "pan": 0.0, "tilt": 0.0}`.
     async fn stop(&self) -> Result<(), PtzError> {
-        self.post_json(
+        self.post_json("ptz", continuous_move_body(0.0, 0.0))
+            .await?;
+        Ok(())
+    }
+
+    /// `POST /tally` with `{"mode": "program" | "preview" | "off"}`.
+    async fn set_tally(&self, state: TallyState) -> Result<(), PtzError> {
+        self.post_json("tally", tally_body(state)).await?;
+        Ok(())
+    }
+
+    /// `POST /encodesetup` with `{"NDIName": <name>}`.
+    async fn set_ndi_name(&self, name: &str) -> Result<(), PtzError> {
+        self.post_json("encodesetup", ndi_name_body(name)?).await?;
+        Ok(())
+    }
+
+    /// `POST /exposure` with `{"iris": "F2.8"}`, the nearest documented stop.
+    async fn set_iris(&self, iris: f64) -> Result<(), PtzError> {
+        self.post_json("exposure", iris_body(iris)?).await?;
+        Ok(())
+    }
+
+    /// `POST /exposure` with `{"gain": <level>}`, one level per 3 dB.
+    async fn set_gain(&self, gain_db: u8) -> Result<(), PtzError> {
+        self.post_json("exposure", gain_body(gain_db)?).await?;
+        Ok(())
+    }
+
+    /// `POST /exposure` with `{"shutter": "1/250"}`.
+    async fn set_shutter(&self, step: u8) -> Result<(), PtzError> {
+        self.post_json("exposure", shutter_body(step)?).await?;
+        Ok(())
+    }
+
+    /// `POST /exposure` with `{"mode": "FULL-AUTO" | "MANUAL"}`.
+    async fn set_exposure_mode(&self, mode: ExposureMode) -> Result<(), PtzError> {
+        self.post_json("exposure", exposure_mode_body(mode)).await?;
+        Ok(())
+    }
+
+    /// `POST /whitebalance` with `{"mode": ...}`, plus the red and blue
+    /// gains for manual balance.
+    async fn set_white_balance(&self, setting: WhiteBalanceSetting) -> Result<(), PtzError> {
+        self.post_json("whitebalance", white_balance_body(setting))
+            .await?;
+        Ok(())
+    }
+
+    fn capabilities(&self) -> Capabilities {
+        Capabilities {
+            supports_absolute: true,
+            supports_position: true,
+            supports_continuous: true,
+            supports_exposure: true,
+            supports_white_balance: true,
+            supports_tally: true,
+            supports_ndi_name: true,
+            supports_native_presets: true,
+            max_preset_slots: 64,
+            ..Capabilities::default()
+        }
+    }
+}
+
+/// Read the raw position ranges from a `/capabilities` response.
+/// Missing or non-positive ranges fall back to 1.0 (already normalized).
+fn parse_position_scale(caps: &serde_json::Value) -> PositionScale {
+    let range = |key: &str| {
+        caps[key]
+            .as_f64()
+            .filter(|v| v.is_finite() && *v > 0.0)
+            .unwrap_or(1.0)
+    };
+    PositionScale {
+        pan_max: range("pan_max"),
+        tilt_max: range("tilt_max"),
+        zoom_max: range("zoom_max"),
+    }
+}
+
+/// Convert a `/ptz/position` response to a normalized position.
+fn parse_position(response: &serde_json::Value, scale: &PositionScale) -> PtzPosition {
+    let field = |key: &str| match response[key].as_f64() {
+        Some(v) => v,
+        None => {
+            log::warn!("BirdDog position response missing '{key}', defaulting to 0.0");
+            0.0
+        }
+    };
+    PtzPosition {
+        pan: (field("pan") / scale.pan_max).clamp(-1.0, 1.0),
+        tilt: (field("tilt") / scale.tilt_max).clamp(-1.0, 1.0),
+        zoom: (field("zoom") / scale.zoom_max).clamp(0.0, 1.0),
+    }
+}
+
+/// Clamp a velocity to -1.0..1.0, treating non-finite input as stopped.
+fn clamp_velocity(value: f64) -> f64 {
+    if value.is_finite() {
+        value.clamp(-1.0, 1.0)
+    } else {
+        0.0
+    }
+}
+
+/// The endpoint a command is posted to, and its body.
+fn command_body(command: &PtzCommand) -> (&'static str, serde_json::Value) {
+    match *command {
+        PtzCommand::MoveAbsolute { pan, tilt, zoom } => (
             "ptz",
             serde_json::json!({
-                "pan": 0.0,
-                "tilt": 0.0,
-                "mode": "velocity"
+                "pan": pan,
+                "tilt": tilt,
+                "zoom": zoom,
+                "mode": "absolute"
             }),
-        )
-        .await?;
-        Ok(())
+        ),
+        PtzCommand::ZoomTo { zoom } => (
+            "ptz",
+            serde_json::json!({
+                "zoom": zoom,
+                "mode": "absolute"
+            }),
+        ),
+        PtzCommand::RecallPreset { index } => ("recall", serde_json::json!({ "preset": index })),
+        PtzCommand::StorePreset { index } => ("store", serde_json::json!({ "preset": index })),
+    }
+}
+
+/// Build the `/ptz` body for a continuous (velocity) move.
+fn continuous_move_body(pan_speed: f64, tilt_speed: f64) -> serde_json::Value {
+    serde_json::json!({
+        "pan": clamp_velocity(pan_speed),
+        "tilt": clamp_velocity(tilt_speed),
+        "mode": "continuous"
+    })
+}
+
+/// Model and firmware from an `/about` response, e.g. `P200 (firmware 5.5.1)`.
+/// Field names vary between firmware releases.
+fn parse_about_model(about: &serde_json::Value) -> Option<String> {
+    let model = about_field(about, ABOUT_MODEL_KEYS);
+    let firmware = about_field(about, ABOUT_FIRMWARE_KEYS);
+    match (model, firmware) {
+        (Some(model), Some(firmware)) => Some(format!("{} (firmware {})", model, firmware)),
+        (Some(model), None) => Some(model),
+        (None, Some(firmware)) => Some(format!("firmware {}", firmware)),
+        (None, None) => None,
+    }
+}
+
+/// Camera identity from an `/about` reply; `None` without a model name.
+fn parse_about_info(about: &serde_json::Value) -> Option<CameraInfo> {
+    Some(CameraInfo {
+        vendor: "BirdDog".into(),
+        model: about_field(about, ABOUT_MODEL_KEYS)?,
+        firmware: about_field(about, ABOUT_FIRMWARE_KEYS),
+    })
+}
+
+/// `/about` keys that carry the model name, across firmware generations.
+const ABOUT_MODEL_KEYS: &[&str] = &["Model", "model", "HardwareVersion", "HostName"];
+/// `/about` keys that carry the firmware version.
+const ABOUT_FIRMWARE_KEYS: &[&str] = &["FirmwareVersion", "firmware_version", "Version", "version"];
+
+/// First non-empty string among `keys` in an `/about` reply.
+fn about_field(about: &serde_json::Value, keys: &[&str]) -> Option<String> {
+    keys.iter()
+        .filter_map(|key| about[*key].as_str())
+        .map(str::trim)
+        .find(|value| !value.is_empty())
+        .map(str::to_string)
+}
+
+/// Longest NDI source name the camera accepts.
+const MAX_NDI_NAME_LEN: usize = 64;
+
+/// Build the `/tally` body for a tally state.
+fn tally_body(state: TallyState) -> serde_json::Value {
+    let mode = match state {
+        TallyState::Off => "off",
+        TallyState::Preview => "preview",
+        TallyState::Program => "program",
+    };
+    serde_json::json!({ "mode": mode })
+}
+
+/// Iris stops in the BirdDog exposure options, from closed to fully open.
+const IRIS_STOPS: &[&str] = &[
+    "CLOSE", "F14", "F11", "F9.6", "F8.0", "F6.8", "F5.6", "F4.8", "F4.0", "F3.4", "F2.8", "F2.4",
+    "F2.0", "F1.8",
+];
+
+/// BirdDog gain levels are 3 dB apart, up to this.
+const MAX_GAIN_DB: u8 = 45;
+const GAIN_STEP_DB: u8 = 3;
+
+/// Shutter speeds in the BirdDog exposure options; step 0 is the nominal
+/// 1/60 with no extra shutter.
+const SHUTTER_SPEEDS: &[&str] = &[
+    "1/60", "1/90", "1/100", "1/125", "1/180", "1/250", "1/350", "1/500", "1/725", "1/1000",
+    "1/1500", "1/2000", "1/3000", "1/4000", "1/6000", "1/10000",
+];
+
+/// Build the `/exposure` body for a normalized iris opening.
+fn iris_body(iris: f64) -> Result<serde_json::Value, PtzError> {
+    if !iris.is_finite() || !(0.0..=1.0).contains(&iris) {
+        return Err(PtzError::ProtocolError(format!(
+            "Iris {iris} out of range 0.0..=1.0"
+        )));
+    }
+    let stop = (iris * (IRIS_STOPS.len() - 1) as f64).round() as usize;
+    Ok(serde_json::json!({ "iris": IRIS_STOPS[stop] }))
+}
+
+/// Build the `/exposure` body for a gain in dB, which must be a BirdDog level.
+fn gain_body(gain_db: u8) -> Result<serde_json::Value, PtzError> {
+    if gain_db > MAX_GAIN_DB || !gain_db.is_multiple_of(GAIN_STEP_DB) {
+        return Err(PtzError::ProtocolError(format!(
+            "Gain {gain_db} dB is not a BirdDog level (0..={MAX_GAIN_DB} in {GAIN_STEP_DB} dB steps)"
+        )));
+    }
+    Ok(serde_json::json!({ "gain": gain_db / GAIN_STEP_DB }))
+}
+
+/// Build the `/exposure` body for a shutter step.
+fn shutter_body(step: u8) -> Result<serde_json::Value, PtzError> {
+    let speed = SHUTTER_SPEEDS.get(step as usize).ok_or_else(|| {
+        PtzError::ProtocolError(format!(
+            "Shutter step {step} out of range 0..={}",
+            SHUTTER_SPEEDS.len() - 1
+        ))
+    })?;
+    Ok(serde_json::json!({ "shutter": speed }))
+}
+
+/// Build the `/exposure` body for an exposure mode.
+fn exposure_mode_body(mode: ExposureMode) -> serde_json::Value {
+    let mode = match mode {
+        ExposureMode::Auto => "FULL-AUTO",
+        ExposureMode::Manual => "MANUAL",
+    };
+    serde_json::json!({ "mode": mode })
+}
+
+/// Build the `/whitebalance` body for a white-balance setting.
+fn white_balance_body(setting: WhiteBalanceSetting) -> serde_json::Value {
+    match setting {
+        WhiteBalanceSetting::Auto => serde_json::json!({ "mode": "AUTO" }),
+        WhiteBalanceSetting::Indoor => serde_json::json!({ "mode": "INDOOR" }),
+        WhiteBalanceSetting::Outdoor => serde_json::json!({ "mode": "OUTDOOR" }),
+        WhiteBalanceSetting::OnePush => serde_json::json!({ "mode": "ONEPUSH" }),
+        WhiteBalanceSetting::Manual {
+            red_gain,
+            blue_gain,
+        } => serde_json::json!({
+            "mode": "MANUAL",
+            "red_gain": red_gain,
+            "blue_gain": blue_gain
+        }),
+    }
+}
+
+/// Build the `/encodesetup` body for an NDI name change. BirdDog only takes
+/// letters, digits, spaces, `-`, `_` and `.`, so anything else is refused here.
+fn ndi_name_body(name: &str) -> Result<serde_json::Value, PtzError> {
+    let name = name.trim();
+    if name.is_empty() {
+        return Err(PtzError::CommandFailed(
+            "NDI name cannot be empty".to_string(),
+        ));
+    }
+    if name.chars().count() > MAX_NDI_NAME_LEN {
+        return Err(PtzError::CommandFailed(format!(
+            "NDI name must be at most {} characters",
+            MAX_NDI_NAME_LEN
+        )));
+    }
+    if let Some(c) = name
+        .chars()
+        .find(|c| !(c.is_ascii_alphanumeric() || matches!(c, ' ' | '-' | '_' | '.')))
+    {
+        return Err(PtzError::CommandFailed(format!(
+            "NDI name cannot contain '{}'",
+            c
+        )));
+    }
+    Ok(serde_json::json!({ "NDIName": name }))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+    use std::sync::Arc;
+
+    #[test]
+    fn ipv6_host_is_bracketed_in_base_url() {
+        let client = BirdDogClient::new("::1", 8080).unwrap();
+        assert_eq!(client.base_url, "http://[::1]:8080");
+        let client = BirdDogClient::new("10.0.0.2", 8080).unwrap();
+        assert_eq!(client.base_url, "http://10.0.0.2:8080");
+    }
+
+    #[test]
+    fn invalid_host_is_a_config_error() {
+        assert!(matches!(
+            BirdDogClient::new("admin@10.0.0.2", 8080).err(),
+            Some(PtzError::InvalidConfig(_))
+        ));
+    }
+
+    #[test]
+    fn client_without_password_skips_login() {
+        let client = BirdDogClient::new("10.0.0.2", 8080)
+            .unwrap()
+            .with_credentials(Some("admin".into()), None);
+        assert!(!client.has_credentials());
+    }
+
+    #[test]
+    fn client_built_with_credentials() {
+        let client = BirdDogClient::new("10.0.0.2", 8080)
+            .unwrap()
+            .with_credentials(None, Some("birddog".into()));
+        assert!(client.has_credentials());
+        let (username, password) = client.credentials.as_ref().unwrap();
+        assert_eq!(username, "admin");
+        assert_eq!(password, "birddog");
+    }
+
+    #[test]
+    fn parse_login_token_reads_token() {
+        let body = serde_json::json!({ "token": "abc123" });
+        assert_eq!(parse_login_token(&body).unwrap(), "abc123");
+    }
+
+    #[test]
+    fn parse_login_token_missing_token_is_auth_error() {
+        for body in [serde_json::json!({}), serde_json::json!({ "token": "" })] {
+            match parse_login_token(&body) {
+                Err(PtzError::AuthFailed(msg)) => {
+                    assert!(msg.contains("authentication required"))
+                }
+                other => panic!("Expected AuthFailed, got {other:?}"),
+            }
+        }
+    }
+
+    #[test]
+    fn parse_position_already_normalized() {
+        let response = serde_json::json!({ "pan": 0.5, "tilt": -0.25, "zoom": 0.75 });
+        let pos = parse_position(&response, &PositionScale::default());
+        assert_eq!(pos.pan, 0.5);
+        assert_eq!(pos.tilt, -0.25);
+        assert_eq!(pos.zoom, 0.75);
+    }
+
+    #[test]
+    fn parse_position_scales_raw_counts() {
+        let scale = PositionScale {
+            pan_max: 2448.0,
+            tilt_max: 1200.0,
+            zoom_max: 16384.0,
+        };
+        let response = serde_json::json!({ "pan": -1224, "tilt": 600, "zoom": 16384 });
+        let pos = parse_position(&response, &scale);
+        assert_eq!(pos.pan, -0.5);
+        assert_eq!(pos.tilt, 0.5);
+        assert_eq!(pos.zoom, 1.0);
+    }
+
+    #[test]
+    fn parse_position_clamps_and_defaults_missing_fields() {
+        let response = serde_json::json!({ "pan": 5.0 });
+        let pos = parse_position(&response, &PositionScale::default());
+        assert_eq!(pos.pan, 1.0);
+        assert_eq!(pos.tilt, 0.0);
+        assert_eq!(pos.zoom, 0.0);
+    }
+
+    #[test]
+    fn parse_position_scale_from_capabilities() {
+        let caps = serde_json::json!({ "pan_max": 2448, "tilt_max": 1200, "zoom_max": 16384 });
+        let scale = parse_position_scale(&caps);
+        assert_eq!(scale.pan_max, 2448.0);
+        assert_eq!(scale.tilt_max, 1200.0);
+        assert_eq!(scale.zoom_max, 16384.0);
+
+        let scale = parse_position_scale(&serde_json::json!({ "zoom_max": 0 }));
+        assert_eq!(scale, PositionScale::default());
+    }
+
+    #[tokio::test]
+    async fn failed_scale_detection_is_retried() {
+        let queries = Arc::new(AtomicUsize::new(0));
+        let capabilities = {
+            let queries = queries.clone();
+            move || async move {
+                // The first query fails, as if the camera were still booting
+                if queries.fetch_add(1, Ordering::SeqCst) == 0 {
+                    "starting".to_string()
+                } else {
+                    r#"{"pan_max":2448,"tilt_max":1200,"zoom_max":16384}"#.to_string()
+                }
+            }
+        };
+        let app = axum::Router::new()
+            .route("/capabilities", axum::routing::get(capabilities))
+            .route(
+                "/ptz/position",
+                axum::routing::get(|| async { r#"{"pan":1224,"tilt":0,"zoom":8192}"# }),
+            );
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let port = listener.local_addr().unwrap().port();
+        tokio::spawn(async move { axum::serve(listener, app).await.unwrap() });
+
+        let client = BirdDogClient::new("127.0.0.1", port)
+            .unwrap()
+            .with_retry_policy(RetryPolicy::none());
+        assert_eq!(client.get_position().await.unwrap().pan, 1.0);
+        let pos = client.get_position().await.unwrap();
+        assert_eq!(pos.pan, 0.5);
+        assert_eq!(pos.zoom, 0.5);
+        client.get_position().await.unwrap();
+        assert_eq!(queries.load(Ordering::SeqCst), 2);
+    }
+
+    #[test]
+    fn continuous_move_body_passes_velocities_through() {
+        let body = continuous_move_body(0.5, -0.25);
+        assert_eq!(body["mode"], "continuous");
+        assert_eq!(body["pan"], 0.5);
+        assert_eq!(body["tilt"], -0.25);
+    }
+
+    #[test]
+    fn continuous_move_body_clamps_velocities() {
+        let body = continuous_move_body(3.0, -2.0);
+        assert_eq!(body["pan"], 1.0);
+        assert_eq!(body["tilt"], -1.0);
+
+        let body = continuous_move_body(f64::NAN, f64::INFINITY);
+        assert_eq!(body["pan"], 0.0);
+        assert_eq!(body["tilt"], 0.0);
+    }
+
+    #[test]
+    fn stop_body_is_zero_velocity() {
+        let body = continuous_move_body(0.0, 0.0);
+        assert_eq!(
+            body,
+            serde_json::json!({ "pan": 0.0, "tilt": 0.0, "mode": "continuous" })
+        );
+    }
+
+    #[test]
+    fn auth_status_rejects_401_and_403() {
+        assert!(check_auth_status(reqwest::StatusCode::OK).is_ok());
+        for status in [
+            reqwest::StatusCode::UNAUTHORIZED,
+            reqwest::StatusCode::FORBIDDEN,
+        ] {
+            match check_auth_status(status) {
+                Err(e @ PtzError::AuthFailed(_)) => {
+                    assert!(e.to_string().contains("authentication required"));
+                    assert!(!e.is_retryable());
+                }
+                other => panic!("Expected AuthFailed, got {other:?}"),
+            }
+        }
+    }
+
+    /// Mock camera whose `/login` hands out `token-1`, `token-2`, ... and
+    /// whose position query only accepts `accepted`. Returns the port and
+    /// the login count.
+    async fn spawn_login_camera(accepted: &'static str) -> (u16, Arc<AtomicUsize>) {
+        let logins = Arc::new(AtomicUsize::new(0));
+        let login = {
+            let logins = logins.clone();
+            move || async move {
+                let n = logins.fetch_add(1, Ordering::SeqCst) + 1;
+                format!(r#"{{"token":"token-{}"}}"#, n)
+            }
+        };
+        let position = move |headers: axum::http::HeaderMap| async move {
+            let authorized = headers
+                .get("authorization")
+                .is_some_and(|v| v.as_bytes() == format!("Bearer {}", accepted).as_bytes());
+            if authorized {
+                (axum::http::StatusCode::OK, r#"{"pan":0,"tilt":0,"zoom":0}"#)
+            } else {
+                (axum::http::StatusCode::UNAUTHORIZED, "")
+            }
+        };
+        let app = axum::Router::new()
+            .route("/login", axum::routing::post(login))
+            .route("/capabilities", axum::routing::get(|| async { "{}" }))
+            .route("/ptz/position", axum::routing::get(position));
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let port = listener.local_addr().unwrap().port();
+        tokio::spawn(async move { axum::serve(listener, app).await.unwrap() });
+        (port, logins)
+    }
+
+    #[tokio::test]
+    async fn expired_session_logs_in_again_once() {
+        let (port, logins) = spawn_login_camera("token-2").await;
+        let client = BirdDogClient::new("127.0.0.1", port)
+            .unwrap()
+            .with_credentials(None, Some("birddog".into()))
+            .with_position_scale(Some(PositionScale::default()));
+        client.get_position().await.unwrap();
+        assert_eq!(logins.load(Ordering::SeqCst), 2);
+    }
+
+    #[tokio::test]
+    async fn rejected_credentials_are_not_retried() {
+        let (port, logins) = spawn_login_camera("never").await;
+        let client = BirdDogClient::new("127.0.0.1", port)
+            .unwrap()
+            .with_credentials(None, Some("wrong".into()))
+            .with_position_scale(Some(PositionScale::default()));
+        let result = client.get_position().await;
+        assert!(matches!(result, Err(PtzError::AuthFailed(_))), "{result:?}");
+        assert_eq!(logins.load(Ordering::SeqCst), 2);
+    }
+
+    #[test]
+    fn capabilities_cover_motion_presets_and_image() {
+        let caps = BirdDogClient::new("10.0.0.2", 8080).unwrap().capabilities();
+        assert!(caps.supports_absolute && caps.supports_continuous);
+        assert!(caps.supports_native_presets);
+        assert!(caps.supports_tally && caps.supports_ndi_name);
+        assert!(caps.supports_exposure && caps.supports_white_balance);
+        assert!(!caps.supports_focus && !caps.supports_menu);
+        assert_eq!(caps.max_preset_slots, 64);
+    }
+
+    #[test]
+    fn tally_body_names_the_state() {
+        assert_eq!(
+            tally_body(TallyState::Program),
+            serde_json::json!({ "mode": "program" })
+        );
+        assert_eq!(tally_body(TallyState::Preview)["mode"], "preview");
+        assert_eq!(tally_body(TallyState::Off)["mode"], "off");
+    }
+
+    #[test]
+    fn ndi_name_body_trims_and_validates() {
+        assert_eq!(
+            ndi_name_body("  CAM-2 Stage_Left ").unwrap(),
+            serde_json::json!({ "NDIName": "CAM-2 Stage_Left" })
+        );
+        assert!(ndi_name_body("   ").is_err());
+        assert!(ndi_name_body("Cam (1)").is_err());
+        assert!(ndi_name_body("Cam/1").is_err());
+        assert!(ndi_name_body(&"a".repeat(65)).is_err());
+    }
+
+    #[test]
+    fn about_model_combines_model_and_firmware() {
+        let about = serde_json::json!({
+            "HostName": "BirdDog-P200",
+            "FirmwareVersion": "5.5.1"
+        });
+        assert_eq!(
+            parse_about_model(&about).as_deref(),
+            Some("BirdDog-P200 (firmware 5.5.1)")
+        );
+        let about = serde_json::json!({ "version": "4.0" });
+        assert_eq!(parse_about_model(&about).as_deref(), Some("firmware 4.0"));
+        assert_eq!(parse_about_model(&serde_json::json!({})), None);
+    }
+
+    #[test]
+    fn about_reply_identifies_the_camera() {
+        let about = serde_json::json!({ "Model": "P400", "FirmwareVersion": "5.5.1" });
+        assert_eq!(
+            parse_about_info(&about),
+            Some(CameraInfo {
+                vendor: "BirdDog".into(),
+                model: "P400".into(),
+                firmware: Some("5.5.1".into()),
+            })
+        );
+        assert_eq!(
+            parse_about_info(&serde_json::json!({ "version": "4.0" })),
+            None
+        );
+    }
+
+    #[test]
+    fn encode_move_absolute_as_json_post() {
+        let client = BirdDogClient::new("192.0.2.20", 8080).unwrap();
+        let requests = client
+            .encode(&PtzCommand::MoveAbsolute {
+                pan: 0.5,
+                tilt: -0.25,
+                zoom: 0.0,
+            })
+            .unwrap();
+        let [EncodedRequest::Http { method, url, body }] = requests.as_slice() else {
+            panic!("unexpected requests {:?}", requests);
+        };
+        assert_eq!(method, "POST");
+        assert_eq!(url, "http://192.0.2.20:8080/ptz");
+        let body: serde_json::Value = serde_json::from_str(body.as_deref().unwrap()).unwrap();
+        assert_eq!(
+            body,
+            serde_json::json!({ "pan": 0.5, "tilt": -0.25, "zoom": 0.0, "mode": "absolute" })
+        );
+    }
+
+    #[test]
+    fn exposure_bodies_use_birddog_options() {
+        assert_eq!(
+            iris_body(0.0).unwrap(),
+            serde_json::json!({ "iris": "CLOSE" })
+        );
+        assert_eq!(
+            iris_body(1.0).unwrap(),
+            serde_json::json!({ "iris": "F1.8" })
+        );
+        assert_eq!(gain_body(9).unwrap(), serde_json::json!({ "gain": 3 }));
+        assert_eq!(
+            shutter_body(5).unwrap(),
+            serde_json::json!({ "shutter": "1/250" })
+        );
+        assert_eq!(
+            exposure_mode_body(ExposureMode::Auto),
+            serde_json::json!({ "mode": "FULL-AUTO" })
+        );
+    }
+
+    #[test]
+    fn exposure_values_outside_the_options_are_rejected() {
+        for result in [
+            iris_body(1.5),
+            iris_body(f64::NAN),
+            gain_body(48),
+            gain_body(4),
+            shutter_body(16),
+        ] {
+            assert!(
+                matches!(result, Err(PtzError::ProtocolError(_))),
+                "{result:?}"
+            );
+        }
+    }
+
+    #[test]
+    fn white_balance_body_carries_manual_gains() {
+        assert_eq!(
+            white_balance_body(WhiteBalanceSetting::OnePush),
+            serde_json::json!({ "mode": "ONEPUSH" })
+        );
+        assert_eq!(
+            white_balance_body(WhiteBalanceSetting::Manual {
+                red_gain: 140,
+                blue_gain: 96
+            }),
+            serde_json::json!({ "mode": "MANUAL", "red_gain": 140, "blue_gain": 96 })
+        );
     }
 }
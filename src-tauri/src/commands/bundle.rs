@@ -0,0 +1,80 @@
+use super::endpoints::build_controller;
+use crate::persistence::bundle::{BundleSettings, CollisionStrategy, ConfigBundle, ImportSummary};
+use crate::AppState;
+
+/// Export all endpoints, profiles and shareable settings as a JSON document.
+/// Camera passwords are left out unless `include_passwords` is set.
+#[tauri::command]
+pub async fn export_config_bundle(
+    state: tauri::State<'_, AppState>,
+    include_passwords: Option<bool>,
+) -> Result<String, String> {
+    let endpoints = state.endpoints.lock().await.get_all();
+    let profiles = state.profiles.lock().await.get_profiles();
+    let settings = BundleSettings::from_config(&*state.config.lock().await);
+
+    let mut bundle = ConfigBundle::new(endpoints, profiles, Some(settings));
+    if !include_passwords.unwrap_or(false) {
+        bundle.strip_passwords();
+    }
+    bundle.to_json()
+}
+
+/// Import a bundle produced by `export_config_bundle`.
+/// `strategy` decides what happens to items whose id already exists (default: skip).
+#[tauri::command]
+pub async fn import_config_bundle(
+    state: tauri::State<'_, AppState>,
+    json: String,
+    strategy: Option<CollisionStrategy>,
+    apply_settings: Option<bool>,
+) -> Result<ImportSummary, String> {
+    let strategy = strategy.unwrap_or_default();
+    let mut bundle = ConfigBundle::from_json(&json)?;
+    let imported_ids: Vec<String> = bundle.endpoints.iter().map(|e| e.id.clone()).collect();
+
+    let mut endpoints = state.endpoints.lock().await;
+    let (endpoint_counts, renamed) =
+        endpoints.import(std::mem::take(&mut bundle.endpoints), strategy)?;
+
+    // Reconnect warm controllers whose endpoint was just replaced
+    if strategy == CollisionStrategy::Overwrite {
+        let mut dispatcher = state.ptz_dispatcher.lock().await;
+        for id in &imported_ids {
+            if let (true, Some(endpoint)) = (dispatcher.contains(id), endpoints.get(id)) {
+                dispatcher.add_pooled_controller(
+                    id.clone(),
+                    endpoint.config.fingerprint(),
+                    build_controller(&endpoint.config)?,
+                );
+            }
+        }
+    }
+    drop(endpoints);
+
+    bundle.remap_endpoint_ids(&renamed);
+    let profile_counts = state
+        .profiles
+        .lock()
+        .await
+        .import(std::mem::take(&mut bundle.profiles), strategy)?;
+
+    let mut settings_applied = false;
+    if let (Some(settings), true) = (&bundle.settings, apply_settings.unwrap_or(false)) {
+        let mut config = state.config.lock().await;
+        settings.apply_to(&mut config);
+        config.save()?;
+        settings_applied = true;
+    }
+
+    log::info!(
+        "Imported config bundle: {} endpoint(s), {} profile(s) added",
+        endpoint_counts.added + endpoint_counts.renamed,
+        profile_counts.added + profile_counts.renamed
+    );
+    Ok(ImportSummary {
+        endpoints: endpoint_counts,
+        profiles: profile_counts,
+        settings_applied,
+    })
+}
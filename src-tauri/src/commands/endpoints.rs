@@ -1,14 +1,225 @@
-use crate::ptz::types::{validate_host, CameraEndpoint, ProtocolConfig};
+use super::tour::stop_tour_task;
+use crate::ptz::connection_status::ConnectionStatus;
+use crate::ptz::controller::{PtzController, PtzDispatcher, PtzError};
+use crate::ptz::detect;
+use crate::ptz::health::{self, EndpointStatus, EndpointStatusReport};
+use crate::ptz::poller;
+use crate::ptz::timeout;
+use crate::ptz::types::{
+    CameraEndpoint, CameraInfo, Capabilities, ConnectionTestResult, EndpointTestResult,
+    ProtocolConfig, PtzProtocol, ViscaCalibration,
+};
 use crate::AppState;
+use std::collections::HashMap;
+use std::time::Duration;
 
-/// Validate the host field in a protocol config before persisting.
-fn validate_endpoint_config(config: &ProtocolConfig) -> Result<(), String> {
-    match config {
-        ProtocolConfig::Ndi | ProtocolConfig::Simulated => Ok(()),
-        ProtocolConfig::Visca { host, .. }
-        | ProtocolConfig::PanasonicAw { host, .. }
-        | ProtocolConfig::BirdDogRest { host, .. } => validate_host(host),
+/// Longest a camera may take to answer a connection test during setup.
+const CONNECTION_TEST_TIMEOUT: Duration = Duration::from_secs(1);
+
+/// Most cameras a bulk connection test talks to at once.
+const MAX_CONCURRENT_CONNECTION_TESTS: usize = 8;
+
+/// Emitted with the new [`CameraEndpoint`] once it is saved.
+pub const ENDPOINT_CREATED_EVENT: &str = "endpoint-created";
+
+/// Emitted with the updated [`CameraEndpoint`] once it is saved.
+pub const ENDPOINT_UPDATED_EVENT: &str = "endpoint-updated";
+
+/// Emitted with the deleted endpoint's id, as a string.
+pub const ENDPOINT_DELETED_EVENT: &str = "endpoint-deleted";
+
+/// Emitted with the id, as a string, of the endpoint that became active.
+pub const ENDPOINT_ACTIVATED_EVENT: &str = "endpoint-activated";
+
+/// Tell the frontend an endpoint changed, so it can update its list
+/// without fetching it again.
+fn emit_endpoint_event<S: serde::Serialize + Clone>(
+    app: &tauri::AppHandle,
+    event: &str,
+    payload: S,
+) {
+    use tauri::Emitter;
+
+    if let Err(e) = app.emit(event, payload) {
+        log::warn!("Failed to emit {}: {}", event, e);
+    }
+}
+
+/// Stop the background position poller, if one is running.
+async fn stop_position_polling(state: &AppState) {
+    if let Some(cancel_tx) = state.position_poll_cancel.lock().await.take() {
+        let _ = cancel_tx.send(true);
+    }
+}
+
+/// Start polling the given endpoint's position, replacing any previous poller.
+async fn start_position_polling(app: tauri::AppHandle, state: &AppState, endpoint_id: String) {
+    use tauri::Emitter;
+
+    let interval_ms = state.config.lock().await.position_poll_interval_ms;
+    let positions = state.positions.clone();
+    let id = endpoint_id.clone();
+    let cancel_tx = poller::spawn(
+        state.ptz_dispatcher.clone(),
+        state.current_position.clone(),
+        endpoint_id,
+        Duration::from_millis(interval_ms),
+        move |pos| {
+            if let Err(e) = positions.record(&id, pos) {
+                log::warn!("Failed to save position: {}", e);
+            }
+            if let Err(e) = app.emit(poller::POSITION_CHANGED_EVENT, pos) {
+                log::warn!("Failed to emit position update: {}", e);
+            }
+        },
+    );
+    if let Some(old) = state.position_poll_cancel.lock().await.replace(cancel_tx) {
+        let _ = old.send(true);
+    }
+}
+
+/// Stop the health monitor and forget the last-known status.
+async fn stop_health_monitoring(state: &AppState) {
+    if let Some(cancel_tx) = state.health_check_cancel.lock().await.take() {
+        let _ = cancel_tx.send(true);
     }
+    *state.endpoint_status.lock().await = None;
+}
+
+/// Test an endpoint's connection for the health monitor. The dispatcher is
+/// held only to look up the controller, so an unreachable camera doesn't hold
+/// up commands to the others.
+async fn probe_endpoint(
+    dispatcher: &tokio::sync::Mutex<PtzDispatcher>,
+    endpoint_id: &str,
+) -> Result<(), PtzError> {
+    let (controller, statuses) = {
+        let dispatcher = dispatcher.lock().await;
+        (
+            dispatcher.shared_controller(endpoint_id)?,
+            dispatcher.statuses(),
+        )
+    };
+    let result = controller.test_connection().await;
+    statuses.record(endpoint_id, &result);
+    result
+}
+
+/// Start monitoring the given endpoint, replacing any previous monitor.
+/// A disconnected endpoint has its controller rebuilt from its stored config.
+async fn start_health_monitoring(app: tauri::AppHandle, state: &AppState, endpoint_id: String) {
+    use tauri::Emitter;
+
+    *state.endpoint_status.lock().await = Some(EndpointStatusReport {
+        endpoint_id: endpoint_id.clone(),
+        status: EndpointStatus::Connected,
+    });
+
+    let (cancel_tx, cancel_rx) = tokio::sync::watch::channel(false);
+    let dispatcher = state.ptz_dispatcher.clone();
+    let endpoints = state.endpoints.clone();
+    let endpoint_status = state.endpoint_status.clone();
+
+    tokio::spawn(async move {
+        let check = || {
+            let dispatcher = dispatcher.clone();
+            let endpoint_id = endpoint_id.clone();
+            async move { probe_endpoint(&dispatcher, &endpoint_id).await }
+        };
+        let reconnect = || {
+            let dispatcher = dispatcher.clone();
+            let endpoints = endpoints.clone();
+            let endpoint_id = endpoint_id.clone();
+            async move {
+                let endpoint = endpoints
+                    .lock()
+                    .await
+                    .get(&endpoint_id)
+                    .ok_or("Endpoint not found")?;
+                let controller = build_controller(&endpoint.config)?;
+                dispatcher.lock().await.add_pooled_controller(
+                    endpoint_id.clone(),
+                    endpoint.config.fingerprint(),
+                    controller,
+                );
+                log::info!("Rebuilt controller for endpoint {}", endpoint_id);
+                Ok(())
+            }
+        };
+        let on_change = |status: EndpointStatus| {
+            let report = EndpointStatusReport {
+                endpoint_id: endpoint_id.clone(),
+                status,
+            };
+            log::info!("Endpoint {} is now {:?}", endpoint_id, status);
+            if let Err(e) = app.emit(health::STATUS_CHANGED_EVENT, &report) {
+                log::warn!("Failed to emit endpoint status: {}", e);
+            }
+            let endpoint_status = endpoint_status.clone();
+            async move {
+                *endpoint_status.lock().await = Some(report);
+            }
+        };
+        health::run_health_checks(
+            health::HEALTH_CHECK_INTERVAL,
+            cancel_rx,
+            check,
+            reconnect,
+            on_change,
+        )
+        .await;
+    });
+
+    if let Some(old) = state.health_check_cancel.lock().await.replace(cancel_tx) {
+        let _ = old.send(true);
+    }
+}
+
+/// Last-known connection status of every configured endpoint, from the
+/// commands sent to it. Endpoints nothing has been sent to are `Unknown`.
+#[tauri::command]
+pub async fn get_endpoint_statuses(
+    state: tauri::State<'_, AppState>,
+) -> Result<HashMap<String, ConnectionStatus>, String> {
+    let mut statuses = state.connection_statuses.snapshot();
+    for endpoint in state.endpoints.lock().await.get_all() {
+        statuses
+            .entry(endpoint.id)
+            .or_insert(ConnectionStatus::Unknown);
+    }
+    Ok(statuses)
+}
+
+/// What an endpoint's camera supports, from its connected controller or,
+/// if it has none yet, one built from its configuration.
+#[tauri::command]
+pub async fn get_endpoint_capabilities(
+    state: tauri::State<'_, AppState>,
+    endpoint_id: String,
+) -> Result<Capabilities, String> {
+    let dispatcher = state.ptz_dispatcher.lock().await;
+    if let Ok(controller) = dispatcher.controller(&endpoint_id) {
+        return Ok(controller.capabilities());
+    }
+    drop(dispatcher);
+
+    let endpoints = state.endpoints.lock().await;
+    let endpoint = endpoints.get(&endpoint_id).ok_or("Endpoint not found")?;
+    Ok(build_controller(&endpoint.config)?.capabilities())
+}
+
+/// Vendor, model and firmware of a connected endpoint's camera, or `None`
+/// when its protocol can't report them.
+#[tauri::command]
+pub async fn get_camera_info(
+    state: tauri::State<'_, AppState>,
+    endpoint_id: String,
+) -> Result<Option<CameraInfo>, String> {
+    let dispatcher = state.ptz_dispatcher.lock().await;
+    let controller = dispatcher
+        .controller(&endpoint_id)
+        .map_err(|e| e.to_string())?;
+    controller.identify().await.map_err(|e| e.to_string())
 }
 
 /// Get all configured camera endpoints.
@@ -23,90 +234,263 @@ pub async fn get_endpoints(
 /// Create a new camera endpoint.
 #[tauri::command]
 pub async fn create_endpoint(
+    app: tauri::AppHandle,
     state: tauri::State<'_, AppState>,
     endpoint: CameraEndpoint,
 ) -> Result<CameraEndpoint, String> {
-    validate_endpoint_config(&endpoint.config)?;
-    let mut endpoints = state.endpoints.lock().await;
-    endpoints.create(endpoint)
+    let created = state.endpoints.lock().await.create(endpoint)?;
+    emit_endpoint_event(&app, ENDPOINT_CREATED_EVENT, &created);
+    Ok(created)
 }
 
 /// Update an existing camera endpoint.
 #[tauri::command]
 pub async fn update_endpoint(
+    app: tauri::AppHandle,
     state: tauri::State<'_, AppState>,
     endpoint: CameraEndpoint,
 ) -> Result<CameraEndpoint, String> {
-    validate_endpoint_config(&endpoint.config)?;
+    let updated = save_endpoint(&state, endpoint).await?;
+    emit_endpoint_event(&app, ENDPOINT_UPDATED_EVENT, &updated);
+    Ok(updated)
+}
+
+/// Save changes to an endpoint, reconnecting its warm controller if any.
+async fn save_endpoint(
+    state: &AppState,
+    endpoint: CameraEndpoint,
+) -> Result<CameraEndpoint, String> {
     let mut endpoints = state.endpoints.lock().await;
-    endpoints.update(endpoint)
+    let updated = endpoints.update(endpoint)?;
+    drop(endpoints);
+
+    // Reconnect a warm controller so it picks up the new settings
+    let mut dispatcher = state.ptz_dispatcher.lock().await;
+    if dispatcher.contains(&updated.id) {
+        let controller = build_controller(&updated.config)?;
+        dispatcher.add_pooled_controller(
+            updated.id.clone(),
+            updated.config.fingerprint(),
+            controller,
+        );
+    }
+    Ok(updated)
 }
 
 /// Delete a camera endpoint by ID.
 #[tauri::command]
 pub async fn delete_endpoint(
+    app: tauri::AppHandle,
     state: tauri::State<'_, AppState>,
     endpoint_id: String,
 ) -> Result<(), String> {
-    // If deleting the active endpoint, clear the dispatcher
-    let active_id = state.active_endpoint_id.lock().await.clone();
-    if active_id.as_deref() == Some(&endpoint_id) {
-        let mut dispatcher = state.ptz_dispatcher.lock().await;
-        dispatcher.clear_controller();
-        drop(dispatcher);
-        *state.active_endpoint_id.lock().await = None;
+    remove_endpoint(&state, &endpoint_id).await?;
+    emit_endpoint_event(&app, ENDPOINT_DELETED_EVENT, &endpoint_id);
+    Ok(())
+}
+
+/// Delete an endpoint along with its controller, cached position and, if it
+/// was active, everything following it.
+async fn remove_endpoint(state: &AppState, endpoint_id: &str) -> Result<(), String> {
+    // Drop the endpoint's controller, clearing the active endpoint if it was this one
+    state
+        .ptz_dispatcher
+        .lock()
+        .await
+        .remove_controller(endpoint_id);
+    let mut active_id = state.active_endpoint_id.lock().await;
+    if active_id.as_deref() == Some(endpoint_id) {
+        *active_id = None;
+        stop_position_polling(state).await;
+        stop_health_monitoring(state).await;
+        stop_tour_task(state).await;
+    }
+    drop(active_id);
+    if let Err(e) = state.positions.remove(endpoint_id) {
+        log::warn!("Failed to forget position of '{}': {}", endpoint_id, e);
     }
 
     let mut endpoints = state.endpoints.lock().await;
-    endpoints.delete(&endpoint_id)
+    endpoints.delete(endpoint_id)
 }
 
-/// Clear the active camera endpoint, removing the PTZ controller.
+/// Clear the active camera endpoint. Its controller stays connected for reuse.
 #[tauri::command]
 pub async fn clear_active_endpoint(state: tauri::State<'_, AppState>) -> Result<(), String> {
+    clear_active(&state).await;
+    Ok(())
+}
+
+/// Drop the active endpoint and stop everything that was following it.
+pub(crate) async fn clear_active(state: &AppState) {
     let mut dispatcher = state.ptz_dispatcher.lock().await;
-    dispatcher.clear_controller();
+    dispatcher.clear_active();
     drop(dispatcher);
+    stop_position_polling(state).await;
+    stop_health_monitoring(state).await;
+    stop_tour_task(state).await;
+    state.positions.deactivate();
     *state.active_endpoint_id.lock().await = None;
     log::info!("Active endpoint cleared");
-    Ok(())
+}
+
+/// Create the protocol controller for an endpoint configuration.
+pub(crate) fn build_controller(config: &ProtocolConfig) -> Result<Box<dyn PtzController>, String> {
+    let controller: Box<dyn PtzController> = match config {
+        ProtocolConfig::Ndi { source_name } => {
+            Box::new(crate::ndi::ptz::NdiPtzController::new(source_name))
+        }
+        ProtocolConfig::Visca {
+            host,
+            port,
+            limits,
+            tally_opcode,
+            gamma_opcode,
+            picture_profile_opcode,
+            zoom_max,
+            digital_zoom_max,
+            digital_zoom_opcode,
+            calibration,
+            wait_for_completion,
+            bind_address,
+            absolute_strategy,
+        } => Box::new(
+            crate::visca::client::ViscaClient::new(host, *port)
+                .map_err(|e| format!("Failed to create VISCA client: {}", e))?
+                .with_bind_address(
+                    bind_address
+                        .as_deref()
+                        .map(|address| crate::ptz::types::validate_bind_address(address, host))
+                        .transpose()?,
+                )
+                .with_limits(limits.unwrap_or_default())
+                .with_tally_opcode(tally_opcode.unwrap_or(crate::visca::commands::TALLY_OPCODE))
+                .with_gamma_opcode(gamma_opcode.unwrap_or(crate::visca::commands::GAMMA_OPCODE))
+                .with_picture_profile_opcode(
+                    picture_profile_opcode
+                        .unwrap_or(crate::visca::commands::PICTURE_PROFILE_OPCODE),
+                )
+                .with_zoom_max(zoom_max.unwrap_or(crate::visca::commands::VISCA_ZOOM_MAX))
+                .with_digital_zoom_max(*digital_zoom_max)
+                .with_digital_zoom_opcode(
+                    digital_zoom_opcode.unwrap_or(crate::visca::commands::DIGITAL_ZOOM_OPCODE),
+                )
+                .with_calibration(calibration.unwrap_or_default())
+                .with_wait_for_completion(*wait_for_completion)
+                .with_absolute_strategy(*absolute_strategy),
+        ),
+        ProtocolConfig::PanasonicAw {
+            host,
+            port,
+            move_speed,
+            move_speed_max,
+            relative_mode,
+            ..
+        } => Box::new(
+            crate::panasonic::client::PanasonicClient::new(host, *port)
+                .map_err(|e| format!("Failed to create Panasonic client: {}", e))?
+                .with_move_speed(*move_speed)
+                .with_move_speed_max(
+                    move_speed_max.unwrap_or(crate::panasonic::client::APS_SPEED_MAX_STANDARD),
+                )
+                .with_relative_mode(*relative_mode),
+        ),
+        ProtocolConfig::BirdDogRest {
+            host,
+            port,
+            username,
+            password,
+            position_scale,
+        } => Box::new(
+            crate::birddog::client::BirdDogClient::new(host, *port)
+                .map_err(|e| format!("Failed to create BirdDog client: {}", e))?
+                .with_credentials(username.clone(), password.clone())
+                .with_position_scale(*position_scale),
+        ),
+        ProtocolConfig::PelcoD {
+            host,
+            port,
+            address,
+        } => Box::new(
+            crate::pelco::client::PelcoClient::new(host, *port, *address)
+                .map_err(|e| format!("Failed to create Pelco-D client: {}", e))?,
+        ),
+        ProtocolConfig::Onvif {
+            host,
+            port,
+            username,
+            password,
+        } => Box::new(
+            crate::onvif::client::OnvifClient::new(host, *port)
+                .map_err(|e| format!("Failed to create ONVIF client: {}", e))?
+                .with_credentials(username.clone(), password.clone()),
+        ),
+        ProtocolConfig::Simulated => Box::new(crate::simulator::client::SimulatedController::new()),
+    };
+    Ok(controller)
 }
 
 /// Set the active camera endpoint and wire up the PTZ dispatcher.
 #[tauri::command]
 pub async fn set_active_endpoint(
+    app: tauri::AppHandle,
     state: tauri::State<'_, AppState>,
     endpoint_id: String,
+) -> Result<(), String> {
+    activate_endpoint(app, &state, endpoint_id).await
+}
+
+/// Make an endpoint the active camera, connecting it if no warm controller
+/// is pooled, and start polling and health checks for it.
+pub(crate) async fn activate_endpoint(
+    app: tauri::AppHandle,
+    state: &AppState,
+    endpoint_id: String,
 ) -> Result<(), String> {
     // Look up the endpoint configuration
     let endpoints = state.endpoints.lock().await;
     let endpoint = endpoints.get(&endpoint_id).ok_or("Endpoint not found")?;
     drop(endpoints);
 
-    // Create the appropriate protocol controller
-    let controller: Box<dyn crate::ptz::controller::PtzController> = match &endpoint.config {
-        ProtocolConfig::Ndi => Box::new(crate::ndi::ptz::NdiPtzController::new()),
-        ProtocolConfig::Visca { host, port } => Box::new(
-            crate::visca::client::ViscaClient::new(host, *port)
-                .map_err(|e| format!("Failed to create VISCA client: {}", e))?,
-        ),
-        ProtocolConfig::PanasonicAw { host, port, .. } => Box::new(
-            crate::panasonic::client::PanasonicClient::new(host, *port)
-                .map_err(|e| format!("Failed to create Panasonic client: {}", e))?,
-        ),
-        ProtocolConfig::BirdDogRest { host, port } => Box::new(
-            crate::birddog::client::BirdDogClient::new(host, *port)
-                .map_err(|e| format!("Failed to create BirdDog client: {}", e))?,
-        ),
-        ProtocolConfig::Simulated => Box::new(crate::simulator::client::SimulatedController::new()),
-    };
+    // Native slots saved with this camera's presets, for cameras that move
+    // by recalling the nearest one
+    let presets = state
+        .profiles
+        .lock()
+        .await
+        .get_active_profile()
+        .filter(|p| p.endpoint_id.as_deref() == Some(endpoint_id.as_str()))
+        .map(|p| p.presets.clone())
+        .unwrap_or_default();
 
-    // Set the controller on the dispatcher
+    // Reuse a warm controller built from the current config, or connect one
     let mut dispatcher = state.ptz_dispatcher.lock().await;
-    dispatcher.set_controller(controller);
+    dispatcher.activate_pooled(&endpoint_id, endpoint.config.fingerprint(), || {
+        build_controller(&endpoint.config)
+    })?;
+    dispatcher.seed_native_presets(&presets);
     drop(dispatcher);
 
+    // A running tour belongs to the previous camera
+    let previous = state.active_endpoint_id.lock().await.clone();
+    if previous.as_deref() != Some(endpoint_id.as_str()) {
+        stop_tour_task(state).await;
+    }
+
+    // Show where the camera was left until polling reads the live position
+    if let Some(pos) = state.positions.activate(&endpoint_id) {
+        use tauri::Emitter;
+
+        *state.current_position.lock().await = pos.clone();
+        if let Err(e) = app.emit(poller::POSITION_CHANGED_EVENT, &pos) {
+            log::warn!("Failed to emit position update: {}", e);
+        }
+    }
+
+    start_position_polling(app.clone(), state, endpoint_id.clone()).await;
+    start_health_monitoring(app.clone(), state, endpoint_id.clone()).await;
     *state.active_endpoint_id.lock().await = Some(endpoint_id.clone());
+    emit_endpoint_event(&app, ENDPOINT_ACTIVATED_EVENT, &endpoint_id);
     log::info!(
         "Active endpoint set to '{}' ({})",
         endpoint.name,
@@ -115,41 +499,362 @@ pub async fn set_active_endpoint(
     Ok(())
 }
 
-/// Test connectivity to a camera endpoint.
+/// Test connectivity to a camera endpoint, timing the round trip.
+#[tauri::command]
+pub async fn test_endpoint_connection(
+    config: ProtocolConfig,
+) -> Result<ConnectionTestResult, String> {
+    let label = protocol_label(&config);
+    match build_controller(&config) {
+        Ok(controller) => Ok(run_connection_test(label, controller.as_ref()).await),
+        Err(e) => Ok(build_failure(e)),
+    }
+}
+
+/// Test every configured endpoint at once, for a pre-service check. A camera
+/// that doesn't answer within the connection test timeout fails without
+/// holding up the rest. Results follow the endpoint list order.
 #[tauri::command]
-pub async fn test_endpoint_connection(config: ProtocolConfig) -> Result<String, String> {
+pub async fn test_all_endpoints(
+    state: tauri::State<'_, AppState>,
+) -> Result<Vec<EndpointTestResult>, String> {
+    let endpoints = state.endpoints.lock().await.get_all();
+    let probes = endpoints
+        .iter()
+        .map(|endpoint| ConnectionProbe {
+            endpoint_id: endpoint.id.clone(),
+            label: protocol_label(&endpoint.config),
+            controller: build_controller(&endpoint.config),
+        })
+        .collect();
+    Ok(run_connection_tests(probes, CONNECTION_TEST_TIMEOUT).await)
+}
+
+/// Name of a protocol as used in connection test messages.
+fn protocol_label(config: &ProtocolConfig) -> &'static str {
     match config {
-        ProtocolConfig::Ndi => Ok("NDI connection test: NDI SDK not linked".to_string()),
-        ProtocolConfig::Visca { host, port } => {
-            use crate::ptz::controller::PtzController;
-            use crate::visca::client::ViscaClient;
-            let client =
-                ViscaClient::new(&host, port).map_err(|e| format!("VISCA init failed: {}", e))?;
-            match client.test_connection().await {
-                Ok(()) => Ok("VISCA connection successful".to_string()),
-                Err(e) => Err(format!("VISCA connection failed: {}", e)),
-            }
+        ProtocolConfig::Ndi { .. } => "NDI PTZ",
+        ProtocolConfig::Visca { .. } => "VISCA",
+        ProtocolConfig::PanasonicAw { .. } => "Panasonic AW",
+        ProtocolConfig::BirdDogRest { .. } => "BirdDog",
+        ProtocolConfig::PelcoD { .. } => "Pelco-D gateway",
+        ProtocolConfig::Onvif { .. } => "ONVIF",
+        ProtocolConfig::Simulated => "Simulated camera",
+    }
+}
+
+/// Failed test result for a config no controller could be built from.
+fn build_failure(error: String) -> ConnectionTestResult {
+    ConnectionTestResult {
+        ok: false,
+        latency_ms: 0,
+        model: None,
+        message: error,
+    }
+}
+
+/// One endpoint's part of a bulk connection test.
+struct ConnectionProbe {
+    endpoint_id: String,
+    label: &'static str,
+    controller: Result<Box<dyn PtzController>, String>,
+}
+
+/// Run connection tests side by side, at most
+/// [`MAX_CONCURRENT_CONNECTION_TESTS`] at a time, failing any that take
+/// longer than `limit` in all. Results keep the order of `probes`.
+async fn run_connection_tests(
+    probes: Vec<ConnectionProbe>,
+    limit: Duration,
+) -> Vec<EndpointTestResult> {
+    let permits = std::sync::Arc::new(tokio::sync::Semaphore::new(MAX_CONCURRENT_CONNECTION_TESTS));
+    let mut tests = tokio::task::JoinSet::new();
+    for (index, probe) in probes.into_iter().enumerate() {
+        let permits = permits.clone();
+        tests.spawn(async move {
+            let result = match probe.controller {
+                Ok(controller) => {
+                    let _permit = permits.acquire().await;
+                    let test = run_connection_test(probe.label, controller.as_ref());
+                    tokio::time::timeout(limit, test).await.unwrap_or_else(|_| {
+                        ConnectionTestResult {
+                            ok: false,
+                            latency_ms: limit.as_millis() as u64,
+                            model: None,
+                            message: format!("{} connection failed: no answer", probe.label),
+                        }
+                    })
+                }
+                Err(e) => build_failure(e),
+            };
+            let result = EndpointTestResult {
+                endpoint_id: probe.endpoint_id,
+                result,
+            };
+            (index, result)
+        });
+    }
+
+    let mut results = Vec::with_capacity(tests.len());
+    while let Some(joined) = tests.join_next().await {
+        match joined {
+            Ok(result) => results.push(result),
+            Err(e) => log::error!("Connection test task failed: {}", e),
         }
-        ProtocolConfig::PanasonicAw { host, port, .. } => {
-            use crate::panasonic::client::PanasonicClient;
-            use crate::ptz::controller::PtzController;
-            let client = PanasonicClient::new(&host, port)
-                .map_err(|e| format!("Panasonic init failed: {}", e))?;
-            match client.test_connection().await {
-                Ok(()) => Ok("Panasonic AW connection successful".to_string()),
-                Err(e) => Err(format!("Panasonic AW connection failed: {}", e)),
-            }
+    }
+    results.sort_by_key(|(index, _)| *index);
+    results.into_iter().map(|(_, result)| result).collect()
+}
+
+/// Find out which protocol the camera at `host` speaks by probing BirdDog,
+/// Panasonic AW and VISCA in turn. `ports` overrides the usual ports for
+/// every probe. `None` if nothing answered.
+#[tauri::command]
+pub async fn detect_protocol(
+    host: String,
+    ports: Option<Vec<u16>>,
+) -> Result<Option<PtzProtocol>, String> {
+    let host = host.trim();
+    crate::ptz::types::validate_host(host)?;
+    let ports = ports.unwrap_or_default();
+    Ok(detect::detect_protocol(host, &ports, detect::PROBE_TIMEOUT).await)
+}
+
+/// Measure a VISCA camera's raw pan/tilt range by driving it to its hard
+/// stops. The result is meant to be saved as the endpoint's `calibration`.
+#[tauri::command]
+pub async fn calibrate_visca_endpoint(config: ProtocolConfig) -> Result<ViscaCalibration, String> {
+    let ProtocolConfig::Visca { host, port, .. } = &config else {
+        return Err("Calibration is only available for VISCA endpoints".to_string());
+    };
+    crate::visca::client::ViscaClient::new(host, *port)
+        .map_err(|e| format!("Failed to create VISCA client: {}", e))?
+        .auto_calibrate()
+        .await
+        .map_err(|e| e.to_string())
+}
+
+/// Probe a controller and describe the outcome. A camera that takes longer
+/// than [`CONNECTION_TEST_TIMEOUT`] to answer fails the test.
+async fn run_connection_test(label: &str, controller: &dyn PtzController) -> ConnectionTestResult {
+    let started = std::time::Instant::now();
+    let result = timeout::scoped(Some(CONNECTION_TEST_TIMEOUT), controller.probe()).await;
+    let latency_ms = started.elapsed().as_millis() as u64;
+    match result {
+        Ok(model) => ConnectionTestResult {
+            ok: true,
+            latency_ms,
+            message: match &model {
+                Some(model) => format!("{} connection successful ({})", label, model),
+                None => format!("{} connection successful", label),
+            },
+            model,
+        },
+        Err(e) => ConnectionTestResult {
+            ok: false,
+            latency_ms,
+            model: None,
+            message: format!("{} connection failed: {}", label, e),
+        },
+    }
+}
+
+/// Get the last-known health of the active endpoint, if one is active.
+#[tauri::command]
+pub async fn get_endpoint_status(
+    state: tauri::State<'_, AppState>,
+) -> Result<Option<EndpointStatusReport>, String> {
+    Ok(state.endpoint_status.lock().await.clone())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::simulator::client::{FailureMode, SimulatedController};
+
+    #[tokio::test]
+    async fn health_probes_release_the_dispatcher_while_waiting() {
+        let mut dispatcher = PtzDispatcher::new();
+        let slow = SimulatedController::new().with_response_delay(Duration::from_millis(300));
+        dispatcher.add_controller("cam", Box::new(slow));
+        let dispatcher = std::sync::Arc::new(tokio::sync::Mutex::new(dispatcher));
+
+        let probe = tokio::spawn({
+            let dispatcher = dispatcher.clone();
+            async move { probe_endpoint(&dispatcher, "cam").await }
+        });
+        tokio::time::sleep(Duration::from_millis(50)).await;
+        let locked = tokio::time::timeout(Duration::from_millis(100), dispatcher.lock()).await;
+        assert!(locked.is_ok());
+        drop(locked);
+        assert!(probe.await.unwrap().is_ok());
+        assert_eq!(
+            dispatcher.lock().await.statuses().get("cam"),
+            ConnectionStatus::Ok
+        );
+    }
+
+    #[tokio::test]
+    async fn connection_test_measures_latency() {
+        let camera = SimulatedController::new().with_response_delay(Duration::from_millis(30));
+        let result = run_connection_test("Simulated camera", &camera).await;
+        assert!(result.ok);
+        assert!(result.latency_ms >= 30, "{}", result.latency_ms);
+        assert_eq!(result.message, "Simulated camera connection successful");
+    }
+
+    #[tokio::test]
+    async fn failed_connection_test_reports_error() {
+        let camera = SimulatedController::new();
+        camera.set_failure_mode(FailureMode::AlwaysTimeout);
+        let result = run_connection_test("Simulated camera", &camera).await;
+        assert!(!result.ok);
+        assert!(result.model.is_none());
+        assert!(
+            result.message.contains("Simulated camera timeout"),
+            "{}",
+            result.message
+        );
+    }
+
+    fn probe(id: &str, controller: Result<Box<dyn PtzController>, String>) -> ConnectionProbe {
+        ConnectionProbe {
+            endpoint_id: id.to_string(),
+            label: "Simulated camera",
+            controller,
         }
-        ProtocolConfig::BirdDogRest { host, port } => {
-            use crate::birddog::client::BirdDogClient;
-            use crate::ptz::controller::PtzController;
-            let client = BirdDogClient::new(&host, port)
-                .map_err(|e| format!("BirdDog init failed: {}", e))?;
-            match client.test_connection().await {
-                Ok(()) => Ok("BirdDog connection successful".to_string()),
-                Err(e) => Err(format!("BirdDog connection failed: {}", e)),
-            }
+    }
+
+    #[tokio::test]
+    async fn bulk_test_reports_every_endpoint_in_order() {
+        let failing = SimulatedController::new();
+        failing.set_failure_mode(FailureMode::RejectCommands);
+        let probes = vec![
+            probe("ok", Ok(Box::new(SimulatedController::new()))),
+            probe("rejecting", Ok(Box::new(failing))),
+            probe("broken", Err("Invalid host".to_string())),
+            probe("also-ok", Ok(Box::new(SimulatedController::new()))),
+        ];
+
+        let results = run_connection_tests(probes, CONNECTION_TEST_TIMEOUT).await;
+        let summary: Vec<_> = results
+            .iter()
+            .map(|r| (r.endpoint_id.as_str(), r.result.ok))
+            .collect();
+        assert_eq!(
+            summary,
+            [
+                ("ok", true),
+                ("rejecting", false),
+                ("broken", false),
+                ("also-ok", true)
+            ]
+        );
+        assert_eq!(results[2].result.message, "Invalid host");
+    }
+
+    #[tokio::test]
+    async fn dead_cameras_do_not_hold_up_the_bulk_test() {
+        let limit = Duration::from_millis(100);
+        let mut probes: Vec<_> = (0..MAX_CONCURRENT_CONNECTION_TESTS * 2)
+            .map(|i| {
+                let hung = SimulatedController::new().with_response_delay(Duration::from_secs(30));
+                probe(&format!("dead-{}", i), Ok(Box::new(hung)))
+            })
+            .collect();
+        probes.push(probe("live", Ok(Box::new(SimulatedController::new()))));
+
+        let started = std::time::Instant::now();
+        let results = run_connection_tests(probes, limit).await;
+        // Two rounds of hung cameras, then the live one
+        assert!(started.elapsed() < limit * 5, "{:?}", started.elapsed());
+        assert_eq!(results.len(), MAX_CONCURRENT_CONNECTION_TESTS * 2 + 1);
+        assert_eq!(results.iter().filter(|r| r.result.ok).count(), 1);
+        assert!(results.last().unwrap().result.ok);
+    }
+
+    fn temp_dir() -> std::path::PathBuf {
+        let dir = std::env::temp_dir().join(format!(
+            "ptzcam-test-endpoint-commands-{}",
+            uuid::Uuid::new_v4()
+        ));
+        std::fs::create_dir_all(&dir).unwrap();
+        dir
+    }
+
+    fn simulated(id: &str, name: &str) -> CameraEndpoint {
+        CameraEndpoint {
+            id: id.to_string(),
+            name: name.to_string(),
+            protocol: PtzProtocol::Simulated,
+            config: ProtocolConfig::Simulated,
+            color: None,
+            icon: None,
         }
-        ProtocolConfig::Simulated => Ok("Simulated camera ready".to_string()),
+    }
+
+    #[tokio::test]
+    async fn update_returns_the_saved_endpoint_and_reconnects() {
+        let dir = temp_dir();
+        let state = AppState::new(dir.clone());
+        state
+            .endpoints
+            .lock()
+            .await
+            .create(simulated("cam", "Stage"))
+            .unwrap();
+        state
+            .ptz_dispatcher
+            .lock()
+            .await
+            .activate_pooled("cam", ProtocolConfig::Simulated.fingerprint(), || {
+                build_controller(&ProtocolConfig::Simulated)
+            })
+            .unwrap();
+
+        let updated = save_endpoint(&state, simulated("cam", "Pulpit"))
+            .await
+            .unwrap();
+        assert_eq!(updated.name, "Pulpit");
+        assert_eq!(
+            state.endpoints.lock().await.get("cam").unwrap().name,
+            "Pulpit"
+        );
+        assert!(state.ptz_dispatcher.lock().await.contains("cam"));
+        assert!(save_endpoint(&state, simulated("missing", "Nope"))
+            .await
+            .is_err());
+        std::fs::remove_dir_all(&dir).ok();
+    }
+
+    #[tokio::test]
+    async fn deleting_the_active_endpoint_clears_it() {
+        let dir = temp_dir();
+        let state = AppState::new(dir.clone());
+        state
+            .endpoints
+            .lock()
+            .await
+            .create(simulated("cam", "Stage"))
+            .unwrap();
+        *state.active_endpoint_id.lock().await = Some("cam".to_string());
+
+        remove_endpoint(&state, "cam").await.unwrap();
+        assert!(state.endpoints.lock().await.get_all().is_empty());
+        assert_eq!(*state.active_endpoint_id.lock().await, None);
+        assert!(remove_endpoint(&state, "cam").await.is_err());
+        std::fs::remove_dir_all(&dir).ok();
+    }
+
+    #[tokio::test]
+    async fn invalid_config_fails_without_connecting() {
+        let config = ProtocolConfig::PelcoD {
+            host: "127.0.0.1".to_string(),
+            port: 4001,
+            address: 0,
+        };
+        let result = test_endpoint_connection(config).await.unwrap();
+        assert!(!result.ok);
+        assert_eq!(result.latency_ms, 0);
     }
 }
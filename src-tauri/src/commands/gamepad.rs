@@ -0,0 +1,90 @@
+use crate::commands::ptz;
+use crate::gamepad::input::{self, GamepadInput};
+use crate::gamepad::mapping::{GamepadVelocity, VELOCITY_EVENT};
+use crate::ptz::deadman::KEEPALIVE_INTERVAL;
+use crate::AppState;
+use tokio::sync::{mpsc, watch};
+
+/// Send stick velocities through the PTZ command layer.
+async fn apply_velocity(
+    state: tauri::State<'_, AppState>,
+    velocity: GamepadVelocity,
+) -> Result<(), String> {
+    ptz::ptz_continuous_move(state.clone(), velocity.pan, velocity.tilt).await?;
+    ptz::ptz_zoom_continuous(state, velocity.zoom).await
+}
+
+/// Start driving the active camera from the first connected gamepad, using
+/// the gamepad settings from the app config. Replaces any running reader.
+#[tauri::command]
+pub async fn start_gamepad(
+    app: tauri::AppHandle,
+    state: tauri::State<'_, AppState>,
+) -> Result<(), String> {
+    use tauri::{Emitter, Manager};
+
+    let mut gamepad_stop = state.gamepad_stop.lock().await;
+    if let Some(stop_tx) = gamepad_stop.take() {
+        let _ = stop_tx.send(true);
+    }
+
+    let settings = state.config.lock().await.gamepad.clone();
+    let (stop_tx, stop_rx) = watch::channel(false);
+    let (input_tx, mut input_rx) = mpsc::unbounded_channel();
+    input::spawn_reader(settings, stop_rx, input_tx)?;
+    *gamepad_stop = Some(stop_tx);
+
+    tokio::spawn(async move {
+        let mut moving = false;
+        loop {
+            let event = if moving {
+                // A held stick sends nothing new, so keep the deadman fed
+                match tokio::time::timeout(KEEPALIVE_INTERVAL, input_rx.recv()).await {
+                    Ok(event) => event,
+                    Err(_) => {
+                        let state = app.state::<AppState>();
+                        if let Err(e) = ptz::ptz_continuous_move_keepalive(state).await {
+                            log::warn!("Gamepad keepalive failed: {}", e);
+                        }
+                        continue;
+                    }
+                }
+            } else {
+                input_rx.recv().await
+            };
+            let Some(event) = event else { break };
+            let state = app.state::<AppState>();
+            let result = match event {
+                GamepadInput::Velocity(velocity) => {
+                    if let Err(e) = app.emit(VELOCITY_EVENT, velocity) {
+                        log::warn!("Failed to emit gamepad velocity: {}", e);
+                    }
+                    moving = !velocity.is_zero();
+                    apply_velocity(state, velocity).await
+                }
+                GamepadInput::PresetButton(number) => {
+                    ptz::recall_preset_number(&app, &state, number).await
+                }
+            };
+            if let Err(e) = result {
+                log::warn!("Gamepad command failed: {}", e);
+            }
+        }
+        // Don't leave the camera drifting if the reader stopped mid-move
+        if moving {
+            if let Err(e) = ptz::ptz_stop(app.state::<AppState>()).await {
+                log::warn!("Failed to stop camera after gamepad input ended: {}", e);
+            }
+        }
+    });
+    Ok(())
+}
+
+/// Stop reading the gamepad.
+#[tauri::command]
+pub async fn stop_gamepad(state: tauri::State<'_, AppState>) -> Result<(), String> {
+    if let Some(stop_tx) = state.gamepad_stop.lock().await.take() {
+        let _ = stop_tx.send(true);
+    }
+    Ok(())
+}
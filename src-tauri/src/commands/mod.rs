@@ -1,5 +1,10 @@
+pub mod bundle;
 pub mod endpoints;
+pub mod gamepad;
+pub mod osc;
 pub mod presets;
 pub mod ptz;
 pub mod settings;
+pub mod tour;
 pub mod video;
+pub mod ws_control;
@@ -0,0 +1,59 @@
+use crate::commands::ptz;
+use crate::osc::message::OscCommand;
+use crate::video::mjpeg_server::ServerOptions;
+use crate::AppState;
+
+/// Run an OSC command through the same command layer as the UI.
+async fn run_osc_command(app: tauri::AppHandle, command: OscCommand) -> Result<(), String> {
+    use tauri::Manager;
+
+    let state = app.state::<AppState>();
+    match command {
+        OscCommand::Move { pan, tilt } => ptz::ptz_continuous_move(state, pan, tilt).await,
+        OscCommand::Keepalive => ptz::ptz_continuous_move_keepalive(state).await,
+        OscCommand::Zoom(zoom) => ptz::ptz_zoom(app.clone(), state, zoom).await,
+        OscCommand::Stop => ptz::ptz_stop(state).await,
+        OscCommand::Home => ptz::ptz_home(app.clone(), state).await,
+        OscCommand::RecallPreset(number) => ptz::recall_preset_number(&app, &state, number).await,
+    }
+}
+
+/// Start listening for OSC control messages on a UDP port, replacing any
+/// running listener. Listens on localhost unless `bind_address` names an
+/// interface, or `0.0.0.0` for all of them. Port 0 picks a free port.
+/// Returns the bound port.
+#[tauri::command]
+pub async fn start_osc_server(
+    app: tauri::AppHandle,
+    state: tauri::State<'_, AppState>,
+    bind_address: Option<String>,
+    port: u16,
+) -> Result<u16, String> {
+    let options = ServerOptions::parse(bind_address.as_deref(), Some(port))?;
+
+    if let Some(shutdown_tx) = state.osc_shutdown.lock().await.take() {
+        let _ = shutdown_tx.send(true);
+    }
+
+    let (port, shutdown_tx) = crate::osc::server::start_server(&options, move |command| {
+        let app = app.clone();
+        async move {
+            if let Err(e) = run_osc_command(app, command.clone()).await {
+                log::warn!("OSC command {:?} failed: {}", command, e);
+            }
+        }
+    })
+    .await?;
+
+    *state.osc_shutdown.lock().await = Some(shutdown_tx);
+    Ok(port)
+}
+
+/// Stop the OSC listener.
+#[tauri::command]
+pub async fn stop_osc_server(state: tauri::State<'_, AppState>) -> Result<(), String> {
+    if let Some(shutdown_tx) = state.osc_shutdown.lock().await.take() {
+        let _ = shutdown_tx.send(true);
+    }
+    Ok(())
+}
@@ -1,4 +1,11 @@
-use crate::ptz::types::{Preset, PresetProfile};
+use super::endpoints::{activate_endpoint, clear_active};
+use crate::persistence::profiles::ProfileBackup;
+use crate::persistence::undo::UndoState;
+use crate::ptz::color::validate_color;
+use crate::ptz::controller::PtzDispatcher;
+use crate::ptz::endpoint_manager::EndpointManager;
+use crate::ptz::types::{BatchResult, Preset, PresetInput, PresetProfile, PtzPosition};
+use crate::video::thumbnail;
 use crate::AppState;
 
 /// Get all presets from the active profile.
@@ -17,7 +24,7 @@ pub async fn create_preset(
     pan: f64,
     tilt: f64,
     zoom: f64,
-    color: String,
+    color: Option<String>,
 ) -> Result<Preset, String> {
     if !pan.is_finite() || !tilt.is_finite() || !zoom.is_finite() {
         return Err("Preset values must be finite numbers".to_string());
@@ -26,19 +33,97 @@ pub async fn create_preset(
     if name.trim().is_empty() {
         return Err("Preset name cannot be empty".to_string());
     }
+    // Capture focus where the camera can report it; others just skip it
+    let dispatcher = state.ptz_dispatcher.lock().await;
+    let focus = if dispatcher.has_controller() {
+        dispatcher.get_focus().await.ok()
+    } else {
+        None
+    };
+    drop(dispatcher);
+    let thumbnail = capture_thumbnail(&state).await.ok();
     let preset = Preset {
         id: uuid::Uuid::new_v4().to_string(),
         name,
         pan: pan.clamp(-1.0, 1.0),
         tilt: tilt.clamp(-1.0, 1.0),
         zoom: zoom.clamp(0.0, 1.0),
-        color,
+        color: color
+            .map(|color| validate_color(&color))
+            .transpose()?
+            .unwrap_or_else(Preset::default_color),
+        native_slot: None,
+        focus,
+        white_balance: None,
+        hotkey: None,
+        thumbnail,
     };
     let mut profiles = state.profiles.lock().await;
     profiles.ensure_default_profile()?;
     profiles.create_preset(preset)
 }
 
+/// Create a preset from where the camera is now. The position is read here
+/// rather than passed in, so it can't be stale: the controller is asked for
+/// it, falling back to the tracked position when there is none or it can't
+/// answer.
+#[tauri::command]
+pub async fn create_preset_from_current(
+    state: tauri::State<'_, AppState>,
+    name: String,
+    color: Option<String>,
+) -> Result<Preset, String> {
+    let tracked = state.current_position.lock().await.clone();
+    let dispatcher = state.ptz_dispatcher.lock().await;
+    let (position, focus) = snapshot_camera(&dispatcher, tracked).await;
+    drop(dispatcher);
+
+    let mut preset = PresetInput {
+        name,
+        pan: position.pan,
+        tilt: position.tilt,
+        zoom: position.zoom,
+        color: color.unwrap_or_else(Preset::default_color),
+        hotkey: None,
+    }
+    .into_preset()?;
+    preset.focus = focus;
+    preset.thumbnail = capture_thumbnail(&state).await.ok();
+    let mut profiles = state.profiles.lock().await;
+    profiles.ensure_default_profile()?;
+    profiles.create_preset(preset)
+}
+
+/// Read the active camera's position and, where it can report it, focus.
+async fn snapshot_camera(
+    dispatcher: &PtzDispatcher,
+    tracked: PtzPosition,
+) -> (PtzPosition, Option<f64>) {
+    if !dispatcher.has_controller() {
+        return (tracked, None);
+    }
+    let position = match dispatcher.get_position().await {
+        Ok(position) => position,
+        Err(e) => {
+            log::warn!("Using the tracked position for the new preset: {}", e);
+            tracked
+        }
+    };
+    (position, dispatcher.get_focus().await.ok())
+}
+
+/// Create several presets in the active profile, e.g. from an imported shot
+/// list, saving once. Invalid entries are skipped and reported by index.
+#[tauri::command]
+pub async fn create_presets(
+    state: tauri::State<'_, AppState>,
+    presets: Vec<PresetInput>,
+) -> Result<BatchResult<Preset>, String> {
+    let mut profiles = state.profiles.lock().await;
+    profiles.ensure_default_profile()?;
+    profiles.create_presets(presets)
+}
+
 /// Update an existing preset.
 #[tauri::command]
 pub async fn update_preset(
@@ -58,7 +143,15 @@ pub async fn update_preset(
         pan: preset.pan.clamp(-1.0, 1.0),
         tilt: preset.tilt.clamp(-1.0, 1.0),
         zoom: preset.zoom.clamp(0.0, 1.0),
-        color: preset.color,
+        color: validate_color(&preset.color)?,
+        native_slot: preset.native_slot,
+        focus: preset
+            .focus
+            .filter(|f| f.is_finite())
+            .map(|f| f.clamp(0.0, 1.0)),
+        white_balance: preset.white_balance,
+        hotkey: preset.hotkey,
+        thumbnail: preset.thumbnail,
     };
     let mut profiles = state.profiles.lock().await;
     profiles.update_preset(validated)
@@ -74,6 +167,134 @@ pub async fn delete_preset(
     profiles.delete_preset(&preset_id)
 }
 
+/// Delete several presets by ID, saving once. Unknown ids are reported by index.
+#[tauri::command]
+pub async fn delete_presets(
+    state: tauri::State<'_, AppState>,
+    preset_ids: Vec<String>,
+) -> Result<BatchResult<String>, String> {
+    let mut profiles = state.profiles.lock().await;
+    profiles.delete_presets(preset_ids)
+}
+
+/// Duplicate a preset in the active profile.
+#[tauri::command]
+pub async fn duplicate_preset(
+    state: tauri::State<'_, AppState>,
+    preset_id: String,
+) -> Result<Preset, String> {
+    let mut profiles = state.profiles.lock().await;
+    profiles.duplicate_preset(&preset_id)
+}
+
+/// Reorder a profile's presets to match the given id list.
+#[tauri::command]
+pub async fn reorder_presets(
+    state: tauri::State<'_, AppState>,
+    profile_id: String,
+    ordered_ids: Vec<String>,
+) -> Result<(), String> {
+    let mut profiles = state.profiles.lock().await;
+    profiles.reorder_presets(&profile_id, &ordered_ids)
+}
+
+/// Move a preset in the active profile to a new position.
+#[tauri::command]
+pub async fn move_preset(
+    state: tauri::State<'_, AppState>,
+    preset_id: String,
+    new_index: usize,
+) -> Result<(), String> {
+    let mut profiles = state.profiles.lock().await;
+    profiles.move_preset(&preset_id, new_index)
+}
+
+/// Reverse the latest preset create, update or delete in the active profile
+/// and return its presets.
+#[tauri::command]
+pub async fn undo_preset_change(state: tauri::State<'_, AppState>) -> Result<Vec<Preset>, String> {
+    let mut profiles = state.profiles.lock().await;
+    profiles.undo_preset_change()
+}
+
+/// Reapply the latest undone preset change and return the presets.
+#[tauri::command]
+pub async fn redo_preset_change(state: tauri::State<'_, AppState>) -> Result<Vec<Preset>, String> {
+    let mut profiles = state.profiles.lock().await;
+    profiles.redo_preset_change()
+}
+
+/// Whether a preset change can currently be undone or redone.
+#[tauri::command]
+pub async fn get_preset_undo_state(state: tauri::State<'_, AppState>) -> Result<UndoState, String> {
+    let profiles = state.profiles.lock().await;
+    Ok(profiles.undo_state())
+}
+
+/// Downscaled copy of the latest preview frame, as base64 JPEG.
+async fn capture_thumbnail(state: &AppState) -> Result<String, String> {
+    let frame = state
+        .mjpeg_state
+        .lock()
+        .await
+        .as_ref()
+        .and_then(|mjpeg| mjpeg.latest_frame())
+        .ok_or("No preview frame available")?;
+    thumbnail::thumbnail_from_rgb(&frame.rgb, frame.width, frame.height)
+}
+
+/// Replace a preset's thumbnail with the current preview frame. Thumbnails
+/// are otherwise only taken when a preset is created.
+#[tauri::command]
+pub async fn refresh_preset_thumbnail(
+    state: tauri::State<'_, AppState>,
+    preset_id: String,
+) -> Result<Preset, String> {
+    let thumbnail = capture_thumbnail(&state).await?;
+    let mut profiles = state.profiles.lock().await;
+    profiles.set_preset_thumbnail(&preset_id, Some(thumbnail))
+}
+
+/// Assign a keyboard shortcut to a preset in the active profile, or clear it
+/// with `None`. Fails if another preset in the profile already uses the key.
+#[tauri::command]
+pub async fn set_preset_hotkey(
+    state: tauri::State<'_, AppState>,
+    preset_id: String,
+    hotkey: Option<String>,
+) -> Result<Preset, String> {
+    let mut profiles = state.profiles.lock().await;
+    profiles.set_preset_hotkey(&preset_id, hotkey.as_deref())
+}
+
+/// Choose the preset the home command recalls for the active profile, or
+/// clear it with `None` to use the camera's own home position.
+#[tauri::command]
+pub async fn set_home_preset(
+    state: tauri::State<'_, AppState>,
+    preset_id: Option<String>,
+) -> Result<(), String> {
+    let mut profiles = state.profiles.lock().await;
+    profiles.set_home_preset(preset_id.as_deref())
+}
+
+/// Search the active profile's presets by name (case-insensitive substring),
+/// optionally keeping only those of one color.
+#[tauri::command]
+pub async fn search_presets(
+    state: tauri::State<'_, AppState>,
+    query: String,
+    color: Option<String>,
+) -> Result<Vec<Preset>, String> {
+    let profiles = state.profiles.lock().await;
+    let mut presets = profiles.find_presets_by_name(&query);
+    if let Some(color) = color {
+        let color = color.trim();
+        presets.retain(|p| p.color.eq_ignore_ascii_case(color));
+    }
+    Ok(presets)
+}
+
 /// Get all profiles.
 #[tauri::command]
 pub async fn get_profiles(state: tauri::State<'_, AppState>) -> Result<Vec<PresetProfile>, String> {
@@ -87,6 +308,9 @@ pub async fn save_profile(
     state: tauri::State<'_, AppState>,
     profile: PresetProfile,
 ) -> Result<PresetProfile, String> {
+    if let Some(transition) = &profile.transition {
+        transition.validate()?;
+    }
     let mut profiles = state.profiles.lock().await;
     profiles.save_profile(profile)
 }
@@ -94,13 +318,68 @@ pub async fn save_profile(
 /// Load (activate) a profile by ID.
 #[tauri::command]
 pub async fn load_profile(
+    app: tauri::AppHandle,
     state: tauri::State<'_, AppState>,
     profile_id: String,
 ) -> Result<(), String> {
+    let profile = state
+        .profiles
+        .lock()
+        .await
+        .get_profiles()
+        .into_iter()
+        .find(|p| p.id == profile_id)
+        .ok_or("Profile not found")?;
+
+    // Switch cameras first, so a failed connection leaves the old profile in place
+    let switch = profile_endpoint(&profile, &*state.endpoints.lock().await);
+    match switch {
+        ProfileEndpoint::Unchanged => {}
+        ProfileEndpoint::Activate(endpoint_id) => {
+            activate_endpoint(app, &state, endpoint_id).await?;
+        }
+        ProfileEndpoint::Missing(endpoint_id) => {
+            log::warn!(
+                "Profile '{}' refers to deleted endpoint '{}'; clearing the active endpoint",
+                profile.name,
+                endpoint_id
+            );
+            clear_active(&state).await;
+        }
+    }
+
     let mut profiles = state.profiles.lock().await;
     profiles.set_active_profile(&profile_id)
 }
 
+/// What loading a profile does to the active endpoint.
+#[derive(Debug, PartialEq)]
+enum ProfileEndpoint {
+    /// The profile isn't tied to a camera; keep the current one.
+    Unchanged,
+    Activate(String),
+    /// The profile's camera was deleted; its presets would go to the wrong one.
+    Missing(String),
+}
+
+fn profile_endpoint(profile: &PresetProfile, endpoints: &EndpointManager) -> ProfileEndpoint {
+    match &profile.endpoint_id {
+        None => ProfileEndpoint::Unchanged,
+        Some(id) if endpoints.get(id).is_some() => ProfileEndpoint::Activate(id.clone()),
+        Some(id) => ProfileEndpoint::Missing(id.clone()),
+    }
+}
+
+/// Duplicate a profile with all its presets. The copy is not activated.
+#[tauri::command]
+pub async fn duplicate_profile(
+    state: tauri::State<'_, AppState>,
+    profile_id: String,
+) -> Result<PresetProfile, String> {
+    let mut profiles = state.profiles.lock().await;
+    profiles.duplicate_profile(&profile_id)
+}
+
 /// Delete a profile by ID.
 #[tauri::command]
 pub async fn delete_profile(
@@ -110,3 +389,135 @@ pub async fn delete_profile(
     let mut profiles = state.profiles.lock().await;
     profiles.delete_profile(&profile_id)
 }
+
+/// List the rotating profiles.json backups, most recent first.
+#[tauri::command]
+pub async fn list_profile_backups(
+    state: tauri::State<'_, AppState>,
+) -> Result<Vec<ProfileBackup>, String> {
+    let profiles = state.profiles.lock().await;
+    Ok(profiles.list_backups())
+}
+
+/// Replace all profiles with the contents of a backup.
+#[tauri::command]
+pub async fn restore_profile_backup(
+    state: tauri::State<'_, AppState>,
+    index: usize,
+) -> Result<Vec<PresetProfile>, String> {
+    let mut profiles = state.profiles.lock().await;
+    profiles.restore_backup(index)?;
+    log::info!("Restored profiles from backup {}", index);
+    Ok(profiles.get_profiles())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ptz::controller::PtzController;
+    use crate::ptz::geometry::LensCurve;
+    use crate::ptz::types::{CameraEndpoint, ProtocolConfig, PtzProtocol};
+    use crate::simulator::client::SimulatedController;
+
+    fn endpoints_with(ids: &[&str]) -> EndpointManager {
+        let dir =
+            std::env::temp_dir().join(format!("ptzcam-test-load-profile-{}", uuid::Uuid::new_v4()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let mut endpoints = EndpointManager::load_or_default(&dir).with_deferred_saves();
+        for id in ids {
+            endpoints
+                .create(CameraEndpoint {
+                    id: id.to_string(),
+                    name: id.to_string(),
+                    protocol: PtzProtocol::Simulated,
+                    config: ProtocolConfig::Simulated,
+                    color: None,
+                    icon: None,
+                })
+                .unwrap();
+        }
+        endpoints
+    }
+
+    fn profile(endpoint_id: Option<&str>) -> PresetProfile {
+        PresetProfile {
+            id: "p".to_string(),
+            name: "Show".to_string(),
+            camera_fov_degrees: 60.0,
+            endpoint_id: endpoint_id.map(str::to_string),
+            presets: Vec::new(),
+            tour: None,
+            transition: None,
+            home_preset_id: None,
+            lens_curve: LensCurve::default(),
+            mechanical_range: None,
+            limits_degrees: None,
+        }
+    }
+
+    #[tokio::test]
+    async fn snapshot_reads_the_cameras_position_and_focus() {
+        let camera = SimulatedController::new();
+        camera.move_absolute(0.3, -0.2, 0.6).await.unwrap();
+        camera.focus_to(0.45).await.unwrap();
+        let mut dispatcher = PtzDispatcher::new();
+        dispatcher.add_controller("cam", Box::new(camera));
+        dispatcher.set_active("cam").unwrap();
+
+        let stale = PtzPosition {
+            pan: 0.0,
+            tilt: 0.0,
+            zoom: 0.0,
+        };
+        let (position, focus) = snapshot_camera(&dispatcher, stale).await;
+        assert_eq!(
+            position,
+            PtzPosition {
+                pan: 0.3,
+                tilt: -0.2,
+                zoom: 0.6,
+            }
+        );
+        assert_eq!(focus, Some(0.45));
+    }
+
+    #[tokio::test]
+    async fn snapshot_without_a_camera_uses_the_tracked_position() {
+        let tracked = PtzPosition {
+            pan: -0.5,
+            tilt: 0.25,
+            zoom: 0.1,
+        };
+        let (position, focus) = snapshot_camera(&PtzDispatcher::new(), tracked.clone()).await;
+        assert_eq!(position, tracked);
+        assert_eq!(focus, None);
+    }
+
+    #[test]
+    fn profile_with_an_endpoint_activates_it() {
+        let endpoints = endpoints_with(&["cam-a", "cam-b"]);
+        assert_eq!(
+            profile_endpoint(&profile(Some("cam-b")), &endpoints),
+            ProfileEndpoint::Activate("cam-b".to_string())
+        );
+    }
+
+    #[test]
+    fn profile_without_an_endpoint_keeps_the_active_one() {
+        let endpoints = endpoints_with(&["cam-a"]);
+        assert_eq!(
+            profile_endpoint(&profile(None), &endpoints),
+            ProfileEndpoint::Unchanged
+        );
+    }
+
+    #[test]
+    fn profile_with_a_deleted_endpoint_clears_the_active_one() {
+        let mut endpoints = endpoints_with(&["cam-a"]);
+        endpoints.delete("cam-a").unwrap();
+        assert_eq!(
+            profile_endpoint(&profile(Some("cam-a")), &endpoints),
+            ProfileEndpoint::Missing("cam-a".to_string())
+        );
+    }
+}
@@ -1,22 +1,130 @@
-use crate::ptz::types::PtzPosition;
+use crate::ptz::command_log::CommandLogEntry;
+use crate::ptz::controller::{PtzDispatcher, PtzError};
+use crate::ptz::geometry::{self, ClickDelta, Lens};
+use crate::ptz::jog_guard;
+use crate::ptz::poller;
+use crate::ptz::preview::{CommandPreview, PtzCommand};
+use crate::ptz::response_curve::apply_response_curve;
+use crate::ptz::transition::{self, STEP_INTERVAL};
+use crate::ptz::types::{
+    ExposureMode, MenuDirection, Preset, PtzLimits, PtzPosition, TallyState, ViscaDiagnostics,
+    WhiteBalanceSetting,
+};
 use crate::AppState;
+use std::time::Duration;
+
+/// Abort any in-progress preset transition or jog guard, and cut short a
+/// multi-step command still running on the active camera, so a new command
+/// takes over.
+async fn abort_moves(state: &AppState) {
+    if let Some(cancel_tx) = state.transition_cancel.lock().await.take() {
+        let _ = cancel_tx.send(true);
+    }
+    if let Some(cancel_tx) = state.jog_guard_cancel.lock().await.take() {
+        let _ = cancel_tx.send(true);
+    }
+    if let Some(id) = state.active_endpoint_id.lock().await.as_deref() {
+        state.move_cancels.cancel(id);
+    }
+}
+
+/// Whether a command should go to the camera. Without an active endpoint,
+/// commands are a no-op unless strict mode turns that into an error.
+fn dispatch_decision(has_controller: bool, strict: bool) -> Result<bool, String> {
+    match (has_controller, strict) {
+        (true, _) => Ok(true),
+        (false, true) => Err("No active endpoint".to_string()),
+        (false, false) => Ok(false),
+    }
+}
+
+/// Whether strict mode is on. Read before locking the dispatcher, so config
+/// is never locked while the dispatcher is held.
+async fn strict_commands(state: &AppState) -> bool {
+    state.config.lock().await.strict_commands
+}
+
+fn should_dispatch(dispatcher: &PtzDispatcher, strict: bool) -> Result<bool, String> {
+    dispatch_decision(dispatcher.has_controller(), strict)
+}
+
+/// The active profile's degree limits, converted for its camera.
+async fn profile_limits(state: &AppState) -> Option<PtzLimits> {
+    state
+        .profiles
+        .lock()
+        .await
+        .get_active_profile()
+        .and_then(|p| p.soft_limits())
+}
+
+/// Receives the position changes commands make. The app handle forwards
+/// them to the frontend; commands take any listener so control surfaces
+/// and tests can run them without one.
+pub(crate) trait PositionListener: Clone + Send + Sync + 'static {
+    fn position_changed(&self, pos: &PtzPosition);
+}
+
+impl PositionListener for tauri::AppHandle {
+    fn position_changed(&self, pos: &PtzPosition) {
+        use tauri::Emitter;
+
+        if let Err(e) = self.emit(poller::POSITION_CHANGED_EVENT, pos) {
+            log::warn!("Failed to emit position update: {}", e);
+        }
+    }
+}
+
+/// Tell the listener about a command-driven position change, debounced so a
+/// burst of moves sends at most one event per interval. The position is also
+/// remembered as the active endpoint's last-known one.
+fn notify_position<L: PositionListener>(listener: &L, state: &AppState, pos: PtzPosition) {
+    if let Err(e) = state.positions.record_active(&pos) {
+        log::warn!("Failed to save position: {}", e);
+    }
+    let listener = listener.clone();
+    state
+        .position_events
+        .publish(pos, move |pos| listener.position_changed(pos));
+}
 
 /// Move the camera by a relative pan/tilt delta.
 #[tauri::command]
 pub async fn ptz_move_relative(
+    app: tauri::AppHandle,
     state: tauri::State<'_, AppState>,
     pan_delta: f64,
     tilt_delta: f64,
 ) -> Result<(), String> {
-    // Update local position tracking
+    move_relative(&app, &state, pan_delta, tilt_delta).await
+}
+
+/// [`ptz_move_relative`] for any caller, reporting the new position to `listener`.
+pub(crate) async fn move_relative<L: PositionListener>(
+    listener: &L,
+    state: &AppState,
+    pan_delta: f64,
+    tilt_delta: f64,
+) -> Result<(), String> {
+    abort_moves(state).await;
+    let limits = profile_limits(state).await.unwrap_or_default();
+
+    // Update local position tracking, shortening the move at a soft limit
     let mut pos = state.current_position.lock().await;
-    pos.pan = (pos.pan + pan_delta).clamp(-1.0, 1.0);
-    pos.tilt = (pos.tilt + tilt_delta).clamp(-1.0, 1.0);
+    let (pan, tilt) = limits.clamp(
+        (pos.pan + pan_delta).clamp(-1.0, 1.0),
+        (pos.tilt + tilt_delta).clamp(-1.0, 1.0),
+    );
+    let (pan_delta, tilt_delta) = (pan - pos.pan, tilt - pos.tilt);
+    pos.pan = pan;
+    pos.tilt = tilt;
+    notify_position(listener, state, pos.clone());
     drop(pos);
 
     // Dispatch to active PTZ controller if connected
+    let strict = strict_commands(state).await;
     let dispatcher = state.ptz_dispatcher.lock().await;
-    if dispatcher.has_controller() {
+    if should_dispatch(&dispatcher, strict)? {
         dispatcher
             .move_relative(pan_delta, tilt_delta)
             .await
@@ -26,16 +134,84 @@ pub async fn ptz_move_relative(
     Ok(())
 }
 
+/// Pan/tilt delta for a click in the video frame, using the configured FOV
+/// and click sensitivity and the current zoom.
+#[tauri::command]
+pub async fn ptz_click_to_delta(
+    state: tauri::State<'_, AppState>,
+    click_x: f64,
+    click_y: f64,
+    frame_width: f64,
+    frame_height: f64,
+) -> Result<ClickDelta, String> {
+    let (fov_degrees, sensitivity) = {
+        let config = state.config.lock().await;
+        (config.camera_fov_degrees, config.click_sensitivity)
+    };
+    let curve = state
+        .profiles
+        .lock()
+        .await
+        .get_active_profile()
+        .map(|p| p.lens_curve)
+        .unwrap_or_default();
+    let lens = Lens { fov_degrees, curve };
+    let zoom = state.current_position.lock().await.zoom;
+    let (pan_delta, tilt_delta) = geometry::click_to_delta(
+        click_x,
+        click_y,
+        frame_width,
+        frame_height,
+        lens,
+        zoom,
+        sensitivity,
+    );
+    Ok(ClickDelta {
+        pan_delta,
+        tilt_delta,
+    })
+}
+
+/// Move toward a clicked point in the video frame. Returns the delta applied.
+#[tauri::command]
+pub async fn ptz_click_move(
+    app: tauri::AppHandle,
+    state: tauri::State<'_, AppState>,
+    click_x: f64,
+    click_y: f64,
+    frame_width: f64,
+    frame_height: f64,
+) -> Result<ClickDelta, String> {
+    let delta =
+        ptz_click_to_delta(state.clone(), click_x, click_y, frame_width, frame_height).await?;
+    ptz_move_relative(app, state, delta.pan_delta, delta.tilt_delta).await?;
+    Ok(delta)
+}
+
 /// Move the camera to an absolute pan/tilt/zoom position.
 #[tauri::command]
 pub async fn ptz_move_absolute(
+    app: tauri::AppHandle,
     state: tauri::State<'_, AppState>,
     pan: f64,
     tilt: f64,
     zoom: f64,
 ) -> Result<(), String> {
-    let pan = pan.clamp(-1.0, 1.0);
-    let tilt = tilt.clamp(-1.0, 1.0);
+    move_absolute(&app, &state, pan, tilt, zoom).await
+}
+
+/// [`ptz_move_absolute`], reporting the new position to `listener`.
+pub(crate) async fn move_absolute<L: PositionListener>(
+    listener: &L,
+    state: &AppState,
+    pan: f64,
+    tilt: f64,
+    zoom: f64,
+) -> Result<(), String> {
+    abort_moves(state).await;
+
+    let limits = profile_limits(state).await.unwrap_or_default();
+    let (pan, tilt) = limits.clamp(pan.clamp(-1.0, 1.0), tilt.clamp(-1.0, 1.0));
     let zoom = zoom.clamp(0.0, 1.0);
 
     // Update local position tracking
@@ -43,11 +219,13 @@ pub async fn ptz_move_absolute(
     pos.pan = pan;
     pos.tilt = tilt;
     pos.zoom = zoom;
+    notify_position(listener, state, pos.clone());
     drop(pos);
 
     // Dispatch to active PTZ controller if connected
+    let strict = strict_commands(state).await;
     let dispatcher = state.ptz_dispatcher.lock().await;
-    if dispatcher.has_controller() {
+    if should_dispatch(&dispatcher, strict)? {
         dispatcher
             .move_absolute(pan, tilt, zoom)
             .await
@@ -59,110 +237,436 @@ pub async fn ptz_move_absolute(
 
 /// Set zoom level.
 #[tauri::command]
-pub async fn ptz_zoom(state: tauri::State<'_, AppState>, zoom: f64) -> Result<(), String> {
+pub async fn ptz_zoom(
+    app: tauri::AppHandle,
+    state: tauri::State<'_, AppState>,
+    zoom: f64,
+) -> Result<(), String> {
+    zoom_to(&app, &state, zoom).await
+}
+
+/// [`ptz_zoom`], reporting the new position to `listener`.
+pub(crate) async fn zoom_to<L: PositionListener>(
+    listener: &L,
+    state: &AppState,
+    zoom: f64,
+) -> Result<(), String> {
+    abort_moves(state).await;
+
     let zoom = zoom.clamp(0.0, 1.0);
 
     // Update local position tracking
     let mut pos = state.current_position.lock().await;
     pos.zoom = zoom;
+    notify_position(listener, state, pos.clone());
     drop(pos);
 
     // Dispatch to active PTZ controller if connected
+    let strict = strict_commands(state).await;
     let dispatcher = state.ptz_dispatcher.lock().await;
-    if dispatcher.has_controller() {
+    if should_dispatch(&dispatcher, strict)? {
         dispatcher.zoom_to(zoom).await.map_err(|e| e.to_string())?;
     }
 
     Ok(())
 }
 
+/// Zoom in or out by a normalized delta from the current zoom.
+#[tauri::command]
+pub async fn ptz_zoom_relative(
+    app: tauri::AppHandle,
+    state: tauri::State<'_, AppState>,
+    delta: f64,
+) -> Result<(), String> {
+    if !delta.is_finite() {
+        return Err("Zoom delta must be a finite number".to_string());
+    }
+    abort_moves(&state).await;
+
+    let mut pos = state.current_position.lock().await;
+    pos.zoom = (pos.zoom + delta).clamp(0.0, 1.0);
+    notify_position(&app, &state, pos.clone());
+    drop(pos);
+
+    let strict = strict_commands(&state).await;
+    let dispatcher = state.ptz_dispatcher.lock().await;
+    if should_dispatch(&dispatcher, strict)? {
+        dispatcher.zoom_by(delta).await.map_err(|e| e.to_string())?;
+    }
+
+    Ok(())
+}
+
 /// Recall a preset by its ID, moving the camera to the saved position.
+/// Presets bound to a native slot use the camera's own preset memory.
+/// Otherwise, if the active profile has a transition configured and the camera
+/// can't move at a controlled speed itself, the move is interpolated host-side.
 #[tauri::command]
 pub async fn ptz_recall_preset(
+    app: tauri::AppHandle,
     state: tauri::State<'_, AppState>,
     preset_id: String,
 ) -> Result<(), String> {
+    recall_preset(&app, &state, preset_id).await
+}
+
+/// [`ptz_recall_preset`], reporting each position on the way to `listener`.
+pub(crate) async fn recall_preset<L: PositionListener>(
+    listener: &L,
+    state: &AppState,
+    preset_id: String,
+) -> Result<(), String> {
+    abort_moves(state).await;
+
+    let limits = profile_limits(state).await.unwrap_or_default();
     let profiles = state.profiles.lock().await;
     let preset = profiles.find_preset(&preset_id).ok_or("Preset not found")?;
+    let preset = limits.clamp_preset(&preset);
+    let transition = profiles
+        .get_active_profile()
+        .and_then(|p| p.transition.clone())
+        .filter(|t| t.duration_ms > 0);
 
-    let pan = preset.pan;
-    let tilt = preset.tilt;
-    let zoom = preset.zoom;
+    let target = PtzPosition {
+        pan: preset.pan,
+        tilt: preset.tilt,
+        zoom: preset.zoom,
+    };
     let name = preset.name.clone();
     drop(profiles);
 
-    // Update local position tracking
-    let mut pos = state.current_position.lock().await;
-    pos.pan = pan;
-    pos.tilt = tilt;
-    pos.zoom = zoom;
-    drop(pos);
-
-    // Dispatch absolute move to active PTZ controller
     let dispatcher = state.ptz_dispatcher.lock().await;
-    if dispatcher.has_controller() {
-        dispatcher
-            .move_absolute(pan, tilt, zoom)
-            .await
-            .map_err(|e| e.to_string())?;
+    let interpolate = preset.native_slot.is_none()
+        && dispatcher.has_controller()
+        && !dispatcher.supports_timed_moves();
+    drop(dispatcher);
+
+    match transition {
+        Some(transition) if interpolate => {
+            let start = state.current_position.lock().await.clone();
+            let path = transition::transition_path(
+                &start,
+                &target,
+                Duration::from_millis(transition.duration_ms),
+                transition.easing,
+                STEP_INTERVAL,
+            );
+            let (cancel_tx, cancel_rx) = tokio::sync::watch::channel(false);
+            *state.transition_cancel.lock().await = Some(cancel_tx);
+
+            let step = |pos: PtzPosition| {
+                let (pan, tilt) = limits.clamp(pos.pan, pos.tilt);
+                let pos = PtzPosition { pan, tilt, ..pos };
+                let dispatcher = state.ptz_dispatcher.clone();
+                let current_position = state.current_position.clone();
+                async move {
+                    dispatcher
+                        .lock()
+                        .await
+                        .move_absolute(pos.pan, pos.tilt, pos.zoom)
+                        .await?;
+                    notify_position(listener, state, pos.clone());
+                    *current_position.lock().await = pos;
+                    Ok::<(), PtzError>(())
+                }
+            };
+            let finished = transition::run_transition(path, STEP_INTERVAL, cancel_rx, step)
+                .await
+                .map_err(|e| e.to_string())?;
+            if !finished {
+                log::info!("PTZ recall preset '{}' interrupted", name);
+                return Ok(());
+            }
+            state
+                .ptz_dispatcher
+                .lock()
+                .await
+                .apply_preset_image(&preset)
+                .await
+                .map_err(|e| e.to_string())?;
+        }
+        _ => {
+            // Update local position tracking
+            *state.current_position.lock().await = target.clone();
+            notify_position(listener, state, target.clone());
+
+            // Dispatch to active PTZ controller
+            let strict = strict_commands(state).await;
+            let dispatcher = state.ptz_dispatcher.lock().await;
+            if should_dispatch(&dispatcher, strict)? {
+                dispatcher
+                    .recall_stored_preset(&preset)
+                    .await
+                    .map_err(|e| e.to_string())?;
+            }
+        }
     }
 
     log::info!(
         "PTZ recall preset '{}': pan={}, tilt={}, zoom={}",
         name,
-        pan,
-        tilt,
-        zoom
+        target.pan,
+        target.tilt,
+        target.zoom
     );
     Ok(())
 }
 
+/// Recall the preset at a 1-based position in the active profile, for
+/// control surfaces that address presets by number.
+pub(crate) async fn recall_preset_number<L: PositionListener>(
+    listener: &L,
+    state: &AppState,
+    number: usize,
+) -> Result<(), String> {
+    let preset_id = state
+        .profiles
+        .lock()
+        .await
+        .get_active_profile()
+        .and_then(|p| p.presets.get(number.checked_sub(1)?))
+        .map(|p| p.id.clone())
+        .ok_or_else(|| format!("No preset {} in the active profile", number))?;
+    recall_preset(listener, state, preset_id).await
+}
+
+/// Recall the active profile's preset bound to a keyboard shortcut.
+#[tauri::command]
+pub async fn recall_preset_hotkey(
+    app: tauri::AppHandle,
+    state: tauri::State<'_, AppState>,
+    key: String,
+) -> Result<(), String> {
+    let preset_id = state
+        .profiles
+        .lock()
+        .await
+        .resolve_hotkey(&key)
+        .ok_or_else(|| format!("No preset is bound to '{}'", key))?;
+    ptz_recall_preset(app, state, preset_id).await
+}
+
+/// Reject a native preset slot the camera doesn't have, so it isn't bound
+/// and then fails or wraps at recall time. Slots are numbered from 0.
+fn check_preset_slot(slot: u8, max_slots: u16) -> Result<(), String> {
+    match max_slots {
+        0 => Err("This camera has no preset slots".to_string()),
+        max if u16::from(slot) >= max => Err(format!(
+            "Preset slot {} is out of range: this camera has slots 0-{}",
+            slot,
+            max - 1
+        )),
+        _ => Ok(()),
+    }
+}
+
 /// Store the current camera position as a camera-native preset.
+/// With `preset_id`, the stored preset is bound to that slot for later recalls.
 #[tauri::command]
 pub async fn ptz_store_preset(
     state: tauri::State<'_, AppState>,
     preset_index: u8,
+    preset_id: Option<String>,
 ) -> Result<(), String> {
+    let strict = strict_commands(&state).await;
     let dispatcher = state.ptz_dispatcher.lock().await;
-    if dispatcher.has_controller() {
+    if should_dispatch(&dispatcher, strict)? {
+        check_preset_slot(preset_index, dispatcher.max_preset_slots())?;
         dispatcher
             .store_preset(preset_index)
             .await
             .map_err(|e| e.to_string())?;
     }
+    drop(dispatcher);
+
+    if let Some(preset_id) = preset_id {
+        let mut profiles = state.profiles.lock().await;
+        profiles.set_native_slot(&preset_id, Some(preset_index))?;
+    }
 
     log::info!("PTZ store preset index: {}", preset_index);
     Ok(())
 }
 
-/// Move the camera to its home/center position.
+/// Where homing leaves the camera: the home preset, or center when the
+/// profile has none, clamped into the soft limits.
+fn home_target(home: Option<&Preset>, limits: &PtzLimits) -> PtzPosition {
+    let target = home
+        .map(|p| PtzPosition {
+            pan: p.pan,
+            tilt: p.tilt,
+            zoom: p.zoom,
+        })
+        .unwrap_or_default();
+    let (pan, tilt) = limits.clamp(target.pan, target.tilt);
+    PtzPosition {
+        pan,
+        tilt,
+        ..target
+    }
+}
+
+/// Send the camera to the profile's home preset, or to the protocol home
+/// position when the profile has none. When the limits exclude center, the
+/// camera moves to the nearest allowed point instead of the protocol home.
+async fn dispatch_home(
+    dispatcher: &PtzDispatcher,
+    home: Option<&Preset>,
+    limits: &PtzLimits,
+) -> Result<(), PtzError> {
+    match home {
+        Some(preset) => {
+            dispatcher
+                .recall_stored_preset(&limits.clamp_preset(preset))
+                .await
+        }
+        None if limits.clamp(0.0, 0.0) == (0.0, 0.0) => dispatcher.home().await,
+        None => {
+            let target = home_target(None, limits);
+            dispatcher
+                .move_absolute(target.pan, target.tilt, target.zoom)
+                .await
+        }
+    }
+}
+
+/// Move the camera to the active profile's home preset, or to its
+/// home/center position if the profile doesn't set one.
 #[tauri::command]
-pub async fn ptz_home(state: tauri::State<'_, AppState>) -> Result<(), String> {
-    let mut pos = state.current_position.lock().await;
-    pos.pan = 0.0;
-    pos.tilt = 0.0;
-    pos.zoom = 0.0;
-    drop(pos);
+pub async fn ptz_home(
+    app: tauri::AppHandle,
+    state: tauri::State<'_, AppState>,
+) -> Result<(), String> {
+    home(&app, &state).await
+}
+
+/// [`ptz_home`], reporting the home position to `listener`.
+pub(crate) async fn home<L: PositionListener>(
+    listener: &L,
+    state: &AppState,
+) -> Result<(), String> {
+    abort_moves(state).await;
+
+    let limits = profile_limits(state).await.unwrap_or_default();
+    let home = state
+        .profiles
+        .lock()
+        .await
+        .get_active_profile()
+        .and_then(|p| p.home_preset().cloned());
+    let target = home_target(home.as_ref(), &limits);
 
+    *state.current_position.lock().await = target.clone();
+    notify_position(listener, state, target);
+
+    let strict = strict_commands(state).await;
     let dispatcher = state.ptz_dispatcher.lock().await;
-    if dispatcher.has_controller() {
-        dispatcher.home().await.map_err(|e| e.to_string())?;
+    if should_dispatch(&dispatcher, strict)? {
+        dispatch_home(&dispatcher, home.as_ref(), &limits)
+            .await
+            .map_err(|e| e.to_string())?;
     }
 
     Ok(())
 }
 
-/// Start continuous pan/tilt movement at a given velocity.
+/// Start continuous pan/tilt movement at a given velocity, shaped by the
+/// configured response curve. With soft limits set, the camera's position
+/// is polled during the jog and the camera stopped at the edge; cameras that
+/// can't report their position refuse to jog.
 #[tauri::command]
 pub async fn ptz_continuous_move(
     state: tauri::State<'_, AppState>,
     pan_speed: f64,
     tilt_speed: f64,
 ) -> Result<(), String> {
+    continuous_move(&state, pan_speed, tilt_speed).await
+}
+
+/// [`ptz_continuous_move`] without a Tauri state handle.
+pub(crate) async fn continuous_move(
+    state: &AppState,
+    pan_speed: f64,
+    tilt_speed: f64,
+) -> Result<(), String> {
+    abort_moves(state).await;
+
+    let curve = state.config.lock().await.response_curve;
+    let pan_speed = apply_response_curve(pan_speed, curve);
+    let tilt_speed = apply_response_curve(tilt_speed, curve);
+    let limits = profile_limits(state).await.unwrap_or_default();
+    let pos = state.current_position.lock().await.clone();
+    let (pan_speed, tilt_speed) = limits.limit_velocity(pos.pan, pos.tilt, pan_speed, tilt_speed);
+
+    let strict = strict_commands(state).await;
+    let dispatcher = state.ptz_dispatcher.lock().await;
+    if !should_dispatch(&dispatcher, strict)? {
+        return Ok(());
+    }
+    let guarded = !limits.is_full_range() && (pan_speed != 0.0 || tilt_speed != 0.0);
+    if guarded && !dispatcher.supports_position() {
+        return Err(
+            "This camera can't report its position, so it can't jog within the soft limits"
+                .to_string(),
+        );
+    }
+    dispatcher
+        .continuous_move(pan_speed, tilt_speed)
+        .await
+        .map_err(|e| e.to_string())?;
+
+    if let (true, Some(id)) = (guarded, dispatcher.active_id()) {
+        let guard = jog_guard::spawn(
+            state.ptz_dispatcher.clone(),
+            id.to_string(),
+            limits,
+            (pan_speed, tilt_speed),
+        );
+        drop(dispatcher);
+        if let Some(old) = state.jog_guard_cancel.lock().await.replace(guard) {
+            let _ = old.send(true);
+        }
+    }
+    Ok(())
+}
+
+/// Keep a held jog moving. Without one of these or another command within
+/// the deadman window, the camera is stopped.
+#[tauri::command]
+pub async fn ptz_continuous_move_keepalive(
+    state: tauri::State<'_, AppState>,
+) -> Result<(), String> {
+    continuous_move_keepalive(&state).await
+}
+
+/// [`ptz_continuous_move_keepalive`] without a Tauri state handle.
+pub(crate) async fn continuous_move_keepalive(state: &AppState) -> Result<(), String> {
+    let strict = strict_commands(state).await;
     let dispatcher = state.ptz_dispatcher.lock().await;
-    if dispatcher.has_controller() {
+    if !should_dispatch(&dispatcher, strict)? {
+        return Ok(());
+    }
+    dispatcher
+        .continuous_move_keepalive()
+        .map_err(|e| e.to_string())
+}
+
+/// Start continuous zoom. Negative = wide, positive = tele, zero = stop.
+/// The speed is shaped by the configured response curve.
+#[tauri::command]
+pub async fn ptz_zoom_continuous(
+    state: tauri::State<'_, AppState>,
+    speed: f64,
+) -> Result<(), String> {
+    abort_moves(&state).await;
+
+    let speed = apply_response_curve(speed, state.config.lock().await.response_curve);
+
+    let strict = strict_commands(&state).await;
+    let dispatcher = state.ptz_dispatcher.lock().await;
+    if should_dispatch(&dispatcher, strict)? {
         dispatcher
-            .continuous_move(pan_speed, tilt_speed)
+            .zoom_continuous(speed)
             .await
             .map_err(|e| e.to_string())?;
     }
@@ -173,8 +677,16 @@ pub async fn ptz_continuous_move(
 /// Stop all camera movement.
 #[tauri::command]
 pub async fn ptz_stop(state: tauri::State<'_, AppState>) -> Result<(), String> {
+    stop(&state).await
+}
+
+/// [`ptz_stop`] without a Tauri state handle.
+pub(crate) async fn stop(state: &AppState) -> Result<(), String> {
+    abort_moves(state).await;
+
+    let strict = strict_commands(state).await;
     let dispatcher = state.ptz_dispatcher.lock().await;
-    if dispatcher.has_controller() {
+    if should_dispatch(&dispatcher, strict)? {
         dispatcher.stop().await.map_err(|e| e.to_string())?;
     }
 
@@ -184,8 +696,9 @@ pub async fn ptz_stop(state: tauri::State<'_, AppState>) -> Result<(), String> {
 /// Start continuous focus movement. Negative = near, positive = far.
 #[tauri::command]
 pub async fn ptz_focus(state: tauri::State<'_, AppState>, speed: f64) -> Result<(), String> {
+    let strict = strict_commands(&state).await;
     let dispatcher = state.ptz_dispatcher.lock().await;
-    if dispatcher.has_controller() {
+    if should_dispatch(&dispatcher, strict)? {
         dispatcher
             .focus_continuous(speed)
             .await
@@ -198,22 +711,46 @@ pub async fn ptz_focus(state: tauri::State<'_, AppState>, speed: f64) -> Result<
 /// Stop focus movement.
 #[tauri::command]
 pub async fn ptz_focus_stop(state: tauri::State<'_, AppState>) -> Result<(), String> {
+    let strict = strict_commands(&state).await;
     let dispatcher = state.ptz_dispatcher.lock().await;
-    if dispatcher.has_controller() {
+    if should_dispatch(&dispatcher, strict)? {
         dispatcher.focus_stop().await.map_err(|e| e.to_string())?;
     }
 
     Ok(())
 }
 
+/// Move focus to an absolute position (0.0 = nearest, 1.0 = infinity).
+#[tauri::command]
+pub async fn ptz_focus_to(state: tauri::State<'_, AppState>, position: f64) -> Result<(), String> {
+    let strict = strict_commands(&state).await;
+    let dispatcher = state.ptz_dispatcher.lock().await;
+    if should_dispatch(&dispatcher, strict)? {
+        dispatcher
+            .focus_to(position)
+            .await
+            .map_err(|e| e.to_string())?;
+    }
+
+    Ok(())
+}
+
+/// Query the active camera's focus position (0.0 = nearest, 1.0 = infinity).
+#[tauri::command]
+pub async fn ptz_get_focus(state: tauri::State<'_, AppState>) -> Result<f64, String> {
+    let dispatcher = state.ptz_dispatcher.lock().await;
+    dispatcher.get_focus().await.map_err(|e| e.to_string())
+}
+
 /// Toggle autofocus on or off.
 #[tauri::command]
 pub async fn ptz_set_autofocus(
     state: tauri::State<'_, AppState>,
     enabled: bool,
 ) -> Result<(), String> {
+    let strict = strict_commands(&state).await;
     let dispatcher = state.ptz_dispatcher.lock().await;
-    if dispatcher.has_controller() {
+    if should_dispatch(&dispatcher, strict)? {
         dispatcher
             .set_autofocus(enabled)
             .await
@@ -226,8 +763,9 @@ pub async fn ptz_set_autofocus(
 /// One-push autofocus trigger.
 #[tauri::command]
 pub async fn ptz_autofocus_trigger(state: tauri::State<'_, AppState>) -> Result<(), String> {
+    let strict = strict_commands(&state).await;
     let dispatcher = state.ptz_dispatcher.lock().await;
-    if dispatcher.has_controller() {
+    if should_dispatch(&dispatcher, strict)? {
         dispatcher
             .autofocus_trigger()
             .await
@@ -237,12 +775,369 @@ pub async fn ptz_autofocus_trigger(state: tauri::State<'_, AppState>) -> Result<
     Ok(())
 }
 
+/// Set the iris opening (normalized 0.0 = closed to 1.0 = fully open).
+#[tauri::command]
+pub async fn ptz_set_iris(state: tauri::State<'_, AppState>, iris: f64) -> Result<(), String> {
+    let strict = strict_commands(&state).await;
+    let dispatcher = state.ptz_dispatcher.lock().await;
+    if should_dispatch(&dispatcher, strict)? {
+        dispatcher.set_iris(iris).await.map_err(|e| e.to_string())?;
+    }
+
+    Ok(())
+}
+
+/// Set the sensor gain in dB.
+#[tauri::command]
+pub async fn ptz_set_gain(state: tauri::State<'_, AppState>, gain_db: u8) -> Result<(), String> {
+    let strict = strict_commands(&state).await;
+    let dispatcher = state.ptz_dispatcher.lock().await;
+    if should_dispatch(&dispatcher, strict)? {
+        dispatcher
+            .set_gain(gain_db)
+            .await
+            .map_err(|e| e.to_string())?;
+    }
+
+    Ok(())
+}
+
+/// Set the shutter step (0 = shutter off).
+#[tauri::command]
+pub async fn ptz_set_shutter(state: tauri::State<'_, AppState>, step: u8) -> Result<(), String> {
+    let strict = strict_commands(&state).await;
+    let dispatcher = state.ptz_dispatcher.lock().await;
+    if should_dispatch(&dispatcher, strict)? {
+        dispatcher
+            .set_shutter(step)
+            .await
+            .map_err(|e| e.to_string())?;
+    }
+
+    Ok(())
+}
+
+/// Switch between automatic and manual exposure.
+#[tauri::command]
+pub async fn ptz_set_exposure_mode(
+    state: tauri::State<'_, AppState>,
+    mode: ExposureMode,
+) -> Result<(), String> {
+    let strict = strict_commands(&state).await;
+    let dispatcher = state.ptz_dispatcher.lock().await;
+    if should_dispatch(&dispatcher, strict)? {
+        dispatcher
+            .set_exposure_mode(mode)
+            .await
+            .map_err(|e| e.to_string())?;
+    }
+
+    Ok(())
+}
+
+/// Flip the image vertically, for cameras mounted upside down.
+#[tauri::command]
+pub async fn ptz_set_image_flip(
+    state: tauri::State<'_, AppState>,
+    enabled: bool,
+) -> Result<(), String> {
+    let strict = strict_commands(&state).await;
+    let dispatcher = state.ptz_dispatcher.lock().await;
+    if should_dispatch(&dispatcher, strict)? {
+        dispatcher
+            .set_image_flip(enabled)
+            .await
+            .map_err(|e| e.to_string())?;
+    }
+
+    Ok(())
+}
+
+/// Turn the camera's digital zoom on or off. Endpoints with a digital zoom
+/// maximum map the top of the zoom range past optical tele.
+#[tauri::command]
+pub async fn ptz_set_digital_zoom(
+    state: tauri::State<'_, AppState>,
+    enabled: bool,
+) -> Result<(), String> {
+    let strict = strict_commands(&state).await;
+    let dispatcher = state.ptz_dispatcher.lock().await;
+    if should_dispatch(&dispatcher, strict)? {
+        dispatcher
+            .set_digital_zoom(enabled)
+            .await
+            .map_err(|e| e.to_string())?;
+    }
+
+    Ok(())
+}
+
+/// Freeze the camera's output on the current frame, or release it.
+#[tauri::command]
+pub async fn ptz_set_freeze(
+    state: tauri::State<'_, AppState>,
+    enabled: bool,
+) -> Result<(), String> {
+    let strict = strict_commands(&state).await;
+    let dispatcher = state.ptz_dispatcher.lock().await;
+    if should_dispatch(&dispatcher, strict)? {
+        dispatcher
+            .set_freeze(enabled)
+            .await
+            .map_err(|e| e.to_string())?;
+    }
+
+    Ok(())
+}
+
+/// Move to an absolute position behind a frozen frame, for repositioning
+/// a camera that is on air without the move showing.
+#[tauri::command]
+pub async fn ptz_reposition_frozen(
+    app: tauri::AppHandle,
+    state: tauri::State<'_, AppState>,
+    pan: f64,
+    tilt: f64,
+    zoom: f64,
+) -> Result<(), String> {
+    abort_moves(&state).await;
+
+    let limits = profile_limits(&state).await.unwrap_or_default();
+    let (pan, tilt) = limits.clamp(pan.clamp(-1.0, 1.0), tilt.clamp(-1.0, 1.0));
+    let zoom = zoom.clamp(0.0, 1.0);
+
+    let mut pos = state.current_position.lock().await;
+    pos.pan = pan;
+    pos.tilt = tilt;
+    pos.zoom = zoom;
+    notify_position(&app, &state, pos.clone());
+    drop(pos);
+
+    let strict = strict_commands(&state).await;
+    let dispatcher = state.ptz_dispatcher.lock().await;
+    if should_dispatch(&dispatcher, strict)? {
+        dispatcher
+            .reposition_frozen(pan, tilt, zoom)
+            .await
+            .map_err(|e| e.to_string())?;
+    }
+
+    Ok(())
+}
+
+/// Mirror the image horizontally.
+#[tauri::command]
+pub async fn ptz_set_mirror(
+    state: tauri::State<'_, AppState>,
+    enabled: bool,
+) -> Result<(), String> {
+    let strict = strict_commands(&state).await;
+    let dispatcher = state.ptz_dispatcher.lock().await;
+    if should_dispatch(&dispatcher, strict)? {
+        dispatcher
+            .set_mirror(enabled)
+            .await
+            .map_err(|e| e.to_string())?;
+    }
+
+    Ok(())
+}
+
+/// Set the white-balance mode, including gains for manual balance.
+#[tauri::command]
+pub async fn ptz_set_white_balance(
+    state: tauri::State<'_, AppState>,
+    setting: WhiteBalanceSetting,
+) -> Result<(), String> {
+    let strict = strict_commands(&state).await;
+    let dispatcher = state.ptz_dispatcher.lock().await;
+    if should_dispatch(&dispatcher, strict)? {
+        dispatcher
+            .set_white_balance(setting)
+            .await
+            .map_err(|e| e.to_string())?;
+    }
+
+    Ok(())
+}
+
+/// Open or close the camera's on-screen menu.
+#[tauri::command]
+pub async fn ptz_menu_toggle(state: tauri::State<'_, AppState>) -> Result<(), String> {
+    let strict = strict_commands(&state).await;
+    let dispatcher = state.ptz_dispatcher.lock().await;
+    if should_dispatch(&dispatcher, strict)? {
+        dispatcher.menu_toggle().await.map_err(|e| e.to_string())?;
+    }
+
+    Ok(())
+}
+
+/// Move the on-screen menu cursor.
+#[tauri::command]
+pub async fn ptz_menu_navigate(
+    state: tauri::State<'_, AppState>,
+    direction: MenuDirection,
+) -> Result<(), String> {
+    let strict = strict_commands(&state).await;
+    let dispatcher = state.ptz_dispatcher.lock().await;
+    if should_dispatch(&dispatcher, strict)? {
+        dispatcher
+            .menu_navigate(direction)
+            .await
+            .map_err(|e| e.to_string())?;
+    }
+
+    Ok(())
+}
+
+/// Select the highlighted on-screen menu item.
+#[tauri::command]
+pub async fn ptz_menu_enter(state: tauri::State<'_, AppState>) -> Result<(), String> {
+    let strict = strict_commands(&state).await;
+    let dispatcher = state.ptz_dispatcher.lock().await;
+    if should_dispatch(&dispatcher, strict)? {
+        dispatcher.menu_enter().await.map_err(|e| e.to_string())?;
+    }
+
+    Ok(())
+}
+
+/// Set the active camera's tally lamp.
+#[tauri::command]
+pub async fn ptz_set_tally(
+    state: tauri::State<'_, AppState>,
+    tally: TallyState,
+) -> Result<(), String> {
+    let strict = strict_commands(&state).await;
+    let dispatcher = state.ptz_dispatcher.lock().await;
+    if should_dispatch(&dispatcher, strict)? {
+        dispatcher
+            .set_tally(tally)
+            .await
+            .map_err(|e| e.to_string())?;
+    }
+
+    Ok(())
+}
+
+/// Rename the NDI source published by the active camera.
+#[tauri::command]
+pub async fn ptz_set_ndi_name(
+    state: tauri::State<'_, AppState>,
+    name: String,
+) -> Result<(), String> {
+    let strict = strict_commands(&state).await;
+    let dispatcher = state.ptz_dispatcher.lock().await;
+    if should_dispatch(&dispatcher, strict)? {
+        dispatcher
+            .set_ndi_name(&name)
+            .await
+            .map_err(|e| e.to_string())?;
+    }
+
+    Ok(())
+}
+
+/// Select a gamma curve on the active camera. Modes are model-specific.
+#[tauri::command]
+pub async fn ptz_set_gamma(state: tauri::State<'_, AppState>, mode: u8) -> Result<(), String> {
+    let strict = strict_commands(&state).await;
+    let dispatcher = state.ptz_dispatcher.lock().await;
+    if should_dispatch(&dispatcher, strict)? {
+        dispatcher
+            .set_gamma(mode)
+            .await
+            .map_err(|e| e.to_string())?;
+    }
+
+    Ok(())
+}
+
+/// Recall a stored picture profile on the active camera.
+#[tauri::command]
+pub async fn ptz_recall_picture_profile(
+    state: tauri::State<'_, AppState>,
+    index: u8,
+) -> Result<(), String> {
+    let strict = strict_commands(&state).await;
+    let dispatcher = state.ptz_dispatcher.lock().await;
+    if should_dispatch(&dispatcher, strict)? {
+        dispatcher
+            .recall_picture_profile(index)
+            .await
+            .map_err(|e| e.to_string())?;
+    }
+
+    Ok(())
+}
+
+/// Bytes as space-separated uppercase hex, e.g. `90 50 FF`.
+fn to_hex(bytes: &[u8]) -> String {
+    bytes
+        .iter()
+        .map(|b| format!("{:02X}", b))
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+/// Send a raw protocol message to the active camera and return its reply as
+/// hex. Only available in developer mode.
+#[tauri::command]
+pub async fn ptz_send_raw(
+    state: tauri::State<'_, AppState>,
+    bytes: Vec<u8>,
+) -> Result<String, String> {
+    if !state.config.lock().await.developer_mode {
+        return Err("Raw commands require developer mode".to_string());
+    }
+    let dispatcher = state.ptz_dispatcher.lock().await;
+    let reply = dispatcher
+        .send_raw(&bytes)
+        .await
+        .map_err(|e| e.to_string())?;
+    Ok(to_hex(&reply))
+}
+
+/// Show the active VISCA camera's sequence counter, connection and last
+/// reply. Only available in developer mode.
+#[tauri::command]
+pub async fn visca_diagnostics(
+    state: tauri::State<'_, AppState>,
+) -> Result<ViscaDiagnostics, String> {
+    if !state.config.lock().await.developer_mode {
+        return Err("VISCA diagnostics require developer mode".to_string());
+    }
+    let dispatcher = state.ptz_dispatcher.lock().await;
+    dispatcher
+        .visca_diagnostics()
+        .await
+        .map_err(|e| e.to_string())
+}
+
+/// Restart the active VISCA camera's sequence numbering without
+/// reconnecting. Only available in developer mode.
+#[tauri::command]
+pub async fn visca_reset_sequence(state: tauri::State<'_, AppState>) -> Result<(), String> {
+    if !state.config.lock().await.developer_mode {
+        return Err("VISCA diagnostics require developer mode".to_string());
+    }
+    let dispatcher = state.ptz_dispatcher.lock().await;
+    dispatcher.reset_sequence().await.map_err(|e| e.to_string())
+}
+
 /// Get the current PTZ position.
 #[tauri::command]
 pub async fn ptz_get_position(state: tauri::State<'_, AppState>) -> Result<PtzPosition, String> {
+    get_position(&state).await
+}
+
+/// [`ptz_get_position`] without a Tauri state handle.
+pub(crate) async fn get_position(state: &AppState) -> Result<PtzPosition, String> {
     // If we have an active controller, query the camera for its real position
+    let strict = strict_commands(state).await;
     let dispatcher = state.ptz_dispatcher.lock().await;
-    if dispatcher.has_controller() {
+    if should_dispatch(&dispatcher, strict)? {
         match dispatcher.get_position().await {
             Ok(hw_pos) => {
                 drop(dispatcher);
@@ -264,3 +1159,243 @@ pub async fn ptz_get_position(state: tauri::State<'_, AppState>) -> Result<PtzPo
     let pos = state.current_position.lock().await;
     Ok(pos.clone())
 }
+
+/// Id of the active profile's preset the camera is on, for highlighting in
+/// the UI. Cameras that report their last recalled preset slot are asked
+/// directly; otherwise the current position is matched against the presets,
+/// allowing `tolerance` (default `ACTIVE_PRESET_TOLERANCE`) on each axis.
+#[tauri::command]
+pub async fn get_active_preset(
+    state: tauri::State<'_, AppState>,
+    tolerance: Option<f64>,
+) -> Result<Option<String>, String> {
+    let tolerance = tolerance.unwrap_or(geometry::ACTIVE_PRESET_TOLERANCE);
+    if !tolerance.is_finite() || tolerance < 0.0 {
+        return Err(format!("Invalid preset tolerance: {}", tolerance));
+    }
+
+    let strict = strict_commands(&state).await;
+    let dispatcher = state.ptz_dispatcher.lock().await;
+    let slot = if should_dispatch(&dispatcher, strict)? {
+        dispatcher.get_active_preset().await.unwrap_or_else(|e| {
+            log::warn!("Failed to query the camera's active preset: {}", e);
+            None
+        })
+    } else {
+        None
+    };
+    drop(dispatcher);
+
+    if let Some(slot) = slot {
+        let presets = state.profiles.lock().await.get_presets();
+        if let Some(preset) = presets.into_iter().find(|p| p.native_slot == Some(slot)) {
+            return Ok(Some(preset.id));
+        }
+    }
+
+    let position = ptz_get_position(state.clone()).await?;
+    let presets = state.profiles.lock().await.get_presets();
+    Ok(geometry::infer_active_preset(&position, &presets, tolerance).map(|p| p.id.clone()))
+}
+
+/// The newest `limit` commands sent to any camera, oldest first.
+#[tauri::command]
+pub async fn get_command_log(
+    state: tauri::State<'_, AppState>,
+    limit: usize,
+) -> Result<Vec<CommandLogEntry>, String> {
+    Ok(state.command_log.recent(limit))
+}
+
+#[tauri::command]
+pub async fn clear_command_log(state: tauri::State<'_, AppState>) -> Result<(), String> {
+    state.command_log.clear();
+    Ok(())
+}
+
+/// Turn dry-run mode on or off. While on, commands that would move or
+/// reconfigure the camera are only logged.
+#[tauri::command]
+pub async fn set_dry_run(state: tauri::State<'_, AppState>, enabled: bool) -> Result<(), String> {
+    state.ptz_dispatcher.lock().await.set_dry_run(enabled);
+    log::info!("Dry-run mode {}", if enabled { "on" } else { "off" });
+    Ok(())
+}
+
+/// The requests a command would send to the active camera, without sending it.
+#[tauri::command]
+pub async fn preview_ptz_command(
+    state: tauri::State<'_, AppState>,
+    command: PtzCommand,
+) -> Result<CommandPreview, String> {
+    state
+        .ptz_dispatcher
+        .lock()
+        .await
+        .preview(&command)
+        .map_err(|e| e.to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::simulator::client::SimulatedController;
+
+    #[test]
+    fn lenient_mode_skips_commands_without_controller() {
+        assert_eq!(dispatch_decision(false, false), Ok(false));
+        assert_eq!(dispatch_decision(true, false), Ok(true));
+    }
+
+    #[test]
+    fn strict_mode_rejects_commands_without_controller() {
+        assert_eq!(
+            dispatch_decision(false, true),
+            Err("No active endpoint".to_string())
+        );
+        assert_eq!(dispatch_decision(true, true), Ok(true));
+    }
+
+    async fn dispatcher_at(pan: f64, tilt: f64, zoom: f64) -> PtzDispatcher {
+        let mut dispatcher = PtzDispatcher::new();
+        dispatcher.add_controller("sim", Box::new(SimulatedController::new()));
+        dispatcher.set_active("sim").unwrap();
+        dispatcher.move_absolute(pan, tilt, zoom).await.unwrap();
+        dispatcher
+    }
+
+    #[tokio::test]
+    async fn home_without_preset_uses_protocol_home() {
+        let dispatcher = dispatcher_at(0.5, 0.5, 0.5).await;
+        dispatch_home(&dispatcher, None, &PtzLimits::default())
+            .await
+            .unwrap();
+        assert_eq!(
+            dispatcher.get_position().await.unwrap(),
+            PtzPosition::default()
+        );
+    }
+
+    #[tokio::test]
+    async fn home_stays_inside_limits_that_exclude_center() {
+        let dispatcher = dispatcher_at(0.5, 0.5, 0.5).await;
+        let limits = PtzLimits {
+            pan_min: 0.2,
+            pan_max: 1.0,
+            ..PtzLimits::default()
+        };
+        dispatch_home(&dispatcher, None, &limits).await.unwrap();
+        let pos = dispatcher.get_position().await.unwrap();
+        assert_eq!((pos.pan, pos.tilt), (0.2, 0.0));
+        assert_eq!(home_target(None, &limits).pan, 0.2);
+    }
+
+    #[tokio::test]
+    async fn home_with_preset_recalls_it() {
+        let dispatcher = dispatcher_at(0.0, 0.0, 0.0).await;
+        let preset = Preset {
+            id: "stage".to_string(),
+            name: "Stage".to_string(),
+            pan: -0.25,
+            tilt: 0.1,
+            zoom: 0.4,
+            color: "#3b82f6".to_string(),
+            native_slot: None,
+            focus: None,
+            white_balance: None,
+            hotkey: None,
+            thumbnail: None,
+        };
+        dispatch_home(&dispatcher, Some(&preset), &PtzLimits::default())
+            .await
+            .unwrap();
+        assert_eq!(
+            dispatcher.get_position().await.unwrap(),
+            PtzPosition {
+                pan: -0.25,
+                tilt: 0.1,
+                zoom: 0.4
+            }
+        );
+
+        let limits = PtzLimits {
+            pan_min: 0.0,
+            pan_max: 1.0,
+            ..PtzLimits::default()
+        };
+        dispatch_home(&dispatcher, Some(&preset), &limits)
+            .await
+            .unwrap();
+        let pos = dispatcher.get_position().await.unwrap();
+        assert_eq!((pos.pan, pos.tilt), (0.0, 0.1));
+    }
+
+    #[tokio::test]
+    async fn jogs_inside_limits_need_position_feedback() {
+        use crate::ptz::geometry::LensCurve;
+        use crate::ptz::types::{DegreeLimits, PresetProfile};
+
+        let dir = std::env::temp_dir().join(format!("ptzcam-test-jog-{}", uuid::Uuid::new_v4()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let state = AppState::new(dir.clone());
+        state
+            .profiles
+            .lock()
+            .await
+            .create_profile(PresetProfile {
+                id: "p1".to_string(),
+                name: "Main".to_string(),
+                camera_fov_degrees: 60.0,
+                endpoint_id: None,
+                presets: Vec::new(),
+                tour: None,
+                transition: None,
+                home_preset_id: None,
+                lens_curve: LensCurve::default(),
+                mechanical_range: None,
+                limits_degrees: Some(DegreeLimits {
+                    pan_min: -10.0,
+                    pan_max: 10.0,
+                    tilt_min: -10.0,
+                    tilt_max: 10.0,
+                }),
+            })
+            .unwrap();
+        {
+            let mut dispatcher = state.ptz_dispatcher.lock().await;
+            let pelco = crate::pelco::client::PelcoClient::new("127.0.0.1", 4001, 1).unwrap();
+            dispatcher.add_controller("pelco", Box::new(pelco));
+            dispatcher.add_controller("sim", Box::new(SimulatedController::new()));
+            dispatcher.set_active("pelco").unwrap();
+        }
+
+        let refused = continuous_move(&state, 0.5, 0.0).await.unwrap_err();
+        assert!(refused.contains("can't report its position"), "{refused}");
+        assert!(state.jog_guard_cancel.lock().await.is_none());
+
+        state.ptz_dispatcher.lock().await.set_active("sim").unwrap();
+        continuous_move(&state, 0.5, 0.0).await.unwrap();
+        assert!(state.jog_guard_cancel.lock().await.is_some());
+        stop(&state).await.unwrap();
+        assert!(state.jog_guard_cancel.lock().await.is_none());
+        std::fs::remove_dir_all(&dir).ok();
+    }
+
+    #[test]
+    fn preset_slots_must_exist_on_the_camera() {
+        assert_eq!(check_preset_slot(0, 100), Ok(()));
+        assert_eq!(check_preset_slot(99, 100), Ok(()));
+        assert_eq!(
+            check_preset_slot(100, 100),
+            Err("Preset slot 100 is out of range: this camera has slots 0-99".to_string())
+        );
+        assert_eq!(check_preset_slot(255, 256), Ok(()));
+        assert!(check_preset_slot(0, 0).is_err());
+    }
+
+    #[test]
+    fn raw_replies_are_formatted_as_hex() {
+        assert_eq!(to_hex(&[0x90, 0x50, 0x0A, 0xFF]), "90 50 0A FF");
+        assert_eq!(to_hex(&[]), "");
+    }
+}
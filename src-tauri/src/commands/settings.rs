@@ -1,5 +1,35 @@
+use crate::gamepad::mapping::{GamepadSettings, MAX_DEADZONE};
 use crate::persistence::config::AppConfig;
+use crate::persistence::save::LoadWarning;
+use crate::ptz::nudge::NudgeDwell;
+use crate::ptz::response_curve::{ResponseCurve, MAX_RESPONSE_EXPONENT, MIN_RESPONSE_EXPONENT};
 use crate::AppState;
+use std::time::Duration;
+
+/// Highest accepted jog command rate, per camera per second.
+const MAX_COMMAND_RATE_LIMIT: u32 = 100;
+
+/// Longest NDI source cache lifetime, in milliseconds.
+const MAX_NDI_SOURCE_CACHE_TTL_MS: u64 = 60_000;
+
+/// Longest a continuous move may go unconfirmed, in milliseconds.
+const MAX_DEADMAN_WINDOW_MS: u64 = 10_000;
+
+/// Data files that failed to load at startup and were set aside, so the UI
+/// can tell the user their data was kept rather than lost.
+#[tauri::command]
+pub async fn get_load_warnings(
+    state: tauri::State<'_, AppState>,
+) -> Result<Vec<LoadWarning>, String> {
+    Ok(state.load_warnings.clone())
+}
+
+/// Copy the settings out and unlock them. PTZ commands lock the dispatcher
+/// before reading config, so settings that reach the dispatcher are applied
+/// only after config is released.
+fn release(config: tokio::sync::MutexGuard<'_, AppConfig>) -> AppConfig {
+    config.clone()
+}
 
 /// Get current application settings.
 #[tauri::command]
@@ -24,6 +54,8 @@ pub async fn update_settings(
     scroll_sensitivity: Option<f64>,
     overlay_opacity: Option<f64>,
     camera_fov_degrees: Option<f64>,
+    position_poll_interval_ms: Option<u64>,
+    profile_backup_count: Option<usize>,
 ) -> Result<AppConfig, String> {
     let mut config = state.config.lock().await;
 
@@ -39,7 +71,236 @@ pub async fn update_settings(
     if let Some(v) = camera_fov_degrees {
         config.camera_fov_degrees = validate_and_clamp(v, 10.0, 180.0, "camera_fov_degrees")?;
     }
+    if let Some(v) = position_poll_interval_ms {
+        config.position_poll_interval_ms = v.clamp(100, 10_000);
+    }
+    if let Some(v) = profile_backup_count {
+        config.profile_backup_count = v.min(50);
+        state
+            .profiles
+            .lock()
+            .await
+            .set_max_backups(config.profile_backup_count);
+    }
+
+    config.save()?;
+    Ok(config.clone())
+}
+
+/// Restore all settings to their defaults and return the new config.
+/// The active profile and video source are kept unless `reset_selection` is true.
+#[tauri::command]
+pub async fn reset_settings(
+    state: tauri::State<'_, AppState>,
+    reset_selection: Option<bool>,
+) -> Result<AppConfig, String> {
+    let mut config = state.config.lock().await;
+    config.reset(reset_selection.unwrap_or(false));
+    state
+        .profiles
+        .lock()
+        .await
+        .set_max_backups(config.profile_backup_count);
+    state
+        .ndi_sources
+        .set_ttl(Duration::from_millis(config.ndi_source_cache_ttl_ms));
+    let saved = config.save();
+    let config = release(config);
+    state
+        .ptz_dispatcher
+        .lock()
+        .await
+        .set_command_rate(config.command_rate_limit);
+    state
+        .ptz_dispatcher
+        .lock()
+        .await
+        .set_deadman_window(Duration::from_millis(config.deadman_window_ms));
+    state
+        .ptz_dispatcher
+        .lock()
+        .await
+        .set_nudge_dwell(config.nudge_dwell);
+    saved?;
+    Ok(config)
+}
+
+/// Restore a single setting to its default and return the new config.
+#[tauri::command]
+pub async fn reset_settings_field(
+    state: tauri::State<'_, AppState>,
+    field_name: String,
+) -> Result<AppConfig, String> {
+    let mut config = state.config.lock().await;
+    config.reset_field(&field_name)?;
+    if field_name == "profile_backup_count" {
+        state
+            .profiles
+            .lock()
+            .await
+            .set_max_backups(config.profile_backup_count);
+    }
+    if field_name == "ndi_source_cache_ttl_ms" {
+        state
+            .ndi_sources
+            .set_ttl(Duration::from_millis(config.ndi_source_cache_ttl_ms));
+    }
+    let saved = config.save();
+    let config = release(config);
+    if field_name == "command_rate_limit" {
+        state
+            .ptz_dispatcher
+            .lock()
+            .await
+            .set_command_rate(config.command_rate_limit);
+    }
+    if field_name == "deadman_window_ms" {
+        state
+            .ptz_dispatcher
+            .lock()
+            .await
+            .set_deadman_window(Duration::from_millis(config.deadman_window_ms));
+    }
+    if field_name == "nudge_dwell" {
+        state
+            .ptz_dispatcher
+            .lock()
+            .await
+            .set_nudge_dwell(config.nudge_dwell);
+    }
+    saved?;
+    Ok(config)
+}
+
+/// Update the gamepad deadzone and axis inversion.
+/// Takes effect the next time the gamepad is started.
+#[tauri::command]
+pub async fn update_gamepad_settings(
+    state: tauri::State<'_, AppState>,
+    mut settings: GamepadSettings,
+) -> Result<AppConfig, String> {
+    settings.deadzone = validate_and_clamp(settings.deadzone, 0.0, MAX_DEADZONE, "deadzone")?;
+    let mut config = state.config.lock().await;
+    config.gamepad = settings;
+    config.save()?;
+    Ok(config.clone())
+}
+
+/// Choose whether PTZ commands fail when no endpoint is active.
+/// Off by default, so commands without a camera are silently ignored.
+#[tauri::command]
+pub async fn set_strict_commands(
+    state: tauri::State<'_, AppState>,
+    enabled: bool,
+) -> Result<AppConfig, String> {
+    let mut config = state.config.lock().await;
+    config.strict_commands = enabled;
+    config.save()?;
+    Ok(config.clone())
+}
+
+/// Turn developer mode, which unlocks raw protocol commands, on or off.
+#[tauri::command]
+pub async fn set_developer_mode(
+    state: tauri::State<'_, AppState>,
+    enabled: bool,
+) -> Result<AppConfig, String> {
+    let mut config = state.config.lock().await;
+    config.developer_mode = enabled;
+    config.save()?;
+    Ok(config.clone())
+}
+
+/// Cap how many jog commands (moves, zoom and focus drives) are sent to each
+/// camera per second; bursts beyond it collapse to the latest command.
+/// 0 removes the cap.
+#[tauri::command]
+pub async fn set_command_rate_limit(
+    state: tauri::State<'_, AppState>,
+    per_second: u32,
+) -> Result<AppConfig, String> {
+    let per_second = per_second.min(MAX_COMMAND_RATE_LIMIT);
+    state
+        .ptz_dispatcher
+        .lock()
+        .await
+        .set_command_rate(per_second);
+    let mut config = state.config.lock().await;
+    config.command_rate_limit = per_second;
+    config.save()?;
+    Ok(config.clone())
+}
+
+/// Set how long discovered NDI sources are reused before discovery runs
+/// again. 0 discovers on every request.
+#[tauri::command]
+pub async fn set_ndi_source_cache_ttl(
+    state: tauri::State<'_, AppState>,
+    ttl_ms: u64,
+) -> Result<AppConfig, String> {
+    let mut config = state.config.lock().await;
+    config.ndi_source_cache_ttl_ms = ttl_ms.min(MAX_NDI_SOURCE_CACHE_TTL_MS);
+    state
+        .ndi_sources
+        .set_ttl(Duration::from_millis(config.ndi_source_cache_ttl_ms));
+    config.save()?;
+    Ok(config.clone())
+}
+
+/// Set how long a continuous move may run without a follow-up command or
+/// keepalive before the camera is stopped. 0 lets moves run until stopped.
+#[tauri::command]
+pub async fn set_deadman_window(
+    state: tauri::State<'_, AppState>,
+    window_ms: u64,
+) -> Result<AppConfig, String> {
+    let window_ms = window_ms.min(MAX_DEADMAN_WINDOW_MS);
+    state
+        .ptz_dispatcher
+        .lock()
+        .await
+        .set_deadman_window(Duration::from_millis(window_ms));
+    let mut config = state.config.lock().await;
+    config.deadman_window_ms = window_ms;
+    config.save()?;
+    Ok(config.clone())
+}
 
+/// Set the shortest and longest time a relative move drives cameras that
+/// nudge by moving briefly; larger moves drive longer.
+#[tauri::command]
+pub async fn set_nudge_dwell(
+    state: tauri::State<'_, AppState>,
+    min_ms: u64,
+    max_ms: u64,
+) -> Result<AppConfig, String> {
+    let dwell = NudgeDwell { min_ms, max_ms };
+    dwell.validate()?;
+    state.ptz_dispatcher.lock().await.set_nudge_dwell(dwell);
+    let mut config = state.config.lock().await;
+    config.nudge_dwell = dwell;
+    config.save()?;
+    Ok(config.clone())
+}
+
+/// Choose how jog velocities from the gamepad and on-screen controls are
+/// shaped before reaching the camera.
+#[tauri::command]
+pub async fn set_response_curve(
+    state: tauri::State<'_, AppState>,
+    curve: ResponseCurve,
+) -> Result<AppConfig, String> {
+    let curve = match curve {
+        ResponseCurve::Exponent(e) => ResponseCurve::Exponent(validate_and_clamp(
+            e,
+            MIN_RESPONSE_EXPONENT,
+            MAX_RESPONSE_EXPONENT,
+            "exponent",
+        )?),
+        other => other,
+    };
+    let mut config = state.config.lock().await;
+    config.response_curve = curve;
     config.save()?;
     Ok(config.clone())
 }
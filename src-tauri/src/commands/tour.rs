@@ -0,0 +1,96 @@
+use crate::ptz::tour::{self, TourStep};
+use crate::AppState;
+
+/// Stop the running tour, if any. Returns whether one was running.
+pub(crate) async fn stop_tour_task(state: &AppState) -> bool {
+    match state.tour_cancel.lock().await.take() {
+        Some(cancel_tx) => {
+            let _ = cancel_tx.send(true);
+            log::info!("Preset tour stopped");
+            true
+        }
+        None => false,
+    }
+}
+
+/// Save (or clear) the tour on the active profile.
+#[tauri::command]
+pub async fn set_tour(
+    state: tauri::State<'_, AppState>,
+    steps: Option<Vec<TourStep>>,
+) -> Result<(), String> {
+    if let Some(steps) = &steps {
+        tour::validate_tour(steps)?;
+    }
+    let mut profiles = state.profiles.lock().await;
+    let profile = profiles
+        .get_active_profile_mut()
+        .ok_or("No active profile")?;
+    profile.tour = steps;
+    profiles.save()
+}
+
+/// Start cycling the active profile's tour, replacing any running tour.
+#[tauri::command]
+pub async fn start_tour(state: tauri::State<'_, AppState>) -> Result<(), String> {
+    let steps = state
+        .profiles
+        .lock()
+        .await
+        .get_active_profile()
+        .and_then(|p| p.tour.clone())
+        .ok_or("Active profile has no tour")?;
+    tour::validate_tour(&steps)?;
+
+    stop_tour_task(&state).await;
+    let (cancel_tx, cancel_rx) = tokio::sync::watch::channel(false);
+
+    let profiles = state.profiles.clone();
+    let dispatcher = state.ptz_dispatcher.clone();
+    let current_position = state.current_position.clone();
+    let step_count = steps.len();
+    tokio::spawn(async move {
+        let recall = |step: &TourStep| {
+            let profiles = profiles.clone();
+            let dispatcher = dispatcher.clone();
+            let current_position = current_position.clone();
+            let preset_id = step.preset_id.clone();
+            async move {
+                // Look the preset up each time so edits apply on the next lap
+                let preset = profiles
+                    .lock()
+                    .await
+                    .find_preset(&preset_id)
+                    .ok_or("Preset not found")?;
+
+                let dispatcher = dispatcher.lock().await;
+                if dispatcher.has_controller() {
+                    dispatcher
+                        .recall_stored_preset(&preset)
+                        .await
+                        .map_err(|e| e.to_string())?;
+                }
+                drop(dispatcher);
+
+                let mut pos = current_position.lock().await;
+                pos.pan = preset.pan;
+                pos.tilt = preset.tilt;
+                pos.zoom = preset.zoom;
+                log::info!("Tour recalled preset '{}'", preset.name);
+                Ok(())
+            }
+        };
+        tour::run_tour(steps, cancel_rx, recall).await;
+    });
+
+    *state.tour_cancel.lock().await = Some(cancel_tx);
+    log::info!("Preset tour started with {} step(s)", step_count);
+    Ok(())
+}
+
+/// Stop the running tour.
+#[tauri::command]
+pub async fn stop_tour(state: tauri::State<'_, AppState>) -> Result<(), String> {
+    stop_tour_task(&state).await;
+    Ok(())
+}
@@ -1,6 +1,7 @@
-use crate::video::ndi_source::{self, NdiSource};
+use crate::video::ndi_source::{self, DiscoveryOptions, NdiSource};
 use crate::AppState;
 use serde::{Deserialize, Serialize};
+use std::time::Duration;
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct LocalDevice {
@@ -9,9 +10,31 @@ pub struct LocalDevice {
 }
 
 /// List available NDI sources on the network.
+/// Optionally scoped to NDI groups and extra IPs; `timeout_ms` bounds the wait.
+/// A recent list for the same scope is reused unless `force` is set.
 #[tauri::command]
-pub async fn list_ndi_sources() -> Result<Vec<NdiSource>, String> {
-    Ok(ndi_source::discover_sources().await)
+pub async fn list_ndi_sources(
+    state: tauri::State<'_, AppState>,
+    timeout_ms: Option<u64>,
+    groups: Option<Vec<String>>,
+    extra_ips: Option<Vec<String>>,
+    force: Option<bool>,
+) -> Result<Vec<NdiSource>, String> {
+    let mut options = DiscoveryOptions {
+        groups: groups.unwrap_or_default(),
+        extra_ips: extra_ips.unwrap_or_default(),
+        ..Default::default()
+    };
+    if let Some(ms) = timeout_ms {
+        options.timeout = Duration::from_millis(ms);
+    }
+    let sources = state
+        .ndi_sources
+        .get_or_discover(&options, force.unwrap_or(false), |options| async move {
+            ndi_source::discover_sources(&options).await
+        })
+        .await;
+    Ok(sources)
 }
 
 /// List local video capture devices.
@@ -25,36 +48,98 @@ pub async fn list_local_devices() -> Result<Vec<LocalDevice>, String> {
 }
 
 /// Start the MJPEG stream server for NDI or fallback capture sources.
-/// Returns the port number of the localhost MJPEG server.
+/// Binds to `bind_address` (default `127.0.0.1`) on `port` (default random).
+/// `target_fps` caps the frame rate sent to clients; `jpeg_quality` is used for the
+/// full-quality tier. Clients pick a tier with `/stream?quality=low|high&fps=N`.
+/// Returns the port number the server is listening on.
 #[tauri::command]
-pub async fn start_mjpeg_stream(state: tauri::State<'_, AppState>) -> Result<u16, String> {
+pub async fn start_mjpeg_stream(
+    state: tauri::State<'_, AppState>,
+    bind_address: Option<String>,
+    port: Option<u16>,
+    target_fps: Option<u32>,
+    jpeg_quality: Option<u8>,
+) -> Result<u16, String> {
     use crate::video::mjpeg_server;
     use std::sync::Arc;
 
-    // Stop any existing server first
+    let options = mjpeg_server::ServerOptions::parse(bind_address.as_deref(), port)?;
+
+    // Stop any existing server (and the preview feeding it) first
+    if let Some(stop_tx) = state.ndi_preview_stop.lock().await.take() {
+        let _ = stop_tx.send(true);
+    }
     if let Some(shutdown_tx) = state.mjpeg_shutdown.lock().await.take() {
         let _ = shutdown_tx.send(true);
     }
 
-    let mjpeg_state = Arc::new(mjpeg_server::MjpegState::new());
-    let (port, shutdown_tx) = mjpeg_server::start_server(mjpeg_state).await?;
+    let mjpeg_state = Arc::new(
+        mjpeg_server::MjpegState::new()
+            .with_target_fps(target_fps.unwrap_or(mjpeg_server::DEFAULT_TARGET_FPS))
+            .with_jpeg_quality(jpeg_quality.unwrap_or(mjpeg_server::DEFAULT_JPEG_QUALITY)),
+    );
+    let (port, shutdown_tx) = mjpeg_server::start_server(mjpeg_state.clone(), &options).await?;
 
     *state.mjpeg_port.lock().await = Some(port);
     *state.mjpeg_shutdown.lock().await = Some(shutdown_tx);
+    *state.mjpeg_state.lock().await = Some(mjpeg_state);
     Ok(port)
 }
 
 /// Stop the MJPEG stream server.
 #[tauri::command]
 pub async fn stop_mjpeg_stream(state: tauri::State<'_, AppState>) -> Result<(), String> {
+    // Stop any NDI preview feeding the server
+    if let Some(stop_tx) = state.ndi_preview_stop.lock().await.take() {
+        let _ = stop_tx.send(true);
+    }
     // Send shutdown signal to the server task
     if let Some(shutdown_tx) = state.mjpeg_shutdown.lock().await.take() {
         let _ = shutdown_tx.send(true);
     }
     *state.mjpeg_port.lock().await = None;
+    *state.mjpeg_state.lock().await = None;
     Ok(())
 }
 
+/// Feed an NDI source into the running MJPEG server, replacing any current preview.
+#[tauri::command]
+pub async fn start_ndi_preview(
+    state: tauri::State<'_, AppState>,
+    source_name: String,
+) -> Result<(), String> {
+    use crate::video::ndi_preview;
+
+    let mjpeg_state = state
+        .mjpeg_state
+        .lock()
+        .await
+        .clone()
+        .ok_or("MJPEG server is not running")?;
+
+    let mut preview_stop = state.ndi_preview_stop.lock().await;
+    if let Some(stop_tx) = preview_stop.take() {
+        let _ = stop_tx.send(true);
+    }
+
+    let (stop_tx, stop_rx) = tokio::sync::watch::channel(false);
+    ndi_preview::spawn_preview(&source_name, mjpeg_state, stop_rx)?;
+    *preview_stop = Some(stop_tx);
+    Ok(())
+}
+
+/// Number of clients watching the MJPEG stream (0 when the server is stopped).
+#[tauri::command]
+pub async fn get_mjpeg_client_count(state: tauri::State<'_, AppState>) -> Result<usize, String> {
+    Ok(state
+        .mjpeg_state
+        .lock()
+        .await
+        .as_ref()
+        .map(|s| s.client_count())
+        .unwrap_or(0))
+}
+
 /// Get the current MJPEG server port, if running.
 #[tauri::command]
 pub async fn get_mjpeg_port(state: tauri::State<'_, AppState>) -> Result<Option<u16>, String> {
@@ -0,0 +1,138 @@
+use crate::commands::ptz::{self, PositionListener};
+use crate::ptz::types::PtzPosition;
+use crate::video::mjpeg_server::ServerOptions;
+use crate::ws_control::message::ControlCommand;
+use crate::AppState;
+
+/// Run a WebSocket control command through the same command layer as the UI,
+/// so soft limits, strict mode and position tracking apply to it too.
+async fn run_control_command<L: PositionListener>(
+    listener: &L,
+    state: &AppState,
+    command: ControlCommand,
+) -> Result<Option<PtzPosition>, String> {
+    match command {
+        ControlCommand::MoveAbsolute { pan, tilt, zoom } => {
+            ptz::move_absolute(listener, state, pan, tilt, zoom).await
+        }
+        ControlCommand::MoveRelative {
+            pan_delta,
+            tilt_delta,
+        } => ptz::move_relative(listener, state, pan_delta, tilt_delta).await,
+        ControlCommand::ContinuousMove {
+            pan_speed,
+            tilt_speed,
+        } => ptz::continuous_move(state, pan_speed, tilt_speed).await,
+        ControlCommand::Keepalive => ptz::continuous_move_keepalive(state).await,
+        ControlCommand::Zoom { zoom } => ptz::zoom_to(listener, state, zoom).await,
+        ControlCommand::Stop => ptz::stop(state).await,
+        ControlCommand::Home => ptz::home(listener, state).await,
+        ControlCommand::RecallPreset { number } => {
+            ptz::recall_preset_number(listener, state, number).await
+        }
+        ControlCommand::GetPosition => return ptz::get_position(state).await.map(Some),
+    }
+    .map(|_| None)
+}
+
+/// Start the WebSocket control server, replacing any running one. Binds to
+/// `bind_address` (default `127.0.0.1`) on `port` (default random) and
+/// serves `/control`. Browser clients are refused unless their origin is
+/// in `allowed_origins`. Returns the bound port.
+#[tauri::command]
+pub async fn start_ws_control_server(
+    app: tauri::AppHandle,
+    state: tauri::State<'_, AppState>,
+    bind_address: Option<String>,
+    port: Option<u16>,
+    allowed_origins: Option<Vec<String>>,
+) -> Result<u16, String> {
+    let options = ServerOptions::parse(bind_address.as_deref(), port)?;
+
+    if let Some(shutdown_tx) = state.ws_control_shutdown.lock().await.take() {
+        let _ = shutdown_tx.send(true);
+    }
+
+    let (port, shutdown_tx) = crate::ws_control::server::start_server(
+        state.current_position.clone(),
+        &options,
+        allowed_origins.unwrap_or_default(),
+        move |command| {
+            let app = app.clone();
+            async move {
+                use tauri::Manager;
+
+                let state = app.state::<AppState>();
+                run_control_command(&app, &state, command).await
+            }
+        },
+    )
+    .await?;
+
+    *state.ws_control_shutdown.lock().await = Some(shutdown_tx);
+    Ok(port)
+}
+
+/// Stop the WebSocket control server and disconnect its clients.
+#[tauri::command]
+pub async fn stop_ws_control_server(state: tauri::State<'_, AppState>) -> Result<(), String> {
+    if let Some(shutdown_tx) = state.ws_control_shutdown.lock().await.take() {
+        let _ = shutdown_tx.send(true);
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::simulator::client::SimulatedController;
+    use crate::ws_control::message;
+
+    #[derive(Clone)]
+    struct NoListener;
+
+    impl PositionListener for NoListener {
+        fn position_changed(&self, _pos: &PtzPosition) {}
+    }
+
+    async fn send(state: &AppState, text: &str) -> Result<Option<PtzPosition>, String> {
+        let request = message::parse_request(text).unwrap();
+        run_control_command(&NoListener, state, request.command).await
+    }
+
+    #[tokio::test]
+    async fn parsed_commands_drive_the_active_camera() {
+        let dir = std::env::temp_dir().join(format!("ptzcam-test-ws-{}", uuid::Uuid::new_v4()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let state = AppState::new(dir.clone());
+        {
+            let mut dispatcher = state.ptz_dispatcher.lock().await;
+            dispatcher.add_controller("sim", Box::new(SimulatedController::new()));
+            dispatcher.set_active("sim").unwrap();
+        }
+
+        let moved = send(
+            &state,
+            r#"{"id":1,"command":{"type":"move_absolute","pan":0.5,"tilt":-0.25,"zoom":0.4}}"#,
+        )
+        .await;
+        assert_eq!(moved, Ok(None));
+
+        let expected = PtzPosition {
+            pan: 0.5,
+            tilt: -0.25,
+            zoom: 0.4,
+        };
+        let reported = send(&state, r#"{"id":2,"command":{"type":"get_position"}}"#).await;
+        assert_eq!(reported, Ok(Some(expected.clone())));
+        let camera = state
+            .ptz_dispatcher
+            .lock()
+            .await
+            .get_position()
+            .await
+            .unwrap();
+        assert_eq!(camera, expected);
+        std::fs::remove_dir_all(&dir).ok();
+    }
+}
@@ -0,0 +1,105 @@
+use super::mapping::{GamepadSettings, GamepadVelocity};
+use tokio::sync::{mpsc, watch};
+
+/// Something the operator did on the pad.
+#[derive(Debug, Clone, PartialEq)]
+pub enum GamepadInput {
+    /// Stick/trigger velocities changed.
+    Velocity(GamepadVelocity),
+    /// A face button was pressed; the number is the 1-based preset position.
+    PresetButton(usize),
+}
+
+/// Read the first connected gamepad on a blocking thread until `stop` fires,
+/// sending velocity changes and preset button presses to `inputs`.
+#[cfg(feature = "gamepad")]
+pub fn spawn_reader(
+    settings: GamepadSettings,
+    stop: watch::Receiver<bool>,
+    inputs: mpsc::UnboundedSender<GamepadInput>,
+) -> Result<(), String> {
+    use super::mapping::map_axes;
+    use gilrs::{Axis, Button, EventType, Gilrs};
+    use std::time::Duration;
+
+    /// How often the stick is sampled.
+    const POLL_INTERVAL: Duration = Duration::from_millis(20);
+
+    /// Face buttons, in preset order.
+    fn preset_number(button: Button) -> Option<usize> {
+        match button {
+            Button::South => Some(1),
+            Button::East => Some(2),
+            Button::West => Some(3),
+            Button::North => Some(4),
+            _ => None,
+        }
+    }
+
+    // Gilrs is not Send on every platform, so it is created on the reader thread
+    let (ready_tx, ready_rx) = std::sync::mpsc::channel();
+    std::thread::spawn(move || {
+        let mut gilrs = match Gilrs::new() {
+            Ok(gilrs) => {
+                let _ = ready_tx.send(Ok(()));
+                gilrs
+            }
+            Err(e) => {
+                let _ = ready_tx.send(Err(format!("Failed to open gamepad input: {}", e)));
+                return;
+            }
+        };
+        log::info!("Gamepad input started");
+
+        let mut active = gilrs.gamepads().next().map(|(id, _)| id);
+        let mut last = GamepadVelocity::default();
+        while !*stop.borrow() {
+            while let Some(event) = gilrs.next_event() {
+                active = Some(event.id);
+                if let EventType::ButtonPressed(button, _) = event.event {
+                    if let Some(number) = preset_number(button) {
+                        let _ = inputs.send(GamepadInput::PresetButton(number));
+                    }
+                }
+            }
+
+            if let Some(pad) = active.and_then(|id| gilrs.connected_gamepad(id)) {
+                let trigger = |button| {
+                    pad.button_data(button)
+                        .map(|d| d.value() as f64)
+                        .unwrap_or(0.0)
+                };
+                let velocity = map_axes(
+                    pad.value(Axis::LeftStickX) as f64,
+                    pad.value(Axis::LeftStickY) as f64,
+                    trigger(Button::LeftTrigger2),
+                    trigger(Button::RightTrigger2),
+                    &settings,
+                );
+                if velocity != last {
+                    last = velocity;
+                    if inputs.send(GamepadInput::Velocity(velocity)).is_err() {
+                        break;
+                    }
+                }
+            }
+            std::thread::sleep(POLL_INTERVAL);
+        }
+        log::info!("Gamepad input stopped");
+    });
+
+    ready_rx
+        .recv()
+        .map_err(|_| "Gamepad reader exited during startup".to_string())?
+}
+
+/// Stub for gamepad input.
+/// Real implementation requires the `gamepad` feature.
+#[cfg(not(feature = "gamepad"))]
+pub fn spawn_reader(
+    _settings: GamepadSettings,
+    _stop: watch::Receiver<bool>,
+    _inputs: mpsc::UnboundedSender<GamepadInput>,
+) -> Result<(), String> {
+    Err("Gamepad support not built; rebuild with the `gamepad` feature".to_string())
+}
@@ -0,0 +1,149 @@
+use serde::{Deserialize, Serialize};
+
+/// Event emitted to the frontend with the stick velocities, before the
+/// response curve is applied.
+pub const VELOCITY_EVENT: &str = "gamepad-velocity";
+
+/// Largest accepted deadzone; anything bigger leaves too little stick travel.
+pub const MAX_DEADZONE: f64 = 0.9;
+
+/// Stick and trigger tuning, stored in the app config.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct GamepadSettings {
+    /// Axis values with a smaller magnitude are treated as centred (0.0 to 0.9).
+    #[serde(default = "default_deadzone")]
+    pub deadzone: f64,
+    #[serde(default)]
+    pub invert_pan: bool,
+    #[serde(default)]
+    pub invert_tilt: bool,
+    #[serde(default)]
+    pub invert_zoom: bool,
+}
+
+fn default_deadzone() -> f64 {
+    0.15
+}
+
+impl Default for GamepadSettings {
+    fn default() -> Self {
+        Self {
+            deadzone: default_deadzone(),
+            invert_pan: false,
+            invert_tilt: false,
+            invert_zoom: false,
+        }
+    }
+}
+
+/// Normalized velocities derived from the pad, -1.0 to 1.0 each.
+#[derive(Debug, Clone, Copy, Default, Serialize, Deserialize, PartialEq)]
+pub struct GamepadVelocity {
+    pub pan: f64,
+    pub tilt: f64,
+    pub zoom: f64,
+}
+
+impl GamepadVelocity {
+    pub fn is_zero(&self) -> bool {
+        self.pan == 0.0 && self.tilt == 0.0 && self.zoom == 0.0
+    }
+}
+
+/// Map a raw axis value to a velocity. Values inside the deadzone are zero and
+/// the remaining travel is rescaled so the output still reaches full speed.
+pub fn axis_to_velocity(value: f64, deadzone: f64, invert: bool) -> f64 {
+    if !value.is_finite() {
+        return 0.0;
+    }
+    let deadzone = deadzone.clamp(0.0, MAX_DEADZONE);
+    let value = value.clamp(-1.0, 1.0);
+    if value.abs() <= deadzone {
+        return 0.0;
+    }
+    let scaled = (value.abs() - deadzone) / (1.0 - deadzone) * value.signum();
+    if invert {
+        -scaled
+    } else {
+        scaled
+    }
+}
+
+/// Map the left stick (up = positive Y) and the triggers (0.0 to 1.0) to
+/// velocities. The right trigger zooms in, the left trigger zooms out.
+pub fn map_axes(
+    stick_x: f64,
+    stick_y: f64,
+    left_trigger: f64,
+    right_trigger: f64,
+    settings: &GamepadSettings,
+) -> GamepadVelocity {
+    let trigger = |v: f64| {
+        if v.is_finite() {
+            v.clamp(0.0, 1.0)
+        } else {
+            0.0
+        }
+    };
+    GamepadVelocity {
+        pan: axis_to_velocity(stick_x, settings.deadzone, settings.invert_pan),
+        tilt: axis_to_velocity(stick_y, settings.deadzone, settings.invert_tilt),
+        zoom: axis_to_velocity(
+            trigger(right_trigger) - trigger(left_trigger),
+            settings.deadzone,
+            settings.invert_zoom,
+        ),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn approx(a: f64, b: f64) -> bool {
+        (a - b).abs() < 1e-9
+    }
+
+    #[test]
+    fn deadzone_zeroes_small_values() {
+        assert_eq!(axis_to_velocity(0.1, 0.15, false), 0.0);
+        assert_eq!(axis_to_velocity(-0.15, 0.15, false), 0.0);
+        assert!(axis_to_velocity(0.16, 0.15, false) > 0.0);
+    }
+
+    #[test]
+    fn remaining_travel_is_rescaled() {
+        assert!(approx(axis_to_velocity(1.0, 0.2, false), 1.0));
+        assert!(approx(axis_to_velocity(0.6, 0.2, false), 0.5));
+        assert!(approx(axis_to_velocity(-0.6, 0.2, false), -0.5));
+    }
+
+    #[test]
+    fn out_of_range_values_are_clamped() {
+        assert!(approx(axis_to_velocity(1.7, 0.1, false), 1.0));
+        assert!(approx(axis_to_velocity(-3.0, 0.1, false), -1.0));
+        assert_eq!(axis_to_velocity(f64::NAN, 0.1, false), 0.0);
+        // An oversized deadzone is limited so the stick still works
+        assert!(axis_to_velocity(1.0, 5.0, false) > 0.0);
+    }
+
+    #[test]
+    fn inversion_flips_direction() {
+        assert!(approx(axis_to_velocity(1.0, 0.0, true), -1.0));
+        let settings = GamepadSettings {
+            invert_tilt: true,
+            ..Default::default()
+        };
+        let v = map_axes(0.0, 1.0, 0.0, 0.0, &settings);
+        assert!(approx(v.tilt, -1.0));
+        assert_eq!(v.pan, 0.0);
+    }
+
+    #[test]
+    fn triggers_drive_zoom() {
+        let settings = GamepadSettings::default();
+        assert!(approx(map_axes(0.0, 0.0, 0.0, 1.0, &settings).zoom, 1.0));
+        assert!(approx(map_axes(0.0, 0.0, 1.0, 0.0, &settings).zoom, -1.0));
+        assert!(map_axes(0.0, 0.0, 1.0, 1.0, &settings).is_zero());
+    }
+}
@@ -1,18 +1,24 @@
 pub mod commands;
+pub mod gamepad;
 pub mod ndi;
+pub mod osc;
 pub mod persistence;
 pub mod ptz;
 pub mod video;
+pub mod ws_control;
 
 // Protocol-specific modules
 pub mod birddog;
+pub mod onvif;
 pub mod panasonic;
+pub mod pelco;
 pub mod simulator;
 pub mod visca;
 
 use tauri::Manager;
 
 use persistence::config::AppConfig;
+use persistence::positions::PositionCache;
 use persistence::profiles::ProfileStore;
 use ptz::controller::PtzDispatcher;
 use ptz::endpoint_manager::EndpointManager;
@@ -26,29 +32,144 @@ pub struct AppState {
     pub profiles: Arc<Mutex<ProfileStore>>,
     pub endpoints: Arc<Mutex<EndpointManager>>,
     pub current_position: Arc<Mutex<PtzPosition>>,
+    /// Last-known position of each endpoint, restored when it becomes active.
+    pub positions: Arc<PositionCache>,
     pub active_endpoint_id: Arc<Mutex<Option<String>>>,
     pub ptz_dispatcher: Arc<Mutex<PtzDispatcher>>,
     pub mjpeg_port: Arc<Mutex<Option<u16>>>,
     pub mjpeg_shutdown: Arc<Mutex<Option<tokio::sync::watch::Sender<bool>>>>,
+    pub mjpeg_state: Arc<Mutex<Option<Arc<video::mjpeg_server::MjpegState>>>>,
+    pub ndi_preview_stop: Arc<Mutex<Option<tokio::sync::watch::Sender<bool>>>>,
+    /// Cancels the background position poller for the active endpoint.
+    pub position_poll_cancel: Arc<Mutex<Option<tokio::sync::watch::Sender<bool>>>>,
+    /// Cancels the health monitor for the active endpoint.
+    pub health_check_cancel: Arc<Mutex<Option<tokio::sync::watch::Sender<bool>>>>,
+    /// Last-known health of the active endpoint.
+    pub endpoint_status: Arc<Mutex<Option<ptz::health::EndpointStatusReport>>>,
+    /// Stops the running preset tour, if any.
+    pub tour_cancel: Arc<Mutex<Option<tokio::sync::watch::Sender<bool>>>>,
+    /// Aborts an in-progress interpolated preset transition.
+    pub transition_cancel: Arc<Mutex<Option<tokio::sync::watch::Sender<bool>>>>,
+    /// Ends the soft-limit guard of the running jog.
+    pub jog_guard_cancel: Arc<Mutex<Option<tokio::sync::watch::Sender<bool>>>>,
+    /// Shuts down the OSC control listener.
+    pub osc_shutdown: Arc<Mutex<Option<tokio::sync::watch::Sender<bool>>>>,
+    /// Shuts down the WebSocket control server.
+    pub ws_control_shutdown: Arc<Mutex<Option<tokio::sync::watch::Sender<bool>>>>,
+    /// Stops the gamepad reader.
+    pub gamepad_stop: Arc<Mutex<Option<tokio::sync::watch::Sender<bool>>>>,
+    /// Reachability of each endpoint, as seen by the commands sent to it.
+    pub connection_statuses: Arc<ptz::connection_status::ConnectionStatuses>,
+    /// Recent commands sent to each endpoint, for troubleshooting.
+    pub command_log: Arc<ptz::command_log::CommandLog>,
+    /// Debounces position events sent after PTZ commands.
+    pub position_events: Arc<ptz::position_events::PositionEvents>,
+    /// Interrupts an endpoint's multi-step command, such as a timed nudge.
+    pub move_cancels: Arc<ptz::cancel::MoveCancels>,
+    /// Recently discovered NDI sources, refreshed in the background.
+    pub ndi_sources: Arc<video::source_cache::SourceCache>,
+    /// Data files that were corrupt at startup and set aside.
+    pub load_warnings: Vec<persistence::save::LoadWarning>,
 }
 
 impl AppState {
     pub fn new(data_dir: std::path::PathBuf) -> Self {
-        let config = AppConfig::load_or_default(&data_dir);
-        let profiles = ProfileStore::load_or_default(&data_dir);
-        let endpoints = EndpointManager::load_or_default(&data_dir);
+        let (config, config_warning) = AppConfig::load(&data_dir);
+        let (profiles, profiles_warning) = ProfileStore::load(&data_dir);
+        let profiles = profiles
+            .with_max_backups(config.profile_backup_count)
+            .with_deferred_saves();
+        let (endpoints, endpoints_warning) = EndpointManager::load(&data_dir);
+        let endpoints = endpoints.with_deferred_saves();
+        let load_warnings = [config_warning, profiles_warning, endpoints_warning]
+            .into_iter()
+            .flatten()
+            .collect();
+        let mut dispatcher = PtzDispatcher::new();
+        dispatcher.set_command_rate(config.command_rate_limit);
+        dispatcher.set_nudge_dwell(config.nudge_dwell);
+        dispatcher.set_deadman_window(std::time::Duration::from_millis(config.deadman_window_ms));
+        let connection_statuses = dispatcher.statuses();
+        let command_log = dispatcher.command_log();
+        let move_cancels = dispatcher.move_cancels();
+        let ndi_sources = video::source_cache::SourceCache::new(std::time::Duration::from_millis(
+            config.ndi_source_cache_ttl_ms,
+        ));
 
         Self {
             config: Arc::new(Mutex::new(config)),
             profiles: Arc::new(Mutex::new(profiles)),
             endpoints: Arc::new(Mutex::new(endpoints)),
             current_position: Arc::new(Mutex::new(PtzPosition::default())),
+            positions: Arc::new(PositionCache::load_or_default(&data_dir).with_deferred_saves()),
             active_endpoint_id: Arc::new(Mutex::new(None)),
-            ptz_dispatcher: Arc::new(Mutex::new(PtzDispatcher::new())),
+            ptz_dispatcher: Arc::new(Mutex::new(dispatcher)),
             mjpeg_port: Arc::new(Mutex::new(None)),
             mjpeg_shutdown: Arc::new(Mutex::new(None)),
+            mjpeg_state: Arc::new(Mutex::new(None)),
+            ndi_preview_stop: Arc::new(Mutex::new(None)),
+            position_poll_cancel: Arc::new(Mutex::new(None)),
+            health_check_cancel: Arc::new(Mutex::new(None)),
+            endpoint_status: Arc::new(Mutex::new(None)),
+            tour_cancel: Arc::new(Mutex::new(None)),
+            transition_cancel: Arc::new(Mutex::new(None)),
+            jog_guard_cancel: Arc::new(Mutex::new(None)),
+            osc_shutdown: Arc::new(Mutex::new(None)),
+            ws_control_shutdown: Arc::new(Mutex::new(None)),
+            gamepad_stop: Arc::new(Mutex::new(None)),
+            connection_statuses,
+            command_log,
+            position_events: Arc::new(ptz::position_events::PositionEvents::default()),
+            move_cancels,
+            ndi_sources: Arc::new(ndi_sources),
+            load_warnings,
         }
     }
+
+    /// Stop background tasks and servers, then write any deferred changes.
+    /// Called on app exit so nothing is left running or unsaved.
+    pub async fn shutdown(&self) {
+        let stops = [
+            &self.tour_cancel,
+            &self.transition_cancel,
+            &self.jog_guard_cancel,
+            &self.position_poll_cancel,
+            &self.health_check_cancel,
+            &self.ndi_preview_stop,
+            &self.mjpeg_shutdown,
+            &self.osc_shutdown,
+            &self.ws_control_shutdown,
+            &self.gamepad_stop,
+        ];
+        for stop in stops {
+            if let Some(stop_tx) = stop.lock().await.take() {
+                let _ = stop_tx.send(true);
+            }
+        }
+        *self.mjpeg_port.lock().await = None;
+        *self.mjpeg_state.lock().await = None;
+        *self.endpoint_status.lock().await = None;
+
+        flush_stores(&self.profiles, &self.endpoints, &self.positions).await;
+        log::info!("Shutdown complete");
+    }
+}
+
+/// Write out any deferred profile, endpoint and position changes.
+async fn flush_stores(
+    profiles: &Mutex<ProfileStore>,
+    endpoints: &Mutex<EndpointManager>,
+    positions: &PositionCache,
+) {
+    if let Err(e) = profiles.lock().await.flush() {
+        log::warn!("Failed to save profiles: {}", e);
+    }
+    if let Err(e) = endpoints.lock().await.flush() {
+        log::warn!("Failed to save endpoints: {}", e);
+    }
+    if let Err(e) = positions.flush() {
+        log::warn!("Failed to save positions: {}", e);
+    }
 }
 
 #[cfg_attr(mobile, tauri::mobile_entry_point)]
@@ -65,6 +186,22 @@ pub fn run() {
             std::fs::create_dir_all(&data_dir).expect("Failed to create app data directory");
 
             let state = AppState::new(data_dir);
+            let profiles = state.profiles.clone();
+            let endpoints = state.endpoints.clone();
+            let positions = state.positions.clone();
+            tauri::async_runtime::spawn(persistence::save::run_periodic_flush(
+                persistence::save::SAVE_DEBOUNCE,
+                move || {
+                    let profiles = profiles.clone();
+                    let endpoints = endpoints.clone();
+                    let positions = positions.clone();
+                    async move { flush_stores(&profiles, &endpoints, &positions).await }
+                },
+            ));
+            tauri::async_runtime::spawn(video::source_cache::run_background_refresh(
+                state.ndi_sources.clone(),
+                |options| async move { video::ndi_source::discover_sources(&options).await },
+            ));
             app.manage(state);
             Ok(())
         })
@@ -74,26 +211,85 @@ pub fn run() {
             commands::video::start_mjpeg_stream,
             commands::video::stop_mjpeg_stream,
             commands::video::get_mjpeg_port,
+            commands::video::get_mjpeg_client_count,
+            commands::osc::start_osc_server,
+            commands::osc::stop_osc_server,
+            commands::ws_control::start_ws_control_server,
+            commands::ws_control::stop_ws_control_server,
+            commands::gamepad::start_gamepad,
+            commands::gamepad::stop_gamepad,
+            commands::video::start_ndi_preview,
             commands::ptz::ptz_move_relative,
+            commands::ptz::ptz_click_to_delta,
+            commands::ptz::ptz_click_move,
             commands::ptz::ptz_move_absolute,
             commands::ptz::ptz_zoom,
+            commands::ptz::ptz_zoom_relative,
             commands::ptz::ptz_recall_preset,
+            commands::ptz::recall_preset_hotkey,
             commands::ptz::ptz_store_preset,
             commands::ptz::ptz_get_position,
+            commands::ptz::get_active_preset,
             commands::ptz::ptz_home,
             commands::ptz::ptz_continuous_move,
+            commands::ptz::ptz_continuous_move_keepalive,
+            commands::ptz::ptz_zoom_continuous,
             commands::ptz::ptz_stop,
             commands::ptz::ptz_focus,
             commands::ptz::ptz_focus_stop,
+            commands::ptz::ptz_focus_to,
+            commands::ptz::ptz_get_focus,
             commands::ptz::ptz_set_autofocus,
             commands::ptz::ptz_autofocus_trigger,
+            commands::ptz::ptz_set_iris,
+            commands::ptz::ptz_set_gain,
+            commands::ptz::ptz_set_shutter,
+            commands::ptz::ptz_set_exposure_mode,
+            commands::ptz::ptz_set_image_flip,
+            commands::ptz::ptz_set_digital_zoom,
+            commands::ptz::ptz_set_mirror,
+            commands::ptz::ptz_set_freeze,
+            commands::ptz::ptz_reposition_frozen,
+            commands::ptz::ptz_set_white_balance,
+            commands::ptz::ptz_menu_toggle,
+            commands::ptz::ptz_menu_navigate,
+            commands::ptz::ptz_menu_enter,
+            commands::ptz::ptz_set_tally,
+            commands::ptz::ptz_set_ndi_name,
+            commands::ptz::ptz_set_gamma,
+            commands::ptz::ptz_recall_picture_profile,
+            commands::ptz::ptz_send_raw,
+            commands::ptz::visca_diagnostics,
+            commands::ptz::visca_reset_sequence,
+            commands::ptz::get_command_log,
+            commands::ptz::clear_command_log,
+            commands::ptz::set_dry_run,
+            commands::ptz::preview_ptz_command,
             commands::presets::get_all_presets,
             commands::presets::create_preset,
+            commands::presets::create_preset_from_current,
             commands::presets::update_preset,
             commands::presets::delete_preset,
+            commands::presets::create_presets,
+            commands::presets::delete_presets,
+            commands::presets::duplicate_preset,
+            commands::presets::reorder_presets,
+            commands::presets::move_preset,
+            commands::presets::search_presets,
+            commands::presets::set_preset_hotkey,
+            commands::presets::refresh_preset_thumbnail,
+            commands::presets::undo_preset_change,
+            commands::presets::redo_preset_change,
+            commands::presets::get_preset_undo_state,
+            commands::presets::set_home_preset,
+            commands::bundle::export_config_bundle,
+            commands::bundle::import_config_bundle,
             commands::presets::get_profiles,
+            commands::presets::list_profile_backups,
+            commands::presets::restore_profile_backup,
             commands::presets::save_profile,
             commands::presets::load_profile,
+            commands::presets::duplicate_profile,
             commands::presets::delete_profile,
             commands::endpoints::get_endpoints,
             commands::endpoints::create_endpoint,
@@ -101,10 +297,95 @@ pub fn run() {
             commands::endpoints::delete_endpoint,
             commands::endpoints::set_active_endpoint,
             commands::endpoints::clear_active_endpoint,
+            commands::endpoints::get_endpoint_status,
+            commands::tour::set_tour,
+            commands::tour::start_tour,
+            commands::tour::stop_tour,
             commands::endpoints::test_endpoint_connection,
+            commands::endpoints::test_all_endpoints,
+            commands::endpoints::calibrate_visca_endpoint,
+            commands::endpoints::detect_protocol,
+            commands::endpoints::get_endpoint_statuses,
+            commands::endpoints::get_endpoint_capabilities,
+            commands::endpoints::get_camera_info,
             commands::settings::get_settings,
+            commands::settings::get_load_warnings,
             commands::settings::update_settings,
+            commands::settings::update_gamepad_settings,
+            commands::settings::set_strict_commands,
+            commands::settings::set_developer_mode,
+            commands::settings::set_command_rate_limit,
+            commands::settings::set_ndi_source_cache_ttl,
+            commands::settings::set_deadman_window,
+            commands::settings::set_nudge_dwell,
+            commands::settings::set_response_curve,
+            commands::settings::reset_settings,
+            commands::settings::reset_settings_field,
         ])
-        .run(tauri::generate_context!())
-        .expect("error while running tauri application");
+        .build(tauri::generate_context!())
+        .expect("error while building tauri application")
+        .run(|app, event| {
+            if let tauri::RunEvent::Exit = event {
+                let state = app.state::<AppState>();
+                tauri::async_runtime::block_on(state.shutdown());
+            }
+        });
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ptz::geometry::LensCurve;
+    use crate::ptz::types::PresetProfile;
+    use crate::video::mjpeg_server;
+
+    #[tokio::test]
+    async fn shutdown_stops_servers_and_tasks_and_flushes() {
+        let dir = std::env::temp_dir().join(format!("ptzcam-test-state-{}", uuid::Uuid::new_v4()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let state = AppState::new(dir.clone());
+
+        let options = mjpeg_server::ServerOptions::parse(Some("127.0.0.1"), Some(0)).unwrap();
+        let mjpeg = Arc::new(mjpeg_server::MjpegState::new());
+        let (port, shutdown_tx) = mjpeg_server::start_server(mjpeg.clone(), &options)
+            .await
+            .unwrap();
+        let mut mjpeg_stopped = shutdown_tx.subscribe();
+        *state.mjpeg_port.lock().await = Some(port);
+        *state.mjpeg_shutdown.lock().await = Some(shutdown_tx);
+        *state.mjpeg_state.lock().await = Some(mjpeg);
+
+        let (poll_tx, mut poll_stopped) = tokio::sync::watch::channel(false);
+        *state.position_poll_cancel.lock().await = Some(poll_tx);
+
+        state
+            .profiles
+            .lock()
+            .await
+            .create_profile(PresetProfile {
+                id: "p1".to_string(),
+                name: "Main".to_string(),
+                camera_fov_degrees: 60.0,
+                endpoint_id: None,
+                presets: Vec::new(),
+                tour: None,
+                transition: None,
+                home_preset_id: None,
+                lens_curve: LensCurve::default(),
+                mechanical_range: None,
+                limits_degrees: None,
+            })
+            .unwrap();
+        assert!(!dir.join("profiles.json").exists());
+
+        state.shutdown().await;
+
+        assert!(*mjpeg_stopped.borrow_and_update());
+        assert!(*poll_stopped.borrow_and_update());
+        assert_eq!(*state.mjpeg_port.lock().await, None);
+        assert!(state.mjpeg_shutdown.lock().await.is_none());
+        assert!(state.mjpeg_state.lock().await.is_none());
+        assert!(dir.join("profiles.json").exists());
+        std::fs::remove_dir_all(&dir).ok();
+    }
 }
@@ -0,0 +1,10 @@
+//! Raw NDI SDK bindings, generated by bindgen in build.rs.
+#![allow(
+    non_upper_case_globals,
+    non_camel_case_types,
+    non_snake_case,
+    dead_code,
+    clippy::all
+)]
+
+include!(concat!(env!("OUT_DIR"), "/ndi_bindings.rs"));
@@ -1,16 +1,108 @@
-use crate::video::ndi_source::NdiSource;
+use crate::video::ndi_source::{DiscoveryOptions, NdiSource};
 
 /// NDI source finder stub.
 /// In production, wraps NDIlib_find_instance_t.
+#[cfg(not(feature = "ndi"))]
 pub struct NdiFinder;
 
+#[cfg(not(feature = "ndi"))]
 impl NdiFinder {
-    pub fn new() -> Option<Self> {
+    pub fn new(_options: &DiscoveryOptions) -> Option<Self> {
         log::warn!("NDI SDK not linked — NdiFinder unavailable");
         None
     }
 
+    pub fn wait_for_sources(&self, _timeout: std::time::Duration) -> bool {
+        false
+    }
+
     pub fn get_sources(&self) -> Vec<NdiSource> {
         Vec::new()
     }
 }
+
+#[cfg(feature = "ndi")]
+pub use sdk::NdiFinder;
+
+#[cfg(feature = "ndi")]
+mod sdk {
+    use super::{DiscoveryOptions, NdiSource};
+    use crate::ndi::ffi;
+    use crate::video::ndi_source::join_list;
+    use std::ffi::{CStr, CString};
+    use std::os::raw::c_char;
+    use std::time::Duration;
+
+    /// NDI source finder scoped to the configured groups and extra IPs.
+    /// Wraps NDIlib_find_instance_t and destroys it on drop.
+    pub struct NdiFinder {
+        instance: ffi::NDIlib_find_instance_t,
+    }
+
+    impl NdiFinder {
+        pub fn new(options: &DiscoveryOptions) -> Option<Self> {
+            if !crate::ndi::initialize() {
+                log::warn!("NDI runtime failed to initialize — NdiFinder unavailable");
+                return None;
+            }
+            let groups = join_list(&options.groups).and_then(|s| CString::new(s).ok());
+            let extra_ips = join_list(&options.extra_ips).and_then(|s| CString::new(s).ok());
+
+            // SAFETY: plain C data for which all-zero is a valid value.
+            let mut create: ffi::NDIlib_find_create_t = unsafe { std::mem::zeroed() };
+            create.show_local_sources = true;
+            create.p_groups = groups.as_ref().map_or(std::ptr::null(), |s| s.as_ptr());
+            create.p_extra_ips = extra_ips.as_ref().map_or(std::ptr::null(), |s| s.as_ptr());
+
+            // SAFETY: `create` and the strings it points at outlive the call.
+            let instance = unsafe { ffi::NDIlib_find_create_v2(&create) };
+            if instance.is_null() {
+                log::warn!("Failed to create NDI finder");
+                return None;
+            }
+            Some(Self { instance })
+        }
+
+        /// Block until the source list changes or the timeout elapses.
+        pub fn wait_for_sources(&self, timeout: Duration) -> bool {
+            let timeout_ms = timeout.as_millis().min(u32::MAX as u128) as u32;
+            // SAFETY: `instance` is a live finder for the lifetime of `self`.
+            unsafe { ffi::NDIlib_find_wait_for_sources(self.instance, timeout_ms) }
+        }
+
+        pub fn get_sources(&self) -> Vec<NdiSource> {
+            let mut count: u32 = 0;
+            // SAFETY: the returned array stays valid until the next call on this finder,
+            // and every string is copied before returning.
+            unsafe {
+                let sources = ffi::NDIlib_find_get_current_sources(self.instance, &mut count);
+                if sources.is_null() {
+                    return Vec::new();
+                }
+                std::slice::from_raw_parts(sources, count as usize)
+                    .iter()
+                    .map(|source| NdiSource {
+                        name: c_string(source.p_ndi_name),
+                        url: c_string(source.__bindgen_anon_1.p_url_address),
+                    })
+                    .collect()
+            }
+        }
+    }
+
+    impl Drop for NdiFinder {
+        fn drop(&mut self) {
+            // SAFETY: `instance` came from NDIlib_find_create_v2 and is destroyed exactly once.
+            unsafe { ffi::NDIlib_find_destroy(self.instance) };
+        }
+    }
+
+    /// Copy a possibly-null C string.
+    unsafe fn c_string(ptr: *const c_char) -> String {
+        if ptr.is_null() {
+            String::new()
+        } else {
+            CStr::from_ptr(ptr).to_string_lossy().into_owned()
+        }
+    }
+}
@@ -2,15 +2,26 @@
 // These modules provide safe Rust wrappers around the NDI C SDK.
 //
 // The NDI SDK is proprietary and must be installed separately.
-// Real FFI bindings would be auto-generated by bindgen from Processing.NDI.Lib.h.
+// FFI bindings are generated by bindgen from Processing.NDI.Lib.h when the
+// `ndi` feature is enabled.
 //
-// For now, these modules contain stub implementations that log warnings
-// and return sensible defaults. To enable real NDI support:
+// Without the feature, these modules contain stub implementations that log
+// warnings and return sensible defaults. To enable real NDI support:
 // 1. Install the NDI SDK
 // 2. Set NDI_SDK_DIR environment variable
-// 3. Enable the `ndi` feature flag in Cargo.toml
-// 4. Uncomment bindgen in build.rs
+// 3. Build with `--features ndi`
 
+#[cfg(feature = "ndi")]
+mod ffi;
 pub mod finder;
 pub mod ptz;
 pub mod receiver;
+pub mod tally;
+
+/// Initialize the NDI runtime once per process.
+#[cfg(feature = "ndi")]
+pub(crate) fn initialize() -> bool {
+    static INITIALIZED: std::sync::OnceLock<bool> = std::sync::OnceLock::new();
+    // SAFETY: NDIlib_initialize has no preconditions and is only called once here.
+    *INITIALIZED.get_or_init(|| unsafe { ffi::NDIlib_initialize() })
+}
@@ -1,23 +1,22 @@
 use crate::ptz::controller::{PtzController, PtzError};
 use crate::ptz::types::PtzPosition;
+#[cfg(feature = "ndi")]
+use crate::ptz::types::{Capabilities, TallyState};
 use async_trait::async_trait;
 
 /// NDI PTZ controller stub.
 /// In production, wraps NDIlib_recv_ptz_* functions.
+#[cfg(not(feature = "ndi"))]
 pub struct NdiPtzController;
 
+#[cfg(not(feature = "ndi"))]
 impl NdiPtzController {
-    pub fn new() -> Self {
+    pub fn new(_source_name: &str) -> Self {
         Self
     }
 }
 
-impl Default for NdiPtzController {
-    fn default() -> Self {
-        Self::new()
-    }
-}
-
+#[cfg(not(feature = "ndi"))]
 #[async_trait]
 impl PtzController for NdiPtzController {
     async fn move_absolute(&self, _pan: f64, _tilt: f64, _zoom: f64) -> Result<(), PtzError> {
@@ -48,3 +47,280 @@ impl PtzController for NdiPtzController {
         Err(PtzError::ConnectionFailed("NDI SDK not linked".to_string()))
     }
 }
+
+/// Highest preset number the NDI PTZ API accepts.
+#[cfg(feature = "ndi")]
+const MAX_PRESET: u8 = 99;
+
+/// The NDIlib_recv_ptz_* calls the controller makes on a receiver.
+/// Implemented by `NdiReceiver`; each returns whether the SDK accepted the command.
+#[cfg(feature = "ndi")]
+pub trait NdiPtzShim: Send + Sync {
+    fn ptz_is_supported(&self) -> bool;
+    /// Pan/tilt -1.0 (left/down) to 1.0 (right/up).
+    fn ptz_pan_tilt(&self, pan: f32, tilt: f32) -> bool;
+    /// Pan speed -1.0 (right) to 1.0 (left); tilt speed -1.0 (down) to 1.0 (up).
+    fn ptz_pan_tilt_speed(&self, pan_speed: f32, tilt_speed: f32) -> bool;
+    /// Zoom 0.0 (zoomed in) to 1.0 (zoomed out).
+    fn ptz_zoom(&self, zoom: f32) -> bool;
+    fn ptz_store_preset(&self, preset: i32) -> bool;
+    /// Speed 0.0 (slowest) to 1.0 (fastest).
+    fn ptz_recall_preset(&self, preset: i32, speed: f32) -> bool;
+    /// Send an XML metadata frame back to the source.
+    fn send_metadata(&self, xml: &str) -> bool;
+}
+
+/// NDI PTZ controller over a receiver connected to a discovered source.
+#[cfg(feature = "ndi")]
+pub struct NdiPtzController {
+    source_name: String,
+    receiver: Option<Box<dyn NdiPtzShim>>,
+}
+
+#[cfg(feature = "ndi")]
+impl NdiPtzController {
+    pub fn new(source_name: &str) -> Self {
+        let receiver = super::receiver::NdiReceiver::connect_for_control(source_name)
+            .map(|r| Box::new(r) as Box<dyn NdiPtzShim>);
+        Self {
+            source_name: source_name.to_string(),
+            receiver,
+        }
+    }
+
+    /// Build a controller over an existing receiver.
+    pub fn with_receiver(source_name: &str, receiver: Box<dyn NdiPtzShim>) -> Self {
+        Self {
+            source_name: source_name.to_string(),
+            receiver: Some(receiver),
+        }
+    }
+
+    fn receiver(&self) -> Result<&dyn NdiPtzShim, PtzError> {
+        self.receiver.as_deref().ok_or_else(|| {
+            PtzError::ConnectionFailed(format!(
+                "NDI source '{}' is not connected",
+                self.source_name
+            ))
+        })
+    }
+
+    fn check(&self, accepted: bool, action: &str) -> Result<(), PtzError> {
+        if accepted {
+            Ok(())
+        } else {
+            Err(PtzError::CommandFailed(format!(
+                "NDI source '{}' rejected {}",
+                self.source_name, action
+            )))
+        }
+    }
+
+    fn preset_number(preset_index: u8) -> Result<i32, PtzError> {
+        if preset_index > MAX_PRESET {
+            return Err(PtzError::ProtocolError(format!(
+                "NDI preset index {} out of range (0-{})",
+                preset_index, MAX_PRESET
+            )));
+        }
+        Ok(preset_index as i32)
+    }
+}
+
+/// Our zoom runs 0.0 (wide) to 1.0 (tele); NDI's runs the other way.
+#[cfg(feature = "ndi")]
+fn to_ndi_zoom(zoom: f64) -> f32 {
+    (1.0 - zoom.clamp(0.0, 1.0)) as f32
+}
+
+#[cfg(feature = "ndi")]
+#[async_trait]
+impl PtzController for NdiPtzController {
+    async fn move_absolute(&self, pan: f64, tilt: f64, zoom: f64) -> Result<(), PtzError> {
+        let receiver = self.receiver()?;
+        let pan = pan.clamp(-1.0, 1.0) as f32;
+        let tilt = tilt.clamp(-1.0, 1.0) as f32;
+        self.check(receiver.ptz_pan_tilt(pan, tilt), "pan/tilt")?;
+        self.check(receiver.ptz_zoom(to_ndi_zoom(zoom)), "zoom")
+    }
+
+    async fn move_relative(&self, _pan_delta: f64, _tilt_delta: f64) -> Result<(), PtzError> {
+        Err(PtzError::CommandFailed(
+            "NDI PTZ does not support relative moves".to_string(),
+        ))
+    }
+
+    async fn zoom_to(&self, zoom: f64) -> Result<(), PtzError> {
+        let receiver = self.receiver()?;
+        self.check(receiver.ptz_zoom(to_ndi_zoom(zoom)), "zoom")
+    }
+
+    async fn recall_preset(&self, preset_index: u8) -> Result<(), PtzError> {
+        let preset = Self::preset_number(preset_index)?;
+        let receiver = self.receiver()?;
+        self.check(receiver.ptz_recall_preset(preset, 1.0), "preset recall")
+    }
+
+    async fn store_preset(&self, preset_index: u8) -> Result<(), PtzError> {
+        let preset = Self::preset_number(preset_index)?;
+        let receiver = self.receiver()?;
+        self.check(receiver.ptz_store_preset(preset), "preset store")
+    }
+
+    async fn get_position(&self) -> Result<PtzPosition, PtzError> {
+        Err(PtzError::CommandFailed(
+            "NDI PTZ does not report position".to_string(),
+        ))
+    }
+
+    async fn test_connection(&self) -> Result<(), PtzError> {
+        if self.receiver()?.ptz_is_supported() {
+            Ok(())
+        } else {
+            Err(PtzError::ConnectionFailed(format!(
+                "NDI source '{}' does not support PTZ",
+                self.source_name
+            )))
+        }
+    }
+
+    /// NDI pan speed is positive to the left, so the sign is flipped.
+    async fn continuous_move(&self, pan_speed: f64, tilt_speed: f64) -> Result<(), PtzError> {
+        let receiver = self.receiver()?;
+        let pan = -pan_speed.clamp(-1.0, 1.0) as f32;
+        let tilt = tilt_speed.clamp(-1.0, 1.0) as f32;
+        self.check(receiver.ptz_pan_tilt_speed(pan, tilt), "pan/tilt speed")
+    }
+
+    async fn stop(&self) -> Result<(), PtzError> {
+        let receiver = self.receiver()?;
+        self.check(receiver.ptz_pan_tilt_speed(0.0, 0.0), "stop")
+    }
+
+    /// Tally goes to the source as `<ndi_tally>` metadata.
+    async fn set_tally(&self, state: TallyState) -> Result<(), PtzError> {
+        let (on_program, on_preview) = match state {
+            TallyState::Off => (false, false),
+            TallyState::Preview => (false, true),
+            TallyState::Program => (true, false),
+        };
+        let receiver = self.receiver()?;
+        let xml = super::tally::tally_metadata(on_program, on_preview);
+        self.check(receiver.send_metadata(&xml), "tally")
+    }
+
+    fn capabilities(&self) -> Capabilities {
+        Capabilities {
+            supports_absolute: true,
+            supports_continuous: true,
+            supports_tally: true,
+            supports_native_presets: true,
+            max_preset_slots: MAX_PRESET as u16 + 1,
+            ..Capabilities::default()
+        }
+    }
+}
+
+#[cfg(all(test, feature = "ndi"))]
+mod tests {
+    use super::*;
+    use std::sync::{Arc, Mutex};
+
+    /// Records each shim call instead of reaching the SDK.
+    #[derive(Clone, Default)]
+    struct RecordingShim {
+        calls: Arc<Mutex<Vec<String>>>,
+    }
+
+    impl RecordingShim {
+        fn record(&self, call: String) -> bool {
+            self.calls.lock().unwrap().push(call);
+            true
+        }
+
+        fn calls(&self) -> Vec<String> {
+            self.calls.lock().unwrap().clone()
+        }
+    }
+
+    impl NdiPtzShim for RecordingShim {
+        fn ptz_is_supported(&self) -> bool {
+            self.record("is_supported".to_string())
+        }
+        fn ptz_pan_tilt(&self, pan: f32, tilt: f32) -> bool {
+            self.record(format!("pan_tilt({}, {})", pan, tilt))
+        }
+        fn ptz_pan_tilt_speed(&self, pan_speed: f32, tilt_speed: f32) -> bool {
+            self.record(format!("pan_tilt_speed({}, {})", pan_speed, tilt_speed))
+        }
+        fn ptz_zoom(&self, zoom: f32) -> bool {
+            self.record(format!("zoom({})", zoom))
+        }
+        fn ptz_store_preset(&self, preset: i32) -> bool {
+            self.record(format!("store_preset({})", preset))
+        }
+        fn ptz_recall_preset(&self, preset: i32, speed: f32) -> bool {
+            self.record(format!("recall_preset({}, {})", preset, speed))
+        }
+        fn send_metadata(&self, xml: &str) -> bool {
+            self.record(format!("metadata({})", xml))
+        }
+    }
+
+    fn controller() -> (NdiPtzController, RecordingShim) {
+        let shim = RecordingShim::default();
+        let ctrl = NdiPtzController::with_receiver("CAM (PTZ)", Box::new(shim.clone()));
+        (ctrl, shim)
+    }
+
+    #[tokio::test]
+    async fn move_absolute_routes_to_pan_tilt_and_zoom() {
+        let (ctrl, shim) = controller();
+        ctrl.move_absolute(0.5, -0.25, 0.25).await.unwrap();
+        assert_eq!(shim.calls(), vec!["pan_tilt(0.5, -0.25)", "zoom(0.75)"]);
+    }
+
+    #[tokio::test]
+    async fn presets_route_to_recall_and_store() {
+        let (ctrl, shim) = controller();
+        ctrl.store_preset(3).await.unwrap();
+        ctrl.recall_preset(3).await.unwrap();
+        assert_eq!(shim.calls(), vec!["store_preset(3)", "recall_preset(3, 1)"]);
+        assert!(ctrl.recall_preset(100).await.is_err());
+    }
+
+    #[tokio::test]
+    async fn continuous_move_flips_pan_and_stop_zeroes_speed() {
+        let (ctrl, shim) = controller();
+        ctrl.continuous_move(0.5, 0.5).await.unwrap();
+        ctrl.stop().await.unwrap();
+        assert_eq!(
+            shim.calls(),
+            vec!["pan_tilt_speed(-0.5, 0.5)", "pan_tilt_speed(0, 0)"]
+        );
+    }
+
+    #[test]
+    fn capabilities_cover_motion_and_presets() {
+        let (ctrl, _) = controller();
+        let caps = ctrl.capabilities();
+        assert!(caps.supports_absolute && caps.supports_continuous);
+        assert!(caps.supports_native_presets && caps.supports_tally);
+        assert_eq!(caps.max_preset_slots, 100);
+        assert!(!caps.supports_focus && !caps.supports_menu);
+    }
+
+    #[tokio::test]
+    async fn tally_is_sent_as_metadata() {
+        let (ctrl, shim) = controller();
+        ctrl.set_tally(TallyState::Program).await.unwrap();
+        ctrl.set_tally(TallyState::Off).await.unwrap();
+        assert_eq!(
+            shim.calls(),
+            vec![
+                r#"metadata(<ndi_tally on_program="true" on_preview="false"/>)"#,
+                r#"metadata(<ndi_tally on_program="false" on_preview="false"/>)"#
+            ]
+        );
+    }
+}
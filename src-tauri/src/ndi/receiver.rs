@@ -1,10 +1,202 @@
 /// NDI video receiver stub.
 /// In production, wraps NDIlib_recv_instance_t.
+#[cfg(not(feature = "ndi"))]
 pub struct NdiReceiver;
 
+#[cfg(not(feature = "ndi"))]
 impl NdiReceiver {
     pub fn connect(_source_name: &str) -> Option<Self> {
         log::warn!("NDI SDK not linked — NdiReceiver unavailable");
         None
     }
+
+    pub fn connect_for_control(_source_name: &str) -> Option<Self> {
+        log::warn!("NDI SDK not linked — NdiReceiver unavailable");
+        None
+    }
+}
+
+#[cfg(feature = "ndi")]
+pub use sdk::NdiReceiver;
+
+#[cfg(feature = "ndi")]
+mod sdk {
+    use crate::ndi::ffi;
+    use crate::ndi::ptz::NdiPtzShim;
+    use crate::video::ndi_preview::{PixelFormat, RawFrame};
+    use std::ffi::CString;
+    use std::time::Duration;
+
+    /// NDI receiver connected to a single named source.
+    /// Wraps NDIlib_recv_instance_t and destroys it on drop.
+    pub struct NdiReceiver {
+        instance: ffi::NDIlib_recv_instance_t,
+        source_name: String,
+    }
+
+    // The NDI SDK documents receiver instances as safe to use across threads.
+    unsafe impl Send for NdiReceiver {}
+    unsafe impl Sync for NdiReceiver {}
+
+    impl NdiReceiver {
+        /// Connect to a source for video and metadata.
+        pub fn connect(source_name: &str) -> Option<Self> {
+            Self::create(
+                source_name,
+                ffi::NDIlib_recv_bandwidth_highest,
+                ffi::NDIlib_recv_color_format_UYVY_BGRA,
+            )
+        }
+
+        /// Connect to a source for PTZ control only, without pulling video.
+        pub fn connect_for_control(source_name: &str) -> Option<Self> {
+            Self::create(
+                source_name,
+                ffi::NDIlib_recv_bandwidth_metadata_only,
+                ffi::NDIlib_recv_color_format_BGRX_BGRA,
+            )
+        }
+
+        fn create(
+            source_name: &str,
+            bandwidth: ffi::NDIlib_recv_bandwidth_e,
+            color_format: ffi::NDIlib_recv_color_format_e,
+        ) -> Option<Self> {
+            if !crate::ndi::initialize() {
+                log::warn!("NDI runtime failed to initialize — NdiReceiver unavailable");
+                return None;
+            }
+            let name = CString::new(source_name).ok()?;
+
+            // SAFETY: both structs are plain C data for which all-zero is a valid value.
+            let mut create: ffi::NDIlib_recv_create_v3_t = unsafe { std::mem::zeroed() };
+            create.source_to_connect_to.p_ndi_name = name.as_ptr();
+            create.color_format = color_format;
+            create.bandwidth = bandwidth;
+            create.allow_video_fields = false;
+
+            // SAFETY: `create` and `name` outlive the call; the SDK copies what it keeps.
+            let instance = unsafe { ffi::NDIlib_recv_create_v3(&create) };
+            if instance.is_null() {
+                log::warn!("Failed to create NDI receiver for '{}'", source_name);
+                return None;
+            }
+
+            Some(Self {
+                instance,
+                source_name: source_name.to_string(),
+            })
+        }
+
+        pub fn source_name(&self) -> &str {
+            &self.source_name
+        }
+
+        /// Wait up to `timeout` for a video frame and hand it to `f` before freeing it.
+        /// Returns `None` if no video frame arrived or its pixel format is unsupported.
+        pub fn capture_video<R>(
+            &self,
+            timeout: Duration,
+            f: impl FnOnce(RawFrame<'_>) -> R,
+        ) -> Option<R> {
+            let timeout_ms = timeout.as_millis().min(u32::MAX as u128) as u32;
+            // SAFETY: plain C data for which all-zero is a valid value.
+            let mut video: ffi::NDIlib_video_frame_v2_t = unsafe { std::mem::zeroed() };
+            // SAFETY: `instance` is live; audio and metadata are not requested.
+            let frame_type = unsafe {
+                ffi::NDIlib_recv_capture_v2(
+                    self.instance,
+                    &mut video,
+                    std::ptr::null_mut(),
+                    std::ptr::null_mut(),
+                    timeout_ms,
+                )
+            };
+            if frame_type != ffi::NDIlib_frame_type_video {
+                return None;
+            }
+
+            let format = match video.FourCC {
+                ffi::NDIlib_FourCC_video_type_UYVY => Some(PixelFormat::Uyvy),
+                ffi::NDIlib_FourCC_video_type_BGRA => Some(PixelFormat::Bgra),
+                ffi::NDIlib_FourCC_video_type_BGRX => Some(PixelFormat::Bgrx),
+                ffi::NDIlib_FourCC_video_type_RGBA => Some(PixelFormat::Rgba),
+                ffi::NDIlib_FourCC_video_type_RGBX => Some(PixelFormat::Rgbx),
+                _ => None,
+            };
+            // SAFETY: for uncompressed formats the union holds the line stride.
+            let stride = unsafe { video.__bindgen_anon_1.line_stride_in_bytes };
+            let result = match format {
+                Some(format) if !video.p_data.is_null() && video.xres > 0 && video.yres > 0 => {
+                    let (width, height, stride) = (
+                        video.xres as usize,
+                        video.yres as usize,
+                        stride.max(0) as usize,
+                    );
+                    // SAFETY: the SDK owns `p_data` (stride * height bytes) until the frame is freed below.
+                    let data = unsafe { std::slice::from_raw_parts(video.p_data, stride * height) };
+                    Some(f(RawFrame {
+                        format,
+                        width,
+                        height,
+                        stride,
+                        data,
+                    }))
+                }
+                _ => None,
+            };
+            // SAFETY: `video` was filled by NDIlib_recv_capture_v2 on this instance.
+            unsafe { ffi::NDIlib_recv_free_video_v2(self.instance, &video) };
+            result
+        }
+    }
+
+    impl Drop for NdiReceiver {
+        fn drop(&mut self) {
+            // SAFETY: `instance` came from NDIlib_recv_create_v3 and is destroyed exactly once.
+            unsafe { ffi::NDIlib_recv_destroy(self.instance) };
+        }
+    }
+
+    // SAFETY (all methods): `instance` is a live receiver for the lifetime of `self`.
+    impl NdiPtzShim for NdiReceiver {
+        fn ptz_is_supported(&self) -> bool {
+            unsafe { ffi::NDIlib_recv_ptz_is_supported(self.instance) }
+        }
+
+        fn ptz_pan_tilt(&self, pan: f32, tilt: f32) -> bool {
+            unsafe { ffi::NDIlib_recv_ptz_pan_tilt(self.instance, pan, tilt) }
+        }
+
+        fn ptz_pan_tilt_speed(&self, pan_speed: f32, tilt_speed: f32) -> bool {
+            unsafe { ffi::NDIlib_recv_ptz_pan_tilt_speed(self.instance, pan_speed, tilt_speed) }
+        }
+
+        fn ptz_zoom(&self, zoom: f32) -> bool {
+            unsafe { ffi::NDIlib_recv_ptz_zoom(self.instance, zoom) }
+        }
+
+        fn ptz_store_preset(&self, preset: i32) -> bool {
+            unsafe { ffi::NDIlib_recv_ptz_store_preset(self.instance, preset) }
+        }
+
+        fn ptz_recall_preset(&self, preset: i32, speed: f32) -> bool {
+            unsafe { ffi::NDIlib_recv_ptz_recall_preset(self.instance, preset, speed) }
+        }
+
+        fn send_metadata(&self, xml: &str) -> bool {
+            let Ok(data) = CString::new(xml) else {
+                return false;
+            };
+            let frame = ffi::NDIlib_metadata_frame_t {
+                // Zero length means the data is null-terminated
+                length: 0,
+                // NDIlib_send_timecode_synthesize
+                timecode: i64::MAX,
+                p_data: data.as_ptr() as *mut _,
+            };
+            // SAFETY: `frame` and `data` outlive the call; the SDK copies the metadata.
+            unsafe { ffi::NDIlib_recv_send_metadata(self.instance, &frame) }
+        }
+    }
 }
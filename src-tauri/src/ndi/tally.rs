@@ -0,0 +1,31 @@
+//! NDI tally metadata, sent from a receiver back to the source so the camera
+//! can light its tally lamp.
+
+/// The `<ndi_tally>` metadata element for a source's program/preview state.
+pub fn tally_metadata(on_program: bool, on_preview: bool) -> String {
+    format!(
+        r#"<ndi_tally on_program="{}" on_preview="{}"/>"#,
+        on_program, on_preview
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn tally_xml_carries_both_flags() {
+        assert_eq!(
+            tally_metadata(true, false),
+            r#"<ndi_tally on_program="true" on_preview="false"/>"#
+        );
+        assert_eq!(
+            tally_metadata(false, true),
+            r#"<ndi_tally on_program="false" on_preview="true"/>"#
+        );
+        assert_eq!(
+            tally_metadata(false, false),
+            r#"<ndi_tally on_program="false" on_preview="false"/>"#
+        );
+    }
+}
@@ -0,0 +1,354 @@
+use crate::ptz::controller::{PtzController, PtzError};
+use crate::ptz::retry::{map_request_error, RetryPolicy};
+use crate::ptz::timeout;
+use crate::ptz::types::{Capabilities, PtzPosition};
+use async_trait::async_trait;
+use std::collections::HashMap;
+use tokio::sync::Mutex;
+
+use super::{auth, soap};
+
+/// Services and profile discovered by the GetCapabilities/GetProfiles handshake.
+#[derive(Debug, Clone)]
+struct Session {
+    ptz_url: String,
+    profile_token: String,
+}
+
+/// ONVIF Profile S client for PTZ cameras that speak SOAP over HTTP.
+/// ONVIF's generic spaces already match ours: pan/tilt -1.0..1.0, zoom 0.0..1.0.
+pub struct OnvifClient {
+    base_url: String,
+    client: reqwest::Client,
+    retry_policy: RetryPolicy,
+    credentials: Option<(String, String)>,
+    session: Mutex<Option<Session>>,
+    /// Camera preset tokens for numbered slots, learned from SetPreset/GetPresets.
+    preset_tokens: Mutex<HashMap<u8, String>>,
+}
+
+impl OnvifClient {
+    pub fn new(host: &str, port: u16) -> Result<Self, PtzError> {
+        crate::ptz::controller::validate_address(host, port)?;
+        Ok(Self {
+            base_url: format!("http://{}", crate::ptz::types::host_with_port(host, port)),
+            client: reqwest::Client::new(),
+            retry_policy: RetryPolicy::default(),
+            credentials: None,
+            session: Mutex::new(None),
+            preset_tokens: Mutex::new(HashMap::new()),
+        })
+    }
+
+    /// Authenticate with a WS-Security UsernameToken.
+    pub fn with_credentials(mut self, username: Option<String>, password: Option<String>) -> Self {
+        self.credentials = password.map(|p| (username.unwrap_or_else(|| "admin".to_string()), p));
+        self
+    }
+
+    /// Set the retry policy for transient network failures.
+    pub fn with_retry_policy(mut self, policy: RetryPolicy) -> Self {
+        self.retry_policy = policy;
+        self
+    }
+
+    /// POST a SOAP request and return the response body.
+    async fn call(&self, url: &str, body: &str) -> Result<String, PtzError> {
+        self.retry_policy
+            .run(|| async {
+                // A fresh nonce and timestamp per attempt, or the camera rejects replays
+                let security = self
+                    .credentials
+                    .as_ref()
+                    .map(|(user, pass)| auth::username_token_header(user, pass));
+                let response = self
+                    .client
+                    .post(url)
+                    .header("Content-Type", "application/soap+xml; charset=utf-8")
+                    .body(soap::envelope(security.as_deref(), body))
+                    .timeout(timeout::or_default(timeout::HTTP_TIMEOUT))
+                    .send()
+                    .await
+                    .map_err(map_request_error)?;
+                let status = response.status();
+                let text = response
+                    .text()
+                    .await
+                    .map_err(|e| PtzError::CommandFailed(e.to_string()))?;
+                check_response(status, &text)?;
+                Ok(text)
+            })
+            .await
+    }
+
+    /// Return the cached session, performing the handshake if needed.
+    async fn session(&self) -> Result<Session, PtzError> {
+        let mut session = self.session.lock().await;
+        if let Some(s) = session.as_ref() {
+            return Ok(s.clone());
+        }
+
+        let device_url = format!("{}/onvif/device_service", self.base_url);
+        let (media_url, ptz_url) = match self.call(&device_url, &soap::get_capabilities()).await {
+            Ok(caps) => soap::parse_capabilities(&caps),
+            Err(e) => {
+                log::warn!("ONVIF GetCapabilities failed, using default service paths: {e}");
+                (None, None)
+            }
+        };
+        let media_url = media_url.unwrap_or_else(|| device_url.clone());
+        let ptz_url = ptz_url.unwrap_or_else(|| device_url.clone());
+
+        let profiles = self.call(&media_url, &soap::get_profiles()).await?;
+        let profile_token = soap::parse_profile_token(&profiles).ok_or_else(|| {
+            PtzError::ProtocolError("ONVIF camera returned no media profiles".to_string())
+        })?;
+        log::info!("ONVIF PTZ using profile '{}' at {}", profile_token, ptz_url);
+
+        let s = Session {
+            ptz_url,
+            profile_token,
+        };
+        *session = Some(s.clone());
+        Ok(s)
+    }
+
+    /// Send a PTZ service request built from the session's profile token.
+    async fn ptz_call(&self, build: impl Fn(&str) -> String) -> Result<String, PtzError> {
+        let session = self.session().await?;
+        self.call(&session.ptz_url, &build(&session.profile_token))
+            .await
+    }
+
+    /// Look up the camera token for a numbered preset slot.
+    async fn preset_token(&self, index: u8) -> Result<Option<String>, PtzError> {
+        if let Some(token) = self.preset_tokens.lock().await.get(&index) {
+            return Ok(Some(token.clone()));
+        }
+        let presets = soap::parse_presets(&self.ptz_call(soap::get_presets).await?);
+        let token = soap::preset_token_for(index, &presets);
+        if let Some(token) = &token {
+            self.preset_tokens.lock().await.insert(index, token.clone());
+        }
+        Ok(token)
+    }
+}
+
+/// Map HTTP auth failures and SOAP faults to errors. Auth failures aren't
+/// retryable, so bad credentials are sent once per command.
+fn check_response(status: reqwest::StatusCode, body: &str) -> Result<(), PtzError> {
+    if status == reqwest::StatusCode::UNAUTHORIZED || status == reqwest::StatusCode::FORBIDDEN {
+        return Err(PtzError::AuthFailed(format!(
+            "ONVIF authentication required (HTTP {}): check the endpoint username and password",
+            status.as_u16()
+        )));
+    }
+    if let Some(reason) = soap::parse_fault(body) {
+        return Err(PtzError::CommandFailed(format!("ONVIF fault: {}", reason)));
+    }
+    if !status.is_success() {
+        return Err(PtzError::CommandFailed(format!(
+            "ONVIF request failed with HTTP {}",
+            status.as_u16()
+        )));
+    }
+    Ok(())
+}
+
+#[async_trait]
+impl PtzController for OnvifClient {
+    async fn move_absolute(&self, pan: f64, tilt: f64, zoom: f64) -> Result<(), PtzError> {
+        self.ptz_call(|p| soap::absolute_move(p, Some((pan, tilt)), zoom))
+            .await?;
+        Ok(())
+    }
+
+    async fn move_relative(&self, pan_delta: f64, tilt_delta: f64) -> Result<(), PtzError> {
+        if pan_delta.abs() < 0.01 && tilt_delta.abs() < 0.01 {
+            return Ok(());
+        }
+        self.ptz_call(|p| soap::relative_move(p, pan_delta, tilt_delta))
+            .await?;
+        Ok(())
+    }
+
+    async fn zoom_to(&self, zoom: f64) -> Result<(), PtzError> {
+        self.ptz_call(|p| soap::absolute_move(p, None, zoom))
+            .await?;
+        Ok(())
+    }
+
+    async fn recall_preset(&self, preset_index: u8) -> Result<(), PtzError> {
+        let token = self.preset_token(preset_index).await?.ok_or_else(|| {
+            PtzError::CommandFailed(format!("ONVIF preset {} is not stored", preset_index))
+        })?;
+        self.ptz_call(|p| soap::goto_preset(p, &token)).await?;
+        Ok(())
+    }
+
+    async fn store_preset(&self, preset_index: u8) -> Result<(), PtzError> {
+        let existing = self.preset_token(preset_index).await?;
+        let name = soap::preset_name(preset_index);
+        let response = self
+            .ptz_call(|p| soap::set_preset(p, &name, existing.as_deref()))
+            .await?;
+        if let Some(token) = soap::parse_set_preset(&response).or(existing) {
+            self.preset_tokens.lock().await.insert(preset_index, token);
+        }
+        Ok(())
+    }
+
+    async fn get_position(&self) -> Result<PtzPosition, PtzError> {
+        let response = self.ptz_call(soap::get_status).await?;
+        let (pan, tilt, zoom) = soap::parse_status(&response).ok_or_else(|| {
+            PtzError::ProtocolError("ONVIF status contained no position".to_string())
+        })?;
+        Ok(PtzPosition { pan, tilt, zoom })
+    }
+
+    async fn test_connection(&self) -> Result<(), PtzError> {
+        // Redo the handshake so changed credentials or profiles are picked up
+        *self.session.lock().await = None;
+        self.session().await?;
+        Ok(())
+    }
+
+    async fn home(&self) -> Result<(), PtzError> {
+        self.ptz_call(soap::goto_home).await?;
+        Ok(())
+    }
+
+    async fn continuous_move(&self, pan_speed: f64, tilt_speed: f64) -> Result<(), PtzError> {
+        self.ptz_call(|p| soap::continuous_move(p, pan_speed, tilt_speed, 0.0))
+            .await?;
+        Ok(())
+    }
+
+    async fn stop(&self) -> Result<(), PtzError> {
+        self.ptz_call(soap::stop).await?;
+        Ok(())
+    }
+
+    async fn zoom_continuous(&self, speed: f64) -> Result<(), PtzError> {
+        self.ptz_call(|p| soap::continuous_move(p, 0.0, 0.0, speed))
+            .await?;
+        Ok(())
+    }
+
+    fn capabilities(&self) -> Capabilities {
+        Capabilities {
+            supports_absolute: true,
+            supports_position: true,
+            supports_continuous: true,
+            supports_continuous_zoom: true,
+            supports_native_presets: true,
+            max_preset_slots: 256,
+            ..Capabilities::default()
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::{Arc, Mutex as StdMutex};
+
+    /// Serve canned SOAP replies at the default device service path and record
+    /// the operation of every request. GetCapabilities fails, so the client
+    /// falls back to the device service for media and PTZ.
+    async fn spawn_mock_camera() -> (u16, Arc<StdMutex<Vec<String>>>) {
+        let calls = Arc::new(StdMutex::new(Vec::new()));
+        let recorded = calls.clone();
+        let handler = move |body: String| {
+            let recorded = recorded.clone();
+            async move {
+                let op = ["GetCapabilities", "GetProfiles", "GetPresets", "GotoPreset"]
+                    .into_iter()
+                    .find(|op| body.contains(&format!("<{} ", op)))
+                    .unwrap_or("Other");
+                recorded.lock().unwrap().push(op.to_string());
+                let reply = match op {
+                    "GetProfiles" => {
+                        r#"<trt:Profiles token="prof"><tt:PTZConfiguration/></trt:Profiles>"#
+                    }
+                    "GetPresets" => {
+                        r#"<tptz:Preset token="abc"><tt:Name>Preset 2</tt:Name></tptz:Preset>"#
+                    }
+                    "GotoPreset"
+                        if body.contains(
+                            "<ProfileToken>prof</ProfileToken><PresetToken>abc</PresetToken>",
+                        ) =>
+                    {
+                        ""
+                    }
+                    _ => r#"<s:Fault><s:Reason><s:Text>Unsupported</s:Text></s:Reason></s:Fault>"#,
+                };
+                soap::envelope(None, reply)
+            }
+        };
+        let app = axum::Router::new().route("/onvif/device_service", axum::routing::post(handler));
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let port = listener.local_addr().unwrap().port();
+        tokio::spawn(async move { axum::serve(listener, app).await.unwrap() });
+        (port, calls)
+    }
+
+    #[tokio::test]
+    async fn recall_resolves_preset_token_after_handshake() {
+        let (port, calls) = spawn_mock_camera().await;
+        let client = OnvifClient::new("127.0.0.1", port)
+            .unwrap()
+            .with_retry_policy(RetryPolicy::none());
+
+        client.recall_preset(2).await.unwrap();
+        // The learned token is reused without another GetPresets
+        client.recall_preset(2).await.unwrap();
+        assert_eq!(
+            *calls.lock().unwrap(),
+            vec![
+                "GetCapabilities",
+                "GetProfiles",
+                "GetPresets",
+                "GotoPreset",
+                "GotoPreset"
+            ]
+        );
+        assert!(matches!(
+            client.recall_preset(5).await,
+            Err(PtzError::CommandFailed(_))
+        ));
+    }
+
+    #[test]
+    fn faults_and_auth_failures_are_errors() {
+        let fault = r#"<s:Envelope><s:Body><s:Fault><s:Reason><s:Text>No such preset</s:Text></s:Reason></s:Fault></s:Body></s:Envelope>"#;
+        assert!(matches!(
+            check_response(reqwest::StatusCode::INTERNAL_SERVER_ERROR, fault),
+            Err(PtzError::CommandFailed(m)) if m.contains("No such preset")
+        ));
+        let denied = check_response(reqwest::StatusCode::UNAUTHORIZED, "").unwrap_err();
+        assert!(matches!(denied, PtzError::AuthFailed(_)));
+        assert!(!denied.is_retryable());
+        assert!(check_response(reqwest::StatusCode::OK, "<s:Envelope/>").is_ok());
+    }
+
+    #[test]
+    fn credentials_default_username() {
+        let client = OnvifClient::new("10.0.0.5", 80)
+            .unwrap()
+            .with_credentials(None, Some("secret".to_string()));
+        assert_eq!(
+            client.credentials,
+            Some(("admin".to_string(), "secret".to_string()))
+        );
+    }
+
+    #[test]
+    fn capabilities_cover_motion_zoom_and_presets() {
+        let caps = OnvifClient::new("127.0.0.1", 80).unwrap().capabilities();
+        assert!(caps.supports_absolute && caps.supports_continuous);
+        assert!(caps.supports_continuous_zoom && caps.supports_native_presets);
+        assert!(!caps.supports_focus && !caps.supports_exposure);
+        assert_eq!(caps.max_preset_slots, 256);
+    }
+}
@@ -0,0 +1,394 @@
+//! ONVIF SOAP envelope construction and the little response parsing the
+//! PTZ client needs. Responses are scanned by element local name, so any
+//! namespace prefix the camera picks is accepted.
+
+const SOAP_ENV: &str = "http://www.w3.org/2003/05/soap-envelope";
+const DEVICE_NS: &str = "http://www.onvif.org/ver10/device/wsdl";
+const MEDIA_NS: &str = "http://www.onvif.org/ver10/media/wsdl";
+const PTZ_NS: &str = "http://www.onvif.org/ver20/ptz/wsdl";
+const SCHEMA_NS: &str = "http://www.onvif.org/ver10/schema";
+
+/// Escape text for use in XML content or attribute values.
+pub fn escape(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+        .replace('\'', "&apos;")
+}
+
+/// Wrap a body in a SOAP 1.2 envelope, with an optional security header.
+pub fn envelope(security: Option<&str>, body: &str) -> String {
+    let header = security
+        .map(|s| format!("<s:Header>{}</s:Header>", s))
+        .unwrap_or_default();
+    format!(
+        r#"<?xml version="1.0" encoding="UTF-8"?><s:Envelope xmlns:s="{}">{}<s:Body>{}</s:Body></s:Envelope>"#,
+        SOAP_ENV, header, body
+    )
+}
+
+pub fn get_capabilities() -> String {
+    format!(
+        r#"<GetCapabilities xmlns="{}"><Category>All</Category></GetCapabilities>"#,
+        DEVICE_NS
+    )
+}
+
+pub fn get_profiles() -> String {
+    format!(r#"<GetProfiles xmlns="{}"/>"#, MEDIA_NS)
+}
+
+/// Pan/tilt and zoom vectors, both in ONVIF's generic normalized spaces.
+fn vector(pan: f64, tilt: f64, zoom: Option<f64>) -> String {
+    let zoom = zoom
+        .map(|z| format!(r#"<tt:Zoom x="{}"/>"#, z))
+        .unwrap_or_default();
+    format!(r#"<tt:PanTilt x="{}" y="{}"/>{}"#, pan, tilt, zoom)
+}
+
+pub fn continuous_move(profile: &str, pan: f64, tilt: f64, zoom: f64) -> String {
+    format!(
+        r#"<ContinuousMove xmlns="{}" xmlns:tt="{}"><ProfileToken>{}</ProfileToken><Velocity>{}</Velocity></ContinuousMove>"#,
+        PTZ_NS,
+        SCHEMA_NS,
+        escape(profile),
+        vector(pan, tilt, Some(zoom))
+    )
+}
+
+/// Absolute move; `None` for `pan_tilt` moves zoom only.
+pub fn absolute_move(profile: &str, pan_tilt: Option<(f64, f64)>, zoom: f64) -> String {
+    let position = match pan_tilt {
+        Some((pan, tilt)) => vector(pan, tilt, Some(zoom)),
+        None => format!(r#"<tt:Zoom x="{}"/>"#, zoom),
+    };
+    format!(
+        r#"<AbsoluteMove xmlns="{}" xmlns:tt="{}"><ProfileToken>{}</ProfileToken><Position>{}</Position></AbsoluteMove>"#,
+        PTZ_NS,
+        SCHEMA_NS,
+        escape(profile),
+        position
+    )
+}
+
+pub fn relative_move(profile: &str, pan_delta: f64, tilt_delta: f64) -> String {
+    format!(
+        r#"<RelativeMove xmlns="{}" xmlns:tt="{}"><ProfileToken>{}</ProfileToken><Translation>{}</Translation></RelativeMove>"#,
+        PTZ_NS,
+        SCHEMA_NS,
+        escape(profile),
+        vector(pan_delta, tilt_delta, None)
+    )
+}
+
+pub fn stop(profile: &str) -> String {
+    format!(
+        r#"<Stop xmlns="{}"><ProfileToken>{}</ProfileToken><PanTilt>true</PanTilt><Zoom>true</Zoom></Stop>"#,
+        PTZ_NS,
+        escape(profile)
+    )
+}
+
+pub fn goto_home(profile: &str) -> String {
+    format!(
+        r#"<GotoHomePosition xmlns="{}"><ProfileToken>{}</ProfileToken></GotoHomePosition>"#,
+        PTZ_NS,
+        escape(profile)
+    )
+}
+
+pub fn goto_preset(profile: &str, preset_token: &str) -> String {
+    format!(
+        r#"<GotoPreset xmlns="{}"><ProfileToken>{}</ProfileToken><PresetToken>{}</PresetToken></GotoPreset>"#,
+        PTZ_NS,
+        escape(profile),
+        escape(preset_token)
+    )
+}
+
+/// Store a preset. With a token the existing preset is overwritten,
+/// otherwise the camera assigns a new token.
+pub fn set_preset(profile: &str, name: &str, preset_token: Option<&str>) -> String {
+    let token = preset_token
+        .map(|t| format!("<PresetToken>{}</PresetToken>", escape(t)))
+        .unwrap_or_default();
+    format!(
+        r#"<SetPreset xmlns="{}"><ProfileToken>{}</ProfileToken><PresetName>{}</PresetName>{}</SetPreset>"#,
+        PTZ_NS,
+        escape(profile),
+        escape(name),
+        token
+    )
+}
+
+pub fn get_presets(profile: &str) -> String {
+    format!(
+        r#"<GetPresets xmlns="{}"><ProfileToken>{}</ProfileToken></GetPresets>"#,
+        PTZ_NS,
+        escape(profile)
+    )
+}
+
+pub fn get_status(profile: &str) -> String {
+    format!(
+        r#"<GetStatus xmlns="{}"><ProfileToken>{}</ProfileToken></GetStatus>"#,
+        PTZ_NS,
+        escape(profile)
+    )
+}
+
+/// Name given to presets stored from a numbered slot.
+pub fn preset_name(index: u8) -> String {
+    format!("Preset {}", index)
+}
+
+/// Find the camera token for a numbered preset slot.
+/// Presets stored by this app are matched by name; otherwise a camera
+/// token equal to the slot number is used, which is how most cameras
+/// number their factory presets.
+pub fn preset_token_for(index: u8, presets: &[(String, String)]) -> Option<String> {
+    let name = preset_name(index);
+    presets
+        .iter()
+        .find(|(_, n)| *n == name)
+        .or_else(|| presets.iter().find(|(t, _)| *t == index.to_string()))
+        .map(|(t, _)| t.clone())
+}
+
+/// Iterate over the start tags of elements with the given local name.
+fn start_tags<'a>(xml: &'a str, local_name: &'a str) -> impl Iterator<Item = &'a str> + 'a {
+    xml.match_indices('<').filter_map(move |(i, _)| {
+        let rest = &xml[i + 1..];
+        let end = rest.find('>')?;
+        let tag = &rest[..end];
+        let name = tag.split(|c: char| c.is_whitespace() || c == '/').next()?;
+        let local = name.rsplit(':').next()?;
+        (local == local_name && !name.starts_with('/')).then_some(tag)
+    })
+}
+
+/// Value of a quoted attribute in a start tag. Unquoted values are malformed
+/// and read as missing.
+fn attribute(tag: &str, name: &str) -> Option<String> {
+    let mut rest = tag;
+    while let Some(i) = rest.find(name) {
+        let before_ok = rest[..i].ends_with(char::is_whitespace);
+        let after = rest[i + name.len()..].trim_start();
+        if before_ok {
+            if let Some(value) = after.strip_prefix('=') {
+                let value = value.trim_start();
+                let quote = value.chars().next().filter(|c| matches!(c, '"' | '\''))?;
+                let value = &value[quote.len_utf8()..];
+                return value.find(quote).map(|end| unescape(&value[..end]));
+            }
+        }
+        rest = &rest[i + name.len()..];
+    }
+    None
+}
+
+/// Text content of the first element with the given local name.
+pub fn element_text(xml: &str, local_name: &str) -> Option<String> {
+    let tag = start_tags(xml, local_name).next()?;
+    if tag.ends_with('/') {
+        return Some(String::new());
+    }
+    let start = tag.as_ptr() as usize - xml.as_ptr() as usize + tag.len() + 1;
+    let content = &xml[start..];
+    let end = content.find("</")?;
+    Some(unescape(content[..end].trim()))
+}
+
+fn unescape(text: &str) -> String {
+    text.replace("&lt;", "<")
+        .replace("&gt;", ">")
+        .replace("&quot;", "\"")
+        .replace("&apos;", "'")
+        .replace("&amp;", "&")
+}
+
+/// Service addresses from a GetCapabilities response: (media, ptz).
+pub fn parse_capabilities(xml: &str) -> (Option<String>, Option<String>) {
+    let xaddr_after = |section: &str| {
+        let tag = start_tags(xml, section).next()?;
+        let offset = tag.as_ptr() as usize - xml.as_ptr() as usize;
+        element_text(&xml[offset..], "XAddr")
+    };
+    (xaddr_after("Media"), xaddr_after("PTZ"))
+}
+
+/// Token of the first media profile that has a PTZ configuration,
+/// falling back to the first profile.
+pub fn parse_profile_token(xml: &str) -> Option<String> {
+    let profiles: Vec<(usize, String)> = start_tags(xml, "Profiles")
+        .filter_map(|tag| {
+            let offset = tag.as_ptr() as usize - xml.as_ptr() as usize;
+            attribute(tag, "token").map(|t| (offset, t))
+        })
+        .collect();
+    let with_ptz = profiles.iter().enumerate().find(|(i, (offset, _))| {
+        let end = profiles.get(i + 1).map(|(o, _)| *o).unwrap_or(xml.len());
+        start_tags(&xml[*offset..end], "PTZConfiguration")
+            .next()
+            .is_some()
+    });
+    with_ptz
+        .map(|(_, (_, t))| t.clone())
+        .or_else(|| profiles.first().map(|(_, t)| t.clone()))
+}
+
+/// (token, name) pairs from a GetPresets response.
+pub fn parse_presets(xml: &str) -> Vec<(String, String)> {
+    start_tags(xml, "Preset")
+        .filter_map(|tag| {
+            let token = attribute(tag, "token")?;
+            let offset = tag.as_ptr() as usize - xml.as_ptr() as usize;
+            let rest = &xml[offset..];
+            let end = rest.find("Preset>").unwrap_or(rest.len());
+            let name = element_text(&rest[..end.min(rest.len())], "Name").unwrap_or_default();
+            Some((token, name))
+        })
+        .collect()
+}
+
+/// Token returned by SetPreset.
+pub fn parse_set_preset(xml: &str) -> Option<String> {
+    element_text(xml, "PresetToken")
+}
+
+/// Pan, tilt and zoom from a GetStatus response.
+pub fn parse_status(xml: &str) -> Option<(f64, f64, f64)> {
+    let position = start_tags(xml, "Position").next()?;
+    let offset = position.as_ptr() as usize - xml.as_ptr() as usize;
+    let rest = &xml[offset..];
+    let pan_tilt = start_tags(rest, "PanTilt").next()?;
+    let zoom = start_tags(rest, "Zoom").next()?;
+    let num = |tag: &str, attr: &str| attribute(tag, attr)?.parse::<f64>().ok();
+    Some((num(pan_tilt, "x")?, num(pan_tilt, "y")?, num(zoom, "x")?))
+}
+
+/// Human-readable reason from a SOAP fault, if the response is one.
+pub fn parse_fault(xml: &str) -> Option<String> {
+    start_tags(xml, "Fault").next()?;
+    element_text(xml, "Text")
+        .or_else(|| element_text(xml, "faultstring"))
+        .or_else(|| Some("SOAP fault".to_string()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn envelope_wraps_header_and_body() {
+        let xml = envelope(Some("<wsse:Security/>"), &stop("profile_1"));
+        assert!(xml.starts_with(r#"<?xml version="1.0" encoding="UTF-8"?><s:Envelope"#));
+        assert!(xml.contains("<s:Header><wsse:Security/></s:Header><s:Body><Stop "));
+        assert!(xml.contains("<ProfileToken>profile_1</ProfileToken>"));
+        assert!(xml.ends_with("</s:Body></s:Envelope>"));
+        assert!(!envelope(None, "<X/>").contains("Header"));
+    }
+
+    #[test]
+    fn unquoted_or_non_ascii_attribute_values_are_missing() {
+        assert_eq!(
+            attribute(r#"Preset token='a&amp;b'"#, "token").unwrap(),
+            "a&b"
+        );
+        assert_eq!(attribute("Preset token=é1", "token"), None);
+        assert_eq!(attribute("Preset token=x1", "token"), None);
+        assert!(parse_presets("<tt:Preset token=€><tt:Name>A</tt:Name></tt:Preset>").is_empty());
+    }
+
+    #[test]
+    fn continuous_move_carries_velocity_vector() {
+        let body = continuous_move("p&1", 0.5, -0.25, 0.0);
+        assert!(body.contains("<ProfileToken>p&amp;1</ProfileToken>"));
+        assert!(body
+            .contains(r#"<Velocity><tt:PanTilt x="0.5" y="-0.25"/><tt:Zoom x="0"/></Velocity>"#));
+    }
+
+    #[test]
+    fn absolute_move_can_target_zoom_only() {
+        let body = absolute_move("p", None, 0.75);
+        assert!(body.contains(r#"<Position><tt:Zoom x="0.75"/></Position>"#));
+        let body = absolute_move("p", Some((1.0, -1.0)), 0.0);
+        assert!(body.contains(r#"<tt:PanTilt x="1" y="-1"/>"#));
+    }
+
+    #[test]
+    fn set_preset_overwrites_known_token() {
+        let body = set_preset("p", &preset_name(3), Some("7"));
+        assert!(body.contains("<PresetName>Preset 3</PresetName><PresetToken>7</PresetToken>"));
+        assert!(!set_preset("p", "x", None).contains("PresetToken"));
+    }
+
+    #[test]
+    fn preset_token_prefers_name_then_numeric_token() {
+        let presets = vec![
+            ("1".to_string(), "Door".to_string()),
+            ("abc".to_string(), "Preset 2".to_string()),
+            ("2".to_string(), "Stage".to_string()),
+        ];
+        assert_eq!(preset_token_for(2, &presets), Some("abc".to_string()));
+        assert_eq!(preset_token_for(1, &presets), Some("1".to_string()));
+        assert_eq!(preset_token_for(9, &presets), None);
+    }
+
+    #[test]
+    fn parses_presets_with_any_prefix() {
+        let xml = r#"<env:Body><tptz:GetPresetsResponse>
+            <tptz:Preset token="1"><tt:Name>Door</tt:Name></tptz:Preset>
+            <tptz:Preset token="Preset_2"><tt:Name>Preset 2</tt:Name><tt:PTZPosition/></tptz:Preset>
+            </tptz:GetPresetsResponse></env:Body>"#;
+        assert_eq!(
+            parse_presets(xml),
+            vec![
+                ("1".to_string(), "Door".to_string()),
+                ("Preset_2".to_string(), "Preset 2".to_string()),
+            ]
+        );
+    }
+
+    #[test]
+    fn picks_profile_with_ptz_configuration() {
+        let xml = r#"<trt:GetProfilesResponse>
+            <trt:Profiles token="main" fixed="true"><tt:Name>Main</tt:Name></trt:Profiles>
+            <trt:Profiles token="ptz"><tt:PTZConfiguration token="c"/></trt:Profiles>
+            </trt:GetProfilesResponse>"#;
+        assert_eq!(parse_profile_token(xml), Some("ptz".to_string()));
+        assert_eq!(
+            parse_profile_token(r#"<Profiles token="only"></Profiles>"#),
+            Some("only".to_string())
+        );
+    }
+
+    #[test]
+    fn parses_capability_addresses() {
+        let xml = r#"<tds:Capabilities>
+            <tt:Device><tt:XAddr>http://cam/onvif/device_service</tt:XAddr></tt:Device>
+            <tt:Media><tt:XAddr>http://cam/onvif/media</tt:XAddr></tt:Media>
+            <tt:PTZ><tt:XAddr>http://cam/onvif/ptz</tt:XAddr></tt:PTZ>
+            </tds:Capabilities>"#;
+        assert_eq!(
+            parse_capabilities(xml),
+            (
+                Some("http://cam/onvif/media".to_string()),
+                Some("http://cam/onvif/ptz".to_string())
+            )
+        );
+    }
+
+    #[test]
+    fn parses_status_position_and_faults() {
+        let xml = r#"<tptz:PTZStatus><tt:Position>
+            <tt:PanTilt x="-0.5" y="0.25" space="http://x"/><tt:Zoom x="1"/>
+            </tt:Position></tptz:PTZStatus>"#;
+        assert_eq!(parse_status(xml), Some((-0.5, 0.25, 1.0)));
+
+        let fault = r#"<s:Fault><s:Reason><s:Text xml:lang="en">Not authorized</s:Text></s:Reason></s:Fault>"#;
+        assert_eq!(parse_fault(fault), Some("Not authorized".to_string()));
+        assert_eq!(parse_fault(xml), None);
+    }
+}
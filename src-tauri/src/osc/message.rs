@@ -0,0 +1,334 @@
+//! OSC 1.0 packet decoding and the mapping from OSC addresses to PTZ commands.
+
+/// A decoded OSC argument. Only the types control surfaces send are supported.
+#[derive(Debug, Clone, PartialEq)]
+pub enum OscArg {
+    Int(i32),
+    Float(f32),
+    Str(String),
+    Bool(bool),
+}
+
+impl OscArg {
+    /// Numeric value of an int or float argument.
+    fn as_f64(&self) -> Option<f64> {
+        match self {
+            OscArg::Int(i) => Some(*i as f64),
+            OscArg::Float(f) if f.is_finite() => Some(*f as f64),
+            _ => None,
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct OscMessage {
+    pub address: String,
+    pub args: Vec<OscArg>,
+}
+
+/// A PTZ action requested over OSC.
+#[derive(Debug, Clone, PartialEq)]
+pub enum OscCommand {
+    /// `/ptz/move f f`: continuous pan/tilt velocity, -1.0 to 1.0. The move
+    /// stops after the deadman window unless repeated or kept alive.
+    Move { pan: f64, tilt: f64 },
+    /// `/ptz/keepalive`: keeps a held move going without changing its speed.
+    Keepalive,
+    /// `/ptz/zoom f`: absolute zoom level, 0.0 to 1.0.
+    Zoom(f64),
+    /// `/ptz/stop`
+    Stop,
+    /// `/ptz/home`
+    Home,
+    /// `/ptz/preset/recall i`: 1-based position in the active profile.
+    RecallPreset(usize),
+}
+
+/// Read a NUL-terminated string padded to a 4-byte boundary.
+fn read_string(data: &[u8], pos: &mut usize) -> Option<String> {
+    let rest = data.get(*pos..)?;
+    let len = rest.iter().position(|&b| b == 0)?;
+    let s = std::str::from_utf8(&rest[..len]).ok()?.to_string();
+    *pos += (len + 4) & !3;
+    (*pos <= data.len()).then_some(s)
+}
+
+fn read_4(data: &[u8], pos: &mut usize) -> Option<[u8; 4]> {
+    let bytes = data.get(*pos..*pos + 4)?.try_into().ok()?;
+    *pos += 4;
+    Some(bytes)
+}
+
+/// Decode a single OSC message. Returns `None` for anything malformed.
+pub fn decode_message(data: &[u8]) -> Option<OscMessage> {
+    let mut pos = 0;
+    let address = read_string(data, &mut pos)?;
+    // Messages without a type tag string are allowed by OSC 1.0 and carry no args
+    if pos >= data.len() {
+        return Some(OscMessage {
+            address,
+            args: Vec::new(),
+        });
+    }
+    let tags = read_string(data, &mut pos)?;
+    let tags = tags.strip_prefix(',')?;
+
+    let mut args = Vec::with_capacity(tags.len());
+    for tag in tags.chars() {
+        let arg = match tag {
+            'i' => OscArg::Int(i32::from_be_bytes(read_4(data, &mut pos)?)),
+            'f' => OscArg::Float(f32::from_be_bytes(read_4(data, &mut pos)?)),
+            's' => OscArg::Str(read_string(data, &mut pos)?),
+            'T' => OscArg::Bool(true),
+            'F' => OscArg::Bool(false),
+            _ => return None,
+        };
+        args.push(arg);
+    }
+    Some(OscMessage { address, args })
+}
+
+/// Decode a packet, flattening bundles into their messages.
+/// Malformed bundle elements are skipped.
+pub fn decode_packet(data: &[u8]) -> Vec<OscMessage> {
+    const BUNDLE_TAG: &[u8] = b"#bundle\0";
+    let Some(mut rest) = data.strip_prefix(BUNDLE_TAG) else {
+        return decode_message(data).into_iter().collect();
+    };
+    // Skip the time tag; commands run on arrival
+    rest = rest.get(8..).unwrap_or_default();
+
+    let mut messages = Vec::new();
+    while rest.len() >= 4 {
+        let size = u32::from_be_bytes([rest[0], rest[1], rest[2], rest[3]]) as usize;
+        let Some(element) = rest.get(4..4 + size) else {
+            break;
+        };
+        messages.extend(decode_packet(element));
+        rest = &rest[4 + size..];
+    }
+    messages
+}
+
+/// Whether an address is a plain OSC path. Pattern characters are rejected
+/// because the server matches addresses literally.
+pub fn is_valid_address(address: &str) -> bool {
+    address.len() > 1
+        && address.starts_with('/')
+        && !address.ends_with('/')
+        && !address.contains("//")
+        && !address
+            .chars()
+            .any(|c| c.is_whitespace() || c.is_control() || "#*,?[]{}".contains(c))
+}
+
+/// A button-style trigger: no argument, or a press (non-zero / true) but not a release.
+fn is_trigger(args: &[OscArg]) -> bool {
+    match args.first() {
+        None => true,
+        Some(OscArg::Bool(b)) => *b,
+        Some(arg) => arg.as_f64().is_some_and(|v| v != 0.0),
+    }
+}
+
+/// Map a message to a PTZ command. Unknown addresses and wrong argument
+/// types return `None` so the message is ignored.
+pub fn parse_command(message: &OscMessage) -> Option<OscCommand> {
+    if !is_valid_address(&message.address) {
+        return None;
+    }
+    let args = &message.args;
+    match message.address.as_str() {
+        "/ptz/move" => match args.as_slice() {
+            [pan, tilt] => Some(OscCommand::Move {
+                pan: pan.as_f64()?.clamp(-1.0, 1.0),
+                tilt: tilt.as_f64()?.clamp(-1.0, 1.0),
+            }),
+            _ => None,
+        },
+        "/ptz/zoom" => match args.as_slice() {
+            [zoom] => Some(OscCommand::Zoom(zoom.as_f64()?.clamp(0.0, 1.0))),
+            _ => None,
+        },
+        "/ptz/keepalive" => is_trigger(args).then_some(OscCommand::Keepalive),
+        "/ptz/stop" => is_trigger(args).then_some(OscCommand::Stop),
+        "/ptz/home" => is_trigger(args).then_some(OscCommand::Home),
+        "/ptz/preset/recall" => match args.as_slice() {
+            [OscArg::Int(i)] if *i >= 1 => Some(OscCommand::RecallPreset(*i as usize)),
+            // Some layouts only send floats; accept whole numbers
+            [OscArg::Float(f)] if *f >= 1.0 && f.fract() == 0.0 => {
+                Some(OscCommand::RecallPreset(*f as usize))
+            }
+            _ => None,
+        },
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+pub(crate) mod tests {
+    use super::*;
+
+    fn pad(bytes: &mut Vec<u8>, s: &str) {
+        bytes.extend_from_slice(s.as_bytes());
+        bytes.push(0);
+        while !bytes.len().is_multiple_of(4) {
+            bytes.push(0);
+        }
+    }
+
+    /// Encode a message, for feeding the decoder and the server in tests.
+    pub(crate) fn encode(address: &str, args: &[OscArg]) -> Vec<u8> {
+        let mut bytes = Vec::new();
+        pad(&mut bytes, address);
+        let mut tags = String::from(",");
+        let mut data = Vec::new();
+        for arg in args {
+            match arg {
+                OscArg::Int(i) => {
+                    tags.push('i');
+                    data.extend_from_slice(&i.to_be_bytes());
+                }
+                OscArg::Float(f) => {
+                    tags.push('f');
+                    data.extend_from_slice(&f.to_be_bytes());
+                }
+                OscArg::Str(s) => {
+                    tags.push('s');
+                    pad(&mut data, s);
+                }
+                OscArg::Bool(b) => tags.push(if *b { 'T' } else { 'F' }),
+            }
+        }
+        pad(&mut bytes, &tags);
+        bytes.extend(data);
+        bytes
+    }
+
+    fn command(address: &str, args: &[OscArg]) -> Option<OscCommand> {
+        decode_message(&encode(address, args)).and_then(|m| parse_command(&m))
+    }
+
+    #[test]
+    fn decodes_padded_strings_and_numbers() {
+        let bytes = encode(
+            "/ptz/move",
+            &[
+                OscArg::Float(0.5),
+                OscArg::Int(-1),
+                OscArg::Str("abcd".into()),
+            ],
+        );
+        assert!(bytes.len().is_multiple_of(4));
+        let message = decode_message(&bytes).unwrap();
+        assert_eq!(message.address, "/ptz/move");
+        assert_eq!(
+            message.args,
+            vec![
+                OscArg::Float(0.5),
+                OscArg::Int(-1),
+                OscArg::Str("abcd".into())
+            ]
+        );
+    }
+
+    #[test]
+    fn truncated_and_unknown_types_are_rejected() {
+        let bytes = encode("/ptz/zoom", &[OscArg::Float(0.5)]);
+        assert!(decode_message(&bytes[..bytes.len() - 2]).is_none());
+
+        let mut blob = Vec::new();
+        pad(&mut blob, "/ptz/zoom");
+        pad(&mut blob, ",b");
+        blob.extend_from_slice(&[0, 0, 0, 0]);
+        assert!(decode_message(&blob).is_none());
+    }
+
+    #[test]
+    fn bundles_are_flattened() {
+        let first = encode("/ptz/stop", &[]);
+        let second = encode("/ptz/zoom", &[OscArg::Float(1.0)]);
+        let mut bundle = b"#bundle\0".to_vec();
+        bundle.extend_from_slice(&[0, 0, 0, 0, 0, 0, 0, 1]);
+        for element in [&first, &second] {
+            bundle.extend_from_slice(&(element.len() as u32).to_be_bytes());
+            bundle.extend_from_slice(element);
+        }
+        let addresses: Vec<String> = decode_packet(&bundle)
+            .into_iter()
+            .map(|m| m.address)
+            .collect();
+        assert_eq!(addresses, vec!["/ptz/stop", "/ptz/zoom"]);
+    }
+
+    #[test]
+    fn move_and_zoom_are_clamped() {
+        assert_eq!(
+            command("/ptz/move", &[OscArg::Float(2.0), OscArg::Int(-1)]),
+            Some(OscCommand::Move {
+                pan: 1.0,
+                tilt: -1.0
+            })
+        );
+        assert_eq!(
+            command("/ptz/zoom", &[OscArg::Float(-0.5)]),
+            Some(OscCommand::Zoom(0.0))
+        );
+    }
+
+    #[test]
+    fn wrong_argument_types_are_ignored() {
+        assert_eq!(command("/ptz/move", &[OscArg::Float(0.5)]), None);
+        assert_eq!(
+            command("/ptz/move", &[OscArg::Str("x".into()), OscArg::Float(0.5)]),
+            None
+        );
+        assert_eq!(command("/ptz/zoom", &[OscArg::Float(f32::NAN)]), None);
+        assert_eq!(command("/ptz/preset/recall", &[OscArg::Int(0)]), None);
+        assert_eq!(command("/ptz/preset/recall", &[OscArg::Float(1.5)]), None);
+    }
+
+    #[test]
+    fn preset_recall_accepts_ints_and_whole_floats() {
+        assert_eq!(
+            command("/ptz/preset/recall", &[OscArg::Int(3)]),
+            Some(OscCommand::RecallPreset(3))
+        );
+        assert_eq!(
+            command("/ptz/preset/recall", &[OscArg::Float(2.0)]),
+            Some(OscCommand::RecallPreset(2))
+        );
+    }
+
+    #[test]
+    fn buttons_trigger_on_press_only() {
+        assert_eq!(command("/ptz/stop", &[]), Some(OscCommand::Stop));
+        assert_eq!(
+            command("/ptz/home", &[OscArg::Float(1.0)]),
+            Some(OscCommand::Home)
+        );
+        assert_eq!(command("/ptz/home", &[OscArg::Float(0.0)]), None);
+        assert_eq!(command("/ptz/stop", &[OscArg::Bool(false)]), None);
+    }
+
+    #[test]
+    fn keepalive_is_a_trigger() {
+        assert_eq!(command("/ptz/keepalive", &[]), Some(OscCommand::Keepalive));
+        assert_eq!(
+            command("/ptz/keepalive", &[OscArg::Int(1)]),
+            Some(OscCommand::Keepalive)
+        );
+        assert_eq!(command("/ptz/keepalive", &[OscArg::Float(0.0)]), None);
+    }
+
+    #[test]
+    fn addresses_are_validated() {
+        assert!(is_valid_address("/ptz/move"));
+        assert!(!is_valid_address("ptz/move"));
+        assert!(!is_valid_address("/ptz/*"));
+        assert!(!is_valid_address("/ptz//move"));
+        assert!(!is_valid_address("/ptz/move/"));
+        assert!(!is_valid_address("/"));
+        assert_eq!(command("/ptz/unknown", &[]), None);
+    }
+}
@@ -0,0 +1,107 @@
+use super::message::{self, OscCommand};
+use crate::video::mjpeg_server::ServerOptions;
+use std::future::Future;
+use std::net::SocketAddr;
+use tokio::net::UdpSocket;
+use tokio::sync::watch;
+
+/// Largest datagram accepted; OSC control messages are far smaller.
+const MAX_PACKET_SIZE: usize = 4096;
+
+/// Start listening for OSC on the configured address. The default is
+/// localhost; tablets on the network need an explicit LAN or `0.0.0.0` bind.
+/// `on_command` runs for each recognised command, one at a time in arrival order.
+/// Returns the bound port (useful without a fixed one) and a shutdown sender.
+pub async fn start_server<F, Fut>(
+    options: &ServerOptions,
+    on_command: F,
+) -> Result<(u16, watch::Sender<bool>), String>
+where
+    F: Fn(OscCommand) -> Fut + Send + 'static,
+    Fut: Future<Output = ()> + Send,
+{
+    let addr = SocketAddr::new(options.bind_address, options.port.unwrap_or(0));
+    let socket = UdpSocket::bind(addr)
+        .await
+        .map_err(|e| format!("Failed to bind OSC server to {}: {}", addr, e))?;
+    let port = socket.local_addr().map_err(|e| e.to_string())?.port();
+
+    let (shutdown_tx, mut shutdown_rx) = watch::channel(false);
+
+    let receiver = tokio::spawn(async move {
+        let mut buf = vec![0u8; MAX_PACKET_SIZE];
+        loop {
+            let (len, from) = match socket.recv_from(&mut buf).await {
+                Ok(received) => received,
+                Err(e) => {
+                    log::debug!("OSC receive failed: {}", e);
+                    continue;
+                }
+            };
+            for msg in message::decode_packet(&buf[..len]) {
+                match message::parse_command(&msg) {
+                    Some(command) => on_command(command).await,
+                    None => log::debug!("Ignoring OSC message {} from {}", msg.address, from),
+                }
+            }
+        }
+    });
+
+    tokio::spawn(async move {
+        // Wait until shutdown signal is received
+        while !*shutdown_rx.borrow_and_update() {
+            if shutdown_rx.changed().await.is_err() {
+                break;
+            }
+        }
+        receiver.abort();
+        log::info!("OSC server on port {} shut down", port);
+    });
+
+    log::info!("OSC server listening on UDP port {}", port);
+    Ok((port, shutdown_tx))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::osc::message::tests::encode;
+    use crate::osc::message::OscArg;
+    use std::time::Duration;
+    use tokio::sync::mpsc;
+
+    #[tokio::test]
+    async fn routes_valid_messages_and_drops_malformed_ones() {
+        let (tx, mut rx) = mpsc::unbounded_channel();
+        let (port, shutdown_tx) = start_server(&ServerOptions::default(), move |command| {
+            let _ = tx.send(command);
+            async {}
+        })
+        .await
+        .unwrap();
+
+        let client = UdpSocket::bind("127.0.0.1:0").await.unwrap();
+        let target = ("127.0.0.1", port);
+        client.send_to(b"garbage", target).await.unwrap();
+        client
+            .send_to(&encode("/ptz/zoom", &[OscArg::Str("x".into())]), target)
+            .await
+            .unwrap();
+        client
+            .send_to(&encode("/ptz/preset/recall", &[OscArg::Int(2)]), target)
+            .await
+            .unwrap();
+
+        let command = tokio::time::timeout(Duration::from_secs(2), rx.recv())
+            .await
+            .expect("no command routed")
+            .unwrap();
+        assert_eq!(command, OscCommand::RecallPreset(2));
+
+        shutdown_tx.send(true).unwrap();
+        // The handler is dropped with the receive task
+        tokio::time::timeout(Duration::from_secs(2), rx.recv())
+            .await
+            .expect("server did not shut down");
+    }
+}
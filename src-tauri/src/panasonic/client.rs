@@ -1,42 +1,242 @@
-use crate::ptz::controller::{PtzController, PtzError};
-use crate::ptz::types::PtzPosition;
+use crate::ptz::cancel::CancelToken;
+use crate::ptz::controller::{move_relative_via_absolute, PtzController, PtzError};
+use crate::ptz::nudge;
+use crate::ptz::preview::{EncodedRequest, PtzCommand};
+use crate::ptz::retry::{map_request_error, RetryPolicy};
+use crate::ptz::timeout;
+use crate::ptz::types::{CameraInfo, Capabilities, ExposureMode, PtzPosition, RelativeMode};
 use async_trait::async_trait;
+use tokio::sync::OnceCell;
+
+/// Highest gain the AW `OGU` command accepts (0x38 - 0x08).
+const MAX_GAIN_DB: u8 = 0x30;
+
+/// Highest shutter step in the AW-UE series step table.
+const MAX_SHUTTER_STEP: u8 = 0x0B;
+
+/// APS speed sent when no move speed is configured.
+const DEFAULT_APS_SPEED: &str = "30";
+
+/// Highest APS speed on standard firmware (`00`-`1D`).
+pub const APS_SPEED_MAX_STANDARD: u8 = 0x1D;
+
+/// Highest APS speed on extended-range firmware (`00`-`29`).
+pub const APS_SPEED_MAX_EXTENDED: u8 = 0x29;
 
 /// Panasonic AW protocol client using HTTP CGI commands.
 /// Supports AW-UE150, AW-UE100, AW-UE70, AW-UE50, AW-UE40, AW-UE20, etc.
 pub struct PanasonicClient {
     base_url: String,
     client: reqwest::Client,
+    /// Normalized absolute-move speed; `None` sends the legacy `30`.
+    move_speed: Option<f64>,
+    /// Top of the firmware's APS speed table.
+    move_speed_max: u8,
+    relative_mode: RelativeMode,
+    retry_policy: RetryPolicy,
+    /// Model and firmware, fetched on first `identify`.
+    info: OnceCell<Option<CameraInfo>>,
+    /// Cuts short the timed burst of a relative move.
+    cancel: CancelToken,
 }
 
 impl PanasonicClient {
     pub fn new(host: &str, port: u16) -> Result<Self, PtzError> {
-        crate::ptz::types::validate_host(host).map_err(PtzError::ConnectionFailed)?;
+        crate::ptz::controller::validate_address(host, port)?;
         Ok(Self {
-            base_url: format!("http://{}:{}", host, port),
+            base_url: format!("http://{}", crate::ptz::types::host_with_port(host, port)),
             client: reqwest::Client::new(),
+            move_speed: None,
+            move_speed_max: APS_SPEED_MAX_STANDARD,
+            relative_mode: RelativeMode::default(),
+            retry_policy: RetryPolicy::default(),
+            info: OnceCell::new(),
+            cancel: CancelToken::new(),
         })
     }
 
+    /// Set the absolute-move speed (normalized 0.0 to 1.0).
+    pub fn with_move_speed(mut self, speed: Option<f64>) -> Self {
+        self.move_speed = speed;
+        self
+    }
+
+    /// Choose how relative moves are carried out.
+    pub fn with_relative_mode(mut self, mode: RelativeMode) -> Self {
+        self.relative_mode = mode;
+        self
+    }
+
+    /// Set the top of the firmware's APS speed table.
+    pub fn with_move_speed_max(mut self, max: u8) -> Self {
+        self.move_speed_max = max;
+        self
+    }
+
+    /// Set the retry policy for transient network failures.
+    pub fn with_retry_policy(mut self, policy: RetryPolicy) -> Self {
+        self.retry_policy = policy;
+        self
+    }
+
     async fn send_ptz_command(&self, cmd: &str) -> Result<String, PtzError> {
-        let url = format!("{}/cgi-bin/aw_ptz", self.base_url);
         let cmd_with_prefix = format!("#{}", cmd);
+        self.send_cgi("aw_ptz", &cmd_with_prefix).await
+    }
 
-        let response = self
-            .client
-            .get(&url)
-            .query(&[("cmd", &cmd_with_prefix), ("res", &"1".to_string())])
-            .timeout(std::time::Duration::from_secs(5))
-            .send()
-            .await
-            .map_err(|e| PtzError::ConnectionFailed(e.to_string()))?;
+    /// Ask for the power state (`#O`). Every AW camera answers `p0`, `p1`
+    /// or `p3`, which tells one apart from any other web server.
+    pub async fn check_power_reply(&self) -> Result<(), PtzError> {
+        let reply = self.send_ptz_command("O").await?;
+        match reply.trim() {
+            "p0" | "p1" | "p3" => Ok(()),
+            other => Err(PtzError::ProtocolError(format!(
+                "Unexpected power status reply: {:?}",
+                other
+            ))),
+        }
+    }
 
-        let text = response
-            .text()
+    /// Send a camera-control command (`/cgi-bin/aw_cam`, no `#` prefix).
+    async fn send_cam_command(&self, cmd: &str) -> Result<String, PtzError> {
+        self.send_cgi("aw_cam", cmd).await
+    }
+
+    /// URL of a CGI request, e.g. `/cgi-bin/aw_ptz?cmd=%23R01&res=1`.
+    fn cgi_url(&self, script: &str, cmd: &str) -> Result<reqwest::Url, PtzError> {
+        reqwest::Url::parse_with_params(
+            &format!("{}/cgi-bin/{}", self.base_url, script),
+            &[("cmd", cmd), ("res", "1")],
+        )
+        .map_err(|e| PtzError::CommandFailed(e.to_string()))
+    }
+
+    /// The request `send_ptz_command` would make for `cmd`.
+    fn encode_ptz_command(&self, cmd: &str) -> Result<EncodedRequest, PtzError> {
+        Ok(EncodedRequest::Http {
+            method: "GET".to_string(),
+            url: self.cgi_url("aw_ptz", &format!("#{}", cmd))?.to_string(),
+            body: None,
+        })
+    }
+
+    /// Pan/tilt and zoom commands of an absolute move.
+    fn move_absolute_commands(&self, pan: f64, tilt: f64, zoom: f64) -> [String; 2] {
+        [
+            // Absolute pan/tilt: #APS[pan][tilt][speed]
+            self.absolute_pan_tilt_command(pan, tilt),
+            // Zoom: #Z[position]
+            format!("Z{}", Self::normalize_to_zoom_hex(zoom)),
+        ]
+    }
+
+    /// Send a CGI request, retrying transient failures per the retry policy.
+    async fn send_cgi(&self, script: &str, cmd: &str) -> Result<String, PtzError> {
+        let url = self.cgi_url(script, cmd)?;
+
+        self.retry_policy
+            .run(|| async {
+                let response = self
+                    .client
+                    .get(url.clone())
+                    .timeout(timeout::or_default(timeout::HTTP_TIMEOUT))
+                    .send()
+                    .await
+                    .map_err(map_request_error)?;
+
+                response
+                    .text()
+                    .await
+                    .map_err(|e| PtzError::CommandFailed(e.to_string()))
+            })
             .await
-            .map_err(|e| PtzError::CommandFailed(e.to_string()))?;
+    }
+
+    /// Zoom speed command: `#Z[speed]`, where 01 = fastest wide, 49 = slowest
+    /// wide, 50 = stop, 51 = slowest tele and 99 = fastest tele (AW-UE150
+    /// zoom speed table). Two digits, unlike the three-digit zoom position.
+    fn zoom_speed_command(speed: f64) -> String {
+        format!("Z{}", Self::delta_to_speed(speed))
+    }
+
+    /// Focus speed command: `#F[speed]`, where 01 = near, 50 = stop, 99 = far.
+    fn focus_speed_command(speed: f64) -> String {
+        format!("F{}", Self::delta_to_speed(speed))
+    }
+
+    /// Focus mode command: `#D11` = auto focus, `#D10` = manual focus.
+    fn focus_mode_command(auto: bool) -> String {
+        format!("D1{}", if auto { 1 } else { 0 })
+    }
+
+    /// One-push auto focus: `OSE:69:1`, a camera-control command.
+    fn one_push_af_command() -> &'static str {
+        "OSE:69:1"
+    }
+
+    /// Iris position command: `#AXI[555-FFF]`, 0x555 = closed, 0xFFF = open.
+    fn iris_command(iris: f64) -> Result<String, PtzError> {
+        if !iris.is_finite() || !(0.0..=1.0).contains(&iris) {
+            return Err(PtzError::ProtocolError(format!(
+                "Iris {iris} out of range 0.0..=1.0"
+            )));
+        }
+        let value = (0x555 as f64 + iris * (0xFFF - 0x555) as f64).round() as u16;
+        Ok(format!("AXI{:03X}", value))
+    }
+
+    /// Iris mode command: `#D31` = auto iris, `#D30` = manual iris.
+    fn iris_mode_command(auto: bool) -> String {
+        format!("D3{}", if auto { 1 } else { 0 })
+    }
+
+    /// Gain command: `OGU:[08-38]`, where 0x08 = 0 dB in 1 dB steps.
+    fn gain_command(gain_db: u8) -> Result<String, PtzError> {
+        if gain_db > MAX_GAIN_DB {
+            return Err(PtzError::ProtocolError(format!(
+                "Gain {gain_db} dB out of range 0..={MAX_GAIN_DB}"
+            )));
+        }
+        Ok(format!("OGU:{:02X}", 0x08 + gain_db))
+    }
+
+    /// Auto gain (AGC) command: `OGU:80`.
+    fn auto_gain_command() -> &'static str {
+        "OGU:80"
+    }
+
+    /// Shutter step command: `OSJ:03:[0-B]`, where 0 = shutter off.
+    fn shutter_command(step: u8) -> Result<String, PtzError> {
+        if step > MAX_SHUTTER_STEP {
+            return Err(PtzError::ProtocolError(format!(
+                "Shutter step {step} out of range 0..={MAX_SHUTTER_STEP}"
+            )));
+        }
+        Ok(format!("OSJ:03:{:X}", step))
+    }
 
-        Ok(text)
+    /// Convert normalized speed (0.0 to 1.0) to an APS speed of `00` up to `max`.
+    fn normalize_to_aps_speed(normalized: f64, max: u8) -> String {
+        let clamped = if normalized.is_finite() {
+            normalized.clamp(0.0, 1.0)
+        } else {
+            0.0
+        };
+        format!("{:02X}", (clamped * max as f64).round() as u8)
+    }
+
+    /// Absolute pan/tilt command: `#APS[pan][tilt][speed]`.
+    fn absolute_pan_tilt_command(&self, pan: f64, tilt: f64) -> String {
+        let speed = match self.move_speed {
+            Some(speed) => Self::normalize_to_aps_speed(speed, self.move_speed_max),
+            None => DEFAULT_APS_SPEED.to_string(),
+        };
+        format!(
+            "APS{}{}{}",
+            Self::normalize_to_pan_hex(pan),
+            Self::normalize_to_tilt_hex(tilt),
+            speed
+        )
     }
 
     /// Convert normalized pan (-1.0 to 1.0) to Panasonic hex value.
@@ -83,22 +283,32 @@ impl PanasonicClient {
 #[async_trait]
 impl PtzController for PanasonicClient {
     async fn move_absolute(&self, pan: f64, tilt: f64, zoom: f64) -> Result<(), PtzError> {
-        let pan_hex = Self::normalize_to_pan_hex(pan);
-        let tilt_hex = Self::normalize_to_tilt_hex(tilt);
-        let zoom_hex = Self::normalize_to_zoom_hex(zoom);
-
-        // Absolute pan/tilt: #APS[pan][tilt][speed]
-        let cmd = format!("APS{}{}30", pan_hex, tilt_hex);
-        self.send_ptz_command(&cmd).await?;
-
-        // Zoom: #Z[position]
-        let zoom_cmd = format!("Z{}", zoom_hex);
-        self.send_ptz_command(&zoom_cmd).await?;
-
+        for cmd in self.move_absolute_commands(pan, tilt, zoom) {
+            self.send_ptz_command(&cmd).await?;
+        }
         Ok(())
     }
 
+    fn encode(&self, command: &PtzCommand) -> Result<Vec<EncodedRequest>, PtzError> {
+        let commands = match *command {
+            PtzCommand::MoveAbsolute { pan, tilt, zoom } => {
+                self.move_absolute_commands(pan, tilt, zoom).to_vec()
+            }
+            PtzCommand::ZoomTo { zoom } => vec![format!("Z{}", Self::normalize_to_zoom_hex(zoom))],
+            PtzCommand::RecallPreset { index } => vec![format!("R{:02}", index)],
+            PtzCommand::StorePreset { index } => vec![format!("M{:02}", index)],
+        };
+        commands
+            .iter()
+            .map(|cmd| self.encode_ptz_command(cmd))
+            .collect()
+    }
+
     async fn move_relative(&self, pan_delta: f64, tilt_delta: f64) -> Result<(), PtzError> {
+        if self.relative_mode == RelativeMode::ViaAbsolute {
+            return move_relative_via_absolute(self, pan_delta, tilt_delta).await;
+        }
+
         let pan_speed = Self::delta_to_speed(pan_delta);
         let tilt_speed = Self::delta_to_speed(tilt_delta);
 
@@ -110,8 +320,14 @@ impl PtzController for PanasonicClient {
         let tilt_cmd = format!("T{}", tilt_speed);
         self.send_ptz_command(&tilt_cmd).await?;
 
-        // Brief movement then stop
-        tokio::time::sleep(std::time::Duration::from_millis(200)).await;
+        // Brief movement then stop, sooner if a newer command arrives
+        if !self
+            .cancel
+            .sleep(nudge::dwell_for(pan_delta, tilt_delta))
+            .await
+        {
+            log::debug!("Relative move cut short by a newer command");
+        }
 
         // Stop: #PTS5050
         self.send_ptz_command("PTS5050").await?;
@@ -182,6 +398,42 @@ impl PtzController for PanasonicClient {
         Ok(())
     }
 
+    /// Follows the connection test with a `QID` model query; cameras that
+    /// don't answer it still pass.
+    async fn probe(&self) -> Result<Option<String>, PtzError> {
+        self.test_connection().await?;
+        Ok(self
+            .send_cam_command("QID")
+            .await
+            .ok()
+            .and_then(|response| parse_model_id(&response)))
+    }
+
+    /// `QID` for the model and `QSV` for the firmware version; cameras that
+    /// don't answer `QID` are reported as unidentified.
+    async fn identify(&self) -> Result<Option<CameraInfo>, PtzError> {
+        let info = self
+            .info
+            .get_or_try_init(|| async {
+                let model = parse_model_id(&self.send_cam_command("QID").await?);
+                let firmware = match model {
+                    Some(_) => self
+                        .send_cam_command("QSV")
+                        .await
+                        .ok()
+                        .and_then(|response| parse_software_version(&response)),
+                    None => None,
+                };
+                Ok::<_, PtzError>(model.map(|model| CameraInfo {
+                    vendor: "Panasonic".into(),
+                    model,
+                    firmware,
+                }))
+            })
+            .await?;
+        Ok(info.clone())
+    }
+
     async fn continuous_move(&self, pan_speed: f64, tilt_speed: f64) -> Result<(), PtzError> {
         let ps = Self::delta_to_speed(pan_speed);
         let ts = Self::delta_to_speed(tilt_speed);
@@ -194,4 +446,317 @@ impl PtzController for PanasonicClient {
         self.send_ptz_command("PTS5050").await?;
         Ok(())
     }
+
+    async fn zoom_continuous(&self, speed: f64) -> Result<(), PtzError> {
+        self.send_ptz_command(&Self::zoom_speed_command(speed))
+            .await?;
+        Ok(())
+    }
+
+    async fn focus_continuous(&self, speed: f64) -> Result<(), PtzError> {
+        self.send_ptz_command(&Self::focus_speed_command(speed))
+            .await?;
+        Ok(())
+    }
+
+    async fn set_autofocus(&self, enabled: bool) -> Result<(), PtzError> {
+        self.send_ptz_command(&Self::focus_mode_command(enabled))
+            .await?;
+        Ok(())
+    }
+
+    async fn autofocus_trigger(&self) -> Result<(), PtzError> {
+        self.send_cam_command(Self::one_push_af_command()).await?;
+        Ok(())
+    }
+
+    async fn focus_stop(&self) -> Result<(), PtzError> {
+        self.send_ptz_command(&Self::focus_speed_command(0.0))
+            .await?;
+        Ok(())
+    }
+
+    async fn set_iris(&self, iris: f64) -> Result<(), PtzError> {
+        let cmd = Self::iris_command(iris)?;
+        self.send_ptz_command(&cmd).await?;
+        Ok(())
+    }
+
+    async fn set_gain(&self, gain_db: u8) -> Result<(), PtzError> {
+        let cmd = Self::gain_command(gain_db)?;
+        self.send_cam_command(&cmd).await?;
+        Ok(())
+    }
+
+    async fn set_shutter(&self, step: u8) -> Result<(), PtzError> {
+        let cmd = Self::shutter_command(step)?;
+        self.send_cam_command(&cmd).await?;
+        Ok(())
+    }
+
+    /// `#D31` plus AGC for auto. Manual is only `#D30`: AW cameras have no
+    /// single manual exposure switch, and AGC can't be turned off without
+    /// choosing a gain, so that is left to `set_gain`.
+    async fn set_exposure_mode(&self, mode: ExposureMode) -> Result<(), PtzError> {
+        let auto = mode == ExposureMode::Auto;
+        self.send_ptz_command(&Self::iris_mode_command(auto))
+            .await?;
+        if auto {
+            self.send_cam_command(Self::auto_gain_command()).await?;
+        }
+        Ok(())
+    }
+
+    /// With a configured APS speed the head travels at that speed.
+    fn supports_timed_moves(&self) -> bool {
+        self.move_speed.is_some()
+    }
+
+    fn cancel_token(&self) -> Option<CancelToken> {
+        Some(self.cancel.clone())
+    }
+
+    /// AW presets R00-R99.
+    fn capabilities(&self) -> Capabilities {
+        Capabilities {
+            supports_absolute: true,
+            supports_position: true,
+            supports_continuous: true,
+            supports_focus: true,
+            supports_exposure: true,
+            supports_native_presets: true,
+            max_preset_slots: 100,
+            ..Capabilities::default()
+        }
+    }
+}
+
+/// Model name from a `QID` reply such as `OID:AW-UE150`.
+fn parse_model_id(response: &str) -> Option<String> {
+    reply_value(response, "OID:")
+}
+
+/// Firmware version from a `QSV` reply such as `OSV:1.42`.
+fn parse_software_version(response: &str) -> Option<String> {
+    reply_value(response, "OSV:")
+}
+
+/// Non-empty value after `prefix` in a query reply.
+fn reply_value(response: &str, prefix: &str) -> Option<String> {
+    response
+        .trim()
+        .strip_prefix(prefix)
+        .map(str::trim)
+        .filter(|value| !value.is_empty())
+        .map(str::to_string)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn ipv6_host_is_bracketed_in_base_url() {
+        let client = PanasonicClient::new("::1", 80).unwrap();
+        assert_eq!(client.base_url, "http://[::1]:80");
+        let client = PanasonicClient::new("10.0.0.2", 80).unwrap();
+        assert_eq!(client.base_url, "http://10.0.0.2:80");
+    }
+
+    #[test]
+    fn invalid_host_is_a_config_error() {
+        assert!(matches!(
+            PanasonicClient::new("10.0.0.2/cgi-bin", 80).err(),
+            Some(PtzError::InvalidConfig(_))
+        ));
+    }
+
+    #[test]
+    fn aps_speed_mapping() {
+        assert_eq!(PanasonicClient::normalize_to_aps_speed(0.0, 0x1D), "00");
+        assert_eq!(PanasonicClient::normalize_to_aps_speed(1.0, 0x1D), "1D");
+        assert_eq!(PanasonicClient::normalize_to_aps_speed(0.5, 0x1D), "0F");
+        assert_eq!(PanasonicClient::normalize_to_aps_speed(1.0, 0x29), "29");
+        assert_eq!(PanasonicClient::normalize_to_aps_speed(2.0, 0x1D), "1D");
+    }
+
+    #[test]
+    fn absolute_command_defaults_to_legacy_speed() {
+        let client = PanasonicClient::new("10.0.0.1", 80).unwrap();
+        assert_eq!(client.absolute_pan_tilt_command(0.0, 0.0), "APS8000800030");
+    }
+
+    #[test]
+    fn absolute_command_uses_configured_speed() {
+        let client = PanasonicClient::new("10.0.0.1", 80)
+            .unwrap()
+            .with_move_speed(Some(1.0));
+        assert_eq!(client.absolute_pan_tilt_command(0.0, 0.0), "APS800080001D");
+
+        let client = client.with_move_speed_max(APS_SPEED_MAX_EXTENDED);
+        assert_eq!(client.absolute_pan_tilt_command(0.0, 0.0), "APS8000800029");
+    }
+
+    #[test]
+    fn zoom_tele_command() {
+        assert_eq!(PanasonicClient::zoom_speed_command(1.0), "Z99");
+        assert_eq!(PanasonicClient::zoom_speed_command(0.5), "Z75");
+    }
+
+    #[test]
+    fn zoom_wide_command() {
+        assert_eq!(PanasonicClient::zoom_speed_command(-1.0), "Z01");
+        assert_eq!(PanasonicClient::zoom_speed_command(-0.5), "Z25");
+    }
+
+    #[test]
+    fn zoom_stop_command() {
+        assert_eq!(PanasonicClient::zoom_speed_command(0.0), "Z50");
+        assert_eq!(PanasonicClient::zoom_speed_command(0.005), "Z50");
+    }
+
+    #[test]
+    fn focus_near_command() {
+        assert_eq!(PanasonicClient::focus_speed_command(-1.0), "F01");
+        assert_eq!(PanasonicClient::focus_speed_command(-0.5), "F25");
+    }
+
+    #[test]
+    fn focus_far_command() {
+        assert_eq!(PanasonicClient::focus_speed_command(1.0), "F99");
+        assert_eq!(PanasonicClient::focus_speed_command(0.5), "F75");
+    }
+
+    #[test]
+    fn focus_stop_command() {
+        assert_eq!(PanasonicClient::focus_speed_command(0.0), "F50");
+    }
+
+    #[test]
+    fn focus_mode_commands() {
+        assert_eq!(PanasonicClient::focus_mode_command(true), "D11");
+        assert_eq!(PanasonicClient::focus_mode_command(false), "D10");
+    }
+
+    #[test]
+    fn one_push_af_command() {
+        assert_eq!(PanasonicClient::one_push_af_command(), "OSE:69:1");
+    }
+
+    #[tokio::test]
+    async fn one_push_af_goes_to_the_camera_control_script() {
+        let requests = std::sync::Arc::new(std::sync::Mutex::new(Vec::new()));
+        let recorded = requests.clone();
+        let app = axum::Router::new().fallback(move |uri: axum::http::Uri| {
+            recorded.lock().unwrap().push(uri.to_string());
+            async { "OSE:69:1" }
+        });
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let port = listener.local_addr().unwrap().port();
+        tokio::spawn(async move { axum::serve(listener, app).await.unwrap() });
+
+        let client = PanasonicClient::new("127.0.0.1", port).unwrap();
+        client.autofocus_trigger().await.unwrap();
+        assert_eq!(
+            *requests.lock().unwrap(),
+            ["/cgi-bin/aw_cam?cmd=OSE%3A69%3A1&res=1"]
+        );
+    }
+
+    #[test]
+    fn iris_command_encoding() {
+        assert_eq!(PanasonicClient::iris_command(0.0).unwrap(), "AXI555");
+        assert_eq!(PanasonicClient::iris_command(1.0).unwrap(), "AXIFFF");
+        assert_eq!(PanasonicClient::iris_command(0.5).unwrap(), "AXIAAA");
+    }
+
+    #[test]
+    fn iris_command_rejects_out_of_range() {
+        assert!(matches!(
+            PanasonicClient::iris_command(1.5),
+            Err(PtzError::ProtocolError(_))
+        ));
+        assert!(PanasonicClient::iris_command(-0.1).is_err());
+        assert!(PanasonicClient::iris_command(f64::NAN).is_err());
+    }
+
+    #[test]
+    fn iris_mode_commands() {
+        assert_eq!(PanasonicClient::iris_mode_command(true), "D31");
+        assert_eq!(PanasonicClient::iris_mode_command(false), "D30");
+    }
+
+    #[test]
+    fn gain_command_encoding() {
+        assert_eq!(PanasonicClient::gain_command(0).unwrap(), "OGU:08");
+        assert_eq!(PanasonicClient::gain_command(12).unwrap(), "OGU:14");
+        assert_eq!(PanasonicClient::gain_command(48).unwrap(), "OGU:38");
+        assert!(matches!(
+            PanasonicClient::gain_command(49),
+            Err(PtzError::ProtocolError(_))
+        ));
+    }
+
+    #[test]
+    fn shutter_command_encoding() {
+        assert_eq!(PanasonicClient::shutter_command(0).unwrap(), "OSJ:03:0");
+        assert_eq!(PanasonicClient::shutter_command(11).unwrap(), "OSJ:03:B");
+        assert!(matches!(
+            PanasonicClient::shutter_command(12),
+            Err(PtzError::ProtocolError(_))
+        ));
+    }
+
+    #[test]
+    fn capabilities_include_focus_and_exposure() {
+        let caps = PanasonicClient::new("10.0.0.1", 80).unwrap().capabilities();
+        assert!(caps.supports_absolute && caps.supports_continuous);
+        assert!(caps.supports_focus && caps.supports_exposure);
+        assert!(!caps.supports_focus_position && !caps.supports_menu);
+        assert_eq!(caps.max_preset_slots, 100);
+    }
+
+    #[test]
+    fn model_id_is_parsed_from_qid_reply() {
+        assert_eq!(parse_model_id("OID:AW-UE150\r\n"), Some("AW-UE150".into()));
+        assert_eq!(parse_model_id("OID:"), None);
+        assert_eq!(parse_model_id("ER1:QID"), None);
+    }
+
+    #[test]
+    fn parses_software_version() {
+        assert_eq!(parse_software_version("OSV:1.42\r\n"), Some("1.42".into()));
+        assert_eq!(parse_software_version("OID:AW-UE150"), None);
+        assert_eq!(parse_software_version("OSV:"), None);
+    }
+
+    #[test]
+    fn encode_move_absolute_as_cgi_urls() {
+        let client = PanasonicClient::new("192.0.2.10", 80).unwrap();
+        let requests = client
+            .encode(&PtzCommand::MoveAbsolute {
+                pan: 0.0,
+                tilt: 0.0,
+                zoom: 0.0,
+            })
+            .unwrap();
+        let urls: Vec<_> = requests
+            .iter()
+            .map(|r| match r {
+                EncodedRequest::Http { method, url, body } => {
+                    assert_eq!(method, "GET");
+                    assert_eq!(body, &None);
+                    url.as_str()
+                }
+                other => panic!("unexpected request {:?}", other),
+            })
+            .collect();
+        assert_eq!(
+            urls,
+            [
+                "http://192.0.2.10/cgi-bin/aw_ptz?cmd=%23APS8000800030&res=1",
+                "http://192.0.2.10/cgi-bin/aw_ptz?cmd=%23Z555&res=1",
+            ]
+        );
+    }
 }
@@ -0,0 +1,301 @@
+use crate::ptz::cancel::CancelToken;
+use crate::ptz::controller::{PtzController, PtzError};
+use crate::ptz::nudge;
+use crate::ptz::timeout;
+use crate::ptz::types::{Capabilities, PtzPosition};
+use async_trait::async_trait;
+use std::sync::atomic::{AtomicI8, Ordering};
+use std::time::Duration;
+use tokio::io::AsyncWriteExt;
+use tokio::net::TcpStream;
+use tokio::sync::Mutex;
+
+use super::commands;
+
+/// How long to wait for the serial gateway to accept a connection.
+const CONNECT_TIMEOUT: Duration = Duration::from_secs(2);
+
+/// Pelco-D client for analog domes behind a serial-to-IP gateway.
+/// Pelco-D is fire-and-forget: the dome never replies, so a command
+/// succeeds once the gateway has accepted the bytes.
+pub struct PelcoClient {
+    stream: Mutex<Option<TcpStream>>,
+    host: String,
+    port: u16,
+    address: u8,
+    /// Running pan/tilt speeds and zoom direction, kept because one Pelco
+    /// message carries pan, tilt and zoom together.
+    pan_tilt: Mutex<(f64, f64)>,
+    zoom_dir: AtomicI8,
+    /// Cuts short the timed burst of a relative move.
+    cancel: CancelToken,
+}
+
+impl PelcoClient {
+    pub fn new(host: &str, port: u16, address: u8) -> Result<Self, PtzError> {
+        crate::ptz::controller::validate_address(host, port)?;
+        if address == 0 {
+            return Err(PtzError::InvalidConfig(
+                "Pelco-D address must be between 1 and 255".to_string(),
+            ));
+        }
+        Ok(Self {
+            stream: Mutex::new(None),
+            host: host.to_string(),
+            port,
+            address,
+            pan_tilt: Mutex::new((0.0, 0.0)),
+            zoom_dir: AtomicI8::new(0),
+            cancel: CancelToken::new(),
+        })
+    }
+
+    async fn connect_stream(&self) -> Result<TcpStream, PtzError> {
+        let addr = crate::ptz::types::host_with_port(&self.host, self.port);
+        match tokio::time::timeout(
+            timeout::or_default(CONNECT_TIMEOUT),
+            TcpStream::connect(&addr),
+        )
+        .await
+        {
+            Ok(Ok(stream)) => Ok(stream),
+            Ok(Err(e)) => Err(PtzError::ConnectionFailed(e.to_string())),
+            Err(_) => Err(PtzError::Timeout(format!("Connecting to {}", addr))),
+        }
+    }
+
+    /// Write one message, reconnecting once if the gateway dropped the connection.
+    async fn send(&self, message: &[u8; 7]) -> Result<(), PtzError> {
+        let mut stream = self.stream.lock().await;
+        if let Some(s) = stream.as_mut() {
+            if s.write_all(message).await.is_ok() {
+                return Ok(());
+            }
+            log::debug!("Pelco gateway connection lost, reconnecting");
+        }
+        let mut s = self.connect_stream().await?;
+        s.write_all(message)
+            .await
+            .map_err(|e| PtzError::CommandFailed(e.to_string()))?;
+        *stream = Some(s);
+        Ok(())
+    }
+
+    /// Send the combined pan/tilt/zoom state.
+    async fn send_motion(&self) -> Result<(), PtzError> {
+        let (pan, tilt) = *self.pan_tilt.lock().await;
+        let zoom = self.zoom_dir.load(Ordering::SeqCst) as f64;
+        self.send(&commands::pan_tilt_zoom(self.address, pan, tilt, zoom))
+            .await
+    }
+
+    fn no_absolute_positioning() -> PtzError {
+        PtzError::CommandFailed(
+            "Pelco-D has no absolute positioning; bind presets to camera slots instead".to_string(),
+        )
+    }
+}
+
+fn direction(speed: f64) -> i8 {
+    if speed > 0.01 {
+        1
+    } else if speed < -0.01 {
+        -1
+    } else {
+        0
+    }
+}
+
+#[async_trait]
+impl PtzController for PelcoClient {
+    async fn move_absolute(&self, _pan: f64, _tilt: f64, _zoom: f64) -> Result<(), PtzError> {
+        Err(Self::no_absolute_positioning())
+    }
+
+    async fn move_relative(&self, pan_delta: f64, tilt_delta: f64) -> Result<(), PtzError> {
+        if pan_delta.abs() < 0.01 && tilt_delta.abs() < 0.01 {
+            return Ok(());
+        }
+        // Brief movement then stop, as the dome cannot move by a fixed amount
+        self.continuous_move(pan_delta, tilt_delta).await?;
+        if !self
+            .cancel
+            .sleep(nudge::dwell_for(pan_delta, tilt_delta))
+            .await
+        {
+            log::debug!("Relative move cut short by a newer command");
+        }
+        self.continuous_move(0.0, 0.0).await
+    }
+
+    async fn zoom_to(&self, _zoom: f64) -> Result<(), PtzError> {
+        Err(Self::no_absolute_positioning())
+    }
+
+    async fn recall_preset(&self, preset_index: u8) -> Result<(), PtzError> {
+        self.send(&commands::preset_recall(self.address, preset_index))
+            .await
+    }
+
+    async fn store_preset(&self, preset_index: u8) -> Result<(), PtzError> {
+        self.send(&commands::preset_store(self.address, preset_index))
+            .await
+    }
+
+    async fn get_position(&self) -> Result<PtzPosition, PtzError> {
+        Err(Self::no_absolute_positioning())
+    }
+
+    async fn test_connection(&self) -> Result<(), PtzError> {
+        let stream = self.connect_stream().await?;
+        *self.stream.lock().await = Some(stream);
+        Ok(())
+    }
+
+    async fn home(&self) -> Result<(), PtzError> {
+        Err(Self::no_absolute_positioning())
+    }
+
+    async fn continuous_move(&self, pan_speed: f64, tilt_speed: f64) -> Result<(), PtzError> {
+        *self.pan_tilt.lock().await = (pan_speed, tilt_speed);
+        self.send_motion().await
+    }
+
+    async fn stop(&self) -> Result<(), PtzError> {
+        *self.pan_tilt.lock().await = (0.0, 0.0);
+        self.zoom_dir.store(0, Ordering::SeqCst);
+        self.send(&commands::stop(self.address)).await
+    }
+
+    async fn zoom_continuous(&self, speed: f64) -> Result<(), PtzError> {
+        let dir = direction(speed);
+        if dir != 0 {
+            self.send(&commands::set_zoom_speed(self.address, speed))
+                .await?;
+        }
+        self.zoom_dir.store(dir, Ordering::SeqCst);
+        self.send_motion().await
+    }
+
+    async fn focus_continuous(&self, speed: f64) -> Result<(), PtzError> {
+        self.send(&commands::focus(self.address, speed)).await
+    }
+
+    async fn focus_stop(&self) -> Result<(), PtzError> {
+        // A focus-only stop would also halt pan/tilt/zoom, so resend the motion state
+        self.send_motion().await
+    }
+
+    /// Pelco-D has no position feedback; presets 1-255 live on the camera.
+    fn capabilities(&self) -> Capabilities {
+        Capabilities {
+            supports_continuous: true,
+            supports_continuous_zoom: true,
+            supports_focus: true,
+            supports_native_presets: true,
+            max_preset_slots: 255,
+            ..Capabilities::default()
+        }
+    }
+
+    fn cancel_token(&self) -> Option<CancelToken> {
+        Some(self.cancel.clone())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tokio::io::AsyncReadExt;
+    use tokio::net::TcpListener;
+
+    /// Accept one gateway connection and collect `count` messages from it.
+    async fn spawn_gateway(count: usize) -> (u16, tokio::task::JoinHandle<Vec<[u8; 7]>>) {
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let port = listener.local_addr().unwrap().port();
+        let handle = tokio::spawn(async move {
+            let (mut socket, _) = listener.accept().await.unwrap();
+            let mut messages = Vec::new();
+            for _ in 0..count {
+                let mut buf = [0u8; 7];
+                socket.read_exact(&mut buf).await.unwrap();
+                messages.push(buf);
+            }
+            messages
+        });
+        (port, handle)
+    }
+
+    #[test]
+    fn address_zero_is_rejected() {
+        assert!(matches!(
+            PelcoClient::new("127.0.0.1", 4001, 0),
+            Err(PtzError::InvalidConfig(_))
+        ));
+    }
+
+    #[tokio::test]
+    async fn preset_recall_reaches_gateway() {
+        let (port, gateway) = spawn_gateway(1).await;
+        let client = PelcoClient::new("127.0.0.1", port, 1).unwrap();
+        client.recall_preset(1).await.unwrap();
+        assert_eq!(
+            gateway.await.unwrap(),
+            vec![[0xFF, 0x01, 0x00, 0x07, 0x00, 0x01, 0x09]]
+        );
+    }
+
+    #[tokio::test]
+    async fn zoom_keeps_running_pan() {
+        let (port, gateway) = spawn_gateway(3).await;
+        let client = PelcoClient::new("127.0.0.1", port, 1).unwrap();
+        client.continuous_move(0.5, 0.0).await.unwrap();
+        client.zoom_continuous(1.0).await.unwrap();
+        let messages = gateway.await.unwrap();
+        // Pan right at 0x20 plus zoom tele, after the zoom speed message
+        assert_eq!(messages[1], commands::set_zoom_speed(1, 1.0));
+        assert_eq!(messages[2], [0xFF, 0x01, 0x00, 0x22, 0x20, 0x00, 0x43]);
+    }
+
+    #[tokio::test]
+    async fn absolute_moves_are_unsupported() {
+        let client = PelcoClient::new("127.0.0.1", 4001, 1).unwrap();
+        assert!(client.move_absolute(0.0, 0.0, 0.0).await.is_err());
+        assert!(client.get_position().await.is_err());
+    }
+
+    #[test]
+    fn capabilities_exclude_absolute_moves() {
+        let caps = PelcoClient::new("127.0.0.1", 4001, 1)
+            .unwrap()
+            .capabilities();
+        assert!(!caps.supports_absolute);
+        assert!(caps.supports_continuous && caps.supports_continuous_zoom);
+        assert!(caps.supports_focus && caps.supports_native_presets);
+        assert_eq!(caps.max_preset_slots, 255);
+    }
+
+    #[tokio::test]
+    async fn newer_command_cuts_a_relative_move_short() {
+        let (port, gateway) = spawn_gateway(2).await;
+        let mut dispatcher = crate::ptz::controller::PtzDispatcher::new();
+        let client = PelcoClient::new("127.0.0.1", port, 1).unwrap();
+        dispatcher.add_controller("cam", Box::new(client));
+        dispatcher.set_active("cam").unwrap();
+
+        let started = std::time::Instant::now();
+        let nudge = async {
+            dispatcher.move_relative(0.5, 0.0).await.unwrap();
+            started.elapsed()
+        };
+        let stop = async {
+            tokio::time::sleep(Duration::from_millis(30)).await;
+            dispatcher.stop().await
+        };
+        let (elapsed, stopped) = tokio::join!(nudge, stop);
+        stopped.unwrap();
+
+        assert!(elapsed < Duration::from_millis(150), "took {:?}", elapsed);
+        assert_eq!(gateway.await.unwrap()[1], commands::stop(1));
+    }
+}
@@ -0,0 +1,164 @@
+//! Pelco-D command encoding for analog PTZ domes.
+//! Every message is 7 bytes: sync, address, cmd1, cmd2, data1, data2, checksum.
+
+/// First byte of every Pelco-D message.
+pub const SYNC: u8 = 0xFF;
+
+/// Highest variable pan/tilt speed; 0xFF is "turbo" on pan only.
+pub const MAX_SPEED: u8 = 0x3F;
+
+// cmd2 direction and zoom bits
+const PAN_RIGHT: u8 = 0x02;
+const PAN_LEFT: u8 = 0x04;
+const TILT_UP: u8 = 0x08;
+const TILT_DOWN: u8 = 0x10;
+const ZOOM_TELE: u8 = 0x20;
+const ZOOM_WIDE: u8 = 0x40;
+const FOCUS_FAR: u8 = 0x80;
+
+// cmd1 focus bit
+const FOCUS_NEAR: u8 = 0x01;
+
+// Extended commands (cmd2 with cmd1 = 0)
+const SET_PRESET: u8 = 0x03;
+const CALL_PRESET: u8 = 0x07;
+const SET_ZOOM_SPEED: u8 = 0x25;
+
+/// Checksum: sum of address through data2, modulo 256.
+pub fn checksum(body: &[u8]) -> u8 {
+    body.iter().fold(0u8, |sum, b| sum.wrapping_add(*b))
+}
+
+/// Build a full message with sync byte and checksum.
+pub fn message(address: u8, cmd1: u8, cmd2: u8, data1: u8, data2: u8) -> [u8; 7] {
+    let body = [address, cmd1, cmd2, data1, data2];
+    [SYNC, address, cmd1, cmd2, data1, data2, checksum(&body)]
+}
+
+/// Convert a normalized speed magnitude (0.0 to 1.0) to a Pelco speed (1 to 0x3F).
+pub fn normalize_to_speed(speed: f64) -> u8 {
+    ((speed.abs().min(1.0) * MAX_SPEED as f64).ceil() as u8).clamp(1, MAX_SPEED)
+}
+
+/// Continuous pan/tilt/zoom. Speeds are normalized -1.0..1.0; values within
+/// the dead zone stop that axis. Positive pan = right, tilt = up, zoom = tele.
+pub fn pan_tilt_zoom(address: u8, pan: f64, tilt: f64, zoom: f64) -> [u8; 7] {
+    const DEAD_ZONE: f64 = 0.01;
+    let mut cmd2 = 0;
+    let mut pan_speed = 0;
+    let mut tilt_speed = 0;
+    if pan > DEAD_ZONE {
+        cmd2 |= PAN_RIGHT;
+    } else if pan < -DEAD_ZONE {
+        cmd2 |= PAN_LEFT;
+    }
+    if cmd2 & (PAN_RIGHT | PAN_LEFT) != 0 {
+        pan_speed = normalize_to_speed(pan);
+    }
+    if tilt > DEAD_ZONE {
+        cmd2 |= TILT_UP;
+    } else if tilt < -DEAD_ZONE {
+        cmd2 |= TILT_DOWN;
+    }
+    if cmd2 & (TILT_UP | TILT_DOWN) != 0 {
+        tilt_speed = normalize_to_speed(tilt);
+    }
+    if zoom > DEAD_ZONE {
+        cmd2 |= ZOOM_TELE;
+    } else if zoom < -DEAD_ZONE {
+        cmd2 |= ZOOM_WIDE;
+    }
+    message(address, 0x00, cmd2, pan_speed, tilt_speed)
+}
+
+/// Stop all pan, tilt, zoom and focus motion.
+pub fn stop(address: u8) -> [u8; 7] {
+    message(address, 0x00, 0x00, 0x00, 0x00)
+}
+
+/// Continuous focus. Negative = near, positive = far, zero = stop.
+pub fn focus(address: u8, speed: f64) -> [u8; 7] {
+    if speed < 0.0 {
+        message(address, FOCUS_NEAR, 0x00, 0x00, 0x00)
+    } else if speed > 0.0 {
+        message(address, 0x00, FOCUS_FAR, 0x00, 0x00)
+    } else {
+        stop(address)
+    }
+}
+
+/// Zoom speed (0 = slowest to 3 = fastest) used by subsequent zoom commands.
+pub fn set_zoom_speed(address: u8, speed: f64) -> [u8; 7] {
+    let level = (speed.abs().min(1.0) * 3.0).round() as u8;
+    message(address, 0x00, SET_ZOOM_SPEED, 0x00, level)
+}
+
+/// Store the current position in a preset slot.
+pub fn preset_store(address: u8, preset: u8) -> [u8; 7] {
+    message(address, 0x00, SET_PRESET, 0x00, preset)
+}
+
+/// Move to a stored preset slot.
+pub fn preset_recall(address: u8, preset: u8) -> [u8; 7] {
+    message(address, 0x00, CALL_PRESET, 0x00, preset)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn checksum_wraps_modulo_256() {
+        assert_eq!(checksum(&[0x01, 0x00, 0x00, 0x00, 0x00]), 0x01);
+        assert_eq!(checksum(&[0xFF, 0x00, 0x02, 0x3F, 0x00]), 0x40);
+        assert_eq!(checksum(&[0x80, 0x80, 0x80, 0x80, 0x80]), 0x80);
+    }
+
+    #[test]
+    fn stop_frame() {
+        assert_eq!(stop(1), [0xFF, 0x01, 0x00, 0x00, 0x00, 0x00, 0x01]);
+    }
+
+    #[test]
+    fn pan_right_half_speed_frame() {
+        assert_eq!(
+            pan_tilt_zoom(1, 0.5, 0.0, 0.0),
+            [0xFF, 0x01, 0x00, 0x02, 0x20, 0x00, 0x23]
+        );
+    }
+
+    #[test]
+    fn tilt_down_and_zoom_wide_combine() {
+        assert_eq!(
+            pan_tilt_zoom(2, 0.0, -1.0, -0.5),
+            [0xFF, 0x02, 0x00, 0x50, 0x00, 0x3F, 0x91]
+        );
+    }
+
+    #[test]
+    fn preset_frames() {
+        assert_eq!(
+            preset_recall(1, 1),
+            [0xFF, 0x01, 0x00, 0x07, 0x00, 0x01, 0x09]
+        );
+        assert_eq!(
+            preset_store(1, 1),
+            [0xFF, 0x01, 0x00, 0x03, 0x00, 0x01, 0x05]
+        );
+    }
+
+    #[test]
+    fn focus_frames() {
+        assert_eq!(focus(1, -0.5), [0xFF, 0x01, 0x01, 0x00, 0x00, 0x00, 0x02]);
+        assert_eq!(focus(1, 0.5), [0xFF, 0x01, 0x00, 0x80, 0x00, 0x00, 0x81]);
+        assert_eq!(focus(1, 0.0), stop(1));
+    }
+
+    #[test]
+    fn speed_mapping_is_clamped() {
+        assert_eq!(normalize_to_speed(0.0), 1);
+        assert_eq!(normalize_to_speed(-1.0), MAX_SPEED);
+        assert_eq!(normalize_to_speed(5.0), MAX_SPEED);
+        assert_eq!(set_zoom_speed(1, 1.0)[5], 3);
+    }
+}
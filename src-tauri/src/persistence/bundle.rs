@@ -0,0 +1,322 @@
+use crate::persistence::config::AppConfig;
+use crate::ptz::types::{CameraEndpoint, PresetProfile, ProtocolConfig};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+
+/// Current bundle format version. Bump when the layout changes incompatibly.
+pub const BUNDLE_VERSION: u32 = 1;
+
+/// Settings that make sense to carry between rooms.
+/// Machine-specific state (active profile, video source) stays behind.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct BundleSettings {
+    pub click_sensitivity: f64,
+    pub scroll_sensitivity: f64,
+    pub overlay_opacity: f64,
+    pub camera_fov_degrees: f64,
+}
+
+impl BundleSettings {
+    pub fn from_config(config: &AppConfig) -> Self {
+        Self {
+            click_sensitivity: config.click_sensitivity,
+            scroll_sensitivity: config.scroll_sensitivity,
+            overlay_opacity: config.overlay_opacity,
+            camera_fov_degrees: config.camera_fov_degrees,
+        }
+    }
+
+    pub fn apply_to(&self, config: &mut AppConfig) {
+        config.click_sensitivity = self.click_sensitivity.clamp(0.01, 0.5);
+        config.scroll_sensitivity = self.scroll_sensitivity.clamp(0.01, 0.2);
+        config.overlay_opacity = self.overlay_opacity.clamp(0.1, 0.9);
+        config.camera_fov_degrees = self.camera_fov_degrees.clamp(10.0, 180.0);
+    }
+}
+
+/// Endpoints, profiles and settings exported as one shareable document.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ConfigBundle {
+    pub version: u32,
+    pub endpoints: Vec<CameraEndpoint>,
+    pub profiles: Vec<PresetProfile>,
+    #[serde(default)]
+    pub settings: Option<BundleSettings>,
+}
+
+impl ConfigBundle {
+    pub fn new(
+        endpoints: Vec<CameraEndpoint>,
+        profiles: Vec<PresetProfile>,
+        settings: Option<BundleSettings>,
+    ) -> Self {
+        Self {
+            version: BUNDLE_VERSION,
+            endpoints,
+            profiles,
+            settings,
+        }
+    }
+
+    /// Remove stored camera passwords, keeping usernames.
+    pub fn strip_passwords(&mut self) {
+        for endpoint in &mut self.endpoints {
+            match &mut endpoint.config {
+                ProtocolConfig::PanasonicAw { password, .. }
+                | ProtocolConfig::BirdDogRest { password, .. }
+                | ProtocolConfig::Onvif { password, .. } => *password = None,
+                ProtocolConfig::Ndi { .. }
+                | ProtocolConfig::Visca { .. }
+                | ProtocolConfig::PelcoD { .. }
+                | ProtocolConfig::Simulated => {}
+            }
+        }
+    }
+
+    pub fn to_json(&self) -> Result<String, String> {
+        serde_json::to_string_pretty(self).map_err(|e| e.to_string())
+    }
+
+    /// Parse a bundle, refusing versions newer than this build understands.
+    pub fn from_json(json: &str) -> Result<Self, String> {
+        let bundle: ConfigBundle =
+            serde_json::from_str(json).map_err(|e| format!("Invalid config bundle: {}", e))?;
+        if bundle.version > BUNDLE_VERSION {
+            return Err(format!(
+                "Config bundle version {} is newer than supported version {}",
+                bundle.version, BUNDLE_VERSION
+            ));
+        }
+        Ok(bundle)
+    }
+
+    /// Point imported profiles at the new ids of renamed endpoints.
+    pub fn remap_endpoint_ids(&mut self, renamed: &HashMap<String, String>) {
+        for profile in &mut self.profiles {
+            if let Some(new_id) = profile.endpoint_id.as_ref().and_then(|id| renamed.get(id)) {
+                profile.endpoint_id = Some(new_id.clone());
+            }
+        }
+    }
+}
+
+/// What to do when an imported item has the same id as an existing one.
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Default)]
+pub enum CollisionStrategy {
+    /// Keep the existing item and drop the imported one.
+    #[default]
+    Skip,
+    /// Replace the existing item with the imported one.
+    Overwrite,
+    /// Import under a fresh id alongside the existing item.
+    Rename,
+}
+
+/// Outcome of merging one kind of item.
+#[derive(Debug, Clone, Default, Serialize, Deserialize, PartialEq)]
+pub struct MergeCounts {
+    pub added: usize,
+    pub overwritten: usize,
+    pub renamed: usize,
+    pub skipped: usize,
+}
+
+/// Result of `import_config_bundle`.
+#[derive(Debug, Clone, Default, Serialize, Deserialize, PartialEq)]
+pub struct ImportSummary {
+    pub endpoints: MergeCounts,
+    pub profiles: MergeCounts,
+    pub settings_applied: bool,
+}
+
+/// Access to the id and display name of a bundled item.
+pub trait BundleItem {
+    fn id_mut(&mut self) -> &mut String;
+    fn name_mut(&mut self) -> &mut String;
+}
+
+impl BundleItem for CameraEndpoint {
+    fn id_mut(&mut self) -> &mut String {
+        &mut self.id
+    }
+    fn name_mut(&mut self) -> &mut String {
+        &mut self.name
+    }
+}
+
+impl BundleItem for PresetProfile {
+    fn id_mut(&mut self) -> &mut String {
+        &mut self.id
+    }
+    fn name_mut(&mut self) -> &mut String {
+        &mut self.name
+    }
+}
+
+/// Merge `incoming` into `existing` by id.
+/// Returns the counts and, for renamed items, a map from old id to new id.
+pub fn merge_items<T: BundleItem>(
+    existing: &mut Vec<T>,
+    incoming: Vec<T>,
+    strategy: CollisionStrategy,
+) -> (MergeCounts, HashMap<String, String>) {
+    let mut counts = MergeCounts::default();
+    let mut renamed = HashMap::new();
+    for mut item in incoming {
+        let id = item.id_mut().clone();
+        let pos = existing.iter_mut().position(|e| *e.id_mut() == id);
+        match (pos, strategy) {
+            (None, _) => {
+                existing.push(item);
+                counts.added += 1;
+            }
+            (Some(_), CollisionStrategy::Skip) => counts.skipped += 1,
+            (Some(pos), CollisionStrategy::Overwrite) => {
+                existing[pos] = item;
+                counts.overwritten += 1;
+            }
+            (Some(_), CollisionStrategy::Rename) => {
+                let new_id = uuid::Uuid::new_v4().to_string();
+                *item.id_mut() = new_id.clone();
+                item.name_mut().push_str(" (imported)");
+                existing.push(item);
+                renamed.insert(id, new_id);
+                counts.renamed += 1;
+            }
+        }
+    }
+    (counts, renamed)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ptz::geometry::LensCurve;
+    use crate::ptz::types::PtzProtocol;
+
+    fn endpoint(id: &str, name: &str) -> CameraEndpoint {
+        CameraEndpoint {
+            id: id.to_string(),
+            name: name.to_string(),
+            protocol: PtzProtocol::PanasonicAw,
+            config: ProtocolConfig::PanasonicAw {
+                host: "192.168.1.50".to_string(),
+                port: 80,
+                username: Some("admin".to_string()),
+                password: Some("secret".to_string()),
+                move_speed: None,
+                move_speed_max: None,
+                relative_mode: Default::default(),
+            },
+            color: None,
+            icon: None,
+        }
+    }
+
+    fn profile(id: &str, endpoint_id: Option<&str>) -> PresetProfile {
+        PresetProfile {
+            id: id.to_string(),
+            name: "Sunday".to_string(),
+            camera_fov_degrees: 60.0,
+            endpoint_id: endpoint_id.map(str::to_string),
+            presets: Vec::new(),
+            tour: None,
+            transition: None,
+            home_preset_id: None,
+            lens_curve: LensCurve::default(),
+            mechanical_range: None,
+            limits_degrees: None,
+        }
+    }
+
+    #[test]
+    fn bundle_roundtrips_through_json() {
+        let settings = BundleSettings::from_config(&AppConfig::default());
+        let bundle = ConfigBundle::new(
+            vec![endpoint("e1", "Cam 1")],
+            vec![profile("p1", Some("e1"))],
+            Some(settings.clone()),
+        );
+        let decoded = ConfigBundle::from_json(&bundle.to_json().unwrap()).unwrap();
+        assert_eq!(decoded.version, BUNDLE_VERSION);
+        assert_eq!(decoded.endpoints[0].name, "Cam 1");
+        assert_eq!(decoded.profiles[0].endpoint_id.as_deref(), Some("e1"));
+        assert_eq!(decoded.settings, Some(settings));
+    }
+
+    #[test]
+    fn newer_bundle_version_is_rejected() {
+        let mut bundle = ConfigBundle::new(Vec::new(), Vec::new(), None);
+        bundle.version = BUNDLE_VERSION + 1;
+        assert!(ConfigBundle::from_json(&bundle.to_json().unwrap()).is_err());
+        assert!(ConfigBundle::from_json("not json").is_err());
+    }
+
+    #[test]
+    fn strip_passwords_keeps_usernames() {
+        let mut bundle = ConfigBundle::new(vec![endpoint("e1", "Cam 1")], Vec::new(), None);
+        bundle.strip_passwords();
+        match &bundle.endpoints[0].config {
+            ProtocolConfig::PanasonicAw {
+                username, password, ..
+            } => {
+                assert_eq!(username.as_deref(), Some("admin"));
+                assert!(password.is_none());
+            }
+            other => panic!("unexpected config {:?}", other),
+        }
+    }
+
+    #[test]
+    fn skip_keeps_existing_items() {
+        let mut existing = vec![endpoint("e1", "Local")];
+        let (counts, renamed) = merge_items(
+            &mut existing,
+            vec![endpoint("e1", "Imported"), endpoint("e2", "New")],
+            CollisionStrategy::Skip,
+        );
+        assert_eq!(counts.added, 1);
+        assert_eq!(counts.skipped, 1);
+        assert!(renamed.is_empty());
+        assert_eq!(existing[0].name, "Local");
+        assert_eq!(existing.len(), 2);
+    }
+
+    #[test]
+    fn overwrite_replaces_existing_items() {
+        let mut existing = vec![endpoint("e1", "Local")];
+        let (counts, _) = merge_items(
+            &mut existing,
+            vec![endpoint("e1", "Imported")],
+            CollisionStrategy::Overwrite,
+        );
+        assert_eq!(counts.overwritten, 1);
+        assert_eq!(existing.len(), 1);
+        assert_eq!(existing[0].name, "Imported");
+    }
+
+    #[test]
+    fn rename_imports_alongside_and_remaps_profiles() {
+        let mut existing = vec![endpoint("e1", "Local")];
+        let mut bundle = ConfigBundle::new(
+            vec![endpoint("e1", "Cam 1")],
+            vec![profile("p1", Some("e1")), profile("p2", None)],
+            None,
+        );
+        let (counts, renamed) = merge_items(
+            &mut existing,
+            std::mem::take(&mut bundle.endpoints),
+            CollisionStrategy::Rename,
+        );
+        assert_eq!(counts.renamed, 1);
+        assert_eq!(existing.len(), 2);
+        let new_id = &renamed["e1"];
+        assert_ne!(new_id, "e1");
+        assert_eq!(existing[1].id, *new_id);
+        assert_eq!(existing[1].name, "Cam 1 (imported)");
+
+        bundle.remap_endpoint_ids(&renamed);
+        assert_eq!(bundle.profiles[0].endpoint_id.as_ref(), Some(new_id));
+        assert!(bundle.profiles[1].endpoint_id.is_none());
+    }
+}
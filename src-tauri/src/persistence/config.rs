@@ -1,3 +1,7 @@
+use crate::gamepad::mapping::GamepadSettings;
+use crate::persistence::save::{self, LoadWarning};
+use crate::ptz::nudge::NudgeDwell;
+use crate::ptz::response_curve::ResponseCurve;
 use serde::{Deserialize, Serialize};
 use std::path::{Path, PathBuf};
 
@@ -16,6 +20,39 @@ pub struct AppConfig {
     pub active_profile_id: Option<String>,
     /// Currently active video source.
     pub video_source: Option<VideoSourceConfig>,
+    /// How often the active camera's position is polled, in milliseconds.
+    #[serde(default = "default_position_poll_interval_ms")]
+    pub position_poll_interval_ms: u64,
+    /// Rotating profiles.json backups to keep; 0 disables them.
+    #[serde(default = "default_profile_backup_count")]
+    pub profile_backup_count: usize,
+    /// Gamepad deadzone and axis inversion.
+    #[serde(default)]
+    pub gamepad: GamepadSettings,
+    /// Fail PTZ commands when no endpoint is active instead of ignoring them.
+    #[serde(default)]
+    pub strict_commands: bool,
+    /// Unlock advanced tools such as raw protocol commands.
+    #[serde(default)]
+    pub developer_mode: bool,
+    /// Most jog commands sent to one camera per second; 0 removes the cap.
+    #[serde(default = "default_command_rate_limit")]
+    pub command_rate_limit: u32,
+    /// Shaping applied to pan, tilt and zoom jog velocities.
+    #[serde(default)]
+    pub response_curve: ResponseCurve,
+    /// How long discovered NDI sources are reused, in milliseconds; 0
+    /// discovers on every request.
+    #[serde(default = "default_ndi_source_cache_ttl_ms")]
+    pub ndi_source_cache_ttl_ms: u64,
+    /// How long a continuous move runs without a follow-up command or
+    /// keepalive before the camera is stopped, in milliseconds; 0 never stops.
+    #[serde(default = "default_deadman_window_ms")]
+    pub deadman_window_ms: u64,
+    /// How long relative moves drive cameras that can't move by a fixed
+    /// amount, scaled by the size of the move.
+    #[serde(default)]
+    pub nudge_dwell: NudgeDwell,
 
     #[serde(skip)]
     file_path: PathBuf,
@@ -29,6 +66,26 @@ pub enum VideoSourceConfig {
     MjpegFallback { device_path: String },
 }
 
+fn default_position_poll_interval_ms() -> u64 {
+    500
+}
+
+fn default_command_rate_limit() -> u32 {
+    crate::ptz::rate_limit::DEFAULT_COMMANDS_PER_SECOND
+}
+
+fn default_ndi_source_cache_ttl_ms() -> u64 {
+    crate::video::source_cache::DEFAULT_SOURCE_CACHE_TTL.as_millis() as u64
+}
+
+fn default_deadman_window_ms() -> u64 {
+    crate::ptz::deadman::DEFAULT_DEADMAN_WINDOW.as_millis() as u64
+}
+
+fn default_profile_backup_count() -> usize {
+    crate::persistence::profiles::DEFAULT_MAX_BACKUPS
+}
+
 impl Default for AppConfig {
     fn default() -> Self {
         Self {
@@ -38,6 +95,16 @@ impl Default for AppConfig {
             camera_fov_degrees: 60.0,
             active_profile_id: None,
             video_source: None,
+            position_poll_interval_ms: default_position_poll_interval_ms(),
+            profile_backup_count: default_profile_backup_count(),
+            gamepad: GamepadSettings::default(),
+            strict_commands: false,
+            developer_mode: false,
+            command_rate_limit: default_command_rate_limit(),
+            response_curve: ResponseCurve::default(),
+            ndi_source_cache_ttl_ms: default_ndi_source_cache_ttl_ms(),
+            deadman_window_ms: default_deadman_window_ms(),
+            nudge_dwell: NudgeDwell::default(),
             file_path: PathBuf::new(),
         }
     }
@@ -45,17 +112,58 @@ impl Default for AppConfig {
 
 impl AppConfig {
     pub fn load_or_default(data_dir: &Path) -> Self {
+        Self::load(data_dir).0
+    }
+
+    /// Load the settings, reporting a corrupt file that was set aside.
+    pub fn load(data_dir: &Path) -> (Self, Option<LoadWarning>) {
         let file_path = data_dir.join("config.json");
-        let mut config = if file_path.exists() {
-            std::fs::read_to_string(&file_path)
-                .ok()
-                .and_then(|s| serde_json::from_str::<AppConfig>(&s).ok())
-                .unwrap_or_default()
-        } else {
-            AppConfig::default()
-        };
+        let (mut config, warning) = save::load_json_or_preserve::<AppConfig>(&file_path);
         config.file_path = file_path;
-        config
+        (config, warning)
+    }
+
+    /// Restore every setting to its default. The active profile and video
+    /// source are kept unless `reset_selection` is set.
+    pub fn reset(&mut self, reset_selection: bool) {
+        let defaults = AppConfig {
+            file_path: std::mem::take(&mut self.file_path),
+            ..AppConfig::default()
+        };
+        let previous = std::mem::replace(self, defaults);
+        if !reset_selection {
+            self.active_profile_id = previous.active_profile_id;
+            self.video_source = previous.video_source;
+        }
+    }
+
+    /// Restore a single setting, named by its serialized field name, to its default.
+    pub fn reset_field(&mut self, field_name: &str) -> Result<(), String> {
+        let defaults = AppConfig::default();
+        match field_name {
+            "click_sensitivity" => self.click_sensitivity = defaults.click_sensitivity,
+            "scroll_sensitivity" => self.scroll_sensitivity = defaults.scroll_sensitivity,
+            "overlay_opacity" => self.overlay_opacity = defaults.overlay_opacity,
+            "camera_fov_degrees" => self.camera_fov_degrees = defaults.camera_fov_degrees,
+            "active_profile_id" => self.active_profile_id = defaults.active_profile_id,
+            "video_source" => self.video_source = defaults.video_source,
+            "position_poll_interval_ms" => {
+                self.position_poll_interval_ms = defaults.position_poll_interval_ms
+            }
+            "profile_backup_count" => self.profile_backup_count = defaults.profile_backup_count,
+            "gamepad" => self.gamepad = defaults.gamepad,
+            "strict_commands" => self.strict_commands = defaults.strict_commands,
+            "developer_mode" => self.developer_mode = defaults.developer_mode,
+            "command_rate_limit" => self.command_rate_limit = defaults.command_rate_limit,
+            "response_curve" => self.response_curve = defaults.response_curve,
+            "ndi_source_cache_ttl_ms" => {
+                self.ndi_source_cache_ttl_ms = defaults.ndi_source_cache_ttl_ms
+            }
+            "deadman_window_ms" => self.deadman_window_ms = defaults.deadman_window_ms,
+            "nudge_dwell" => self.nudge_dwell = defaults.nudge_dwell,
+            _ => return Err(format!("Unknown setting '{}'", field_name)),
+        }
+        Ok(())
     }
 
     pub fn save(&self) -> Result<(), String> {
@@ -110,13 +218,74 @@ mod tests {
         fs::remove_dir_all(&dir).ok();
     }
 
+    #[test]
+    fn reset_restores_defaults_and_keeps_selection() {
+        let dir = temp_dir();
+        let mut config = AppConfig::load_or_default(&dir);
+        config.click_sensitivity = 0.4;
+        config.overlay_opacity = 0.8;
+        config.active_profile_id = Some("prof-1".to_string());
+        config.video_source = Some(VideoSourceConfig::Ndi {
+            source_name: "Camera 1".to_string(),
+        });
+
+        config.reset(false);
+        assert_eq!(config.click_sensitivity, 0.1);
+        assert_eq!(config.overlay_opacity, 0.3);
+        assert_eq!(config.active_profile_id.as_deref(), Some("prof-1"));
+        assert!(config.video_source.is_some());
+
+        // The reset config still saves to the same file
+        config.save().unwrap();
+        assert_eq!(AppConfig::load_or_default(&dir).overlay_opacity, 0.3);
+
+        config.reset(true);
+        assert!(config.active_profile_id.is_none());
+        assert!(config.video_source.is_none());
+        fs::remove_dir_all(&dir).ok();
+    }
+
+    #[test]
+    fn reset_field_only_touches_that_field() {
+        let mut config = AppConfig {
+            click_sensitivity: 0.4,
+            overlay_opacity: 0.8,
+            ..AppConfig::default()
+        };
+        config.reset_field("click_sensitivity").unwrap();
+        assert_eq!(config.click_sensitivity, 0.1);
+        assert_eq!(config.overlay_opacity, 0.8);
+
+        config.reset_field("overlay_opacity").unwrap();
+        assert_eq!(config.overlay_opacity, 0.3);
+        assert!(config.reset_field("file_path").is_err());
+    }
+
+    #[test]
+    fn load_fills_missing_poll_interval() {
+        let dir = temp_dir();
+        fs::write(
+            dir.join("config.json"),
+            r#"{"click_sensitivity":0.2,"scroll_sensitivity":0.05,"overlay_opacity":0.3,"camera_fov_degrees":60.0,"active_profile_id":null,"video_source":null}"#,
+        )
+        .unwrap();
+        let config = AppConfig::load_or_default(&dir);
+        assert_eq!(config.click_sensitivity, 0.2);
+        assert_eq!(config.position_poll_interval_ms, 500);
+        assert_eq!(config.profile_backup_count, 5);
+        assert_eq!(config.command_rate_limit, 20);
+        fs::remove_dir_all(&dir).ok();
+    }
+
     #[test]
     fn load_ignores_corrupt_json() {
         let dir = temp_dir();
         fs::write(dir.join("config.json"), "not valid json!!!").unwrap();
-        let config = AppConfig::load_or_default(&dir);
-        // Should fall back to defaults
+        let (config, warning) = AppConfig::load(&dir);
+        // Should fall back to defaults, keeping the bad file aside
         assert_eq!(config.click_sensitivity, 0.1);
+        assert_eq!(warning.unwrap().file, "config.json");
+        assert!(!dir.join("config.json").exists());
         fs::remove_dir_all(&dir).ok();
     }
 
@@ -1,2 +1,6 @@
+pub mod bundle;
 pub mod config;
+pub mod positions;
 pub mod profiles;
+pub mod save;
+pub mod undo;
@@ -0,0 +1,185 @@
+use crate::persistence::save::{self, SaveQueue};
+use crate::ptz::types::PtzPosition;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize, PartialEq)]
+struct PositionFile {
+    positions: HashMap<String, PtzPosition>,
+}
+
+#[derive(Debug, Default)]
+struct Cached {
+    file: PositionFile,
+    /// Endpoint that command-driven position changes belong to.
+    active_id: Option<String>,
+}
+
+/// Last-known position of each endpoint, kept in `positions.json` so the
+/// overlay can show where a camera was left before its first position query.
+pub struct PositionCache {
+    cached: Mutex<Cached>,
+    file_path: PathBuf,
+    saves: SaveQueue,
+}
+
+impl PositionCache {
+    pub fn load_or_default(data_dir: &Path) -> Self {
+        let file_path = data_dir.join("positions.json");
+        let file = std::fs::read_to_string(&file_path)
+            .ok()
+            .and_then(|s| serde_json::from_str(&s).ok())
+            .unwrap_or_default();
+        Self {
+            cached: Mutex::new(Cached {
+                file,
+                active_id: None,
+            }),
+            file_path,
+            saves: SaveQueue::immediate(),
+        }
+    }
+
+    /// Hold saves in memory until `flush`, since positions change constantly
+    /// while a camera moves.
+    pub fn with_deferred_saves(mut self) -> Self {
+        self.saves = SaveQueue::deferred();
+        self
+    }
+
+    fn lock(&self) -> std::sync::MutexGuard<'_, Cached> {
+        self.cached.lock().unwrap_or_else(|e| e.into_inner())
+    }
+
+    /// Write out a deferred save, if one is pending. Returns whether it wrote.
+    pub fn flush(&self) -> Result<bool, String> {
+        self.saves.flush(|| self.write(&self.lock().file))
+    }
+
+    fn write(&self, file: &PositionFile) -> Result<(), String> {
+        let json = serde_json::to_string_pretty(file).map_err(|e| e.to_string())?;
+        save::write_atomic(&self.file_path, &json)
+    }
+
+    pub fn get(&self, id: &str) -> Option<PtzPosition> {
+        self.lock().file.positions.get(id).cloned()
+    }
+
+    /// Make `id` the endpoint that [`Self::record_active`] updates, and
+    /// return its last-known position.
+    pub fn activate(&self, id: &str) -> Option<PtzPosition> {
+        let mut cached = self.lock();
+        cached.active_id = Some(id.to_string());
+        cached.file.positions.get(id).cloned()
+    }
+
+    pub fn deactivate(&self) {
+        self.lock().active_id = None;
+    }
+
+    /// Remember an endpoint's position, saving only when it moved.
+    pub fn record(&self, id: &str, position: &PtzPosition) -> Result<(), String> {
+        let mut cached = self.lock();
+        if cached.file.positions.get(id) == Some(position) {
+            return Ok(());
+        }
+        cached
+            .file
+            .positions
+            .insert(id.to_string(), position.clone());
+        self.saves.save(|| self.write(&cached.file))
+    }
+
+    /// Remember the position of the active endpoint, if there is one.
+    pub fn record_active(&self, position: &PtzPosition) -> Result<(), String> {
+        let Some(id) = self.lock().active_id.clone() else {
+            return Ok(());
+        };
+        self.record(&id, position)
+    }
+
+    /// Forget a deleted endpoint's position.
+    pub fn remove(&self, id: &str) -> Result<(), String> {
+        let mut cached = self.lock();
+        if cached.active_id.as_deref() == Some(id) {
+            cached.active_id = None;
+        }
+        if cached.file.positions.remove(id).is_none() {
+            return Ok(());
+        }
+        self.saves.save(|| self.write(&cached.file))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::fs;
+
+    fn temp_dir() -> PathBuf {
+        let dir =
+            std::env::temp_dir().join(format!("ptzcam-test-positions-{}", uuid::Uuid::new_v4()));
+        fs::create_dir_all(&dir).unwrap();
+        dir
+    }
+
+    fn position(pan: f64, tilt: f64, zoom: f64) -> PtzPosition {
+        PtzPosition { pan, tilt, zoom }
+    }
+
+    #[test]
+    fn positions_survive_a_reload() {
+        let dir = temp_dir();
+        let cache = PositionCache::load_or_default(&dir);
+        cache.record("cam-a", &position(0.5, -0.25, 0.75)).unwrap();
+        cache.record("cam-b", &position(-1.0, 0.0, 0.1)).unwrap();
+
+        let reloaded = PositionCache::load_or_default(&dir);
+        assert_eq!(reloaded.get("cam-a"), Some(position(0.5, -0.25, 0.75)));
+        assert_eq!(reloaded.get("cam-b"), Some(position(-1.0, 0.0, 0.1)));
+        assert_eq!(reloaded.get("cam-c"), None);
+        fs::remove_dir_all(&dir).ok();
+    }
+
+    #[test]
+    fn active_endpoint_receives_recorded_positions() {
+        let dir = temp_dir();
+        let cache = PositionCache::load_or_default(&dir);
+        cache.record_active(&position(0.1, 0.1, 0.1)).unwrap();
+        assert_eq!(cache.get("cam-a"), None);
+
+        assert_eq!(cache.activate("cam-a"), None);
+        cache.record_active(&position(0.2, 0.0, 0.0)).unwrap();
+        cache.activate("cam-b");
+        assert_eq!(cache.activate("cam-a"), Some(position(0.2, 0.0, 0.0)));
+
+        cache.remove("cam-a").unwrap();
+        cache.record_active(&position(0.3, 0.0, 0.0)).unwrap();
+        assert_eq!(cache.get("cam-a"), None);
+        fs::remove_dir_all(&dir).ok();
+    }
+
+    #[test]
+    fn deferred_saves_wait_for_flush() {
+        let dir = temp_dir();
+        let cache = PositionCache::load_or_default(&dir).with_deferred_saves();
+        cache.record("cam", &position(0.5, 0.5, 0.5)).unwrap();
+        assert!(!dir.join("positions.json").exists());
+
+        assert!(cache.flush().unwrap());
+        assert!(!cache.flush().unwrap());
+        let reloaded = PositionCache::load_or_default(&dir);
+        assert_eq!(reloaded.get("cam"), Some(position(0.5, 0.5, 0.5)));
+        fs::remove_dir_all(&dir).ok();
+    }
+
+    #[test]
+    fn corrupt_file_starts_empty() {
+        let dir = temp_dir();
+        fs::write(dir.join("positions.json"), "not json").unwrap();
+        assert_eq!(PositionCache::load_or_default(&dir).get("cam"), None);
+        fs::remove_dir_all(&dir).ok();
+    }
+}
@@ -1,6 +1,15 @@
-use crate::ptz::types::{Preset, PresetProfile};
+use crate::persistence::bundle::{self, CollisionStrategy, MergeCounts};
+use crate::persistence::save::{self, LoadWarning, SaveQueue};
+use crate::persistence::undo::{PresetChange, UndoHistory, UndoState};
+use crate::ptz::geometry::LensCurve;
+use crate::ptz::types::{BatchFailure, BatchResult, Preset, PresetInput, PresetProfile};
 use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
 use std::path::{Path, PathBuf};
+use std::time::UNIX_EPOCH;
+
+/// Rotating backups kept unless configured otherwise.
+pub const DEFAULT_MAX_BACKUPS: usize = 5;
 
 #[derive(Debug, Clone, Default, Serialize, Deserialize)]
 struct ProfileData {
@@ -8,29 +17,160 @@ struct ProfileData {
     active_profile_id: Option<String>,
 }
 
+impl ProfileData {
+    /// The active profile, borrowed apart from the rest of the store.
+    fn active_profile_mut(&mut self) -> Result<&mut PresetProfile, String> {
+        let id = self
+            .active_profile_id
+            .as_deref()
+            .ok_or("No active profile")?;
+        self.profiles
+            .iter_mut()
+            .find(|p| p.id == id)
+            .ok_or_else(|| "No active profile".to_string())
+    }
+}
+
+/// A rotated copy of profiles.json; index 1 is the most recent.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct ProfileBackup {
+    pub index: usize,
+    /// Modification time in milliseconds since the Unix epoch.
+    pub modified_ms: u64,
+    pub profile_count: usize,
+}
+
 /// Manages preset profiles and their persistence.
+/// Every write first rotates the previous file into `profiles.json.bak.1..N`.
 pub struct ProfileStore {
     data: ProfileData,
     file_path: PathBuf,
+    max_backups: usize,
+    saves: SaveQueue,
+    /// Preset edits in the active profile; cleared when it changes.
+    history: UndoHistory,
 }
 
 impl ProfileStore {
     pub fn load_or_default(data_dir: &Path) -> Self {
+        Self::load(data_dir).0
+    }
+
+    /// Load the profiles, reporting a corrupt file that was set aside.
+    pub fn load(data_dir: &Path) -> (Self, Option<LoadWarning>) {
         let file_path = data_dir.join("profiles.json");
-        let data = if file_path.exists() {
-            std::fs::read_to_string(&file_path)
-                .ok()
-                .and_then(|s| serde_json::from_str(&s).ok())
-                .unwrap_or_default()
-        } else {
-            ProfileData::default()
+        let (data, warning) = save::load_json_or_preserve(&file_path);
+        let store = Self {
+            data,
+            file_path,
+            max_backups: DEFAULT_MAX_BACKUPS,
+            saves: SaveQueue::immediate(),
+            history: UndoHistory::default(),
         };
-        Self { data, file_path }
+        (store, warning)
+    }
+
+    /// Hold saves in memory until `flush`, so bursts of edits share one write.
+    pub fn with_deferred_saves(mut self) -> Self {
+        self.saves = SaveQueue::deferred();
+        self
     }
 
+    /// Set how many backups to keep; 0 disables them.
+    pub fn with_max_backups(mut self, max_backups: usize) -> Self {
+        self.max_backups = max_backups;
+        self
+    }
+
+    pub fn set_max_backups(&mut self, max_backups: usize) {
+        self.max_backups = max_backups;
+    }
+
+    /// Persist the profiles, or mark them dirty when saves are deferred.
     pub fn save(&self) -> Result<(), String> {
+        self.saves.save(|| self.write())
+    }
+
+    /// Write out a deferred save, if one is pending. Returns whether it wrote.
+    pub fn flush(&self) -> Result<bool, String> {
+        self.saves.flush(|| self.write())
+    }
+
+    pub fn is_dirty(&self) -> bool {
+        self.saves.is_dirty()
+    }
+
+    fn write(&self) -> Result<(), String> {
         let json = serde_json::to_string_pretty(&self.data).map_err(|e| e.to_string())?;
-        std::fs::write(&self.file_path, json).map_err(|e| e.to_string())
+        self.rotate_backups()?;
+        save::write_atomic(&self.file_path, &json)
+    }
+
+    // --- Backups ---
+
+    fn backup_path(&self, index: usize) -> PathBuf {
+        let mut name = self.file_path.as_os_str().to_owned();
+        name.push(format!(".bak.{}", index));
+        PathBuf::from(name)
+    }
+
+    /// Shift existing backups up one slot and copy the current file into slot 1.
+    /// Backups beyond `max_backups` are pruned.
+    fn rotate_backups(&self) -> Result<(), String> {
+        // Prune anything past the limit, including slots left over from a larger limit
+        let mut index = self.max_backups.max(1);
+        while self.backup_path(index).exists() {
+            std::fs::remove_file(self.backup_path(index)).map_err(|e| e.to_string())?;
+            index += 1;
+        }
+        if self.max_backups == 0 || !self.file_path.exists() {
+            return Ok(());
+        }
+        for index in (1..self.max_backups).rev() {
+            let from = self.backup_path(index);
+            if from.exists() {
+                std::fs::rename(&from, self.backup_path(index + 1)).map_err(|e| e.to_string())?;
+            }
+        }
+        std::fs::copy(&self.file_path, self.backup_path(1)).map_err(|e| e.to_string())?;
+        Ok(())
+    }
+
+    fn read_backup(&self, index: usize) -> Result<ProfileData, String> {
+        let json = std::fs::read_to_string(self.backup_path(index))
+            .map_err(|_| format!("Backup {} not found", index))?;
+        serde_json::from_str(&json).map_err(|e| format!("Backup {} is unreadable: {}", index, e))
+    }
+
+    /// Available backups, most recent first. Unreadable backups are skipped.
+    pub fn list_backups(&self) -> Vec<ProfileBackup> {
+        (1..=self.max_backups)
+            .filter_map(|index| {
+                let data = self.read_backup(index).ok()?;
+                let modified_ms = std::fs::metadata(self.backup_path(index))
+                    .and_then(|m| m.modified())
+                    .ok()
+                    .and_then(|t| t.duration_since(UNIX_EPOCH).ok())
+                    .map(|d| d.as_millis() as u64)
+                    .unwrap_or(0);
+                Some(ProfileBackup {
+                    index,
+                    modified_ms,
+                    profile_count: data.profiles.len(),
+                })
+            })
+            .collect()
+    }
+
+    /// Replace all profiles with a backup's contents.
+    /// The current state is itself backed up first, so a restore can be undone.
+    pub fn restore_backup(&mut self, index: usize) -> Result<(), String> {
+        if index == 0 || index > self.max_backups {
+            return Err(format!("Backup {} not found", index));
+        }
+        self.data = self.read_backup(index)?;
+        self.history.clear();
+        self.save()
     }
 
     // --- Profile operations ---
@@ -55,11 +195,17 @@ impl ProfileStore {
         if !self.data.profiles.iter().any(|p| p.id == id) {
             return Err("Profile not found".to_string());
         }
+        if self.data.active_profile_id.as_deref() != Some(id) {
+            self.history.clear();
+        }
         self.data.active_profile_id = Some(id.to_string());
         self.save()
     }
 
     pub fn create_profile(&mut self, profile: PresetProfile) -> Result<PresetProfile, String> {
+        profile.validate_home_preset()?;
+        profile.lens_curve.validate()?;
+        profile.validate_limits()?;
         self.data.profiles.push(profile.clone());
         if self.data.active_profile_id.is_none() {
             self.data.active_profile_id = Some(profile.id.clone());
@@ -69,15 +215,70 @@ impl ProfileStore {
     }
 
     pub fn save_profile(&mut self, profile: PresetProfile) -> Result<PresetProfile, String> {
+        profile.validate_home_preset()?;
+        profile.lens_curve.validate()?;
+        profile.validate_limits()?;
         if let Some(pos) = self.data.profiles.iter().position(|p| p.id == profile.id) {
             self.data.profiles[pos] = profile.clone();
         } else {
             self.data.profiles.push(profile.clone());
         }
+        if self.data.active_profile_id.as_deref() == Some(profile.id.as_str()) {
+            self.history.clear();
+        }
         self.save()?;
         Ok(profile)
     }
 
+    /// Merge profiles from a config bundle.
+    pub fn import(
+        &mut self,
+        profiles: Vec<PresetProfile>,
+        strategy: CollisionStrategy,
+    ) -> Result<MergeCounts, String> {
+        let (counts, _) = bundle::merge_items(&mut self.data.profiles, profiles, strategy);
+        self.history.clear();
+        if self.data.active_profile_id.is_none() {
+            self.data.active_profile_id = self.data.profiles.first().map(|p| p.id.clone());
+        }
+        self.save()?;
+        Ok(counts)
+    }
+
+    /// Copy a profile and all its presets under fresh ids. The copy is not activated.
+    pub fn duplicate_profile(&mut self, id: &str) -> Result<PresetProfile, String> {
+        let mut copy = self
+            .data
+            .profiles
+            .iter()
+            .find(|p| p.id == id)
+            .cloned()
+            .ok_or("Profile not found")?;
+        copy.id = uuid::Uuid::new_v4().to_string();
+        copy.name.push_str(" (copy)");
+
+        let mut new_ids = HashMap::new();
+        for preset in &mut copy.presets {
+            let new_id = uuid::Uuid::new_v4().to_string();
+            new_ids.insert(std::mem::replace(&mut preset.id, new_id.clone()), new_id);
+        }
+        // Keep the tour and home preset pointing at the copied presets
+        for step in copy.tour.iter_mut().flatten() {
+            if let Some(new_id) = new_ids.get(&step.preset_id) {
+                step.preset_id = new_id.clone();
+            }
+        }
+        if let Some(home) = &mut copy.home_preset_id {
+            if let Some(new_id) = new_ids.get(home) {
+                *home = new_id.clone();
+            }
+        }
+
+        self.data.profiles.push(copy.clone());
+        self.save()?;
+        Ok(copy)
+    }
+
     pub fn delete_profile(&mut self, id: &str) -> Result<(), String> {
         let pos = self
             .data
@@ -88,6 +289,7 @@ impl ProfileStore {
         self.data.profiles.remove(pos);
         if self.data.active_profile_id.as_deref() == Some(id) {
             self.data.active_profile_id = self.data.profiles.first().map(|p| p.id.clone());
+            self.history.clear();
         }
         self.save()
     }
@@ -100,25 +302,111 @@ impl ProfileStore {
             .unwrap_or_default()
     }
 
-    pub fn create_preset(&mut self, preset: Preset) -> Result<Preset, String> {
+    pub fn create_preset(&mut self, mut preset: Preset) -> Result<Preset, String> {
         let profile = self.get_active_profile_mut().ok_or("No active profile")?;
+        preset.hotkey = normalize_hotkey(preset.hotkey.as_deref());
+        check_hotkey_free(profile, &preset)?;
+        let index = profile.presets.len();
         profile.presets.push(preset.clone());
+        self.history.record(PresetChange::Created {
+            index,
+            preset: preset.clone(),
+            home: false,
+        });
         self.save()?;
         Ok(preset)
     }
 
-    pub fn update_preset(&mut self, preset: Preset) -> Result<Preset, String> {
+    pub fn update_preset(&mut self, mut preset: Preset) -> Result<Preset, String> {
         let profile = self.get_active_profile_mut().ok_or("No active profile")?;
+        preset.hotkey = normalize_hotkey(preset.hotkey.as_deref());
+        check_hotkey_free(profile, &preset)?;
         let pos = profile
             .presets
             .iter()
             .position(|p| p.id == preset.id)
             .ok_or("Preset not found")?;
-        profile.presets[pos] = preset.clone();
+        let before = std::mem::replace(&mut profile.presets[pos], preset.clone());
+        self.history.record(PresetChange::Updated {
+            before,
+            after: preset.clone(),
+        });
         self.save()?;
         Ok(preset)
     }
 
+    /// Create several presets in the active profile with a single save.
+    /// Invalid entries are reported by index and left out; the rest are kept.
+    pub fn create_presets(
+        &mut self,
+        inputs: Vec<PresetInput>,
+    ) -> Result<BatchResult<Preset>, String> {
+        let profile = self.get_active_profile_mut().ok_or("No active profile")?;
+        let mut result = BatchResult::new();
+        let mut changes = Vec::new();
+        for (index, input) in inputs.into_iter().enumerate() {
+            let created = input.into_preset().and_then(|mut preset| {
+                preset.hotkey = normalize_hotkey(preset.hotkey.as_deref());
+                check_hotkey_free(profile, &preset)?;
+                Ok(preset)
+            });
+            match created {
+                Ok(preset) => {
+                    changes.push(PresetChange::Created {
+                        index: profile.presets.len(),
+                        preset: preset.clone(),
+                        home: false,
+                    });
+                    profile.presets.push(preset.clone());
+                    result.succeeded.push(preset);
+                }
+                Err(error) => result.failed.push(BatchFailure { index, error }),
+            }
+        }
+        self.history.record(PresetChange::Batch(changes));
+        if !result.succeeded.is_empty() {
+            self.save()?;
+        }
+        Ok(result)
+    }
+
+    /// Delete several presets from the active profile with a single save.
+    /// Ids that aren't found are reported by index.
+    pub fn delete_presets(
+        &mut self,
+        preset_ids: Vec<String>,
+    ) -> Result<BatchResult<String>, String> {
+        let profile = self.get_active_profile_mut().ok_or("No active profile")?;
+        let mut result = BatchResult::new();
+        let mut changes = Vec::new();
+        for (index, preset_id) in preset_ids.into_iter().enumerate() {
+            match profile.presets.iter().position(|p| p.id == preset_id) {
+                Some(pos) => {
+                    let preset = profile.presets.remove(pos);
+                    let home = profile.home_preset_id.as_deref() == Some(preset_id.as_str());
+                    if home {
+                        profile.home_preset_id = None;
+                    }
+                    changes.push(PresetChange::Deleted {
+                        index: pos,
+                        preset,
+                        home,
+                    });
+                    result.succeeded.push(preset_id);
+                }
+                None => result.failed.push(BatchFailure {
+                    index,
+                    error: "Preset not found".to_string(),
+                }),
+            }
+        }
+        self.history.record(PresetChange::Batch(changes));
+        if !result.succeeded.is_empty() {
+            self.save()?;
+        }
+        Ok(result)
+    }
+
     pub fn delete_preset(&mut self, preset_id: &str) -> Result<(), String> {
         let profile = self.get_active_profile_mut().ok_or("No active profile")?;
         let pos = profile
@@ -126,15 +414,207 @@ impl ProfileStore {
             .iter()
             .position(|p| p.id == preset_id)
             .ok_or("Preset not found")?;
-        profile.presets.remove(pos);
+        let preset = profile.presets.remove(pos);
+        let home = profile.home_preset_id.as_deref() == Some(preset_id);
+        if home {
+            profile.home_preset_id = None;
+        }
+        self.history.record(PresetChange::Deleted {
+            index: pos,
+            preset,
+            home,
+        });
+        self.save()
+    }
+
+    /// Choose the active profile's home preset, or clear it with `None`.
+    pub fn set_home_preset(&mut self, preset_id: Option<&str>) -> Result<(), String> {
+        let profile = self.get_active_profile_mut().ok_or("No active profile")?;
+        let previous =
+            std::mem::replace(&mut profile.home_preset_id, preset_id.map(str::to_string));
+        if let Err(e) = profile.validate_home_preset() {
+            profile.home_preset_id = previous;
+            return Err(e);
+        }
+        self.save()
+    }
+
+    /// Bind a preset in the active profile to a camera preset slot.
+    pub fn set_native_slot(&mut self, preset_id: &str, slot: Option<u8>) -> Result<Preset, String> {
+        let profile = self.get_active_profile_mut().ok_or("No active profile")?;
+        let preset = profile
+            .presets
+            .iter_mut()
+            .find(|p| p.id == preset_id)
+            .ok_or("Preset not found")?;
+        let before = preset.clone();
+        preset.native_slot = slot;
+        let preset = preset.clone();
+        self.history.record(PresetChange::Updated {
+            before,
+            after: preset.clone(),
+        });
+        self.save()?;
+        Ok(preset)
+    }
+
+    /// Assign a keyboard shortcut to a preset in the active profile. A blank
+    /// or `None` hotkey clears it.
+    pub fn set_preset_hotkey(
+        &mut self,
+        preset_id: &str,
+        hotkey: Option<&str>,
+    ) -> Result<Preset, String> {
+        let mut preset = self.find_preset(preset_id).ok_or("Preset not found")?;
+        preset.hotkey = hotkey.map(str::to_string);
+        self.update_preset(preset)
+    }
+
+    /// Replace the thumbnail of a preset in the active profile.
+    pub fn set_preset_thumbnail(
+        &mut self,
+        preset_id: &str,
+        thumbnail: Option<String>,
+    ) -> Result<Preset, String> {
+        let mut preset = self.find_preset(preset_id).ok_or("Preset not found")?;
+        preset.thumbnail = thumbnail;
+        self.update_preset(preset)
+    }
+
+    /// The preset in the active profile bound to a keyboard shortcut.
+    pub fn resolve_hotkey(&self, key: &str) -> Option<String> {
+        let key = key.trim();
+        self.get_active_profile()?
+            .presets
+            .iter()
+            .find(|p| {
+                p.hotkey
+                    .as_deref()
+                    .is_some_and(|hotkey| hotkey.eq_ignore_ascii_case(key))
+            })
+            .map(|p| p.id.clone())
+    }
+
+    /// Copy a preset in the active profile, placing the copy right after it.
+    pub fn duplicate_preset(&mut self, preset_id: &str) -> Result<Preset, String> {
+        let profile = self.get_active_profile_mut().ok_or("No active profile")?;
+        let pos = profile
+            .presets
+            .iter()
+            .position(|p| p.id == preset_id)
+            .ok_or("Preset not found")?;
+        let mut copy = profile.presets[pos].clone();
+        copy.id = uuid::Uuid::new_v4().to_string();
+        copy.name.push_str(" (copy)");
+        profile.presets.insert(pos + 1, copy.clone());
+        self.history.record(PresetChange::Created {
+            index: pos + 1,
+            preset: copy.clone(),
+            home: false,
+        });
+        self.save()?;
+        Ok(copy)
+    }
+
+    /// Reorder a profile's presets to match `ordered_ids` exactly.
+    pub fn reorder_presets(
+        &mut self,
+        profile_id: &str,
+        ordered_ids: &[String],
+    ) -> Result<(), String> {
+        let profile = self
+            .data
+            .profiles
+            .iter_mut()
+            .find(|p| p.id == profile_id)
+            .ok_or("Profile not found")?;
+
+        let mut current: Vec<&str> = profile.presets.iter().map(|p| p.id.as_str()).collect();
+        let mut requested: Vec<&str> = ordered_ids.iter().map(String::as_str).collect();
+        current.sort_unstable();
+        requested.sort_unstable();
+        if current != requested {
+            return Err(
+                "Preset order must list every preset in the profile exactly once".to_string(),
+            );
+        }
+
+        let mut presets = std::mem::take(&mut profile.presets);
+        for id in ordered_ids {
+            let pos = presets
+                .iter()
+                .position(|p| p.id == *id)
+                .ok_or("Preset not found")?;
+            profile.presets.push(presets.remove(pos));
+        }
+        self.save()
+    }
+
+    /// Move one preset in the active profile to `new_index`.
+    pub fn move_preset(&mut self, preset_id: &str, new_index: usize) -> Result<(), String> {
+        let profile = self.get_active_profile_mut().ok_or("No active profile")?;
+        if new_index >= profile.presets.len() {
+            return Err(format!(
+                "Index {} is out of range for {} presets",
+                new_index,
+                profile.presets.len()
+            ));
+        }
+        let pos = profile
+            .presets
+            .iter()
+            .position(|p| p.id == preset_id)
+            .ok_or("Preset not found")?;
+        let preset = profile.presets.remove(pos);
+        profile.presets.insert(new_index, preset);
         self.save()
     }
 
+    /// Reverse the latest preset create, update or delete in the active
+    /// profile. Returns its presets afterwards.
+    pub fn undo_preset_change(&mut self) -> Result<Vec<Preset>, String> {
+        let profile = self.data.active_profile_mut()?;
+        self.history.undo(profile)?;
+        self.save()?;
+        Ok(self.get_presets())
+    }
+
+    /// Reapply the latest undone preset change in the active profile.
+    pub fn redo_preset_change(&mut self) -> Result<Vec<Preset>, String> {
+        let profile = self.data.active_profile_mut()?;
+        self.history.redo(profile)?;
+        self.save()?;
+        Ok(self.get_presets())
+    }
+
+    pub fn undo_state(&self) -> UndoState {
+        self.history.state()
+    }
+
     pub fn find_preset(&self, preset_id: &str) -> Option<Preset> {
         self.get_active_profile()
             .and_then(|p| p.presets.iter().find(|pr| pr.id == preset_id).cloned())
     }
 
+    /// Presets in the active profile whose name contains `query`, ignoring
+    /// case. A blank query matches every preset.
+    pub fn find_presets_by_name(&self, query: &str) -> Vec<Preset> {
+        let query = query.trim().to_lowercase();
+        self.get_presets()
+            .into_iter()
+            .filter(|p| p.name.to_lowercase().contains(&query))
+            .collect()
+    }
+
+    /// Presets in the active profile with the given color, ignoring case.
+    pub fn presets_by_color(&self, color: &str) -> Vec<Preset> {
+        let color = color.trim();
+        self.get_presets()
+            .into_iter()
+            .filter(|p| p.color.eq_ignore_ascii_case(color))
+            .collect()
+    }
+
     /// Ensure there is at least one profile. Creates a default if empty.
     pub fn ensure_default_profile(&mut self) -> Result<(), String> {
         if self.data.profiles.is_empty() {
@@ -144,6 +624,12 @@ impl ProfileStore {
                 camera_fov_degrees: 60.0,
                 endpoint_id: None,
                 presets: Vec::new(),
+                tour: None,
+                transition: None,
+                home_preset_id: None,
+                lens_curve: LensCurve::default(),
+                mechanical_range: None,
+                limits_degrees: None,
             };
             self.create_profile(profile)?;
         }
@@ -151,9 +637,37 @@ impl ProfileStore {
     }
 }
 
+/// Trim a hotkey, treating a blank one as none.
+fn normalize_hotkey(hotkey: Option<&str>) -> Option<String> {
+    hotkey
+        .map(str::trim)
+        .filter(|h| !h.is_empty())
+        .map(str::to_string)
+}
+
+/// Reject a preset whose hotkey another preset in the profile already uses.
+fn check_hotkey_free(profile: &PresetProfile, preset: &Preset) -> Result<(), String> {
+    let Some(hotkey) = preset.hotkey.as_deref() else {
+        return Ok(());
+    };
+    match profile.presets.iter().find(|p| {
+        p.id != preset.id
+            && p.hotkey
+                .as_deref()
+                .is_some_and(|other| other.eq_ignore_ascii_case(hotkey))
+    }) {
+        Some(other) => Err(format!(
+            "Hotkey '{}' is already used by preset '{}'",
+            hotkey, other.name
+        )),
+        None => Ok(()),
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::ptz::tour::TourStep;
     use std::fs;
 
     fn temp_dir() -> std::path::PathBuf {
@@ -163,6 +677,34 @@ mod tests {
         dir
     }
 
+    #[test]
+    fn corrupt_file_is_preserved_and_reported() {
+        let dir = temp_dir();
+        let mut store = ProfileStore::load_or_default(&dir);
+        store.create_profile(make_profile("p1", "Main")).unwrap();
+        let good = fs::read_to_string(dir.join("profiles.json")).unwrap();
+        fs::write(dir.join("profiles.json"), &good[..good.len() / 2]).unwrap();
+
+        let (store, warning) = ProfileStore::load(&dir);
+        assert!(store.get_profiles().is_empty());
+        let warning = warning.unwrap();
+        assert_eq!(warning.file, "profiles.json");
+        let preserved = warning.preserved_as.unwrap();
+        assert!(
+            preserved.contains("profiles.json.corrupt-"),
+            "{}",
+            preserved
+        );
+        assert_eq!(
+            fs::read_to_string(&preserved).unwrap(),
+            good[..good.len() / 2]
+        );
+
+        // A clean load has nothing to report
+        assert!(ProfileStore::load(&dir).1.is_none());
+        fs::remove_dir_all(&dir).ok();
+    }
+
     fn make_profile(id: &str, name: &str) -> PresetProfile {
         PresetProfile {
             id: id.to_string(),
@@ -170,6 +712,12 @@ mod tests {
             camera_fov_degrees: 60.0,
             endpoint_id: None,
             presets: Vec::new(),
+            tour: None,
+            transition: None,
+            home_preset_id: None,
+            lens_curve: LensCurve::default(),
+            mechanical_range: None,
+            limits_degrees: None,
         }
     }
 
@@ -181,6 +729,11 @@ mod tests {
             tilt: 0.0,
             zoom: 0.5,
             color: "#3b82f6".to_string(),
+            native_slot: None,
+            focus: None,
+            white_balance: None,
+            hotkey: None,
+            thumbnail: None,
         }
     }
 
@@ -370,6 +923,155 @@ mod tests {
         fs::remove_dir_all(&dir).ok();
     }
 
+    fn store_with_presets(dir: &Path, ids: &[&str]) -> ProfileStore {
+        let mut store = ProfileStore::load_or_default(dir);
+        store.create_profile(make_profile("p1", "Show")).unwrap();
+        for id in ids {
+            store.create_preset(make_preset(id, id)).unwrap();
+        }
+        store
+    }
+
+    fn preset_ids(store: &ProfileStore) -> Vec<String> {
+        store.get_presets().into_iter().map(|p| p.id).collect()
+    }
+
+    #[test]
+    fn reorder_presets_matches_given_order_and_persists() {
+        let dir = temp_dir();
+        let mut store = store_with_presets(&dir, &["a", "b", "c"]);
+        let order = vec!["c".to_string(), "a".to_string(), "b".to_string()];
+        store.reorder_presets("p1", &order).unwrap();
+        assert_eq!(preset_ids(&store), order);
+        assert_eq!(preset_ids(&ProfileStore::load_or_default(&dir)), order);
+        fs::remove_dir_all(&dir).ok();
+    }
+
+    #[test]
+    fn reorder_presets_rejects_mismatched_ids() {
+        let dir = temp_dir();
+        let mut store = store_with_presets(&dir, &["a", "b", "c"]);
+        let incomplete = vec!["c".to_string(), "a".to_string()];
+        assert!(store.reorder_presets("p1", &incomplete).is_err());
+        let duplicated = vec!["a".to_string(), "a".to_string(), "b".to_string()];
+        assert!(store.reorder_presets("p1", &duplicated).is_err());
+        assert!(store.reorder_presets("nope", &[]).is_err());
+        assert_eq!(preset_ids(&store), vec!["a", "b", "c"]);
+        fs::remove_dir_all(&dir).ok();
+    }
+
+    #[test]
+    fn move_preset_shifts_single_item() {
+        let dir = temp_dir();
+        let mut store = store_with_presets(&dir, &["a", "b", "c"]);
+        store.move_preset("a", 2).unwrap();
+        assert_eq!(preset_ids(&store), vec!["b", "c", "a"]);
+        store.move_preset("a", 0).unwrap();
+        assert_eq!(preset_ids(&store), vec!["a", "b", "c"]);
+        fs::remove_dir_all(&dir).ok();
+    }
+
+    #[test]
+    fn move_preset_rejects_out_of_range_index() {
+        let dir = temp_dir();
+        let mut store = store_with_presets(&dir, &["a", "b"]);
+        assert!(store.move_preset("a", 2).is_err());
+        assert!(store.move_preset("missing", 0).is_err());
+        assert_eq!(preset_ids(&store), vec!["a", "b"]);
+        fs::remove_dir_all(&dir).ok();
+    }
+
+    #[test]
+    fn set_native_slot_binds_and_unbinds() {
+        let dir = temp_dir();
+        let mut store = store_with_presets(&dir, &["a"]);
+        assert_eq!(
+            store.set_native_slot("a", Some(7)).unwrap().native_slot,
+            Some(7)
+        );
+        let reloaded = ProfileStore::load_or_default(&dir);
+        assert_eq!(reloaded.find_preset("a").unwrap().native_slot, Some(7));
+        store.set_native_slot("a", None).unwrap();
+        assert_eq!(store.find_preset("a").unwrap().native_slot, None);
+        assert!(store.set_native_slot("missing", Some(1)).is_err());
+        fs::remove_dir_all(&dir).ok();
+    }
+
+    #[test]
+    fn duplicate_preset_gets_fresh_id_and_same_position() {
+        let dir = temp_dir();
+        let mut store = store_with_presets(&dir, &["a", "b"]);
+        let mut original = make_preset("a", "a");
+        original.pan = 0.25;
+        original.tilt = -0.5;
+        original.color = "#ff0000".to_string();
+        store.update_preset(original).unwrap();
+
+        let copy = store.duplicate_preset("a").unwrap();
+        assert_ne!(copy.id, "a");
+        assert!(uuid::Uuid::parse_str(&copy.id).is_ok());
+        assert_eq!(copy.name, "a (copy)");
+        assert_eq!((copy.pan, copy.tilt, copy.zoom), (0.25, -0.5, 0.5));
+        assert_eq!(copy.color, "#ff0000");
+        assert_eq!(
+            preset_ids(&store),
+            vec!["a".to_string(), copy.id, "b".to_string()]
+        );
+        fs::remove_dir_all(&dir).ok();
+    }
+
+    #[test]
+    fn duplicate_preset_requires_existing_preset() {
+        let dir = temp_dir();
+        let mut store = ProfileStore::load_or_default(&dir);
+        assert!(store.duplicate_preset("a").is_err());
+        store.ensure_default_profile().unwrap();
+        assert!(store.duplicate_preset("a").is_err());
+        fs::remove_dir_all(&dir).ok();
+    }
+
+    #[test]
+    fn duplicate_profile_copies_presets_with_fresh_ids() {
+        let dir = temp_dir();
+        let mut store = store_with_presets(&dir, &["a", "b"]);
+        let mut profile = store.get_active_profile().unwrap().clone();
+        profile.tour = Some(vec![TourStep {
+            preset_id: "b".to_string(),
+            dwell_ms: 1000,
+        }]);
+        store.save_profile(profile).unwrap();
+
+        let copy = store.duplicate_profile("p1").unwrap();
+        assert_ne!(copy.id, "p1");
+        assert_eq!(copy.name, "Show (copy)");
+        assert_eq!(copy.presets.len(), 2);
+        let original = store.get_presets();
+        for (orig, dup) in original.iter().zip(&copy.presets) {
+            assert_ne!(orig.id, dup.id);
+            assert!(uuid::Uuid::parse_str(&dup.id).is_ok());
+            assert_eq!(orig.name, dup.name);
+            assert_eq!(
+                (orig.pan, orig.tilt, orig.zoom),
+                (dup.pan, dup.tilt, dup.zoom)
+            );
+            assert_eq!(orig.color, dup.color);
+        }
+        assert_eq!(copy.tour.unwrap()[0].preset_id, copy.presets[1].id);
+        // The original stays active
+        assert_eq!(store.get_active_profile().unwrap().id, "p1");
+        assert_eq!(store.get_profiles().len(), 2);
+        fs::remove_dir_all(&dir).ok();
+    }
+
+    #[test]
+    fn duplicate_profile_in_empty_store_is_an_error() {
+        let dir = temp_dir();
+        let mut store = ProfileStore::load_or_default(&dir);
+        assert!(store.duplicate_profile("p1").is_err());
+        assert!(store.get_profiles().is_empty());
+        fs::remove_dir_all(&dir).ok();
+    }
+
     #[test]
     fn save_and_reload_preserves_data() {
         let dir = temp_dir();
@@ -411,4 +1113,426 @@ mod tests {
         assert_eq!(store.get_profiles().len(), 2);
         fs::remove_dir_all(&dir).ok();
     }
+
+    #[test]
+    fn import_into_empty_store_activates_first_profile() {
+        let dir = temp_dir();
+        let mut store = ProfileStore::load_or_default(&dir);
+        let counts = store
+            .import(
+                vec![make_profile("p1", "A"), make_profile("p2", "B")],
+                CollisionStrategy::Skip,
+            )
+            .unwrap();
+        assert_eq!(counts.added, 2);
+        assert_eq!(store.get_active_profile().unwrap().id, "p1");
+        let reloaded = ProfileStore::load_or_default(&dir);
+        assert_eq!(reloaded.get_profiles().len(), 2);
+        fs::remove_dir_all(&dir).ok();
+    }
+
+    // --- Backups ---
+
+    #[test]
+    fn first_save_has_nothing_to_back_up() {
+        let dir = temp_dir();
+        let mut store = ProfileStore::load_or_default(&dir);
+        store.create_profile(make_profile("p1", "First")).unwrap();
+        assert!(store.list_backups().is_empty());
+        fs::remove_dir_all(&dir).ok();
+    }
+
+    #[test]
+    fn restore_backup_recovers_deleted_profile() {
+        let dir = temp_dir();
+        let mut store = ProfileStore::load_or_default(&dir);
+        store.create_profile(make_profile("p1", "Sunday")).unwrap();
+        store.create_profile(make_profile("p2", "Wedding")).unwrap();
+        store.delete_profile("p1").unwrap();
+        store.delete_profile("p2").unwrap();
+
+        // bak.1 = [p2], bak.2 = [p1, p2], bak.3 = [p1]
+        let backups = store.list_backups();
+        let counts: Vec<usize> = backups.iter().map(|b| b.profile_count).collect();
+        assert_eq!(counts, vec![1, 2, 1]);
+
+        store.restore_backup(2).unwrap();
+        let names: Vec<String> = store.get_profiles().into_iter().map(|p| p.name).collect();
+        assert_eq!(names, vec!["Sunday", "Wedding"]);
+        // The restore survives a reload
+        let reloaded = ProfileStore::load_or_default(&dir);
+        assert_eq!(reloaded.get_profiles().len(), 2);
+        // The empty state we restored over was itself backed up
+        assert_eq!(store.list_backups()[0].profile_count, 0);
+        fs::remove_dir_all(&dir).ok();
+    }
+
+    #[test]
+    fn backups_are_pruned_to_limit() {
+        let dir = temp_dir();
+        let mut store = ProfileStore::load_or_default(&dir).with_max_backups(2);
+        for i in 0..5 {
+            store
+                .create_profile(make_profile(&format!("p{}", i), "P"))
+                .unwrap();
+        }
+        let backups = store.list_backups();
+        assert_eq!(backups.len(), 2);
+        assert_eq!(backups[0].profile_count, 4);
+        assert_eq!(backups[1].profile_count, 3);
+        assert!(!dir.join("profiles.json.bak.3").exists());
+
+        // Lowering the limit prunes on the next save
+        store.set_max_backups(1);
+        store.save().unwrap();
+        assert_eq!(store.list_backups().len(), 1);
+        assert!(!dir.join("profiles.json.bak.2").exists());
+        fs::remove_dir_all(&dir).ok();
+    }
+
+    #[test]
+    fn zero_backups_disables_rotation() {
+        let dir = temp_dir();
+        let mut store = ProfileStore::load_or_default(&dir).with_max_backups(0);
+        store.create_profile(make_profile("p1", "First")).unwrap();
+        store.save().unwrap();
+        assert!(!dir.join("profiles.json.bak.1").exists());
+        fs::remove_dir_all(&dir).ok();
+    }
+
+    #[test]
+    fn restore_missing_backup_is_an_error() {
+        let dir = temp_dir();
+        let mut store = ProfileStore::load_or_default(&dir);
+        store.create_profile(make_profile("p1", "First")).unwrap();
+        assert!(store.restore_backup(1).is_err());
+        assert!(store.restore_backup(0).is_err());
+        assert_eq!(store.get_profiles().len(), 1);
+        fs::remove_dir_all(&dir).ok();
+    }
+
+    fn store_for_search(dir: &Path) -> ProfileStore {
+        let mut store = ProfileStore::load_or_default(dir);
+        store.create_profile(make_profile("p1", "First")).unwrap();
+        for (id, name, color) in [
+            ("a", "Pulpit Wide", "#3b82f6"),
+            ("b", "pulpit close", "#EF4444"),
+            ("c", "Choir", "#ef4444"),
+        ] {
+            let mut preset = make_preset(id, name);
+            preset.color = color.to_string();
+            store.create_preset(preset).unwrap();
+        }
+        store
+    }
+
+    #[test]
+    fn find_presets_by_name_ignores_case() {
+        let dir = temp_dir();
+        let store = store_for_search(&dir);
+        let ids: Vec<String> = store
+            .find_presets_by_name("PULPIT")
+            .into_iter()
+            .map(|p| p.id)
+            .collect();
+        assert_eq!(ids, vec!["a", "b"]);
+        assert!(store.find_presets_by_name("balcony").is_empty());
+        fs::remove_dir_all(&dir).ok();
+    }
+
+    #[test]
+    fn empty_query_returns_all_presets() {
+        let dir = temp_dir();
+        let store = store_for_search(&dir);
+        assert_eq!(store.find_presets_by_name("").len(), 3);
+        assert_eq!(store.find_presets_by_name("  ").len(), 3);
+        fs::remove_dir_all(&dir).ok();
+    }
+
+    #[test]
+    fn presets_by_color_matches_hex_case_insensitively() {
+        let dir = temp_dir();
+        let store = store_for_search(&dir);
+        let ids: Vec<String> = store
+            .presets_by_color("#ef4444")
+            .into_iter()
+            .map(|p| p.id)
+            .collect();
+        assert_eq!(ids, vec!["b", "c"]);
+        assert!(store.presets_by_color("#000000").is_empty());
+        fs::remove_dir_all(&dir).ok();
+    }
+
+    #[test]
+    fn conflicting_hotkey_is_rejected() {
+        let dir = temp_dir();
+        let mut store = store_for_search(&dir);
+        store.set_preset_hotkey("a", Some("Ctrl+1")).unwrap();
+
+        let err = store.set_preset_hotkey("b", Some("ctrl+1")).unwrap_err();
+        assert!(err.contains("Pulpit Wide"), "{err}");
+        assert!(store.find_preset("b").unwrap().hotkey.is_none());
+
+        // Re-assigning a preset's own key is not a conflict
+        store.set_preset_hotkey("a", Some("CTRL+1")).unwrap();
+
+        let mut duplicate = make_preset("d", "Balcony");
+        duplicate.hotkey = Some("Ctrl+1".to_string());
+        assert!(store.create_preset(duplicate).is_err());
+        fs::remove_dir_all(&dir).ok();
+    }
+
+    #[test]
+    fn hotkey_resolves_to_preset() {
+        let dir = temp_dir();
+        let mut store = store_for_search(&dir);
+        store.set_preset_hotkey("c", Some(" F1 ")).unwrap();
+        assert_eq!(
+            store.find_preset("c").unwrap().hotkey.as_deref(),
+            Some("F1")
+        );
+        assert_eq!(store.resolve_hotkey("f1").as_deref(), Some("c"));
+        assert_eq!(store.resolve_hotkey("F2"), None);
+
+        store.set_preset_hotkey("c", Some("")).unwrap();
+        assert_eq!(store.resolve_hotkey("F1"), None);
+        fs::remove_dir_all(&dir).ok();
+    }
+
+    #[test]
+    fn thumbnail_is_replaced_and_saved() {
+        let dir = temp_dir();
+        let mut store = store_for_search(&dir);
+        let updated = store
+            .set_preset_thumbnail("c", Some("AAAA".to_string()))
+            .unwrap();
+        assert_eq!(updated.thumbnail.as_deref(), Some("AAAA"));
+
+        let reloaded = ProfileStore::load_or_default(&dir);
+        assert_eq!(
+            reloaded.find_preset("c").unwrap().thumbnail.as_deref(),
+            Some("AAAA")
+        );
+        assert!(store.set_preset_thumbnail("missing", None).is_err());
+        fs::remove_dir_all(&dir).ok();
+    }
+
+    #[test]
+    fn home_preset_must_exist_and_clears_on_delete() {
+        let dir = temp_dir();
+        let mut store = store_for_search(&dir);
+        assert!(store.set_home_preset(Some("missing")).is_err());
+        assert!(store.get_active_profile().unwrap().home_preset_id.is_none());
+
+        store.set_home_preset(Some("c")).unwrap();
+        assert_eq!(
+            store
+                .get_active_profile()
+                .unwrap()
+                .home_preset()
+                .unwrap()
+                .name,
+            "Choir"
+        );
+
+        let mut profile = store.get_active_profile().unwrap().clone();
+        profile.home_preset_id = Some("missing".to_string());
+        assert!(store.save_profile(profile).is_err());
+
+        store.delete_preset("c").unwrap();
+        assert!(store.get_active_profile().unwrap().home_preset_id.is_none());
+        fs::remove_dir_all(&dir).ok();
+    }
+
+    fn preset_input(name: &str) -> PresetInput {
+        PresetInput {
+            name: name.to_string(),
+            pan: 0.2,
+            tilt: -0.1,
+            zoom: 0.4,
+            color: "#22c55e".to_string(),
+            hotkey: None,
+        }
+    }
+
+    #[test]
+    fn create_presets_saves_valid_entries_and_reports_the_rest() {
+        let dir = temp_dir();
+        let mut store = ProfileStore::load_or_default(&dir);
+        store.create_profile(make_profile("p1", "Main")).unwrap();
+
+        let inputs = vec![
+            preset_input("Pulpit"),
+            preset_input("   "),
+            PresetInput {
+                pan: f64::NAN,
+                ..preset_input("Broken")
+            },
+            preset_input("Choir"),
+        ];
+        let result = store.create_presets(inputs).unwrap();
+        let names: Vec<_> = result.succeeded.iter().map(|p| p.name.as_str()).collect();
+        assert_eq!(names, ["Pulpit", "Choir"]);
+        let failed: Vec<_> = result.failed.iter().map(|f| f.index).collect();
+        assert_eq!(failed, [1, 2]);
+
+        let reloaded = ProfileStore::load_or_default(&dir);
+        let names: Vec<_> = reloaded.get_presets().into_iter().map(|p| p.name).collect();
+        assert_eq!(names, ["Pulpit", "Choir"]);
+        fs::remove_dir_all(&dir).ok();
+    }
+
+    #[test]
+    fn create_presets_checks_hotkeys_within_the_batch() {
+        let dir = temp_dir();
+        let mut store = ProfileStore::load_or_default(&dir);
+        store.create_profile(make_profile("p1", "Main")).unwrap();
+
+        let with_key = |name: &str, key: &str| PresetInput {
+            hotkey: Some(key.to_string()),
+            ..preset_input(name)
+        };
+        let result = store
+            .create_presets(vec![with_key("A", "F1"), with_key("B", "f1")])
+            .unwrap();
+        assert_eq!(result.succeeded.len(), 1);
+        assert_eq!(result.failed[0].index, 1);
+        assert!(result.failed[0].error.contains("preset 'A'"));
+        fs::remove_dir_all(&dir).ok();
+    }
+
+    #[test]
+    fn delete_presets_removes_found_ids_and_reports_missing() {
+        let dir = temp_dir();
+        let mut store = ProfileStore::load_or_default(&dir);
+        let mut profile = make_profile("p1", "Main");
+        profile.presets = vec![
+            make_preset("a", "A"),
+            make_preset("b", "B"),
+            make_preset("c", "C"),
+        ];
+        profile.home_preset_id = Some("a".to_string());
+        store.create_profile(profile).unwrap();
+
+        let result = store
+            .delete_presets(vec!["a".into(), "missing".into(), "c".into()])
+            .unwrap();
+        assert_eq!(result.succeeded, ["a", "c"]);
+        assert_eq!(
+            result.failed,
+            [BatchFailure {
+                index: 1,
+                error: "Preset not found".to_string()
+            }]
+        );
+
+        let reloaded = ProfileStore::load_or_default(&dir);
+        let profile = reloaded.get_active_profile().unwrap();
+        let ids: Vec<_> = profile.presets.iter().map(|p| p.id.as_str()).collect();
+        assert_eq!(ids, ["b"]);
+        assert_eq!(profile.home_preset_id, None);
+        fs::remove_dir_all(&dir).ok();
+    }
+
+    #[test]
+    fn deferred_saves_write_once_on_flush() {
+        let dir = temp_dir();
+        let mut store = ProfileStore::load_or_default(&dir).with_deferred_saves();
+        store.create_profile(make_profile("p1", "Main")).unwrap();
+        for i in 0..5 {
+            store
+                .create_preset(make_preset(&format!("s{i}"), &format!("Shot {i}")))
+                .unwrap();
+        }
+        assert!(store.is_dirty());
+        assert!(!dir.join("profiles.json").exists());
+
+        assert!(store.flush().unwrap());
+        assert!(!store.flush().unwrap());
+        let reloaded = ProfileStore::load_or_default(&dir);
+        assert_eq!(reloaded.get_presets().len(), 5);
+        // A single write had no earlier file to back up
+        assert!(reloaded.list_backups().is_empty());
+        fs::remove_dir_all(&dir).ok();
+    }
+
+    #[test]
+    fn undo_create_removes_the_preset() {
+        let dir = temp_dir();
+        let mut store = store_with_presets(&dir, &["a"]);
+        store.create_preset(make_preset("b", "Wide")).unwrap();
+
+        let presets = store.undo_preset_change().unwrap();
+        assert_eq!(presets.len(), 1);
+        assert!(store.find_preset("b").is_none());
+
+        store.redo_preset_change().unwrap();
+        assert!(store.find_preset("b").is_some());
+        fs::remove_dir_all(&dir).ok();
+    }
+
+    #[test]
+    fn undo_update_restores_old_values() {
+        let dir = temp_dir();
+        let mut store = store_with_presets(&dir, &["wide"]);
+        let mut overwritten = make_preset("wide", "Ceiling");
+        overwritten.pan = 0.9;
+        overwritten.tilt = 1.0;
+        store.update_preset(overwritten).unwrap();
+
+        store.undo_preset_change().unwrap();
+        let restored = store.find_preset("wide").unwrap();
+        assert_eq!(restored.name, "wide");
+        assert_eq!((restored.pan, restored.tilt), (0.0, 0.0));
+
+        // The restore is saved
+        let reloaded = ProfileStore::load_or_default(&dir);
+        assert_eq!(reloaded.find_preset("wide").unwrap().name, "wide");
+        fs::remove_dir_all(&dir).ok();
+    }
+
+    #[test]
+    fn undo_delete_restores_the_preset_in_place() {
+        let dir = temp_dir();
+        let mut store = store_with_presets(&dir, &["a", "b", "c"]);
+        store.set_home_preset(Some("b")).unwrap();
+        store.delete_preset("b").unwrap();
+
+        store.undo_preset_change().unwrap();
+        assert_eq!(preset_ids(&store), ["a", "b", "c"]);
+        assert_eq!(
+            store
+                .get_active_profile()
+                .unwrap()
+                .home_preset_id
+                .as_deref(),
+            Some("b")
+        );
+        fs::remove_dir_all(&dir).ok();
+    }
+
+    #[test]
+    fn undo_batch_delete_restores_all() {
+        let dir = temp_dir();
+        let mut store = store_with_presets(&dir, &["a", "b", "c", "d"]);
+        store
+            .delete_presets(vec!["b".to_string(), "d".to_string()])
+            .unwrap();
+        store.undo_preset_change().unwrap();
+        assert_eq!(preset_ids(&store), ["a", "b", "c", "d"]);
+        fs::remove_dir_all(&dir).ok();
+    }
+
+    #[test]
+    fn history_clears_on_profile_switch() {
+        let dir = temp_dir();
+        let mut store = store_with_presets(&dir, &["a"]);
+        store.create_profile(make_profile("p2", "Other")).unwrap();
+        assert!(store.undo_state().can_undo);
+
+        store.set_active_profile("p2").unwrap();
+        assert_eq!(store.undo_state(), UndoState::default());
+        assert!(store.undo_preset_change().is_err());
+        fs::remove_dir_all(&dir).ok();
+    }
 }
@@ -0,0 +1,249 @@
+use serde::de::DeserializeOwned;
+use serde::{Deserialize, Serialize};
+use std::future::Future;
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+/// How long store mutations may accumulate before they are written out.
+pub const SAVE_DEBOUNCE: Duration = Duration::from_millis(500);
+
+/// Replace `path` with `contents` by writing a sibling temp file and renaming
+/// it into place, so a crash mid-write leaves the previous file intact.
+pub fn write_atomic(path: &Path, contents: &str) -> Result<(), String> {
+    let mut tmp = path.as_os_str().to_owned();
+    tmp.push(".tmp");
+    let tmp = PathBuf::from(tmp);
+    std::fs::write(&tmp, contents).map_err(|e| e.to_string())?;
+    std::fs::rename(&tmp, path).map_err(|e| e.to_string())
+}
+
+/// A data file that could not be loaded at startup, so its store started
+/// from defaults.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct LoadWarning {
+    /// The file that failed to load, e.g. `profiles.json`.
+    pub file: String,
+    /// Where the unreadable file was moved, if it could be kept.
+    pub preserved_as: Option<String>,
+    pub error: String,
+}
+
+/// Load a JSON data file, or defaults if it doesn't exist. A file that
+/// can't be read or fails to parse is renamed to `<name>.corrupt-<unix
+/// seconds>` before defaults are used, so the next save can't overwrite
+/// what the user had.
+pub fn load_json_or_preserve<T: DeserializeOwned + Default>(
+    path: &Path,
+) -> (T, Option<LoadWarning>) {
+    let error = match std::fs::read_to_string(path) {
+        Ok(contents) => match serde_json::from_str(&contents) {
+            Ok(value) => return (value, None),
+            Err(e) => e.to_string(),
+        },
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => return (T::default(), None),
+        Err(e) => e.to_string(),
+    };
+    let secs = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_secs())
+        .unwrap_or(0);
+    let mut preserved = path.as_os_str().to_owned();
+    preserved.push(format!(".corrupt-{}", secs));
+    let preserved = PathBuf::from(preserved);
+    let preserved_as = match std::fs::rename(path, &preserved) {
+        Ok(()) => Some(preserved),
+        Err(e) => {
+            log::error!("Failed to set aside corrupt {}: {}", path.display(), e);
+            None
+        }
+    };
+    log::error!(
+        "{} could not be loaded ({}); starting from defaults, original kept at {:?}",
+        path.display(),
+        error,
+        preserved_as
+    );
+    (T::default(), Some(load_warning(path, preserved_as, error)))
+}
+
+fn load_warning(path: &Path, preserved_as: Option<PathBuf>, error: String) -> LoadWarning {
+    LoadWarning {
+        file: path
+            .file_name()
+            .map(|name| name.to_string_lossy().into_owned())
+            .unwrap_or_default(),
+        preserved_as: preserved_as.map(|p| p.display().to_string()),
+        error,
+    }
+}
+
+/// When a store writes itself to disk: on every save, or only on `flush`
+/// with saves in between just marking it dirty.
+#[derive(Debug, Default)]
+pub struct SaveQueue {
+    deferred: bool,
+    dirty: AtomicBool,
+}
+
+impl SaveQueue {
+    /// Saves are written straight away.
+    pub fn immediate() -> Self {
+        Self::default()
+    }
+
+    /// Saves mark the store dirty until the next flush.
+    pub fn deferred() -> Self {
+        Self {
+            deferred: true,
+            dirty: AtomicBool::new(false),
+        }
+    }
+
+    /// Run `write` now, or just note that one is due when deferred.
+    pub fn save(&self, write: impl FnOnce() -> Result<(), String>) -> Result<(), String> {
+        if self.deferred {
+            self.dirty.store(true, Ordering::SeqCst);
+            return Ok(());
+        }
+        write()
+    }
+
+    /// Run `write` if a save is pending. Returns whether anything was written;
+    /// a failed write stays pending for the next flush.
+    pub fn flush(&self, write: impl FnOnce() -> Result<(), String>) -> Result<bool, String> {
+        if !self.dirty.swap(false, Ordering::SeqCst) {
+            return Ok(false);
+        }
+        if let Err(e) = write() {
+            self.dirty.store(true, Ordering::SeqCst);
+            return Err(e);
+        }
+        Ok(true)
+    }
+
+    pub fn is_dirty(&self) -> bool {
+        self.dirty.load(Ordering::SeqCst)
+    }
+}
+
+/// Run `flush` every `interval`, so mutations within one interval share a
+/// single write.
+pub async fn run_periodic_flush<F, Fut>(interval: Duration, mut flush: F)
+where
+    F: FnMut() -> Fut,
+    Fut: Future<Output = ()>,
+{
+    let mut ticker = tokio::time::interval(interval);
+    ticker.set_missed_tick_behavior(tokio::time::MissedTickBehavior::Skip);
+    loop {
+        ticker.tick().await;
+        flush().await;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::cell::Cell;
+    use std::fs;
+
+    #[test]
+    fn atomic_write_replaces_the_file_and_leaves_no_temp() {
+        let dir = std::env::temp_dir().join(format!("ptzcam-test-save-{}", uuid::Uuid::new_v4()));
+        fs::create_dir_all(&dir).unwrap();
+        let path = dir.join("store.json");
+
+        write_atomic(&path, "first").unwrap();
+        write_atomic(&path, "second").unwrap();
+        assert_eq!(fs::read_to_string(&path).unwrap(), "second");
+        assert!(!dir.join("store.json.tmp").exists());
+        fs::remove_dir_all(&dir).ok();
+    }
+
+    #[test]
+    fn corrupt_file_is_set_aside_with_a_warning() {
+        let dir = std::env::temp_dir().join(format!("ptzcam-test-save-{}", uuid::Uuid::new_v4()));
+        fs::create_dir_all(&dir).unwrap();
+        let path = dir.join("store.json");
+
+        let (value, warning) = load_json_or_preserve::<Vec<u32>>(&path);
+        assert!(value.is_empty() && warning.is_none());
+
+        fs::write(&path, "[1, 2,").unwrap();
+        let (value, warning) = load_json_or_preserve::<Vec<u32>>(&path);
+        assert!(value.is_empty());
+        let warning = warning.unwrap();
+        assert_eq!(warning.file, "store.json");
+        let preserved = PathBuf::from(warning.preserved_as.unwrap());
+        assert!(preserved
+            .file_name()
+            .unwrap()
+            .to_string_lossy()
+            .starts_with("store.json.corrupt-"));
+        assert_eq!(fs::read_to_string(&preserved).unwrap(), "[1, 2,");
+        assert!(!path.exists());
+        fs::remove_dir_all(&dir).ok();
+    }
+
+    #[test]
+    fn unreadable_file_is_set_aside_too() {
+        let dir = std::env::temp_dir().join(format!("ptzcam-test-save-{}", uuid::Uuid::new_v4()));
+        fs::create_dir_all(&dir).unwrap();
+        let path = dir.join("store.json");
+
+        // Not UTF-8, so reading it as text fails before parsing
+        fs::write(&path, [0xFF, 0xFE, 0x00]).unwrap();
+        let (value, warning) = load_json_or_preserve::<Vec<u32>>(&path);
+        assert!(value.is_empty());
+        let preserved = PathBuf::from(warning.unwrap().preserved_as.unwrap());
+        assert_eq!(fs::read(&preserved).unwrap(), [0xFF, 0xFE, 0x00]);
+        assert!(!path.exists());
+        fs::remove_dir_all(&dir).ok();
+    }
+
+    #[test]
+    fn deferred_saves_coalesce_into_one_flush() {
+        let queue = SaveQueue::deferred();
+        let writes = Cell::new(0);
+        let write = || {
+            writes.set(writes.get() + 1);
+            Ok(())
+        };
+
+        for _ in 0..10 {
+            queue.save(write).unwrap();
+        }
+        assert_eq!(writes.get(), 0);
+        assert!(queue.is_dirty());
+
+        assert!(queue.flush(write).unwrap());
+        assert!(!queue.flush(write).unwrap());
+        assert_eq!(writes.get(), 1);
+    }
+
+    #[test]
+    fn failed_flush_stays_pending() {
+        let queue = SaveQueue::deferred();
+        queue.save(|| Ok(())).unwrap();
+        assert!(queue.flush(|| Err("disk full".to_string())).is_err());
+        assert!(queue.is_dirty());
+        assert!(queue.flush(|| Ok(())).unwrap());
+    }
+
+    #[test]
+    fn immediate_saves_write_every_time() {
+        let queue = SaveQueue::immediate();
+        let writes = Cell::new(0);
+        for _ in 0..3 {
+            queue
+                .save(|| {
+                    writes.set(writes.get() + 1);
+                    Ok(())
+                })
+                .unwrap();
+        }
+        assert_eq!(writes.get(), 3);
+        assert!(!queue.is_dirty());
+    }
+}
@@ -0,0 +1,349 @@
+use crate::ptz::types::{Preset, PresetProfile};
+use serde::{Deserialize, Serialize};
+use std::collections::VecDeque;
+
+/// Preset edits that can be undone before the oldest is forgotten.
+pub const MAX_UNDO_STEPS: usize = 50;
+
+/// A preset edit, recorded with enough state to reverse it.
+#[derive(Debug, Clone)]
+pub enum PresetChange {
+    /// `preset` was inserted at `index`; `home` if it is the profile's home preset.
+    Created {
+        index: usize,
+        preset: Preset,
+        home: bool,
+    },
+    Updated {
+        before: Preset,
+        after: Preset,
+    },
+    /// `preset` was removed from `index`; `home` if it was the home preset.
+    Deleted {
+        index: usize,
+        preset: Preset,
+        home: bool,
+    },
+    /// Several changes made by one operation, undone together.
+    Batch(Vec<PresetChange>),
+}
+
+impl PresetChange {
+    /// The change that reverses this one.
+    pub fn inverse(self) -> Self {
+        match self {
+            PresetChange::Created {
+                index,
+                preset,
+                home,
+            } => PresetChange::Deleted {
+                index,
+                preset,
+                home,
+            },
+            PresetChange::Deleted {
+                index,
+                preset,
+                home,
+            } => PresetChange::Created {
+                index,
+                preset,
+                home,
+            },
+            PresetChange::Updated { before, after } => PresetChange::Updated {
+                before: after,
+                after: before,
+            },
+            PresetChange::Batch(changes) => {
+                PresetChange::Batch(changes.into_iter().rev().map(Self::inverse).collect())
+            }
+        }
+    }
+
+    /// Carry out the change on a profile.
+    pub fn apply(&self, profile: &mut PresetProfile) -> Result<(), String> {
+        match self {
+            PresetChange::Created {
+                index,
+                preset,
+                home,
+            } => {
+                if profile.presets.iter().any(|p| p.id == preset.id) {
+                    return Err(format!("Preset '{}' already exists", preset.name));
+                }
+                let index = (*index).min(profile.presets.len());
+                profile.presets.insert(index, preset.clone());
+                if *home {
+                    profile.home_preset_id = Some(preset.id.clone());
+                }
+            }
+            PresetChange::Updated { after, .. } => {
+                let existing = profile
+                    .presets
+                    .iter_mut()
+                    .find(|p| p.id == after.id)
+                    .ok_or("Preset not found")?;
+                *existing = after.clone();
+            }
+            PresetChange::Deleted { preset, .. } => {
+                let pos = profile
+                    .presets
+                    .iter()
+                    .position(|p| p.id == preset.id)
+                    .ok_or("Preset not found")?;
+                profile.presets.remove(pos);
+                if profile.home_preset_id.as_deref() == Some(preset.id.as_str()) {
+                    profile.home_preset_id = None;
+                }
+            }
+            PresetChange::Batch(changes) => {
+                for change in changes {
+                    change.apply(profile)?;
+                }
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Whether there is anything to undo or redo, for enabling the UI buttons.
+#[derive(Debug, Clone, Copy, Default, Serialize, Deserialize, PartialEq)]
+pub struct UndoState {
+    pub can_undo: bool,
+    pub can_redo: bool,
+}
+
+/// Bounded undo and redo stacks of preset changes.
+#[derive(Debug, Default)]
+pub struct UndoHistory {
+    undo: VecDeque<PresetChange>,
+    redo: Vec<PresetChange>,
+}
+
+impl UndoHistory {
+    /// Remember a new change. Anything that could be redone is dropped.
+    pub fn record(&mut self, change: PresetChange) {
+        if matches!(&change, PresetChange::Batch(changes) if changes.is_empty()) {
+            return;
+        }
+        self.redo.clear();
+        self.push_undo(change);
+    }
+
+    fn push_undo(&mut self, change: PresetChange) {
+        if self.undo.len() == MAX_UNDO_STEPS {
+            self.undo.pop_front();
+        }
+        self.undo.push_back(change);
+    }
+
+    /// Undo the latest change on `profile`. It moves to the redo stack, or
+    /// stays put, leaving the profile untouched, if it no longer applies.
+    pub fn undo(&mut self, profile: &mut PresetProfile) -> Result<(), String> {
+        let change = self.undo.pop_back().ok_or("Nothing to undo")?;
+        if let Err(e) = apply_all_or_nothing(&change.clone().inverse(), profile) {
+            self.undo.push_back(change);
+            return Err(e);
+        }
+        self.redo.push(change);
+        Ok(())
+    }
+
+    /// Reapply the latest undone change on `profile`.
+    pub fn redo(&mut self, profile: &mut PresetProfile) -> Result<(), String> {
+        let change = self.redo.pop().ok_or("Nothing to redo")?;
+        if let Err(e) = apply_all_or_nothing(&change, profile) {
+            self.redo.push(change);
+            return Err(e);
+        }
+        self.push_undo(change);
+        Ok(())
+    }
+
+    pub fn state(&self) -> UndoState {
+        UndoState {
+            can_undo: !self.undo.is_empty(),
+            can_redo: !self.redo.is_empty(),
+        }
+    }
+
+    pub fn clear(&mut self) {
+        self.undo.clear();
+        self.redo.clear();
+    }
+}
+
+/// Apply a change to a copy of the profile, so a batch that fails halfway
+/// leaves the original as it was.
+fn apply_all_or_nothing(change: &PresetChange, profile: &mut PresetProfile) -> Result<(), String> {
+    let mut updated = profile.clone();
+    change.apply(&mut updated)?;
+    *profile = updated;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ptz::geometry::LensCurve;
+
+    fn preset(id: &str) -> Preset {
+        Preset {
+            id: id.to_string(),
+            name: id.to_string(),
+            pan: 0.0,
+            tilt: 0.0,
+            zoom: 0.0,
+            color: "#fff".to_string(),
+            native_slot: None,
+            focus: None,
+            white_balance: None,
+            hotkey: None,
+            thumbnail: None,
+        }
+    }
+
+    fn profile(ids: &[&str]) -> PresetProfile {
+        PresetProfile {
+            id: "p".to_string(),
+            name: "Show".to_string(),
+            camera_fov_degrees: 60.0,
+            endpoint_id: None,
+            presets: ids.iter().map(|id| preset(id)).collect(),
+            tour: None,
+            transition: None,
+            home_preset_id: None,
+            lens_curve: LensCurve::default(),
+            mechanical_range: None,
+            limits_degrees: None,
+        }
+    }
+
+    fn ids(profile: &PresetProfile) -> Vec<&str> {
+        profile.presets.iter().map(|p| p.id.as_str()).collect()
+    }
+
+    #[test]
+    fn deleted_preset_returns_to_its_slot_and_home() {
+        let mut profile = profile(&["a", "c"]);
+        let mut history = UndoHistory::default();
+        // "b" was deleted from index 1 while it was the home preset
+        history.record(PresetChange::Deleted {
+            index: 1,
+            preset: preset("b"),
+            home: true,
+        });
+
+        history.undo(&mut profile).unwrap();
+        assert_eq!(ids(&profile), ["a", "b", "c"]);
+        assert_eq!(profile.home_preset_id.as_deref(), Some("b"));
+
+        history.redo(&mut profile).unwrap();
+        assert_eq!(ids(&profile), ["a", "c"]);
+        assert_eq!(profile.home_preset_id, None);
+    }
+
+    #[test]
+    fn batch_is_undone_as_one_step() {
+        let mut profile = profile(&["a", "b", "c"]);
+        let mut history = UndoHistory::default();
+        history.record(PresetChange::Batch(vec![
+            PresetChange::Created {
+                index: 1,
+                preset: preset("b"),
+                home: false,
+            },
+            PresetChange::Created {
+                index: 2,
+                preset: preset("c"),
+                home: false,
+            },
+        ]));
+
+        history.undo(&mut profile).unwrap();
+        assert_eq!(ids(&profile), ["a"]);
+        assert_eq!(
+            history.state(),
+            UndoState {
+                can_undo: false,
+                can_redo: true,
+            }
+        );
+    }
+
+    #[test]
+    fn new_change_clears_redo() {
+        let mut profile = profile(&["a"]);
+        let mut history = UndoHistory::default();
+        history.record(PresetChange::Created {
+            index: 0,
+            preset: preset("a"),
+            home: false,
+        });
+        history.undo(&mut profile).unwrap();
+        assert!(history.state().can_redo);
+
+        history.record(PresetChange::Created {
+            index: 0,
+            preset: preset("x"),
+            home: false,
+        });
+        assert!(!history.state().can_redo);
+        assert!(history.redo(&mut profile).is_err());
+    }
+
+    #[test]
+    fn history_is_bounded() {
+        let mut history = UndoHistory::default();
+        for n in 0..MAX_UNDO_STEPS + 10 {
+            history.record(PresetChange::Created {
+                index: n,
+                preset: preset(&n.to_string()),
+                home: false,
+            });
+        }
+        let mut profile = profile(&[]);
+        profile.presets = (0..MAX_UNDO_STEPS + 10)
+            .map(|n| preset(&n.to_string()))
+            .collect();
+        let mut undone = 0;
+        while history.undo(&mut profile).is_ok() {
+            undone += 1;
+        }
+        assert_eq!(undone, MAX_UNDO_STEPS);
+        assert_eq!(profile.presets.len(), 10);
+    }
+
+    #[test]
+    fn change_that_no_longer_applies_stays_on_the_stack() {
+        let mut profile = profile(&[]);
+        let mut history = UndoHistory::default();
+        history.record(PresetChange::Created {
+            index: 0,
+            preset: preset("gone"),
+            home: false,
+        });
+        assert!(history.undo(&mut profile).is_err());
+        assert!(history.state().can_undo);
+    }
+
+    #[test]
+    fn failed_batch_leaves_the_profile_untouched() {
+        let mut profile = profile(&["a"]);
+        let mut history = UndoHistory::default();
+        history.record(PresetChange::Batch(vec![
+            PresetChange::Created {
+                index: 0,
+                preset: preset("a"),
+                home: false,
+            },
+            PresetChange::Created {
+                index: 1,
+                preset: preset("gone"),
+                home: false,
+            },
+        ]));
+        assert!(history.undo(&mut profile).is_err());
+        assert_eq!(ids(&profile), ["a"]);
+    }
+}
@@ -0,0 +1,151 @@
+use std::collections::HashMap;
+use std::future::Future;
+use std::sync::{Arc, Mutex};
+use std::task::Poll;
+use std::time::Duration;
+use tokio::sync::watch;
+
+/// Cuts short the waits of a multi-step command, such as a timed relative
+/// move, when a newer command for the same camera arrives. Each controller
+/// owns one; clones share it.
+#[derive(Clone)]
+pub struct CancelToken {
+    generation: Arc<watch::Sender<u64>>,
+}
+
+impl CancelToken {
+    pub fn new() -> Self {
+        Self {
+            generation: Arc::new(watch::channel(0).0),
+        }
+    }
+
+    /// Wake every wait in progress. Waits that start afterwards are unaffected.
+    pub fn cancel(&self) {
+        self.generation.send_modify(|generation| *generation += 1);
+    }
+
+    /// Sleep for `duration`. Returns `false` if the wait was cancelled first.
+    pub async fn sleep(&self, duration: Duration) -> bool {
+        let mut cancelled = self.generation.subscribe();
+        tokio::time::timeout(duration, cancelled.changed())
+            .await
+            .is_err()
+    }
+
+    /// Run `future` to completion, or drop it and return `None` if the wait
+    /// is cancelled first.
+    pub async fn until_cancelled<F: Future>(&self, future: F) -> Option<F::Output> {
+        let mut generation = self.generation.subscribe();
+        let mut cancelled = std::pin::pin!(generation.changed());
+        let mut future = std::pin::pin!(future);
+        std::future::poll_fn(|cx| {
+            if let Poll::Ready(output) = future.as_mut().poll(cx) {
+                return Poll::Ready(Some(output));
+            }
+            match cancelled.as_mut().poll(cx) {
+                Poll::Ready(Ok(())) => Poll::Ready(None),
+                // The token holds the sender, so it can't be dropped
+                Poll::Ready(Err(_)) | Poll::Pending => Poll::Pending,
+            }
+        })
+        .await
+    }
+}
+
+impl Default for CancelToken {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Cancel token of each registered controller, by endpoint id, so a new
+/// command can interrupt the previous one without waiting for the dispatcher.
+#[derive(Default)]
+pub struct MoveCancels {
+    tokens: Mutex<HashMap<String, CancelToken>>,
+}
+
+impl MoveCancels {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    fn lock(&self) -> std::sync::MutexGuard<'_, HashMap<String, CancelToken>> {
+        self.tokens.lock().unwrap_or_else(|e| e.into_inner())
+    }
+
+    /// Register an endpoint's token; `None` for controllers that never wait.
+    pub fn register(&self, id: &str, token: Option<CancelToken>) {
+        match token {
+            Some(token) => self.lock().insert(id.to_string(), token),
+            None => self.lock().remove(id),
+        };
+    }
+
+    pub fn remove(&self, id: &str) {
+        self.lock().remove(id);
+    }
+
+    /// Interrupt whatever the endpoint's controller is waiting on.
+    pub fn cancel(&self, id: &str) {
+        if let Some(token) = self.lock().get(id) {
+            token.cancel();
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::time::Instant;
+
+    #[tokio::test]
+    async fn uncancelled_sleep_runs_its_full_duration() {
+        let token = CancelToken::new();
+        let started = Instant::now();
+        assert!(token.sleep(Duration::from_millis(20)).await);
+        assert!(started.elapsed() >= Duration::from_millis(20));
+    }
+
+    #[tokio::test]
+    async fn cancel_ends_a_sleep_early() {
+        let token = CancelToken::new();
+        let canceller = token.clone();
+        tokio::spawn(async move {
+            tokio::time::sleep(Duration::from_millis(20)).await;
+            canceller.cancel();
+        });
+        let started = Instant::now();
+        assert!(!token.sleep(Duration::from_secs(5)).await);
+        assert!(started.elapsed() < Duration::from_secs(1));
+    }
+
+    #[tokio::test]
+    async fn cancel_drops_a_pending_future() {
+        let token = CancelToken::new();
+        assert_eq!(token.until_cancelled(async { 7 }).await, Some(7));
+
+        let canceller = token.clone();
+        tokio::spawn(async move {
+            tokio::time::sleep(Duration::from_millis(20)).await;
+            canceller.cancel();
+        });
+        let pending = tokio::time::sleep(Duration::from_secs(5));
+        let started = Instant::now();
+        assert_eq!(token.until_cancelled(pending).await, None);
+        assert!(started.elapsed() < Duration::from_secs(1));
+    }
+
+    #[tokio::test]
+    async fn earlier_cancel_does_not_affect_a_new_sleep() {
+        let cancels = MoveCancels::new();
+        let token = CancelToken::new();
+        cancels.register("cam", Some(token.clone()));
+        cancels.cancel("cam");
+        assert!(token.sleep(Duration::from_millis(5)).await);
+
+        cancels.remove("cam");
+        cancels.cancel("cam");
+    }
+}
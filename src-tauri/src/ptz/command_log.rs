@@ -0,0 +1,136 @@
+use super::controller::PtzError;
+use serde::{Deserialize, Serialize};
+use std::collections::VecDeque;
+use std::sync::Mutex;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+/// Most entries kept; older ones are dropped as new commands arrive.
+pub const COMMAND_LOG_CAPACITY: usize = 1000;
+
+/// One command sent to a camera.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct CommandLogEntry {
+    /// When the command completed (Unix ms).
+    pub timestamp: u64,
+    pub endpoint_id: String,
+    /// Command name and arguments, e.g. `move_absolute(0.5, 0.1, 0.0)`.
+    pub command: String,
+    /// `None` when the command succeeded, otherwise the error.
+    pub error: Option<String>,
+}
+
+/// Ring buffer of the commands dispatched to cameras, for tracing who moved
+/// a camera where.
+pub struct CommandLog {
+    entries: Mutex<VecDeque<CommandLogEntry>>,
+    capacity: usize,
+}
+
+impl CommandLog {
+    pub fn new(capacity: usize) -> Self {
+        Self {
+            entries: Mutex::new(VecDeque::with_capacity(capacity)),
+            capacity,
+        }
+    }
+
+    fn lock(&self) -> std::sync::MutexGuard<'_, VecDeque<CommandLogEntry>> {
+        self.entries.lock().unwrap_or_else(|e| e.into_inner())
+    }
+
+    /// Append the outcome of a command, dropping the oldest entry when full.
+    pub fn record<T>(&self, endpoint_id: &str, command: String, result: &Result<T, PtzError>) {
+        if self.capacity == 0 {
+            return;
+        }
+        let entry = CommandLogEntry {
+            timestamp: now_ms(),
+            endpoint_id: endpoint_id.to_string(),
+            command,
+            error: result.as_ref().err().map(|e| e.to_string()),
+        };
+        let mut entries = self.lock();
+        if entries.len() == self.capacity {
+            entries.pop_front();
+        }
+        entries.push_back(entry);
+    }
+
+    /// The newest `limit` entries, oldest first.
+    pub fn recent(&self, limit: usize) -> Vec<CommandLogEntry> {
+        let entries = self.lock();
+        let skip = entries.len().saturating_sub(limit);
+        entries.iter().skip(skip).cloned().collect()
+    }
+
+    pub fn clear(&self) {
+        self.lock().clear();
+    }
+}
+
+impl Default for CommandLog {
+    fn default() -> Self {
+        Self::new(COMMAND_LOG_CAPACITY)
+    }
+}
+
+fn now_ms() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_millis() as u64)
+        .unwrap_or(0)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn records_outcomes_in_order() {
+        let log = CommandLog::default();
+        log.record("cam", "home()".to_string(), &Ok::<_, PtzError>(()));
+        log.record(
+            "cam",
+            "stop()".to_string(),
+            &Err::<(), _>(PtzError::Timeout("no reply".into())),
+        );
+
+        let entries = log.recent(10);
+        assert_eq!(entries.len(), 2);
+        assert_eq!(entries[0].command, "home()");
+        assert_eq!(entries[0].error, None);
+        assert_eq!(entries[1].error.as_deref(), Some("Timeout: no reply"));
+        assert!(entries[1].timestamp >= entries[0].timestamp);
+    }
+
+    #[test]
+    fn drops_the_oldest_entries_when_full() {
+        let log = CommandLog::new(3);
+        for n in 0..5 {
+            log.record(
+                "cam",
+                format!("recall_preset({})", n),
+                &Ok::<_, PtzError>(()),
+            );
+        }
+        let commands: Vec<_> = log.recent(10).into_iter().map(|e| e.command).collect();
+        assert_eq!(
+            commands,
+            ["recall_preset(2)", "recall_preset(3)", "recall_preset(4)"]
+        );
+    }
+
+    #[test]
+    fn recent_returns_the_newest_entries() {
+        let log = CommandLog::default();
+        for n in 0..5 {
+            log.record("cam", format!("zoom_to({})", n), &Ok::<_, PtzError>(()));
+        }
+        let entries = log.recent(2);
+        assert_eq!(entries[0].command, "zoom_to(3)");
+        assert_eq!(entries[1].command, "zoom_to(4)");
+
+        log.clear();
+        assert!(log.recent(10).is_empty());
+    }
+}
@@ -0,0 +1,165 @@
+use super::controller::PtzError;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::sync::Mutex;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+/// Last-known reachability of an endpoint, from the commands sent to it.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub enum ConnectionStatus {
+    /// No command has reached the camera or failed to since it was registered.
+    Unknown,
+    Ok,
+    /// Commands have been failing since `since` (Unix ms).
+    Failed {
+        since: u64,
+        reason: String,
+    },
+}
+
+/// Connection status per endpoint id, updated as commands complete.
+/// Only errors that say the camera couldn't be reached count as failures;
+/// a camera rejecting a command is still connected.
+#[derive(Default)]
+pub struct ConnectionStatuses {
+    statuses: Mutex<HashMap<String, ConnectionStatus>>,
+}
+
+impl ConnectionStatuses {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    fn lock(&self) -> std::sync::MutexGuard<'_, HashMap<String, ConnectionStatus>> {
+        self.statuses.lock().unwrap_or_else(|e| e.into_inner())
+    }
+
+    pub fn get(&self, id: &str) -> ConnectionStatus {
+        self.lock()
+            .get(id)
+            .cloned()
+            .unwrap_or(ConnectionStatus::Unknown)
+    }
+
+    pub fn snapshot(&self) -> HashMap<String, ConnectionStatus> {
+        self.lock().clone()
+    }
+
+    /// Forget what is known about an endpoint, e.g. after its controller was rebuilt.
+    pub fn reset(&self, id: &str) {
+        self.lock()
+            .insert(id.to_string(), ConnectionStatus::Unknown);
+    }
+
+    pub fn remove(&self, id: &str) {
+        self.lock().remove(id);
+    }
+
+    pub fn record_success(&self, id: &str) {
+        self.lock().insert(id.to_string(), ConnectionStatus::Ok);
+    }
+
+    /// Mark an endpoint failed at `now_ms`, keeping the original time if it was already failing.
+    pub fn record_failure(&self, id: &str, reason: String, now_ms: u64) {
+        let mut statuses = self.lock();
+        let since = match statuses.get(id) {
+            Some(ConnectionStatus::Failed { since, .. }) => *since,
+            _ => now_ms,
+        };
+        statuses.insert(id.to_string(), ConnectionStatus::Failed { since, reason });
+    }
+
+    /// Update an endpoint's status from a command result.
+    pub fn record<T>(&self, id: &str, result: &Result<T, PtzError>) {
+        match result {
+            Ok(_) => self.record_success(id),
+            Err(e)
+                if e.is_retryable()
+                    || matches!(e, PtzError::NotConnected | PtzError::AuthFailed(_)) =>
+            {
+                self.record_failure(id, e.to_string(), now_ms())
+            }
+            Err(_) => {}
+        }
+    }
+}
+
+fn now_ms() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_millis() as u64)
+        .unwrap_or(0)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ptz::controller::PtzController;
+    use crate::simulator::client::{FailureMode, SimulatedController};
+
+    #[test]
+    fn unseen_endpoint_is_unknown() {
+        let statuses = ConnectionStatuses::new();
+        assert_eq!(statuses.get("cam-a"), ConnectionStatus::Unknown);
+        assert!(statuses.snapshot().is_empty());
+    }
+
+    #[test]
+    fn repeated_failures_keep_first_failure_time() {
+        let statuses = ConnectionStatuses::new();
+        statuses.record_failure("cam-a", "timeout".into(), 1_000);
+        statuses.record_failure("cam-a", "refused".into(), 2_000);
+        assert_eq!(
+            statuses.get("cam-a"),
+            ConnectionStatus::Failed {
+                since: 1_000,
+                reason: "refused".into()
+            }
+        );
+
+        statuses.record_success("cam-a");
+        statuses.record_failure("cam-a", "timeout".into(), 3_000);
+        assert!(matches!(
+            statuses.get("cam-a"),
+            ConnectionStatus::Failed { since: 3_000, .. }
+        ));
+    }
+
+    #[tokio::test]
+    async fn simulated_results_drive_transitions() {
+        let statuses = ConnectionStatuses::new();
+        let camera = SimulatedController::new();
+
+        statuses.record("cam-a", &camera.test_connection().await);
+        assert_eq!(statuses.get("cam-a"), ConnectionStatus::Ok);
+
+        camera.set_failure_mode(FailureMode::AlwaysTimeout);
+        statuses.record("cam-a", &camera.test_connection().await);
+        assert!(matches!(
+            statuses.get("cam-a"),
+            ConnectionStatus::Failed { .. }
+        ));
+
+        // A rejected command means the camera answered; the status stands
+        camera.set_failure_mode(FailureMode::RejectCommands);
+        statuses.record("cam-a", &camera.move_absolute(0.0, 0.0, 0.0).await);
+        assert!(matches!(
+            statuses.get("cam-a"),
+            ConnectionStatus::Failed { .. }
+        ));
+
+        camera.set_failure_mode(FailureMode::None);
+        statuses.record("cam-a", &camera.get_position().await);
+        assert_eq!(statuses.get("cam-a"), ConnectionStatus::Ok);
+
+        // Refused credentials leave the camera unusable until fixed
+        statuses.record::<()>("cam-a", &Err(PtzError::AuthFailed("HTTP 401".into())));
+        assert!(matches!(
+            statuses.get("cam-a"),
+            ConnectionStatus::Failed { .. }
+        ));
+
+        statuses.reset("cam-a");
+        assert_eq!(statuses.get("cam-a"), ConnectionStatus::Unknown);
+    }
+}
@@ -1,5 +1,25 @@
-use super::types::PtzPosition;
+use super::cancel::{CancelToken, MoveCancels};
+use super::command_log::CommandLog;
+use super::connection_status::ConnectionStatuses;
+use super::deadman::{self, Deadman, DEFAULT_DEADMAN_WINDOW};
+use super::nudge::{self, NudgeDwell};
+use super::preview::{CommandPreview, EncodedRequest, PtzCommand};
+use super::rate_limit::{CommandLane, RateAction, RateLimiter, DEFAULT_COMMANDS_PER_SECOND};
+use super::timeout;
+use super::types::{
+    CameraInfo, Capabilities, ExposureMode, MenuDirection, Preset, PtzPosition, TallyState,
+    ViscaDiagnostics, WhiteBalanceSetting,
+};
 use async_trait::async_trait;
+use std::collections::HashMap;
+use std::future::Future;
+use std::pin::Pin;
+use std::sync::{Arc, Mutex};
+use std::time::Instant;
+
+/// Controllers kept warm for quick switching between cameras. Beyond this,
+/// the least recently activated is dropped, closing its connection.
+pub const MAX_POOLED_CONTROLLERS: usize = 4;
 
 /// Protocol-agnostic PTZ controller trait.
 /// All protocol implementations (NDI, VISCA, Panasonic AW, BirdDog) implement this.
@@ -14,6 +34,14 @@ pub trait PtzController: Send + Sync {
     /// Set zoom level (normalized 0.0 to 1.0).
     async fn zoom_to(&self, zoom: f64) -> Result<(), PtzError>;
 
+    /// Change zoom by a normalized delta. The default reads the current zoom
+    /// and moves to the clamped sum; protocols with relative zoom can send it
+    /// directly instead.
+    async fn zoom_by(&self, delta: f64) -> Result<(), PtzError> {
+        let zoom = self.get_position().await?.zoom;
+        self.zoom_to((zoom + delta).clamp(0.0, 1.0)).await
+    }
+
     /// Recall a camera-native preset by index.
     async fn recall_preset(&self, preset_index: u8) -> Result<(), PtzError>;
 
@@ -26,6 +54,12 @@ pub trait PtzController: Send + Sync {
     /// Test connectivity to the camera.
     async fn test_connection(&self) -> Result<(), PtzError>;
 
+    /// Test connectivity and report the camera model, when the protocol
+    /// exposes one.
+    async fn probe(&self) -> Result<Option<String>, PtzError> {
+        self.test_connection().await.map(|()| None)
+    }
+
     /// Move to the home/center position.
     async fn home(&self) -> Result<(), PtzError> {
         self.move_absolute(0.0, 0.0, 0.0).await
@@ -43,6 +77,11 @@ pub trait PtzController: Send + Sync {
         Ok(())
     }
 
+    /// Start continuous zoom. speed: -1.0 (wide) to 1.0 (tele), 0 = stop zoom.
+    async fn zoom_continuous(&self, _speed: f64) -> Result<(), PtzError> {
+        Ok(())
+    }
+
     /// Start continuous focus movement. speed: negative = near, positive = far.
     async fn focus_continuous(&self, _speed: f64) -> Result<(), PtzError> {
         Ok(())
@@ -62,6 +101,189 @@ pub trait PtzController: Send + Sync {
     async fn focus_stop(&self) -> Result<(), PtzError> {
         Ok(())
     }
+
+    /// Move focus to an absolute position (normalized 0.0 = nearest to 1.0 = infinity).
+    async fn focus_to(&self, _position: f64) -> Result<(), PtzError> {
+        Ok(())
+    }
+
+    /// Once the zoom of the move just sent settles at `zoom`, move focus to
+    /// the absolute `focus`, so focus is right at the new focal length. Zoom
+    /// is not sent again. The default can't tell when zoom finishes and
+    /// focuses at once.
+    async fn focus_after_zoom(&self, _zoom: f64, focus: f64) -> Result<(), PtzError> {
+        self.focus_to(focus).await
+    }
+
+    /// Query the current focus position (normalized 0.0 = nearest to 1.0 = infinity).
+    async fn get_focus(&self) -> Result<f64, PtzError> {
+        Err(PtzError::CommandFailed(
+            "Focus position is not supported by this camera".into(),
+        ))
+    }
+
+    /// Set the iris opening (normalized 0.0 = closed to 1.0 = fully open).
+    async fn set_iris(&self, _iris: f64) -> Result<(), PtzError> {
+        Ok(())
+    }
+
+    /// Set the sensor gain in dB.
+    async fn set_gain(&self, _gain_db: u8) -> Result<(), PtzError> {
+        Ok(())
+    }
+
+    /// Set the shutter step (0 = shutter off, higher = faster).
+    async fn set_shutter(&self, _step: u8) -> Result<(), PtzError> {
+        Ok(())
+    }
+
+    /// Switch between automatic and manual exposure. `Manual` doesn't apply
+    /// any iris, gain or shutter value; what it hands over varies by
+    /// protocol. BirdDog switches iris, gain and shutter to manual together,
+    /// holding the camera's current values. Panasonic only turns off auto
+    /// iris: gain stays under AGC until `set_gain` fixes it, and the shutter
+    /// keeps its step.
+    async fn set_exposure_mode(&self, _mode: ExposureMode) -> Result<(), PtzError> {
+        Ok(())
+    }
+
+    /// Set the white-balance mode, including gains for manual balance.
+    async fn set_white_balance(&self, _setting: WhiteBalanceSetting) -> Result<(), PtzError> {
+        Ok(())
+    }
+
+    /// Flip the image vertically, for cameras mounted upside down.
+    async fn set_image_flip(&self, _enabled: bool) -> Result<(), PtzError> {
+        Ok(())
+    }
+
+    /// Mirror the image horizontally.
+    async fn set_mirror(&self, _enabled: bool) -> Result<(), PtzError> {
+        Ok(())
+    }
+
+    /// Let zoom continue past optical tele using digital zoom, or stop at
+    /// optical tele.
+    async fn set_digital_zoom(&self, _enabled: bool) -> Result<(), PtzError> {
+        Ok(())
+    }
+
+    /// Freeze the camera's output on the current frame, or release it.
+    async fn set_freeze(&self, _enabled: bool) -> Result<(), PtzError> {
+        Ok(())
+    }
+
+    /// Move to an absolute position behind a frozen frame, so the move never
+    /// shows on air. The output is released even if the move fails.
+    async fn reposition_frozen(&self, pan: f64, tilt: f64, zoom: f64) -> Result<(), PtzError> {
+        self.set_freeze(true).await?;
+        let moved = self.move_absolute(pan, tilt, zoom).await;
+        self.set_freeze(false).await?;
+        moved
+    }
+
+    /// Open or close the camera's on-screen menu.
+    async fn menu_toggle(&self) -> Result<(), PtzError> {
+        Ok(())
+    }
+
+    /// Move the on-screen menu cursor.
+    async fn menu_navigate(&self, _direction: MenuDirection) -> Result<(), PtzError> {
+        Ok(())
+    }
+
+    /// Select the highlighted on-screen menu item.
+    async fn menu_enter(&self) -> Result<(), PtzError> {
+        Ok(())
+    }
+
+    /// Light the on-camera tally lamp for the camera's program/preview state.
+    async fn set_tally(&self, _state: TallyState) -> Result<(), PtzError> {
+        Ok(())
+    }
+
+    /// Rename the NDI source the camera publishes.
+    async fn set_ndi_name(&self, _name: &str) -> Result<(), PtzError> {
+        Err(PtzError::CommandFailed(
+            "Setting the NDI name is not supported by this camera".into(),
+        ))
+    }
+
+    /// Select a model-specific gamma curve.
+    async fn set_gamma(&self, _mode: u8) -> Result<(), PtzError> {
+        Err(PtzError::CommandFailed(
+            "Gamma selection is not supported by this camera".into(),
+        ))
+    }
+
+    /// Recall one of the camera's stored picture profiles.
+    async fn recall_picture_profile(&self, _index: u8) -> Result<(), PtzError> {
+        Err(PtzError::CommandFailed(
+            "Picture profiles are not supported by this camera".into(),
+        ))
+    }
+
+    /// Send a protocol message as-is and return the camera's reply payload.
+    async fn send_raw(&self, _payload: &[u8]) -> Result<Vec<u8>, PtzError> {
+        Err(PtzError::CommandFailed(
+            "Raw commands are not supported by this camera".into(),
+        ))
+    }
+
+    /// The VISCA sequence counter and last reply, for troubleshooting.
+    async fn visca_diagnostics(&self) -> Result<ViscaDiagnostics, PtzError> {
+        Err(PtzError::CommandFailed(
+            "VISCA diagnostics are not supported by this camera".into(),
+        ))
+    }
+
+    /// Restart VISCA sequence numbering at 1 without reconnecting.
+    async fn reset_sequence(&self) -> Result<(), PtzError> {
+        Err(PtzError::CommandFailed(
+            "Sequence reset is not supported by this camera".into(),
+        ))
+    }
+
+    /// The requests `command` would send, without sending them.
+    fn encode(&self, _command: &PtzCommand) -> Result<Vec<EncodedRequest>, PtzError> {
+        Err(PtzError::CommandFailed(
+            "Command preview is not supported by this camera".into(),
+        ))
+    }
+
+    /// Ask the camera for its vendor, model and firmware. `None` when the
+    /// protocol has no way to tell.
+    async fn identify(&self) -> Result<Option<CameraInfo>, PtzError> {
+        Ok(None)
+    }
+
+    /// The camera preset slot last recalled, when the camera can report it.
+    /// `None` for cameras that can't.
+    async fn get_active_preset(&self) -> Result<Option<u8>, PtzError> {
+        Ok(None)
+    }
+
+    /// Note where a camera-native preset slot points, for controllers that
+    /// carry out absolute moves by recalling a nearby slot. Positions the
+    /// controller stored itself take precedence.
+    fn remember_native_preset(&self, _slot: u8, _position: PtzPosition) {}
+
+    /// Features this controller implements. Defaults to none.
+    fn capabilities(&self) -> Capabilities {
+        Capabilities::default()
+    }
+
+    /// Whether absolute moves already travel at a controlled speed, so
+    /// host-side interpolation would only fight the camera.
+    fn supports_timed_moves(&self) -> bool {
+        false
+    }
+
+    /// Token that cuts short this controller's waits in multi-step
+    /// commands. `None` for controllers whose commands never wait.
+    fn cancel_token(&self) -> Option<CancelToken> {
+        None
+    }
 }
 
 #[derive(Debug, thiserror::Error)]
@@ -80,92 +302,841 @@ pub enum PtzError {
 
     #[error("Not connected")]
     NotConnected,
+
+    /// The endpoint's settings can never work, e.g. a malformed host.
+    #[error("Invalid configuration: {0}")]
+    InvalidConfig(String),
+
+    /// The camera refused the configured credentials. Not retried, since
+    /// sending the same ones again won't help.
+    #[error("Authentication failed: {0}")]
+    AuthFailed(String),
 }
 
-/// Routes PTZ commands to the active protocol-specific controller.
+impl PtzError {
+    /// Whether the error is transient and the command may succeed if retried.
+    pub fn is_retryable(&self) -> bool {
+        matches!(self, PtzError::ConnectionFailed(_) | PtzError::Timeout(_))
+    }
+}
+
+/// Check the host and port a network client is built for.
+pub fn validate_address(host: &str, port: u16) -> Result<(), PtzError> {
+    super::types::validate_host(host).map_err(PtzError::InvalidConfig)?;
+    super::types::validate_port(port).map_err(PtzError::InvalidConfig)
+}
+
+/// Relative pan/tilt carried out as an absolute move: read the current
+/// position, add the deltas, and move there with zoom unchanged. Lands on the
+/// same spot every time, unlike a timed speed burst.
+pub async fn move_relative_via_absolute<C>(
+    controller: &C,
+    pan_delta: f64,
+    tilt_delta: f64,
+) -> Result<(), PtzError>
+where
+    C: PtzController + ?Sized,
+{
+    let pos = controller.get_position().await?;
+    controller
+        .move_absolute(
+            (pos.pan + pan_delta).clamp(-1.0, 1.0),
+            (pos.tilt + tilt_delta).clamp(-1.0, 1.0),
+            pos.zoom,
+        )
+        .await
+}
+
+/// A jog command held by the rate limiter until it may be sent.
+type QueuedCommand = Box<
+    dyn FnOnce(Arc<dyn PtzController>) -> Pin<Box<dyn Future<Output = Result<(), PtzError>> + Send>>
+        + Send,
+>;
+
+fn queued<F, Fut>(command: F) -> QueuedCommand
+where
+    F: FnOnce(Arc<dyn PtzController>) -> Fut + Send + 'static,
+    Fut: Future<Output = Result<(), PtzError>> + Send + 'static,
+{
+    Box::new(move |controller| Box::pin(command(controller)))
+}
+
+/// Holds a controller per endpoint id and routes PTZ commands.
+/// Controllers stay connected while another endpoint is active; the
+/// convenience methods below target the active one.
 pub struct PtzDispatcher {
-    controller: Option<Box<dyn PtzController>>,
+    controllers: HashMap<String, Arc<dyn PtzController>>,
+    active_id: Option<String>,
+    statuses: Arc<ConnectionStatuses>,
+    /// Caps jog commands per endpoint, coalescing bursts to the latest.
+    limiter: Arc<Mutex<RateLimiter<(String, QueuedCommand)>>>,
+    log: Arc<CommandLog>,
+    /// Interrupts a controller's multi-step command when a newer one arrives.
+    cancels: Arc<MoveCancels>,
+    /// Stops a continuous move that stops hearing from its operator.
+    deadman: Arc<Deadman>,
+    /// Fingerprint of the config each pooled controller was built from.
+    fingerprints: HashMap<String, u64>,
+    /// When each endpoint was last activated, as a running count.
+    last_used: HashMap<String, u64>,
+    activations: u64,
+    /// Log state-changing commands instead of sending them.
+    dry_run: bool,
+    /// How long relative moves drive cameras that nudge by time.
+    nudge_dwell: NudgeDwell,
 }
 
 impl PtzDispatcher {
     pub fn new() -> Self {
-        Self { controller: None }
+        Self {
+            controllers: HashMap::new(),
+            active_id: None,
+            statuses: Arc::new(ConnectionStatuses::new()),
+            limiter: Arc::new(Mutex::new(RateLimiter::new(DEFAULT_COMMANDS_PER_SECOND))),
+            log: Arc::new(CommandLog::default()),
+            cancels: Arc::new(MoveCancels::new()),
+            deadman: Arc::new(Deadman::new(DEFAULT_DEADMAN_WINDOW)),
+            fingerprints: HashMap::new(),
+            last_used: HashMap::new(),
+            activations: 0,
+            dry_run: false,
+            nudge_dwell: NudgeDwell::default(),
+        }
+    }
+
+    /// Register a controller for an endpoint, replacing any existing one.
+    /// Its connection status starts over as unknown.
+    pub fn add_controller(&mut self, id: impl Into<String>, controller: Box<dyn PtzController>) {
+        let id = id.into();
+        self.statuses.reset(&id);
+        self.limiter().remove(&id);
+        self.fingerprints.remove(&id);
+        self.cancels.register(&id, controller.cancel_token());
+        self.controllers.insert(id, Arc::from(controller));
+    }
+
+    /// Register a controller built from a config with the given
+    /// fingerprint, so [`Self::activate_pooled`] can reuse it.
+    pub fn add_pooled_controller(
+        &mut self,
+        id: impl Into<String>,
+        fingerprint: u64,
+        controller: Box<dyn PtzController>,
+    ) {
+        let id = id.into();
+        self.add_controller(id.clone(), controller);
+        self.fingerprints.insert(id, fingerprint);
+    }
+
+    /// Make `id` active, reusing its warm controller if it was built from
+    /// the config with this `fingerprint` and calling `build` otherwise.
+    /// Controllers beyond [`MAX_POOLED_CONTROLLERS`] are dropped, least
+    /// recently activated first.
+    pub fn activate_pooled<E>(
+        &mut self,
+        id: &str,
+        fingerprint: u64,
+        build: impl FnOnce() -> Result<Box<dyn PtzController>, E>,
+    ) -> Result<(), E> {
+        if self.fingerprints.get(id) != Some(&fingerprint) || !self.contains(id) {
+            self.add_pooled_controller(id, fingerprint, build()?);
+        }
+        self.active_id = Some(id.to_string());
+        self.activations += 1;
+        self.last_used.insert(id.to_string(), self.activations);
+        self.evict_least_recent();
+        Ok(())
+    }
+
+    fn evict_least_recent(&mut self) {
+        while self.controllers.len() > MAX_POOLED_CONTROLLERS {
+            let oldest = self
+                .controllers
+                .keys()
+                .filter(|id| self.active_id.as_deref() != Some(id.as_str()))
+                .min_by_key(|id| self.last_used.get(*id).copied().unwrap_or(0))
+                .cloned();
+            let Some(id) = oldest else {
+                break;
+            };
+            log::debug!("Dropping idle controller for endpoint {}", id);
+            self.remove_controller(&id);
+        }
+    }
+
+    /// Remove an endpoint's controller, deactivating it if it was active.
+    pub fn remove_controller(&mut self, id: &str) -> Option<Arc<dyn PtzController>> {
+        if self.active_id.as_deref() == Some(id) {
+            self.active_id = None;
+        }
+        self.statuses.remove(id);
+        self.limiter().remove(id);
+        self.cancels.remove(id);
+        self.deadman.disarm(id);
+        self.fingerprints.remove(id);
+        self.last_used.remove(id);
+        self.controllers.remove(id)
+    }
+
+    /// In dry-run mode, commands that would change the camera's state are
+    /// logged, with their encoded requests where the protocol can tell, and
+    /// never sent. Queries such as position reads still reach the camera.
+    pub fn set_dry_run(&mut self, enabled: bool) {
+        self.dry_run = enabled;
+    }
+
+    pub fn is_dry_run(&self) -> bool {
+        self.dry_run
+    }
+
+    /// Encode a command for the active camera without sending it.
+    pub fn preview(&self, command: &PtzCommand) -> Result<CommandPreview, PtzError> {
+        let id = self.active_id.as_deref().ok_or(PtzError::NotConnected)?;
+        Ok(CommandPreview {
+            endpoint_id: id.to_string(),
+            command: command.describe(),
+            requests: self.controller(id)?.encode(command)?,
+        })
+    }
+
+    /// Cap jog commands (moves, zoom and focus drives) to `per_second` per
+    /// endpoint; 0 removes the cap. Held commands are dropped.
+    pub fn set_command_rate(&self, per_second: u32) {
+        self.limiter().set_rate(per_second);
+    }
+
+    /// Stop a continuous move after `window` without a follow-up command or
+    /// keepalive; zero lets moves run until told to stop.
+    pub fn set_deadman_window(&self, window: std::time::Duration) {
+        self.deadman.set_window(window);
+    }
+
+    /// Drive times for relative moves on protocols that can't move by a
+    /// fixed amount.
+    pub fn set_nudge_dwell(&mut self, dwell: NudgeDwell) {
+        self.nudge_dwell = dwell;
+    }
+
+    fn limiter(&self) -> std::sync::MutexGuard<'_, RateLimiter<(String, QueuedCommand)>> {
+        self.limiter.lock().unwrap_or_else(|e| e.into_inner())
+    }
+
+    pub fn contains(&self, id: &str) -> bool {
+        self.controllers.contains_key(id)
+    }
+
+    pub fn controller_ids(&self) -> Vec<String> {
+        self.controllers.keys().cloned().collect()
+    }
+
+    /// Make a registered controller the target of the convenience methods.
+    pub fn set_active(&mut self, id: &str) -> Result<(), PtzError> {
+        if !self.controllers.contains_key(id) {
+            return Err(PtzError::NotConnected);
+        }
+        self.active_id = Some(id.to_string());
+        Ok(())
     }
 
-    pub fn set_controller(&mut self, controller: Box<dyn PtzController>) {
-        self.controller = Some(controller);
+    pub fn active_id(&self) -> Option<&str> {
+        self.active_id.as_deref()
     }
 
+    /// Deactivate without dropping the controller.
+    pub fn clear_active(&mut self) {
+        self.active_id = None;
+    }
+
+    /// Remove the active controller entirely.
     pub fn clear_controller(&mut self) {
-        self.controller = None;
+        if let Some(id) = self.active_id.take() {
+            self.statuses.remove(&id);
+            self.limiter().remove(&id);
+            self.cancels.remove(&id);
+            self.deadman.disarm(&id);
+            self.fingerprints.remove(&id);
+            self.last_used.remove(&id);
+            self.controllers.remove(&id);
+        }
     }
 
     pub fn has_controller(&self) -> bool {
-        self.controller.is_some()
+        self.get_controller().is_ok()
+    }
+
+    /// Whether the active controller moves smoothly on its own.
+    pub fn supports_timed_moves(&self) -> bool {
+        self.get_controller()
+            .map(|c| c.supports_timed_moves())
+            .unwrap_or(false)
+    }
+
+    /// Whether the active controller can report where it is.
+    pub fn supports_position(&self) -> bool {
+        self.get_controller()
+            .map(|c| c.capabilities().supports_position)
+            .unwrap_or(false)
+    }
+
+    /// Number of preset slots on the active controller's camera.
+    pub fn max_preset_slots(&self) -> u16 {
+        self.get_controller()
+            .map(|c| c.capabilities().max_preset_slots)
+            .unwrap_or(0)
+    }
+
+    /// Whether the active controller can move focus to an absolute position.
+    pub fn supports_focus_position(&self) -> bool {
+        self.get_controller()
+            .map(|c| c.capabilities().supports_focus_position)
+            .unwrap_or(false)
+    }
+
+    /// Tell the active controller where the given presets' native slots
+    /// point. Presets without a slot are skipped.
+    pub fn seed_native_presets(&self, presets: &[Preset]) {
+        let Ok(controller) = self.get_controller() else {
+            return;
+        };
+        for preset in presets {
+            if let Some(slot) = preset.native_slot {
+                controller.remember_native_preset(
+                    slot,
+                    PtzPosition {
+                        pan: preset.pan,
+                        tilt: preset.tilt,
+                        zoom: preset.zoom,
+                    },
+                );
+            }
+        }
+    }
+
+    /// The controller for a specific endpoint, for per-camera routing.
+    pub fn controller(&self, id: &str) -> Result<&dyn PtzController, PtzError> {
+        self.controllers
+            .get(id)
+            .map(|c| c.as_ref())
+            .ok_or(PtzError::NotConnected)
+    }
+
+    /// A handle to an endpoint's controller that outlives the dispatcher
+    /// lock, for slow queries that shouldn't hold up other commands.
+    pub fn shared_controller(&self, id: &str) -> Result<Arc<dyn PtzController>, PtzError> {
+        self.controllers
+            .get(id)
+            .cloned()
+            .ok_or(PtzError::NotConnected)
     }
 
     fn get_controller(&self) -> Result<&dyn PtzController, PtzError> {
-        self.controller.as_deref().ok_or(PtzError::NotConnected)
+        let id = self.active_id.as_deref().ok_or(PtzError::NotConnected)?;
+        self.controller(id)
+    }
+
+    /// Shared connection status of every registered endpoint.
+    pub fn statuses(&self) -> Arc<ConnectionStatuses> {
+        self.statuses.clone()
+    }
+
+    /// Shared log of the commands sent to every endpoint.
+    pub fn command_log(&self) -> Arc<CommandLog> {
+        self.log.clone()
+    }
+
+    /// Shared cancel tokens, for interrupting a command in progress without
+    /// waiting for the dispatcher.
+    pub fn move_cancels(&self) -> Arc<MoveCancels> {
+        self.cancels.clone()
+    }
+
+    /// Record the outcome of a command sent to a specific endpoint.
+    pub fn record<T>(&self, id: &str, result: Result<T, PtzError>) -> Result<T, PtzError> {
+        self.statuses.record(id, &result);
+        result
+    }
+
+    /// Run a command on the active controller, recording the outcome in its
+    /// connection status.
+    async fn tracked<'a, T, F, Fut>(&'a self, call: F) -> Result<T, PtzError>
+    where
+        F: FnOnce(&'a dyn PtzController) -> Fut,
+        Fut: Future<Output = Result<T, PtzError>>,
+    {
+        let id = self.active_id.as_deref().ok_or(PtzError::NotConnected)?;
+        let result = call(self.controller(id)?).await;
+        self.record(id, result)
+    }
+
+    /// Like `tracked`, for commands that change the camera's state: the
+    /// command is also added to the command log.
+    async fn dispatched<'a, T, F, Fut>(&'a self, command: String, call: F) -> Result<T, PtzError>
+    where
+        T: Default,
+        F: FnOnce(&'a dyn PtzController) -> Fut,
+        Fut: Future<Output = Result<T, PtzError>>,
+    {
+        let id = self.active_id.as_deref().ok_or(PtzError::NotConnected)?;
+        if self.dry_run {
+            self.skip_dry_run(id, command);
+            return Ok(T::default());
+        }
+        self.cancels.cancel(id);
+        self.deadman.disarm(id);
+        let result = call(self.controller(id)?).await;
+        self.log.record(id, command, &result);
+        self.record(id, result)
+    }
+
+    /// Like `dispatched`, for commands the protocol can encode; in dry-run
+    /// mode the would-be requests are logged.
+    async fn previewed<'a, F, Fut>(&'a self, command: PtzCommand, call: F) -> Result<(), PtzError>
+    where
+        F: FnOnce(&'a dyn PtzController) -> Fut,
+        Fut: Future<Output = Result<(), PtzError>>,
+    {
+        if self.dry_run {
+            match self.preview(&command) {
+                Ok(preview) => log::info!(
+                    "Dry run: {} would send {:?} to '{}'",
+                    preview.command,
+                    preview.requests,
+                    preview.endpoint_id
+                ),
+                Err(e) => log::debug!("Dry run: cannot preview {}: {}", command.describe(), e),
+            }
+        }
+        self.dispatched(command.describe(), call).await
+    }
+
+    /// Record a command that dry-run mode kept from the camera.
+    fn skip_dry_run(&self, id: &str, command: String) {
+        log::info!("Dry run: not sending {} to '{}'", command, id);
+        self.log
+            .record(id, format!("{} [dry run]", command), &Ok::<_, PtzError>(()));
+    }
+
+    /// Run a jog command on the active controller through the rate limiter.
+    /// Commands over the limit are held and only the latest per lane is sent
+    /// once the interval has passed, so the camera ends on the final intent.
+    async fn throttled(
+        &self,
+        lane: CommandLane,
+        description: String,
+        command: QueuedCommand,
+    ) -> Result<(), PtzError> {
+        let id = self.active_id.as_deref().ok_or(PtzError::NotConnected)?;
+        if self.dry_run {
+            self.skip_dry_run(id, description);
+            return Ok(());
+        }
+        let controller = self
+            .controllers
+            .get(id)
+            .cloned()
+            .ok_or(PtzError::NotConnected)?;
+        self.cancels.cancel(id);
+        let action = self
+            .limiter()
+            .offer(id, lane, (description, command), Instant::now());
+        match action {
+            RateAction::Send((description, command)) => {
+                let result = command(controller).await;
+                self.log.record(id, description, &result);
+                self.record(id, result)
+            }
+            RateAction::Schedule(delay) => {
+                let limiter = self.limiter.clone();
+                let statuses = self.statuses.clone();
+                let log = self.log.clone();
+                let id = id.to_string();
+                // The spawned task doesn't inherit the caller's timeout override
+                let override_timeout = timeout::current();
+                tokio::spawn(timeout::scoped(override_timeout, async move {
+                    tokio::time::sleep(delay).await;
+                    let commands = limiter
+                        .lock()
+                        .unwrap_or_else(|e| e.into_inner())
+                        .flush(&id, Instant::now());
+                    for (description, command) in commands {
+                        let result = command(controller.clone()).await;
+                        if let Err(e) = &result {
+                            log::warn!("Held command for '{}' failed: {}", id, e);
+                        }
+                        log.record(&id, description, &result);
+                        statuses.record(&id, &result);
+                    }
+                }));
+                Ok(())
+            }
+            RateAction::Coalesced => Ok(()),
+        }
+    }
+
+    /// Drop jog commands still held for the active endpoint, so they can't
+    /// land after a move that replaces them.
+    fn discard_held(&self) {
+        if let Some(id) = self.active_id.as_deref() {
+            self.limiter().discard(id);
+        }
     }
 
     pub async fn move_absolute(&self, pan: f64, tilt: f64, zoom: f64) -> Result<(), PtzError> {
-        self.get_controller()?.move_absolute(pan, tilt, zoom).await
+        self.discard_held();
+        self.previewed(PtzCommand::MoveAbsolute { pan, tilt, zoom }, |c| {
+            c.move_absolute(pan, tilt, zoom)
+        })
+        .await
     }
 
     pub async fn move_relative(&self, pan_delta: f64, tilt_delta: f64) -> Result<(), PtzError> {
-        self.get_controller()?
-            .move_relative(pan_delta, tilt_delta)
-            .await
+        let dwell = self.nudge_dwell;
+        self.throttled(
+            CommandLane::PanTilt,
+            format!("move_relative({:?}, {:?})", pan_delta, tilt_delta),
+            queued(move |c| async move {
+                nudge::scoped(dwell, c.move_relative(pan_delta, tilt_delta)).await
+            }),
+        )
+        .await
     }
 
     pub async fn zoom_to(&self, zoom: f64) -> Result<(), PtzError> {
-        self.get_controller()?.zoom_to(zoom).await
+        self.previewed(PtzCommand::ZoomTo { zoom }, |c| c.zoom_to(zoom))
+            .await
+    }
+
+    pub async fn zoom_by(&self, delta: f64) -> Result<(), PtzError> {
+        self.throttled(
+            CommandLane::Zoom,
+            format!("zoom_by({:?})", delta),
+            queued(move |c| async move { c.zoom_by(delta).await }),
+        )
+        .await
     }
 
     pub async fn recall_preset(&self, preset_index: u8) -> Result<(), PtzError> {
-        self.get_controller()?.recall_preset(preset_index).await
+        self.discard_held();
+        self.previewed(
+            PtzCommand::RecallPreset {
+                index: preset_index,
+            },
+            |c| c.recall_preset(preset_index),
+        )
+        .await
+    }
+
+    /// Move to a stored preset: through the camera's own preset memory when it
+    /// has a native slot, otherwise with an absolute move to its saved position.
+    /// Saved focus and white balance are applied after the move.
+    pub async fn recall_stored_preset(&self, preset: &Preset) -> Result<(), PtzError> {
+        match preset.native_slot {
+            Some(slot) => {
+                self.seed_native_presets(std::slice::from_ref(preset));
+                self.recall_preset(slot).await?
+            }
+            None => {
+                self.move_absolute(preset.pan, preset.tilt, preset.zoom)
+                    .await?
+            }
+        }
+        self.apply_preset_image(preset).await
+    }
+
+    /// Apply a preset's saved focus and white balance, if it has any. Where
+    /// the camera takes absolute focus, zoom is settled first since the focal
+    /// plane shifts with it.
+    pub async fn apply_preset_image(&self, preset: &Preset) -> Result<(), PtzError> {
+        match preset.focus {
+            Some(focus) if self.supports_focus_position() => {
+                self.focus_after_zoom(preset.zoom, focus).await?
+            }
+            Some(focus) => self.focus_to(focus).await?,
+            None => {}
+        }
+        if let Some(white_balance) = preset.white_balance {
+            self.set_white_balance(white_balance).await?;
+        }
+        Ok(())
     }
 
     pub async fn store_preset(&self, preset_index: u8) -> Result<(), PtzError> {
-        self.get_controller()?.store_preset(preset_index).await
+        self.previewed(
+            PtzCommand::StorePreset {
+                index: preset_index,
+            },
+            |c| c.store_preset(preset_index),
+        )
+        .await
     }
 
     pub async fn get_position(&self) -> Result<PtzPosition, PtzError> {
-        self.get_controller()?.get_position().await
+        self.tracked(|c| c.get_position()).await
+    }
+
+    pub async fn get_active_preset(&self) -> Result<Option<u8>, PtzError> {
+        self.tracked(|c| c.get_active_preset()).await
     }
 
     pub async fn test_connection(&self) -> Result<(), PtzError> {
-        self.get_controller()?.test_connection().await
+        self.tracked(|c| c.test_connection()).await
     }
 
     pub async fn home(&self) -> Result<(), PtzError> {
-        self.get_controller()?.home().await
+        self.discard_held();
+        self.dispatched("home()".to_string(), |c| c.home()).await
     }
 
+    /// Start or change a continuous move. The camera is stopped if neither
+    /// another command nor a keepalive follows within the deadman window.
     pub async fn continuous_move(&self, pan_speed: f64, tilt_speed: f64) -> Result<(), PtzError> {
-        self.get_controller()?
-            .continuous_move(pan_speed, tilt_speed)
-            .await
+        self.throttled(
+            CommandLane::PanTilt,
+            format!("continuous_move({:?}, {:?})", pan_speed, tilt_speed),
+            queued(move |c| async move { c.continuous_move(pan_speed, tilt_speed).await }),
+        )
+        .await?;
+        if pan_speed == 0.0 && tilt_speed == 0.0 {
+            if let Some(id) = self.active_id.as_deref() {
+                self.deadman.disarm(id);
+            }
+        } else if !self.dry_run {
+            self.arm_deadman()?;
+        }
+        Ok(())
+    }
+
+    /// Keep a held continuous move going past the deadman window.
+    pub fn continuous_move_keepalive(&self) -> Result<(), PtzError> {
+        let id = self.active_id.as_deref().ok_or(PtzError::NotConnected)?;
+        self.deadman.keepalive(id, Instant::now());
+        Ok(())
+    }
+
+    /// Start the deadman timer for the active endpoint's continuous move.
+    fn arm_deadman(&self) -> Result<(), PtzError> {
+        let id = self.active_id.as_deref().ok_or(PtzError::NotConnected)?;
+        let Some(generation) = self.deadman.arm(id, Instant::now()) else {
+            return Ok(());
+        };
+        let controller = self
+            .controllers
+            .get(id)
+            .cloned()
+            .ok_or(PtzError::NotConnected)?;
+        let limiter = self.limiter.clone();
+        let statuses = self.statuses.clone();
+        let log = self.log.clone();
+        let id = id.to_string();
+        tokio::spawn(deadman::watch(
+            self.deadman.clone(),
+            id.clone(),
+            generation,
+            move || async move {
+                log::warn!("No keepalive for '{}' mid-jog, stopping the camera", id);
+                limiter
+                    .lock()
+                    .unwrap_or_else(|e| e.into_inner())
+                    .discard(&id);
+                let result = controller.stop().await;
+                log.record(&id, "stop() [deadman]".to_string(), &result);
+                statuses.record(&id, &result);
+            },
+        ));
+        Ok(())
     }
 
     pub async fn stop(&self) -> Result<(), PtzError> {
-        self.get_controller()?.stop().await
+        if let Some(id) = self.active_id.as_deref() {
+            self.deadman.disarm(id);
+        }
+        self.throttled(
+            CommandLane::PanTilt,
+            "stop()".to_string(),
+            queued(|c| async move { c.stop().await }),
+        )
+        .await
+    }
+
+    pub async fn zoom_continuous(&self, speed: f64) -> Result<(), PtzError> {
+        self.throttled(
+            CommandLane::Zoom,
+            format!("zoom_continuous({:?})", speed),
+            queued(move |c| async move { c.zoom_continuous(speed).await }),
+        )
+        .await
     }
 
     pub async fn focus_continuous(&self, speed: f64) -> Result<(), PtzError> {
-        self.get_controller()?.focus_continuous(speed).await
+        self.throttled(
+            CommandLane::Focus,
+            format!("focus_continuous({:?})", speed),
+            queued(move |c| async move { c.focus_continuous(speed).await }),
+        )
+        .await
     }
 
     pub async fn set_autofocus(&self, enabled: bool) -> Result<(), PtzError> {
-        self.get_controller()?.set_autofocus(enabled).await
+        self.dispatched(format!("set_autofocus({:?})", enabled), |c| {
+            c.set_autofocus(enabled)
+        })
+        .await
     }
 
     pub async fn autofocus_trigger(&self) -> Result<(), PtzError> {
-        self.get_controller()?.autofocus_trigger().await
+        self.dispatched("autofocus_trigger()".to_string(), |c| c.autofocus_trigger())
+            .await
     }
 
     pub async fn focus_stop(&self) -> Result<(), PtzError> {
-        self.get_controller()?.focus_stop().await
+        self.throttled(
+            CommandLane::Focus,
+            "focus_stop()".to_string(),
+            queued(|c| async move { c.focus_stop().await }),
+        )
+        .await
+    }
+
+    pub async fn focus_to(&self, position: f64) -> Result<(), PtzError> {
+        self.dispatched(format!("focus_to({:?})", position), |c| {
+            c.focus_to(position)
+        })
+        .await
+    }
+
+    pub async fn focus_after_zoom(&self, zoom: f64, focus: f64) -> Result<(), PtzError> {
+        self.dispatched(format!("focus_after_zoom({:?}, {:?})", zoom, focus), |c| {
+            c.focus_after_zoom(zoom, focus)
+        })
+        .await
+    }
+
+    pub async fn get_focus(&self) -> Result<f64, PtzError> {
+        self.tracked(|c| c.get_focus()).await
+    }
+
+    pub async fn set_iris(&self, iris: f64) -> Result<(), PtzError> {
+        self.dispatched(format!("set_iris({:?})", iris), |c| c.set_iris(iris))
+            .await
+    }
+
+    pub async fn set_gain(&self, gain_db: u8) -> Result<(), PtzError> {
+        self.dispatched(format!("set_gain({:?})", gain_db), |c| c.set_gain(gain_db))
+            .await
+    }
+
+    pub async fn set_shutter(&self, step: u8) -> Result<(), PtzError> {
+        self.dispatched(format!("set_shutter({:?})", step), |c| c.set_shutter(step))
+            .await
+    }
+
+    pub async fn set_exposure_mode(&self, mode: ExposureMode) -> Result<(), PtzError> {
+        self.dispatched(format!("set_exposure_mode({:?})", mode), |c| {
+            c.set_exposure_mode(mode)
+        })
+        .await
+    }
+
+    pub async fn set_white_balance(&self, setting: WhiteBalanceSetting) -> Result<(), PtzError> {
+        self.dispatched(format!("set_white_balance({:?})", setting), |c| {
+            c.set_white_balance(setting)
+        })
+        .await
+    }
+
+    pub async fn set_image_flip(&self, enabled: bool) -> Result<(), PtzError> {
+        self.dispatched(format!("set_image_flip({:?})", enabled), |c| {
+            c.set_image_flip(enabled)
+        })
+        .await
+    }
+
+    pub async fn set_mirror(&self, enabled: bool) -> Result<(), PtzError> {
+        self.dispatched(format!("set_mirror({:?})", enabled), |c| {
+            c.set_mirror(enabled)
+        })
+        .await
+    }
+
+    pub async fn set_digital_zoom(&self, enabled: bool) -> Result<(), PtzError> {
+        self.dispatched(format!("set_digital_zoom({:?})", enabled), |c| {
+            c.set_digital_zoom(enabled)
+        })
+        .await
+    }
+
+    pub async fn set_freeze(&self, enabled: bool) -> Result<(), PtzError> {
+        self.dispatched(format!("set_freeze({:?})", enabled), |c| {
+            c.set_freeze(enabled)
+        })
+        .await
+    }
+
+    pub async fn reposition_frozen(&self, pan: f64, tilt: f64, zoom: f64) -> Result<(), PtzError> {
+        self.discard_held();
+        self.dispatched(
+            format!("reposition_frozen({:?}, {:?}, {:?})", pan, tilt, zoom),
+            |c| c.reposition_frozen(pan, tilt, zoom),
+        )
+        .await
+    }
+
+    pub async fn menu_toggle(&self) -> Result<(), PtzError> {
+        self.dispatched("menu_toggle()".to_string(), |c| c.menu_toggle())
+            .await
+    }
+
+    pub async fn menu_navigate(&self, direction: MenuDirection) -> Result<(), PtzError> {
+        self.dispatched(format!("menu_navigate({:?})", direction), |c| {
+            c.menu_navigate(direction)
+        })
+        .await
+    }
+
+    pub async fn menu_enter(&self) -> Result<(), PtzError> {
+        self.dispatched("menu_enter()".to_string(), |c| c.menu_enter())
+            .await
+    }
+
+    pub async fn set_tally(&self, state: TallyState) -> Result<(), PtzError> {
+        self.dispatched(format!("set_tally({:?})", state), |c| c.set_tally(state))
+            .await
+    }
+
+    pub async fn set_ndi_name(&self, name: &str) -> Result<(), PtzError> {
+        self.dispatched(format!("set_ndi_name({:?})", name), |c| {
+            c.set_ndi_name(name)
+        })
+        .await
+    }
+
+    pub async fn set_gamma(&self, mode: u8) -> Result<(), PtzError> {
+        self.dispatched(format!("set_gamma({:?})", mode), |c| c.set_gamma(mode))
+            .await
+    }
+
+    pub async fn recall_picture_profile(&self, index: u8) -> Result<(), PtzError> {
+        self.dispatched(format!("recall_picture_profile({:?})", index), |c| {
+            c.recall_picture_profile(index)
+        })
+        .await
+    }
+
+    pub async fn send_raw(&self, payload: &[u8]) -> Result<Vec<u8>, PtzError> {
+        self.dispatched(format!("send_raw({:02X?})", payload), |c| {
+            c.send_raw(payload)
+        })
+        .await
+    }
+
+    pub async fn visca_diagnostics(&self) -> Result<ViscaDiagnostics, PtzError> {
+        let id = self.active_id.as_deref().ok_or(PtzError::NotConnected)?;
+        self.controller(id)?.visca_diagnostics().await
+    }
+
+    pub async fn reset_sequence(&self) -> Result<(), PtzError> {
+        self.dispatched("reset_sequence()".to_string(), |c| c.reset_sequence())
+            .await
     }
 }
 
@@ -174,3 +1145,528 @@ impl Default for PtzDispatcher {
         Self::new()
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::simulator::client::SimulatedController;
+    use std::time::Duration;
+
+    fn dispatcher_with_two() -> PtzDispatcher {
+        let mut dispatcher = PtzDispatcher::new();
+        dispatcher.add_controller("cam-a", Box::new(SimulatedController::new()));
+        dispatcher.add_controller("cam-b", Box::new(SimulatedController::new()));
+        dispatcher
+    }
+
+    #[tokio::test]
+    async fn no_active_controller_is_not_connected() {
+        let dispatcher = dispatcher_with_two();
+        assert!(!dispatcher.has_controller());
+        assert!(matches!(
+            dispatcher.move_absolute(0.1, 0.1, 0.1).await,
+            Err(PtzError::NotConnected)
+        ));
+    }
+
+    #[tokio::test]
+    async fn active_commands_route_to_active_controller() {
+        let mut dispatcher = dispatcher_with_two();
+        dispatcher.set_active("cam-a").unwrap();
+        dispatcher.move_absolute(0.5, 0.25, 0.75).await.unwrap();
+
+        let a = dispatcher
+            .controller("cam-a")
+            .unwrap()
+            .get_position()
+            .await
+            .unwrap();
+        let b = dispatcher
+            .controller("cam-b")
+            .unwrap()
+            .get_position()
+            .await
+            .unwrap();
+        assert_eq!((a.pan, a.tilt, a.zoom), (0.5, 0.25, 0.75));
+        assert_eq!((b.pan, b.tilt, b.zoom), (0.0, 0.0, 0.0));
+    }
+
+    #[tokio::test]
+    async fn per_id_routing_leaves_active_untouched() {
+        let mut dispatcher = dispatcher_with_two();
+        dispatcher.set_active("cam-a").unwrap();
+        dispatcher
+            .controller("cam-b")
+            .unwrap()
+            .move_absolute(-0.5, 0.0, 0.0)
+            .await
+            .unwrap();
+
+        assert_eq!(dispatcher.get_position().await.unwrap().pan, 0.0);
+        dispatcher.set_active("cam-b").unwrap();
+        assert_eq!(dispatcher.get_position().await.unwrap().pan, -0.5);
+    }
+
+    #[test]
+    fn set_active_requires_registered_controller() {
+        let mut dispatcher = dispatcher_with_two();
+        assert!(dispatcher.set_active("cam-c").is_err());
+        assert_eq!(dispatcher.active_id(), None);
+    }
+
+    #[test]
+    fn remove_controller_clears_active() {
+        let mut dispatcher = dispatcher_with_two();
+        dispatcher.set_active("cam-a").unwrap();
+        assert!(dispatcher.remove_controller("cam-a").is_some());
+        assert_eq!(dispatcher.active_id(), None);
+        assert!(!dispatcher.contains("cam-a"));
+        assert!(dispatcher.contains("cam-b"));
+    }
+
+    fn stored_preset(native_slot: Option<u8>) -> Preset {
+        Preset {
+            id: "p1".to_string(),
+            name: "Pulpit".to_string(),
+            pan: -0.25,
+            tilt: 0.1,
+            zoom: 0.2,
+            color: "#fff".to_string(),
+            native_slot,
+            focus: None,
+            white_balance: None,
+            hotkey: None,
+            thumbnail: None,
+        }
+    }
+
+    #[tokio::test]
+    async fn commands_update_active_endpoint_status() {
+        use super::super::connection_status::ConnectionStatus;
+        use crate::simulator::client::FailureMode;
+
+        let failing = SimulatedController::new();
+        failing.set_failure_mode(FailureMode::AlwaysTimeout);
+        let mut dispatcher = dispatcher_with_two();
+        dispatcher.add_controller("cam-c", Box::new(failing));
+        let statuses = dispatcher.statuses();
+        assert_eq!(statuses.get("cam-a"), ConnectionStatus::Unknown);
+
+        dispatcher.set_active("cam-a").unwrap();
+        dispatcher.move_absolute(0.1, 0.1, 0.1).await.unwrap();
+        assert_eq!(statuses.get("cam-a"), ConnectionStatus::Ok);
+
+        dispatcher.set_active("cam-c").unwrap();
+        assert!(dispatcher.get_position().await.is_err());
+        assert!(matches!(
+            statuses.get("cam-c"),
+            ConnectionStatus::Failed { .. }
+        ));
+        // Other endpoints are untouched
+        assert_eq!(statuses.get("cam-a"), ConnectionStatus::Ok);
+        assert_eq!(statuses.get("cam-b"), ConnectionStatus::Unknown);
+
+        dispatcher.remove_controller("cam-c");
+        assert!(!statuses.snapshot().contains_key("cam-c"));
+    }
+
+    #[tokio::test]
+    async fn relative_via_absolute_lands_on_current_plus_delta() {
+        let camera = SimulatedController::new();
+        camera.move_absolute(0.25, -0.5, 0.4).await.unwrap();
+
+        move_relative_via_absolute(&camera, 0.125, 0.25)
+            .await
+            .unwrap();
+        let pos = camera.get_position().await.unwrap();
+        assert_eq!((pos.pan, pos.tilt, pos.zoom), (0.375, -0.25, 0.4));
+
+        // Repeated nudges accumulate exactly and clamp at the range edge
+        for _ in 0..3 {
+            move_relative_via_absolute(&camera, 0.25, 0.0)
+                .await
+                .unwrap();
+        }
+        assert_eq!(camera.get_position().await.unwrap().pan, 1.0);
+    }
+
+    #[tokio::test]
+    async fn stored_preset_with_native_slot_uses_camera_memory() {
+        let mut dispatcher = dispatcher_with_two();
+        dispatcher.set_active("cam-a").unwrap();
+        // Slot 3 holds a different position than the stored preset
+        dispatcher.move_absolute(0.5, 0.5, 0.5).await.unwrap();
+        dispatcher.store_preset(3).await.unwrap();
+        dispatcher.home().await.unwrap();
+
+        dispatcher
+            .recall_stored_preset(&stored_preset(Some(3)))
+            .await
+            .unwrap();
+        let pos = dispatcher.get_position().await.unwrap();
+        assert_eq!((pos.pan, pos.tilt, pos.zoom), (0.5, 0.5, 0.5));
+
+        // An empty slot surfaces the camera's error instead of moving
+        assert!(dispatcher
+            .recall_stored_preset(&stored_preset(Some(9)))
+            .await
+            .is_err());
+    }
+
+    #[tokio::test]
+    async fn stored_preset_without_native_slot_moves_absolute() {
+        let mut dispatcher = dispatcher_with_two();
+        dispatcher.set_active("cam-a").unwrap();
+        dispatcher
+            .recall_stored_preset(&stored_preset(None))
+            .await
+            .unwrap();
+        let pos = dispatcher.get_position().await.unwrap();
+        assert_eq!((pos.pan, pos.tilt, pos.zoom), (-0.25, 0.1, 0.2));
+    }
+
+    #[tokio::test]
+    async fn stored_preset_applies_saved_focus() {
+        let mut dispatcher = dispatcher_with_two();
+        dispatcher.set_active("cam-a").unwrap();
+
+        let preset = Preset {
+            focus: Some(0.35),
+            white_balance: Some(WhiteBalanceSetting::Outdoor),
+            ..stored_preset(None)
+        };
+        dispatcher.recall_stored_preset(&preset).await.unwrap();
+        assert_eq!(dispatcher.get_focus().await.unwrap(), 0.35);
+
+        // A preset without saved focus leaves the lens where it is
+        dispatcher
+            .recall_stored_preset(&stored_preset(None))
+            .await
+            .unwrap();
+        assert_eq!(dispatcher.get_focus().await.unwrap(), 0.35);
+    }
+
+    #[tokio::test]
+    async fn stored_preset_focuses_once_zoom_has_settled() {
+        let mut dispatcher = PtzDispatcher::new();
+        let camera = SimulatedController::new().with_slew_rate(20.0);
+        dispatcher.add_controller("cam", Box::new(camera));
+        dispatcher.set_active("cam").unwrap();
+
+        let preset = Preset {
+            zoom: 0.8,
+            focus: Some(0.35),
+            ..stored_preset(None)
+        };
+        dispatcher.recall_stored_preset(&preset).await.unwrap();
+        assert_eq!(dispatcher.get_position().await.unwrap().zoom, 0.8);
+        assert_eq!(dispatcher.get_focus().await.unwrap(), 0.35);
+        let sent: Vec<String> = dispatcher
+            .command_log()
+            .recent(10)
+            .into_iter()
+            .map(|entry| entry.command)
+            .collect();
+        assert_eq!(
+            sent,
+            [
+                "move_absolute(-0.25, 0.1, 0.8)",
+                "focus_after_zoom(0.8, 0.35)"
+            ]
+        );
+    }
+
+    #[test]
+    fn clear_active_keeps_controller_warm() {
+        let mut dispatcher = dispatcher_with_two();
+        dispatcher.set_active("cam-a").unwrap();
+        dispatcher.clear_active();
+        assert!(!dispatcher.has_controller());
+        assert!(dispatcher.contains("cam-a"));
+    }
+
+    #[tokio::test]
+    async fn jog_burst_sends_the_first_and_last_commands() {
+        let mut dispatcher = dispatcher_with_two();
+        dispatcher.set_active("cam-a").unwrap();
+        for _ in 0..10 {
+            dispatcher.move_relative(0.01, 0.0).await.unwrap();
+        }
+        dispatcher.move_relative(0.1, 0.0).await.unwrap();
+        tokio::time::sleep(Duration::from_millis(100)).await;
+
+        let pan = dispatcher.get_position().await.unwrap().pan;
+        assert!((pan - 0.11).abs() < 1e-9, "pan was {}", pan);
+    }
+
+    #[tokio::test]
+    async fn preset_recall_drops_held_jog_commands() {
+        let mut dispatcher = dispatcher_with_two();
+        dispatcher.set_active("cam-a").unwrap();
+        dispatcher.move_relative(0.1, 0.0).await.unwrap();
+        dispatcher.move_relative(0.1, 0.0).await.unwrap();
+        dispatcher.move_absolute(-0.5, 0.0, 0.0).await.unwrap();
+        tokio::time::sleep(Duration::from_millis(100)).await;
+
+        assert_eq!(dispatcher.get_position().await.unwrap().pan, -0.5);
+    }
+
+    /// Records the reply timeout each relative move would wait.
+    struct TimeoutProbe(Arc<Mutex<Vec<Duration>>>);
+
+    #[async_trait]
+    impl PtzController for TimeoutProbe {
+        async fn move_absolute(&self, _: f64, _: f64, _: f64) -> Result<(), PtzError> {
+            Ok(())
+        }
+        async fn move_relative(&self, _: f64, _: f64) -> Result<(), PtzError> {
+            let waited = timeout::or_default(Duration::from_secs(5));
+            self.0.lock().unwrap().push(waited);
+            Ok(())
+        }
+        async fn zoom_to(&self, _: f64) -> Result<(), PtzError> {
+            Ok(())
+        }
+        async fn recall_preset(&self, _: u8) -> Result<(), PtzError> {
+            Ok(())
+        }
+        async fn store_preset(&self, _: u8) -> Result<(), PtzError> {
+            Ok(())
+        }
+        async fn get_position(&self) -> Result<PtzPosition, PtzError> {
+            Ok(PtzPosition::default())
+        }
+        async fn test_connection(&self) -> Result<(), PtzError> {
+            Ok(())
+        }
+    }
+
+    #[tokio::test]
+    async fn held_commands_keep_the_timeout_override() {
+        let waits = Arc::new(Mutex::new(Vec::new()));
+        let mut dispatcher = PtzDispatcher::new();
+        dispatcher.add_controller("probe", Box::new(TimeoutProbe(waits.clone())));
+        dispatcher.set_active("probe").unwrap();
+
+        let short = Duration::from_millis(50);
+        timeout::scoped(Some(short), async {
+            dispatcher.move_relative(0.1, 0.0).await.unwrap();
+            dispatcher.move_relative(0.1, 0.0).await.unwrap();
+        })
+        .await;
+        tokio::time::sleep(Duration::from_millis(250)).await;
+
+        assert_eq!(*waits.lock().unwrap(), [short, short]);
+    }
+
+    #[tokio::test]
+    async fn unlimited_rate_sends_every_command() {
+        let mut dispatcher = dispatcher_with_two();
+        dispatcher.set_active("cam-a").unwrap();
+        dispatcher.set_command_rate(0);
+        for _ in 0..10 {
+            dispatcher.move_relative(0.01, 0.0).await.unwrap();
+        }
+        let pan = dispatcher.get_position().await.unwrap().pan;
+        assert!((pan - 0.1).abs() < 1e-9, "pan was {}", pan);
+    }
+
+    #[tokio::test]
+    async fn dispatched_commands_are_logged() {
+        let mut dispatcher = dispatcher_with_two();
+        dispatcher.set_active("cam-a").unwrap();
+        dispatcher.move_absolute(0.5, 0.25, 0.0).await.unwrap();
+        dispatcher.get_position().await.unwrap();
+        dispatcher.continuous_move(0.2, 0.0).await.unwrap();
+        dispatcher.set_active("cam-b").unwrap();
+        assert!(dispatcher.recall_preset(3).await.is_err());
+
+        let entries = dispatcher.command_log().recent(10);
+        let logged: Vec<_> = entries
+            .iter()
+            .map(|e| (e.endpoint_id.as_str(), e.command.as_str()))
+            .collect();
+        assert_eq!(
+            logged,
+            [
+                ("cam-a", "move_absolute(0.5, 0.25, 0.0)"),
+                ("cam-a", "continuous_move(0.2, 0.0)"),
+                ("cam-b", "recall_preset(3)"),
+            ]
+        );
+        assert!(entries[..2].iter().all(|e| e.error.is_none()));
+        assert!(entries[2].error.as_deref().unwrap().contains("No preset"));
+    }
+
+    #[tokio::test]
+    async fn continuous_move_stops_without_a_keepalive() {
+        let mut dispatcher = dispatcher_with_two();
+        dispatcher.set_active("cam-a").unwrap();
+        dispatcher.set_deadman_window(Duration::from_millis(50));
+        dispatcher.continuous_move(1.0, 0.0).await.unwrap();
+        tokio::time::sleep(Duration::from_millis(120)).await;
+
+        let stopped_at = dispatcher.get_position().await.unwrap().pan;
+        assert!(stopped_at > 0.0);
+        tokio::time::sleep(Duration::from_millis(50)).await;
+        assert_eq!(dispatcher.get_position().await.unwrap().pan, stopped_at);
+        let last = dispatcher.command_log().recent(1).pop().unwrap();
+        assert_eq!(last.command, "stop() [deadman]");
+    }
+
+    #[tokio::test]
+    async fn keepalives_hold_a_continuous_move() {
+        let mut dispatcher = dispatcher_with_two();
+        dispatcher.set_active("cam-a").unwrap();
+        dispatcher.set_deadman_window(Duration::from_millis(50));
+        dispatcher.continuous_move(1.0, 0.0).await.unwrap();
+        for _ in 0..6 {
+            tokio::time::sleep(Duration::from_millis(20)).await;
+            dispatcher.continuous_move_keepalive().unwrap();
+        }
+        let pan = dispatcher.get_position().await.unwrap().pan;
+        tokio::time::sleep(Duration::from_millis(20)).await;
+        assert!(dispatcher.get_position().await.unwrap().pan > pan);
+        assert!(dispatcher
+            .command_log()
+            .recent(10)
+            .iter()
+            .all(|entry| entry.command != "stop() [deadman]"));
+
+        // An explicit stop disarms the timer, so it never fires on its own
+        dispatcher.stop().await.unwrap();
+        tokio::time::sleep(Duration::from_millis(100)).await;
+        let last = dispatcher.command_log().recent(1).pop().unwrap();
+        assert_eq!(last.command, "stop()");
+    }
+
+    fn counting_build(
+        builds: &std::sync::atomic::AtomicUsize,
+    ) -> impl FnOnce() -> Result<Box<dyn PtzController>, String> + '_ {
+        move || {
+            builds.fetch_add(1, std::sync::atomic::Ordering::SeqCst);
+            Ok(Box::new(SimulatedController::new()))
+        }
+    }
+
+    #[tokio::test]
+    async fn switching_back_reuses_the_warm_controller() {
+        let builds = std::sync::atomic::AtomicUsize::new(0);
+        let mut dispatcher = PtzDispatcher::new();
+        dispatcher
+            .activate_pooled("cam-a", 1, counting_build(&builds))
+            .unwrap();
+        dispatcher.zoom_to(0.5).await.unwrap();
+        dispatcher
+            .activate_pooled("cam-b", 2, counting_build(&builds))
+            .unwrap();
+        dispatcher
+            .activate_pooled("cam-a", 1, counting_build(&builds))
+            .unwrap();
+
+        assert_eq!(builds.load(std::sync::atomic::Ordering::SeqCst), 2);
+        assert_eq!(dispatcher.active_id(), Some("cam-a"));
+        // Same instance, so it still remembers the zoom
+        assert!((dispatcher.get_position().await.unwrap().zoom - 0.5).abs() < 1e-9);
+    }
+
+    #[test]
+    fn changed_config_rebuilds_the_controller() {
+        let builds = std::sync::atomic::AtomicUsize::new(0);
+        let mut dispatcher = PtzDispatcher::new();
+        dispatcher
+            .activate_pooled("cam-a", 1, counting_build(&builds))
+            .unwrap();
+        dispatcher
+            .activate_pooled("cam-a", 7, counting_build(&builds))
+            .unwrap();
+        assert_eq!(builds.load(std::sync::atomic::Ordering::SeqCst), 2);
+
+        // A controller added without a fingerprint is never reused
+        dispatcher.add_controller("cam-b", Box::new(SimulatedController::new()));
+        dispatcher
+            .activate_pooled("cam-b", 1, counting_build(&builds))
+            .unwrap();
+        assert_eq!(builds.load(std::sync::atomic::Ordering::SeqCst), 3);
+    }
+
+    #[test]
+    fn pool_drops_the_least_recently_used() {
+        let builds = std::sync::atomic::AtomicUsize::new(0);
+        let mut dispatcher = PtzDispatcher::new();
+        for n in 0..MAX_POOLED_CONTROLLERS {
+            dispatcher
+                .activate_pooled(&format!("cam-{}", n), 0, counting_build(&builds))
+                .unwrap();
+        }
+        // Touch cam-0 so cam-1 becomes the oldest
+        dispatcher
+            .activate_pooled("cam-0", 0, counting_build(&builds))
+            .unwrap();
+        dispatcher
+            .activate_pooled("cam-new", 0, counting_build(&builds))
+            .unwrap();
+
+        assert_eq!(dispatcher.controller_ids().len(), MAX_POOLED_CONTROLLERS);
+        assert!(dispatcher.contains("cam-0"));
+        assert!(!dispatcher.contains("cam-1"));
+        assert_eq!(dispatcher.active_id(), Some("cam-new"));
+    }
+
+    #[test]
+    fn failed_build_leaves_the_active_endpoint_alone() {
+        let builds = std::sync::atomic::AtomicUsize::new(0);
+        let mut dispatcher = PtzDispatcher::new();
+        dispatcher
+            .activate_pooled("cam-a", 1, counting_build(&builds))
+            .unwrap();
+        let result = dispatcher.activate_pooled("cam-b", 1, || Err("unreachable".to_string()));
+        assert!(result.is_err());
+        assert_eq!(dispatcher.active_id(), Some("cam-a"));
+        assert!(!dispatcher.contains("cam-b"));
+    }
+
+    #[tokio::test]
+    async fn dry_run_logs_without_sending() {
+        let mut dispatcher = dispatcher_with_two();
+        dispatcher.set_active("cam-a").unwrap();
+        dispatcher.set_dry_run(true);
+        dispatcher.move_absolute(0.5, 0.25, 0.5).await.unwrap();
+        dispatcher.continuous_move(0.2, 0.0).await.unwrap();
+
+        assert_eq!(
+            dispatcher.get_position().await.unwrap(),
+            PtzPosition::default()
+        );
+        let logged: Vec<_> = dispatcher
+            .command_log()
+            .recent(10)
+            .into_iter()
+            .map(|e| e.command)
+            .collect();
+        assert_eq!(
+            logged,
+            [
+                "move_absolute(0.5, 0.25, 0.5) [dry run]",
+                "continuous_move(0.2, 0.0) [dry run]"
+            ]
+        );
+
+        dispatcher.set_dry_run(false);
+        dispatcher.move_absolute(0.5, 0.25, 0.5).await.unwrap();
+        assert_eq!(dispatcher.get_position().await.unwrap().pan, 0.5);
+    }
+
+    #[test]
+    fn preview_reports_unsupported_protocols() {
+        let mut dispatcher = dispatcher_with_two();
+        assert!(matches!(
+            dispatcher.preview(&PtzCommand::ZoomTo { zoom: 0.5 }),
+            Err(PtzError::NotConnected)
+        ));
+        dispatcher.set_active("cam-a").unwrap();
+        assert!(dispatcher
+            .preview(&PtzCommand::ZoomTo { zoom: 0.5 })
+            .is_err());
+    }
+}
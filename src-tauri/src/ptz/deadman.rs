@@ -0,0 +1,190 @@
+use std::collections::HashMap;
+use std::future::Future;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+
+/// How long a continuous move runs without a follow-up command before the
+/// camera is stopped.
+pub const DEFAULT_DEADMAN_WINDOW: Duration = Duration::from_millis(500);
+
+/// How often a sender holding a steady jog should send a keepalive.
+pub const KEEPALIVE_INTERVAL: Duration = Duration::from_millis(100);
+
+struct Armed {
+    generation: u64,
+    deadline: Instant,
+}
+
+/// What a timer's watcher should do next.
+#[derive(Debug, PartialEq)]
+pub enum Watch {
+    /// The move ended or was replaced; nothing to stop.
+    Disarmed,
+    /// Check again after this long.
+    Wait(Duration),
+    /// Nothing arrived in time: stop the camera.
+    Expired,
+}
+
+/// Stops a camera left in a continuous move once its operator goes quiet,
+/// e.g. the UI crashed or a control connection dropped mid-jog. Each
+/// continuous move arms the timer for its endpoint and keepalives push the
+/// deadline back.
+pub struct Deadman {
+    window: Mutex<Duration>,
+    armed: Mutex<HashMap<String, Armed>>,
+    generations: AtomicU64,
+}
+
+impl Deadman {
+    /// A zero `window` turns the timer off.
+    pub fn new(window: Duration) -> Self {
+        Self {
+            window: Mutex::new(window),
+            armed: Mutex::new(HashMap::new()),
+            generations: AtomicU64::new(0),
+        }
+    }
+
+    fn lock(&self) -> std::sync::MutexGuard<'_, HashMap<String, Armed>> {
+        self.armed.lock().unwrap_or_else(|e| e.into_inner())
+    }
+
+    fn window(&self) -> Duration {
+        *self.window.lock().unwrap_or_else(|e| e.into_inner())
+    }
+
+    /// Applies to timers armed from now on.
+    pub fn set_window(&self, window: Duration) {
+        *self.window.lock().unwrap_or_else(|e| e.into_inner()) = window;
+    }
+
+    /// Start the timer for a continuous move on `id`, replacing any running
+    /// one. Returns the generation its watcher follows, or `None` when the
+    /// timer is off.
+    pub fn arm(&self, id: &str, now: Instant) -> Option<u64> {
+        let window = self.window();
+        if window.is_zero() {
+            self.disarm(id);
+            return None;
+        }
+        let generation = self.generations.fetch_add(1, Ordering::SeqCst) + 1;
+        self.lock().insert(
+            id.to_string(),
+            Armed {
+                generation,
+                deadline: now + window,
+            },
+        );
+        Some(generation)
+    }
+
+    /// Push back the deadline of `id`'s timer. Returns whether one was armed.
+    pub fn keepalive(&self, id: &str, now: Instant) -> bool {
+        let window = self.window();
+        match self.lock().get_mut(id) {
+            Some(armed) => {
+                armed.deadline = armed.deadline.max(now + window);
+                true
+            }
+            None => false,
+        }
+    }
+
+    /// The move on `id` ended; its timer no longer applies.
+    pub fn disarm(&self, id: &str) {
+        self.lock().remove(id);
+    }
+
+    /// What the watcher of `generation` should do at `now`. An expired timer
+    /// is disarmed, so it fires once.
+    pub fn check(&self, id: &str, generation: u64, now: Instant) -> Watch {
+        let mut armed = self.lock();
+        let deadline = match armed.get(id) {
+            Some(timer) if timer.generation == generation => timer.deadline,
+            _ => return Watch::Disarmed,
+        };
+        if now < deadline {
+            return Watch::Wait(deadline - now);
+        }
+        armed.remove(id);
+        Watch::Expired
+    }
+}
+
+/// Follow an armed timer until it is disarmed or expires, calling `stop` if
+/// it expires.
+pub async fn watch<F, Fut>(deadman: Arc<Deadman>, id: String, generation: u64, stop: F)
+where
+    F: FnOnce() -> Fut,
+    Fut: Future<Output = ()>,
+{
+    loop {
+        match deadman.check(&id, generation, Instant::now()) {
+            Watch::Disarmed => return,
+            Watch::Wait(delay) => tokio::time::sleep(delay).await,
+            Watch::Expired => {
+                stop().await;
+                return;
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const WINDOW: Duration = Duration::from_millis(500);
+
+    #[test]
+    fn timer_expires_once_without_keepalives() {
+        let deadman = Deadman::new(WINDOW);
+        let start = Instant::now();
+        let generation = deadman.arm("cam", start).unwrap();
+        assert_eq!(
+            deadman.check("cam", generation, start + Duration::from_millis(200)),
+            Watch::Wait(Duration::from_millis(300))
+        );
+        assert_eq!(
+            deadman.check("cam", generation, start + WINDOW),
+            Watch::Expired
+        );
+        assert_eq!(
+            deadman.check("cam", generation, start + WINDOW),
+            Watch::Disarmed
+        );
+    }
+
+    #[test]
+    fn keepalive_pushes_the_deadline_back() {
+        let deadman = Deadman::new(WINDOW);
+        let start = Instant::now();
+        let generation = deadman.arm("cam", start).unwrap();
+        assert!(deadman.keepalive("cam", start + Duration::from_millis(400)));
+        assert_eq!(
+            deadman.check("cam", generation, start + WINDOW),
+            Watch::Wait(Duration::from_millis(400))
+        );
+        assert!(!deadman.keepalive("other", start));
+    }
+
+    #[test]
+    fn rearming_or_disarming_retires_the_old_watcher() {
+        let deadman = Deadman::new(WINDOW);
+        let start = Instant::now();
+        let first = deadman.arm("cam", start).unwrap();
+        let second = deadman.arm("cam", start).unwrap();
+        assert_eq!(deadman.check("cam", first, start + WINDOW), Watch::Disarmed);
+
+        deadman.disarm("cam");
+        assert_eq!(
+            deadman.check("cam", second, start + WINDOW),
+            Watch::Disarmed
+        );
+
+        deadman.set_window(Duration::ZERO);
+        assert_eq!(deadman.arm("cam", start), None);
+    }
+}
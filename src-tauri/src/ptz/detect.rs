@@ -0,0 +1,142 @@
+use super::controller::{PtzController, PtzError};
+use super::retry::RetryPolicy;
+use super::timeout;
+use super::types::PtzProtocol;
+use crate::birddog::client::BirdDogClient;
+use crate::panasonic::client::PanasonicClient;
+use crate::visca::client::ViscaClient;
+use std::future::Future;
+use std::time::Duration;
+
+/// How long each probe may take before it counts as no answer.
+pub const PROBE_TIMEOUT: Duration = Duration::from_millis(1500);
+
+/// Port the HTTP probes try when none are given.
+pub const DEFAULT_HTTP_PORT: u16 = 80;
+
+/// UDP ports the VISCA probe tries when none are given: Sony's
+/// VISCA-over-IP port, then the port most PTZOptics-style cameras use.
+pub const DEFAULT_VISCA_PORTS: &[u16] = &[52381, 1259];
+
+/// Find out which protocol a camera at `host` speaks. BirdDog (`GET
+/// /about`), Panasonic AW (`#O` power query) and a VISCA position inquiry
+/// are tried in that order on each of `ports`, or on the usual ports when
+/// `ports` is empty. Returns the first that answers as expected.
+pub async fn detect_protocol(
+    host: &str,
+    ports: &[u16],
+    probe_timeout: Duration,
+) -> Option<PtzProtocol> {
+    let (http_ports, visca_ports) = if ports.is_empty() {
+        (&[DEFAULT_HTTP_PORT][..], DEFAULT_VISCA_PORTS)
+    } else {
+        (ports, ports)
+    };
+
+    for &port in http_ports {
+        let birddog = BirdDogClient::new(host, port)
+            .ok()?
+            .with_retry_policy(RetryPolicy::none());
+        if answers(probe_timeout, birddog.test_connection()).await {
+            return Some(PtzProtocol::BirdDogRest);
+        }
+        let panasonic = PanasonicClient::new(host, port)
+            .ok()?
+            .with_retry_policy(RetryPolicy::none());
+        if answers(probe_timeout, panasonic.check_power_reply()).await {
+            return Some(PtzProtocol::PanasonicAw);
+        }
+    }
+    for &port in visca_ports {
+        let visca = ViscaClient::new(host, port).ok()?;
+        if answers(probe_timeout, visca.test_connection()).await {
+            return Some(PtzProtocol::Visca);
+        }
+    }
+    None
+}
+
+/// Run one probe, capped at `limit` both per request and overall.
+async fn answers(limit: Duration, probe: impl Future<Output = Result<(), PtzError>>) -> bool {
+    let result = tokio::time::timeout(limit, timeout::scoped(Some(limit), probe)).await;
+    match result {
+        Ok(Ok(())) => true,
+        Ok(Err(e)) => {
+            log::debug!("Protocol probe failed: {}", e);
+            false
+        }
+        Err(_) => false,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::visca::commands;
+    use axum::routing::get;
+
+    const TEST_TIMEOUT: Duration = Duration::from_millis(300);
+
+    async fn serve(app: axum::Router) -> u16 {
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let port = listener.local_addr().unwrap().port();
+        tokio::spawn(async move { axum::serve(listener, app).await.unwrap() });
+        port
+    }
+
+    #[tokio::test]
+    async fn birddog_answers_about() {
+        let app = axum::Router::new().route(
+            "/about",
+            get(|| async { axum::Json(serde_json::json!({ "Model": "P200" })) }),
+        );
+        let port = serve(app).await;
+        assert_eq!(
+            detect_protocol("127.0.0.1", &[port], TEST_TIMEOUT).await,
+            Some(PtzProtocol::BirdDogRest)
+        );
+    }
+
+    #[tokio::test]
+    async fn panasonic_answers_the_power_query() {
+        let app = axum::Router::new().route("/cgi-bin/aw_ptz", get(|| async { "p1" }));
+        let port = serve(app).await;
+        assert_eq!(
+            detect_protocol("127.0.0.1", &[port], TEST_TIMEOUT).await,
+            Some(PtzProtocol::PanasonicAw)
+        );
+    }
+
+    #[tokio::test]
+    async fn other_web_servers_are_not_cameras() {
+        let app = axum::Router::new().fallback(|| async { "<html>Router login</html>" });
+        let port = serve(app).await;
+        assert_eq!(
+            detect_protocol("127.0.0.1", &[port], TEST_TIMEOUT).await,
+            None
+        );
+    }
+
+    #[tokio::test]
+    async fn visca_answers_the_position_inquiry() {
+        let socket = tokio::net::UdpSocket::bind("127.0.0.1:0").await.unwrap();
+        let port = socket.local_addr().unwrap().port();
+        tokio::spawn(async move {
+            let mut buf = [0u8; 64];
+            while let Ok((len, peer)) = socket.recv_from(&mut buf).await {
+                let packet = &buf[..len];
+                if commands::payload_type(packet) == Some(commands::ViscaIpHeader::CONTROL) {
+                    continue;
+                }
+                let seq = commands::reply_sequence(packet).unwrap();
+                let reply =
+                    commands::build_visca_packet(&[0x90, 0x50, 0, 0, 0, 0, 0, 0, 0, 0, 0xFF], seq);
+                let _ = socket.send_to(&reply, peer).await;
+            }
+        });
+        assert_eq!(
+            detect_protocol("127.0.0.1", &[port], TEST_TIMEOUT).await,
+            Some(PtzProtocol::Visca)
+        );
+    }
+}
@@ -1,5 +1,8 @@
 use super::types::CameraEndpoint;
+use crate::persistence::bundle::{self, CollisionStrategy, MergeCounts};
+use crate::persistence::save::{self, LoadWarning, SaveQueue};
 use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
 use std::path::{Path, PathBuf};
 
 #[derive(Debug, Clone, Default, Serialize, Deserialize)]
@@ -11,25 +14,49 @@ struct EndpointStore {
 pub struct EndpointManager {
     store: EndpointStore,
     file_path: PathBuf,
+    saves: SaveQueue,
 }
 
 impl EndpointManager {
     pub fn load_or_default(data_dir: &Path) -> Self {
+        Self::load(data_dir).0
+    }
+
+    /// Load the endpoints, reporting a corrupt file that was set aside.
+    pub fn load(data_dir: &Path) -> (Self, Option<LoadWarning>) {
         let file_path = data_dir.join("endpoints.json");
-        let store = if file_path.exists() {
-            std::fs::read_to_string(&file_path)
-                .ok()
-                .and_then(|s| serde_json::from_str(&s).ok())
-                .unwrap_or_default()
-        } else {
-            EndpointStore::default()
+        let (store, warning) = save::load_json_or_preserve(&file_path);
+        let manager = Self {
+            store,
+            file_path,
+            saves: SaveQueue::immediate(),
         };
-        Self { store, file_path }
+        (manager, warning)
+    }
+
+    /// Hold saves in memory until `flush`, so bursts of edits share one write.
+    pub fn with_deferred_saves(mut self) -> Self {
+        self.saves = SaveQueue::deferred();
+        self
     }
 
+    /// Persist the endpoints, or mark them dirty when saves are deferred.
     pub fn save(&self) -> Result<(), String> {
+        self.saves.save(|| self.write())
+    }
+
+    /// Write out a deferred save, if one is pending. Returns whether it wrote.
+    pub fn flush(&self) -> Result<bool, String> {
+        self.saves.flush(|| self.write())
+    }
+
+    pub fn is_dirty(&self) -> bool {
+        self.saves.is_dirty()
+    }
+
+    fn write(&self) -> Result<(), String> {
         let json = serde_json::to_string_pretty(&self.store).map_err(|e| e.to_string())?;
-        std::fs::write(&self.file_path, json).map_err(|e| e.to_string())
+        save::write_atomic(&self.file_path, &json)
     }
 
     pub fn get_all(&self) -> Vec<CameraEndpoint> {
@@ -41,12 +68,17 @@ impl EndpointManager {
     }
 
     pub fn create(&mut self, endpoint: CameraEndpoint) -> Result<CameraEndpoint, String> {
+        if self.store.endpoints.iter().any(|e| e.id == endpoint.id) {
+            return Err(format!("Endpoint '{}' already exists", endpoint.id));
+        }
+        let endpoint = endpoint.validated()?;
         self.store.endpoints.push(endpoint.clone());
         self.save()?;
         Ok(endpoint)
     }
 
     pub fn update(&mut self, endpoint: CameraEndpoint) -> Result<CameraEndpoint, String> {
+        let endpoint = endpoint.validated()?;
         let pos = self
             .store
             .endpoints
@@ -58,6 +90,27 @@ impl EndpointManager {
         Ok(endpoint)
     }
 
+    /// Merge endpoints from a config bundle. Nothing is imported if any config is invalid.
+    /// Returns the merge counts and the new ids of renamed endpoints.
+    pub fn import(
+        &mut self,
+        endpoints: Vec<CameraEndpoint>,
+        strategy: CollisionStrategy,
+    ) -> Result<(MergeCounts, HashMap<String, String>), String> {
+        let endpoints = endpoints
+            .into_iter()
+            .map(|endpoint| {
+                let name = endpoint.name.clone();
+                endpoint
+                    .validated()
+                    .map_err(|e| format!("Endpoint '{}': {}", name, e))
+            })
+            .collect::<Result<Vec<_>, _>>()?;
+        let result = bundle::merge_items(&mut self.store.endpoints, endpoints, strategy);
+        self.save()?;
+        Ok(result)
+    }
+
     pub fn delete(&mut self, id: &str) -> Result<(), String> {
         let pos = self
             .store
@@ -73,7 +126,7 @@ impl EndpointManager {
 #[cfg(test)]
 mod tests {
     use super::*;
-    use crate::ptz::types::{ProtocolConfig, PtzProtocol};
+    use crate::ptz::types::{AbsoluteStrategy, ProtocolConfig, PtzProtocol};
     use std::fs;
 
     fn temp_dir() -> PathBuf {
@@ -91,7 +144,20 @@ mod tests {
             config: ProtocolConfig::Visca {
                 host: "192.168.1.100".to_string(),
                 port: 1259,
+                limits: None,
+                tally_opcode: None,
+                gamma_opcode: None,
+                picture_profile_opcode: None,
+                zoom_max: None,
+                digital_zoom_max: None,
+                digital_zoom_opcode: None,
+                calibration: None,
+                wait_for_completion: false,
+                bind_address: None,
+                absolute_strategy: AbsoluteStrategy::AbsolutePosition,
             },
+            color: None,
+            icon: None,
         }
     }
 
@@ -144,6 +210,125 @@ mod tests {
         fs::remove_dir_all(&dir).ok();
     }
 
+    #[test]
+    fn create_rejects_duplicate_id() {
+        let dir = temp_dir();
+        let mut mgr = EndpointManager::load_or_default(&dir);
+        mgr.create(make_endpoint("e1", "First")).unwrap();
+        assert!(mgr.create(make_endpoint("e1", "Second")).is_err());
+        assert_eq!(mgr.get_all().len(), 1);
+        assert_eq!(mgr.get("e1").unwrap().name, "First");
+        fs::remove_dir_all(&dir).ok();
+    }
+
+    #[test]
+    fn create_rejects_invalid_color() {
+        let dir = temp_dir();
+        let mut mgr = EndpointManager::load_or_default(&dir);
+        let endpoint = CameraEndpoint {
+            color: Some("not-a-color".to_string()),
+            ..make_endpoint("e1", "Stage")
+        };
+        assert!(mgr.create(endpoint).is_err());
+        assert!(mgr.get_all().is_empty());
+        fs::remove_dir_all(&dir).ok();
+    }
+
+    #[test]
+    fn display_fields_persist() {
+        let dir = temp_dir();
+        {
+            let mut mgr = EndpointManager::load_or_default(&dir);
+            let endpoint = CameraEndpoint {
+                color: Some("#F0A".to_string()),
+                icon: Some("podium".to_string()),
+                ..make_endpoint("e1", "Stage")
+            };
+            assert_eq!(
+                mgr.create(endpoint).unwrap().color.as_deref(),
+                Some("#ff00aa")
+            );
+        }
+        let stored = EndpointManager::load_or_default(&dir).get("e1").unwrap();
+        assert_eq!(stored.color.as_deref(), Some("#ff00aa"));
+        assert_eq!(stored.icon.as_deref(), Some("podium"));
+        fs::remove_dir_all(&dir).ok();
+    }
+
+    #[test]
+    fn create_and_update_reject_bad_host_or_port() {
+        let dir = temp_dir();
+        let mut mgr = EndpointManager::load_or_default(&dir);
+        let mut bad = make_endpoint("e1", "Bad");
+        bad.config = ProtocolConfig::Visca {
+            host: "http://cam".to_string(),
+            port: 1259,
+            limits: None,
+            tally_opcode: None,
+            gamma_opcode: None,
+            picture_profile_opcode: None,
+            zoom_max: None,
+            digital_zoom_max: None,
+            digital_zoom_opcode: None,
+            calibration: None,
+            wait_for_completion: false,
+            bind_address: None,
+            absolute_strategy: AbsoluteStrategy::AbsolutePosition,
+        };
+        assert!(mgr.create(bad).is_err());
+        assert!(mgr.get_all().is_empty());
+
+        mgr.create(make_endpoint("e1", "Good")).unwrap();
+        let mut zero_port = make_endpoint("e1", "Zero");
+        zero_port.config = ProtocolConfig::Visca {
+            host: "192.168.1.100".to_string(),
+            port: 0,
+            limits: None,
+            tally_opcode: None,
+            gamma_opcode: None,
+            picture_profile_opcode: None,
+            zoom_max: None,
+            digital_zoom_max: None,
+            digital_zoom_opcode: None,
+            calibration: None,
+            wait_for_completion: false,
+            bind_address: None,
+            absolute_strategy: AbsoluteStrategy::AbsolutePosition,
+        };
+        assert!(mgr.update(zero_port).is_err());
+        assert_eq!(mgr.get("e1").unwrap().name, "Good");
+        fs::remove_dir_all(&dir).ok();
+    }
+
+    #[test]
+    fn import_rejects_bundle_with_invalid_endpoint() {
+        let dir = temp_dir();
+        let mut mgr = EndpointManager::load_or_default(&dir);
+        let mut bad = make_endpoint("e2", "Bad");
+        bad.config = ProtocolConfig::Visca {
+            host: "".to_string(),
+            port: 1259,
+            limits: None,
+            tally_opcode: None,
+            gamma_opcode: None,
+            picture_profile_opcode: None,
+            zoom_max: None,
+            digital_zoom_max: None,
+            digital_zoom_opcode: None,
+            calibration: None,
+            wait_for_completion: false,
+            bind_address: None,
+            absolute_strategy: AbsoluteStrategy::AbsolutePosition,
+        };
+        let result = mgr.import(
+            vec![make_endpoint("e1", "Good"), bad],
+            CollisionStrategy::Skip,
+        );
+        assert!(result.is_err());
+        assert!(mgr.get_all().is_empty());
+        fs::remove_dir_all(&dir).ok();
+    }
+
     #[test]
     fn delete_removes_endpoint() {
         let dir = temp_dir();
@@ -175,4 +360,21 @@ mod tests {
         assert_eq!(mgr.get("e1").unwrap().name, "Persisted");
         fs::remove_dir_all(&dir).ok();
     }
+
+    #[test]
+    fn flush_writes_current_state() {
+        let dir = temp_dir();
+        let mut mgr = EndpointManager::load_or_default(&dir).with_deferred_saves();
+        mgr.create(make_endpoint("e1", "First")).unwrap();
+        mgr.create(make_endpoint("e2", "Second")).unwrap();
+        mgr.delete("e1").unwrap();
+        assert!(EndpointManager::load_or_default(&dir).get_all().is_empty());
+
+        assert!(mgr.flush().unwrap());
+        let reloaded = EndpointManager::load_or_default(&dir);
+        let ids: Vec<_> = reloaded.get_all().into_iter().map(|e| e.id).collect();
+        assert_eq!(ids, ["e2"]);
+        assert!(!mgr.is_dirty());
+        fs::remove_dir_all(&dir).ok();
+    }
 }
@@ -0,0 +1,384 @@
+use super::types::{Preset, PtzPosition};
+use serde::{Deserialize, Serialize};
+
+/// Share of the wide-angle FOV lost at full zoom, matching the overlay projection.
+const ZOOM_FOV_REDUCTION: f64 = 0.9;
+
+/// Highest optical zoom ratio accepted for a lens curve.
+pub const MAX_ZOOM_RATIO: f64 = 100.0;
+
+/// How normalized zoom maps to magnification, and so how much a zoomed-in
+/// click should move the camera.
+#[derive(Debug, Clone, Copy, Default, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "snake_case")]
+pub enum LensCurve {
+    /// FOV narrows linearly to a tenth of wide at full zoom, as the overlay
+    /// projection assumes.
+    #[default]
+    Linear,
+    /// Magnification grows geometrically from 1x at wide to this optical
+    /// zoom ratio at full zoom, as on most PTZ zoom servos.
+    Optical(f64),
+}
+
+impl LensCurve {
+    /// Magnification at a normalized zoom level, 1.0 at full wide.
+    pub fn zoom_factor(self, zoom: f64) -> f64 {
+        let zoom = zoom.clamp(0.0, 1.0);
+        match self {
+            LensCurve::Linear => 1.0 / (1.0 - zoom * ZOOM_FOV_REDUCTION),
+            LensCurve::Optical(ratio) if ratio.is_finite() => {
+                ratio.clamp(1.0, MAX_ZOOM_RATIO).powf(zoom)
+            }
+            LensCurve::Optical(_) => 1.0,
+        }
+    }
+
+    pub fn validate(self) -> Result<(), String> {
+        match self {
+            LensCurve::Optical(ratio) if !(1.0..=MAX_ZOOM_RATIO).contains(&ratio) => Err(format!(
+                "Optical zoom ratio must be between 1 and {}",
+                MAX_ZOOM_RATIO
+            )),
+            _ => Ok(()),
+        }
+    }
+}
+
+/// A camera's wide-angle horizontal FOV and how zoom narrows it.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Lens {
+    pub fov_degrees: f64,
+    pub curve: LensCurve,
+}
+
+impl Lens {
+    /// Horizontal FOV in degrees at a normalized zoom level.
+    pub fn effective_fov(&self, zoom: f64) -> f64 {
+        self.fov_degrees / self.curve.zoom_factor(zoom)
+    }
+}
+
+/// How far a pan/tilt head travels either side of center, in degrees; the
+/// travel normalized -1.0 and 1.0 map to.
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq)]
+pub struct MechanicalRange {
+    pub pan_degrees: f64,
+    pub tilt_degrees: f64,
+}
+
+impl MechanicalRange {
+    pub fn validate(&self) -> Result<(), String> {
+        let valid = |v: f64| v.is_finite() && v > 0.0 && v <= 180.0;
+        if !valid(self.pan_degrees) || !valid(self.tilt_degrees) {
+            return Err("Mechanical range must be between 0 and 180 degrees".to_string());
+        }
+        Ok(())
+    }
+}
+
+/// Degrees one normalized pan/tilt unit covers on each axis.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct DegreeScale {
+    pub pan: f64,
+    pub tilt: f64,
+}
+
+impl DegreeScale {
+    /// The head's mechanical range when known. Otherwise a unit is half the
+    /// wide-angle FOV on both axes, as the overlay and click-to-move assume.
+    pub fn new(fov_degrees: f64, range: Option<MechanicalRange>) -> Self {
+        match range {
+            Some(range) => Self {
+                pan: range.pan_degrees,
+                tilt: range.tilt_degrees,
+            },
+            None => Self {
+                pan: fov_degrees / 2.0,
+                tilt: fov_degrees / 2.0,
+            },
+        }
+    }
+
+    /// Normalized pan/tilt for an angle in degrees, clamped to -1.0..1.0.
+    pub fn to_normalized(&self, pan_degrees: f64, tilt_degrees: f64) -> (f64, f64) {
+        (
+            (pan_degrees / self.pan).clamp(-1.0, 1.0),
+            (tilt_degrees / self.tilt).clamp(-1.0, 1.0),
+        )
+    }
+
+    /// Angle in degrees for a normalized pan/tilt.
+    pub fn to_degrees(&self, pan: f64, tilt: f64) -> (f64, f64) {
+        (pan * self.pan, tilt * self.tilt)
+    }
+}
+
+/// Pan/tilt adjustment produced by a click in the video frame.
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq)]
+pub struct ClickDelta {
+    pub pan_delta: f64,
+    pub tilt_delta: f64,
+}
+
+/// Pan/tilt delta that brings a clicked point toward the center of the frame.
+///
+/// The click is projected through the lens to an angle off-axis, using the
+/// FOV narrowed by the lens curve at `current_zoom`, and expressed as a fraction of the wide-angle half FOV.
+/// `sensitivity` scales that fraction, so at full wide a click on the left or
+/// right edge pans by exactly `sensitivity`. Tilt uses the same degrees-per-unit
+/// as pan, so a non-square frame yields proportionally smaller tilt deltas.
+/// Up and right are positive. Degenerate frames or FOVs give no movement.
+pub fn click_to_delta(
+    click_x: f64,
+    click_y: f64,
+    frame_w: f64,
+    frame_h: f64,
+    lens: Lens,
+    current_zoom: f64,
+    sensitivity: f64,
+) -> (f64, f64) {
+    let fov_degrees = lens.fov_degrees;
+    let valid = [click_x, click_y, frame_w, frame_h, fov_degrees, sensitivity]
+        .iter()
+        .all(|v| v.is_finite());
+    if !valid || frame_w <= 0.0 || frame_h <= 0.0 || fov_degrees <= 0.0 || fov_degrees >= 180.0 {
+        return (0.0, 0.0);
+    }
+    let zoom = if current_zoom.is_finite() {
+        current_zoom
+    } else {
+        0.0
+    };
+
+    // Offsets from center in half-frame units, clamped to the frame
+    let dx = ((click_x - frame_w / 2.0) / (frame_w / 2.0)).clamp(-1.0, 1.0);
+    let dy = ((frame_h / 2.0 - click_y) / (frame_h / 2.0)).clamp(-1.0, 1.0);
+
+    let half_h = (lens.effective_fov(zoom) / 2.0).to_radians().tan();
+    let half_v = half_h * frame_h / frame_w;
+    let wide_half = (fov_degrees / 2.0).to_radians();
+
+    let pan_angle = (dx * half_h).atan();
+    let tilt_angle = (dy * half_v).atan();
+    (
+        pan_angle / wide_half * sensitivity,
+        tilt_angle / wide_half * sensitivity,
+    )
+}
+
+/// How far, in normalized units on each axis, the camera may sit from a
+/// preset's position and still count as on that preset.
+pub const ACTIVE_PRESET_TOLERANCE: f64 = 0.01;
+
+/// The preset the camera at `position` is sitting on: the nearest one whose
+/// pan, tilt and zoom are each within `tolerance`. For cameras that can't
+/// report their last recalled preset.
+pub fn infer_active_preset<'a>(
+    position: &PtzPosition,
+    presets: &'a [Preset],
+    tolerance: f64,
+) -> Option<&'a Preset> {
+    presets
+        .iter()
+        .map(|preset| {
+            let offset = (preset.pan - position.pan)
+                .abs()
+                .max((preset.tilt - position.tilt).abs())
+                .max((preset.zoom - position.zoom).abs());
+            (preset, offset)
+        })
+        .filter(|(_, offset)| *offset <= tolerance)
+        .min_by(|a, b| a.1.total_cmp(&b.1))
+        .map(|(preset, _)| preset)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const WIDE_60: Lens = Lens {
+        fov_degrees: 60.0,
+        curve: LensCurve::Linear,
+    };
+
+    fn lens(fov_degrees: f64) -> Lens {
+        Lens {
+            fov_degrees,
+            curve: LensCurve::Linear,
+        }
+    }
+
+    fn assert_close(actual: f64, expected: f64) {
+        assert!(
+            (actual - expected).abs() < 1e-9,
+            "expected {expected}, got {actual}"
+        );
+    }
+
+    #[test]
+    fn center_click_does_not_move() {
+        let (pan, tilt) = click_to_delta(960.0, 540.0, 1920.0, 1080.0, WIDE_60, 0.4, 0.1);
+        assert_close(pan, 0.0);
+        assert_close(tilt, 0.0);
+    }
+
+    #[test]
+    fn edge_clicks_at_wide_move_by_sensitivity() {
+        let (pan, tilt) = click_to_delta(1920.0, 540.0, 1920.0, 1080.0, WIDE_60, 0.0, 0.1);
+        assert_close(pan, 0.1);
+        assert_close(tilt, 0.0);
+
+        let (pan, _) = click_to_delta(0.0, 540.0, 1920.0, 1080.0, WIDE_60, 0.0, 0.1);
+        assert_close(pan, -0.1);
+
+        // Square frame: the top edge is as far off-axis as the side edges
+        let (pan, tilt) = click_to_delta(500.0, 0.0, 1000.0, 1000.0, WIDE_60, 0.0, 0.1);
+        assert_close(pan, 0.0);
+        assert_close(tilt, 0.1);
+    }
+
+    #[test]
+    fn widescreen_tilt_is_smaller_than_pan() {
+        let (pan, tilt) = click_to_delta(1920.0, 0.0, 1920.0, 1080.0, WIDE_60, 0.0, 0.1);
+        assert!(tilt > 0.0 && tilt < pan, "pan {pan}, tilt {tilt}");
+    }
+
+    #[test]
+    fn zoom_scales_delta_down() {
+        let wide = click_to_delta(1440.0, 540.0, 1920.0, 1080.0, WIDE_60, 0.0, 0.1).0;
+        let mid = click_to_delta(1440.0, 540.0, 1920.0, 1080.0, WIDE_60, 0.5, 0.1).0;
+        let tele = click_to_delta(1440.0, 540.0, 1920.0, 1080.0, WIDE_60, 1.0, 0.1).0;
+        assert!(wide > mid && mid > tele, "{wide} {mid} {tele}");
+        // At full zoom the FOV is a tenth of wide, so the edge is ~a tenth as far
+        let tele_edge = click_to_delta(1920.0, 540.0, 1920.0, 1080.0, WIDE_60, 1.0, 0.1).0;
+        assert!((tele_edge - 0.01).abs() < 0.001, "{tele_edge}");
+    }
+
+    #[test]
+    fn degenerate_inputs_do_not_move() {
+        assert_eq!(
+            click_to_delta(10.0, 10.0, 0.0, 1080.0, WIDE_60, 0.0, 0.1),
+            (0.0, 0.0)
+        );
+        assert_eq!(
+            click_to_delta(f64::NAN, 10.0, 1920.0, 1080.0, WIDE_60, 0.0, 0.1),
+            (0.0, 0.0)
+        );
+        assert_eq!(
+            click_to_delta(10.0, 10.0, 1920.0, 1080.0, lens(180.0), 0.0, 0.1),
+            (0.0, 0.0)
+        );
+    }
+
+    #[test]
+    fn effective_fov_narrows_with_zoom() {
+        assert_close(WIDE_60.effective_fov(0.0), 60.0);
+        assert_close(WIDE_60.effective_fov(1.0), 6.0);
+        assert_close(WIDE_60.effective_fov(2.0), 6.0);
+    }
+
+    #[test]
+    fn degrees_follow_the_fov_without_a_mechanical_range() {
+        let wide = DegreeScale::new(60.0, None);
+        assert_eq!(wide.to_normalized(15.0, -30.0), (0.5, -1.0));
+        assert_eq!(wide.to_degrees(0.5, -1.0), (15.0, -30.0));
+
+        let narrow = DegreeScale::new(90.0, None);
+        assert_eq!(narrow.to_normalized(15.0, -30.0), (1.0 / 3.0, -2.0 / 3.0));
+        // Beyond the range clamps to the end of travel
+        assert_eq!(narrow.to_normalized(100.0, -100.0), (1.0, -1.0));
+    }
+
+    #[test]
+    fn mechanical_range_sets_the_scale_regardless_of_fov() {
+        let range = MechanicalRange {
+            pan_degrees: 170.0,
+            tilt_degrees: 90.0,
+        };
+        assert!(range.validate().is_ok());
+        for fov in [60.0, 90.0] {
+            let scale = DegreeScale::new(fov, Some(range));
+            let (pan, tilt) = scale.to_normalized(42.5, -45.0);
+            assert_close(pan, 0.25);
+            assert_close(tilt, -0.5);
+            assert_eq!(scale.to_degrees(pan, tilt), (42.5, -45.0));
+        }
+        let flat = MechanicalRange {
+            tilt_degrees: 0.0,
+            ..range
+        };
+        assert!(flat.validate().is_err());
+    }
+
+    #[test]
+    fn optical_curve_reaches_the_zoom_ratio_geometrically() {
+        let curve = LensCurve::Optical(20.0);
+        assert_close(curve.zoom_factor(0.0), 1.0);
+        assert_close(curve.zoom_factor(0.5), 20f64.sqrt());
+        assert_close(curve.zoom_factor(1.0), 20.0);
+        assert!(curve.validate().is_ok());
+        assert!(LensCurve::Optical(0.5).validate().is_err());
+        assert!(LensCurve::Optical(f64::NAN).validate().is_err());
+    }
+
+    #[test]
+    fn same_offset_moves_less_when_zoomed_in() {
+        let optical = Lens {
+            fov_degrees: 60.0,
+            curve: LensCurve::Optical(30.0),
+        };
+        let wide = click_to_delta(1440.0, 540.0, 1920.0, 1080.0, optical, 0.0, 0.1).0;
+        let mid = click_to_delta(1440.0, 540.0, 1920.0, 1080.0, optical, 0.5, 0.1).0;
+        let tele = click_to_delta(1440.0, 540.0, 1920.0, 1080.0, optical, 1.0, 0.1).0;
+        assert!(wide > mid && mid > tele, "{wide} {mid} {tele}");
+        // A 30x lens at full zoom: the edge is about a thirtieth as far as at wide
+        let tele_edge = click_to_delta(1920.0, 540.0, 1920.0, 1080.0, optical, 1.0, 0.1).0;
+        assert!((tele_edge - 0.1 / 30.0).abs() < 0.0005, "{tele_edge}");
+    }
+
+    fn preset_at(id: &str, pan: f64, tilt: f64, zoom: f64) -> Preset {
+        Preset {
+            id: id.to_string(),
+            name: id.to_string(),
+            pan,
+            tilt,
+            zoom,
+            color: Preset::default_color(),
+            native_slot: None,
+            focus: None,
+            white_balance: None,
+            hotkey: None,
+            thumbnail: None,
+        }
+    }
+
+    #[test]
+    fn active_preset_is_the_nearest_within_tolerance() {
+        let presets = [
+            preset_at("wide", 0.0, 0.0, 0.0),
+            preset_at("podium", 0.5, -0.2, 0.4),
+            preset_at("podium-close", 0.505, -0.2, 0.4),
+        ];
+        let position = PtzPosition {
+            pan: 0.504,
+            tilt: -0.199,
+            zoom: 0.4,
+        };
+        let found = infer_active_preset(&position, &presets, 0.01).map(|p| p.id.as_str());
+        assert_eq!(found, Some("podium-close"));
+    }
+
+    #[test]
+    fn no_active_preset_when_any_axis_is_off() {
+        let presets = [preset_at("podium", 0.5, -0.2, 0.4)];
+        let zoomed_in = PtzPosition {
+            pan: 0.5,
+            tilt: -0.2,
+            zoom: 0.45,
+        };
+        assert!(infer_active_preset(&zoomed_in, &presets, 0.01).is_none());
+        assert!(infer_active_preset(&zoomed_in, &presets, 0.05).is_some());
+        assert!(infer_active_preset(&zoomed_in, &[], 1.0).is_none());
+    }
+}
@@ -0,0 +1,224 @@
+use super::controller::PtzError;
+use serde::{Deserialize, Serialize};
+use std::future::Future;
+use std::time::Duration;
+use tokio::sync::watch;
+
+/// Event emitted to the frontend when the active endpoint's status changes.
+pub const STATUS_CHANGED_EVENT: &str = "endpoint-status-changed";
+
+/// How often the active controller is probed.
+pub const HEALTH_CHECK_INTERVAL: Duration = Duration::from_secs(5);
+
+/// Consecutive failed probes before an endpoint counts as disconnected.
+const DISCONNECT_AFTER_FAILURES: u32 = 3;
+
+/// Health of a camera endpoint as seen by the health monitor.
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq)]
+pub enum EndpointStatus {
+    Connected,
+    /// Recent probes failed, but not enough to give up on the connection.
+    Degraded,
+    Disconnected,
+}
+
+/// Last-known status of a specific endpoint; also the event payload.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct EndpointStatusReport {
+    pub endpoint_id: String,
+    pub status: EndpointStatus,
+}
+
+/// Status state machine driven by probe results.
+pub struct HealthTracker {
+    status: EndpointStatus,
+    consecutive_failures: u32,
+}
+
+impl HealthTracker {
+    /// Endpoints start out connected; the first failed probe degrades them.
+    pub fn new() -> Self {
+        Self {
+            status: EndpointStatus::Connected,
+            consecutive_failures: 0,
+        }
+    }
+
+    pub fn status(&self) -> EndpointStatus {
+        self.status
+    }
+
+    /// Record a successful probe. Returns the new status if it changed.
+    pub fn record_success(&mut self) -> Option<EndpointStatus> {
+        self.consecutive_failures = 0;
+        self.transition(EndpointStatus::Connected)
+    }
+
+    /// Record a failed probe. Returns the new status if it changed.
+    pub fn record_failure(&mut self) -> Option<EndpointStatus> {
+        self.consecutive_failures = self.consecutive_failures.saturating_add(1);
+        if self.consecutive_failures >= DISCONNECT_AFTER_FAILURES {
+            self.transition(EndpointStatus::Disconnected)
+        } else {
+            self.transition(EndpointStatus::Degraded)
+        }
+    }
+
+    fn transition(&mut self, next: EndpointStatus) -> Option<EndpointStatus> {
+        if self.status == next {
+            None
+        } else {
+            self.status = next;
+            Some(next)
+        }
+    }
+}
+
+impl Default for HealthTracker {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Probe with `check` every `interval` until `cancel` is set or its sender is dropped.
+/// `on_change` runs on every status transition; `reconnect` runs after each
+/// probe while the endpoint is disconnected.
+pub async fn run_health_checks<F, Fut, R, RFut, C, CFut>(
+    interval: Duration,
+    mut cancel: watch::Receiver<bool>,
+    mut check: F,
+    mut reconnect: R,
+    mut on_change: C,
+) where
+    F: FnMut() -> Fut,
+    Fut: Future<Output = Result<(), PtzError>>,
+    R: FnMut() -> RFut,
+    RFut: Future<Output = Result<(), String>>,
+    C: FnMut(EndpointStatus) -> CFut,
+    CFut: Future<Output = ()>,
+{
+    let mut tracker = HealthTracker::new();
+    loop {
+        // Wait one interval first; the endpoint was just activated
+        match tokio::time::timeout(interval, cancel.changed()).await {
+            Ok(Ok(())) if *cancel.borrow_and_update() => break,
+            Ok(Ok(())) => continue,
+            Ok(Err(_)) => break,
+            Err(_) => {}
+        }
+
+        let changed = match check().await {
+            Ok(()) => tracker.record_success(),
+            Err(e) => {
+                log::debug!("Health check failed: {}", e);
+                tracker.record_failure()
+            }
+        };
+        if let Some(status) = changed {
+            on_change(status).await;
+        }
+
+        if tracker.status() == EndpointStatus::Disconnected {
+            if let Err(e) = reconnect().await {
+                log::warn!("Reconnect attempt failed: {}", e);
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+
+    #[test]
+    fn new_tracker_is_connected() {
+        assert_eq!(HealthTracker::new().status(), EndpointStatus::Connected);
+    }
+
+    #[test]
+    fn failures_degrade_then_disconnect() {
+        let mut tracker = HealthTracker::new();
+        assert_eq!(tracker.record_failure(), Some(EndpointStatus::Degraded));
+        assert_eq!(tracker.record_failure(), None);
+        assert_eq!(tracker.record_failure(), Some(EndpointStatus::Disconnected));
+        assert_eq!(tracker.record_failure(), None);
+    }
+
+    #[test]
+    fn success_recovers_and_resets_failure_count() {
+        let mut tracker = HealthTracker::new();
+        tracker.record_failure();
+        tracker.record_failure();
+        tracker.record_failure();
+        assert_eq!(tracker.record_success(), Some(EndpointStatus::Connected));
+        assert_eq!(tracker.record_success(), None);
+        // The count restarted, so one failure only degrades again
+        assert_eq!(tracker.record_failure(), Some(EndpointStatus::Degraded));
+    }
+
+    #[tokio::test]
+    async fn monitor_reconnects_while_disconnected_and_reports_recovery() {
+        let (cancel_tx, cancel_rx) = watch::channel(false);
+        let probes = AtomicUsize::new(0);
+        let reconnects = AtomicUsize::new(0);
+        let mut changes = Vec::new();
+
+        run_health_checks(
+            Duration::from_millis(1),
+            cancel_rx,
+            || {
+                // Fail four probes, then recover and stop
+                let i = probes.fetch_add(1, Ordering::SeqCst);
+                if i == 4 {
+                    let _ = cancel_tx.send(true);
+                }
+                async move {
+                    if i < 4 {
+                        Err(PtzError::Timeout("no reply".to_string()))
+                    } else {
+                        Ok(())
+                    }
+                }
+            },
+            || {
+                reconnects.fetch_add(1, Ordering::SeqCst);
+                async { Ok(()) }
+            },
+            |status| {
+                changes.push(status);
+                async {}
+            },
+        )
+        .await;
+
+        assert_eq!(
+            changes,
+            vec![
+                EndpointStatus::Degraded,
+                EndpointStatus::Disconnected,
+                EndpointStatus::Connected
+            ]
+        );
+        // Probes 3 and 4 left the endpoint disconnected
+        assert_eq!(reconnects.load(Ordering::SeqCst), 2);
+    }
+
+    #[tokio::test]
+    async fn monitor_stops_on_cancel() {
+        let (cancel_tx, cancel_rx) = watch::channel(false);
+        let handle = tokio::spawn(run_health_checks(
+            Duration::from_secs(60),
+            cancel_rx,
+            || async { Ok(()) },
+            || async { Ok(()) },
+            |_| async {},
+        ));
+
+        cancel_tx.send(true).unwrap();
+        tokio::time::timeout(Duration::from_secs(1), handle)
+            .await
+            .expect("monitor did not stop after cancellation")
+            .unwrap();
+    }
+}
@@ -0,0 +1,178 @@
+use super::controller::{PtzDispatcher, PtzError};
+use super::types::{PtzLimits, PtzPosition};
+use std::future::Future;
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::sync::{watch, Mutex};
+
+/// How often a jog's position is checked against the soft limits.
+pub const CHECK_INTERVAL: Duration = Duration::from_millis(100);
+
+/// Readings in a row without movement after which the jog is taken to have
+/// ended, e.g. stopped by the deadman, and the guard gives up.
+const IDLE_READINGS: u32 = 10;
+
+/// Poll `fetch` every `interval` while a jog at `pan_speed`/`tilt_speed`
+/// runs, and call `stop` once it reaches `limits`. Ends without stopping
+/// when `cancel` is set or its sender is dropped, or when the camera stops
+/// moving on its own. Fetch errors are logged and polling continues.
+/// Returns whether the jog was stopped at a limit.
+pub async fn guard<F, Fut, S, SFut>(
+    limits: PtzLimits,
+    (pan_speed, tilt_speed): (f64, f64),
+    interval: Duration,
+    mut cancel: watch::Receiver<bool>,
+    mut fetch: F,
+    stop: S,
+) -> bool
+where
+    F: FnMut() -> Fut,
+    Fut: Future<Output = Result<PtzPosition, PtzError>>,
+    S: FnOnce() -> SFut,
+    SFut: Future<Output = Result<(), PtzError>>,
+{
+    let mut last: Option<PtzPosition> = None;
+    let mut idle = 0;
+    loop {
+        // Sleep for one interval, waking early on cancellation
+        match tokio::time::timeout(interval, cancel.changed()).await {
+            Ok(Ok(())) if *cancel.borrow_and_update() => return false,
+            Ok(Ok(())) => continue,
+            Ok(Err(_)) => return false,
+            Err(_) => {}
+        }
+        let pos = match fetch().await {
+            Ok(pos) => pos,
+            Err(e) => {
+                log::debug!("Soft limit check failed: {}", e);
+                continue;
+            }
+        };
+        if limits.limit_velocity(pos.pan, pos.tilt, pan_speed, tilt_speed)
+            != (pan_speed, tilt_speed)
+        {
+            log::info!(
+                "Soft limit reached at pan={}, tilt={}, stopping the jog",
+                pos.pan,
+                pos.tilt
+            );
+            if let Err(e) = stop().await {
+                log::warn!("Failed to stop at soft limit: {}", e);
+            }
+            return true;
+        }
+        idle = if last.as_ref() == Some(&pos) {
+            idle + 1
+        } else {
+            0
+        };
+        if idle >= IDLE_READINGS {
+            return false;
+        }
+        last = Some(pos);
+    }
+}
+
+/// Spawn a guard for the jog just started on `endpoint_id`. The stop goes
+/// through the dispatcher, and only while that endpoint is still active.
+/// Send `true` on the returned channel (or drop it) to end the guard.
+pub fn spawn(
+    dispatcher: Arc<Mutex<PtzDispatcher>>,
+    endpoint_id: String,
+    limits: PtzLimits,
+    velocity: (f64, f64),
+) -> watch::Sender<bool> {
+    let (cancel_tx, cancel_rx) = watch::channel(false);
+
+    tokio::spawn(async move {
+        let fetch = || {
+            let dispatcher = dispatcher.clone();
+            let endpoint_id = endpoint_id.clone();
+            async move {
+                // Only look the controller up under the lock, so polling
+                // doesn't hold up commands to the other cameras
+                let controller = dispatcher.lock().await.shared_controller(&endpoint_id)?;
+                controller.get_position().await
+            }
+        };
+        let stop = || async {
+            let dispatcher = dispatcher.lock().await;
+            if dispatcher.active_id() != Some(endpoint_id.as_str()) {
+                return Ok(());
+            }
+            dispatcher.stop().await
+        };
+        guard(limits, velocity, CHECK_INTERVAL, cancel_rx, fetch, stop).await;
+    });
+
+    cancel_tx
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::simulator::client::SimulatedController;
+
+    fn pan_limited(max: f64) -> PtzLimits {
+        PtzLimits {
+            pan_max: max,
+            ..PtzLimits::default()
+        }
+    }
+
+    #[tokio::test]
+    async fn jog_is_stopped_at_the_limit() {
+        let mut dispatcher = PtzDispatcher::new();
+        dispatcher.add_controller("sim", Box::new(SimulatedController::new()));
+        dispatcher.set_active("sim").unwrap();
+        dispatcher.continuous_move(1.0, 0.0).await.unwrap();
+        let dispatcher = Arc::new(Mutex::new(dispatcher));
+
+        let _guard = spawn(
+            dispatcher.clone(),
+            "sim".to_string(),
+            pan_limited(0.1),
+            (1.0, 0.0),
+        );
+        tokio::time::sleep(Duration::from_millis(600)).await;
+
+        let dispatcher = dispatcher.lock().await;
+        let controller = dispatcher.controller("sim").unwrap();
+        let stopped_at = controller.get_position().await.unwrap().pan;
+        tokio::time::sleep(Duration::from_millis(100)).await;
+        assert_eq!(controller.get_position().await.unwrap().pan, stopped_at);
+        // One check interval of travel past the limit at most
+        assert!((0.1..0.2).contains(&stopped_at), "{stopped_at}");
+    }
+
+    #[tokio::test]
+    async fn guard_gives_up_when_the_camera_stops_on_its_own() {
+        let (_cancel_tx, cancel_rx) = watch::channel(false);
+        let stopped = guard(
+            pan_limited(0.5),
+            (1.0, 0.0),
+            Duration::from_millis(1),
+            cancel_rx,
+            || async { Ok(PtzPosition::default()) },
+            || async { panic!("a still camera is not at the limit") },
+        )
+        .await;
+        assert!(!stopped);
+    }
+
+    #[tokio::test]
+    async fn cancelled_guard_does_not_stop() {
+        let (cancel_tx, cancel_rx) = watch::channel(false);
+        cancel_tx.send(true).unwrap();
+        let stopped = guard(
+            pan_limited(0.5),
+            (1.0, 0.0),
+            Duration::from_secs(5),
+            cancel_rx,
+            || async { Ok(PtzPosition::default()) },
+            || async { Ok(()) },
+        )
+        .await;
+        assert!(!stopped);
+    }
+}
@@ -1,3 +1,22 @@
+pub mod cancel;
+pub mod color;
+pub mod command_log;
+pub mod connection_status;
 pub mod controller;
+pub mod deadman;
+pub mod detect;
 pub mod endpoint_manager;
+pub mod geometry;
+pub mod health;
+pub mod jog_guard;
+pub mod nudge;
+pub mod poller;
+pub mod position_events;
+pub mod preview;
+pub mod rate_limit;
+pub mod response_curve;
+pub mod retry;
+pub mod timeout;
+pub mod tour;
+pub mod transition;
 pub mod types;
@@ -0,0 +1,133 @@
+use serde::{Deserialize, Serialize};
+use std::future::Future;
+use std::time::Duration;
+
+/// Longest a single relative nudge may drive the camera, in milliseconds.
+pub const MAX_NUDGE_DWELL_MS: u64 = 2000;
+
+/// How long a relative move drives the camera before stopping, for
+/// protocols that nudge by moving briefly rather than by a fixed amount.
+/// The time grows with the size of the nudge, from `min_ms` for the
+/// smallest to `max_ms` for a full-range delta.
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq)]
+pub struct NudgeDwell {
+    pub min_ms: u64,
+    pub max_ms: u64,
+}
+
+impl Default for NudgeDwell {
+    /// A quarter-range nudge drives for 200 ms.
+    fn default() -> Self {
+        Self {
+            min_ms: 100,
+            max_ms: 500,
+        }
+    }
+}
+
+impl NudgeDwell {
+    pub fn validate(&self) -> Result<(), String> {
+        if self.min_ms == 0 {
+            return Err("Minimum nudge time must be above 0 ms".to_string());
+        }
+        if self.min_ms > self.max_ms {
+            return Err(format!(
+                "Minimum nudge time ({} ms) is above the maximum ({} ms)",
+                self.min_ms, self.max_ms
+            ));
+        }
+        if self.max_ms > MAX_NUDGE_DWELL_MS {
+            return Err(format!(
+                "Maximum nudge time must be at most {} ms",
+                MAX_NUDGE_DWELL_MS
+            ));
+        }
+        Ok(())
+    }
+
+    /// Drive time for a nudge, scaled by its larger axis.
+    pub fn for_delta(&self, pan_delta: f64, tilt_delta: f64) -> Duration {
+        let magnitude = pan_delta.abs().max(tilt_delta.abs());
+        let magnitude = if magnitude.is_finite() {
+            magnitude.min(1.0)
+        } else {
+            0.0
+        };
+        let span = self.max_ms.saturating_sub(self.min_ms) as f64;
+        Duration::from_millis(self.min_ms + (span * magnitude).round() as u64)
+    }
+}
+
+tokio::task_local! {
+    static DWELL: NudgeDwell;
+}
+
+/// Run a relative move with `dwell` replacing the default drive times.
+pub async fn scoped<F: Future>(dwell: NudgeDwell, command: F) -> F::Output {
+    DWELL.scope(dwell, command).await
+}
+
+/// Drive time for a nudge, using the caller's dwell if the move runs inside
+/// [`scoped`], otherwise the default.
+pub fn dwell_for(pan_delta: f64, tilt_delta: f64) -> Duration {
+    DWELL
+        .try_with(|dwell| *dwell)
+        .unwrap_or_default()
+        .for_delta(pan_delta, tilt_delta)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn small_nudges_dwell_near_the_minimum() {
+        let dwell = NudgeDwell::default();
+        assert_eq!(dwell.for_delta(0.0, 0.0), Duration::from_millis(100));
+        assert_eq!(dwell.for_delta(0.05, -0.02), Duration::from_millis(120));
+        assert_eq!(dwell.for_delta(0.0, -0.25), Duration::from_millis(200));
+    }
+
+    #[test]
+    fn large_nudges_cap_at_the_maximum() {
+        let dwell = NudgeDwell {
+            min_ms: 50,
+            max_ms: 1050,
+        };
+        assert_eq!(dwell.for_delta(0.5, 0.1), Duration::from_millis(550));
+        assert_eq!(dwell.for_delta(-1.0, 0.0), Duration::from_millis(1050));
+        assert_eq!(dwell.for_delta(3.0, 0.0), Duration::from_millis(1050));
+        assert_eq!(dwell.for_delta(f64::NAN, 0.0), Duration::from_millis(50));
+    }
+
+    #[test]
+    fn validate_rejects_inverted_or_excessive_ranges() {
+        assert!(NudgeDwell::default().validate().is_ok());
+        let inverted = NudgeDwell {
+            min_ms: 300,
+            max_ms: 200,
+        };
+        assert!(inverted.validate().is_err());
+        let zero = NudgeDwell {
+            min_ms: 0,
+            max_ms: 200,
+        };
+        assert!(zero.validate().is_err());
+        let excessive = NudgeDwell {
+            min_ms: 100,
+            max_ms: MAX_NUDGE_DWELL_MS + 1,
+        };
+        assert!(excessive.validate().is_err());
+    }
+
+    #[tokio::test]
+    async fn scoped_dwell_applies_only_inside_the_scope() {
+        let fixed = NudgeDwell {
+            min_ms: 40,
+            max_ms: 40,
+        };
+        assert_eq!(dwell_for(0.25, 0.0), Duration::from_millis(200));
+        let inside = scoped(fixed, async { dwell_for(0.25, 0.0) }).await;
+        assert_eq!(inside, Duration::from_millis(40));
+    }
+}
@@ -0,0 +1,225 @@
+use super::controller::{PtzDispatcher, PtzError};
+use super::types::PtzPosition;
+use std::future::Future;
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::sync::{watch, Mutex};
+
+/// Event emitted to the frontend whenever the polled position changes.
+pub const POSITION_CHANGED_EVENT: &str = "ptz-position-changed";
+
+/// Poll `fetch` every `interval` until `cancel` is set or its sender is dropped.
+/// `on_change` runs for the first position and whenever it differs from the last one.
+/// Fetch errors are logged and polling continues.
+pub async fn poll_positions<F, Fut, C, CFut>(
+    interval: Duration,
+    mut cancel: watch::Receiver<bool>,
+    mut fetch: F,
+    mut on_change: C,
+) where
+    F: FnMut() -> Fut,
+    Fut: Future<Output = Result<PtzPosition, PtzError>>,
+    C: FnMut(PtzPosition) -> CFut,
+    CFut: Future<Output = ()>,
+{
+    let mut last: Option<PtzPosition> = None;
+    loop {
+        if *cancel.borrow_and_update() {
+            break;
+        }
+        match fetch().await {
+            Ok(pos) => {
+                if last.as_ref() != Some(&pos) {
+                    last = Some(pos.clone());
+                    on_change(pos).await;
+                }
+            }
+            Err(e) => log::debug!("Position poll failed: {}", e),
+        }
+        // Sleep for one interval, waking early on cancellation
+        match tokio::time::timeout(interval, cancel.changed()).await {
+            Ok(Ok(())) => continue,
+            Ok(Err(_)) => break,
+            Err(_) => {}
+        }
+    }
+}
+
+/// Spawn a poller for one endpoint's controller.
+/// New positions are written to `current_position` and passed to `emit`.
+/// Send `true` on the returned channel (or drop it) to stop polling.
+pub fn spawn(
+    dispatcher: Arc<Mutex<PtzDispatcher>>,
+    current_position: Arc<Mutex<PtzPosition>>,
+    endpoint_id: String,
+    interval: Duration,
+    emit: impl Fn(&PtzPosition) + Send + Sync + 'static,
+) -> watch::Sender<bool> {
+    let (cancel_tx, cancel_rx) = watch::channel(false);
+
+    tokio::spawn(async move {
+        log::info!("Position polling started for endpoint {}", endpoint_id);
+        let fetch = || {
+            let dispatcher = dispatcher.clone();
+            let endpoint_id = endpoint_id.clone();
+            async move {
+                // Only look the controller up under the lock, so a slow
+                // camera doesn't hold up commands to the others
+                let (controller, statuses) = {
+                    let dispatcher = dispatcher.lock().await;
+                    (
+                        dispatcher.shared_controller(&endpoint_id)?,
+                        dispatcher.statuses(),
+                    )
+                };
+                let result = controller.get_position().await;
+                statuses.record(&endpoint_id, &result);
+                result
+            }
+        };
+        let on_change = |pos: PtzPosition| {
+            let current_position = current_position.clone();
+            emit(&pos);
+            async move {
+                *current_position.lock().await = pos;
+            }
+        };
+        poll_positions(interval, cancel_rx, fetch, on_change).await;
+        log::info!("Position polling stopped for endpoint {}", endpoint_id);
+    });
+
+    cancel_tx
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+
+    fn pos(pan: f64) -> PtzPosition {
+        PtzPosition {
+            pan,
+            tilt: 0.0,
+            zoom: 0.0,
+        }
+    }
+
+    #[tokio::test]
+    async fn emits_only_when_position_changes() {
+        let (cancel_tx, cancel_rx) = watch::channel(false);
+        let readings = [0.0, 0.0, 0.5, 0.5, 1.0];
+        let calls = AtomicUsize::new(0);
+        let mut seen = Vec::new();
+
+        poll_positions(
+            Duration::from_millis(1),
+            cancel_rx,
+            || {
+                let i = calls.fetch_add(1, Ordering::SeqCst);
+                if i + 1 >= readings.len() {
+                    let _ = cancel_tx.send(true);
+                }
+                let reading = readings[i.min(readings.len() - 1)];
+                async move { Ok(pos(reading)) }
+            },
+            |p| {
+                seen.push(p.pan);
+                async {}
+            },
+        )
+        .await;
+
+        assert_eq!(seen, vec![0.0, 0.5, 1.0]);
+        assert_eq!(calls.load(Ordering::SeqCst), readings.len());
+    }
+
+    #[tokio::test]
+    async fn errors_do_not_stop_polling() {
+        let (cancel_tx, cancel_rx) = watch::channel(false);
+        let calls = AtomicUsize::new(0);
+        let mut seen = 0;
+
+        poll_positions(
+            Duration::from_millis(1),
+            cancel_rx,
+            || {
+                let i = calls.fetch_add(1, Ordering::SeqCst);
+                if i == 2 {
+                    let _ = cancel_tx.send(true);
+                }
+                async move {
+                    if i < 2 {
+                        Err(PtzError::Timeout("no reply".to_string()))
+                    } else {
+                        Ok(pos(0.25))
+                    }
+                }
+            },
+            |_| {
+                seen += 1;
+                async {}
+            },
+        )
+        .await;
+
+        assert_eq!(calls.load(Ordering::SeqCst), 3);
+        assert_eq!(seen, 1);
+    }
+
+    #[tokio::test]
+    async fn cancellation_wakes_sleeping_poller() {
+        let (cancel_tx, cancel_rx) = watch::channel(false);
+        let handle = tokio::spawn(poll_positions(
+            Duration::from_secs(60),
+            cancel_rx,
+            || async { Ok(pos(0.0)) },
+            |_| async {},
+        ));
+
+        tokio::time::sleep(Duration::from_millis(20)).await;
+        cancel_tx.send(true).unwrap();
+        tokio::time::timeout(Duration::from_secs(1), handle)
+            .await
+            .expect("poller did not stop after cancellation")
+            .unwrap();
+    }
+
+    #[tokio::test]
+    async fn slow_position_queries_do_not_hold_the_dispatcher() {
+        use crate::simulator::client::SimulatedController;
+
+        let mut dispatcher = PtzDispatcher::new();
+        let camera = SimulatedController::new().with_response_delay(Duration::from_secs(5));
+        dispatcher.add_controller("cam", Box::new(camera));
+        let dispatcher = Arc::new(Mutex::new(dispatcher));
+        let cancel_tx = spawn(
+            dispatcher.clone(),
+            Arc::new(Mutex::new(PtzPosition::default())),
+            "cam".to_string(),
+            Duration::from_millis(10),
+            |_| {},
+        );
+
+        tokio::time::sleep(Duration::from_millis(50)).await;
+        let locked = tokio::time::timeout(Duration::from_millis(100), dispatcher.lock()).await;
+        assert!(locked.is_ok());
+        let _ = cancel_tx.send(true);
+    }
+
+    #[tokio::test]
+    async fn dropping_sender_stops_poller() {
+        let (cancel_tx, cancel_rx) = watch::channel(false);
+        let handle = tokio::spawn(poll_positions(
+            Duration::from_secs(60),
+            cancel_rx,
+            || async { Ok(pos(0.0)) },
+            |_| async {},
+        ));
+
+        drop(cancel_tx);
+        tokio::time::timeout(Duration::from_secs(1), handle)
+            .await
+            .expect("poller did not stop after sender dropped")
+            .unwrap();
+    }
+}
@@ -0,0 +1,183 @@
+use super::types::PtzPosition;
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+
+/// Minimum spacing between position events sent for command-driven moves.
+pub const POSITION_EVENT_DEBOUNCE: Duration = Duration::from_millis(50);
+
+/// What to do with a newly offered position.
+#[derive(Debug, Clone, PartialEq)]
+pub enum DebounceAction {
+    /// Emit this position right away.
+    Emit(PtzPosition),
+    /// Hold the position and flush it after this delay.
+    Schedule(Duration),
+    /// A flush is already scheduled; it will carry the newest position.
+    Coalesced,
+}
+
+/// Leading-and-trailing debounce: the first update in a quiet period goes out
+/// immediately, later ones collapse into a single trailing update carrying
+/// the latest position.
+pub struct Debounce {
+    interval: Duration,
+    last_emit: Option<Instant>,
+    pending: Option<PtzPosition>,
+}
+
+impl Debounce {
+    pub fn new(interval: Duration) -> Self {
+        Self {
+            interval,
+            last_emit: None,
+            pending: None,
+        }
+    }
+
+    /// Offer a new position at time `now`.
+    pub fn offer(&mut self, pos: PtzPosition, now: Instant) -> DebounceAction {
+        if self.pending.is_some() {
+            self.pending = Some(pos);
+            return DebounceAction::Coalesced;
+        }
+        match self.last_emit {
+            Some(last) if now.saturating_duration_since(last) < self.interval => {
+                self.pending = Some(pos);
+                DebounceAction::Schedule(self.interval - now.saturating_duration_since(last))
+            }
+            _ => {
+                self.last_emit = Some(now);
+                DebounceAction::Emit(pos)
+            }
+        }
+    }
+
+    /// Take the held position once its scheduled delay has passed.
+    pub fn flush(&mut self, now: Instant) -> Option<PtzPosition> {
+        let pos = self.pending.take()?;
+        self.last_emit = Some(now);
+        Some(pos)
+    }
+}
+
+/// Debounced position notifications shared by the PTZ commands.
+pub struct PositionEvents {
+    debounce: Arc<Mutex<Debounce>>,
+}
+
+impl PositionEvents {
+    pub fn new(interval: Duration) -> Self {
+        Self {
+            debounce: Arc::new(Mutex::new(Debounce::new(interval))),
+        }
+    }
+
+    /// Report a new position. `emit` runs now, later from a spawned task with
+    /// the newest position, or not at all if a pending flush will cover it.
+    pub fn publish<E>(&self, pos: PtzPosition, emit: E)
+    where
+        E: Fn(&PtzPosition) + Send + 'static,
+    {
+        let action = self
+            .debounce
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .offer(pos, Instant::now());
+        match action {
+            DebounceAction::Emit(pos) => emit(&pos),
+            DebounceAction::Schedule(delay) => {
+                let debounce = self.debounce.clone();
+                tokio::spawn(async move {
+                    tokio::time::sleep(delay).await;
+                    let pos = debounce
+                        .lock()
+                        .unwrap_or_else(|e| e.into_inner())
+                        .flush(Instant::now());
+                    if let Some(pos) = pos {
+                        emit(&pos);
+                    }
+                });
+            }
+            DebounceAction::Coalesced => {}
+        }
+    }
+}
+
+impl Default for PositionEvents {
+    fn default() -> Self {
+        Self::new(POSITION_EVENT_DEBOUNCE)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn pos(pan: f64) -> PtzPosition {
+        PtzPosition {
+            pan,
+            tilt: 0.0,
+            zoom: 0.0,
+        }
+    }
+
+    #[test]
+    fn first_update_emits_and_burst_coalesces() {
+        let interval = Duration::from_millis(50);
+        let start = Instant::now();
+        let mut debounce = Debounce::new(interval);
+
+        assert_eq!(
+            debounce.offer(pos(0.1), start),
+            DebounceAction::Emit(pos(0.1))
+        );
+        assert_eq!(
+            debounce.offer(pos(0.2), start + Duration::from_millis(10)),
+            DebounceAction::Schedule(Duration::from_millis(40))
+        );
+        assert_eq!(
+            debounce.offer(pos(0.3), start + Duration::from_millis(20)),
+            DebounceAction::Coalesced
+        );
+        // The trailing flush carries the newest position
+        assert_eq!(debounce.flush(start + interval), Some(pos(0.3)));
+        assert_eq!(debounce.flush(start + interval), None);
+    }
+
+    #[test]
+    fn update_after_quiet_interval_emits_immediately() {
+        let interval = Duration::from_millis(50);
+        let start = Instant::now();
+        let mut debounce = Debounce::new(interval);
+
+        debounce.offer(pos(0.1), start);
+        assert_eq!(
+            debounce.offer(pos(0.2), start + interval),
+            DebounceAction::Emit(pos(0.2))
+        );
+        // A flush restarts the interval
+        debounce.offer(pos(0.3), start + interval + Duration::from_millis(5));
+        let flushed_at = start + interval * 2;
+        debounce.flush(flushed_at);
+        assert!(matches!(
+            debounce.offer(pos(0.4), flushed_at + Duration::from_millis(10)),
+            DebounceAction::Schedule(_)
+        ));
+    }
+
+    #[tokio::test]
+    async fn publish_sends_leading_and_trailing_events() {
+        let events = PositionEvents::new(Duration::from_millis(20));
+        let seen = Arc::new(Mutex::new(Vec::new()));
+        for i in 1..=5 {
+            let seen = seen.clone();
+            events.publish(pos(i as f64 / 10.0), move |p| {
+                seen.lock().unwrap().push(p.pan);
+            });
+        }
+        assert_eq!(*seen.lock().unwrap(), vec![0.1]);
+
+        tokio::time::sleep(Duration::from_millis(100)).await;
+        assert_eq!(*seen.lock().unwrap(), vec![0.1, 0.5]);
+    }
+}
@@ -0,0 +1,88 @@
+use serde::{Deserialize, Serialize};
+
+/// A camera command that protocol clients can encode without sending.
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq)]
+#[serde(tag = "command", rename_all = "snake_case")]
+pub enum PtzCommand {
+    MoveAbsolute { pan: f64, tilt: f64, zoom: f64 },
+    ZoomTo { zoom: f64 },
+    RecallPreset { index: u8 },
+    StorePreset { index: u8 },
+}
+
+impl PtzCommand {
+    /// Name and arguments, in the form used by the command log.
+    pub fn describe(&self) -> String {
+        match self {
+            PtzCommand::MoveAbsolute { pan, tilt, zoom } => {
+                format!("move_absolute({:?}, {:?}, {:?})", pan, tilt, zoom)
+            }
+            PtzCommand::ZoomTo { zoom } => format!("zoom_to({:?})", zoom),
+            PtzCommand::RecallPreset { index } => format!("recall_preset({:?})", index),
+            PtzCommand::StorePreset { index } => format!("store_preset({:?})", index),
+        }
+    }
+}
+
+/// One request a command would put on the wire.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum EncodedRequest {
+    /// A protocol payload as space-separated hex bytes, e.g. `81 01 04 47 ...`.
+    /// Transport framing such as the VISCA-over-IP header is not included.
+    Bytes { hex: String },
+    Http {
+        method: String,
+        url: String,
+        /// JSON body, if the request has one.
+        #[serde(default)]
+        body: Option<String>,
+    },
+}
+
+impl EncodedRequest {
+    pub fn bytes(payload: &[u8]) -> Self {
+        let hex = payload
+            .iter()
+            .map(|b| format!("{:02X}", b))
+            .collect::<Vec<_>>()
+            .join(" ");
+        EncodedRequest::Bytes { hex }
+    }
+}
+
+/// What a command would send to the active camera.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct CommandPreview {
+    pub endpoint_id: String,
+    /// The command, as it would appear in the command log.
+    pub command: String,
+    pub requests: Vec<EncodedRequest>,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn bytes_are_formatted_as_hex() {
+        assert_eq!(
+            EncodedRequest::bytes(&[0x81, 0x01, 0x04, 0x07, 0x2F, 0xFF]),
+            EncodedRequest::Bytes {
+                hex: "81 01 04 07 2F FF".to_string()
+            }
+        );
+    }
+
+    #[test]
+    fn commands_deserialize_from_tagged_json() {
+        let command: PtzCommand = serde_json::from_str(
+            r#"{"command": "move_absolute", "pan": 0.5, "tilt": -0.25, "zoom": 0.0}"#,
+        )
+        .unwrap();
+        assert_eq!(command.describe(), "move_absolute(0.5, -0.25, 0.0)");
+        let recall: PtzCommand =
+            serde_json::from_str(r#"{"command": "recall_preset", "index": 3}"#).unwrap();
+        assert_eq!(recall, PtzCommand::RecallPreset { index: 3 });
+    }
+}
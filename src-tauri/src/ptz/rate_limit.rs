@@ -0,0 +1,264 @@
+use std::collections::HashMap;
+use std::time::{Duration, Instant};
+
+/// Default cap on jog commands sent to one camera each second.
+pub const DEFAULT_COMMANDS_PER_SECOND: u32 = 20;
+
+/// Commands that supersede each other: a newer command in the same lane
+/// replaces a pending one, while other lanes keep theirs.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CommandLane {
+    PanTilt,
+    Zoom,
+    Focus,
+}
+
+/// What to do with a newly offered command.
+#[derive(Debug, PartialEq)]
+pub enum RateAction<C> {
+    /// Send this command right away.
+    Send(C),
+    /// Hold the command and flush it after this delay.
+    Schedule(Duration),
+    /// A flush is already scheduled; it will carry the newest command.
+    Coalesced,
+}
+
+/// Minimum-interval limiter for one camera. The first command in a quiet
+/// period goes out immediately; commands arriving faster than the interval
+/// collapse into one trailing flush carrying the latest command of each lane.
+pub struct CommandThrottle<C> {
+    interval: Duration,
+    last_sent: Option<Instant>,
+    /// Held commands in arrival order, at most one per lane.
+    pending: Vec<(CommandLane, C)>,
+}
+
+impl<C> CommandThrottle<C> {
+    pub fn new(interval: Duration) -> Self {
+        Self {
+            interval,
+            last_sent: None,
+            pending: Vec::new(),
+        }
+    }
+
+    /// Offer a command at time `now`.
+    pub fn offer(&mut self, lane: CommandLane, command: C, now: Instant) -> RateAction<C> {
+        if !self.pending.is_empty() {
+            self.hold(lane, command);
+            return RateAction::Coalesced;
+        }
+        match self.last_sent {
+            Some(last) if now.saturating_duration_since(last) < self.interval => {
+                self.hold(lane, command);
+                RateAction::Schedule(self.interval - now.saturating_duration_since(last))
+            }
+            _ => {
+                self.last_sent = Some(now);
+                RateAction::Send(command)
+            }
+        }
+    }
+
+    /// Take the held commands once their scheduled delay has passed.
+    pub fn flush(&mut self, now: Instant) -> Vec<C> {
+        if !self.pending.is_empty() {
+            self.last_sent = Some(now);
+        }
+        self.pending.drain(..).map(|(_, command)| command).collect()
+    }
+
+    /// Drop held commands, e.g. when a preset recall overrides a jog.
+    pub fn discard(&mut self) {
+        self.pending.clear();
+    }
+
+    fn hold(&mut self, lane: CommandLane, command: C) {
+        self.pending.retain(|(held, _)| *held != lane);
+        self.pending.push((lane, command));
+    }
+}
+
+/// A [`CommandThrottle`] per endpoint, all sharing one rate.
+pub struct RateLimiter<C> {
+    interval: Duration,
+    throttles: HashMap<String, CommandThrottle<C>>,
+}
+
+impl<C> RateLimiter<C> {
+    /// Allow `per_second` commands to each endpoint; 0 disables the limit.
+    pub fn new(per_second: u32) -> Self {
+        Self {
+            interval: interval_for(per_second),
+            throttles: HashMap::new(),
+        }
+    }
+
+    /// Change the rate. Throttles start over, dropping held commands.
+    pub fn set_rate(&mut self, per_second: u32) {
+        self.interval = interval_for(per_second);
+        self.throttles.clear();
+    }
+
+    pub fn offer(
+        &mut self,
+        id: &str,
+        lane: CommandLane,
+        command: C,
+        now: Instant,
+    ) -> RateAction<C> {
+        let interval = self.interval;
+        self.throttles
+            .entry(id.to_string())
+            .or_insert_with(|| CommandThrottle::new(interval))
+            .offer(lane, command, now)
+    }
+
+    pub fn flush(&mut self, id: &str, now: Instant) -> Vec<C> {
+        self.throttles
+            .get_mut(id)
+            .map(|throttle| throttle.flush(now))
+            .unwrap_or_default()
+    }
+
+    pub fn discard(&mut self, id: &str) {
+        if let Some(throttle) = self.throttles.get_mut(id) {
+            throttle.discard();
+        }
+    }
+
+    /// Forget an endpoint entirely, e.g. when its controller is replaced.
+    pub fn remove(&mut self, id: &str) {
+        self.throttles.remove(id);
+    }
+}
+
+fn interval_for(per_second: u32) -> Duration {
+    if per_second == 0 {
+        Duration::ZERO
+    } else {
+        Duration::from_secs(1) / per_second
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const INTERVAL: Duration = Duration::from_millis(50);
+
+    #[test]
+    fn first_command_goes_out_immediately() {
+        let mut throttle = CommandThrottle::new(INTERVAL);
+        let t0 = Instant::now();
+        assert_eq!(
+            throttle.offer(CommandLane::PanTilt, 1, t0),
+            RateAction::Send(1)
+        );
+        assert_eq!(
+            throttle.offer(CommandLane::PanTilt, 2, t0 + INTERVAL),
+            RateAction::Send(2)
+        );
+    }
+
+    #[test]
+    fn burst_coalesces_to_the_last_command() {
+        let mut throttle = CommandThrottle::new(INTERVAL);
+        let t0 = Instant::now();
+        assert_eq!(
+            throttle.offer(CommandLane::PanTilt, 0, t0),
+            RateAction::Send(0)
+        );
+        assert_eq!(
+            throttle.offer(CommandLane::PanTilt, 1, t0 + Duration::from_millis(10)),
+            RateAction::Schedule(Duration::from_millis(40))
+        );
+        for n in 2..10 {
+            assert_eq!(
+                throttle.offer(CommandLane::PanTilt, n, t0 + Duration::from_millis(20)),
+                RateAction::Coalesced
+            );
+        }
+        assert_eq!(throttle.flush(t0 + INTERVAL), vec![9]);
+        assert!(throttle.flush(t0 + INTERVAL).is_empty());
+    }
+
+    #[test]
+    fn lanes_coalesce_independently_in_arrival_order() {
+        let mut throttle = CommandThrottle::new(INTERVAL);
+        let t0 = Instant::now();
+        throttle.offer(CommandLane::PanTilt, "pan 1", t0);
+        throttle.offer(CommandLane::PanTilt, "pan 2", t0);
+        throttle.offer(CommandLane::Zoom, "zoom 1", t0);
+        throttle.offer(CommandLane::PanTilt, "pan 3", t0);
+        assert_eq!(throttle.flush(t0 + INTERVAL), vec!["zoom 1", "pan 3"]);
+    }
+
+    #[test]
+    fn steady_stream_is_capped_at_the_rate_and_the_last_lands() {
+        // 100 commands at 100/s against a 20/s limit
+        let mut throttle = CommandThrottle::new(INTERVAL);
+        let t0 = Instant::now();
+        let mut sent = Vec::new();
+        let mut flush_at = None;
+        for n in 0..100u32 {
+            let now = t0 + Duration::from_millis(10 * n as u64);
+            if flush_at.is_some_and(|at| now >= at) {
+                sent.extend(throttle.flush(now));
+                flush_at = None;
+            }
+            match throttle.offer(CommandLane::PanTilt, n, now) {
+                RateAction::Send(command) => sent.push(command),
+                RateAction::Schedule(delay) => flush_at = Some(now + delay),
+                RateAction::Coalesced => {}
+            }
+        }
+        sent.extend(throttle.flush(t0 + Duration::from_secs(1)));
+
+        assert_eq!(sent.len(), 21);
+        assert_eq!(sent.last(), Some(&99));
+    }
+
+    #[test]
+    fn discarded_commands_never_flush() {
+        let mut throttle = CommandThrottle::new(INTERVAL);
+        let t0 = Instant::now();
+        throttle.offer(CommandLane::PanTilt, 1, t0);
+        throttle.offer(CommandLane::PanTilt, 2, t0);
+        throttle.discard();
+        assert!(throttle.flush(t0 + INTERVAL).is_empty());
+    }
+
+    #[test]
+    fn zero_rate_is_unlimited() {
+        let mut limiter = RateLimiter::new(0);
+        let t0 = Instant::now();
+        for n in 0..5 {
+            assert_eq!(
+                limiter.offer("cam", CommandLane::Zoom, n, t0),
+                RateAction::Send(n)
+            );
+        }
+    }
+
+    #[test]
+    fn endpoints_are_limited_separately() {
+        let mut limiter = RateLimiter::new(DEFAULT_COMMANDS_PER_SECOND);
+        let t0 = Instant::now();
+        assert_eq!(
+            limiter.offer("a", CommandLane::PanTilt, 1, t0),
+            RateAction::Send(1)
+        );
+        assert_eq!(
+            limiter.offer("b", CommandLane::PanTilt, 2, t0),
+            RateAction::Send(2)
+        );
+        assert!(matches!(
+            limiter.offer("a", CommandLane::PanTilt, 3, t0),
+            RateAction::Schedule(_)
+        ));
+        assert!(limiter.flush("b", t0 + INTERVAL).is_empty());
+        assert_eq!(limiter.flush("a", t0 + INTERVAL), vec![3]);
+    }
+}
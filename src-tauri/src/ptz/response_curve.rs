@@ -0,0 +1,130 @@
+use serde::{Deserialize, Serialize};
+
+/// Smallest accepted custom exponent; lower values make the centre jumpy.
+pub const MIN_RESPONSE_EXPONENT: f64 = 0.25;
+/// Largest accepted custom exponent.
+pub const MAX_RESPONSE_EXPONENT: f64 = 5.0;
+
+/// How jog input (stick or on-screen) maps to camera velocity. Steeper
+/// curves give finer control near centre while still reaching full speed.
+#[derive(Debug, Clone, Copy, Default, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "snake_case")]
+pub enum ResponseCurve {
+    #[default]
+    Linear,
+    Squared,
+    Cubic,
+    /// Output is `|input|^exponent`, keeping the sign.
+    Exponent(f64),
+}
+
+impl ResponseCurve {
+    pub fn exponent(self) -> f64 {
+        match self {
+            ResponseCurve::Linear => 1.0,
+            ResponseCurve::Squared => 2.0,
+            ResponseCurve::Cubic => 3.0,
+            ResponseCurve::Exponent(e) => e,
+        }
+    }
+}
+
+/// Shape a velocity in -1.0 to 1.0 with `curve`. The sign is kept, so the
+/// curve applies equally in both directions. Non-finite input is treated as 0.
+pub fn apply_response_curve(input: f64, curve: ResponseCurve) -> f64 {
+    if !input.is_finite() {
+        return 0.0;
+    }
+    let input = input.clamp(-1.0, 1.0);
+    let exponent = curve.exponent();
+    if !exponent.is_finite() || exponent <= 0.0 {
+        return input;
+    }
+    input.abs().powf(exponent).copysign(input)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn approx(a: f64, b: f64) -> bool {
+        (a - b).abs() < 1e-9
+    }
+
+    #[test]
+    fn linear_passes_input_through() {
+        for v in [0.0, 0.25, 0.5, 1.0] {
+            assert!(approx(apply_response_curve(v, ResponseCurve::Linear), v));
+        }
+    }
+
+    #[test]
+    fn squared_and_cubic_soften_the_centre() {
+        assert!(approx(
+            apply_response_curve(0.5, ResponseCurve::Squared),
+            0.25
+        ));
+        assert!(approx(
+            apply_response_curve(0.5, ResponseCurve::Cubic),
+            0.125
+        ));
+        assert!(approx(
+            apply_response_curve(0.1, ResponseCurve::Cubic),
+            0.001
+        ));
+        assert!(approx(
+            apply_response_curve(1.0, ResponseCurve::Squared),
+            1.0
+        ));
+        assert!(approx(apply_response_curve(1.0, ResponseCurve::Cubic), 1.0));
+    }
+
+    #[test]
+    fn custom_exponent() {
+        let curve = ResponseCurve::Exponent(1.5);
+        assert!(approx(apply_response_curve(0.25, curve), 0.125));
+        assert!(approx(apply_response_curve(1.0, curve), 1.0));
+        assert!(approx(apply_response_curve(0.0, curve), 0.0));
+        // Below 1 the curve is more sensitive near centre
+        assert!(apply_response_curve(0.25, ResponseCurve::Exponent(0.5)) > 0.25);
+    }
+
+    #[test]
+    fn sign_is_preserved() {
+        for curve in [
+            ResponseCurve::Linear,
+            ResponseCurve::Squared,
+            ResponseCurve::Cubic,
+            ResponseCurve::Exponent(2.5),
+        ] {
+            let positive = apply_response_curve(0.6, curve);
+            assert!(positive > 0.0);
+            assert!(approx(apply_response_curve(-0.6, curve), -positive));
+        }
+    }
+
+    #[test]
+    fn out_of_range_input_is_clamped() {
+        assert!(approx(
+            apply_response_curve(2.0, ResponseCurve::Squared),
+            1.0
+        ));
+        assert!(approx(
+            apply_response_curve(-4.0, ResponseCurve::Cubic),
+            -1.0
+        ));
+        assert_eq!(apply_response_curve(f64::NAN, ResponseCurve::Squared), 0.0);
+    }
+
+    #[test]
+    fn serializes_as_names_or_exponent() {
+        assert_eq!(
+            serde_json::to_string(&ResponseCurve::Squared).unwrap(),
+            "\"squared\""
+        );
+        assert_eq!(
+            serde_json::from_str::<ResponseCurve>("{\"exponent\":1.5}").unwrap(),
+            ResponseCurve::Exponent(1.5)
+        );
+    }
+}
@@ -0,0 +1,171 @@
+use super::controller::PtzError;
+use std::future::Future;
+use std::time::Duration;
+
+/// Retry policy for transient network failures.
+///
+/// Only `ConnectionFailed` and `Timeout` errors are retried; protocol,
+/// command and configuration errors are returned immediately since
+/// repeating them cannot help.
+#[derive(Debug, Clone)]
+pub struct RetryPolicy {
+    /// Total number of attempts, including the first one.
+    pub max_attempts: u32,
+    /// Delay before the first retry. Doubles after each failed attempt.
+    pub initial_delay: Duration,
+    /// Upper bound on the delay between attempts.
+    pub max_delay: Duration,
+}
+
+impl Default for RetryPolicy {
+    fn default() -> Self {
+        Self {
+            max_attempts: 3,
+            initial_delay: Duration::from_millis(100),
+            max_delay: Duration::from_secs(1),
+        }
+    }
+}
+
+impl RetryPolicy {
+    /// A policy that tries exactly once.
+    pub fn none() -> Self {
+        Self {
+            max_attempts: 1,
+            ..Self::default()
+        }
+    }
+
+    /// Run `op`, retrying retryable errors with exponential backoff.
+    pub async fn run<T, F, Fut>(&self, mut op: F) -> Result<T, PtzError>
+    where
+        F: FnMut() -> Fut,
+        Fut: Future<Output = Result<T, PtzError>>,
+    {
+        let mut delay = self.initial_delay;
+        let mut attempt = 1;
+        loop {
+            match op().await {
+                Ok(value) => return Ok(value),
+                Err(e) if e.is_retryable() && attempt < self.max_attempts => {
+                    log::warn!(
+                        "Attempt {}/{} failed, retrying in {:?}: {}",
+                        attempt,
+                        self.max_attempts,
+                        delay,
+                        e
+                    );
+                    if !delay.is_zero() {
+                        tokio::time::sleep(delay).await;
+                    }
+                    delay = (delay * 2).min(self.max_delay);
+                    attempt += 1;
+                }
+                Err(e) => return Err(e),
+            }
+        }
+    }
+}
+
+/// Map a reqwest send error to a retryable `PtzError`.
+pub fn map_request_error(e: reqwest::Error) -> PtzError {
+    if e.is_timeout() {
+        PtzError::Timeout(e.to_string())
+    } else {
+        PtzError::ConnectionFailed(e.to_string())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::atomic::{AtomicU32, Ordering};
+
+    fn instant_policy(max_attempts: u32) -> RetryPolicy {
+        RetryPolicy {
+            max_attempts,
+            initial_delay: Duration::ZERO,
+            max_delay: Duration::ZERO,
+        }
+    }
+
+    #[tokio::test]
+    async fn retries_connection_failures_up_to_max_attempts() {
+        let calls = AtomicU32::new(0);
+        let result: Result<(), PtzError> = instant_policy(3)
+            .run(|| async {
+                calls.fetch_add(1, Ordering::SeqCst);
+                Err(PtzError::ConnectionFailed("down".into()))
+            })
+            .await;
+        assert!(matches!(result, Err(PtzError::ConnectionFailed(_))));
+        assert_eq!(calls.load(Ordering::SeqCst), 3);
+    }
+
+    #[tokio::test]
+    async fn retries_timeouts() {
+        let calls = AtomicU32::new(0);
+        let result: Result<(), PtzError> = instant_policy(3)
+            .run(|| async {
+                calls.fetch_add(1, Ordering::SeqCst);
+                Err(PtzError::Timeout("slow".into()))
+            })
+            .await;
+        assert!(result.is_err());
+        assert_eq!(calls.load(Ordering::SeqCst), 3);
+    }
+
+    #[tokio::test]
+    async fn does_not_retry_config_errors() {
+        let calls = AtomicU32::new(0);
+        let result: Result<(), PtzError> = instant_policy(3)
+            .run(|| async {
+                calls.fetch_add(1, Ordering::SeqCst);
+                Err(PtzError::InvalidConfig("bad host".into()))
+            })
+            .await;
+        assert!(matches!(result, Err(PtzError::InvalidConfig(_))));
+        assert_eq!(calls.load(Ordering::SeqCst), 1);
+    }
+
+    #[tokio::test]
+    async fn does_not_retry_protocol_errors() {
+        let calls = AtomicU32::new(0);
+        let result: Result<(), PtzError> = instant_policy(3)
+            .run(|| async {
+                calls.fetch_add(1, Ordering::SeqCst);
+                Err(PtzError::ProtocolError("bad reply".into()))
+            })
+            .await;
+        assert!(matches!(result, Err(PtzError::ProtocolError(_))));
+        assert_eq!(calls.load(Ordering::SeqCst), 1);
+    }
+
+    #[tokio::test]
+    async fn stops_retrying_after_success() {
+        let calls = AtomicU32::new(0);
+        let result = instant_policy(5)
+            .run(|| async {
+                if calls.fetch_add(1, Ordering::SeqCst) < 2 {
+                    Err(PtzError::ConnectionFailed("blip".into()))
+                } else {
+                    Ok(42)
+                }
+            })
+            .await;
+        assert_eq!(result.unwrap(), 42);
+        assert_eq!(calls.load(Ordering::SeqCst), 3);
+    }
+
+    #[tokio::test]
+    async fn none_policy_tries_once() {
+        let calls = AtomicU32::new(0);
+        let _: Result<(), PtzError> = RetryPolicy::none()
+            .run(|| async {
+                calls.fetch_add(1, Ordering::SeqCst);
+                Err(PtzError::ConnectionFailed("down".into()))
+            })
+            .await;
+        assert_eq!(calls.load(Ordering::SeqCst), 1);
+    }
+}
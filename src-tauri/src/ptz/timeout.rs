@@ -0,0 +1,50 @@
+use std::future::Future;
+use std::time::Duration;
+
+/// Default wait for a camera to answer an HTTP request.
+pub const HTTP_TIMEOUT: Duration = Duration::from_secs(5);
+
+tokio::task_local! {
+    static COMMAND_TIMEOUT: Duration;
+}
+
+/// Run a command with `timeout` replacing each protocol client's default
+/// reply timeout, e.g. to fail a connection test fast or to give a long
+/// move more time. `None` keeps the defaults.
+pub async fn scoped<F: Future>(timeout: Option<Duration>, command: F) -> F::Output {
+    match timeout {
+        Some(timeout) => COMMAND_TIMEOUT.scope(timeout, command).await,
+        None => command.await,
+    }
+}
+
+/// The override in effect, to carry into work spawned for the command.
+pub fn current() -> Option<Duration> {
+    COMMAND_TIMEOUT.try_with(|timeout| *timeout).ok()
+}
+
+/// The timeout a protocol request should wait: the caller's override if
+/// the command runs inside [`scoped`], otherwise the client's `default`.
+pub fn or_default(default: Duration) -> Duration {
+    COMMAND_TIMEOUT
+        .try_with(|timeout| *timeout)
+        .unwrap_or(default)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn override_applies_only_inside_the_scope() {
+        let default = Duration::from_secs(2);
+        assert_eq!(or_default(default), default);
+        let inside = scoped(Some(Duration::from_millis(100)), async {
+            or_default(default)
+        })
+        .await;
+        assert_eq!(inside, Duration::from_millis(100));
+        assert_eq!(scoped(None, async { or_default(default) }).await, default);
+        assert_eq!(or_default(default), default);
+    }
+}
@@ -0,0 +1,186 @@
+use serde::{Deserialize, Serialize};
+use std::future::Future;
+use std::time::Duration;
+use tokio::sync::watch;
+
+/// Shortest dwell accepted for a tour step.
+pub const MIN_DWELL_MS: u64 = 500;
+
+/// One stop on a preset tour: recall the preset, then hold for `dwell_ms`.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct TourStep {
+    pub preset_id: String,
+    pub dwell_ms: u64,
+}
+
+/// Check a tour definition before running or saving it.
+pub fn validate_tour(steps: &[TourStep]) -> Result<(), String> {
+    if steps.is_empty() {
+        return Err("Tour has no steps".to_string());
+    }
+    for step in steps {
+        if step.preset_id.trim().is_empty() {
+            return Err("Tour step is missing a preset".to_string());
+        }
+        if step.dwell_ms < MIN_DWELL_MS {
+            return Err(format!(
+                "Tour dwell must be at least {} ms (got {})",
+                MIN_DWELL_MS, step.dwell_ms
+            ));
+        }
+    }
+    Ok(())
+}
+
+/// Wait for `duration`, returning early with `true` if the tour was cancelled.
+async fn dwell(duration: Duration, cancel: &mut watch::Receiver<bool>) -> bool {
+    loop {
+        match tokio::time::timeout(duration, cancel.changed()).await {
+            Ok(Ok(())) if *cancel.borrow_and_update() => return true,
+            Ok(Ok(())) => continue,
+            Ok(Err(_)) => return true,
+            Err(_) => return false,
+        }
+    }
+}
+
+/// Cycle through `steps` until `cancel` is set or its sender is dropped.
+/// A failed recall is logged and the tour moves on to the next step.
+/// A single-step tour recalls its preset once and then holds.
+pub async fn run_tour<R, RFut>(
+    steps: Vec<TourStep>,
+    mut cancel: watch::Receiver<bool>,
+    mut recall: R,
+) where
+    R: FnMut(&TourStep) -> RFut,
+    RFut: Future<Output = Result<(), String>>,
+{
+    if steps.is_empty() {
+        return;
+    }
+    for (i, step) in steps.iter().cycle().enumerate() {
+        if *cancel.borrow_and_update() {
+            break;
+        }
+        if steps.len() > 1 || i == 0 {
+            if let Err(e) = recall(step).await {
+                log::warn!("Tour step '{}' failed: {}", step.preset_id, e);
+            }
+        }
+        if dwell(Duration::from_millis(step.dwell_ms), &mut cancel).await {
+            break;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ptz::controller::PtzController;
+    use crate::simulator::client::SimulatedController;
+    use std::collections::HashMap;
+    use std::sync::{Arc, Mutex};
+
+    fn step(preset_id: &str, dwell_ms: u64) -> TourStep {
+        TourStep {
+            preset_id: preset_id.to_string(),
+            dwell_ms,
+        }
+    }
+
+    /// Run a tour against the simulator until `recalls` presets were visited.
+    async fn run_against_simulator(steps: Vec<TourStep>, recalls: usize) -> Vec<(String, f64)> {
+        let presets: HashMap<&str, f64> = [("a", -0.5), ("b", 0.0), ("c", 0.5)].into();
+        let camera = SimulatedController::new();
+        let visited = Arc::new(Mutex::new(Vec::new()));
+        let (cancel_tx, cancel_rx) = watch::channel(false);
+
+        let recall = |step: &TourStep| {
+            let pan = presets.get(step.preset_id.as_str()).copied();
+            let preset_id = step.preset_id.clone();
+            let camera = &camera;
+            let visited = visited.clone();
+            let cancel_tx = &cancel_tx;
+            async move {
+                let pan = pan.ok_or("Preset not found")?;
+                camera
+                    .move_absolute(pan, 0.0, 0.0)
+                    .await
+                    .map_err(|e| e.to_string())?;
+                let reached = camera.get_position().await.unwrap().pan;
+                let mut visited = visited.lock().unwrap();
+                visited.push((preset_id, reached));
+                if visited.len() >= recalls {
+                    let _ = cancel_tx.send(true);
+                }
+                Ok(())
+            }
+        };
+        tokio::time::timeout(Duration::from_secs(5), run_tour(steps, cancel_rx, recall))
+            .await
+            .expect("tour did not stop");
+
+        let visited = visited.lock().unwrap().clone();
+        visited
+    }
+
+    #[tokio::test]
+    async fn tour_cycles_steps_in_order() {
+        let steps = vec![step("a", 1), step("b", 1), step("c", 1)];
+        let visited = run_against_simulator(steps, 5).await;
+        let ids: Vec<&str> = visited.iter().map(|(id, _)| id.as_str()).collect();
+        assert_eq!(ids, vec!["a", "b", "c", "a", "b"]);
+        assert_eq!(visited[2].1, 0.5);
+    }
+
+    #[tokio::test]
+    async fn tour_skips_missing_presets() {
+        let steps = vec![step("a", 1), step("missing", 1), step("c", 1)];
+        let visited = run_against_simulator(steps, 3).await;
+        let ids: Vec<&str> = visited.iter().map(|(id, _)| id.as_str()).collect();
+        assert_eq!(ids, vec!["a", "c", "a"]);
+    }
+
+    #[tokio::test]
+    async fn empty_tour_returns_immediately() {
+        let (_cancel_tx, cancel_rx) = watch::channel(false);
+        let calls = Mutex::new(0);
+        tokio::time::timeout(
+            Duration::from_secs(1),
+            run_tour(Vec::new(), cancel_rx, |_| {
+                *calls.lock().unwrap() += 1;
+                async { Ok(()) }
+            }),
+        )
+        .await
+        .expect("empty tour should not block");
+        assert_eq!(*calls.lock().unwrap(), 0);
+    }
+
+    #[tokio::test]
+    async fn single_step_tour_recalls_once_then_holds() {
+        let (cancel_tx, cancel_rx) = watch::channel(false);
+        let calls = Arc::new(Mutex::new(0));
+        let counter = calls.clone();
+        let handle = tokio::spawn(run_tour(vec![step("a", 1)], cancel_rx, move |_| {
+            *counter.lock().unwrap() += 1;
+            async { Ok(()) }
+        }));
+
+        tokio::time::sleep(Duration::from_millis(30)).await;
+        cancel_tx.send(true).unwrap();
+        tokio::time::timeout(Duration::from_secs(1), handle)
+            .await
+            .expect("tour did not stop")
+            .unwrap();
+        assert_eq!(*calls.lock().unwrap(), 1);
+    }
+
+    #[test]
+    fn validate_rejects_empty_and_short_dwell() {
+        assert!(validate_tour(&[]).is_err());
+        assert!(validate_tour(&[step("a", 10)]).is_err());
+        assert!(validate_tour(&[step(" ", MIN_DWELL_MS)]).is_err());
+        assert!(validate_tour(&[step("a", MIN_DWELL_MS), step("b", 2000)]).is_ok());
+    }
+}
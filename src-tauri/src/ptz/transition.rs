@@ -0,0 +1,214 @@
+use super::types::PtzPosition;
+use serde::{Deserialize, Serialize};
+use std::future::Future;
+use std::time::Duration;
+use tokio::sync::watch;
+
+/// Time between interpolated `move_absolute` steps.
+pub const STEP_INTERVAL: Duration = Duration::from_millis(50);
+
+/// Longest transition accepted, so a typo can't lock the camera up for minutes.
+pub const MAX_TRANSITION_MS: u64 = 30_000;
+
+/// Easing curve applied to a host-side transition.
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Default)]
+pub enum Easing {
+    Linear,
+    #[default]
+    EaseInOut,
+}
+
+impl Easing {
+    /// Map linear progress `t` in 0..=1 to eased progress in 0..=1.
+    pub fn apply(self, t: f64) -> f64 {
+        let t = t.clamp(0.0, 1.0);
+        match self {
+            Easing::Linear => t,
+            // Cubic ease-in-out
+            Easing::EaseInOut => {
+                if t < 0.5 {
+                    4.0 * t * t * t
+                } else {
+                    1.0 - (-2.0 * t + 2.0).powi(3) / 2.0
+                }
+            }
+        }
+    }
+}
+
+/// Per-profile settings for smooth preset recall.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct PresetTransition {
+    pub duration_ms: u64,
+    #[serde(default)]
+    pub easing: Easing,
+}
+
+impl PresetTransition {
+    pub fn validate(&self) -> Result<(), String> {
+        if self.duration_ms > MAX_TRANSITION_MS {
+            return Err(format!(
+                "Transition duration must be at most {} ms (got {})",
+                MAX_TRANSITION_MS, self.duration_ms
+            ));
+        }
+        Ok(())
+    }
+}
+
+/// Intermediate positions from `start` to `end`, excluding `start` and ending exactly on `end`.
+/// A duration shorter than one step yields just `end`.
+pub fn transition_path(
+    start: &PtzPosition,
+    end: &PtzPosition,
+    duration: Duration,
+    easing: Easing,
+    step_interval: Duration,
+) -> Vec<PtzPosition> {
+    let steps = (duration.as_millis() / step_interval.as_millis().max(1)).max(1) as usize;
+    let lerp = |a: f64, b: f64, t: f64| a + (b - a) * t;
+    (1..=steps)
+        .map(|i| {
+            if i == steps {
+                return end.clone();
+            }
+            let t = easing.apply(i as f64 / steps as f64);
+            PtzPosition {
+                pan: lerp(start.pan, end.pan, t),
+                tilt: lerp(start.tilt, end.tilt, t),
+                zoom: lerp(start.zoom, end.zoom, t),
+            }
+        })
+        .collect()
+}
+
+/// Send each position in `path` through `step`, one every `step_interval`.
+/// Returns `Ok(false)` if `cancel` fired (or its sender was dropped) before the end.
+pub async fn run_transition<S, SFut, E>(
+    path: Vec<PtzPosition>,
+    step_interval: Duration,
+    mut cancel: watch::Receiver<bool>,
+    mut step: S,
+) -> Result<bool, E>
+where
+    S: FnMut(PtzPosition) -> SFut,
+    SFut: Future<Output = Result<(), E>>,
+{
+    let last = path.len().saturating_sub(1);
+    for (i, pos) in path.into_iter().enumerate() {
+        if *cancel.borrow_and_update() {
+            return Ok(false);
+        }
+        step(pos).await?;
+        if i == last {
+            break;
+        }
+        match tokio::time::timeout(step_interval, cancel.changed()).await {
+            Ok(Ok(())) if *cancel.borrow_and_update() => return Ok(false),
+            Ok(Ok(())) | Err(_) => {}
+            Ok(Err(_)) => return Ok(false),
+        }
+    }
+    Ok(true)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn pos(pan: f64, tilt: f64, zoom: f64) -> PtzPosition {
+        PtzPosition { pan, tilt, zoom }
+    }
+
+    #[test]
+    fn easing_hits_endpoints_and_midpoint() {
+        for easing in [Easing::Linear, Easing::EaseInOut] {
+            assert_eq!(easing.apply(0.0), 0.0);
+            assert_eq!(easing.apply(1.0), 1.0);
+            assert!((easing.apply(0.5) - 0.5).abs() < 1e-9);
+        }
+        // Ease-in-out starts slower than linear
+        assert!(Easing::EaseInOut.apply(0.1) < Easing::Linear.apply(0.1));
+    }
+
+    #[test]
+    fn path_is_monotonic_and_ends_on_target() {
+        let start = pos(-1.0, 0.5, 0.0);
+        let end = pos(0.5, -0.5, 1.0);
+        for easing in [Easing::Linear, Easing::EaseInOut] {
+            let path = transition_path(
+                &start,
+                &end,
+                Duration::from_millis(1000),
+                easing,
+                STEP_INTERVAL,
+            );
+            assert_eq!(path.len(), 20);
+            assert_eq!(path.last(), Some(&end));
+            let mut prev = start.clone();
+            for p in &path {
+                assert!(p.pan >= prev.pan, "pan went backwards with {:?}", easing);
+                assert!(p.tilt <= prev.tilt, "tilt went backwards with {:?}", easing);
+                assert!(p.zoom >= prev.zoom, "zoom went backwards with {:?}", easing);
+                prev = p.clone();
+            }
+        }
+    }
+
+    #[test]
+    fn short_duration_jumps_straight_to_target() {
+        let end = pos(0.3, 0.3, 0.3);
+        let path = transition_path(
+            &pos(0.0, 0.0, 0.0),
+            &end,
+            Duration::from_millis(10),
+            Easing::Linear,
+            STEP_INTERVAL,
+        );
+        assert_eq!(path, vec![end]);
+    }
+
+    #[test]
+    fn validate_caps_duration() {
+        let mut transition = PresetTransition {
+            duration_ms: 2000,
+            easing: Easing::Linear,
+        };
+        assert!(transition.validate().is_ok());
+        transition.duration_ms = MAX_TRANSITION_MS + 1;
+        assert!(transition.validate().is_err());
+    }
+
+    #[tokio::test]
+    async fn run_transition_sends_every_step() {
+        let (_cancel_tx, cancel_rx) = watch::channel(false);
+        let path = vec![pos(0.1, 0.0, 0.0), pos(0.2, 0.0, 0.0), pos(0.3, 0.0, 0.0)];
+        let mut sent = Vec::new();
+        let finished = run_transition(path, Duration::from_millis(1), cancel_rx, |p| {
+            sent.push(p.pan);
+            async { Ok::<(), String>(()) }
+        })
+        .await
+        .unwrap();
+        assert!(finished);
+        assert_eq!(sent, vec![0.1, 0.2, 0.3]);
+    }
+
+    #[tokio::test]
+    async fn run_transition_aborts_on_cancel() {
+        let (cancel_tx, cancel_rx) = watch::channel(false);
+        let path = (1..=10).map(|i| pos(i as f64 / 10.0, 0.0, 0.0)).collect();
+        let mut sent = 0;
+        let finished = run_transition(path, Duration::from_millis(1), cancel_rx, |_| {
+            sent += 1;
+            if sent == 3 {
+                let _ = cancel_tx.send(true);
+            }
+            async { Ok::<(), String>(()) }
+        })
+        .await
+        .unwrap();
+        assert!(!finished);
+        assert_eq!(sent, 3);
+    }
+}
@@ -1,7 +1,10 @@
+use super::geometry::{DegreeScale, LensCurve, MechanicalRange};
+use super::tour::TourStep;
+use super::transition::PresetTransition;
 use serde::{Deserialize, Serialize};
 
 /// Normalized PTZ position: pan/tilt in [-1.0, 1.0], zoom in [0.0, 1.0].
-#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[derive(Debug, Clone, Default, Serialize, Deserialize, PartialEq)]
 pub struct PtzPosition {
     pub pan: f64,
     pub tilt: f64,
@@ -18,6 +21,110 @@ pub enum PtzCommand {
     StorePreset { index: u8 },
 }
 
+/// Camera auto-exposure mode.
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq)]
+pub enum ExposureMode {
+    Auto,
+    Manual,
+}
+
+/// Camera white-balance mode, with explicit gains for manual balance.
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq)]
+pub enum WhiteBalanceSetting {
+    Auto,
+    Indoor,
+    Outdoor,
+    /// Hold the balance from the last one-push calibration.
+    OnePush,
+    Manual {
+        red_gain: u8,
+        blue_gain: u8,
+    },
+}
+
+/// How a camera carries out relative pan/tilt moves.
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Default)]
+pub enum RelativeMode {
+    /// Drive at a speed for a short burst; quick but the distance varies.
+    #[default]
+    NativeSpeed,
+    /// Read the position and move to position + delta; repeatable.
+    ViaAbsolute,
+}
+
+/// How a VISCA camera carries out absolute moves.
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Default)]
+pub enum AbsoluteStrategy {
+    /// Send the absolute pan/tilt and zoom position commands.
+    #[default]
+    AbsolutePosition,
+    /// Recall the stored native preset closest to the target, for camera
+    /// blocks that ignore absolute positioning but honor preset recall.
+    /// Moves with no stored slot nearby fail.
+    NearestNativePreset,
+}
+
+/// Tally lamp state following the switcher's program/preview bus.
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq)]
+pub enum TallyState {
+    Off,
+    Preview,
+    Program,
+}
+
+/// Who made a camera and what it is, as reported by the camera itself.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct CameraInfo {
+    pub vendor: String,
+    pub model: String,
+    pub firmware: Option<String>,
+}
+
+/// What a camera's protocol can do, so the UI can hide controls it lacks.
+#[derive(Debug, Clone, Copy, Default, Serialize, Deserialize, PartialEq)]
+pub struct Capabilities {
+    /// Move to an absolute pan/tilt/zoom position.
+    pub supports_absolute: bool,
+    /// Reports its pan/tilt/zoom position.
+    pub supports_position: bool,
+    /// Continuous pan/tilt at a velocity.
+    pub supports_continuous: bool,
+    /// Continuous zoom at a velocity.
+    pub supports_continuous_zoom: bool,
+    /// Manual focus and autofocus control.
+    pub supports_focus: bool,
+    /// Absolute focus positioning and focus queries.
+    pub supports_focus_position: bool,
+    /// Iris, gain, shutter and exposure mode.
+    pub supports_exposure: bool,
+    pub supports_white_balance: bool,
+    /// Image flip and mirror.
+    pub supports_image_flip: bool,
+    /// On-screen menu navigation.
+    pub supports_menu: bool,
+    /// On-camera tally lamp.
+    pub supports_tally: bool,
+    /// Renaming the camera's NDI source.
+    pub supports_ndi_name: bool,
+    /// Gamma selection and picture profile recall.
+    pub supports_picture_profile: bool,
+    /// Camera-side preset memory.
+    pub supports_native_presets: bool,
+    /// Standby and power on.
+    pub supports_power: bool,
+    /// Number of camera preset slots, 0 without native presets.
+    pub max_preset_slots: u16,
+}
+
+/// Cursor movement in a camera's on-screen menu.
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq)]
+pub enum MenuDirection {
+    Up,
+    Down,
+    Left,
+    Right,
+}
+
 /// Supported PTZ control protocols.
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
 pub enum PtzProtocol {
@@ -25,32 +132,385 @@ pub enum PtzProtocol {
     Visca,
     PanasonicAw,
     BirdDogRest,
+    PelcoD,
+    Onvif,
     Simulated,
 }
 
+/// Divisors mapping raw camera position units to the normalized range.
+/// Pan/tilt are divided into -1.0..1.0, zoom into 0.0..1.0.
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq)]
+pub struct PositionScale {
+    pub pan_max: f64,
+    pub tilt_max: f64,
+    pub zoom_max: f64,
+}
+
+impl Default for PositionScale {
+    /// Values are already normalized.
+    fn default() -> Self {
+        Self {
+            pan_max: 1.0,
+            tilt_max: 1.0,
+            zoom_max: 1.0,
+        }
+    }
+}
+
+/// Raw VISCA pan/tilt range of a camera model, mapped onto -1.0..1.0.
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq)]
+pub struct ViscaCalibration {
+    pub pan_min: i16,
+    pub pan_max: i16,
+    pub tilt_min: i16,
+    pub tilt_max: i16,
+}
+
+impl Default for ViscaCalibration {
+    /// The range of Sony BRC/SRG cameras.
+    fn default() -> Self {
+        Self {
+            pan_min: -880,
+            pan_max: 880,
+            tilt_min: -400,
+            tilt_max: 288,
+        }
+    }
+}
+
+impl ViscaCalibration {
+    pub fn validate(&self) -> Result<(), String> {
+        if self.pan_min >= self.pan_max || self.tilt_min >= self.tilt_max {
+            return Err("Calibration minimums must be below their maximums".to_string());
+        }
+        Ok(())
+    }
+}
+
+/// Software pan/tilt limits in normalized units, to keep a camera from
+/// driving into walls or pillars.
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq)]
+pub struct PtzLimits {
+    pub pan_min: f64,
+    pub pan_max: f64,
+    pub tilt_min: f64,
+    pub tilt_max: f64,
+}
+
+impl Default for PtzLimits {
+    /// The full -1.0..1.0 range on both axes.
+    fn default() -> Self {
+        Self {
+            pan_min: -1.0,
+            pan_max: 1.0,
+            tilt_min: -1.0,
+            tilt_max: 1.0,
+        }
+    }
+}
+
+impl PositionScale {
+    pub fn validate(&self) -> Result<(), String> {
+        if [self.pan_max, self.tilt_max, self.zoom_max]
+            .into_iter()
+            .all(|v| v.is_finite() && v > 0.0)
+        {
+            Ok(())
+        } else {
+            Err("Position scale values must be positive numbers".to_string())
+        }
+    }
+}
+
+impl PtzLimits {
+    pub fn validate(&self) -> Result<(), String> {
+        let in_range = |v: f64| v.is_finite() && (-1.0..=1.0).contains(&v);
+        if ![self.pan_min, self.pan_max, self.tilt_min, self.tilt_max]
+            .into_iter()
+            .all(in_range)
+        {
+            return Err("Limits must be between -1.0 and 1.0".to_string());
+        }
+        if self.pan_min >= self.pan_max || self.tilt_min >= self.tilt_max {
+            return Err("Limit minimums must be below their maximums".to_string());
+        }
+        Ok(())
+    }
+
+    /// Whether the limits restrict movement at all.
+    pub fn is_full_range(&self) -> bool {
+        *self == Self::default()
+    }
+
+    /// Clamp a pan/tilt target into the allowed area.
+    pub fn clamp(&self, pan: f64, tilt: f64) -> (f64, f64) {
+        (
+            pan.clamp(self.pan_min, self.pan_max),
+            tilt.clamp(self.tilt_min, self.tilt_max),
+        )
+    }
+
+    /// The preset with its pan/tilt clamped into the allowed area. A preset
+    /// outside the limits loses its native slot, since the camera would
+    /// otherwise recall the stored, out-of-bounds position.
+    pub fn clamp_preset(&self, preset: &Preset) -> Preset {
+        let (pan, tilt) = self.clamp(preset.pan, preset.tilt);
+        if (pan, tilt) == (preset.pan, preset.tilt) {
+            return preset.clone();
+        }
+        Preset {
+            pan,
+            tilt,
+            native_slot: None,
+            ..preset.clone()
+        }
+    }
+
+    /// Zero the velocity of any axis at or beyond a limit and moving further out.
+    pub fn limit_velocity(
+        &self,
+        pan: f64,
+        tilt: f64,
+        pan_speed: f64,
+        tilt_speed: f64,
+    ) -> (f64, f64) {
+        let axis = |pos: f64, speed: f64, min: f64, max: f64| {
+            if (speed > 0.0 && pos >= max) || (speed < 0.0 && pos <= min) {
+                0.0
+            } else {
+                speed
+            }
+        };
+        (
+            axis(pan, pan_speed, self.pan_min, self.pan_max),
+            axis(tilt, tilt_speed, self.tilt_min, self.tilt_max),
+        )
+    }
+}
+
+/// Software pan/tilt limits in degrees from center, converted to
+/// [`PtzLimits`] when a command is sent. Right and up are positive.
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq)]
+pub struct DegreeLimits {
+    pub pan_min: f64,
+    pub pan_max: f64,
+    pub tilt_min: f64,
+    pub tilt_max: f64,
+}
+
+impl DegreeLimits {
+    pub fn validate(&self) -> Result<(), String> {
+        if ![self.pan_min, self.pan_max, self.tilt_min, self.tilt_max]
+            .into_iter()
+            .all(f64::is_finite)
+        {
+            return Err("Limits must be finite".to_string());
+        }
+        if self.pan_min >= self.pan_max || self.tilt_min >= self.tilt_max {
+            return Err("Limit minimums must be below their maximums".to_string());
+        }
+        Ok(())
+    }
+
+    /// The same limits in normalized units; angles past the end of travel
+    /// clamp to it.
+    pub fn to_normalized(&self, scale: DegreeScale) -> PtzLimits {
+        let (pan_min, tilt_min) = scale.to_normalized(self.pan_min, self.tilt_min);
+        let (pan_max, tilt_max) = scale.to_normalized(self.pan_max, self.tilt_max);
+        PtzLimits {
+            pan_min,
+            pan_max,
+            tilt_min,
+            tilt_max,
+        }
+    }
+}
+
 /// Protocol-specific connection configuration.
-#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 #[serde(tag = "type")]
 pub enum ProtocolConfig {
-    #[default]
-    Ndi,
+    Ndi {
+        /// Name of the discovered NDI source that carries PTZ control.
+        #[serde(default)]
+        source_name: String,
+    },
     Visca {
         host: String,
         port: u16,
+        /// Software pan/tilt limits; `None` allows the full range.
+        #[serde(default)]
+        limits: Option<PtzLimits>,
+        /// Tally lamp opcode bytes after `81 01`; `None` uses `7E 01 0A`.
+        #[serde(default)]
+        tally_opcode: Option<[u8; 3]>,
+        /// Gamma select opcode bytes after `81 01`; `None` uses `04 5B`.
+        #[serde(default)]
+        gamma_opcode: Option<[u8; 2]>,
+        /// Picture profile opcode bytes after `81 01`; `None` uses `7E 04 5F`.
+        #[serde(default)]
+        picture_profile_opcode: Option<[u8; 3]>,
+        /// Raw full-tele zoom position; `None` uses `0x4000`.
+        #[serde(default)]
+        zoom_max: Option<u16>,
+        /// Raw full-tele position with digital zoom, which the top of the
+        /// zoom range maps into; `None` keeps zoom optical-only.
+        #[serde(default)]
+        digital_zoom_max: Option<u16>,
+        /// Digital zoom on/off opcode bytes after `81 01`; `None` uses `04 06`.
+        #[serde(default)]
+        digital_zoom_opcode: Option<[u8; 2]>,
+        /// Raw pan/tilt range; `None` uses the Sony range.
+        #[serde(default)]
+        calibration: Option<ViscaCalibration>,
+        /// Send zoom only after pan/tilt reports completion in absolute
+        /// moves, for cameras that drop commands mid-slew.
+        #[serde(default)]
+        wait_for_completion: bool,
+        /// Local IP address to send from, pinning the interface on hosts
+        /// with several networks; `None` lets the OS choose.
+        #[serde(default)]
+        bind_address: Option<String>,
+        /// How absolute moves reach their target.
+        #[serde(default)]
+        absolute_strategy: AbsoluteStrategy,
     },
     PanasonicAw {
         host: String,
         port: u16,
         username: Option<String>,
         password: Option<String>,
+        /// Absolute-move speed (normalized 0.0 to 1.0). `None` keeps the legacy `30` speed.
+        #[serde(default)]
+        move_speed: Option<f64>,
+        /// Top of the firmware's APS speed table, `0x29` on extended-range
+        /// firmware. `None` uses the standard `0x1D`.
+        #[serde(default)]
+        move_speed_max: Option<u8>,
+        #[serde(default)]
+        relative_mode: RelativeMode,
     },
     BirdDogRest {
         host: String,
         port: u16,
+        #[serde(default)]
+        username: Option<String>,
+        #[serde(default)]
+        password: Option<String>,
+        /// Raw position range; `None` auto-detects from the camera.
+        #[serde(default)]
+        position_scale: Option<PositionScale>,
+    },
+    /// Pelco-D dome behind a serial-to-IP gateway.
+    PelcoD {
+        host: String,
+        port: u16,
+        /// Receiver address set on the dome's DIP switches (1 to 255).
+        address: u8,
+    },
+    /// ONVIF PTZ service, authenticated with a WS-Security UsernameToken.
+    Onvif {
+        host: String,
+        port: u16,
+        #[serde(default)]
+        username: Option<String>,
+        #[serde(default)]
+        password: Option<String>,
     },
     Simulated,
 }
 
+impl ProtocolConfig {
+    /// Check the host and port of network protocols before persisting.
+    pub fn validate(&self) -> Result<(), String> {
+        match self {
+            ProtocolConfig::Ndi { .. } | ProtocolConfig::Simulated => Ok(()),
+            ProtocolConfig::Visca { host, port, .. }
+            | ProtocolConfig::PanasonicAw { host, port, .. }
+            | ProtocolConfig::BirdDogRest { host, port, .. }
+            | ProtocolConfig::PelcoD { host, port, .. }
+            | ProtocolConfig::Onvif { host, port, .. } => {
+                validate_host(host)?;
+                validate_port(*port)?;
+                match self {
+                    ProtocolConfig::PelcoD { address: 0, .. } => {
+                        Err("Pelco-D address must be between 1 and 255".to_string())
+                    }
+                    ProtocolConfig::BirdDogRest {
+                        position_scale: Some(scale),
+                        ..
+                    } => scale.validate(),
+                    ProtocolConfig::PanasonicAw {
+                        move_speed: Some(speed),
+                        ..
+                    } if !speed.is_finite() || !(0.0..=1.0).contains(speed) => {
+                        Err("Panasonic move speed must be between 0.0 and 1.0".to_string())
+                    }
+                    ProtocolConfig::PanasonicAw {
+                        move_speed_max: Some(max),
+                        ..
+                    } if !(1..=crate::panasonic::client::APS_SPEED_MAX_EXTENDED).contains(max) => {
+                        Err(format!(
+                            "Panasonic speed maximum must be between 1 and {}",
+                            crate::panasonic::client::APS_SPEED_MAX_EXTENDED
+                        ))
+                    }
+                    ProtocolConfig::Visca {
+                        zoom_max: Some(0), ..
+                    } => Err("VISCA zoom maximum must be above zero".to_string()),
+                    ProtocolConfig::Visca {
+                        zoom_max,
+                        digital_zoom_max: Some(digital_zoom_max),
+                        ..
+                    } if *digital_zoom_max
+                        <= zoom_max.unwrap_or(crate::visca::commands::VISCA_ZOOM_MAX) =>
+                    {
+                        Err(
+                            "VISCA digital zoom maximum must be above the optical maximum"
+                                .to_string(),
+                        )
+                    }
+                    ProtocolConfig::Visca {
+                        host,
+                        limits,
+                        calibration,
+                        bind_address,
+                        ..
+                    } => {
+                        if let Some(bind_address) = bind_address {
+                            validate_bind_address(bind_address, host)?;
+                        }
+                        if let Some(calibration) = calibration {
+                            calibration.validate()?;
+                        }
+                        limits.map_or(Ok(()), |limits| limits.validate())
+                    }
+                    _ => Ok(()),
+                }
+            }
+        }
+    }
+
+    /// Hash of the whole config, to tell whether a controller built from an
+    /// earlier version of it is still current.
+    pub fn fingerprint(&self) -> u64 {
+        use std::hash::{DefaultHasher, Hash, Hasher};
+        let mut hasher = DefaultHasher::new();
+        serde_json::to_string(self)
+            .unwrap_or_default()
+            .hash(&mut hasher);
+        hasher.finish()
+    }
+}
+
+impl Default for ProtocolConfig {
+    fn default() -> Self {
+        ProtocolConfig::Ndi {
+            source_name: String::new(),
+        }
+    }
+}
+
 /// A camera endpoint definition for PTZ control.
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct CameraEndpoint {
@@ -58,6 +518,67 @@ pub struct CameraEndpoint {
     pub name: String,
     pub protocol: PtzProtocol,
     pub config: ProtocolConfig,
+    /// Color the UI tags the endpoint with, as lowercase `#rrggbb`.
+    #[serde(default)]
+    pub color: Option<String>,
+    /// Name of the icon the UI shows next to the endpoint.
+    #[serde(default)]
+    pub icon: Option<String>,
+}
+
+/// Longest icon name an endpoint may carry.
+pub const MAX_ENDPOINT_ICON_LEN: usize = 64;
+
+impl CameraEndpoint {
+    /// Check the config and display fields, normalizing the color like a
+    /// preset's and dropping a blank icon.
+    pub fn validated(mut self) -> Result<Self, String> {
+        self.config.validate()?;
+        if let Some(color) = &self.color {
+            self.color = Some(super::color::validate_color(color)?);
+        }
+        if let Some(icon) = &self.icon {
+            let icon = icon.trim();
+            if icon.chars().count() > MAX_ENDPOINT_ICON_LEN {
+                return Err(format!(
+                    "Icon name is longer than {} characters",
+                    MAX_ENDPOINT_ICON_LEN
+                ));
+            }
+            self.icon = (!icon.is_empty()).then(|| icon.to_string());
+        }
+        Ok(self)
+    }
+}
+
+/// Live state of a VISCA link, for troubleshooting flaky cameras.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct ViscaDiagnostics {
+    /// Sequence number the next command will carry.
+    pub sequence: u32,
+    /// Whether a socket to the camera is open.
+    pub connected: bool,
+    /// Last datagram received from the camera, header included, as hex.
+    pub last_reply_hex: Option<String>,
+}
+
+/// Outcome of an endpoint connection test.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct ConnectionTestResult {
+    pub ok: bool,
+    /// Round-trip time of the test, in milliseconds.
+    pub latency_ms: u64,
+    /// Camera model or firmware, if the camera reported one.
+    pub model: Option<String>,
+    /// Human-readable summary for display.
+    pub message: String,
+}
+
+/// Connection test outcome for one endpoint of a bulk test.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct EndpointTestResult {
+    pub endpoint_id: String,
+    pub result: ConnectionTestResult,
 }
 
 /// A single preset definition.
@@ -69,6 +590,98 @@ pub struct Preset {
     pub tilt: f64,
     pub zoom: f64,
     pub color: String,
+    /// Camera preset memory slot; when set, recall uses the camera's own preset.
+    #[serde(default)]
+    pub native_slot: Option<u8>,
+    /// Focus position (0.0 = nearest, 1.0 = infinity) applied after the move.
+    #[serde(default)]
+    pub focus: Option<f64>,
+    /// White balance applied after the move.
+    #[serde(default)]
+    pub white_balance: Option<WhiteBalanceSetting>,
+    /// Keyboard shortcut that recalls the preset, e.g. `F1` or `Ctrl+1`.
+    /// Unique within a profile, ignoring case.
+    #[serde(default)]
+    pub hotkey: Option<String>,
+    /// Downscaled frame from when the preset was captured, as base64 JPEG.
+    #[serde(default)]
+    pub thumbnail: Option<String>,
+}
+
+impl Preset {
+    /// Color given to presets created without one.
+    pub fn default_color() -> String {
+        "#3b82f6".to_string()
+    }
+}
+
+/// Fields for a new preset in a batch create; the id is assigned on creation.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PresetInput {
+    pub name: String,
+    pub pan: f64,
+    pub tilt: f64,
+    pub zoom: f64,
+    #[serde(default = "Preset::default_color")]
+    pub color: String,
+    #[serde(default)]
+    pub hotkey: Option<String>,
+}
+
+impl PresetInput {
+    /// Validate the fields and build a preset with a fresh id.
+    pub fn into_preset(self) -> Result<Preset, String> {
+        if !self.pan.is_finite() || !self.tilt.is_finite() || !self.zoom.is_finite() {
+            return Err("Preset values must be finite numbers".to_string());
+        }
+        let name = self.name.chars().take(100).collect::<String>();
+        if name.trim().is_empty() {
+            return Err("Preset name cannot be empty".to_string());
+        }
+        Ok(Preset {
+            id: uuid::Uuid::new_v4().to_string(),
+            name,
+            pan: self.pan.clamp(-1.0, 1.0),
+            tilt: self.tilt.clamp(-1.0, 1.0),
+            zoom: self.zoom.clamp(0.0, 1.0),
+            color: super::color::validate_color(&self.color)?,
+            native_slot: None,
+            focus: None,
+            white_balance: None,
+            hotkey: self.hotkey,
+            thumbnail: None,
+        })
+    }
+}
+
+/// An item of a batch operation that was not applied.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct BatchFailure {
+    /// Position of the item in the request.
+    pub index: usize,
+    pub error: String,
+}
+
+/// Outcome of a batch operation: the items applied and those rejected.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BatchResult<T> {
+    pub succeeded: Vec<T>,
+    pub failed: Vec<BatchFailure>,
+}
+
+impl<T> BatchResult<T> {
+    pub fn new() -> Self {
+        Self {
+            succeeded: Vec::new(),
+            failed: Vec::new(),
+        }
+    }
+}
+
+impl<T> Default for BatchResult<T> {
+    fn default() -> Self {
+        Self::new()
+    }
 }
 
 /// Validate that a host string is a safe IP address or hostname.
@@ -87,9 +700,54 @@ pub fn validate_host(host: &str) -> Result<(), String> {
     if !valid {
         return Err(format!("Invalid host characters: '{}'", host));
     }
+    // Colons only belong in IPv6 literals; `host:port` or `a:b:c` would build a bad URL
+    if host.contains(':') && host.parse::<std::net::Ipv6Addr>().is_err() {
+        return Err(format!(
+            "Invalid host: '{}' (set the port separately)",
+            host
+        ));
+    }
+    Ok(())
+}
+
+/// Reject port 0, which no camera listens on.
+pub fn validate_port(port: u16) -> Result<(), String> {
+    if port == 0 {
+        return Err("Port must be between 1 and 65535".to_string());
+    }
     Ok(())
 }
 
+/// Parse a local address to send to `host` from. It must be a unicast IP
+/// of the same family as `host`, when `host` is an IP literal.
+pub fn validate_bind_address(address: &str, host: &str) -> Result<std::net::IpAddr, String> {
+    let ip: std::net::IpAddr = address
+        .trim()
+        .parse()
+        .map_err(|_| format!("Invalid bind address: '{}'", address))?;
+    if ip.is_multicast() {
+        return Err(format!("Bind address '{}' is a multicast address", ip));
+    }
+    if let Ok(target) = host.parse::<std::net::IpAddr>() {
+        if target.is_ipv4() != ip.is_ipv4() {
+            return Err(format!(
+                "Bind address '{}' is not in the same address family as '{}'",
+                ip, host
+            ));
+        }
+    }
+    Ok(ip)
+}
+
+/// Format `host:port` for URLs and socket addresses, bracketing IPv6 literals.
+pub fn host_with_port(host: &str, port: u16) -> String {
+    if host.parse::<std::net::Ipv6Addr>().is_ok() {
+        format!("[{}]:{}", host, port)
+    } else {
+        format!("{}:{}", host, port)
+    }
+}
+
 /// A named collection of presets for a particular camera setup.
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct PresetProfile {
@@ -98,6 +756,66 @@ pub struct PresetProfile {
     pub camera_fov_degrees: f64,
     pub endpoint_id: Option<String>,
     pub presets: Vec<Preset>,
+    /// Preset sequence for unattended tours.
+    #[serde(default)]
+    pub tour: Option<Vec<TourStep>>,
+    /// Host-side interpolation for preset recall; `None` moves directly.
+    #[serde(default)]
+    pub transition: Option<PresetTransition>,
+    /// Preset the home command recalls; `None` uses the camera's own home.
+    #[serde(default)]
+    pub home_preset_id: Option<String>,
+    /// How zoom narrows the FOV of this profile's camera, for click-to-move.
+    #[serde(default)]
+    pub lens_curve: LensCurve,
+    /// The head's travel, for converting degrees; `None` scales by the FOV.
+    #[serde(default)]
+    pub mechanical_range: Option<MechanicalRange>,
+    /// Soft pan/tilt limits in degrees for absolute and relative moves;
+    /// `None` allows the full range.
+    #[serde(default)]
+    pub limits_degrees: Option<DegreeLimits>,
+}
+
+impl PresetProfile {
+    /// The preset home recalls, if one is set and still exists.
+    pub fn home_preset(&self) -> Option<&Preset> {
+        let id = self.home_preset_id.as_deref()?;
+        self.presets.iter().find(|p| p.id == id)
+    }
+
+    /// Check that the home preset, if set, is one of the profile's presets.
+    pub fn validate_home_preset(&self) -> Result<(), String> {
+        match &self.home_preset_id {
+            Some(id) if !self.presets.iter().any(|p| &p.id == id) => Err(format!(
+                "Home preset '{}' is not in profile '{}'",
+                id, self.name
+            )),
+            _ => Ok(()),
+        }
+    }
+
+    /// Degrees per normalized unit for this profile's camera.
+    pub fn degree_scale(&self) -> DegreeScale {
+        DegreeScale::new(self.camera_fov_degrees, self.mechanical_range)
+    }
+
+    /// The profile's degree limits in normalized units, if it has any.
+    pub fn soft_limits(&self) -> Option<PtzLimits> {
+        self.limits_degrees
+            .map(|limits| limits.to_normalized(self.degree_scale()))
+    }
+
+    /// Check the mechanical range and degree limits, if set.
+    pub fn validate_limits(&self) -> Result<(), String> {
+        if let Some(range) = &self.mechanical_range {
+            range.validate()?;
+        }
+        if let Some(limits) = &self.limits_degrees {
+            limits.validate()?;
+        }
+        Ok(())
+    }
 }
 
 #[cfg(test)]
@@ -174,21 +892,41 @@ mod tests {
     fn protocol_config_default_is_ndi() {
         let config = ProtocolConfig::default();
         match config {
-            ProtocolConfig::Ndi => {}
+            ProtocolConfig::Ndi { source_name } => assert!(source_name.is_empty()),
             _ => panic!("Expected Ndi default"),
         }
     }
 
+    #[test]
+    fn protocol_config_ndi_without_source_name_deserializes() {
+        let decoded: ProtocolConfig = serde_json::from_str(r#"{"type":"Ndi"}"#).unwrap();
+        match decoded {
+            ProtocolConfig::Ndi { source_name } => assert!(source_name.is_empty()),
+            _ => panic!("Expected Ndi"),
+        }
+    }
+
     #[test]
     fn protocol_config_visca_roundtrips() {
         let config = ProtocolConfig::Visca {
             host: "192.168.1.100".to_string(),
             port: 1259,
+            limits: None,
+            tally_opcode: None,
+            gamma_opcode: None,
+            picture_profile_opcode: None,
+            zoom_max: None,
+            digital_zoom_max: None,
+            digital_zoom_opcode: None,
+            calibration: None,
+            wait_for_completion: false,
+            bind_address: None,
+            absolute_strategy: AbsoluteStrategy::AbsolutePosition,
         };
         let json = serde_json::to_string(&config).unwrap();
         let decoded: ProtocolConfig = serde_json::from_str(&json).unwrap();
         match decoded {
-            ProtocolConfig::Visca { host, port } => {
+            ProtocolConfig::Visca { host, port, .. } => {
                 assert_eq!(host, "192.168.1.100");
                 assert_eq!(port, 1259);
             }
@@ -203,6 +941,9 @@ mod tests {
             port: 80,
             username: Some("admin".to_string()),
             password: Some("secret".to_string()),
+            move_speed: Some(0.5),
+            move_speed_max: Some(0x29),
+            relative_mode: RelativeMode::ViaAbsolute,
         };
         let json = serde_json::to_string(&config).unwrap();
         let decoded: ProtocolConfig = serde_json::from_str(&json).unwrap();
@@ -212,11 +953,37 @@ mod tests {
                 port,
                 username,
                 password,
+                move_speed,
+                move_speed_max,
+                relative_mode,
             } => {
                 assert_eq!(host, "10.0.0.1");
                 assert_eq!(port, 80);
                 assert_eq!(username.as_deref(), Some("admin"));
                 assert_eq!(password.as_deref(), Some("secret"));
+                assert_eq!(move_speed, Some(0.5));
+                assert_eq!(move_speed_max, Some(0x29));
+                assert_eq!(relative_mode, RelativeMode::ViaAbsolute);
+            }
+            _ => panic!("Expected PanasonicAw"),
+        }
+    }
+
+    #[test]
+    fn protocol_config_panasonic_without_move_speed_deserializes() {
+        let json =
+            r#"{"type":"PanasonicAw","host":"10.0.0.1","port":80,"username":null,"password":null}"#;
+        let decoded: ProtocolConfig = serde_json::from_str(json).unwrap();
+        match decoded {
+            ProtocolConfig::PanasonicAw {
+                move_speed,
+                move_speed_max,
+                relative_mode,
+                ..
+            } => {
+                assert!(move_speed.is_none());
+                assert!(move_speed_max.is_none());
+                assert_eq!(relative_mode, RelativeMode::NativeSpeed);
             }
             _ => panic!("Expected PanasonicAw"),
         }
@@ -224,6 +991,134 @@ mod tests {
 
     // --- validate_host tests ---
 
+    #[test]
+    fn protocol_config_validate_checks_host_and_port() {
+        let visca = |host: &str, port: u16| ProtocolConfig::Visca {
+            host: host.to_string(),
+            port,
+            limits: None,
+            tally_opcode: None,
+            gamma_opcode: None,
+            picture_profile_opcode: None,
+            zoom_max: None,
+            digital_zoom_max: None,
+            digital_zoom_opcode: None,
+            calibration: None,
+            wait_for_completion: false,
+            bind_address: None,
+            absolute_strategy: AbsoluteStrategy::AbsolutePosition,
+        };
+        assert!(visca("192.168.1.10", 52381).validate().is_ok());
+        assert!(visca("cam/../x", 52381).validate().is_err());
+        assert!(visca("192.168.1.10", 0).validate().is_err());
+        let zoom_max = |zoom_max| ProtocolConfig::Visca {
+            host: "192.168.1.10".to_string(),
+            port: 52381,
+            limits: None,
+            tally_opcode: None,
+            gamma_opcode: None,
+            picture_profile_opcode: None,
+            zoom_max: Some(zoom_max),
+            digital_zoom_max: None,
+            digital_zoom_opcode: None,
+            calibration: None,
+            wait_for_completion: false,
+            bind_address: None,
+            absolute_strategy: AbsoluteStrategy::AbsolutePosition,
+        };
+        assert!(zoom_max(0x7AC0).validate().is_ok());
+        assert!(zoom_max(0).validate().is_err());
+        let digital = |digital_zoom_max| ProtocolConfig::Visca {
+            host: "192.168.1.10".to_string(),
+            port: 52381,
+            limits: None,
+            tally_opcode: None,
+            gamma_opcode: None,
+            picture_profile_opcode: None,
+            zoom_max: None,
+            digital_zoom_max: Some(digital_zoom_max),
+            digital_zoom_opcode: None,
+            calibration: None,
+            wait_for_completion: false,
+            bind_address: None,
+            absolute_strategy: AbsoluteStrategy::AbsolutePosition,
+        };
+        assert!(digital(0x7AC0).validate().is_ok());
+        assert!(digital(0x4000).validate().is_err());
+        let calibrated = |calibration| ProtocolConfig::Visca {
+            host: "192.168.1.10".to_string(),
+            port: 52381,
+            limits: None,
+            tally_opcode: None,
+            gamma_opcode: None,
+            picture_profile_opcode: None,
+            zoom_max: None,
+            digital_zoom_max: None,
+            digital_zoom_opcode: None,
+            calibration: Some(calibration),
+            wait_for_completion: false,
+            bind_address: None,
+            absolute_strategy: AbsoluteStrategy::AbsolutePosition,
+        };
+        assert!(calibrated(ViscaCalibration::default()).validate().is_ok());
+        let inverted = ViscaCalibration {
+            pan_min: 880,
+            pan_max: -880,
+            ..ViscaCalibration::default()
+        };
+        assert!(calibrated(inverted).validate().is_err());
+        assert!(ProtocolConfig::Simulated.validate().is_ok());
+        let pelco = |address| ProtocolConfig::PelcoD {
+            host: "192.168.1.20".to_string(),
+            port: 4001,
+            address,
+        };
+        assert!(pelco(1).validate().is_ok());
+        assert!(pelco(0).validate().is_err());
+        let birddog = |zoom_max| ProtocolConfig::BirdDogRest {
+            host: "192.168.1.30".to_string(),
+            port: 8080,
+            username: None,
+            password: None,
+            position_scale: Some(PositionScale {
+                pan_max: 2448.0,
+                tilt_max: 1200.0,
+                zoom_max,
+            }),
+        };
+        assert!(birddog(16384.0).validate().is_ok());
+        assert!(birddog(0.0).validate().is_err());
+        assert!(birddog(f64::NAN).validate().is_err());
+        let panasonic = |move_speed, move_speed_max| ProtocolConfig::PanasonicAw {
+            host: "192.168.1.40".to_string(),
+            port: 80,
+            username: None,
+            password: None,
+            move_speed,
+            move_speed_max,
+            relative_mode: RelativeMode::NativeSpeed,
+        };
+        assert!(panasonic(Some(1.0), Some(0x29)).validate().is_ok());
+        assert!(panasonic(Some(1.5), None).validate().is_err());
+        assert!(panasonic(None, Some(0)).validate().is_err());
+        assert!(panasonic(None, Some(0x30)).validate().is_err());
+    }
+
+    #[test]
+    fn validate_host_rejects_colons_outside_ipv6() {
+        assert!(validate_host("camera:8080").is_err());
+        assert!(validate_host("host:port:extra").is_err());
+        assert!(validate_host("fe80::1:2:3").is_ok());
+    }
+
+    #[test]
+    fn host_with_port_brackets_ipv6() {
+        assert_eq!(host_with_port("::1", 80), "[::1]:80");
+        assert_eq!(host_with_port("fd00::10", 52381), "[fd00::10]:52381");
+        assert_eq!(host_with_port("192.168.1.5", 80), "192.168.1.5:80");
+        assert_eq!(host_with_port("cam.local", 80), "cam.local:80");
+    }
+
     #[test]
     fn validate_host_accepts_ip_address() {
         assert!(validate_host("192.168.1.100").is_ok());
@@ -234,6 +1129,17 @@ mod tests {
         assert!(validate_host("camera-1.local").is_ok());
     }
 
+    #[test]
+    fn validate_bind_address_checks_family_and_kind() {
+        assert!(validate_bind_address("192.168.1.10", "192.168.1.100").is_ok());
+        assert!(validate_bind_address(" 10.0.0.2 ", "camera-1.local").is_ok());
+        assert!(validate_bind_address("fe80::2", "fe80::1").is_ok());
+        assert!(validate_bind_address("eth0", "192.168.1.100").is_err());
+        assert!(validate_bind_address("224.0.0.1", "192.168.1.100").is_err());
+        assert!(validate_bind_address("::1", "192.168.1.100").is_err());
+        assert!(validate_bind_address("127.0.0.1", "::1").is_err());
+    }
+
     #[test]
     fn validate_host_accepts_ipv6_shorthand() {
         assert!(validate_host("::1").is_ok());
@@ -280,6 +1186,14 @@ mod tests {
             tilt: -0.3,
             zoom: 0.8,
             color: "#3b82f6".to_string(),
+            native_slot: Some(4),
+            focus: Some(0.4),
+            white_balance: Some(WhiteBalanceSetting::Manual {
+                red_gain: 120,
+                blue_gain: 90,
+            }),
+            hotkey: Some("Ctrl+1".to_string()),
+            thumbnail: Some("/9j/4AAQSkZJRg==".to_string()),
         };
         let json = serde_json::to_string(&preset).unwrap();
         let decoded: Preset = serde_json::from_str(&json).unwrap();
@@ -289,6 +1203,17 @@ mod tests {
         assert_eq!(decoded.tilt, -0.3);
         assert_eq!(decoded.zoom, 0.8);
         assert_eq!(decoded.color, "#3b82f6");
+        assert_eq!(decoded.native_slot, Some(4));
+        assert_eq!(decoded.focus, Some(0.4));
+        assert_eq!(
+            decoded.white_balance,
+            Some(WhiteBalanceSetting::Manual {
+                red_gain: 120,
+                blue_gain: 90
+            })
+        );
+        assert_eq!(decoded.hotkey.as_deref(), Some("Ctrl+1"));
+        assert_eq!(decoded.thumbnail.as_deref(), Some("/9j/4AAQSkZJRg=="));
     }
 
     // --- CameraEndpoint tests ---
@@ -302,13 +1227,56 @@ mod tests {
             config: ProtocolConfig::Visca {
                 host: "10.0.0.50".to_string(),
                 port: 1259,
+                limits: None,
+                tally_opcode: None,
+                gamma_opcode: None,
+                picture_profile_opcode: None,
+                zoom_max: None,
+                digital_zoom_max: None,
+                digital_zoom_opcode: None,
+                calibration: None,
+                wait_for_completion: false,
+                bind_address: None,
+                absolute_strategy: AbsoluteStrategy::AbsolutePosition,
             },
+            color: Some("#22c55e".to_string()),
+            icon: Some("podium".to_string()),
         };
         let json = serde_json::to_string(&endpoint).unwrap();
         let decoded: CameraEndpoint = serde_json::from_str(&json).unwrap();
         assert_eq!(decoded.id, "ep-1");
         assert_eq!(decoded.name, "Main Camera");
         assert_eq!(decoded.protocol, PtzProtocol::Visca);
+        assert_eq!(decoded.color.as_deref(), Some("#22c55e"));
+        assert_eq!(decoded.icon.as_deref(), Some("podium"));
+    }
+
+    #[test]
+    fn camera_endpoint_without_display_fields_deserializes() {
+        let json = r#"{"id":"ep-1","name":"Main Camera","protocol":"Simulated","config":{"type":"Simulated"}}"#;
+        let decoded: CameraEndpoint = serde_json::from_str(json).unwrap();
+        assert_eq!(decoded.color, None);
+        assert_eq!(decoded.icon, None);
+
+        let encoded = serde_json::to_value(&decoded).unwrap();
+        assert!(encoded["color"].is_null());
+        assert!(encoded["icon"].is_null());
+    }
+
+    #[test]
+    fn camera_endpoint_display_fields_are_normalized() {
+        let endpoint = CameraEndpoint {
+            id: "ep-1".to_string(),
+            name: "Main Camera".to_string(),
+            protocol: PtzProtocol::Simulated,
+            config: ProtocolConfig::Simulated,
+            color: Some(" Teal ".to_string()),
+            icon: Some("  ".to_string()),
+        }
+        .validated()
+        .unwrap();
+        assert_eq!(endpoint.color.as_deref(), Some("#008080"));
+        assert_eq!(endpoint.icon, None);
     }
 
     // --- PresetProfile tests ---
@@ -327,12 +1295,169 @@ mod tests {
                 tilt: 0.0,
                 zoom: 0.0,
                 color: "#fff".to_string(),
+                native_slot: None,
+                focus: None,
+                white_balance: None,
+                hotkey: None,
+                thumbnail: None,
             }],
+            tour: Some(vec![TourStep {
+                preset_id: "p1".to_string(),
+                dwell_ms: 5000,
+            }]),
+            transition: None,
+            home_preset_id: None,
+            lens_curve: LensCurve::default(),
+            mechanical_range: None,
+            limits_degrees: None,
         };
         let json = serde_json::to_string(&profile).unwrap();
         let decoded: PresetProfile = serde_json::from_str(&json).unwrap();
         assert_eq!(decoded.presets.len(), 1);
         assert_eq!(decoded.presets[0].name, "Wide");
         assert_eq!(decoded.endpoint_id.as_deref(), Some("ep-1"));
+        assert_eq!(decoded.tour.unwrap()[0].dwell_ms, 5000);
+    }
+
+    #[test]
+    fn preset_without_native_slot_deserializes() {
+        let json = r##"{"id":"a","name":"Old","pan":0.1,"tilt":0.2,"zoom":0.3,"color":"#fff"}"##;
+        let decoded: Preset = serde_json::from_str(json).unwrap();
+        assert_eq!(decoded.native_slot, None);
+        assert_eq!(decoded.focus, None);
+        assert_eq!(decoded.white_balance, None);
+        assert_eq!(decoded.hotkey, None);
+        assert_eq!(decoded.thumbnail, None);
+    }
+
+    #[test]
+    fn preset_input_color_is_normalized_or_defaulted() {
+        let json = r##"{"name":"Pulpit","pan":0.0,"tilt":0.0,"zoom":0.0,"color":"#F00"}"##;
+        let input: PresetInput = serde_json::from_str(json).unwrap();
+        assert_eq!(input.into_preset().unwrap().color, "#ff0000");
+
+        let json = r#"{"name":"Pulpit","pan":0.0,"tilt":0.0,"zoom":0.0}"#;
+        let input: PresetInput = serde_json::from_str(json).unwrap();
+        assert_eq!(input.into_preset().unwrap().color, Preset::default_color());
+
+        let json = r#"{"name":"Pulpit","pan":0.0,"tilt":0.0,"zoom":0.0,"color":"url(x)"}"#;
+        let input: PresetInput = serde_json::from_str(json).unwrap();
+        assert!(input.into_preset().is_err());
+    }
+
+    #[test]
+    fn preset_profile_without_tour_deserializes() {
+        let json =
+            r#"{"id":"p","name":"Old","camera_fov_degrees":60.0,"endpoint_id":null,"presets":[]}"#;
+        let decoded: PresetProfile = serde_json::from_str(json).unwrap();
+        assert!(decoded.tour.is_none());
+        assert!(decoded.transition.is_none());
+    }
+
+    #[test]
+    fn limits_clamp_and_block_outward_motion() {
+        let limits = PtzLimits {
+            pan_min: -0.5,
+            pan_max: 0.25,
+            tilt_min: -1.0,
+            tilt_max: 0.0,
+        };
+        assert!(limits.validate().is_ok());
+        assert!(!limits.is_full_range());
+        assert_eq!(limits.clamp(1.0, 0.5), (0.25, 0.0));
+        assert_eq!(limits.clamp(-0.75, -0.5), (-0.5, -0.5));
+        // At the pan maximum, moving right stops but moving left is allowed
+        assert_eq!(limits.limit_velocity(0.25, -0.5, 1.0, 0.5), (0.0, 0.5));
+        assert_eq!(limits.limit_velocity(0.25, 0.0, -1.0, 0.5), (-1.0, 0.0));
+    }
+
+    #[test]
+    fn limits_clamp_presets_outside_them() {
+        let limits = PtzLimits {
+            pan_min: -0.5,
+            pan_max: 0.5,
+            ..PtzLimits::default()
+        };
+        let inside = Preset {
+            id: "a".to_string(),
+            name: "Pulpit".to_string(),
+            pan: 0.25,
+            tilt: 0.1,
+            zoom: 0.4,
+            color: Preset::default_color(),
+            native_slot: Some(3),
+            focus: None,
+            white_balance: None,
+            hotkey: None,
+            thumbnail: None,
+        };
+        let kept = limits.clamp_preset(&inside);
+        assert_eq!((kept.pan, kept.native_slot), (0.25, Some(3)));
+
+        let outside = Preset {
+            pan: 0.9,
+            ..inside.clone()
+        };
+        let clamped = limits.clamp_preset(&outside);
+        assert_eq!((clamped.pan, clamped.tilt, clamped.zoom), (0.5, 0.1, 0.4));
+        assert_eq!(clamped.native_slot, None);
+    }
+
+    #[test]
+    fn limits_validate_ranges() {
+        assert!(PtzLimits::default().is_full_range());
+        let inverted = PtzLimits {
+            pan_min: 0.5,
+            pan_max: -0.5,
+            ..PtzLimits::default()
+        };
+        assert!(inverted.validate().is_err());
+        let out_of_range = PtzLimits {
+            tilt_max: 1.5,
+            ..PtzLimits::default()
+        };
+        assert!(out_of_range.validate().is_err());
+    }
+
+    #[test]
+    fn degree_limits_convert_at_the_profile_fov() {
+        // Keep clear of the PA stack past 40 degrees right
+        let limits = DegreeLimits {
+            pan_min: -90.0,
+            pan_max: 40.0,
+            tilt_min: -15.0,
+            tilt_max: 15.0,
+        };
+        assert!(limits.validate().is_ok());
+        let wide = limits.to_normalized(DegreeScale::new(60.0, None));
+        assert_eq!(
+            wide,
+            PtzLimits {
+                pan_min: -1.0,
+                pan_max: 1.0,
+                tilt_min: -0.5,
+                tilt_max: 0.5,
+            }
+        );
+        let range = MechanicalRange {
+            pan_degrees: 160.0,
+            tilt_degrees: 30.0,
+        };
+        let mechanical = limits.to_normalized(DegreeScale::new(60.0, Some(range)));
+        assert_eq!(
+            mechanical,
+            PtzLimits {
+                pan_min: -0.5625,
+                pan_max: 0.25,
+                tilt_min: -0.5,
+                tilt_max: 0.5,
+            }
+        );
+        let inverted = DegreeLimits {
+            pan_min: 40.0,
+            pan_max: -90.0,
+            ..limits
+        };
+        assert!(inverted.validate().is_err());
     }
 }
@@ -1,26 +1,165 @@
 use std::collections::HashMap;
-use std::sync::Mutex;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Arc, Mutex, MutexGuard};
+use std::time::{Duration, Instant};
 
 use async_trait::async_trait;
 
 use crate::ptz::controller::{PtzController, PtzError};
-use crate::ptz::types::PtzPosition;
+use crate::ptz::types::{Capabilities, PtzPosition, WhiteBalanceSetting};
+
+/// Normalized units per second travelled at full continuous-move velocity.
+const CONTINUOUS_SPEED: f64 = 0.5;
+
+/// How often a command that waits for a move checks whether it arrived.
+const SETTLE_POLL_INTERVAL: Duration = Duration::from_millis(10);
+
+/// Time source for the simulator, injectable so tests can advance time.
+pub trait Clock: Send + Sync {
+    fn now(&self) -> Instant;
+}
+
+/// Wall-clock time source.
+pub struct SystemClock;
+
+impl Clock for SystemClock {
+    fn now(&self) -> Instant {
+        Instant::now()
+    }
+}
+
+/// Manually advanced time source for tests.
+#[derive(Clone)]
+pub struct ManualClock {
+    now: Arc<Mutex<Instant>>,
+}
+
+impl Default for ManualClock {
+    fn default() -> Self {
+        Self {
+            now: Arc::new(Mutex::new(Instant::now())),
+        }
+    }
+}
+
+impl ManualClock {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn advance(&self, by: Duration) {
+        let mut now = self.now.lock().unwrap_or_else(|e| e.into_inner());
+        *now += by;
+    }
+}
+
+impl Clock for ManualClock {
+    fn now(&self) -> Instant {
+        *self.now.lock().unwrap_or_else(|e| e.into_inner())
+    }
+}
+
+/// Fault-injection mode for exercising error handling without real hardware.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum FailureMode {
+    /// Commands behave normally.
+    #[default]
+    None,
+    /// Every command times out.
+    AlwaysTimeout,
+    /// Every command is rejected by the "camera".
+    RejectCommands,
+    /// Every nth command times out, as if the packet was dropped.
+    DropEveryNth(u64),
+}
+
+/// Position and motion of the simulated head.
+struct SimState {
+    position: PtzPosition,
+    /// Continuous pan/tilt velocity in -1.0..1.0.
+    velocity: (f64, f64),
+    /// Destination of an in-progress absolute move.
+    target: Option<PtzPosition>,
+    /// Slew rate in normalized units per second; `None` moves instantly.
+    slew_rate: Option<f64>,
+    /// When `position` was last brought up to date.
+    updated_at: Instant,
+    /// Focus position, 0.0 = nearest to 1.0 = infinity.
+    focus: f64,
+    /// Zoom the lens was at when focus was last set. Focus only holds at
+    /// that focal length.
+    focus_zoom: f64,
+    white_balance: WhiteBalanceSetting,
+}
+
+impl SimState {
+    /// Integrate motion up to `now`.
+    fn advance(&mut self, now: Instant) {
+        let dt = now.saturating_duration_since(self.updated_at).as_secs_f64();
+        self.updated_at = now;
+        let (pan_v, tilt_v) = self.velocity;
+        if pan_v != 0.0 || tilt_v != 0.0 {
+            self.position.pan = clamp_pan_tilt(self.position.pan + pan_v * CONTINUOUS_SPEED * dt);
+            self.position.tilt =
+                clamp_pan_tilt(self.position.tilt + tilt_v * CONTINUOUS_SPEED * dt);
+        }
+        if let (Some(target), Some(rate)) = (&self.target, self.slew_rate) {
+            // Coordinated move: all axes arrive together, limited by the longest axis.
+            let remaining = (target.pan - self.position.pan)
+                .abs()
+                .max((target.tilt - self.position.tilt).abs())
+                .max((target.zoom - self.position.zoom).abs());
+            let fraction = if remaining > 0.0 {
+                (rate * dt / remaining).min(1.0)
+            } else {
+                1.0
+            };
+            self.position.pan += (target.pan - self.position.pan) * fraction;
+            self.position.tilt += (target.tilt - self.position.tilt) * fraction;
+            self.position.zoom += (target.zoom - self.position.zoom) * fraction;
+            if fraction >= 1.0 {
+                self.position = target.clone();
+                self.target = None;
+            }
+        }
+    }
+
+    /// Start moving to `target`, or jump there if no slew rate is set.
+    fn move_to(&mut self, target: PtzPosition) {
+        self.velocity = (0.0, 0.0);
+        if self.slew_rate.is_some() {
+            self.target = Some(target);
+        } else {
+            self.position = target;
+            self.target = None;
+        }
+    }
+
+    /// Where the head is heading: the in-progress target, or the current position.
+    fn destination(&self) -> PtzPosition {
+        self.target.clone().unwrap_or_else(|| self.position.clone())
+    }
+}
 
 /// Simulated PTZ camera for development and demo use.
 ///
 /// Tracks position and presets in memory with no hardware
-/// or network dependencies.
+/// or network dependencies. Continuous moves are integrated over
+/// time, so `get_position` reflects an in-progress jog. With a slew
+/// rate set, absolute moves travel at that rate instead of jumping.
 pub struct SimulatedController {
-    position: Mutex<PtzPosition>,
+    state: Mutex<SimState>,
     presets: Mutex<HashMap<u8, PtzPosition>>,
+    clock: Box<dyn Clock>,
+    failure_mode: Mutex<FailureMode>,
+    command_count: AtomicU64,
+    /// How long a connection test takes to answer.
+    response_delay: Duration,
 }
 
 impl Default for SimulatedController {
     fn default() -> Self {
-        Self {
-            position: Mutex::new(PtzPosition::default()),
-            presets: Mutex::new(HashMap::new()),
-        }
+        Self::with_clock(Box::new(SystemClock))
     }
 }
 
@@ -28,6 +167,94 @@ impl SimulatedController {
     pub fn new() -> Self {
         Self::default()
     }
+
+    /// Create a simulator driven by the given time source.
+    pub fn with_clock(clock: Box<dyn Clock>) -> Self {
+        let now = clock.now();
+        Self {
+            state: Mutex::new(SimState {
+                position: PtzPosition::default(),
+                velocity: (0.0, 0.0),
+                target: None,
+                slew_rate: None,
+                updated_at: now,
+                focus: 1.0,
+                focus_zoom: 0.0,
+                white_balance: WhiteBalanceSetting::Auto,
+            }),
+            presets: Mutex::new(HashMap::new()),
+            clock,
+            failure_mode: Mutex::new(FailureMode::None),
+            command_count: AtomicU64::new(0),
+            response_delay: Duration::ZERO,
+        }
+    }
+
+    /// Make subsequent commands fail according to `mode`.
+    pub fn set_failure_mode(&self, mode: FailureMode) {
+        *self.failure_mode.lock().unwrap_or_else(|e| e.into_inner()) = mode;
+        self.command_count.store(0, Ordering::SeqCst);
+    }
+
+    /// Count a command and return the error the failure mode dictates, if any.
+    fn check_failure(&self) -> Result<(), PtzError> {
+        let mode = *self.failure_mode.lock().unwrap_or_else(|e| e.into_inner());
+        let count = self.command_count.fetch_add(1, Ordering::SeqCst) + 1;
+        match mode {
+            FailureMode::None => Ok(()),
+            FailureMode::AlwaysTimeout => {
+                Err(PtzError::Timeout("Simulated camera timeout".to_string()))
+            }
+            FailureMode::RejectCommands => Err(PtzError::CommandFailed(
+                "Simulated camera rejected the command".to_string(),
+            )),
+            FailureMode::DropEveryNth(n) if n > 0 && count.is_multiple_of(n) => Err(
+                PtzError::Timeout(format!("Simulated dropped command #{count}")),
+            ),
+            FailureMode::DropEveryNth(_) => Ok(()),
+        }
+    }
+
+    /// Move at `units_per_sec` (normalized units) instead of jumping to absolute targets.
+    pub fn with_slew_rate(self, units_per_sec: f64) -> Self {
+        if let Ok(mut state) = self.state.lock() {
+            state.slew_rate = Some(units_per_sec).filter(|r| r.is_finite() && *r > 0.0);
+        }
+        self
+    }
+
+    /// Answer connection tests and position queries after `delay`, like a
+    /// camera across a network.
+    pub fn with_response_delay(mut self, delay: Duration) -> Self {
+        self.response_delay = delay;
+        self
+    }
+
+    /// Zoom the simulated lens was at when focus was last set.
+    pub fn focus_zoom(&self) -> f64 {
+        self.state
+            .lock()
+            .map(|state| state.focus_zoom)
+            .unwrap_or(0.0)
+    }
+
+    /// The white balance most recently set on the simulated camera.
+    pub fn white_balance(&self) -> WhiteBalanceSetting {
+        self.state
+            .lock()
+            .map(|state| state.white_balance)
+            .unwrap_or(WhiteBalanceSetting::Auto)
+    }
+
+    /// Lock the state with motion integrated up to the current time.
+    fn state(&self) -> Result<MutexGuard<'_, SimState>, PtzError> {
+        let mut state = self
+            .state
+            .lock()
+            .map_err(|e| PtzError::CommandFailed(format!("Lock poisoned: {e}")))?;
+        state.advance(self.clock.now());
+        Ok(state)
+    }
 }
 
 fn clamp_pan_tilt(value: f64) -> f64 {
@@ -41,42 +268,47 @@ fn clamp_zoom(value: f64) -> f64 {
 #[async_trait]
 impl PtzController for SimulatedController {
     async fn move_absolute(&self, pan: f64, tilt: f64, zoom: f64) -> Result<(), PtzError> {
-        let mut pos = self
-            .position
-            .lock()
-            .map_err(|e| PtzError::CommandFailed(format!("Lock poisoned: {e}")))?;
-        pos.pan = clamp_pan_tilt(pan);
-        pos.tilt = clamp_pan_tilt(tilt);
-        pos.zoom = clamp_zoom(zoom);
+        self.check_failure()?;
+        self.state()?.move_to(PtzPosition {
+            pan: clamp_pan_tilt(pan),
+            tilt: clamp_pan_tilt(tilt),
+            zoom: clamp_zoom(zoom),
+        });
         Ok(())
     }
 
     async fn move_relative(&self, pan_delta: f64, tilt_delta: f64) -> Result<(), PtzError> {
-        let mut pos = self
-            .position
-            .lock()
-            .map_err(|e| PtzError::CommandFailed(format!("Lock poisoned: {e}")))?;
-        pos.pan = clamp_pan_tilt(pos.pan + pan_delta);
-        pos.tilt = clamp_pan_tilt(pos.tilt + tilt_delta);
+        self.check_failure()?;
+        let mut state = self.state()?;
+        let mut target = state.destination();
+        target.pan = clamp_pan_tilt(target.pan + pan_delta);
+        target.tilt = clamp_pan_tilt(target.tilt + tilt_delta);
+        state.move_to(target);
         Ok(())
     }
 
     async fn zoom_to(&self, zoom: f64) -> Result<(), PtzError> {
-        let mut pos = self
-            .position
-            .lock()
-            .map_err(|e| PtzError::CommandFailed(format!("Lock poisoned: {e}")))?;
-        pos.zoom = clamp_zoom(zoom);
+        self.check_failure()?;
+        let mut state = self.state()?;
+        let mut target = state.destination();
+        target.zoom = clamp_zoom(zoom);
+        state.move_to(target);
+        Ok(())
+    }
+
+    /// Adds to the zoom the camera is heading for, so quick deltas accumulate.
+    async fn zoom_by(&self, delta: f64) -> Result<(), PtzError> {
+        self.check_failure()?;
+        let mut state = self.state()?;
+        let mut target = state.destination();
+        target.zoom = clamp_zoom(target.zoom + delta);
+        state.move_to(target);
         Ok(())
     }
 
     async fn store_preset(&self, preset_index: u8) -> Result<(), PtzError> {
-        let pos = self
-            .position
-            .lock()
-            .map_err(|e| PtzError::CommandFailed(format!("Lock poisoned: {e}")))?;
-        let snapshot = pos.clone();
-        drop(pos);
+        self.check_failure()?;
+        let snapshot = self.state()?.position.clone();
 
         let mut presets = self
             .presets
@@ -87,6 +319,7 @@ impl PtzController for SimulatedController {
     }
 
     async fn recall_preset(&self, preset_index: u8) -> Result<(), PtzError> {
+        self.check_failure()?;
         let presets = self
             .presets
             .lock()
@@ -96,40 +329,88 @@ impl PtzController for SimulatedController {
         })?;
         drop(presets);
 
-        let mut pos = self
-            .position
-            .lock()
-            .map_err(|e| PtzError::CommandFailed(format!("Lock poisoned: {e}")))?;
-        *pos = stored;
+        self.state()?.move_to(stored);
         Ok(())
     }
 
     async fn get_position(&self) -> Result<PtzPosition, PtzError> {
-        let pos = self
-            .position
-            .lock()
-            .map_err(|e| PtzError::CommandFailed(format!("Lock poisoned: {e}")))?;
-        Ok(pos.clone())
+        if !self.response_delay.is_zero() {
+            tokio::time::sleep(self.response_delay).await;
+        }
+        self.check_failure()?;
+        Ok(self.state()?.position.clone())
     }
 
     async fn test_connection(&self) -> Result<(), PtzError> {
+        if !self.response_delay.is_zero() {
+            tokio::time::sleep(self.response_delay).await;
+        }
+        self.check_failure()?;
         Ok(())
     }
 
     async fn continuous_move(&self, pan_speed: f64, tilt_speed: f64) -> Result<(), PtzError> {
-        // In simulation, apply a small step proportional to speed
-        let mut pos = self
-            .position
-            .lock()
-            .map_err(|e| PtzError::CommandFailed(format!("Lock poisoned: {e}")))?;
-        pos.pan = clamp_pan_tilt(pos.pan + pan_speed * 0.05);
-        pos.tilt = clamp_pan_tilt(pos.tilt + tilt_speed * 0.05);
+        self.check_failure()?;
+        let mut state = self.state()?;
+        state.target = None;
+        state.velocity = (clamp_pan_tilt(pan_speed), clamp_pan_tilt(tilt_speed));
         Ok(())
     }
 
     async fn stop(&self) -> Result<(), PtzError> {
+        self.check_failure()?;
+        let mut state = self.state()?;
+        state.velocity = (0.0, 0.0);
+        state.target = None;
+        Ok(())
+    }
+
+    async fn focus_to(&self, position: f64) -> Result<(), PtzError> {
+        self.check_failure()?;
+        let mut state = self.state()?;
+        state.focus = position.clamp(0.0, 1.0);
+        state.focus_zoom = state.position.zoom;
+        Ok(())
+    }
+
+    /// Waits for a slewing zoom to arrive before focusing.
+    async fn focus_after_zoom(&self, _zoom: f64, focus: f64) -> Result<(), PtzError> {
+        while self.state()?.target.is_some() {
+            tokio::time::sleep(SETTLE_POLL_INTERVAL).await;
+        }
+        self.focus_to(focus).await
+    }
+
+    async fn get_focus(&self) -> Result<f64, PtzError> {
+        self.check_failure()?;
+        Ok(self.state()?.focus)
+    }
+
+    async fn set_white_balance(&self, setting: WhiteBalanceSetting) -> Result<(), PtzError> {
+        self.check_failure()?;
+        self.state()?.white_balance = setting;
         Ok(())
     }
+
+    fn supports_timed_moves(&self) -> bool {
+        self.state
+            .lock()
+            .map(|state| state.slew_rate.is_some())
+            .unwrap_or(false)
+    }
+
+    fn capabilities(&self) -> Capabilities {
+        Capabilities {
+            supports_absolute: true,
+            supports_position: true,
+            supports_continuous: true,
+            supports_focus_position: true,
+            supports_white_balance: true,
+            supports_native_presets: true,
+            max_preset_slots: 256,
+            ..Capabilities::default()
+        }
+    }
 }
 
 #[cfg(test)]
@@ -202,6 +483,22 @@ mod tests {
         assert_eq!(pos.zoom, 0.75);
     }
 
+    #[tokio::test]
+    async fn focus_and_white_balance_are_tracked() {
+        let ctrl = SimulatedController::new();
+        assert_eq!(ctrl.get_focus().await.unwrap(), 1.0);
+        ctrl.focus_to(1.5).await.unwrap();
+        assert_eq!(ctrl.get_focus().await.unwrap(), 1.0);
+        ctrl.focus_to(0.25).await.unwrap();
+        assert_eq!(ctrl.get_focus().await.unwrap(), 0.25);
+
+        assert_eq!(ctrl.white_balance(), WhiteBalanceSetting::Auto);
+        ctrl.set_white_balance(WhiteBalanceSetting::Indoor)
+            .await
+            .unwrap();
+        assert_eq!(ctrl.white_balance(), WhiteBalanceSetting::Indoor);
+    }
+
     #[tokio::test]
     async fn zoom_to_clamps() {
         let ctrl = SimulatedController::new();
@@ -258,9 +555,229 @@ mod tests {
         assert_eq!(pos.zoom, 0.6);
     }
 
+    fn manual_controller() -> (SimulatedController, ManualClock) {
+        let clock = ManualClock::new();
+        let ctrl = SimulatedController::with_clock(Box::new(clock.clone()));
+        (ctrl, clock)
+    }
+
+    #[tokio::test]
+    async fn continuous_move_integrates_velocity_over_time() {
+        let (ctrl, clock) = manual_controller();
+        ctrl.continuous_move(1.0, -0.5).await.unwrap();
+        clock.advance(Duration::from_secs(1));
+
+        let pos = ctrl.get_position().await.unwrap();
+        assert!((pos.pan - CONTINUOUS_SPEED).abs() < 1e-9);
+        assert!((pos.tilt + CONTINUOUS_SPEED * 0.5).abs() < 1e-9);
+    }
+
+    #[tokio::test]
+    async fn continuous_move_clamps_at_bounds() {
+        let (ctrl, clock) = manual_controller();
+        ctrl.continuous_move(-1.0, 1.0).await.unwrap();
+        clock.advance(Duration::from_secs(60));
+
+        let pos = ctrl.get_position().await.unwrap();
+        assert_eq!(pos.pan, -1.0);
+        assert_eq!(pos.tilt, 1.0);
+    }
+
+    #[tokio::test]
+    async fn stop_halts_continuous_move() {
+        let (ctrl, clock) = manual_controller();
+        ctrl.continuous_move(1.0, 0.0).await.unwrap();
+        clock.advance(Duration::from_millis(500));
+        ctrl.stop().await.unwrap();
+        let stopped_at = ctrl.get_position().await.unwrap().pan;
+
+        clock.advance(Duration::from_secs(1));
+        assert_eq!(ctrl.get_position().await.unwrap().pan, stopped_at);
+        assert!(stopped_at > 0.0);
+    }
+
+    #[tokio::test]
+    async fn move_absolute_cancels_continuous_move() {
+        let (ctrl, clock) = manual_controller();
+        ctrl.continuous_move(1.0, 1.0).await.unwrap();
+        ctrl.move_absolute(-0.5, 0.0, 0.0).await.unwrap();
+        clock.advance(Duration::from_secs(1));
+
+        let pos = ctrl.get_position().await.unwrap();
+        assert_eq!(pos.pan, -0.5);
+        assert_eq!(pos.tilt, 0.0);
+    }
+
+    fn slewing_controller(rate: f64) -> (SimulatedController, ManualClock) {
+        let clock = ManualClock::new();
+        let ctrl = SimulatedController::with_clock(Box::new(clock.clone())).with_slew_rate(rate);
+        (ctrl, clock)
+    }
+
+    #[tokio::test]
+    async fn slewing_move_absolute_is_partway_then_arrives() {
+        let (ctrl, clock) = slewing_controller(0.5);
+        ctrl.move_absolute(1.0, -0.5, 0.5).await.unwrap();
+
+        // Longest axis is pan (1.0 units), so the move takes 2s.
+        clock.advance(Duration::from_secs(1));
+        let pos = ctrl.get_position().await.unwrap();
+        assert!((pos.pan - 0.5).abs() < 1e-9);
+        assert!((pos.tilt + 0.25).abs() < 1e-9);
+        assert!((pos.zoom - 0.25).abs() < 1e-9);
+
+        clock.advance(Duration::from_secs(1));
+        let pos = ctrl.get_position().await.unwrap();
+        assert_eq!(pos.pan, 1.0);
+        assert_eq!(pos.tilt, -0.5);
+        assert_eq!(pos.zoom, 0.5);
+    }
+
+    #[tokio::test]
+    async fn focus_after_zoom_focuses_at_the_new_zoom() {
+        // Sent back to back, focus lands while zoom is still travelling
+        let (ctrl, _clock) = slewing_controller(0.5);
+        ctrl.zoom_to(0.8).await.unwrap();
+        ctrl.focus_to(0.3).await.unwrap();
+        assert_eq!(ctrl.focus_zoom(), 0.0);
+
+        let (ctrl, clock) = slewing_controller(0.5);
+        let arrive = async {
+            tokio::time::sleep(Duration::from_millis(30)).await;
+            clock.advance(Duration::from_secs(2));
+        };
+        ctrl.zoom_to(0.8).await.unwrap();
+        let (recalled, ()) = tokio::join!(ctrl.focus_after_zoom(0.8, 0.3), arrive);
+        recalled.unwrap();
+        assert_eq!(ctrl.get_position().await.unwrap().zoom, 0.8);
+        assert_eq!(ctrl.get_focus().await.unwrap(), 0.3);
+        assert_eq!(ctrl.focus_zoom(), 0.8);
+    }
+
+    #[tokio::test]
+    async fn slewing_does_not_overshoot() {
+        let (ctrl, clock) = slewing_controller(0.5);
+        ctrl.move_absolute(0.2, 0.0, 0.0).await.unwrap();
+        clock.advance(Duration::from_secs(10));
+        assert_eq!(ctrl.get_position().await.unwrap().pan, 0.2);
+    }
+
+    #[tokio::test]
+    async fn slewing_recall_preset_interpolates() {
+        let (ctrl, clock) = slewing_controller(1.0);
+        ctrl.move_absolute(-1.0, 0.0, 0.0).await.unwrap();
+        clock.advance(Duration::from_secs(1));
+        ctrl.store_preset(1).await.unwrap();
+        ctrl.move_absolute(0.0, 0.0, 0.0).await.unwrap();
+        clock.advance(Duration::from_secs(1));
+
+        ctrl.recall_preset(1).await.unwrap();
+        clock.advance(Duration::from_millis(500));
+        let pos = ctrl.get_position().await.unwrap();
+        assert!((pos.pan + 0.5).abs() < 1e-9);
+
+        clock.advance(Duration::from_millis(500));
+        assert_eq!(ctrl.get_position().await.unwrap().pan, -1.0);
+    }
+
+    #[test]
+    fn timed_moves_follow_slew_rate() {
+        assert!(!SimulatedController::new().supports_timed_moves());
+        let (ctrl, _clock) = slewing_controller(0.5);
+        assert!(ctrl.supports_timed_moves());
+    }
+
+    #[tokio::test]
+    async fn slewing_test_connection_is_instant() {
+        let (ctrl, _clock) = slewing_controller(0.1);
+        ctrl.move_absolute(1.0, 1.0, 1.0).await.unwrap();
+        assert!(ctrl.test_connection().await.is_ok());
+    }
+
+    #[tokio::test]
+    async fn failure_mode_always_timeout() {
+        let ctrl = SimulatedController::new();
+        ctrl.set_failure_mode(FailureMode::AlwaysTimeout);
+        assert!(matches!(
+            ctrl.move_absolute(0.1, 0.1, 0.1).await,
+            Err(PtzError::Timeout(_))
+        ));
+        assert!(matches!(
+            ctrl.get_position().await,
+            Err(PtzError::Timeout(_))
+        ));
+        assert!(ctrl.test_connection().await.is_err());
+    }
+
+    #[tokio::test]
+    async fn failure_mode_reject_commands() {
+        let ctrl = SimulatedController::new();
+        ctrl.set_failure_mode(FailureMode::RejectCommands);
+        assert!(matches!(
+            ctrl.move_absolute(0.1, 0.1, 0.1).await,
+            Err(PtzError::CommandFailed(_))
+        ));
+        assert!(matches!(
+            ctrl.get_position().await,
+            Err(PtzError::CommandFailed(_))
+        ));
+    }
+
+    #[tokio::test]
+    async fn failure_mode_drop_every_nth() {
+        let ctrl = SimulatedController::new();
+        ctrl.set_failure_mode(FailureMode::DropEveryNth(3));
+        assert!(ctrl.move_absolute(0.1, 0.1, 0.1).await.is_ok());
+        assert!(ctrl.get_position().await.is_ok());
+        assert!(matches!(
+            ctrl.move_absolute(0.2, 0.2, 0.2).await,
+            Err(PtzError::Timeout(_))
+        ));
+        assert!(ctrl.move_absolute(0.3, 0.3, 0.3).await.is_ok());
+        assert!(ctrl.move_absolute(0.4, 0.4, 0.4).await.is_ok());
+        assert!(matches!(
+            ctrl.get_position().await,
+            Err(PtzError::Timeout(_))
+        ));
+        // The dropped command never reached the head
+        let pos = ctrl.get_position().await.unwrap();
+        assert_eq!(pos.pan, 0.4);
+    }
+
+    #[tokio::test]
+    async fn failure_mode_none_restores_normal_behavior() {
+        let ctrl = SimulatedController::new();
+        ctrl.set_failure_mode(FailureMode::AlwaysTimeout);
+        ctrl.set_failure_mode(FailureMode::None);
+        assert!(ctrl.move_absolute(0.1, 0.1, 0.1).await.is_ok());
+        assert!(ctrl.get_position().await.is_ok());
+    }
+
     #[tokio::test]
     async fn test_connection_always_succeeds() {
         let ctrl = SimulatedController::new();
         assert!(ctrl.test_connection().await.is_ok());
     }
+
+    #[test]
+    fn capabilities_match_simulated_features() {
+        let caps = SimulatedController::new().capabilities();
+        assert!(caps.supports_absolute && caps.supports_continuous);
+        assert!(caps.supports_focus_position && caps.supports_white_balance);
+        assert!(caps.supports_native_presets);
+        assert!(!caps.supports_menu && !caps.supports_power);
+    }
+
+    #[tokio::test]
+    async fn zoom_deltas_accumulate_and_clamp() {
+        let ctrl = SimulatedController::new();
+        ctrl.zoom_by(0.25).await.unwrap();
+        ctrl.zoom_by(0.25).await.unwrap();
+        assert!((ctrl.get_position().await.unwrap().zoom - 0.5).abs() < f64::EPSILON);
+
+        ctrl.zoom_by(2.0).await.unwrap();
+        assert_eq!(ctrl.get_position().await.unwrap().zoom, 1.0);
+        ctrl.zoom_by(-3.0).await.unwrap();
+        assert_eq!(ctrl.get_position().await.unwrap().zoom, 0.0);
+    }
 }
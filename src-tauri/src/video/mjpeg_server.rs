@@ -1,12 +1,231 @@
-use axum::{body::Body, http::header, response::Response, routing::get, Router};
-use std::sync::Arc;
-use tokio::sync::broadcast;
+use crate::video::thumbnail::downscale_rgb;
+use axum::{
+    body::Body,
+    extract::Query,
+    http::{header, StatusCode},
+    response::Response,
+    routing::get,
+    Router,
+};
+use serde::{Deserialize, Serialize};
+use std::net::{IpAddr, Ipv4Addr, SocketAddr};
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+use tokio::sync::{broadcast, Semaphore};
 
 const BOUNDARY: &str = "mjpeg_boundary";
 
-/// Shared state for the MJPEG server.
+/// Frame rate pushed to clients unless configured otherwise.
+pub const DEFAULT_TARGET_FPS: u32 = 30;
+
+/// JPEG quality used by frame encoders unless configured otherwise.
+pub const DEFAULT_JPEG_QUALITY: u8 = 80;
+
+/// JPEG quality of the low-bandwidth tier.
+const LOW_TIER_JPEG_QUALITY: u8 = 50;
+/// Largest frame size served on the low-bandwidth tier.
+const LOW_TIER_MAX_WIDTH: usize = 640;
+const LOW_TIER_MAX_HEIGHT: usize = 360;
+
+/// Frames encoded at once across all clients; further encodes wait their
+/// turn, and the frames they miss are skipped.
+pub const MAX_CONCURRENT_ENCODES: usize = 4;
+
+/// A frame as tightly packed 8-bit RGB.
+#[derive(Debug, Clone, PartialEq)]
+pub struct RgbFrame {
+    pub width: usize,
+    pub height: usize,
+    pub rgb: Vec<u8>,
+}
+
+/// Encoding tier a stream client asks for.
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub enum StreamQuality {
+    /// Downscaled and more compressed, for remote or mobile viewers.
+    Low,
+    /// Full resolution at the server's configured JPEG quality.
+    #[default]
+    High,
+}
+
+impl StreamQuality {
+    pub fn parse(value: &str) -> Result<Self, String> {
+        match value.trim().to_ascii_lowercase().as_str() {
+            "low" => Ok(StreamQuality::Low),
+            "high" => Ok(StreamQuality::High),
+            other => Err(format!("Unknown stream quality '{}'", other)),
+        }
+    }
+}
+
+/// Query parameters accepted by `/stream`.
+#[derive(Debug, Default, Deserialize)]
+pub struct StreamQuery {
+    pub quality: Option<String>,
+    pub fps: Option<String>,
+}
+
+/// How frames are delivered to one stream client.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct StreamOptions {
+    pub quality: StreamQuality,
+    pub fps: u32,
+}
+
+impl StreamOptions {
+    /// Resolve a client's query. Missing values use the high tier at the
+    /// server's rate, which is also the most a client can ask for.
+    pub fn from_query(query: &StreamQuery, max_fps: u32) -> Result<Self, String> {
+        let quality = match query.quality.as_deref().map(str::trim) {
+            None | Some("") => StreamQuality::default(),
+            Some(quality) => StreamQuality::parse(quality)?,
+        };
+        let fps = match query.fps.as_deref().map(str::trim) {
+            None | Some("") => max_fps,
+            Some(fps) => fps
+                .parse::<u32>()
+                .ok()
+                .filter(|fps| *fps > 0)
+                .ok_or_else(|| format!("Invalid fps '{}'", fps))?
+                .min(max_fps),
+        };
+        Ok(Self { quality, fps })
+    }
+}
+
+/// JPEG-encode packed RGB.
+pub fn encode_rgb(rgb: &[u8], width: usize, height: usize, quality: u8) -> Result<Vec<u8>, String> {
+    let width = u16::try_from(width).map_err(|_| "Frame too wide for JPEG".to_string())?;
+    let height = u16::try_from(height).map_err(|_| "Frame too tall for JPEG".to_string())?;
+    let mut jpeg = Vec::new();
+    jpeg_encoder::Encoder::new(&mut jpeg, quality)
+        .encode(rgb, width, height, jpeg_encoder::ColorType::Rgb)
+        .map_err(|e| e.to_string())?;
+    Ok(jpeg)
+}
+
+/// Drops frames arriving faster than the configured rate.
+pub struct FrameThrottle {
+    interval: Duration,
+    last: Option<Instant>,
+}
+
+impl FrameThrottle {
+    pub fn new(max_fps: u32) -> Self {
+        Self {
+            interval: Duration::from_secs(1) / max_fps.max(1),
+            last: None,
+        }
+    }
+
+    /// Whether a frame at `now` would be emitted, without recording it.
+    pub fn is_due(&self, now: Instant) -> bool {
+        match self.last {
+            Some(last) => now.saturating_duration_since(last) >= self.interval,
+            None => true,
+        }
+    }
+
+    /// Whether a frame at `now` should be emitted; records it if so.
+    pub fn ready(&mut self, now: Instant) -> bool {
+        let due = self.is_due(now);
+        if due {
+            self.last = Some(now);
+        }
+        due
+    }
+}
+
+/// Where the MJPEG server listens.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ServerOptions {
+    pub bind_address: IpAddr,
+    /// Fixed port; `None` picks a random free port.
+    pub port: Option<u16>,
+}
+
+impl Default for ServerOptions {
+    fn default() -> Self {
+        Self {
+            bind_address: IpAddr::V4(Ipv4Addr::LOCALHOST),
+            port: None,
+        }
+    }
+}
+
+impl ServerOptions {
+    /// Build options from user input, rejecting unparseable addresses.
+    pub fn parse(bind_address: Option<&str>, port: Option<u16>) -> Result<Self, String> {
+        let bind_address = match bind_address.map(str::trim) {
+            None | Some("") => Self::default().bind_address,
+            Some(addr) => addr
+                .parse()
+                .map_err(|_| format!("Invalid bind address: '{}'", addr))?,
+        };
+        Ok(Self {
+            bind_address,
+            port: port.filter(|p| *p != 0),
+        })
+    }
+}
+
+/// Bind the listener, retrying briefly on a fixed port that a server we
+/// just shut down may still be releasing.
+async fn bind_listener(options: &ServerOptions) -> Result<tokio::net::TcpListener, String> {
+    const BIND_ATTEMPTS: u32 = 5;
+    let addr = SocketAddr::new(options.bind_address, options.port.unwrap_or(0));
+    let mut attempt = 1;
+    loop {
+        match tokio::net::TcpListener::bind(addr).await {
+            Ok(listener) => return Ok(listener),
+            Err(e) if e.kind() == std::io::ErrorKind::AddrInUse => {
+                if options.port.is_none() || attempt >= BIND_ATTEMPTS {
+                    return Err(format!("Port {} is already in use", addr.port()));
+                }
+                attempt += 1;
+                tokio::time::sleep(Duration::from_millis(100)).await;
+            }
+            Err(e) => return Err(format!("Failed to bind MJPEG server to {}: {}", addr, e)),
+        }
+    }
+}
+
+/// Shared state for the MJPEG server. Frames are held raw and encoded for
+/// each client at the tier it asked for.
 pub struct MjpegState {
-    pub frame_sender: broadcast::Sender<Vec<u8>>,
+    pub frame_sender: broadcast::Sender<Arc<RgbFrame>>,
+    /// Most recent frame, served by `/snapshot`, and when it was pushed.
+    latest_frame: Mutex<Option<(Arc<RgbFrame>, Instant)>>,
+    throttle: Mutex<FrameThrottle>,
+    target_fps: u32,
+    jpeg_quality: u8,
+    encode_permits: Arc<Semaphore>,
+    clients: Arc<AtomicUsize>,
+    started_at: Instant,
+}
+
+/// Server health reported by `/status`.
+#[derive(Debug, Clone, Serialize, PartialEq)]
+pub struct ServerStatus {
+    pub uptime_secs: u64,
+    /// Clients connected to `/stream`.
+    pub clients: usize,
+    /// Time since the last frame was pushed; `None` before the first one.
+    pub last_frame_age_ms: Option<u64>,
+    pub target_fps: u32,
+}
+
+/// Counts a connected stream client for as long as it is alive.
+pub struct ClientGuard {
+    clients: Arc<AtomicUsize>,
+}
+
+impl Drop for ClientGuard {
+    fn drop(&mut self) {
+        self.clients.fetch_sub(1, Ordering::SeqCst);
+    }
 }
 
 impl Default for MjpegState {
@@ -20,24 +239,220 @@ impl MjpegState {
         let (sender, _) = broadcast::channel(4); // Small buffer, drop old frames
         Self {
             frame_sender: sender,
+            latest_frame: Mutex::new(None),
+            throttle: Mutex::new(FrameThrottle::new(DEFAULT_TARGET_FPS)),
+            target_fps: DEFAULT_TARGET_FPS,
+            jpeg_quality: DEFAULT_JPEG_QUALITY,
+            encode_permits: Arc::new(Semaphore::new(MAX_CONCURRENT_ENCODES)),
+            clients: Arc::new(AtomicUsize::new(0)),
+            started_at: Instant::now(),
         }
     }
 
-    /// Push a JPEG-encoded frame to all connected clients.
-    pub fn push_frame(&self, jpeg_data: Vec<u8>) {
+    /// Register a stream client; it is counted until the guard is dropped.
+    pub fn connect_client(&self) -> ClientGuard {
+        self.clients.fetch_add(1, Ordering::SeqCst);
+        ClientGuard {
+            clients: self.clients.clone(),
+        }
+    }
+
+    /// Number of clients currently connected to `/stream`.
+    pub fn client_count(&self) -> usize {
+        self.clients.load(Ordering::SeqCst)
+    }
+
+    /// Cap the rate of frames pushed to clients (clamped to 1..=60).
+    pub fn with_target_fps(mut self, fps: u32) -> Self {
+        self.target_fps = fps.clamp(1, 60);
+        *self.throttle.lock().unwrap_or_else(|e| e.into_inner()) =
+            FrameThrottle::new(self.target_fps);
+        self
+    }
+
+    /// Set the JPEG quality of the high tier (clamped to 1..=100).
+    pub fn with_jpeg_quality(mut self, quality: u8) -> Self {
+        self.jpeg_quality = quality.clamp(1, 100);
+        self
+    }
+
+    pub fn jpeg_quality(&self) -> u8 {
+        self.jpeg_quality
+    }
+
+    /// Whether a frame pushed at `now` would be accepted by the rate limiter.
+    pub fn frame_due(&self, now: Instant) -> bool {
+        self.throttle
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .is_due(now)
+    }
+
+    /// Push a frame to all connected clients.
+    /// Frames arriving faster than the target fps are dropped; returns whether it was sent.
+    pub fn push_frame(&self, frame: RgbFrame) -> bool {
+        self.push_frame_at(frame, Instant::now())
+    }
+
+    fn push_frame_at(&self, frame: RgbFrame, now: Instant) -> bool {
+        if !self
+            .throttle
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .ready(now)
+        {
+            return false;
+        }
+        let frame = Arc::new(frame);
+        *self.latest_frame.lock().unwrap_or_else(|e| e.into_inner()) = Some((frame.clone(), now));
         // Ignore send error (no receivers connected)
-        let _ = self.frame_sender.send(jpeg_data);
+        let _ = self.frame_sender.send(frame);
+        true
+    }
+
+    /// The most recently pushed frame, if any.
+    pub fn latest_frame(&self) -> Option<Arc<RgbFrame>> {
+        self.latest_frame
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .as_ref()
+            .map(|(frame, _)| frame.clone())
+    }
+
+    /// Encode a frame for a tier.
+    pub fn encode(&self, frame: &RgbFrame, quality: StreamQuality) -> Result<Vec<u8>, String> {
+        match quality {
+            StreamQuality::High => {
+                encode_rgb(&frame.rgb, frame.width, frame.height, self.jpeg_quality)
+            }
+            StreamQuality::Low => {
+                let (rgb, width, height) = downscale_rgb(
+                    &frame.rgb,
+                    frame.width,
+                    frame.height,
+                    LOW_TIER_MAX_WIDTH,
+                    LOW_TIER_MAX_HEIGHT,
+                );
+                encode_rgb(
+                    &rgb,
+                    width,
+                    height,
+                    LOW_TIER_JPEG_QUALITY.min(self.jpeg_quality),
+                )
+            }
+        }
+    }
+
+    /// Uptime, connected clients and frame freshness, as of `now`.
+    pub fn status(&self, now: Instant) -> ServerStatus {
+        let last_frame_at = self
+            .latest_frame
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .as_ref()
+            .map(|(_, at)| *at);
+        ServerStatus {
+            uptime_secs: now.saturating_duration_since(self.started_at).as_secs(),
+            clients: self.client_count(),
+            last_frame_age_ms: last_frame_at
+                .map(|at| now.saturating_duration_since(at).as_millis() as u64),
+            target_fps: self.target_fps,
+        }
+    }
+}
+
+/// Encode a frame off the async runtime, waiting for one of the shared
+/// encode slots first.
+async fn encode_limited(
+    state: Arc<MjpegState>,
+    frame: Arc<RgbFrame>,
+    quality: StreamQuality,
+) -> Result<Vec<u8>, String> {
+    let _permit = state
+        .encode_permits
+        .clone()
+        .acquire_owned()
+        .await
+        .map_err(|e| e.to_string())?;
+    tokio::task::spawn_blocking(move || state.encode(&frame, quality))
+        .await
+        .map_err(|e| e.to_string())?
+}
+
+/// Handle for the status endpoint polled by monitoring.
+async fn status_handler(state: axum::extract::State<Arc<MjpegState>>) -> Response<Body> {
+    match serde_json::to_vec(&state.status(Instant::now())) {
+        Ok(json) => Response::builder()
+            .header(header::CONTENT_TYPE, "application/json")
+            .header(header::CACHE_CONTROL, "no-store")
+            .body(Body::from(json))
+            .unwrap(),
+        Err(e) => Response::builder()
+            .status(StatusCode::INTERNAL_SERVER_ERROR)
+            .body(Body::from(e.to_string()))
+            .unwrap(),
+    }
+}
+
+/// Handle for the single-frame snapshot endpoint.
+async fn snapshot_handler(state: axum::extract::State<Arc<MjpegState>>) -> Response<Body> {
+    let latest = state.latest_frame();
+    match latest {
+        Some(frame) => match encode_limited(state.0, frame, StreamQuality::High).await {
+            Ok(jpeg) => Response::builder()
+                .header(header::CONTENT_TYPE, "image/jpeg")
+                .header(header::CACHE_CONTROL, "no-cache")
+                .body(Body::from(jpeg))
+                .unwrap(),
+            Err(e) => Response::builder()
+                .status(StatusCode::INTERNAL_SERVER_ERROR)
+                .body(Body::from(e))
+                .unwrap(),
+        },
+        None => Response::builder()
+            .status(StatusCode::SERVICE_UNAVAILABLE)
+            .header(header::RETRY_AFTER, "1")
+            .body(Body::from("No frame available yet"))
+            .unwrap(),
     }
 }
 
-/// Handle for the MJPEG stream endpoint.
-async fn stream_handler(state: axum::extract::State<Arc<MjpegState>>) -> Response<Body> {
+/// Handle for the MJPEG stream endpoint. `?quality=low|high` picks the
+/// encoding tier and `?fps=N` lowers the frame rate for this client.
+async fn stream_handler(
+    state: axum::extract::State<Arc<MjpegState>>,
+    Query(query): Query<StreamQuery>,
+) -> Response<Body> {
+    let options = match StreamOptions::from_query(&query, state.target_fps) {
+        Ok(options) => options,
+        Err(e) => {
+            return Response::builder()
+                .status(StatusCode::BAD_REQUEST)
+                .body(Body::from(e))
+                .unwrap()
+        }
+    };
+    let state = state.0;
     let mut receiver = state.frame_sender.subscribe();
+    let guard = state.connect_client();
 
     let stream = async_stream::stream! {
+        // Dropped with the stream, including when the client disconnects abruptly
+        let _guard = guard;
+        let mut throttle = FrameThrottle::new(options.fps);
         loop {
             match receiver.recv().await {
                 Ok(frame) => {
+                    if !throttle.ready(Instant::now()) {
+                        continue;
+                    }
+                    let frame = match encode_limited(state.clone(), frame, options.quality).await {
+                        Ok(jpeg) => jpeg,
+                        Err(e) => {
+                            log::warn!("Failed to encode MJPEG frame: {}", e);
+                            continue;
+                        }
+                    };
                     let part = format!(
                         "--{}\r\nContent-Type: image/jpeg\r\nContent-Length: {}\r\n\r\n",
                         BOUNDARY,
@@ -68,19 +483,20 @@ async fn stream_handler(state: axum::extract::State<Arc<MjpegState>>) -> Respons
         .unwrap()
 }
 
-/// Start the MJPEG HTTP server on a random available port.
+/// Start the MJPEG HTTP server on the configured address and port.
 /// Returns the port number and a shutdown sender.
 /// Send `true` on the watch channel to gracefully shut down the server.
 pub async fn start_server(
     state: Arc<MjpegState>,
+    options: &ServerOptions,
 ) -> Result<(u16, tokio::sync::watch::Sender<bool>), String> {
     let app = Router::new()
         .route("/stream", get(stream_handler))
+        .route("/snapshot", get(snapshot_handler))
+        .route("/status", get(status_handler))
         .with_state(state);
 
-    let listener = tokio::net::TcpListener::bind("127.0.0.1:0")
-        .await
-        .map_err(|e| e.to_string())?;
+    let listener = bind_listener(options).await?;
 
     let port = listener.local_addr().map_err(|e| e.to_string())?.port();
 
@@ -103,6 +519,300 @@ pub async fn start_server(
         log::info!("MJPEG server on port {} shut down", port);
     });
 
-    log::info!("MJPEG server started on port {}", port);
+    log::info!("MJPEG server started on {}:{}", options.bind_address, port);
     Ok((port, shutdown_tx))
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn frame(width: usize, height: usize, value: u8) -> RgbFrame {
+        RgbFrame {
+            width,
+            height,
+            rgb: vec![value; width * height * 3],
+        }
+    }
+
+    fn jpeg_size(jpeg: &[u8]) -> (u16, u16) {
+        let mut decoder = jpeg_decoder::Decoder::new(jpeg);
+        decoder.decode().unwrap();
+        let info = decoder.info().unwrap();
+        (info.width, info.height)
+    }
+
+    #[tokio::test]
+    async fn snapshot_is_unavailable_until_a_frame_arrives() {
+        let state = Arc::new(MjpegState::new());
+        let (port, shutdown_tx) = start_server(state.clone(), &ServerOptions::default())
+            .await
+            .unwrap();
+        let url = format!("http://127.0.0.1:{}/snapshot", port);
+
+        let response = reqwest::get(&url).await.unwrap();
+        assert_eq!(response.status(), reqwest::StatusCode::SERVICE_UNAVAILABLE);
+
+        state.push_frame(frame(16, 8, 128));
+        let response = reqwest::get(&url).await.unwrap();
+        assert_eq!(response.status(), reqwest::StatusCode::OK);
+        assert_eq!(response.headers()["content-type"], "image/jpeg");
+        assert_eq!(jpeg_size(&response.bytes().await.unwrap()), (16, 8));
+
+        let _ = shutdown_tx.send(true);
+    }
+
+    #[test]
+    fn throttle_drops_frames_inside_interval() {
+        let mut throttle = FrameThrottle::new(10);
+        let start = Instant::now();
+        assert!(throttle.ready(start));
+        assert!(!throttle.is_due(start + Duration::from_millis(50)));
+        assert!(!throttle.ready(start + Duration::from_millis(50)));
+        assert!(throttle.ready(start + Duration::from_millis(100)));
+    }
+
+    #[test]
+    fn push_frame_limits_effective_rate() {
+        let state = MjpegState::new().with_target_fps(10);
+        let start = Instant::now();
+        // 60fps for one second
+        let pushed = (0..60)
+            .filter(|i| {
+                state.push_frame_at(
+                    frame(1, 1, *i as u8),
+                    start + Duration::from_millis(i * 1000 / 60),
+                )
+            })
+            .count();
+        assert_eq!(pushed, 10);
+        // The last accepted frame was at 900ms
+        assert_eq!(state.latest_frame().unwrap().rgb, vec![54; 3]);
+    }
+
+    #[test]
+    fn client_guards_track_connected_clients() {
+        let state = MjpegState::new();
+        assert_eq!(state.client_count(), 0);
+        let first = state.connect_client();
+        let second = state.connect_client();
+        assert_eq!(state.client_count(), 2);
+        drop(first);
+        assert_eq!(state.client_count(), 1);
+        drop(second);
+        assert_eq!(state.client_count(), 0);
+    }
+
+    #[tokio::test]
+    async fn stream_clients_are_counted_until_they_disconnect() {
+        let state = Arc::new(MjpegState::new());
+        let (port, shutdown_tx) = start_server(state.clone(), &ServerOptions::default())
+            .await
+            .unwrap();
+
+        let response = reqwest::get(format!("http://127.0.0.1:{}/stream", port))
+            .await
+            .unwrap();
+        assert_eq!(state.client_count(), 1);
+
+        drop(response);
+        // Give the server a moment to notice the closed connection
+        let deadline = Instant::now() + Duration::from_secs(2);
+        while state.client_count() > 0 && Instant::now() < deadline {
+            state.push_frame(frame(2, 2, 0));
+            tokio::time::sleep(Duration::from_millis(40)).await;
+        }
+        assert_eq!(state.client_count(), 0);
+        let _ = shutdown_tx.send(true);
+    }
+
+    #[test]
+    fn quality_and_fps_are_clamped() {
+        let state = MjpegState::new().with_jpeg_quality(0).with_target_fps(1000);
+        assert_eq!(state.jpeg_quality(), 1);
+        assert_eq!(MjpegState::new().jpeg_quality(), DEFAULT_JPEG_QUALITY);
+        let start = Instant::now();
+        assert!(state.push_frame_at(frame(1, 1, 1), start));
+        // Capped at 60fps, so a frame 10ms later is dropped
+        assert!(!state.push_frame_at(frame(1, 1, 2), start + Duration::from_millis(10)));
+        assert!(state.push_frame_at(frame(1, 1, 3), start + Duration::from_millis(17)));
+    }
+
+    #[tokio::test]
+    async fn fixed_port_is_used_when_free() {
+        let free_port = std::net::TcpListener::bind("127.0.0.1:0")
+            .unwrap()
+            .local_addr()
+            .unwrap()
+            .port();
+        let options = ServerOptions::parse(Some("127.0.0.1"), Some(free_port)).unwrap();
+        let (port, shutdown_tx) = start_server(Arc::new(MjpegState::new()), &options)
+            .await
+            .unwrap();
+        assert_eq!(port, free_port);
+        let _ = shutdown_tx.send(true);
+    }
+
+    #[tokio::test]
+    async fn busy_port_is_reported() {
+        let held = std::net::TcpListener::bind("127.0.0.1:0").unwrap();
+        let busy_port = held.local_addr().unwrap().port();
+        let options = ServerOptions::parse(None, Some(busy_port)).unwrap();
+        let err = start_server(Arc::new(MjpegState::new()), &options)
+            .await
+            .unwrap_err();
+        assert!(err.contains("already in use"), "unexpected error: {}", err);
+    }
+
+    #[test]
+    fn options_parse_validates_bind_address() {
+        assert_eq!(
+            ServerOptions::parse(None, None).unwrap(),
+            ServerOptions::default()
+        );
+        let lan = ServerOptions::parse(Some("0.0.0.0"), Some(8090)).unwrap();
+        assert_eq!(lan.bind_address, IpAddr::V4(Ipv4Addr::UNSPECIFIED));
+        assert_eq!(lan.port, Some(8090));
+        assert!(ServerOptions::parse(Some("::1"), None).is_ok());
+        assert!(ServerOptions::parse(Some("camera.local"), None).is_err());
+        // Port 0 means "pick one"
+        assert_eq!(ServerOptions::parse(None, Some(0)).unwrap().port, None);
+    }
+
+    #[tokio::test]
+    async fn status_reports_clients_and_frame_age() {
+        let state = Arc::new(MjpegState::new().with_target_fps(15));
+        let (port, shutdown_tx) = start_server(state.clone(), &ServerOptions::default())
+            .await
+            .unwrap();
+        let url = format!("http://127.0.0.1:{}/status", port);
+
+        let before: serde_json::Value = reqwest::get(&url).await.unwrap().json().await.unwrap();
+        assert!(before["last_frame_age_ms"].is_null());
+
+        state.push_frame(frame(2, 2, 0));
+        let _client = state.connect_client();
+        let response = reqwest::get(&url).await.unwrap();
+        assert_eq!(response.headers()["content-type"], "application/json");
+        assert_eq!(response.headers()["cache-control"], "no-store");
+        let status: serde_json::Value = response.json().await.unwrap();
+        assert!(status["uptime_secs"].as_u64().unwrap() < 60);
+        assert_eq!(status["clients"], 1);
+        assert!(status["last_frame_age_ms"].as_u64().unwrap() < 5_000);
+        assert_eq!(status["target_fps"], 15);
+
+        let _ = shutdown_tx.send(true);
+    }
+
+    fn query(quality: Option<&str>, fps: Option<&str>) -> StreamQuery {
+        StreamQuery {
+            quality: quality.map(str::to_string),
+            fps: fps.map(str::to_string),
+        }
+    }
+
+    #[test]
+    fn stream_query_defaults_to_high_at_the_server_rate() {
+        assert_eq!(
+            StreamOptions::from_query(&StreamQuery::default(), 30).unwrap(),
+            StreamOptions {
+                quality: StreamQuality::High,
+                fps: 30,
+            }
+        );
+        let empty = StreamOptions::from_query(&query(Some(""), Some(" ")), 25).unwrap();
+        assert_eq!(empty.quality, StreamQuality::High);
+        assert_eq!(empty.fps, 25);
+    }
+
+    #[test]
+    fn stream_query_parses_quality_and_caps_fps() {
+        let low = StreamOptions::from_query(&query(Some("LOW"), Some("10")), 30).unwrap();
+        assert_eq!(low.quality, StreamQuality::Low);
+        assert_eq!(low.fps, 10);
+        // A client can't ask for more than the server produces
+        let fast = StreamOptions::from_query(&query(Some("high"), Some("120")), 30).unwrap();
+        assert_eq!(fast.fps, 30);
+    }
+
+    #[test]
+    fn stream_query_rejects_bad_values() {
+        assert!(StreamOptions::from_query(&query(Some("ultra"), None), 30).is_err());
+        assert!(StreamOptions::from_query(&query(None, Some("0")), 30).is_err());
+        assert!(StreamOptions::from_query(&query(None, Some("fast")), 30).is_err());
+    }
+
+    #[test]
+    fn tiers_encode_at_their_own_size() {
+        let state = MjpegState::new();
+        let source = frame(1280, 720, 90);
+        assert_eq!(
+            jpeg_size(&state.encode(&source, StreamQuality::High).unwrap()),
+            (1280, 720)
+        );
+        assert_eq!(
+            jpeg_size(&state.encode(&source, StreamQuality::Low).unwrap()),
+            (640, 360)
+        );
+        // Frames already smaller than the low tier keep their size
+        assert_eq!(
+            jpeg_size(
+                &state
+                    .encode(&frame(320, 180, 90), StreamQuality::Low)
+                    .unwrap()
+            ),
+            (320, 180)
+        );
+    }
+
+    #[tokio::test]
+    async fn stream_serves_the_requested_tier() {
+        let state = Arc::new(MjpegState::new());
+        let (port, shutdown_tx) = start_server(state.clone(), &ServerOptions::default())
+            .await
+            .unwrap();
+        let base = format!("http://127.0.0.1:{}/stream", port);
+
+        let rejected = reqwest::get(format!("{}?quality=ultra", base))
+            .await
+            .unwrap();
+        assert_eq!(rejected.status(), reqwest::StatusCode::BAD_REQUEST);
+
+        let mut response = reqwest::get(format!("{}?quality=low&fps=5", base))
+            .await
+            .unwrap();
+        let pusher = {
+            let state = state.clone();
+            tokio::spawn(async move {
+                loop {
+                    state.push_frame(frame(1280, 720, 200));
+                    tokio::time::sleep(Duration::from_millis(40)).await;
+                }
+            })
+        };
+
+        // Read until the first part's JPEG body is complete
+        let mut body = Vec::new();
+        let jpeg = loop {
+            body.extend_from_slice(&response.chunk().await.unwrap().unwrap());
+            let Some(header_end) = body.windows(4).position(|w| w == b"\r\n\r\n") else {
+                continue;
+            };
+            let length: usize = std::str::from_utf8(&body[..header_end])
+                .unwrap()
+                .lines()
+                .find_map(|line| line.strip_prefix("Content-Length: "))
+                .unwrap()
+                .parse()
+                .unwrap();
+            let start = header_end + 4;
+            if body.len() >= start + length {
+                break body[start..start + length].to_vec();
+            }
+        };
+        assert_eq!(jpeg_size(&jpeg), (640, 360));
+
+        pusher.abort();
+        let _ = shutdown_tx.send(true);
+    }
+}
@@ -1,2 +1,5 @@
 pub mod mjpeg_server;
+pub mod ndi_preview;
 pub mod ndi_source;
+pub mod source_cache;
+pub mod thumbnail;
@@ -0,0 +1,233 @@
+use crate::video::mjpeg_server::{MjpegState, RgbFrame};
+use std::sync::Arc;
+use tokio::sync::watch;
+
+/// Pixel layouts the preview can convert from.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum PixelFormat {
+    /// 4:2:2 packed as U0 Y0 V0 Y1.
+    Uyvy,
+    Bgra,
+    Bgrx,
+    Rgba,
+    Rgbx,
+}
+
+/// A borrowed video frame as delivered by the receiver.
+pub struct RawFrame<'a> {
+    pub format: PixelFormat,
+    pub width: usize,
+    pub height: usize,
+    /// Bytes per row, including any padding.
+    pub stride: usize,
+    pub data: &'a [u8],
+}
+
+/// Convert a frame to tightly packed 8-bit RGB.
+pub fn to_rgb(frame: &RawFrame) -> Result<Vec<u8>, String> {
+    let row_bytes = match frame.format {
+        PixelFormat::Uyvy => frame.width.div_ceil(2) * 4,
+        _ => frame.width * 4,
+    };
+    if frame.stride < row_bytes {
+        return Err(format!(
+            "Frame stride {} too small for width {}",
+            frame.stride, frame.width
+        ));
+    }
+    if frame.height > 0 && frame.data.len() < frame.stride * (frame.height - 1) + row_bytes {
+        return Err(format!(
+            "Frame data too short: {} bytes for {}x{}",
+            frame.data.len(),
+            frame.width,
+            frame.height
+        ));
+    }
+
+    let mut rgb = Vec::with_capacity(frame.width * frame.height * 3);
+    for y in 0..frame.height {
+        let row = &frame.data[y * frame.stride..y * frame.stride + row_bytes];
+        match frame.format {
+            PixelFormat::Uyvy => {
+                for (i, chunk) in row.chunks_exact(4).enumerate() {
+                    let (u, y0, v, y1) = (chunk[0], chunk[1], chunk[2], chunk[3]);
+                    rgb.extend_from_slice(&yuv_to_rgb(y0, u, v));
+                    if i * 2 + 1 < frame.width {
+                        rgb.extend_from_slice(&yuv_to_rgb(y1, u, v));
+                    }
+                }
+            }
+            PixelFormat::Bgra | PixelFormat::Bgrx => {
+                for px in row.chunks_exact(4) {
+                    rgb.extend_from_slice(&[px[2], px[1], px[0]]);
+                }
+            }
+            PixelFormat::Rgba | PixelFormat::Rgbx => {
+                for px in row.chunks_exact(4) {
+                    rgb.extend_from_slice(&px[..3]);
+                }
+            }
+        }
+    }
+    Ok(rgb)
+}
+
+/// BT.709 limited-range YUV to RGB, as used by NDI for HD sources.
+fn yuv_to_rgb(y: u8, u: u8, v: u8) -> [u8; 3] {
+    let c = 1.164 * (y as f32 - 16.0);
+    let d = u as f32 - 128.0;
+    let e = v as f32 - 128.0;
+    let clamp = |x: f32| x.round().clamp(0.0, 255.0) as u8;
+    [
+        clamp(c + 1.793 * e),
+        clamp(c - 0.213 * d - 0.533 * e),
+        clamp(c + 2.112 * d),
+    ]
+}
+
+/// Convert a frame to the RGB layout the MJPEG server holds.
+pub fn to_rgb_frame(frame: &RawFrame) -> Result<RgbFrame, String> {
+    Ok(RgbFrame {
+        width: frame.width,
+        height: frame.height,
+        rgb: to_rgb(frame)?,
+    })
+}
+
+/// Connect to an NDI source and feed its video into the MJPEG server until `stop` fires.
+#[cfg(feature = "ndi")]
+pub fn spawn_preview(
+    source_name: &str,
+    mjpeg: Arc<MjpegState>,
+    stop: watch::Receiver<bool>,
+) -> Result<(), String> {
+    use crate::ndi::receiver::NdiReceiver;
+    use std::time::{Duration, Instant};
+
+    /// How long each capture call blocks, so stop requests are seen promptly.
+    const CAPTURE_TIMEOUT: Duration = Duration::from_millis(100);
+
+    let receiver = NdiReceiver::connect(source_name)
+        .ok_or_else(|| format!("Failed to connect to NDI source '{}'", source_name))?;
+    let source_name = source_name.to_string();
+
+    tokio::task::spawn_blocking(move || {
+        log::info!("NDI preview started for '{}'", source_name);
+        while !*stop.borrow() {
+            let converted = receiver.capture_video(CAPTURE_TIMEOUT, |frame| {
+                // Skip the conversion for frames the server would drop anyway
+                if !mjpeg.frame_due(Instant::now()) {
+                    return None;
+                }
+                match to_rgb_frame(&frame) {
+                    Ok(rgb) => Some(rgb),
+                    Err(e) => {
+                        log::warn!("Dropping NDI frame from '{}': {}", source_name, e);
+                        None
+                    }
+                }
+            });
+            if let Some(Some(rgb)) = converted {
+                mjpeg.push_frame(rgb);
+            }
+        }
+        log::info!("NDI preview stopped for '{}'", source_name);
+    });
+    Ok(())
+}
+
+/// Stub for the NDI preview.
+/// Real implementation requires the `ndi` feature and the NDI SDK.
+#[cfg(not(feature = "ndi"))]
+pub fn spawn_preview(
+    _source_name: &str,
+    _mjpeg: Arc<MjpegState>,
+    _stop: watch::Receiver<bool>,
+) -> Result<(), String> {
+    Err("NDI SDK not linked".to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn bgra_converts_with_row_padding() {
+        // 2x2 BGRA with 4 bytes of padding per row.
+        let data = [
+            0, 0, 255, 255, 0, 255, 0, 255, 9, 9, 9, 9, //
+            255, 0, 0, 255, 10, 20, 30, 255, 9, 9, 9, 9,
+        ];
+        let frame = RawFrame {
+            format: PixelFormat::Bgra,
+            width: 2,
+            height: 2,
+            stride: 12,
+            data: &data,
+        };
+        assert_eq!(
+            to_rgb(&frame).unwrap(),
+            vec![255, 0, 0, 0, 255, 0, 0, 0, 255, 30, 20, 10]
+        );
+    }
+
+    #[test]
+    fn uyvy_converts_black_white_and_gray() {
+        let data = [128, 16, 128, 235, 128, 126, 128, 126];
+        let frame = RawFrame {
+            format: PixelFormat::Uyvy,
+            width: 4,
+            height: 1,
+            stride: 8,
+            data: &data,
+        };
+        let rgb = to_rgb(&frame).unwrap();
+        assert_eq!(&rgb[0..3], &[0, 0, 0]);
+        assert_eq!(&rgb[3..6], &[255, 255, 255]);
+        for &c in &rgb[6..12] {
+            assert!((c as i32 - 128).abs() <= 1, "gray channel was {}", c);
+        }
+    }
+
+    #[test]
+    fn uyvy_odd_width_drops_trailing_sample() {
+        let data = [128, 16, 128, 16];
+        let frame = RawFrame {
+            format: PixelFormat::Uyvy,
+            width: 1,
+            height: 1,
+            stride: 4,
+            data: &data,
+        };
+        assert_eq!(to_rgb(&frame).unwrap().len(), 3);
+    }
+
+    #[test]
+    fn short_frame_is_rejected() {
+        let data = [0u8; 8];
+        let frame = RawFrame {
+            format: PixelFormat::Bgrx,
+            width: 2,
+            height: 2,
+            stride: 8,
+            data: &data,
+        };
+        assert!(to_rgb(&frame).is_err());
+    }
+
+    #[test]
+    fn converted_frame_encodes_as_jpeg() {
+        let data = vec![128u8; 16 * 8 * 4];
+        let frame = RawFrame {
+            format: PixelFormat::Rgbx,
+            width: 16,
+            height: 8,
+            stride: 64,
+            data: &data,
+        };
+        let rgb = to_rgb_frame(&frame).unwrap();
+        assert_eq!((rgb.width, rgb.height), (16, 8));
+        let jpeg = crate::video::mjpeg_server::encode_rgb(&rgb.rgb, 16, 8, 80).unwrap();
+        assert_eq!(&jpeg[..2], &[0xFF, 0xD8]);
+    }
+}
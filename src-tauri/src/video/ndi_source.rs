@@ -1,4 +1,11 @@
 use serde::{Deserialize, Serialize};
+use std::time::{Duration, Instant};
+
+/// Default time to wait for sources to announce themselves.
+pub const DEFAULT_DISCOVERY_TIMEOUT: Duration = Duration::from_millis(2000);
+
+/// Once sources start arriving, stop after this long without changes.
+const SETTLE_WINDOW: Duration = Duration::from_millis(500);
 
 /// Represents a discovered NDI source on the network.
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -7,16 +14,152 @@ pub struct NdiSource {
     pub url: String,
 }
 
+/// Scope and duration of an NDI discovery pass.
+#[derive(Debug, Clone)]
+pub struct DiscoveryOptions {
+    /// NDI groups to search; empty searches the default group.
+    pub groups: Vec<String>,
+    /// Additional IPs to query directly, e.g. cameras on another subnet.
+    pub extra_ips: Vec<String>,
+    pub timeout: Duration,
+}
+
+impl Default for DiscoveryOptions {
+    fn default() -> Self {
+        Self {
+            groups: Vec::new(),
+            extra_ips: Vec::new(),
+            timeout: DEFAULT_DISCOVERY_TIMEOUT,
+        }
+    }
+}
+
+/// Join a list into the comma-separated form the NDI SDK expects.
+/// Returns `None` for an empty list so the SDK falls back to its defaults.
+pub fn join_list(items: &[String]) -> Option<String> {
+    let items: Vec<&str> = items
+        .iter()
+        .map(|s| s.trim())
+        .filter(|s| !s.is_empty())
+        .collect();
+    if items.is_empty() {
+        None
+    } else {
+        Some(items.join(","))
+    }
+}
+
+/// Drive a finder's wait function until sources settle or the timeout elapses.
+/// `wait` blocks for at most the given duration and returns true if the source list changed.
+pub fn wait_for_discovery(mut wait: impl FnMut(Duration) -> bool, timeout: Duration) {
+    let deadline = Instant::now() + timeout;
+    let mut seen_change = false;
+    while let Some(remaining) = deadline.checked_duration_since(Instant::now()) {
+        if remaining.is_zero() {
+            break;
+        }
+        let window = if seen_change {
+            remaining.min(SETTLE_WINDOW)
+        } else {
+            remaining
+        };
+        if wait(window) {
+            seen_change = true;
+        } else if seen_change {
+            break;
+        }
+    }
+}
+
+/// Discover NDI sources on the network.
+/// Returns whatever was found when the timeout elapses.
+#[cfg(feature = "ndi")]
+pub async fn discover_sources(options: &DiscoveryOptions) -> Vec<NdiSource> {
+    use crate::ndi::finder::NdiFinder;
+
+    let options = options.clone();
+    let result = tokio::task::spawn_blocking(move || {
+        let finder = match NdiFinder::new(&options) {
+            Some(finder) => finder,
+            None => return Vec::new(),
+        };
+        wait_for_discovery(|window| finder.wait_for_sources(window), options.timeout);
+        finder.get_sources()
+    })
+    .await;
+
+    match result {
+        Ok(sources) => {
+            log::info!("NDI source discovery found {} source(s)", sources.len());
+            sources
+        }
+        Err(e) => {
+            log::error!("NDI source discovery task failed: {}", e);
+            Vec::new()
+        }
+    }
+}
+
 /// Stub for NDI source discovery.
-/// Real implementation requires NDI SDK FFI bindings via bindgen.
-/// The NDI SDK is proprietary and must be installed separately.
-pub async fn discover_sources() -> Vec<NdiSource> {
-    // In production, this would:
-    // 1. Initialize NDIlib_find_create_t
-    // 2. Call NDIlib_find_create_v2
-    // 3. Wait for sources via NDIlib_find_wait_for_sources
-    // 4. Get sources via NDIlib_find_get_current_sources
-    // 5. Map to NdiSource structs
+/// Real implementation requires the `ndi` feature and the NDI SDK.
+#[cfg(not(feature = "ndi"))]
+pub async fn discover_sources(_options: &DiscoveryOptions) -> Vec<NdiSource> {
     log::info!("NDI source discovery: NDI SDK not linked — returning empty list");
     Vec::new()
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn join_list_skips_blanks() {
+        let items = vec!["studio".to_string(), " ".to_string(), " field ".to_string()];
+        assert_eq!(join_list(&items).as_deref(), Some("studio,field"));
+        assert_eq!(join_list(&[]), None);
+    }
+
+    #[test]
+    fn discovery_waits_full_timeout_when_nothing_appears() {
+        let mut calls = 0;
+        let start = Instant::now();
+        wait_for_discovery(
+            |window| {
+                calls += 1;
+                std::thread::sleep(window);
+                false
+            },
+            Duration::from_millis(50),
+        );
+        assert!(start.elapsed() >= Duration::from_millis(50));
+        assert_eq!(calls, 1);
+    }
+
+    #[test]
+    fn discovery_stops_once_sources_settle() {
+        let mut changes = [true, true, false].into_iter();
+        let mut calls = 0;
+        wait_for_discovery(
+            |_| {
+                calls += 1;
+                changes.next().unwrap_or(false)
+            },
+            Duration::from_secs(60),
+        );
+        assert_eq!(calls, 3);
+    }
+
+    #[test]
+    fn discovery_returns_partial_results_at_timeout() {
+        // The source list keeps changing; the deadline still ends the wait.
+        let start = Instant::now();
+        wait_for_discovery(
+            |window| {
+                std::thread::sleep(window.min(Duration::from_millis(10)));
+                true
+            },
+            Duration::from_millis(40),
+        );
+        assert!(start.elapsed() < Duration::from_secs(1));
+    }
+}
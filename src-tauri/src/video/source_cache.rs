@@ -0,0 +1,290 @@
+use crate::simulator::client::{Clock, SystemClock};
+use crate::video::ndi_source::{DiscoveryOptions, NdiSource};
+use std::future::Future;
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+
+/// How long discovered sources are served before discovery runs again.
+pub const DEFAULT_SOURCE_CACHE_TTL: Duration = Duration::from_secs(5);
+
+/// Stop refreshing in the background once nobody has asked for the list in
+/// this long.
+const REFRESH_IDLE: Duration = Duration::from_secs(60);
+
+/// How often the background refresh checks whether the list is due.
+const REFRESH_CHECK_INTERVAL: Duration = Duration::from_secs(1);
+
+struct Cached {
+    options: DiscoveryOptions,
+    sources: Vec<NdiSource>,
+    fetched_at: Instant,
+    requested_at: Instant,
+}
+
+impl Cached {
+    /// Results only answer requests searching the same groups and IPs.
+    fn matches(&self, options: &DiscoveryOptions) -> bool {
+        self.options.groups == options.groups && self.options.extra_ips == options.extra_ips
+    }
+}
+
+/// Short-lived cache in front of NDI discovery, which blocks for seconds
+/// while the UI asks for the source list repeatedly. A background refresh
+/// keeps the last requested list warm so those calls don't wait.
+pub struct SourceCache {
+    cached: Mutex<Option<Cached>>,
+    ttl: Mutex<Duration>,
+    clock: Box<dyn Clock>,
+}
+
+impl SourceCache {
+    /// A `ttl` of zero turns caching off.
+    pub fn new(ttl: Duration) -> Self {
+        Self {
+            cached: Mutex::new(None),
+            ttl: Mutex::new(ttl),
+            clock: Box::new(SystemClock),
+        }
+    }
+
+    pub fn with_clock(mut self, clock: Box<dyn Clock>) -> Self {
+        self.clock = clock;
+        self
+    }
+
+    fn lock(&self) -> std::sync::MutexGuard<'_, Option<Cached>> {
+        self.cached.lock().unwrap_or_else(|e| e.into_inner())
+    }
+
+    fn ttl(&self) -> Duration {
+        *self.ttl.lock().unwrap_or_else(|e| e.into_inner())
+    }
+
+    pub fn set_ttl(&self, ttl: Duration) {
+        *self.ttl.lock().unwrap_or_else(|e| e.into_inner()) = ttl;
+    }
+
+    /// Cached sources for the scope of `options`, if still fresh. Counts as
+    /// a request, keeping the background refresh going.
+    pub fn fresh(&self, options: &DiscoveryOptions) -> Option<Vec<NdiSource>> {
+        let now = self.clock.now();
+        let ttl = self.ttl();
+        let mut cached = self.lock();
+        let entry = cached.as_mut().filter(|entry| entry.matches(options))?;
+        entry.requested_at = now;
+        (now.duration_since(entry.fetched_at) < ttl).then(|| entry.sources.clone())
+    }
+
+    /// Remember the result of a discovery pass.
+    pub fn store(&self, options: &DiscoveryOptions, sources: Vec<NdiSource>) {
+        let now = self.clock.now();
+        let mut cached = self.lock();
+        let requested_at = cached
+            .as_ref()
+            .filter(|entry| entry.matches(options))
+            .map_or(now, |entry| entry.requested_at);
+        *cached = Some(Cached {
+            options: options.clone(),
+            sources,
+            fetched_at: now,
+            requested_at,
+        });
+    }
+
+    /// Serve cached sources, or run `discover` and cache what it finds.
+    /// `force` always runs discovery.
+    pub async fn get_or_discover<F, Fut>(
+        &self,
+        options: &DiscoveryOptions,
+        force: bool,
+        discover: F,
+    ) -> Vec<NdiSource>
+    where
+        F: FnOnce(DiscoveryOptions) -> Fut,
+        Fut: Future<Output = Vec<NdiSource>>,
+    {
+        if !force {
+            if let Some(sources) = self.fresh(options) {
+                return sources;
+            }
+        }
+        let sources = discover(options.clone()).await;
+        self.store(options, sources.clone());
+        sources
+    }
+
+    /// Options to rediscover with, when the cached list is past half its TTL
+    /// and was requested recently enough to be worth keeping warm.
+    fn due_for_refresh(&self) -> Option<DiscoveryOptions> {
+        let now = self.clock.now();
+        let ttl = self.ttl();
+        let cached = self.lock();
+        let entry = cached.as_ref()?;
+        let due = !ttl.is_zero()
+            && now.duration_since(entry.fetched_at) >= ttl / 2
+            && now.duration_since(entry.requested_at) < REFRESH_IDLE;
+        due.then(|| entry.options.clone())
+    }
+
+    /// Rediscover the cached list if it is due. Returns whether it ran.
+    pub async fn refresh<F, Fut>(&self, discover: F) -> bool
+    where
+        F: FnOnce(DiscoveryOptions) -> Fut,
+        Fut: Future<Output = Vec<NdiSource>>,
+    {
+        let Some(options) = self.due_for_refresh() else {
+            return false;
+        };
+        let sources = discover(options.clone()).await;
+        self.store(&options, sources);
+        true
+    }
+}
+
+/// Keep the cached source list warm for as long as the app runs.
+pub async fn run_background_refresh<F, Fut>(cache: Arc<SourceCache>, discover: F)
+where
+    F: Fn(DiscoveryOptions) -> Fut,
+    Fut: Future<Output = Vec<NdiSource>>,
+{
+    let mut ticker = tokio::time::interval(REFRESH_CHECK_INTERVAL);
+    ticker.set_missed_tick_behavior(tokio::time::MissedTickBehavior::Skip);
+    loop {
+        ticker.tick().await;
+        cache.refresh(&discover).await;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::simulator::client::ManualClock;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+
+    fn source(name: &str) -> NdiSource {
+        NdiSource {
+            name: name.to_string(),
+            url: format!("{}.local:5961", name),
+        }
+    }
+
+    /// Discovery stub that counts its runs and names sources after them.
+    fn counting_discovery(
+        runs: &AtomicUsize,
+    ) -> impl Fn(DiscoveryOptions) -> std::future::Ready<Vec<NdiSource>> + '_ {
+        move |_| {
+            let run = runs.fetch_add(1, Ordering::SeqCst) + 1;
+            std::future::ready(vec![source(&format!("CAM {}", run))])
+        }
+    }
+
+    fn cache(clock: &ManualClock) -> SourceCache {
+        SourceCache::new(Duration::from_secs(5)).with_clock(Box::new(clock.clone()))
+    }
+
+    #[tokio::test]
+    async fn fresh_list_is_served_until_it_expires() {
+        let clock = ManualClock::new();
+        let cache = cache(&clock);
+        let runs = AtomicUsize::new(0);
+        let options = DiscoveryOptions::default();
+
+        let first = cache
+            .get_or_discover(&options, false, counting_discovery(&runs))
+            .await;
+        clock.advance(Duration::from_secs(4));
+        let cached = cache
+            .get_or_discover(&options, false, counting_discovery(&runs))
+            .await;
+        assert_eq!(runs.load(Ordering::SeqCst), 1);
+        assert_eq!(cached[0].name, first[0].name);
+
+        clock.advance(Duration::from_secs(1));
+        let expired = cache
+            .get_or_discover(&options, false, counting_discovery(&runs))
+            .await;
+        assert_eq!(runs.load(Ordering::SeqCst), 2);
+        assert_eq!(expired[0].name, "CAM 2");
+    }
+
+    #[tokio::test]
+    async fn force_and_a_new_scope_bypass_the_cache() {
+        let clock = ManualClock::new();
+        let cache = cache(&clock);
+        let runs = AtomicUsize::new(0);
+        let options = DiscoveryOptions::default();
+        cache
+            .get_or_discover(&options, false, counting_discovery(&runs))
+            .await;
+
+        cache
+            .get_or_discover(&options, true, counting_discovery(&runs))
+            .await;
+        assert_eq!(runs.load(Ordering::SeqCst), 2);
+
+        let studio = DiscoveryOptions {
+            groups: vec!["studio".to_string()],
+            ..Default::default()
+        };
+        cache
+            .get_or_discover(&studio, false, counting_discovery(&runs))
+            .await;
+        assert_eq!(runs.load(Ordering::SeqCst), 3);
+    }
+
+    #[tokio::test]
+    async fn zero_ttl_always_discovers() {
+        let clock = ManualClock::new();
+        let cache = cache(&clock);
+        cache.set_ttl(Duration::ZERO);
+        let runs = AtomicUsize::new(0);
+        let options = DiscoveryOptions::default();
+        for _ in 0..3 {
+            cache
+                .get_or_discover(&options, false, counting_discovery(&runs))
+                .await;
+        }
+        assert_eq!(runs.load(Ordering::SeqCst), 3);
+        assert!(!cache.refresh(counting_discovery(&runs)).await);
+    }
+
+    #[tokio::test]
+    async fn background_refresh_keeps_a_requested_list_warm() {
+        let clock = ManualClock::new();
+        let cache = cache(&clock);
+        let runs = AtomicUsize::new(0);
+        let options = DiscoveryOptions::default();
+        assert!(!cache.refresh(counting_discovery(&runs)).await);
+
+        cache
+            .get_or_discover(&options, false, counting_discovery(&runs))
+            .await;
+        clock.advance(Duration::from_secs(2));
+        assert!(!cache.refresh(counting_discovery(&runs)).await);
+
+        // Past half the TTL the list is rediscovered, so requests still hit
+        clock.advance(Duration::from_secs(1));
+        assert!(cache.refresh(counting_discovery(&runs)).await);
+        clock.advance(Duration::from_secs(4));
+        assert_eq!(cache.fresh(&options).unwrap()[0].name, "CAM 2");
+        assert_eq!(runs.load(Ordering::SeqCst), 2);
+    }
+
+    #[tokio::test]
+    async fn background_refresh_stops_when_nobody_asks() {
+        let clock = ManualClock::new();
+        let cache = cache(&clock);
+        let runs = AtomicUsize::new(0);
+        cache
+            .get_or_discover(
+                &DiscoveryOptions::default(),
+                false,
+                counting_discovery(&runs),
+            )
+            .await;
+
+        clock.advance(REFRESH_IDLE);
+        assert!(!cache.refresh(counting_discovery(&runs)).await);
+        assert_eq!(runs.load(Ordering::SeqCst), 1);
+    }
+}
@@ -0,0 +1,136 @@
+use crate::video::mjpeg_server::encode_rgb;
+use base64::engine::general_purpose::STANDARD;
+use base64::Engine;
+
+/// Largest thumbnail width stored on a preset.
+pub const MAX_THUMBNAIL_WIDTH: usize = 320;
+/// Largest thumbnail height stored on a preset.
+pub const MAX_THUMBNAIL_HEIGHT: usize = 180;
+/// JPEG quality for thumbnails; they are small enough that artifacts don't show.
+const THUMBNAIL_QUALITY: u8 = 70;
+
+/// Shrink packed 8-bit RGB to fit within `max_width` x `max_height`, keeping
+/// the aspect ratio. Each output pixel averages the source pixels it covers.
+/// Frames that already fit are returned unchanged.
+pub fn downscale_rgb(
+    rgb: &[u8],
+    width: usize,
+    height: usize,
+    max_width: usize,
+    max_height: usize,
+) -> (Vec<u8>, usize, usize) {
+    if width <= max_width && height <= max_height {
+        return (rgb.to_vec(), width, height);
+    }
+    let scale = (max_width as f64 / width as f64).min(max_height as f64 / height as f64);
+    let out_width = ((width as f64 * scale).round() as usize).max(1);
+    let out_height = ((height as f64 * scale).round() as usize).max(1);
+
+    let mut out = Vec::with_capacity(out_width * out_height * 3);
+    for oy in 0..out_height {
+        let y0 = oy * height / out_height;
+        let y1 = ((oy + 1) * height / out_height).max(y0 + 1);
+        for ox in 0..out_width {
+            let x0 = ox * width / out_width;
+            let x1 = ((ox + 1) * width / out_width).max(x0 + 1);
+            let mut sum = [0u32; 3];
+            for y in y0..y1 {
+                for x in x0..x1 {
+                    let i = (y * width + x) * 3;
+                    for (channel, total) in sum.iter_mut().enumerate() {
+                        *total += rgb[i + channel] as u32;
+                    }
+                }
+            }
+            let count = ((y1 - y0) * (x1 - x0)) as u32;
+            out.extend(sum.iter().map(|total| (total / count) as u8));
+        }
+    }
+    (out, out_width, out_height)
+}
+
+/// Decode a JPEG frame, downscale it and re-encode it as a base64 JPEG
+/// thumbnail.
+pub fn thumbnail_from_jpeg(jpeg: &[u8]) -> Result<String, String> {
+    let mut decoder = jpeg_decoder::Decoder::new(jpeg);
+    let pixels = decoder.decode().map_err(|e| e.to_string())?;
+    let info = decoder.info().ok_or("JPEG frame has no header")?;
+    let (width, height) = (info.width as usize, info.height as usize);
+    let rgb = match info.pixel_format {
+        jpeg_decoder::PixelFormat::RGB24 => pixels,
+        jpeg_decoder::PixelFormat::L8 => pixels.iter().flat_map(|&l| [l, l, l]).collect(),
+        other => return Err(format!("Unsupported JPEG pixel format {:?}", other)),
+    };
+
+    thumbnail_from_rgb(&rgb, width, height)
+}
+
+/// Downscale packed RGB and encode it as a base64 JPEG thumbnail.
+pub fn thumbnail_from_rgb(rgb: &[u8], width: usize, height: usize) -> Result<String, String> {
+    let (small, width, height) = downscale_rgb(
+        rgb,
+        width,
+        height,
+        MAX_THUMBNAIL_WIDTH,
+        MAX_THUMBNAIL_HEIGHT,
+    );
+    let encoded = encode_rgb(&small, width, height, THUMBNAIL_QUALITY)?;
+    Ok(STANDARD.encode(encoded))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn downscale_keeps_aspect_ratio_and_averages() {
+        // 4x2 frame: left half black, right half white
+        let mut rgb = Vec::new();
+        for _ in 0..2 {
+            for x in 0..4 {
+                let v = if x < 2 { 0 } else { 255 };
+                rgb.extend_from_slice(&[v, v, v]);
+            }
+        }
+        let (small, width, height) = downscale_rgb(&rgb, 4, 2, 2, 2);
+        assert_eq!((width, height), (2, 1));
+        assert_eq!(small, vec![0, 0, 0, 255, 255, 255]);
+    }
+
+    #[test]
+    fn downscale_leaves_small_frames_alone() {
+        let rgb = vec![10; 2 * 2 * 3];
+        assert_eq!(downscale_rgb(&rgb, 2, 2, 320, 180), (rgb.clone(), 2, 2));
+    }
+
+    #[test]
+    fn downscale_fits_wide_frames_within_both_bounds() {
+        let rgb = vec![128; 1920 * 1080 * 3];
+        let (small, width, height) = downscale_rgb(&rgb, 1920, 1080, 320, 180);
+        assert_eq!((width, height), (320, 180));
+        assert_eq!(small.len(), 320 * 180 * 3);
+        assert!(small.iter().all(|&v| v == 128));
+    }
+
+    #[test]
+    fn thumbnail_is_a_smaller_base64_jpeg() {
+        let rgb = vec![200; 1280 * 720 * 3];
+        let mut frame = Vec::new();
+        jpeg_encoder::Encoder::new(&mut frame, 90)
+            .encode(&rgb, 1280, 720, jpeg_encoder::ColorType::Rgb)
+            .unwrap();
+
+        let thumbnail = STANDARD
+            .decode(thumbnail_from_jpeg(&frame).unwrap())
+            .unwrap();
+        let mut decoder = jpeg_decoder::Decoder::new(thumbnail.as_slice());
+        decoder.decode().unwrap();
+        let info = decoder.info().unwrap();
+        assert_eq!((info.width, info.height), (320, 180));
+    }
+
+    #[test]
+    fn rejects_data_that_is_not_a_jpeg() {
+        assert!(thumbnail_from_jpeg(b"not a jpeg").is_err());
+    }
+}
@@ -1,79 +1,638 @@
+use crate::ptz::cancel::CancelToken;
 use crate::ptz::controller::{PtzController, PtzError};
-use crate::ptz::types::PtzPosition;
+use crate::ptz::nudge;
+use crate::ptz::preview::{EncodedRequest, PtzCommand};
+use crate::ptz::timeout;
+use crate::ptz::types::{
+    AbsoluteStrategy, CameraInfo, Capabilities, MenuDirection, PtzLimits, PtzPosition, TallyState,
+    ViscaCalibration, ViscaDiagnostics, WhiteBalanceSetting,
+};
 use async_trait::async_trait;
+use std::collections::{BTreeMap, HashMap};
+use std::net::{IpAddr, Ipv4Addr, Ipv6Addr, SocketAddr};
 use std::sync::atomic::{AtomicU32, Ordering};
+use std::sync::Arc;
+use std::time::Duration;
 use tokio::net::UdpSocket;
-use tokio::sync::Mutex;
+use tokio::sync::{watch, Mutex, OnceCell};
 
 use super::commands;
 
-/// VISCA-over-IP client for Sony and compatible PTZ cameras.
-pub struct ViscaClient {
+/// How long to wait for the camera to answer a command.
+const RESPONSE_TIMEOUT: Duration = Duration::from_secs(2);
+
+/// How long to wait for a command to complete, long enough for a full slew.
+const COMPLETION_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// How long a completion wait listens before letting other commands use the
+/// socket, so a stop can go out during a long slew.
+const COMPLETION_LISTEN_SLICE: Duration = Duration::from_millis(50);
+
+/// How often the position is checked while a continuous move runs toward a limit.
+const LIMIT_CHECK_INTERVAL: Duration = Duration::from_millis(100);
+
+/// How often the position is read while calibration drives to an extreme.
+const CALIBRATION_POLL_INTERVAL: Duration = Duration::from_millis(100);
+
+/// How often the zoom position is read while waiting for it to settle.
+const ZOOM_SETTLE_INTERVAL: Duration = Duration::from_millis(100);
+
+/// Position reads allowed per extreme before calibration gives up.
+const CALIBRATION_MAX_POLLS: u32 = 300;
+
+/// The UDP connection to one camera, shared with the limit guard task.
+struct ViscaLink {
+    /// Held across each whole request/response cycle so commands never interleave.
     socket: Mutex<Option<UdpSocket>>,
     host: String,
     port: u16,
     sequence: AtomicU32,
+    /// Local address to send from; `None` binds the unspecified address.
+    bind_address: Option<IpAddr>,
+    /// Last datagram received, kept for diagnostics.
+    last_reply: std::sync::Mutex<Option<Vec<u8>>>,
+    /// Completion replies awaited by commands that let go of the socket
+    /// after their ACK, by sequence number. Filled in by whichever caller
+    /// happens to read them.
+    completions: std::sync::Mutex<HashMap<u32, Option<Vec<u8>>>>,
 }
 
-impl ViscaClient {
-    pub fn new(host: &str, port: u16) -> Result<Self, PtzError> {
-        crate::ptz::types::validate_host(host).map_err(PtzError::ConnectionFailed)?;
-        Ok(Self {
+impl ViscaLink {
+    fn new(host: &str, port: u16, bind_address: Option<IpAddr>) -> Self {
+        Self {
             socket: Mutex::new(None),
             host: host.to_string(),
             port,
             sequence: AtomicU32::new(1),
-        })
+            bind_address,
+            last_reply: std::sync::Mutex::new(None),
+            completions: std::sync::Mutex::new(HashMap::new()),
+        }
+    }
+
+    fn lock_completions(&self) -> std::sync::MutexGuard<'_, HashMap<u32, Option<Vec<u8>>>> {
+        self.completions.lock().unwrap_or_else(|e| e.into_inner())
+    }
+
+    /// Keep a received datagram for diagnostics, and hand it to a completion
+    /// wait if one is listening for its sequence number. Returns whether it
+    /// was for such a wait.
+    fn record_reply(&self, reply: &[u8]) -> bool {
+        *self.last_reply.lock().unwrap_or_else(|e| e.into_inner()) = Some(reply.to_vec());
+        let Some(seq) = commands::reply_sequence(reply) else {
+            return false;
+        };
+        match self.lock_completions().get_mut(&seq) {
+            Some(slot) => {
+                if !commands::is_ack(self::payload(reply)) {
+                    *slot = Some(reply.to_vec());
+                }
+                true
+            }
+            None => false,
+        }
+    }
+
+    /// The camera's address, with IPv6 literals bracketed.
+    fn target_addr(&self) -> String {
+        crate::ptz::types::host_with_port(&self.host, self.port)
+    }
+
+    async fn connect_socket(&self) -> Result<UdpSocket, PtzError> {
+        // Bind the local socket in the same address family as an IPv6 literal target
+        let local = match self.bind_address {
+            Some(ip) => SocketAddr::new(ip, 0),
+            None if self.host.parse::<Ipv6Addr>().is_ok() => {
+                SocketAddr::new(Ipv6Addr::UNSPECIFIED.into(), 0)
+            }
+            None => SocketAddr::new(Ipv4Addr::UNSPECIFIED.into(), 0),
+        };
+        let s = UdpSocket::bind(local)
+            .await
+            .map_err(|e| PtzError::ConnectionFailed(e.to_string()))?;
+        s.connect(self.target_addr())
+            .await
+            .map_err(|e| PtzError::ConnectionFailed(e.to_string()))?;
+        // The camera may have rebooted or seen another controller; start
+        // both sides over at sequence 1
+        self.reset_sequence(&s).await?;
+        Ok(s)
+    }
+
+    /// Send the control `RESET` and restart our numbering to match.
+    async fn reset_sequence(&self, s: &UdpSocket) -> Result<(), PtzError> {
+        s.send(&commands::sequence_reset_packet())
+            .await
+            .map_err(|e| PtzError::ConnectionFailed(e.to_string()))?;
+        self.sequence.store(1, Ordering::SeqCst);
+        Ok(())
+    }
+
+    async fn diagnostics(&self) -> ViscaDiagnostics {
+        let connected = self.socket.lock().await.is_some();
+        let last_reply = self
+            .last_reply
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .clone();
+        ViscaDiagnostics {
+            sequence: self.sequence.load(Ordering::SeqCst),
+            connected,
+            last_reply_hex: last_reply.map(|reply| {
+                reply
+                    .iter()
+                    .map(|b| format!("{:02X}", b))
+                    .collect::<Vec<_>>()
+                    .join(" ")
+            }),
+        }
+    }
+
+    /// Restart numbering at 1, telling the camera too if a socket is open.
+    async fn force_sequence_reset(&self) -> Result<(), PtzError> {
+        match self.socket.lock().await.as_ref() {
+            Some(s) => self.reset_sequence(s).await,
+            None => {
+                self.sequence.store(1, Ordering::SeqCst);
+                Ok(())
+            }
+        }
     }
 
     async fn ensure_connected(&self) -> Result<(), PtzError> {
         let mut socket = self.socket.lock().await;
         if socket.is_none() {
-            let s = UdpSocket::bind("0.0.0.0:0")
-                .await
-                .map_err(|e| PtzError::ConnectionFailed(e.to_string()))?;
-            s.connect(format!("{}:{}", self.host, self.port))
-                .await
-                .map_err(|e| PtzError::ConnectionFailed(e.to_string()))?;
-            *socket = Some(s);
+            *socket = Some(self.connect_socket().await?);
         }
         Ok(())
     }
 
+    /// Send one command and wait for the reply carrying its sequence number.
+    /// The socket lock is held throughout, so concurrent callers queue up and
+    /// late replies to earlier commands are discarded instead of mismatched.
+    /// A rejected sequence number triggers a `RESET` and a single resend; a
+    /// timeout drops the socket so the next command reconnects and resets.
     async fn send_command(&self, payload: &[u8]) -> Result<Vec<u8>, PtzError> {
-        self.ensure_connected().await?;
-        let seq = self.sequence.fetch_add(1, Ordering::SeqCst);
-        let packet = commands::build_visca_packet(payload, seq);
+        self.send(payload, false).await
+    }
 
-        let socket = self.socket.lock().await;
+    /// Like `send_command`, but wait until the camera reports the command
+    /// complete or failed. The socket is held only until the ACK and then in
+    /// short turns, so other commands, a stop in particular, go out during a
+    /// long slew. `None` when `cancel` fires before the command completes.
+    async fn send_command_until_complete(
+        &self,
+        payload: &[u8],
+        cancel: &CancelToken,
+    ) -> Result<Option<Vec<u8>>, PtzError> {
+        let mut reply = self.send(payload, true).await?;
+        if let Some(seq) =
+            commands::reply_sequence(&reply).filter(|_| commands::is_ack(self::payload(&reply)))
+        {
+            let completed = cancel.until_cancelled(self.wait_for_completion(seq)).await;
+            self.lock_completions().remove(&seq);
+            match completed {
+                Some(completed) => reply = completed?,
+                None => return Ok(None),
+            }
+        }
+        match commands::reply_error(self::payload(&reply)) {
+            Some(code) => Err(PtzError::CommandFailed(format!(
+                "VISCA camera reported error {:02X}",
+                code
+            ))),
+            None => Ok(Some(reply)),
+        }
+    }
+
+    /// Listen for the completion of the ACKed command `seq`, taking the
+    /// socket for one short slice at a time.
+    async fn wait_for_completion(&self, seq: u32) -> Result<Vec<u8>, PtzError> {
+        let deadline = tokio::time::Instant::now() + timeout::or_default(COMPLETION_TIMEOUT);
+        let mut buf = [0u8; 256];
+        loop {
+            if let Some(reply) = self.lock_completions().get_mut(&seq).and_then(Option::take) {
+                return Ok(reply);
+            }
+            if tokio::time::Instant::now() >= deadline {
+                return Err(PtzError::Timeout("VISCA completion timeout".to_string()));
+            }
+            let socket = self.socket.lock().await;
+            let s = socket.as_ref().ok_or(PtzError::NotConnected)?;
+            let slice = (tokio::time::Instant::now() + COMPLETION_LISTEN_SLICE).min(deadline);
+            if let Ok(received) = tokio::time::timeout_at(slice, s.recv(&mut buf)).await {
+                let len = received.map_err(|e| PtzError::CommandFailed(e.to_string()))?;
+                if !self.record_reply(&buf[..len]) {
+                    log::debug!(
+                        "Discarding VISCA reply for sequence {:?} while waiting for {}",
+                        commands::reply_sequence(&buf[..len]),
+                        seq
+                    );
+                }
+            }
+        }
+    }
+
+    async fn send(&self, payload: &[u8], until_complete: bool) -> Result<Vec<u8>, PtzError> {
+        let mut socket = self.socket.lock().await;
+        if socket.is_none() {
+            *socket = Some(self.connect_socket().await?);
+        }
         let s = socket.as_ref().ok_or(PtzError::NotConnected)?;
 
+        let result = match self.exchange(s, payload, until_complete).await {
+            Ok(None) => {
+                log::warn!("VISCA camera rejected the sequence number, resetting");
+                self.reset_sequence(s).await?;
+                self.exchange(s, payload, until_complete)
+                    .await
+                    .and_then(|reply| {
+                        reply.ok_or_else(|| {
+                            PtzError::ProtocolError(
+                                "VISCA camera rejected the sequence number after a reset".into(),
+                            )
+                        })
+                    })
+            }
+            other => other.map(|reply| reply.unwrap_or_default()),
+        };
+        if matches!(result, Err(PtzError::Timeout(_))) {
+            *socket = None;
+        }
+        result
+    }
+
+    /// Send a command and wait for its reply. With `until_complete`, an ACK
+    /// also registers the command for `wait_for_completion` before the
+    /// socket is released. `None` means the camera answered with a sequence
+    /// number error instead.
+    async fn exchange(
+        &self,
+        s: &UdpSocket,
+        payload: &[u8],
+        until_complete: bool,
+    ) -> Result<Option<Vec<u8>>, PtzError> {
+        let seq = self.sequence.fetch_add(1, Ordering::SeqCst);
+        let packet = commands::build_visca_packet(payload, seq);
         s.send(&packet)
             .await
             .map_err(|e| PtzError::CommandFailed(e.to_string()))?;
 
+        let deadline = tokio::time::Instant::now() + timeout::or_default(RESPONSE_TIMEOUT);
         let mut buf = [0u8; 256];
-        let timeout = tokio::time::timeout(std::time::Duration::from_secs(2), s.recv(&mut buf));
+        loop {
+            match tokio::time::timeout_at(deadline, s.recv(&mut buf)).await {
+                Ok(Ok(len)) => {
+                    let reply = &buf[..len];
+                    if commands::is_sequence_error(reply) {
+                        *self.last_reply.lock().unwrap_or_else(|e| e.into_inner()) =
+                            Some(reply.to_vec());
+                        return Ok(None);
+                    }
+                    if self.record_reply(reply) {
+                        continue;
+                    }
+                    if commands::reply_sequence(reply) == Some(seq) {
+                        if until_complete && commands::is_ack(self::payload(reply)) {
+                            self.lock_completions().insert(seq, None);
+                        }
+                        return Ok(Some(reply.to_vec()));
+                    }
+                    log::debug!(
+                        "Discarding VISCA reply for sequence {:?} while waiting for {}",
+                        commands::reply_sequence(reply),
+                        seq
+                    );
+                }
+                Ok(Err(e)) => return Err(PtzError::CommandFailed(e.to_string())),
+                Err(_) => return Err(PtzError::Timeout("VISCA response timeout".to_string())),
+            }
+        }
+    }
 
-        match timeout.await {
-            Ok(Ok(len)) => Ok(buf[..len].to_vec()),
-            Ok(Err(e)) => Err(PtzError::CommandFailed(e.to_string())),
-            Err(_) => Err(PtzError::Timeout("VISCA response timeout".to_string())),
+    /// Current pan and tilt in raw VISCA units.
+    async fn raw_pan_tilt(&self) -> Result<(i16, i16), PtzError> {
+        let response = self
+            .send_command(&commands::pan_tilt_position_inquiry())
+            .await?;
+        commands::parse_pan_tilt_response(payload(&response)).ok_or(PtzError::ProtocolError(
+            "Invalid pan/tilt inquiry response".into(),
+        ))
+    }
+
+    /// Current normalized pan and tilt.
+    async fn pan_tilt(&self, calibration: &ViscaCalibration) -> Result<(f64, f64), PtzError> {
+        let (visca_pan, visca_tilt) = self.raw_pan_tilt().await?;
+        Ok((
+            commands::visca_pan_to_normalized(visca_pan, calibration),
+            commands::visca_tilt_to_normalized(visca_tilt, calibration),
+        ))
+    }
+}
+
+/// Strip the 8-byte VISCA-over-IP header to get the VISCA payload.
+fn payload(response: &[u8]) -> &[u8] {
+    if response.len() > 8 {
+        &response[8..]
+    } else {
+        response
+    }
+}
+
+/// Watch a continuous move and stop the camera once an axis reaches its limit.
+async fn guard_limits(
+    link: Arc<ViscaLink>,
+    limits: PtzLimits,
+    calibration: ViscaCalibration,
+    pan_speed: f64,
+    tilt_speed: f64,
+    mut cancel: watch::Receiver<bool>,
+) {
+    loop {
+        match tokio::time::timeout(LIMIT_CHECK_INTERVAL, cancel.changed()).await {
+            Ok(Ok(())) if *cancel.borrow_and_update() => return,
+            Ok(Ok(())) => continue,
+            Ok(Err(_)) => return,
+            Err(_) => {}
+        }
+        let (pan, tilt) = match link.pan_tilt(&calibration).await {
+            Ok(position) => position,
+            Err(e) => {
+                log::debug!("VISCA limit check failed: {}", e);
+                continue;
+            }
+        };
+        if limits.limit_velocity(pan, tilt, pan_speed, tilt_speed) != (pan_speed, tilt_speed) {
+            log::info!("VISCA soft limit reached at pan={}, tilt={}", pan, tilt);
+            if let Err(e) = link.send_command(&commands::pan_tilt_stop()).await {
+                log::warn!("Failed to stop at soft limit: {}", e);
+            }
+            return;
         }
     }
 }
 
+/// VISCA-over-IP client for Sony and compatible PTZ cameras.
+pub struct ViscaClient {
+    link: Arc<ViscaLink>,
+    limits: PtzLimits,
+    tally_opcode: [u8; 3],
+    gamma_opcode: [u8; 2],
+    picture_profile_opcode: [u8; 3],
+    digital_zoom_opcode: [u8; 2],
+    zoom_range: commands::ZoomRange,
+    calibration: ViscaCalibration,
+    /// Version inquiry reply, fetched on first `identify`.
+    info: OnceCell<Option<CameraInfo>>,
+    /// Stops the limit guard of the running continuous move.
+    limit_guard: Mutex<Option<watch::Sender<bool>>>,
+    /// Hold back zoom in absolute moves until pan/tilt has completed.
+    wait_for_completion: bool,
+    /// Cuts short the timed burst of a relative move.
+    cancel: CancelToken,
+    absolute_strategy: AbsoluteStrategy,
+    /// Where the camera was when each native preset slot was stored.
+    native_presets: std::sync::Mutex<BTreeMap<u8, PtzPosition>>,
+}
+
+impl ViscaClient {
+    pub fn new(host: &str, port: u16) -> Result<Self, PtzError> {
+        crate::ptz::controller::validate_address(host, port)?;
+        Ok(Self {
+            link: Arc::new(ViscaLink::new(host, port, None)),
+            limits: PtzLimits::default(),
+            tally_opcode: commands::TALLY_OPCODE,
+            gamma_opcode: commands::GAMMA_OPCODE,
+            picture_profile_opcode: commands::PICTURE_PROFILE_OPCODE,
+            digital_zoom_opcode: commands::DIGITAL_ZOOM_OPCODE,
+            zoom_range: commands::ZoomRange::default(),
+            calibration: ViscaCalibration::default(),
+            info: OnceCell::new(),
+            limit_guard: Mutex::new(None),
+            wait_for_completion: false,
+            cancel: CancelToken::new(),
+            absolute_strategy: AbsoluteStrategy::default(),
+            native_presets: std::sync::Mutex::new(BTreeMap::new()),
+        })
+    }
+
+    /// Send from this local address, pinning the network interface.
+    pub fn with_bind_address(mut self, address: Option<IpAddr>) -> Self {
+        self.link = Arc::new(ViscaLink::new(&self.link.host, self.link.port, address));
+        self
+    }
+
+    /// Keep pan and tilt within software limits.
+    pub fn with_limits(mut self, limits: PtzLimits) -> Self {
+        self.limits = limits;
+        self
+    }
+
+    /// Use a model-specific tally lamp opcode.
+    pub fn with_tally_opcode(mut self, opcode: [u8; 3]) -> Self {
+        self.tally_opcode = opcode;
+        self
+    }
+
+    /// Use a model-specific gamma select opcode.
+    pub fn with_gamma_opcode(mut self, opcode: [u8; 2]) -> Self {
+        self.gamma_opcode = opcode;
+        self
+    }
+
+    /// Use a model-specific picture profile recall opcode.
+    pub fn with_picture_profile_opcode(mut self, opcode: [u8; 3]) -> Self {
+        self.picture_profile_opcode = opcode;
+        self
+    }
+
+    /// Use a model-specific digital zoom on/off opcode.
+    pub fn with_digital_zoom_opcode(mut self, opcode: [u8; 2]) -> Self {
+        self.digital_zoom_opcode = opcode;
+        self
+    }
+
+    /// Map normalized zoom onto a model-specific full-tele position.
+    pub fn with_zoom_max(mut self, zoom_max: u16) -> Self {
+        self.zoom_range.optical_max = zoom_max.max(1);
+        self
+    }
+
+    /// Map the top of the normalized zoom range past optical tele, up to
+    /// `digital_max`. `None` keeps zoom optical-only.
+    pub fn with_digital_zoom_max(mut self, digital_max: Option<u16>) -> Self {
+        self.zoom_range.digital_max = digital_max;
+        self
+    }
+
+    /// Map normalized pan/tilt onto a model-specific raw range.
+    pub fn with_calibration(mut self, calibration: ViscaCalibration) -> Self {
+        self.calibration = calibration;
+        self
+    }
+
+    /// Wait for pan/tilt to complete before sending zoom in absolute moves.
+    /// Slower, but slow cameras otherwise drop the zoom mid-slew. Off by
+    /// default, sending both at once.
+    pub fn with_wait_for_completion(mut self, wait: bool) -> Self {
+        self.wait_for_completion = wait;
+        self
+    }
+
+    /// Choose how absolute moves reach their target. With
+    /// `NearestNativePreset`, a move recalls the closest known slot within
+    /// [`NATIVE_PRESET_TOLERANCE`], and fails when none is that close, since
+    /// the camera would ignore an absolute move.
+    pub fn with_absolute_strategy(mut self, strategy: AbsoluteStrategy) -> Self {
+        self.absolute_strategy = strategy;
+        self
+    }
+
+    fn lock_native_presets(&self) -> std::sync::MutexGuard<'_, BTreeMap<u8, PtzPosition>> {
+        self.native_presets
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+    }
+
+    /// Infer the camera's raw pan/tilt range by driving to the down-left and
+    /// up-right extremes and reading back where it stopped. Soft limits are
+    /// ignored. The camera is left at the middle of the measured range.
+    pub async fn auto_calibrate(&self) -> Result<ViscaCalibration, PtzError> {
+        self.cancel_limit_guard().await;
+        let (pan_min, tilt_min) = self.drive_to_extreme(-1.0, -1.0).await?;
+        let (pan_max, tilt_max) = self.drive_to_extreme(1.0, 1.0).await?;
+        let calibration = ViscaCalibration {
+            pan_min,
+            pan_max,
+            tilt_min,
+            tilt_max,
+        };
+        calibration.validate().map_err(PtzError::ProtocolError)?;
+
+        let center = |min: i16, max: i16| ((min as i32 + max as i32) / 2) as i16;
+        let cmd = commands::pan_tilt_absolute(
+            0x0C,
+            0x0C,
+            center(pan_min, pan_max),
+            center(tilt_min, tilt_max),
+        );
+        self.send_command(&cmd).await?;
+        log::info!("VISCA calibration measured {:?}", calibration);
+        Ok(calibration)
+    }
+
+    /// Drive at full speed until the raw position stops changing, then stop
+    /// and return where the camera came to rest.
+    async fn drive_to_extreme(&self, pan: f64, tilt: f64) -> Result<(i16, i16), PtzError> {
+        self.drive(pan, tilt).await?;
+        let mut last = self.link.raw_pan_tilt().await?;
+        for _ in 0..CALIBRATION_MAX_POLLS {
+            tokio::time::sleep(CALIBRATION_POLL_INTERVAL).await;
+            let position = self.link.raw_pan_tilt().await?;
+            if position == last {
+                self.send_command(&commands::pan_tilt_stop()).await?;
+                return Ok(position);
+            }
+            last = position;
+        }
+        self.send_command(&commands::pan_tilt_stop()).await?;
+        Err(PtzError::Timeout(
+            "Camera did not reach its pan/tilt limits during calibration".into(),
+        ))
+    }
+
+    #[cfg(test)]
+    fn target_addr(&self) -> String {
+        self.link.target_addr()
+    }
+
+    async fn ensure_connected(&self) -> Result<(), PtzError> {
+        self.link.ensure_connected().await
+    }
+
+    async fn send_command(&self, payload: &[u8]) -> Result<Vec<u8>, PtzError> {
+        self.link.send_command(payload).await
+    }
+
+    /// Stop watching the previous continuous move.
+    async fn cancel_limit_guard(&self) {
+        if let Some(cancel) = self.limit_guard.lock().await.take() {
+            let _ = cancel.send(true);
+        }
+    }
+
+    /// Pan/tilt and zoom payloads of an absolute move, within soft limits.
+    fn move_absolute_payloads(&self, pan: f64, tilt: f64, zoom: f64) -> (Vec<u8>, Vec<u8>) {
+        let (pan, tilt) = self.limits.clamp(pan, tilt);
+        let visca_pan = commands::normalize_to_visca_pan(pan, &self.calibration);
+        let visca_tilt = commands::normalize_to_visca_tilt(tilt, &self.calibration);
+        let visca_zoom = self.zoom_range.to_raw(zoom);
+        (
+            commands::pan_tilt_absolute(0x0C, 0x0C, visca_pan, visca_tilt),
+            commands::zoom_absolute(visca_zoom),
+        )
+    }
+
+    fn zoom_to_payload(&self, zoom: f64) -> Vec<u8> {
+        commands::zoom_absolute(self.zoom_range.to_raw(zoom))
+    }
+
+    /// Start a pan/tilt drive at the given normalized speeds.
+    async fn drive(&self, pan_speed: f64, tilt_speed: f64) -> Result<(), PtzError> {
+        let ps = ((pan_speed.abs() * 24.0).ceil() as u8).clamp(1, 24);
+        let ts = ((tilt_speed.abs() * 23.0).ceil() as u8).clamp(1, 23);
+        let pd = if pan_speed < -0.01 {
+            0x01
+        } else if pan_speed > 0.01 {
+            0x02
+        } else {
+            0x03
+        };
+        let td = if tilt_speed > 0.01 {
+            0x01
+        } else if tilt_speed < -0.01 {
+            0x02
+        } else {
+            0x03
+        };
+        let cmd = commands::pan_tilt_relative(ps, ts, pd, td);
+        self.send_command(&cmd).await?;
+        Ok(())
+    }
+}
+
 #[async_trait]
 impl PtzController for ViscaClient {
     async fn move_absolute(&self, pan: f64, tilt: f64, zoom: f64) -> Result<(), PtzError> {
-        let visca_pan = commands::normalize_to_visca_pan(pan);
-        let visca_tilt = commands::normalize_to_visca_tilt(tilt);
-        let visca_zoom = commands::normalize_to_visca_zoom(zoom);
-
-        let pt_cmd = commands::pan_tilt_absolute(0x0C, 0x0C, visca_pan, visca_tilt);
-        self.send_command(&pt_cmd).await?;
-
-        let zoom_cmd = commands::zoom_absolute(visca_zoom);
+        self.cancel_limit_guard().await;
+        if self.absolute_strategy == AbsoluteStrategy::NearestNativePreset {
+            let target = PtzPosition { pan, tilt, zoom };
+            let slot = nearest_native_preset(
+                &self.lock_native_presets(),
+                &target,
+                NATIVE_PRESET_TOLERANCE,
+            );
+            return match slot {
+                Some(slot) => self.recall_preset(slot).await,
+                None => Err(PtzError::CommandFailed(format!(
+                    "No native preset is within {} of pan={:.2}, tilt={:.2}, zoom={:.2}: store one near the target first",
+                    NATIVE_PRESET_TOLERANCE, pan, tilt, zoom
+                ))),
+            };
+        }
+        let (pt_cmd, zoom_cmd) = self.move_absolute_payloads(pan, tilt, zoom);
+        if self.wait_for_completion {
+            let completed = self
+                .link
+                .send_command_until_complete(&pt_cmd, &self.cancel)
+                .await?;
+            if completed.is_none() {
+                log::debug!("Absolute move interrupted before zoom was sent");
+                return Ok(());
+            }
+        } else {
+            self.send_command(&pt_cmd).await?;
+        }
         self.send_command(&zoom_cmd).await?;
 
         Ok(())
@@ -85,6 +644,21 @@ impl PtzController for ViscaClient {
             return Ok(());
         }
 
+        // With limits, move to the clamped target instead of nudging blindly
+        if !self.limits.is_full_range() {
+            self.cancel_limit_guard().await;
+            let (pan, tilt) = self.link.pan_tilt(&self.calibration).await?;
+            let (pan, tilt) = self.limits.clamp(pan + pan_delta, tilt + tilt_delta);
+            let cmd = commands::pan_tilt_absolute(
+                0x0C,
+                0x0C,
+                commands::normalize_to_visca_pan(pan, &self.calibration),
+                commands::normalize_to_visca_tilt(tilt, &self.calibration),
+            );
+            self.send_command(&cmd).await?;
+            return Ok(());
+        }
+
         // Determine direction and speed from delta magnitudes
         let pan_speed = ((pan_delta.abs() * 24.0).ceil() as u8).clamp(1, 24);
         let tilt_speed = ((tilt_delta.abs() * 23.0).ceil() as u8).clamp(1, 23);
@@ -108,8 +682,14 @@ impl PtzController for ViscaClient {
         let cmd = commands::pan_tilt_relative(pan_speed, tilt_speed, pan_dir, tilt_dir);
         self.send_command(&cmd).await?;
 
-        // Brief movement then stop
-        tokio::time::sleep(std::time::Duration::from_millis(200)).await;
+        // Brief movement then stop, sooner if a newer command arrives
+        if !self
+            .cancel
+            .sleep(nudge::dwell_for(pan_delta, tilt_delta))
+            .await
+        {
+            log::debug!("Relative move cut short by a newer command");
+        }
         let stop_cmd = commands::pan_tilt_stop();
         self.send_command(&stop_cmd).await?;
 
@@ -117,12 +697,90 @@ impl PtzController for ViscaClient {
     }
 
     async fn zoom_to(&self, zoom: f64) -> Result<(), PtzError> {
-        let visca_zoom = commands::normalize_to_visca_zoom(zoom);
-        let cmd = commands::zoom_absolute(visca_zoom);
-        self.send_command(&cmd).await?;
+        self.send_command(&self.zoom_to_payload(zoom)).await?;
+        Ok(())
+    }
+
+    async fn focus_to(&self, position: f64) -> Result<(), PtzError> {
+        let visca_focus = commands::normalize_to_visca_focus(position);
+        self.send_command(&commands::focus_direct(visca_focus))
+            .await?;
         Ok(())
     }
 
+    /// Polls the zoom position, without holding the socket in between, until
+    /// it reaches `zoom` or stops moving, then sends focus direct. A stop or
+    /// newer command ends the wait without focusing.
+    async fn focus_after_zoom(&self, zoom: f64, focus: f64) -> Result<(), PtzError> {
+        let target = self.zoom_range.to_raw(zoom);
+        let deadline = tokio::time::Instant::now() + timeout::or_default(COMPLETION_TIMEOUT);
+        let mut last = None;
+        let mut unchanged = 0;
+        loop {
+            let response = self
+                .send_command(&commands::zoom_position_inquiry())
+                .await?;
+            let raw = commands::parse_zoom_response(payload(&response)).ok_or(
+                PtzError::ProtocolError("Invalid zoom inquiry response".into()),
+            )?;
+            if raw == target {
+                break;
+            }
+            // A native preset recall may settle short of the preset's zoom
+            unchanged = if last == Some(raw) { unchanged + 1 } else { 0 };
+            if unchanged >= 2 {
+                break;
+            }
+            last = Some(raw);
+            if tokio::time::Instant::now() >= deadline {
+                log::warn!("VISCA zoom did not settle, focusing anyway");
+                break;
+            }
+            if !self.cancel.sleep(ZOOM_SETTLE_INTERVAL).await {
+                log::debug!("Focus after zoom interrupted");
+                return Ok(());
+            }
+        }
+        self.focus_to(focus).await
+    }
+
+    async fn get_focus(&self) -> Result<f64, PtzError> {
+        let response = self
+            .send_command(&commands::focus_position_inquiry())
+            .await?;
+        let visca_focus = commands::parse_focus_response(payload(&response)).ok_or(
+            PtzError::ProtocolError("Invalid focus inquiry response".into()),
+        )?;
+        Ok(commands::visca_focus_to_normalized(visca_focus))
+    }
+
+    /// Cameras without the preset inquiry answer with an error: no preset.
+    async fn get_active_preset(&self) -> Result<Option<u8>, PtzError> {
+        let response = self.send_command(&commands::preset_inquiry()).await?;
+        let reply = payload(&response);
+        if commands::reply_error(reply).is_some() {
+            return Ok(None);
+        }
+        commands::parse_preset_response(reply)
+            .map(Some)
+            .ok_or(PtzError::ProtocolError(
+                "Invalid preset inquiry response".into(),
+            ))
+    }
+
+    fn encode(&self, command: &PtzCommand) -> Result<Vec<EncodedRequest>, PtzError> {
+        let payloads = match *command {
+            PtzCommand::MoveAbsolute { pan, tilt, zoom } => {
+                let (pt_cmd, zoom_cmd) = self.move_absolute_payloads(pan, tilt, zoom);
+                vec![pt_cmd, zoom_cmd]
+            }
+            PtzCommand::ZoomTo { zoom } => vec![self.zoom_to_payload(zoom)],
+            PtzCommand::RecallPreset { index } => vec![commands::preset_recall(index)],
+            PtzCommand::StorePreset { index } => vec![commands::preset_store(index)],
+        };
+        Ok(payloads.iter().map(|p| EncodedRequest::bytes(p)).collect())
+    }
+
     async fn recall_preset(&self, preset_index: u8) -> Result<(), PtzError> {
         let cmd = commands::preset_recall(preset_index);
         self.send_command(&cmd).await?;
@@ -132,6 +790,18 @@ impl PtzController for ViscaClient {
     async fn store_preset(&self, preset_index: u8) -> Result<(), PtzError> {
         let cmd = commands::preset_store(preset_index);
         self.send_command(&cmd).await?;
+        if self.absolute_strategy == AbsoluteStrategy::NearestNativePreset {
+            match self.get_position().await {
+                Ok(position) => {
+                    self.lock_native_presets().insert(preset_index, position);
+                }
+                Err(e) => log::warn!(
+                    "Could not read the position of preset {}: {}",
+                    preset_index,
+                    e
+                ),
+            }
+        }
         Ok(())
     }
 
@@ -143,17 +813,8 @@ impl PtzController for ViscaClient {
             .send_command(&commands::zoom_position_inquiry())
             .await?;
 
-        // Strip 8-byte VISCA-over-IP header to get the VISCA payload
-        let pt_payload = if pt_response.len() > 8 {
-            &pt_response[8..]
-        } else {
-            &pt_response
-        };
-        let z_payload = if zoom_response.len() > 8 {
-            &zoom_response[8..]
-        } else {
-            &zoom_response
-        };
+        let pt_payload = payload(&pt_response);
+        let z_payload = payload(&zoom_response);
 
         let (visca_pan, visca_tilt) = commands::parse_pan_tilt_response(pt_payload).ok_or(
             PtzError::ProtocolError("Invalid pan/tilt inquiry response".into()),
@@ -163,9 +824,9 @@ impl PtzController for ViscaClient {
         )?;
 
         Ok(PtzPosition {
-            pan: commands::visca_pan_to_normalized(visca_pan),
-            tilt: commands::visca_tilt_to_normalized(visca_tilt),
-            zoom: commands::visca_zoom_to_normalized(visca_zoom),
+            pan: commands::visca_pan_to_normalized(visca_pan, &self.calibration),
+            tilt: commands::visca_tilt_to_normalized(visca_tilt, &self.calibration),
+            zoom: self.zoom_range.to_normalized(visca_zoom),
         })
     }
 
@@ -182,31 +843,36 @@ impl PtzController for ViscaClient {
     }
 
     async fn continuous_move(&self, pan_speed: f64, tilt_speed: f64) -> Result<(), PtzError> {
+        self.cancel_limit_guard().await;
+        let (mut pan_speed, mut tilt_speed) = (pan_speed, tilt_speed);
+        if !self.limits.is_full_range() {
+            // Don't start driving an axis that is already at its limit
+            let (pan, tilt) = self.link.pan_tilt(&self.calibration).await?;
+            (pan_speed, tilt_speed) = self.limits.limit_velocity(pan, tilt, pan_speed, tilt_speed);
+        }
         if pan_speed.abs() < 0.01 && tilt_speed.abs() < 0.01 {
             return self.stop().await;
         }
-        let ps = ((pan_speed.abs() * 24.0).ceil() as u8).clamp(1, 24);
-        let ts = ((tilt_speed.abs() * 23.0).ceil() as u8).clamp(1, 23);
-        let pd = if pan_speed < -0.01 {
-            0x01
-        } else if pan_speed > 0.01 {
-            0x02
-        } else {
-            0x03
-        };
-        let td = if tilt_speed > 0.01 {
-            0x01
-        } else if tilt_speed < -0.01 {
-            0x02
-        } else {
-            0x03
-        };
-        let cmd = commands::pan_tilt_relative(ps, ts, pd, td);
-        self.send_command(&cmd).await?;
+        self.drive(pan_speed, tilt_speed).await?;
+
+        if !self.limits.is_full_range() {
+            let (cancel_tx, cancel_rx) = watch::channel(false);
+            tokio::spawn(guard_limits(
+                self.link.clone(),
+                self.limits,
+                self.calibration,
+                pan_speed,
+                tilt_speed,
+                cancel_rx,
+            ));
+            *self.limit_guard.lock().await = Some(cancel_tx);
+        }
         Ok(())
     }
 
     async fn stop(&self) -> Result<(), PtzError> {
+        self.cancel.cancel();
+        self.cancel_limit_guard().await;
         self.send_command(&commands::pan_tilt_stop()).await?;
         Ok(())
     }
@@ -242,4 +908,1231 @@ impl PtzController for ViscaClient {
         self.send_command(&commands::focus_stop()).await?;
         Ok(())
     }
+
+    async fn set_image_flip(&self, enabled: bool) -> Result<(), PtzError> {
+        self.send_command(&commands::picture_flip(enabled)).await?;
+        Ok(())
+    }
+
+    async fn set_mirror(&self, enabled: bool) -> Result<(), PtzError> {
+        self.send_command(&commands::lr_reverse(enabled)).await?;
+        Ok(())
+    }
+
+    async fn set_digital_zoom(&self, enabled: bool) -> Result<(), PtzError> {
+        self.send_command(&commands::digital_zoom(self.digital_zoom_opcode, enabled))
+            .await?;
+        Ok(())
+    }
+
+    async fn set_freeze(&self, enabled: bool) -> Result<(), PtzError> {
+        self.send_command(&commands::freeze(enabled)).await?;
+        Ok(())
+    }
+
+    /// Unlike `move_absolute`, always waits for pan/tilt and then zoom to
+    /// report completion, so the unfreeze lands on the settled shot. A stop
+    /// or newer command cuts the wait short and unfreezes at once.
+    async fn reposition_frozen(&self, pan: f64, tilt: f64, zoom: f64) -> Result<(), PtzError> {
+        self.cancel_limit_guard().await;
+        self.set_freeze(true).await?;
+        let (pt_cmd, zoom_cmd) = self.move_absolute_payloads(pan, tilt, zoom);
+        let moved = match self
+            .link
+            .send_command_until_complete(&pt_cmd, &self.cancel)
+            .await
+        {
+            Ok(Some(_)) => {
+                self.link
+                    .send_command_until_complete(&zoom_cmd, &self.cancel)
+                    .await
+            }
+            other => other,
+        };
+        if matches!(moved, Ok(None)) {
+            log::debug!("Frozen reposition interrupted, unfreezing");
+        }
+        self.set_freeze(false).await?;
+        moved.map(|_| ())
+    }
+
+    async fn set_white_balance(&self, setting: WhiteBalanceSetting) -> Result<(), PtzError> {
+        self.send_command(&commands::white_balance_mode(setting))
+            .await?;
+        if let WhiteBalanceSetting::Manual {
+            red_gain,
+            blue_gain,
+        } = setting
+        {
+            self.send_command(&commands::red_gain_direct(red_gain))
+                .await?;
+            self.send_command(&commands::blue_gain_direct(blue_gain))
+                .await?;
+        }
+        Ok(())
+    }
+
+    async fn menu_toggle(&self) -> Result<(), PtzError> {
+        self.send_command(&commands::menu_toggle()).await?;
+        Ok(())
+    }
+
+    async fn menu_navigate(&self, direction: MenuDirection) -> Result<(), PtzError> {
+        self.send_command(&commands::menu_navigate(direction))
+            .await?;
+        Ok(())
+    }
+
+    async fn menu_enter(&self) -> Result<(), PtzError> {
+        self.send_command(&commands::menu_enter()).await?;
+        Ok(())
+    }
+
+    async fn set_tally(&self, state: TallyState) -> Result<(), PtzError> {
+        self.send_command(&commands::tally(self.tally_opcode, state))
+            .await?;
+        Ok(())
+    }
+
+    async fn set_gamma(&self, mode: u8) -> Result<(), PtzError> {
+        if mode > commands::MAX_GAMMA_MODE {
+            return Err(PtzError::CommandFailed(format!(
+                "Gamma mode must be 0 to {}",
+                commands::MAX_GAMMA_MODE
+            )));
+        }
+        self.send_command(&commands::gamma(self.gamma_opcode, mode))
+            .await?;
+        Ok(())
+    }
+
+    async fn recall_picture_profile(&self, index: u8) -> Result<(), PtzError> {
+        if index > commands::MAX_PICTURE_PROFILE {
+            return Err(PtzError::CommandFailed(format!(
+                "Picture profile must be 0 to {}",
+                commands::MAX_PICTURE_PROFILE
+            )));
+        }
+        self.send_command(&commands::picture_profile(
+            self.picture_profile_opcode,
+            index,
+        ))
+        .await?;
+        Ok(())
+    }
+
+    async fn visca_diagnostics(&self) -> Result<ViscaDiagnostics, PtzError> {
+        Ok(self.link.diagnostics().await)
+    }
+
+    async fn reset_sequence(&self) -> Result<(), PtzError> {
+        self.link.force_sequence_reset().await
+    }
+
+    async fn send_raw(&self, message: &[u8]) -> Result<Vec<u8>, PtzError> {
+        commands::validate_raw(message).map_err(PtzError::CommandFailed)?;
+        let response = self.send_command(message).await?;
+        Ok(payload(&response).to_vec())
+    }
+
+    async fn identify(&self) -> Result<Option<CameraInfo>, PtzError> {
+        let info = self
+            .info
+            .get_or_try_init(|| async {
+                let response = self.send_command(&commands::version_inquiry()).await?;
+                let version = commands::parse_version_response(payload(&response)).ok_or(
+                    PtzError::ProtocolError("Invalid version inquiry response".into()),
+                )?;
+                Ok::<_, PtzError>(Some(CameraInfo {
+                    vendor: version.vendor_name(),
+                    model: format!("{:04X}", version.model_id),
+                    firmware: Some(format!("{:04X}", version.rom_version)),
+                }))
+            })
+            .await?;
+        Ok(info.clone())
+    }
+
+    fn capabilities(&self) -> Capabilities {
+        Capabilities {
+            supports_absolute: true,
+            supports_position: true,
+            supports_continuous: true,
+            supports_focus: true,
+            supports_focus_position: true,
+            supports_white_balance: true,
+            supports_image_flip: true,
+            supports_menu: true,
+            supports_tally: true,
+            supports_picture_profile: true,
+            supports_native_presets: true,
+            max_preset_slots: 128,
+            ..Capabilities::default()
+        }
+    }
+
+    fn cancel_token(&self) -> Option<CancelToken> {
+        Some(self.cancel.clone())
+    }
+
+    fn remember_native_preset(&self, slot: u8, position: PtzPosition) {
+        self.lock_native_presets().entry(slot).or_insert(position);
+    }
+}
+
+/// How far, in normalized units across pan, tilt and zoom, a native preset
+/// may be from an absolute move's target and still stand in for it.
+pub const NATIVE_PRESET_TOLERANCE: f64 = 0.1;
+
+/// The stored slot closest to `target`, by straight-line distance across
+/// normalized pan, tilt and zoom, if it is within `max_distance`. Ties go
+/// to the lowest slot.
+pub fn nearest_native_preset(
+    presets: &BTreeMap<u8, PtzPosition>,
+    target: &PtzPosition,
+    max_distance: f64,
+) -> Option<u8> {
+    let distance = |p: &PtzPosition| {
+        ((p.pan - target.pan).powi(2)
+            + (p.tilt - target.tilt).powi(2)
+            + (p.zoom - target.zoom).powi(2))
+        .sqrt()
+    };
+    presets
+        .iter()
+        .filter(|(_, p)| distance(p) <= max_distance)
+        .min_by(|(_, a), (_, b)| distance(a).total_cmp(&distance(b)))
+        .map(|(slot, _)| *slot)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ptz::controller::PtzDispatcher;
+    use std::sync::Arc;
+
+    #[test]
+    fn ipv6_host_is_bracketed_in_target_addr() {
+        let client = ViscaClient::new("::1", 52381).unwrap();
+        assert_eq!(client.target_addr(), "[::1]:52381");
+        assert!(client.target_addr().parse::<std::net::SocketAddr>().is_ok());
+        let client = ViscaClient::new("192.168.1.20", 52381).unwrap();
+        assert_eq!(client.target_addr(), "192.168.1.20:52381");
+    }
+
+    #[test]
+    fn invalid_host_or_port_is_a_config_error() {
+        for (host, port) in [("cam;reboot", 52381), ("192.168.1.20", 0)] {
+            assert!(matches!(
+                ViscaClient::new(host, port).err(),
+                Some(PtzError::InvalidConfig(_))
+            ));
+        }
+    }
+
+    fn is_control(packet: &[u8]) -> bool {
+        commands::payload_type(packet) == Some(commands::ViscaIpHeader::CONTROL)
+    }
+
+    /// Mock camera that answers every packet with a stale reply (wrong
+    /// sequence) followed by a reply echoing the sequence and the command's
+    /// first payload byte after a short, varying delay.
+    async fn spawn_mock_camera() -> u16 {
+        let socket = UdpSocket::bind("127.0.0.1:0").await.unwrap();
+        let port = socket.local_addr().unwrap().port();
+        tokio::spawn(async move {
+            let mut buf = [0u8; 256];
+            loop {
+                let Ok((len, peer)) = socket.recv_from(&mut buf).await else {
+                    break;
+                };
+                if is_control(&buf[..len]) {
+                    continue;
+                }
+                let seq = commands::reply_sequence(&buf[..len]).unwrap();
+                let marker = buf[8];
+
+                let stale =
+                    commands::build_visca_packet(&[0x90, 0x51, 0xFF], seq.wrapping_add(1000));
+                let _ = socket.send_to(&stale, peer).await;
+                tokio::time::sleep(Duration::from_millis((seq % 3) as u64)).await;
+                let reply = commands::build_visca_packet(&[0x90, 0x50, marker, 0xFF], seq);
+                let _ = socket.send_to(&reply, peer).await;
+            }
+        });
+        port
+    }
+
+    #[tokio::test]
+    async fn reset_sets_the_sequence_back_to_one() {
+        let port = spawn_mock_camera().await;
+        let client = ViscaClient::new("127.0.0.1", port).unwrap();
+        let before = client.visca_diagnostics().await.unwrap();
+        assert_eq!((before.sequence, before.connected), (1, false));
+        assert_eq!(before.last_reply_hex, None);
+
+        client.send_command(&[0x01, 0xFF]).await.unwrap();
+        client.send_command(&[0x02, 0xFF]).await.unwrap();
+        let busy = client.visca_diagnostics().await.unwrap();
+        assert_eq!((busy.sequence, busy.connected), (3, true));
+        // Sequence 2 in the header, then the reply to the second command
+        let last_reply = busy.last_reply_hex.unwrap();
+        assert!(
+            last_reply.ends_with("00 00 00 02 90 50 02 FF"),
+            "{}",
+            last_reply
+        );
+
+        client.reset_sequence().await.unwrap();
+        assert_eq!(client.visca_diagnostics().await.unwrap().sequence, 1);
+        let reply = client.send_command(&[0x03, 0xFF]).await.unwrap();
+        assert_eq!(commands::reply_sequence(&reply), Some(1));
+    }
+
+    #[tokio::test]
+    async fn concurrent_commands_get_their_own_replies() {
+        let port = spawn_mock_camera().await;
+        let client = Arc::new(ViscaClient::new("127.0.0.1", port).unwrap());
+
+        let tasks: Vec<_> = (0..50u8)
+            .map(|marker| {
+                let client = client.clone();
+                tokio::spawn(async move {
+                    let reply = client.send_command(&[marker, 0xFF]).await.unwrap();
+                    (marker, reply)
+                })
+            })
+            .collect();
+
+        for task in tasks {
+            let (marker, reply) = task.await.unwrap();
+            assert_eq!(reply[8..], [0x90, 0x50, marker, 0xFF]);
+        }
+    }
+
+    #[tokio::test]
+    async fn reply_sequence_matches_request() {
+        let port = spawn_mock_camera().await;
+        let client = ViscaClient::new("127.0.0.1", port).unwrap();
+
+        let first = client.send_command(&[0x01, 0xFF]).await.unwrap();
+        let second = client.send_command(&[0x02, 0xFF]).await.unwrap();
+        assert_eq!(commands::reply_sequence(&first), Some(1));
+        assert_eq!(commands::reply_sequence(&second), Some(2));
+    }
+
+    /// Mock camera that answers every command, but only after `delay`.
+    async fn spawn_slow_camera(delay: Duration) -> u16 {
+        let socket = Arc::new(UdpSocket::bind("127.0.0.1:0").await.unwrap());
+        let port = socket.local_addr().unwrap().port();
+        tokio::spawn(async move {
+            let mut buf = [0u8; 256];
+            loop {
+                let Ok((len, peer)) = socket.recv_from(&mut buf).await else {
+                    break;
+                };
+                if is_control(&buf[..len]) {
+                    continue;
+                }
+                let seq = commands::reply_sequence(&buf[..len]).unwrap();
+                let socket = socket.clone();
+                tokio::spawn(async move {
+                    tokio::time::sleep(delay).await;
+                    let reply = commands::build_visca_packet(&[0x90, 0x50, 0xFF], seq);
+                    let _ = socket.send_to(&reply, peer).await;
+                });
+            }
+        });
+        port
+    }
+
+    #[tokio::test]
+    async fn timeout_override_reaches_the_client_through_the_dispatcher() {
+        let port = spawn_slow_camera(Duration::from_millis(200)).await;
+        let mut dispatcher = PtzDispatcher::new();
+        let client = ViscaClient::new("127.0.0.1", port).unwrap();
+        dispatcher.add_controller("cam", Box::new(client));
+        dispatcher.set_active("cam").unwrap();
+
+        let short = timeout::scoped(
+            Some(Duration::from_millis(50)),
+            dispatcher.test_connection(),
+        )
+        .await;
+        assert!(matches!(short, Err(PtzError::Timeout(_))), "{:?}", short);
+
+        // The default timeout outlasts the camera's delay
+        dispatcher.test_connection().await.unwrap();
+    }
+
+    fn at(pan: f64, tilt: f64, zoom: f64) -> PtzPosition {
+        PtzPosition { pan, tilt, zoom }
+    }
+
+    #[test]
+    fn nearest_native_preset_picks_the_closest_slot() {
+        let presets = BTreeMap::from([
+            (1, at(-0.5, 0.0, 0.0)),
+            (2, at(0.5, 0.2, 0.0)),
+            (3, at(0.5, 0.2, 1.0)),
+        ]);
+        assert_eq!(
+            nearest_native_preset(&presets, &at(-0.9, -0.1, 0.2), 1.0),
+            Some(1)
+        );
+        assert_eq!(
+            nearest_native_preset(&presets, &at(0.4, 0.1, 0.3), 1.0),
+            Some(2)
+        );
+        assert_eq!(
+            nearest_native_preset(&presets, &at(0.6, 0.3, 0.8), 1.0),
+            Some(3)
+        );
+    }
+
+    #[test]
+    fn nearest_native_preset_ignores_slots_beyond_the_tolerance() {
+        let presets = BTreeMap::from([(1, at(0.5, 0.0, 0.0))]);
+        assert_eq!(
+            nearest_native_preset(&presets, &at(0.45, 0.0, 0.0), NATIVE_PRESET_TOLERANCE),
+            Some(1)
+        );
+        assert_eq!(
+            nearest_native_preset(&presets, &at(-0.5, 0.0, 0.0), NATIVE_PRESET_TOLERANCE),
+            None
+        );
+    }
+
+    #[test]
+    fn nearest_native_preset_breaks_ties_by_lowest_slot() {
+        let presets = BTreeMap::from([(7, at(0.5, 0.0, 0.0)), (4, at(-0.5, 0.0, 0.0))]);
+        assert_eq!(
+            nearest_native_preset(&presets, &at(0.0, 0.0, 0.0), 1.0),
+            Some(4)
+        );
+        assert_eq!(
+            nearest_native_preset(&BTreeMap::new(), &at(0.0, 0.0, 0.0), 1.0),
+            None
+        );
+    }
+
+    #[tokio::test]
+    async fn nearest_preset_strategy_recalls_a_stored_slot() {
+        let (port, received) = spawn_panning_camera().await;
+        let client = ViscaClient::new("127.0.0.1", port)
+            .unwrap()
+            .with_absolute_strategy(AbsoluteStrategy::NearestNativePreset);
+
+        // Until a slot is stored there is nothing to recall, and the
+        // absolute move the camera would ignore is not sent
+        assert!(matches!(
+            client.move_absolute(0.0, 0.0, 0.0).await,
+            Err(PtzError::CommandFailed(_))
+        ));
+        assert!(received.lock().unwrap().is_empty());
+
+        client.store_preset(5).await.unwrap();
+        let stored = client.get_position().await.unwrap();
+        received.lock().unwrap().clear();
+        client
+            .move_absolute(stored.pan + 0.05, stored.tilt, stored.zoom)
+            .await
+            .unwrap();
+        assert_eq!(*received.lock().unwrap(), [commands::preset_recall(5)]);
+
+        // A slot far from the target is not used
+        received.lock().unwrap().clear();
+        let err = client.move_absolute(0.8, 0.2, 0.5).await.unwrap_err();
+        assert!(err.to_string().contains("No native preset"), "{err}");
+        assert!(received.lock().unwrap().is_empty());
+
+        // Slots seeded from saved presets are used, but don't replace
+        // positions read back from the camera
+        client.remember_native_preset(6, at(0.8, 0.2, 0.5));
+        client.remember_native_preset(5, at(0.8, 0.2, 0.5));
+        received.lock().unwrap().clear();
+        client.move_absolute(0.8, 0.2, 0.5).await.unwrap();
+        assert_eq!(*received.lock().unwrap(), [commands::preset_recall(6)]);
+    }
+
+    #[tokio::test]
+    async fn bind_address_sets_the_local_interface() {
+        let port = spawn_mock_camera().await;
+        let client = ViscaClient::new("127.0.0.1", port)
+            .unwrap()
+            .with_bind_address(Some(Ipv4Addr::LOCALHOST.into()));
+        client.ensure_connected().await.unwrap();
+
+        let socket = client.link.socket.lock().await;
+        let local = socket.as_ref().unwrap().local_addr().unwrap();
+        assert_eq!(local.ip(), IpAddr::from(Ipv4Addr::LOCALHOST));
+    }
+
+    #[tokio::test]
+    async fn bind_address_not_on_this_host_fails_to_connect() {
+        let port = spawn_mock_camera().await;
+        let client = ViscaClient::new("127.0.0.1", port)
+            .unwrap()
+            .with_bind_address(Some(Ipv4Addr::new(192, 0, 2, 1).into()));
+        assert!(matches!(
+            client.ensure_connected().await,
+            Err(PtzError::ConnectionFailed(_))
+        ));
+    }
+
+    /// Mock camera that tracks pan: absolute moves set it, and while a
+    /// rightward drive is running each position inquiry advances it by 200
+    /// VISCA units. Zoom follows direct zoom commands, and version inquiries
+    /// report a Sony camera. Every command payload is recorded.
+    async fn spawn_panning_camera() -> (u16, Arc<std::sync::Mutex<Vec<Vec<u8>>>>) {
+        let socket = UdpSocket::bind("127.0.0.1:0").await.unwrap();
+        let port = socket.local_addr().unwrap().port();
+        let received = Arc::new(std::sync::Mutex::new(Vec::new()));
+        let recorded = received.clone();
+        tokio::spawn(async move {
+            let mut buf = [0u8; 256];
+            let mut pan: i16 = 0;
+            let mut zoom: u16 = 0;
+            let mut preset: u8 = 0;
+            let mut driving = false;
+            loop {
+                let Ok((len, peer)) = socket.recv_from(&mut buf).await else {
+                    break;
+                };
+                if is_control(&buf[..len]) {
+                    continue;
+                }
+                let seq = commands::reply_sequence(&buf[..len]).unwrap();
+                let cmd = buf[8..len].to_vec();
+                recorded.lock().unwrap().push(cmd.clone());
+
+                let reply = match cmd.as_slice() {
+                    [0x81, 0x09, 0x06, 0x12, 0xFF] => {
+                        if driving {
+                            pan = pan.saturating_add(200);
+                        }
+                        let [hi, lo] = (pan as u16).to_be_bytes();
+                        vec![
+                            0x90,
+                            0x50,
+                            hi >> 4,
+                            hi & 0x0F,
+                            lo >> 4,
+                            lo & 0x0F,
+                            0,
+                            0,
+                            0,
+                            0,
+                            0xFF,
+                        ]
+                    }
+                    [0x81, 0x09, 0x00, 0x02, 0xFF] => {
+                        vec![0x90, 0x50, 0x00, 0x01, 0x05, 0x1C, 0x01, 0x20, 0x02, 0xFF]
+                    }
+                    [0x81, 0x09, 0x04, 0x3F, 0xFF] => vec![0x90, 0x50, preset, 0xFF],
+                    [0x81, 0x01, 0x04, 0x3F, 0x02, recalled, 0xFF] => {
+                        preset = *recalled;
+                        vec![0x90, 0x51, 0xFF]
+                    }
+                    [0x81, 0x09, 0x04, 0x47, 0xFF] => {
+                        let [hi, lo] = zoom.to_be_bytes();
+                        vec![0x90, 0x50, hi >> 4, hi & 0x0F, lo >> 4, lo & 0x0F, 0xFF]
+                    }
+                    [0x81, 0x01, 0x04, 0x47, z0, z1, z2, z3, 0xFF] => {
+                        zoom = u16::from_be_bytes([(z0 << 4) | z1, (z2 << 4) | z3]);
+                        vec![0x90, 0x51, 0xFF]
+                    }
+                    [0x81, 0x01, 0x06, 0x01, _, _, pan_dir, _, 0xFF] => {
+                        driving = *pan_dir == 0x02;
+                        vec![0x90, 0x51, 0xFF]
+                    }
+                    [0x81, 0x01, 0x06, 0x02, _, _, p0, p1, p2, p3, ..] => {
+                        pan = u16::from_be_bytes([(p0 << 4) | p1, (p2 << 4) | p3]) as i16;
+                        vec![0x90, 0x51, 0xFF]
+                    }
+                    _ => vec![0x90, 0x51, 0xFF],
+                };
+                let _ = socket
+                    .send_to(&commands::build_visca_packet(&reply, seq), peer)
+                    .await;
+            }
+        });
+        (port, received)
+    }
+
+    fn pan_limited(max: f64) -> PtzLimits {
+        PtzLimits {
+            pan_max: max,
+            ..PtzLimits::default()
+        }
+    }
+
+    #[tokio::test]
+    async fn absolute_move_beyond_limit_is_clamped() {
+        let (port, received) = spawn_panning_camera().await;
+        let client = ViscaClient::new("127.0.0.1", port)
+            .unwrap()
+            .with_limits(pan_limited(0.5));
+
+        client.move_absolute(1.0, 0.0, 0.0).await.unwrap();
+        assert_eq!(
+            received.lock().unwrap()[0],
+            commands::pan_tilt_absolute(
+                0x0C,
+                0x0C,
+                commands::normalize_to_visca_pan(0.5, &ViscaCalibration::default()),
+                commands::normalize_to_visca_tilt(0.0, &ViscaCalibration::default())
+            )
+        );
+        let (pan, _) = client
+            .link
+            .pan_tilt(&ViscaCalibration::default())
+            .await
+            .unwrap();
+        assert!((pan - 0.5).abs() < 0.01);
+    }
+
+    #[tokio::test]
+    async fn zoom_max_applies_to_moves_and_position() {
+        let (port, received) = spawn_panning_camera().await;
+        let client = ViscaClient::new("127.0.0.1", port)
+            .unwrap()
+            .with_zoom_max(0x7AC0);
+
+        client.zoom_to(1.0).await.unwrap();
+        assert_eq!(received.lock().unwrap()[0], commands::zoom_absolute(0x7AC0));
+        assert_eq!(client.get_position().await.unwrap().zoom, 1.0);
+
+        client.move_absolute(0.0, 0.0, 0.5).await.unwrap();
+        assert_eq!(received.lock().unwrap()[4], commands::zoom_absolute(0x3D60));
+        assert_eq!(client.get_position().await.unwrap().zoom, 0.5);
+    }
+
+    #[tokio::test]
+    async fn digital_zoom_max_extends_moves_and_position() {
+        let (port, received) = spawn_panning_camera().await;
+        let client = ViscaClient::new("127.0.0.1", port)
+            .unwrap()
+            .with_digital_zoom_max(Some(commands::VISCA_DIGITAL_ZOOM_MAX));
+
+        client.zoom_to(1.0).await.unwrap();
+        assert_eq!(
+            received.lock().unwrap()[0],
+            commands::zoom_absolute(commands::VISCA_DIGITAL_ZOOM_MAX)
+        );
+        assert_eq!(client.get_position().await.unwrap().zoom, 1.0);
+
+        client.zoom_to(commands::OPTICAL_ZOOM_SHARE).await.unwrap();
+        assert_eq!(
+            received.lock().unwrap()[3],
+            commands::zoom_absolute(commands::VISCA_ZOOM_MAX)
+        );
+        let zoom = client.get_position().await.unwrap().zoom;
+        assert!((zoom - commands::OPTICAL_ZOOM_SHARE).abs() < 1e-9);
+    }
+
+    #[tokio::test]
+    async fn continuous_move_into_limit_stops() {
+        let (port, received) = spawn_panning_camera().await;
+        let client = ViscaClient::new("127.0.0.1", port)
+            .unwrap()
+            .with_limits(pan_limited(0.5));
+
+        client.continuous_move(1.0, 0.0).await.unwrap();
+        let stopped = tokio::time::timeout(Duration::from_secs(2), async {
+            loop {
+                if received
+                    .lock()
+                    .unwrap()
+                    .contains(&commands::pan_tilt_stop())
+                {
+                    break;
+                }
+                tokio::time::sleep(Duration::from_millis(10)).await;
+            }
+        })
+        .await;
+        assert!(stopped.is_ok(), "no stop sent at the limit");
+
+        // Already at the limit: a further move right does not drive the camera
+        received.lock().unwrap().clear();
+        client.continuous_move(1.0, 0.0).await.unwrap();
+        let sent = received.lock().unwrap().clone();
+        assert_eq!(sent.last(), Some(&commands::pan_tilt_stop()));
+    }
+
+    #[test]
+    fn capabilities_include_focus_image_and_menu() {
+        let caps = ViscaClient::new("127.0.0.1", 52381).unwrap().capabilities();
+        assert!(caps.supports_absolute && caps.supports_continuous);
+        assert!(caps.supports_focus && caps.supports_focus_position);
+        assert!(caps.supports_white_balance && caps.supports_image_flip);
+        assert!(caps.supports_menu && caps.supports_native_presets);
+        assert!(caps.supports_tally && caps.supports_picture_profile);
+        assert!(!caps.supports_exposure && !caps.supports_power);
+        assert_eq!(caps.max_preset_slots, 128);
+    }
+
+    /// Mock camera that checks sequence numbers like real firmware: `RESET`
+    /// restarts the count at 1, and any other number gets the `0F 01` control
+    /// error. Setting `expected` to 0 simulates a reboot. Every packet's
+    /// payload type and sequence number is recorded.
+    async fn spawn_strict_camera() -> (u16, Arc<AtomicU32>, Arc<std::sync::Mutex<Vec<(u16, u32)>>>)
+    {
+        let socket = UdpSocket::bind("127.0.0.1:0").await.unwrap();
+        let port = socket.local_addr().unwrap().port();
+        let expected = Arc::new(AtomicU32::new(0));
+        let received = Arc::new(std::sync::Mutex::new(Vec::new()));
+        let (next, recorded) = (expected.clone(), received.clone());
+        tokio::spawn(async move {
+            let mut buf = [0u8; 256];
+            loop {
+                let Ok((len, peer)) = socket.recv_from(&mut buf).await else {
+                    break;
+                };
+                let packet = &buf[..len];
+                let seq = commands::reply_sequence(packet).unwrap();
+                let kind = commands::payload_type(packet).unwrap();
+                recorded.lock().unwrap().push((kind, seq));
+
+                if is_control(packet) {
+                    next.store(1, Ordering::SeqCst);
+                    continue;
+                }
+                let reply = if next.load(Ordering::SeqCst) == seq {
+                    next.fetch_add(1, Ordering::SeqCst);
+                    commands::build_visca_packet(&[0x90, 0x51, 0xFF], seq)
+                } else {
+                    let mut error = commands::ViscaIpHeader {
+                        payload_type: commands::ViscaIpHeader::CONTROL_REPLY,
+                        payload_length: 2,
+                        sequence_number: seq,
+                    }
+                    .to_bytes();
+                    error.extend_from_slice(&[0x0F, 0x01]);
+                    error
+                };
+                let _ = socket.send_to(&reply, peer).await;
+            }
+        });
+        (port, expected, received)
+    }
+
+    #[tokio::test]
+    async fn reconnecting_resets_the_sequence() {
+        let (port, _, received) = spawn_strict_camera().await;
+        let client = ViscaClient::new("127.0.0.1", port).unwrap();
+
+        client.send_command(&[0x01, 0xFF]).await.unwrap();
+        client.send_command(&[0x02, 0xFF]).await.unwrap();
+        *client.link.socket.lock().await = None;
+        client.send_command(&[0x03, 0xFF]).await.unwrap();
+
+        let control = commands::ViscaIpHeader::CONTROL;
+        let command = commands::ViscaIpHeader::COMMAND;
+        assert_eq!(
+            *received.lock().unwrap(),
+            vec![
+                (control, 0),
+                (command, 1),
+                (command, 2),
+                (control, 0),
+                (command, 1)
+            ]
+        );
+    }
+
+    #[tokio::test]
+    async fn sequence_error_resets_and_resends() {
+        let (port, expected, received) = spawn_strict_camera().await;
+        let client = ViscaClient::new("127.0.0.1", port).unwrap();
+
+        client.send_command(&[0x01, 0xFF]).await.unwrap();
+        client.send_command(&[0x02, 0xFF]).await.unwrap();
+        // The camera reboots and no longer accepts our numbering
+        expected.store(0, Ordering::SeqCst);
+        let reply = client.send_command(&[0x03, 0xFF]).await.unwrap();
+        assert_eq!(commands::reply_sequence(&reply), Some(1));
+
+        let sent: Vec<u32> = received
+            .lock()
+            .unwrap()
+            .iter()
+            .map(|(_, seq)| *seq)
+            .collect();
+        assert_eq!(sent, vec![0, 1, 2, 3, 0, 1]);
+    }
+
+    /// Mock camera with hard stops at `range`: while a drive runs, each
+    /// position inquiry steps pan and tilt 300 raw units in the driven
+    /// direction. Absolute moves jump straight to the target. Every command
+    /// payload is recorded.
+    async fn spawn_bounded_camera(
+        range: ViscaCalibration,
+    ) -> (u16, Arc<std::sync::Mutex<Vec<Vec<u8>>>>) {
+        let socket = UdpSocket::bind("127.0.0.1:0").await.unwrap();
+        let port = socket.local_addr().unwrap().port();
+        let received = Arc::new(std::sync::Mutex::new(Vec::new()));
+        let recorded = received.clone();
+        tokio::spawn(async move {
+            let mut buf = [0u8; 256];
+            let (mut pan, mut tilt) = (0i16, 0i16);
+            let (mut pan_dir, mut tilt_dir) = (0i16, 0i16);
+            let nibbles = |v: i16| {
+                let [hi, lo] = (v as u16).to_be_bytes();
+                [hi >> 4, hi & 0x0F, lo >> 4, lo & 0x0F]
+            };
+            let value =
+                |n: &[u8]| u16::from_be_bytes([(n[0] << 4) | n[1], (n[2] << 4) | n[3]]) as i16;
+            loop {
+                let Ok((len, peer)) = socket.recv_from(&mut buf).await else {
+                    break;
+                };
+                if is_control(&buf[..len]) {
+                    continue;
+                }
+                let seq = commands::reply_sequence(&buf[..len]).unwrap();
+                let cmd = buf[8..len].to_vec();
+                recorded.lock().unwrap().push(cmd.clone());
+
+                let reply = match cmd.as_slice() {
+                    [0x81, 0x09, 0x06, 0x12, 0xFF] => {
+                        pan = (pan + pan_dir * 300).clamp(range.pan_min, range.pan_max);
+                        tilt = (tilt + tilt_dir * 300).clamp(range.tilt_min, range.tilt_max);
+                        let mut reply = vec![0x90, 0x50];
+                        reply.extend_from_slice(&nibbles(pan));
+                        reply.extend_from_slice(&nibbles(tilt));
+                        reply.push(0xFF);
+                        reply
+                    }
+                    [0x81, 0x09, 0x04, 0x47, 0xFF] => vec![0x90, 0x50, 0, 0, 0, 0, 0xFF],
+                    [0x81, 0x01, 0x06, 0x01, _, _, pd, td, 0xFF] => {
+                        pan_dir = match pd {
+                            0x01 => -1,
+                            0x02 => 1,
+                            _ => 0,
+                        };
+                        tilt_dir = match td {
+                            0x01 => 1,
+                            0x02 => -1,
+                            _ => 0,
+                        };
+                        vec![0x90, 0x51, 0xFF]
+                    }
+                    [0x81, 0x01, 0x06, 0x02, _, _, rest @ ..] if rest.len() == 9 => {
+                        pan = value(&rest[0..4]);
+                        tilt = value(&rest[4..8]);
+                        vec![0x90, 0x51, 0xFF]
+                    }
+                    _ => vec![0x90, 0x51, 0xFF],
+                };
+                let _ = socket
+                    .send_to(&commands::build_visca_packet(&reply, seq), peer)
+                    .await;
+            }
+        });
+        (port, received)
+    }
+
+    fn wide_range() -> ViscaCalibration {
+        ViscaCalibration {
+            pan_min: -2448,
+            pan_max: 2448,
+            tilt_min: -432,
+            tilt_max: 1296,
+        }
+    }
+
+    #[tokio::test]
+    async fn auto_calibrate_finds_the_hard_stops() {
+        let (port, received) = spawn_bounded_camera(wide_range()).await;
+        let client = ViscaClient::new("127.0.0.1", port).unwrap();
+
+        assert_eq!(client.auto_calibrate().await.unwrap(), wide_range());
+        // The camera is parked at the middle of the measured range
+        assert_eq!(
+            received.lock().unwrap().last().unwrap(),
+            &commands::pan_tilt_absolute(0x0C, 0x0C, 0, 432)
+        );
+    }
+
+    #[tokio::test]
+    async fn calibration_maps_moves_and_position() {
+        let (port, received) = spawn_bounded_camera(wide_range()).await;
+        let client = ViscaClient::new("127.0.0.1", port)
+            .unwrap()
+            .with_calibration(wide_range());
+
+        client.move_absolute(1.0, -1.0, 0.0).await.unwrap();
+        assert_eq!(
+            received.lock().unwrap()[0],
+            commands::pan_tilt_absolute(0x0C, 0x0C, 2448, -432)
+        );
+        let position = client.get_position().await.unwrap();
+        assert_eq!((position.pan, position.tilt), (1.0, -1.0));
+    }
+
+    #[tokio::test]
+    async fn raw_inquiry_returns_the_reply_payload() {
+        let (port, received) = spawn_panning_camera().await;
+        let client = ViscaClient::new("127.0.0.1", port).unwrap();
+        client.zoom_to(1.0).await.unwrap();
+
+        let reply = client
+            .send_raw(&[0x81, 0x09, 0x04, 0x47, 0xFF])
+            .await
+            .unwrap();
+        assert_eq!(reply, [0x90, 0x50, 0x04, 0x00, 0x00, 0x00, 0xFF]);
+        assert_eq!(
+            received.lock().unwrap().last().unwrap(),
+            &[0x81, 0x09, 0x04, 0x47, 0xFF]
+        );
+
+        // Malformed payloads never reach the camera
+        assert!(client.send_raw(&[0x81, 0x09]).await.is_err());
+        assert_eq!(received.lock().unwrap().len(), 2);
+    }
+
+    #[tokio::test]
+    async fn gamma_and_picture_profile_use_endpoint_opcodes() {
+        let (port, received) = spawn_panning_camera().await;
+        let client = ViscaClient::new("127.0.0.1", port)
+            .unwrap()
+            .with_gamma_opcode([0x7E, 0x51])
+            .with_picture_profile_opcode([0x04, 0x3F, 0x20]);
+
+        client.set_gamma(2).await.unwrap();
+        client.recall_picture_profile(4).await.unwrap();
+        assert_eq!(
+            *received.lock().unwrap(),
+            [
+                commands::gamma([0x7E, 0x51], 2),
+                commands::picture_profile([0x04, 0x3F, 0x20], 4)
+            ]
+        );
+
+        // Out-of-range values are rejected before reaching the camera
+        assert!(client.set_gamma(0x10).await.is_err());
+        assert!(client.recall_picture_profile(0x80).await.is_err());
+        assert_eq!(received.lock().unwrap().len(), 2);
+    }
+
+    #[tokio::test]
+    async fn identify_asks_once_and_caches_the_version() {
+        let (port, received) = spawn_panning_camera().await;
+        let client = ViscaClient::new("127.0.0.1", port).unwrap();
+
+        let expected = CameraInfo {
+            vendor: "Sony".into(),
+            model: "051C".into(),
+            firmware: Some("0120".into()),
+        };
+        assert_eq!(client.identify().await.unwrap(), Some(expected.clone()));
+        assert_eq!(client.identify().await.unwrap(), Some(expected));
+        assert_eq!(received.lock().unwrap().len(), 1);
+    }
+
+    #[tokio::test]
+    async fn active_preset_is_the_last_recalled() {
+        let (port, _) = spawn_panning_camera().await;
+        let client = ViscaClient::new("127.0.0.1", port).unwrap();
+        client.recall_preset(5).await.unwrap();
+        assert_eq!(client.get_active_preset().await.unwrap(), Some(5));
+    }
+
+    #[tokio::test]
+    async fn default_zoom_by_reads_then_clamps() {
+        let (port, received) = spawn_panning_camera().await;
+        let client = ViscaClient::new("127.0.0.1", port).unwrap();
+        client.zoom_to(0.5).await.unwrap();
+
+        client.zoom_by(0.25).await.unwrap();
+        assert!((client.get_position().await.unwrap().zoom - 0.75).abs() < 1e-3);
+
+        client.zoom_by(1.0).await.unwrap();
+        assert_eq!(
+            received.lock().unwrap().last().unwrap(),
+            &commands::zoom_absolute(commands::VISCA_ZOOM_MAX)
+        );
+        client.zoom_by(-2.0).await.unwrap();
+        assert_eq!(client.get_position().await.unwrap().zoom, 0.0);
+    }
+
+    /// Mock camera that ACKs pan/tilt and zoom moves at once and reports
+    /// them complete after `slew`. Returns the events it saw, in order.
+    async fn spawn_slewing_camera(
+        slew: Duration,
+    ) -> (u16, Arc<std::sync::Mutex<Vec<&'static str>>>) {
+        let socket = Arc::new(UdpSocket::bind("127.0.0.1:0").await.unwrap());
+        let port = socket.local_addr().unwrap().port();
+        let events = Arc::new(std::sync::Mutex::new(Vec::new()));
+        let recorded = events.clone();
+        tokio::spawn(async move {
+            let mut buf = [0u8; 256];
+            loop {
+                let Ok((len, peer)) = socket.recv_from(&mut buf).await else {
+                    break;
+                };
+                if is_control(&buf[..len]) {
+                    continue;
+                }
+                let seq = commands::reply_sequence(&buf[..len]).unwrap();
+                let ack = commands::build_visca_packet(&[0x90, 0x41, 0xFF], seq);
+                let done = commands::build_visca_packet(&[0x90, 0x51, 0xFF], seq);
+                let _ = socket.send_to(&ack, peer).await;
+                let slewing = match buf[8..len] {
+                    [0x81, 0x01, 0x06, 0x02, ..] => Some(("pan_tilt", "pan_tilt complete")),
+                    [0x81, 0x01, 0x04, 0x47, ..] => Some(("zoom", "zoom complete")),
+                    _ => None,
+                };
+                if let Some((event, complete)) = slewing {
+                    recorded.lock().unwrap().push(event);
+                    let socket = socket.clone();
+                    let recorded = recorded.clone();
+                    tokio::spawn(async move {
+                        tokio::time::sleep(slew).await;
+                        recorded.lock().unwrap().push(complete);
+                        let _ = socket.send_to(&done, peer).await;
+                    });
+                    continue;
+                }
+                match buf[8..len] {
+                    [0x81, 0x01, 0x04, 0x48, ..] => {
+                        recorded.lock().unwrap().push("focus");
+                        let _ = socket.send_to(&done, peer).await;
+                    }
+                    [0x81, 0x01, 0x04, 0x62, on_off, 0xFF] => {
+                        let event = if on_off == 0x02 { "freeze" } else { "unfreeze" };
+                        recorded.lock().unwrap().push(event);
+                        let _ = socket.send_to(&done, peer).await;
+                    }
+                    _ => {
+                        let _ = socket.send_to(&done, peer).await;
+                    }
+                }
+            }
+        });
+        (port, events)
+    }
+
+    #[tokio::test]
+    async fn zoom_waits_for_pan_tilt_completion() {
+        let (port, events) = spawn_slewing_camera(Duration::from_millis(200)).await;
+        let client = ViscaClient::new("127.0.0.1", port)
+            .unwrap()
+            .with_wait_for_completion(true);
+        client.move_absolute(0.5, 0.2, 0.8).await.unwrap();
+        assert_eq!(
+            *events.lock().unwrap(),
+            ["pan_tilt", "pan_tilt complete", "zoom"]
+        );
+    }
+
+    #[tokio::test]
+    async fn stop_goes_out_while_a_move_waits_for_completion() {
+        let (port, events) = spawn_slewing_camera(Duration::from_secs(5)).await;
+        let client = Arc::new(
+            ViscaClient::new("127.0.0.1", port)
+                .unwrap()
+                .with_wait_for_completion(true),
+        );
+        let mover = {
+            let client = client.clone();
+            tokio::spawn(async move { client.move_absolute(0.5, 0.2, 0.8).await })
+        };
+        tokio::time::sleep(Duration::from_millis(100)).await;
+
+        let started = std::time::Instant::now();
+        client.stop().await.unwrap();
+        assert!(started.elapsed() < Duration::from_secs(1));
+        // The stop also ends the wait, and zoom is never sent
+        tokio::time::timeout(Duration::from_secs(1), mover)
+            .await
+            .expect("move still waiting after stop")
+            .unwrap()
+            .unwrap();
+        assert_eq!(*events.lock().unwrap(), ["pan_tilt"]);
+    }
+
+    #[tokio::test]
+    async fn frozen_reposition_unfreezes_after_the_move_completes() {
+        let (port, events) = spawn_slewing_camera(Duration::from_millis(100)).await;
+        let client = ViscaClient::new("127.0.0.1", port).unwrap();
+        client.reposition_frozen(0.5, 0.2, 0.8).await.unwrap();
+        assert_eq!(
+            *events.lock().unwrap(),
+            [
+                "freeze",
+                "pan_tilt",
+                "pan_tilt complete",
+                "zoom",
+                "zoom complete",
+                "unfreeze"
+            ]
+        );
+    }
+
+    #[tokio::test]
+    async fn interrupted_frozen_reposition_unfreezes_at_once() {
+        let (port, events) = spawn_slewing_camera(Duration::from_secs(5)).await;
+        let client = Arc::new(ViscaClient::new("127.0.0.1", port).unwrap());
+        let mover = {
+            let client = client.clone();
+            tokio::spawn(async move { client.reposition_frozen(0.5, 0.2, 0.8).await })
+        };
+        tokio::time::sleep(Duration::from_millis(100)).await;
+
+        client.stop().await.unwrap();
+        tokio::time::timeout(Duration::from_secs(1), mover)
+            .await
+            .expect("reposition still waiting after stop")
+            .unwrap()
+            .unwrap();
+        assert_eq!(*events.lock().unwrap(), ["freeze", "pan_tilt", "unfreeze"]);
+    }
+
+    /// Mock camera whose zoom reads `start` for the first `travel` zoom
+    /// inquiries and `end` after that. Returns the events it saw.
+    async fn spawn_zooming_camera(
+        start: u16,
+        end: u16,
+        travel: usize,
+    ) -> (u16, Arc<std::sync::Mutex<Vec<&'static str>>>) {
+        let socket = UdpSocket::bind("127.0.0.1:0").await.unwrap();
+        let port = socket.local_addr().unwrap().port();
+        let events = Arc::new(std::sync::Mutex::new(Vec::new()));
+        let recorded = events.clone();
+        tokio::spawn(async move {
+            let mut buf = [0u8; 256];
+            let mut inquiries = 0;
+            loop {
+                let Ok((len, peer)) = socket.recv_from(&mut buf).await else {
+                    break;
+                };
+                if is_control(&buf[..len]) {
+                    continue;
+                }
+                let seq = commands::reply_sequence(&buf[..len]).unwrap();
+                let reply = match buf[8..len] {
+                    [0x81, 0x09, 0x04, 0x47, 0xFF] => {
+                        inquiries += 1;
+                        let zoom = if inquiries > travel { end } else { start };
+                        let [hi, lo] = zoom.to_be_bytes();
+                        vec![0x90, 0x50, hi >> 4, hi & 0x0F, lo >> 4, lo & 0x0F, 0xFF]
+                    }
+                    [0x81, 0x01, 0x04, 0x47, ..] => {
+                        recorded.lock().unwrap().push("zoom");
+                        vec![0x90, 0x51, 0xFF]
+                    }
+                    [0x81, 0x01, 0x04, 0x48, ..] => {
+                        recorded.lock().unwrap().push("focus");
+                        vec![0x90, 0x51, 0xFF]
+                    }
+                    _ => vec![0x90, 0x51, 0xFF],
+                };
+                let reply = commands::build_visca_packet(&reply, seq);
+                let _ = socket.send_to(&reply, peer).await;
+            }
+        });
+        (port, events)
+    }
+
+    #[tokio::test]
+    async fn focus_waits_for_zoom_without_resending_it() {
+        let client = ViscaClient::new("127.0.0.1", 1).unwrap();
+        let target = client.zoom_range.to_raw(0.8);
+        let (port, events) = spawn_zooming_camera(0x1000, target, 1).await;
+        let client = ViscaClient::new("127.0.0.1", port).unwrap();
+        client.focus_after_zoom(0.8, 0.3).await.unwrap();
+        assert_eq!(*events.lock().unwrap(), ["focus"]);
+    }
+
+    #[tokio::test]
+    async fn focus_after_zoom_stops_waiting_when_cancelled() {
+        // Zoom never arrives nor settles within the test
+        let (port, events) = spawn_zooming_camera(0x1000, 0x1000, usize::MAX).await;
+        let client = Arc::new(ViscaClient::new("127.0.0.1", port).unwrap());
+        let focuser = {
+            let client = client.clone();
+            tokio::spawn(async move { client.focus_after_zoom(0.8, 0.3).await })
+        };
+        tokio::time::sleep(Duration::from_millis(50)).await;
+        client.cancel.cancel();
+        tokio::time::timeout(Duration::from_secs(1), focuser)
+            .await
+            .expect("still waiting after cancel")
+            .unwrap()
+            .unwrap();
+        assert!(events.lock().unwrap().is_empty());
+    }
+
+    #[tokio::test]
+    async fn fire_and_forget_sends_zoom_mid_slew() {
+        let (port, events) = spawn_slewing_camera(Duration::from_millis(200)).await;
+        let client = ViscaClient::new("127.0.0.1", port).unwrap();
+        client.move_absolute(0.5, 0.2, 0.8).await.unwrap();
+        assert_eq!(*events.lock().unwrap(), ["pan_tilt", "zoom"]);
+    }
+
+    #[tokio::test]
+    async fn encode_move_absolute_without_network() {
+        let client = ViscaClient::new("192.0.2.1", 52381).unwrap();
+        let requests = client
+            .encode(&PtzCommand::MoveAbsolute {
+                pan: 0.0,
+                tilt: 0.0,
+                zoom: 1.0,
+            })
+            .unwrap();
+        assert_eq!(
+            requests,
+            [
+                EncodedRequest::bytes(&commands::pan_tilt_absolute(
+                    0x0C,
+                    0x0C,
+                    commands::normalize_to_visca_pan(0.0, &ViscaCalibration::default()),
+                    commands::normalize_to_visca_tilt(0.0, &ViscaCalibration::default()),
+                )),
+                EncodedRequest::Bytes {
+                    hex: "81 01 04 47 04 00 00 00 FF".to_string()
+                },
+            ]
+        );
+        // Nothing was sent, so no socket was ever opened
+        assert!(client.link.socket.lock().await.is_none());
+    }
+
+    #[tokio::test]
+    async fn jog_cuts_a_relative_move_short() {
+        let (port, received) = spawn_panning_camera().await;
+        let mut dispatcher = crate::ptz::controller::PtzDispatcher::new();
+        let client = ViscaClient::new("127.0.0.1", port).unwrap();
+        dispatcher.add_controller("cam", Box::new(client));
+        dispatcher.set_active("cam").unwrap();
+        let cancels = dispatcher.move_cancels();
+        let dispatcher = Mutex::new(dispatcher);
+
+        let started = std::time::Instant::now();
+        let nudge = async {
+            dispatcher
+                .lock()
+                .await
+                .move_relative(0.5, 0.0)
+                .await
+                .unwrap();
+            started.elapsed()
+        };
+        // The joystick interrupts while the dispatcher is busy with the nudge
+        let jog = async {
+            tokio::time::sleep(Duration::from_millis(30)).await;
+            cancels.cancel("cam");
+            dispatcher.lock().await.continuous_move(-0.5, 0.0).await
+        };
+        let (elapsed, jogged) = tokio::join!(nudge, jog);
+        jogged.unwrap();
+
+        assert!(elapsed < Duration::from_millis(150), "took {:?}", elapsed);
+        // The nudge still stops the head before the jog takes over
+        assert_eq!(received.lock().unwrap()[1], commands::pan_tilt_stop());
+    }
 }
@@ -1,6 +1,8 @@
 //! VISCA command encoding for Sony and compatible PTZ cameras.
 //! VISCA-over-IP uses UDP with a framing header.
 
+use crate::ptz::types::{MenuDirection, TallyState, ViscaCalibration, WhiteBalanceSetting};
+
 /// VISCA-over-IP framing header (8 bytes).
 pub struct ViscaIpHeader {
     pub payload_type: u16,
@@ -11,6 +13,8 @@ pub struct ViscaIpHeader {
 impl ViscaIpHeader {
     pub const COMMAND: u16 = 0x0100;
     pub const INQUIRY: u16 = 0x0110;
+    pub const CONTROL: u16 = 0x0200;
+    pub const CONTROL_REPLY: u16 = 0x0201;
 
     pub fn new_command(payload_length: u16, seq: u32) -> Self {
         Self {
@@ -20,6 +24,14 @@ impl ViscaIpHeader {
         }
     }
 
+    pub fn new_control(payload_length: u16, seq: u32) -> Self {
+        Self {
+            payload_type: Self::CONTROL,
+            payload_length,
+            sequence_number: seq,
+        }
+    }
+
     pub fn to_bytes(&self) -> Vec<u8> {
         let mut buf = Vec::with_capacity(8);
         buf.extend_from_slice(&self.payload_type.to_be_bytes());
@@ -29,6 +41,24 @@ impl ViscaIpHeader {
     }
 }
 
+/// Split a 16-bit value into the four `0x` nibble bytes VISCA uses for positions.
+fn to_nibbles(value: u16) -> [u8; 4] {
+    [
+        ((value >> 12) & 0x0F) as u8,
+        ((value >> 8) & 0x0F) as u8,
+        ((value >> 4) & 0x0F) as u8,
+        (value & 0x0F) as u8,
+    ]
+}
+
+/// Reassemble a 16-bit value from four `0x` nibble bytes.
+fn from_nibbles(nibbles: &[u8]) -> u16 {
+    nibbles
+        .iter()
+        .take(4)
+        .fold(0, |acc, &n| (acc << 4) | (n as u16 & 0x0F))
+}
+
 /// Build a full VISCA-over-IP packet (header + payload).
 pub fn build_visca_packet(payload: &[u8], seq: u32) -> Vec<u8> {
     let header = ViscaIpHeader::new_command(payload.len() as u16, seq);
@@ -37,32 +67,59 @@ pub fn build_visca_packet(payload: &[u8], seq: u32) -> Vec<u8> {
     packet
 }
 
+/// Sequence number from a VISCA-over-IP reply header.
+/// Cameras echo the sequence number of the command being answered.
+pub fn reply_sequence(packet: &[u8]) -> Option<u32> {
+    let bytes: [u8; 4] = packet.get(4..8)?.try_into().ok()?;
+    Some(u32::from_be_bytes(bytes))
+}
+
+/// Payload type from a VISCA-over-IP header.
+pub fn payload_type(packet: &[u8]) -> Option<u16> {
+    let bytes: [u8; 2] = packet.get(0..2)?.try_into().ok()?;
+    Some(u16::from_be_bytes(bytes))
+}
+
+/// VISCA-over-IP control `RESET` (payload 01), which zeroes the camera's
+/// expected sequence number.
+pub fn sequence_reset_packet() -> Vec<u8> {
+    let mut packet = ViscaIpHeader::new_control(1, 0).to_bytes();
+    packet.push(0x01);
+    packet
+}
+
+/// Whether a packet is the control reply `0F 01`, sent when the camera
+/// rejects a command's sequence number.
+pub fn is_sequence_error(packet: &[u8]) -> bool {
+    payload_type(packet) == Some(ViscaIpHeader::CONTROL_REPLY)
+        && packet.get(8..10) == Some(&[0x0F, 0x01][..])
+}
+
+/// Whether a reply payload is an ACK (`9y 4z FF`): the camera accepted the
+/// command and will send a completion once it has finished.
+pub fn is_ack(payload: &[u8]) -> bool {
+    matches!(payload, [_, kind, 0xFF] if kind & 0xF0 == 0x40)
+}
+
+/// Error code of an error reply payload (`9y 6z ee FF`), e.g. `41` for a
+/// command the camera can't execute right now.
+pub fn reply_error(payload: &[u8]) -> Option<u8> {
+    match payload {
+        [_, kind, code, 0xFF] if kind & 0xF0 == 0x60 => Some(*code),
+        _ => None,
+    }
+}
+
 /// VISCA absolute pan/tilt position command.
 /// pan: 16-bit signed, range 0xFC90 to 0x0370
 /// tilt: 16-bit signed, range 0xFE70 to 0x0120
 /// speed: 1-24 for pan, 1-23 for tilt
 pub fn pan_tilt_absolute(pan_speed: u8, tilt_speed: u8, pan: i16, tilt: i16) -> Vec<u8> {
-    let pan_bytes = (pan as u16).to_be_bytes();
-    let tilt_bytes = (tilt as u16).to_be_bytes();
-    vec![
-        0x81,
-        0x01,
-        0x06,
-        0x02,
-        pan_speed,
-        tilt_speed,
-        // Pan position (4 nibbles)
-        (pan_bytes[0] >> 4) & 0x0F,
-        pan_bytes[0] & 0x0F,
-        (pan_bytes[1] >> 4) & 0x0F,
-        pan_bytes[1] & 0x0F,
-        // Tilt position (4 nibbles)
-        (tilt_bytes[0] >> 4) & 0x0F,
-        tilt_bytes[0] & 0x0F,
-        (tilt_bytes[1] >> 4) & 0x0F,
-        tilt_bytes[1] & 0x0F,
-        0xFF,
-    ]
+    let mut cmd = vec![0x81, 0x01, 0x06, 0x02, pan_speed, tilt_speed];
+    cmd.extend_from_slice(&to_nibbles(pan as u16));
+    cmd.extend_from_slice(&to_nibbles(tilt as u16));
+    cmd.push(0xFF);
+    cmd
 }
 
 /// VISCA relative pan/tilt movement.
@@ -80,20 +137,20 @@ pub fn pan_tilt_stop() -> Vec<u8> {
     vec![0x81, 0x01, 0x06, 0x01, 0x00, 0x00, 0x03, 0x03, 0xFF]
 }
 
-/// VISCA zoom absolute position (0x0000 to 0x4000).
+/// VISCA zoom absolute position (0x0000 to the camera's full-tele position).
 pub fn zoom_absolute(position: u16) -> Vec<u8> {
-    let bytes = position.to_be_bytes();
-    vec![
-        0x81,
-        0x01,
-        0x04,
-        0x47,
-        (bytes[0] >> 4) & 0x0F,
-        bytes[0] & 0x0F,
-        (bytes[1] >> 4) & 0x0F,
-        bytes[1] & 0x0F,
-        0xFF,
-    ]
+    let mut cmd = vec![0x81, 0x01, 0x04, 0x47];
+    cmd.extend_from_slice(&to_nibbles(position));
+    cmd.push(0xFF);
+    cmd
+}
+
+/// VISCA focus absolute position: 81 01 04 48 0p 0p 0p 0p FF
+pub fn focus_direct(position: u16) -> Vec<u8> {
+    let mut cmd = vec![0x81, 0x01, 0x04, 0x48];
+    cmd.extend_from_slice(&to_nibbles(position));
+    cmd.push(0xFF);
+    cmd
 }
 
 /// VISCA preset recall: 81 01 04 3F 02 pp FF
@@ -106,6 +163,12 @@ pub fn preset_store(preset_number: u8) -> Vec<u8> {
     vec![0x81, 0x01, 0x04, 0x3F, 0x01, preset_number, 0xFF]
 }
 
+/// VISCA preset inquiry (`CAM_MemoryInq`): 81 09 04 3F FF. Only some
+/// models answer it; others reply with a syntax error.
+pub fn preset_inquiry() -> Vec<u8> {
+    vec![0x81, 0x09, 0x04, 0x3F, 0xFF]
+}
+
 /// VISCA position inquiry command.
 pub fn pan_tilt_position_inquiry() -> Vec<u8> {
     vec![0x81, 0x09, 0x06, 0x12, 0xFF]
@@ -116,28 +179,97 @@ pub fn zoom_position_inquiry() -> Vec<u8> {
     vec![0x81, 0x09, 0x04, 0x47, 0xFF]
 }
 
+/// VISCA focus position inquiry command.
+pub fn focus_position_inquiry() -> Vec<u8> {
+    vec![0x81, 0x09, 0x04, 0x48, 0xFF]
+}
+
+/// VISCA version inquiry (`CAM_VersionInq`): 81 09 00 02 FF
+pub fn version_inquiry() -> Vec<u8> {
+    vec![0x81, 0x09, 0x00, 0x02, 0xFF]
+}
+
+/// VISCA vendor ID assigned to Sony.
+pub const SONY_VENDOR_ID: u16 = 0x0001;
+
+/// Codes from a `CAM_VersionInq` reply.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct ViscaVersion {
+    pub vendor_id: u16,
+    pub model_id: u16,
+    pub rom_version: u16,
+}
+
+impl ViscaVersion {
+    /// Vendor name for known IDs, the raw code otherwise.
+    pub fn vendor_name(&self) -> String {
+        match self.vendor_id {
+            SONY_VENDOR_ID => "Sony".to_string(),
+            id => format!("VISCA vendor {:04X}", id),
+        }
+    }
+}
+
+/// Parse VISCA version inquiry response payload.
+/// Response format: `90 50 GG GG HH HH JJ JJ KK FF`, with vendor (`G`),
+/// model (`H`) and ROM version (`J`) as big-endian 16-bit codes.
+pub fn parse_version_response(payload: &[u8]) -> Option<ViscaVersion> {
+    if payload.len() < 10 || payload[0] != 0x90 || payload[1] != 0x50 {
+        return None;
+    }
+    let word = |i: usize| u16::from_be_bytes([payload[i], payload[i + 1]]);
+    Some(ViscaVersion {
+        vendor_id: word(2),
+        model_id: word(4),
+        rom_version: word(6),
+    })
+}
+
+/// Map a normalized -1.0..1.0 value linearly onto `min..max`.
+fn normalized_to_raw(normalized: f64, min: i16, max: i16) -> i16 {
+    let t = (normalized.clamp(-1.0, 1.0) + 1.0) / 2.0;
+    (min as f64 + t * (max as f64 - min as f64)).round() as i16
+}
+
+/// Map a raw value in `min..max` back onto -1.0..1.0.
+fn raw_to_normalized(raw: i16, min: i16, max: i16) -> f64 {
+    let span = (max as f64 - min as f64).max(1.0);
+    ((raw as f64 - min as f64) / span * 2.0 - 1.0).clamp(-1.0, 1.0)
+}
+
 /// Convert normalized pan (-1.0 to 1.0) to VISCA pan value.
-/// VISCA range: 0xFC90 (-880) to 0x0370 (880)
-pub fn normalize_to_visca_pan(normalized: f64) -> i16 {
-    let clamped = normalized.clamp(-1.0, 1.0);
-    (clamped * 880.0) as i16
+/// Sony range: 0xFC90 (-880) to 0x0370 (880)
+pub fn normalize_to_visca_pan(normalized: f64, calibration: &ViscaCalibration) -> i16 {
+    normalized_to_raw(normalized, calibration.pan_min, calibration.pan_max)
 }
 
 /// Convert normalized tilt (-1.0 to 1.0) to VISCA tilt value.
-/// VISCA range: 0xFE70 (-400) to 0x0120 (288)
-pub fn normalize_to_visca_tilt(normalized: f64) -> i16 {
-    let clamped = normalized.clamp(-1.0, 1.0);
-    // Map -1..1 to -400..288 (asymmetric range centered approximately)
-    let center = (-400.0 + 288.0) / 2.0; // -56
-    let half_range = (288.0 - (-400.0)) / 2.0; // 344
-    (center + clamped * half_range) as i16
+/// Sony range: 0xFE70 (-400) to 0x0120 (288), so level is not at the midpoint
+pub fn normalize_to_visca_tilt(normalized: f64, calibration: &ViscaCalibration) -> i16 {
+    normalized_to_raw(normalized, calibration.tilt_min, calibration.tilt_max)
 }
 
+/// Full-tele zoom position of Sony cameras without digital zoom.
+pub const VISCA_ZOOM_MAX: u16 = 0x4000;
+
 /// Convert normalized zoom (0.0 to 1.0) to VISCA zoom value.
-/// VISCA range: 0x0000 to 0x4000
-pub fn normalize_to_visca_zoom(normalized: f64) -> u16 {
+/// VISCA range: 0x0000 to `zoom_max`, the camera's full-tele position.
+pub fn normalize_to_visca_zoom(normalized: f64, zoom_max: u16) -> u16 {
+    let clamped = normalized.clamp(0.0, 1.0);
+    (clamped * zoom_max as f64) as u16
+}
+
+/// VISCA focus position at infinity.
+pub const VISCA_FOCUS_FAR: u16 = 0x1000;
+/// VISCA focus position at the closest focusing distance.
+pub const VISCA_FOCUS_NEAR: u16 = 0xF000;
+
+/// Convert normalized focus (0.0 = nearest to 1.0 = infinity) to VISCA focus value.
+/// VISCA range: 0xF000 (near) to 0x1000 (far)
+pub fn normalize_to_visca_focus(normalized: f64) -> u16 {
     let clamped = normalized.clamp(0.0, 1.0);
-    (clamped * 0x4000 as f64) as u16
+    let span = (VISCA_FOCUS_NEAR - VISCA_FOCUS_FAR) as f64;
+    VISCA_FOCUS_NEAR - (clamped * span).round() as u16
 }
 
 /// Parse VISCA pan/tilt inquiry response payload.
@@ -147,14 +279,8 @@ pub fn parse_pan_tilt_response(payload: &[u8]) -> Option<(i16, i16)> {
     if payload.len() < 11 || payload[0] != 0x90 || payload[1] != 0x50 {
         return None;
     }
-    let pan = ((payload[2] as u16 & 0x0F) << 12)
-        | ((payload[3] as u16 & 0x0F) << 8)
-        | ((payload[4] as u16 & 0x0F) << 4)
-        | (payload[5] as u16 & 0x0F);
-    let tilt = ((payload[6] as u16 & 0x0F) << 12)
-        | ((payload[7] as u16 & 0x0F) << 8)
-        | ((payload[8] as u16 & 0x0F) << 4)
-        | (payload[9] as u16 & 0x0F);
+    let pan = from_nibbles(&payload[2..6]);
+    let tilt = from_nibbles(&payload[6..10]);
     Some((pan as i16, tilt as i16))
 }
 
@@ -164,28 +290,109 @@ pub fn parse_zoom_response(payload: &[u8]) -> Option<u16> {
     if payload.len() < 7 || payload[0] != 0x90 || payload[1] != 0x50 {
         return None;
     }
-    let zoom = ((payload[2] as u16 & 0x0F) << 12)
-        | ((payload[3] as u16 & 0x0F) << 8)
-        | ((payload[4] as u16 & 0x0F) << 4)
-        | (payload[5] as u16 & 0x0F);
+    let zoom = from_nibbles(&payload[2..6]);
     Some(zoom)
 }
 
+/// Parse VISCA focus inquiry response payload.
+/// Response format: `90 50 0p 0p 0p 0p FF`
+pub fn parse_focus_response(payload: &[u8]) -> Option<u16> {
+    if payload.len() < 7 || payload[0] != 0x90 || payload[1] != 0x50 {
+        return None;
+    }
+    Some(from_nibbles(&payload[2..6]))
+}
+
+/// Parse VISCA preset inquiry response payload: the last recalled preset.
+/// Response format: `90 50 pp FF`
+pub fn parse_preset_response(payload: &[u8]) -> Option<u8> {
+    match payload {
+        [0x90, 0x50, preset, 0xFF] => Some(*preset),
+        _ => None,
+    }
+}
+
 /// Convert VISCA pan value back to normalized -1.0..1.0.
-pub fn visca_pan_to_normalized(visca_pan: i16) -> f64 {
-    (visca_pan as f64 / 880.0).clamp(-1.0, 1.0)
+pub fn visca_pan_to_normalized(visca_pan: i16, calibration: &ViscaCalibration) -> f64 {
+    raw_to_normalized(visca_pan, calibration.pan_min, calibration.pan_max)
 }
 
 /// Convert VISCA tilt value back to normalized -1.0..1.0.
-pub fn visca_tilt_to_normalized(visca_tilt: i16) -> f64 {
-    let center = (-400.0 + 288.0) / 2.0; // -56
-    let half_range = (288.0 - (-400.0)) / 2.0; // 344
-    ((visca_tilt as f64 - center) / half_range).clamp(-1.0, 1.0)
+pub fn visca_tilt_to_normalized(visca_tilt: i16, calibration: &ViscaCalibration) -> f64 {
+    raw_to_normalized(visca_tilt, calibration.tilt_min, calibration.tilt_max)
+}
+
+/// Convert VISCA zoom value back to normalized 0.0..1.0, given the
+/// camera's full-tele position.
+pub fn visca_zoom_to_normalized(visca_zoom: u16, zoom_max: u16) -> f64 {
+    (visca_zoom as f64 / zoom_max.max(1) as f64).clamp(0.0, 1.0)
+}
+
+/// Full-tele position of Sony cameras with digital zoom enabled.
+pub const VISCA_DIGITAL_ZOOM_MAX: u16 = 0x7AC0;
+
+/// Share of the normalized zoom range that covers optical zoom when digital
+/// zoom is mapped; the rest reaches into the digital range.
+pub const OPTICAL_ZOOM_SHARE: f64 = 0.8;
+
+/// Raw zoom range of a camera: optical full tele, and optionally the
+/// combined optical+digital full tele beyond it.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct ZoomRange {
+    pub optical_max: u16,
+    /// `None` maps the whole normalized range onto optical zoom.
+    pub digital_max: Option<u16>,
+}
+
+impl Default for ZoomRange {
+    fn default() -> Self {
+        Self {
+            optical_max: VISCA_ZOOM_MAX,
+            digital_max: None,
+        }
+    }
+}
+
+impl ZoomRange {
+    /// Digital full tele, if it reaches past optical full tele.
+    fn digital_max(&self) -> Option<u16> {
+        self.digital_max.filter(|max| *max > self.optical_max)
+    }
+
+    /// Convert normalized zoom (0.0 to 1.0) to a raw position. With digital
+    /// zoom, the bottom [`OPTICAL_ZOOM_SHARE`] reaches optical full tele and
+    /// the top of the range covers the digital zoom.
+    pub fn to_raw(&self, normalized: f64) -> u16 {
+        let Some(digital_max) = self.digital_max() else {
+            return normalize_to_visca_zoom(normalized, self.optical_max);
+        };
+        let clamped = normalized.clamp(0.0, 1.0);
+        if clamped <= OPTICAL_ZOOM_SHARE {
+            return normalize_to_visca_zoom(clamped / OPTICAL_ZOOM_SHARE, self.optical_max);
+        }
+        let digital = (clamped - OPTICAL_ZOOM_SHARE) / (1.0 - OPTICAL_ZOOM_SHARE);
+        let span = (digital_max - self.optical_max) as f64;
+        self.optical_max + (digital * span).round() as u16
+    }
+
+    /// Convert a raw zoom position back to normalized 0.0..1.0.
+    pub fn to_normalized(&self, raw: u16) -> f64 {
+        let Some(digital_max) = self.digital_max() else {
+            return visca_zoom_to_normalized(raw, self.optical_max);
+        };
+        if raw <= self.optical_max {
+            return visca_zoom_to_normalized(raw, self.optical_max) * OPTICAL_ZOOM_SHARE;
+        }
+        let digital =
+            ((raw - self.optical_max) as f64 / (digital_max - self.optical_max) as f64).min(1.0);
+        OPTICAL_ZOOM_SHARE + digital * (1.0 - OPTICAL_ZOOM_SHARE)
+    }
 }
 
-/// Convert VISCA zoom value back to normalized 0.0..1.0.
-pub fn visca_zoom_to_normalized(visca_zoom: u16) -> f64 {
-    (visca_zoom as f64 / 0x4000 as f64).clamp(0.0, 1.0)
+/// Convert VISCA focus value back to normalized 0.0..1.0.
+pub fn visca_focus_to_normalized(visca_focus: u16) -> f64 {
+    let span = (VISCA_FOCUS_NEAR - VISCA_FOCUS_FAR) as f64;
+    ((VISCA_FOCUS_NEAR as f64 - visca_focus as f64) / span).clamp(0.0, 1.0)
 }
 
 /// VISCA home position command.
@@ -223,15 +430,240 @@ pub fn autofocus_trigger() -> Vec<u8> {
     vec![0x81, 0x01, 0x04, 0x18, 0x01, 0xFF]
 }
 
+/// VISCA white-balance mode: 81 01 04 35 0p FF
+pub fn white_balance_mode(setting: WhiteBalanceSetting) -> Vec<u8> {
+    let mode = match setting {
+        WhiteBalanceSetting::Auto => 0x00,
+        WhiteBalanceSetting::Indoor => 0x01,
+        WhiteBalanceSetting::Outdoor => 0x02,
+        WhiteBalanceSetting::OnePush => 0x03,
+        WhiteBalanceSetting::Manual { .. } => 0x05,
+    };
+    vec![0x81, 0x01, 0x04, 0x35, mode, 0xFF]
+}
+
+/// VISCA red gain direct: 81 01 04 43 00 00 0p 0p FF
+pub fn red_gain_direct(gain: u8) -> Vec<u8> {
+    vec![
+        0x81,
+        0x01,
+        0x04,
+        0x43,
+        0x00,
+        0x00,
+        gain >> 4,
+        gain & 0x0F,
+        0xFF,
+    ]
+}
+
+/// VISCA blue gain direct: 81 01 04 44 00 00 0p 0p FF
+pub fn blue_gain_direct(gain: u8) -> Vec<u8> {
+    vec![
+        0x81,
+        0x01,
+        0x04,
+        0x44,
+        0x00,
+        0x00,
+        gain >> 4,
+        gain & 0x0F,
+        0xFF,
+    ]
+}
+
+/// Common digital zoom on/off opcode (`81 01 04 06 ...`).
+pub const DIGITAL_ZOOM_OPCODE: [u8; 2] = [0x04, 0x06];
+
+/// VISCA digital zoom on/off: 81 01 <opcode> 02/03 FF. Models differ, so
+/// endpoints can configure their own opcode.
+pub fn digital_zoom(opcode: [u8; 2], enabled: bool) -> Vec<u8> {
+    let mut cmd = vec![0x81, 0x01];
+    cmd.extend_from_slice(&opcode);
+    cmd.extend_from_slice(&[if enabled { 0x02 } else { 0x03 }, 0xFF]);
+    cmd
+}
+
+/// VISCA picture flip (vertical flip for ceiling mounts): 81 01 04 66 02/03 FF
+pub fn picture_flip(enabled: bool) -> Vec<u8> {
+    vec![
+        0x81,
+        0x01,
+        0x04,
+        0x66,
+        if enabled { 0x02 } else { 0x03 },
+        0xFF,
+    ]
+}
+
+/// VISCA freeze: 81 01 04 62 02/03 FF. Holds the current frame on the
+/// camera's outputs while the head keeps moving.
+pub fn freeze(enabled: bool) -> Vec<u8> {
+    vec![
+        0x81,
+        0x01,
+        0x04,
+        0x62,
+        if enabled { 0x02 } else { 0x03 },
+        0xFF,
+    ]
+}
+
+/// VISCA left/right reverse (horizontal mirror): 81 01 04 61 02/03 FF
+pub fn lr_reverse(enabled: bool) -> Vec<u8> {
+    vec![
+        0x81,
+        0x01,
+        0x04,
+        0x61,
+        if enabled { 0x02 } else { 0x03 },
+        0xFF,
+    ]
+}
+
+/// VISCA on-screen menu on/off: 81 01 06 06 02/03 FF
+pub fn menu(enabled: bool) -> Vec<u8> {
+    vec![
+        0x81,
+        0x01,
+        0x06,
+        0x06,
+        if enabled { 0x02 } else { 0x03 },
+        0xFF,
+    ]
+}
+
+/// VISCA on-screen menu toggle: 81 01 06 06 10 FF
+pub fn menu_toggle() -> Vec<u8> {
+    vec![0x81, 0x01, 0x06, 0x06, 0x10, 0xFF]
+}
+
+/// Move the menu cursor. While the menu is open the camera treats
+/// pan/tilt drive commands as cursor keys, so this is a drive at the
+/// standard menu speed (0x0E) in a single direction.
+pub fn menu_navigate(direction: MenuDirection) -> Vec<u8> {
+    let (pan_dir, tilt_dir) = match direction {
+        MenuDirection::Up => (0x03, 0x01),
+        MenuDirection::Down => (0x03, 0x02),
+        MenuDirection::Left => (0x01, 0x03),
+        MenuDirection::Right => (0x02, 0x03),
+    };
+    pan_tilt_relative(0x0E, 0x0E, pan_dir, tilt_dir)
+}
+
+/// VISCA menu enter (select the highlighted item): 81 01 7E 01 02 00 01 FF
+pub fn menu_enter() -> Vec<u8> {
+    vec![0x81, 0x01, 0x7E, 0x01, 0x02, 0x00, 0x01, 0xFF]
+}
+
+/// Common tally lamp opcode (`81 01 7E 01 0A ...`). Models differ, so
+/// endpoints can configure their own.
+pub const TALLY_OPCODE: [u8; 3] = [0x7E, 0x01, 0x0A];
+
+/// VISCA tally lamp: 81 01 <opcode> ll 0p FF. Lamp 00 is the red (program)
+/// lamp and 01 the green (preview) lamp on cameras that have one; p is
+/// 2 for on and 3 for off.
+pub fn tally(opcode: [u8; 3], state: TallyState) -> Vec<u8> {
+    let (lamp, on_off) = match state {
+        TallyState::Off => (0x00, 0x03),
+        TallyState::Preview => (0x01, 0x02),
+        TallyState::Program => (0x00, 0x02),
+    };
+    let mut cmd = vec![0x81, 0x01];
+    cmd.extend_from_slice(&opcode);
+    cmd.extend_from_slice(&[lamp, on_off, 0xFF]);
+    cmd
+}
+
+/// Common gamma select opcode (`81 01 04 5B ...`).
+pub const GAMMA_OPCODE: [u8; 2] = [0x04, 0x5B];
+
+/// Common picture profile recall opcode (`81 01 7E 04 5F ...`).
+pub const PICTURE_PROFILE_OPCODE: [u8; 3] = [0x7E, 0x04, 0x5F];
+
+/// Highest gamma mode that fits the one-nibble parameter.
+pub const MAX_GAMMA_MODE: u8 = 0x0F;
+
+/// Highest picture profile index that fits a VISCA data byte.
+pub const MAX_PICTURE_PROFILE: u8 = 0x7F;
+
+/// VISCA gamma select: 81 01 <opcode> 0p FF. The meaning of each mode
+/// (standard, cine, still, HLG...) depends on the model.
+pub fn gamma(opcode: [u8; 2], mode: u8) -> Vec<u8> {
+    let mut cmd = vec![0x81, 0x01];
+    cmd.extend_from_slice(&opcode);
+    cmd.extend_from_slice(&[mode & 0x0F, 0xFF]);
+    cmd
+}
+
+/// VISCA picture profile recall: 81 01 <opcode> pp FF.
+pub fn picture_profile(opcode: [u8; 3], index: u8) -> Vec<u8> {
+    let mut cmd = vec![0x81, 0x01];
+    cmd.extend_from_slice(&opcode);
+    cmd.extend_from_slice(&[index & 0x7F, 0xFF]);
+    cmd
+}
+
+/// Longest VISCA message a camera accepts.
+pub const MAX_MESSAGE_LEN: usize = 16;
+
+/// Check that a user-supplied VISCA message is non-empty, fits in one
+/// message and ends with the `FF` terminator.
+pub fn validate_raw(payload: &[u8]) -> Result<(), String> {
+    if payload.is_empty() {
+        return Err("VISCA command is empty".to_string());
+    }
+    if payload.len() > MAX_MESSAGE_LEN {
+        return Err(format!(
+            "VISCA command is longer than {} bytes",
+            MAX_MESSAGE_LEN
+        ));
+    }
+    if payload.last() != Some(&0xFF) {
+        return Err("VISCA command must end with FF".to_string());
+    }
+    Ok(())
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
 
+    #[test]
+    fn sequence_reset_is_a_control_packet() {
+        assert_eq!(
+            sequence_reset_packet(),
+            vec![0x02, 0x00, 0x00, 0x01, 0x00, 0x00, 0x00, 0x00, 0x01]
+        );
+    }
+
+    #[test]
+    fn sequence_error_reply_is_recognized() {
+        let mut error = ViscaIpHeader {
+            payload_type: ViscaIpHeader::CONTROL_REPLY,
+            payload_length: 2,
+            sequence_number: 7,
+        }
+        .to_bytes();
+        error.extend_from_slice(&[0x0F, 0x01]);
+        assert!(is_sequence_error(&error));
+
+        // A command error with the same bytes is not a sequence error
+        assert!(!is_sequence_error(&build_visca_packet(&[0x0F, 0x01], 7)));
+    }
+
+    #[test]
+    fn reply_sequence_reads_header() {
+        let packet = build_visca_packet(&[0x90, 0x41, 0xFF], 0x0102_0304);
+        assert_eq!(reply_sequence(&packet), Some(0x0102_0304));
+        assert_eq!(reply_sequence(&[0x01, 0x11, 0x00]), None);
+    }
+
     #[test]
     fn pan_round_trip() {
         for &val in &[-1.0, -0.5, 0.0, 0.5, 1.0] {
-            let visca = normalize_to_visca_pan(val);
-            let back = visca_pan_to_normalized(visca);
+            let visca = normalize_to_visca_pan(val, &ViscaCalibration::default());
+            let back = visca_pan_to_normalized(visca, &ViscaCalibration::default());
             assert!(
                 (back - val).abs() < 0.01,
                 "pan round trip failed: {val} -> {visca} -> {back}"
@@ -242,8 +674,8 @@ mod tests {
     #[test]
     fn zoom_round_trip() {
         for &val in &[0.0, 0.25, 0.5, 0.75, 1.0] {
-            let visca = normalize_to_visca_zoom(val);
-            let back = visca_zoom_to_normalized(visca);
+            let visca = normalize_to_visca_zoom(val, VISCA_ZOOM_MAX);
+            let back = visca_zoom_to_normalized(visca, VISCA_ZOOM_MAX);
             assert!(
                 (back - val).abs() < 0.01,
                 "zoom round trip failed: {val} -> {visca} -> {back}"
@@ -251,6 +683,59 @@ mod tests {
         }
     }
 
+    #[test]
+    fn focus_round_trip() {
+        for &val in &[0.0, 0.25, 0.5, 0.75, 1.0] {
+            let visca = normalize_to_visca_focus(val);
+            let back = visca_focus_to_normalized(visca);
+            assert!(
+                (back - val).abs() < 0.001,
+                "focus round trip failed: {val} -> {visca} -> {back}"
+            );
+        }
+        assert_eq!(normalize_to_visca_focus(0.0), VISCA_FOCUS_NEAR);
+        assert_eq!(normalize_to_visca_focus(1.0), VISCA_FOCUS_FAR);
+        assert_eq!(normalize_to_visca_focus(2.0), VISCA_FOCUS_FAR);
+        assert_eq!(visca_focus_to_normalized(0x0000), 1.0);
+    }
+
+    #[test]
+    fn focus_direct_encoding_round_trips_through_response() {
+        let cmd = focus_direct(0x1A2B);
+        assert_eq!(
+            cmd,
+            vec![0x81, 0x01, 0x04, 0x48, 0x01, 0x0A, 0x02, 0x0B, 0xFF]
+        );
+        assert_eq!(focus_position_inquiry(), vec![0x81, 0x09, 0x04, 0x48, 0xFF]);
+        let mut reply = vec![0x90, 0x50];
+        reply.extend_from_slice(&cmd[4..8]);
+        reply.push(0xFF);
+        assert_eq!(parse_focus_response(&reply), Some(0x1A2B));
+        assert!(parse_focus_response(&[0x90, 0x50, 0x01]).is_none());
+    }
+
+    #[test]
+    fn preset_inquiry_round_trip() {
+        assert_eq!(preset_inquiry(), vec![0x81, 0x09, 0x04, 0x3F, 0xFF]);
+        assert_eq!(parse_preset_response(&[0x90, 0x50, 0x07, 0xFF]), Some(7));
+        assert_eq!(parse_preset_response(&[0x90, 0x60, 0x02, 0xFF]), None);
+    }
+
+    #[test]
+    fn absolute_encodings_split_nibbles() {
+        assert_eq!(
+            zoom_absolute(0x4000),
+            vec![0x81, 0x01, 0x04, 0x47, 0x04, 0x00, 0x00, 0x00, 0xFF]
+        );
+        assert_eq!(
+            pan_tilt_absolute(0x18, 0x17, -880, 288),
+            vec![
+                0x81, 0x01, 0x06, 0x02, 0x18, 0x17, 0x0F, 0x0C, 0x09, 0x00, 0x00, 0x01, 0x02, 0x00,
+                0xFF
+            ]
+        );
+    }
+
     #[test]
     fn parse_pan_tilt_known_bytes() {
         // Pan = 0x0370 (880), Tilt = 0x0120 (288)
@@ -317,4 +802,277 @@ mod tests {
             vec![0x81, 0x01, 0x04, 0x18, 0x01, 0xFF]
         );
     }
+
+    #[test]
+    fn white_balance_encodings() {
+        assert_eq!(
+            white_balance_mode(WhiteBalanceSetting::Auto),
+            vec![0x81, 0x01, 0x04, 0x35, 0x00, 0xFF]
+        );
+        assert_eq!(
+            white_balance_mode(WhiteBalanceSetting::OnePush),
+            vec![0x81, 0x01, 0x04, 0x35, 0x03, 0xFF]
+        );
+        assert_eq!(
+            white_balance_mode(WhiteBalanceSetting::Manual {
+                red_gain: 0,
+                blue_gain: 0
+            }),
+            vec![0x81, 0x01, 0x04, 0x35, 0x05, 0xFF]
+        );
+        assert_eq!(
+            red_gain_direct(0xA7),
+            vec![0x81, 0x01, 0x04, 0x43, 0x00, 0x00, 0x0A, 0x07, 0xFF]
+        );
+        assert_eq!(
+            blue_gain_direct(0x3C),
+            vec![0x81, 0x01, 0x04, 0x44, 0x00, 0x00, 0x03, 0x0C, 0xFF]
+        );
+    }
+
+    #[test]
+    fn freeze_encodings() {
+        assert_eq!(freeze(true), vec![0x81, 0x01, 0x04, 0x62, 0x02, 0xFF]);
+        assert_eq!(freeze(false), vec![0x81, 0x01, 0x04, 0x62, 0x03, 0xFF]);
+    }
+
+    #[test]
+    fn flip_and_mirror_encodings() {
+        assert_eq!(picture_flip(true), vec![0x81, 0x01, 0x04, 0x66, 0x02, 0xFF]);
+        assert_eq!(
+            picture_flip(false),
+            vec![0x81, 0x01, 0x04, 0x66, 0x03, 0xFF]
+        );
+        assert_eq!(lr_reverse(true), vec![0x81, 0x01, 0x04, 0x61, 0x02, 0xFF]);
+        assert_eq!(lr_reverse(false), vec![0x81, 0x01, 0x04, 0x61, 0x03, 0xFF]);
+    }
+
+    #[test]
+    fn menu_encodings() {
+        assert_eq!(menu(true), vec![0x81, 0x01, 0x06, 0x06, 0x02, 0xFF]);
+        assert_eq!(menu(false), vec![0x81, 0x01, 0x06, 0x06, 0x03, 0xFF]);
+        assert_eq!(menu_toggle(), vec![0x81, 0x01, 0x06, 0x06, 0x10, 0xFF]);
+        assert_eq!(
+            menu_enter(),
+            vec![0x81, 0x01, 0x7E, 0x01, 0x02, 0x00, 0x01, 0xFF]
+        );
+    }
+
+    #[test]
+    fn menu_navigation_encodings() {
+        let cases = [
+            (MenuDirection::Up, [0x03, 0x01]),
+            (MenuDirection::Down, [0x03, 0x02]),
+            (MenuDirection::Left, [0x01, 0x03]),
+            (MenuDirection::Right, [0x02, 0x03]),
+        ];
+        for (direction, [pan_dir, tilt_dir]) in cases {
+            assert_eq!(
+                menu_navigate(direction),
+                vec![0x81, 0x01, 0x06, 0x01, 0x0E, 0x0E, pan_dir, tilt_dir, 0xFF],
+                "{:?}",
+                direction
+            );
+        }
+    }
+
+    #[test]
+    fn tally_bytes_per_state() {
+        assert_eq!(
+            tally(TALLY_OPCODE, TallyState::Off),
+            vec![0x81, 0x01, 0x7E, 0x01, 0x0A, 0x00, 0x03, 0xFF]
+        );
+        assert_eq!(
+            tally(TALLY_OPCODE, TallyState::Preview),
+            vec![0x81, 0x01, 0x7E, 0x01, 0x0A, 0x01, 0x02, 0xFF]
+        );
+        assert_eq!(
+            tally(TALLY_OPCODE, TallyState::Program),
+            vec![0x81, 0x01, 0x7E, 0x01, 0x0A, 0x00, 0x02, 0xFF]
+        );
+    }
+
+    #[test]
+    fn tally_uses_configured_opcode() {
+        assert_eq!(
+            tally([0x7E, 0x04, 0x1A], TallyState::Program),
+            vec![0x81, 0x01, 0x7E, 0x04, 0x1A, 0x00, 0x02, 0xFF]
+        );
+    }
+
+    #[test]
+    fn custom_zoom_max_scales_normalization() {
+        // e.g. a camera whose optical zoom ends at 0x7AC0
+        let zoom_max = 0x7AC0;
+        assert_eq!(normalize_to_visca_zoom(1.0, zoom_max), 0x7AC0);
+        assert_eq!(normalize_to_visca_zoom(0.5, zoom_max), 0x3D60);
+        assert_eq!(visca_zoom_to_normalized(0x7AC0, zoom_max), 1.0);
+        assert_eq!(visca_zoom_to_normalized(0x3D60, zoom_max), 0.5);
+        // Positions past the configured maximum clamp to full tele
+        assert_eq!(visca_zoom_to_normalized(0xFFFF, zoom_max), 1.0);
+        for &val in &[0.0, 0.3, 0.8, 1.0] {
+            let back = visca_zoom_to_normalized(normalize_to_visca_zoom(val, zoom_max), zoom_max);
+            assert!((back - val).abs() < 0.001, "{val} -> {back}");
+        }
+    }
+
+    #[test]
+    fn optical_only_zoom_range_spans_the_whole_normalized_range() {
+        let optical = ZoomRange::default();
+        assert_eq!(optical.to_raw(1.0), VISCA_ZOOM_MAX);
+        assert_eq!(optical.to_raw(0.5), 0x2000);
+        assert_eq!(optical.to_normalized(VISCA_ZOOM_MAX), 1.0);
+        // A digital maximum at or below optical tele is ignored
+        let ignored = ZoomRange {
+            digital_max: Some(VISCA_ZOOM_MAX),
+            ..optical
+        };
+        assert_eq!(ignored.to_raw(0.8), optical.to_raw(0.8));
+        assert_eq!(ignored.to_normalized(0x1000), optical.to_normalized(0x1000));
+    }
+
+    #[test]
+    fn digital_zoom_range_maps_the_top_share_past_optical_tele() {
+        let combined = ZoomRange {
+            optical_max: VISCA_ZOOM_MAX,
+            digital_max: Some(VISCA_DIGITAL_ZOOM_MAX),
+        };
+        assert_eq!(combined.to_raw(0.0), 0);
+        assert_eq!(combined.to_raw(0.4), 0x2000);
+        assert_eq!(combined.to_raw(OPTICAL_ZOOM_SHARE), VISCA_ZOOM_MAX);
+        assert_eq!(combined.to_raw(0.9), 0x5D60);
+        assert_eq!(combined.to_raw(1.0), VISCA_DIGITAL_ZOOM_MAX);
+        assert_eq!(combined.to_raw(5.0), VISCA_DIGITAL_ZOOM_MAX);
+
+        assert!((combined.to_normalized(0x2000) - 0.4).abs() < 1e-9);
+        assert!((combined.to_normalized(VISCA_ZOOM_MAX) - OPTICAL_ZOOM_SHARE).abs() < 1e-9);
+        assert_eq!(combined.to_normalized(0xFFFF), 1.0);
+        for &val in &[0.0, 0.3, 0.8, 0.85, 1.0] {
+            let back = combined.to_normalized(combined.to_raw(val));
+            assert!((back - val).abs() < 0.001, "{val} -> {back}");
+        }
+    }
+
+    #[test]
+    fn digital_zoom_uses_the_configured_opcode() {
+        assert_eq!(
+            digital_zoom(DIGITAL_ZOOM_OPCODE, true),
+            [0x81, 0x01, 0x04, 0x06, 0x02, 0xFF]
+        );
+        assert_eq!(
+            digital_zoom([0x04, 0x36], false),
+            [0x81, 0x01, 0x04, 0x36, 0x03, 0xFF]
+        );
+    }
+
+    #[test]
+    fn default_calibration_matches_sony_range() {
+        let sony = ViscaCalibration::default();
+        assert_eq!(normalize_to_visca_pan(1.0, &sony), 880);
+        assert_eq!(normalize_to_visca_pan(-1.0, &sony), -880);
+        assert_eq!(normalize_to_visca_pan(0.0, &sony), 0);
+        assert_eq!(normalize_to_visca_tilt(1.0, &sony), 288);
+        assert_eq!(normalize_to_visca_tilt(-1.0, &sony), -400);
+        assert_eq!(normalize_to_visca_tilt(0.0, &sony), -56);
+    }
+
+    #[test]
+    fn custom_calibration_scales_pan_and_tilt() {
+        let calibration = ViscaCalibration {
+            pan_min: -2448,
+            pan_max: 2448,
+            tilt_min: -432,
+            tilt_max: 1296,
+        };
+        assert_eq!(normalize_to_visca_pan(1.0, &calibration), 2448);
+        assert_eq!(normalize_to_visca_pan(-0.5, &calibration), -1224);
+        assert_eq!(normalize_to_visca_tilt(-1.0, &calibration), -432);
+        assert_eq!(normalize_to_visca_tilt(0.0, &calibration), 432);
+        assert_eq!(visca_tilt_to_normalized(1296, &calibration), 1.0);
+        assert_eq!(visca_tilt_to_normalized(432, &calibration), 0.0);
+        // Readings outside the calibrated range clamp
+        assert_eq!(visca_pan_to_normalized(3000, &calibration), 1.0);
+        for &val in &[-1.0, -0.3, 0.0, 0.6, 1.0] {
+            let pan =
+                visca_pan_to_normalized(normalize_to_visca_pan(val, &calibration), &calibration);
+            let tilt =
+                visca_tilt_to_normalized(normalize_to_visca_tilt(val, &calibration), &calibration);
+            assert!((pan - val).abs() < 0.001, "pan {val} -> {pan}");
+            assert!((tilt - val).abs() < 0.002, "tilt {val} -> {tilt}");
+        }
+    }
+
+    #[test]
+    fn raw_payload_must_be_terminated() {
+        assert!(validate_raw(&[0x81, 0x09, 0x04, 0x47, 0xFF]).is_ok());
+        assert!(validate_raw(&[]).is_err());
+        assert!(validate_raw(&[0x81, 0x09, 0x04, 0x47]).is_err());
+        assert!(validate_raw(&[0x00; 17]).is_err());
+    }
+
+    #[test]
+    fn gamma_and_picture_profile_default_bytes() {
+        assert_eq!(
+            gamma(GAMMA_OPCODE, 0x02),
+            vec![0x81, 0x01, 0x04, 0x5B, 0x02, 0xFF]
+        );
+        assert_eq!(
+            picture_profile(PICTURE_PROFILE_OPCODE, 3),
+            vec![0x81, 0x01, 0x7E, 0x04, 0x5F, 0x03, 0xFF]
+        );
+    }
+
+    #[test]
+    fn gamma_and_picture_profile_use_configured_opcodes() {
+        assert_eq!(
+            gamma([0x7E, 0x51], 1),
+            vec![0x81, 0x01, 0x7E, 0x51, 0x01, 0xFF]
+        );
+        assert_eq!(
+            picture_profile([0x04, 0x3F, 0x20], 5),
+            vec![0x81, 0x01, 0x04, 0x3F, 0x20, 0x05, 0xFF]
+        );
+    }
+
+    #[test]
+    fn version_inquiry_bytes() {
+        assert_eq!(version_inquiry(), vec![0x81, 0x09, 0x00, 0x02, 0xFF]);
+    }
+
+    #[test]
+    fn parses_version_reply_into_vendor_and_model_codes() {
+        let reply = [0x90, 0x50, 0x00, 0x01, 0x05, 0x1C, 0x01, 0x20, 0x02, 0xFF];
+        let version = parse_version_response(&reply).unwrap();
+        assert_eq!(
+            version,
+            ViscaVersion {
+                vendor_id: 0x0001,
+                model_id: 0x051C,
+                rom_version: 0x0120,
+            }
+        );
+        assert_eq!(version.vendor_name(), "Sony");
+
+        let other = parse_version_response(&[0x90, 0x50, 0x00, 0x20, 0, 1, 0, 1, 2, 0xFF]);
+        assert_eq!(other.unwrap().vendor_name(), "VISCA vendor 0020");
+    }
+
+    #[test]
+    fn rejects_short_or_error_version_replies() {
+        assert_eq!(
+            parse_version_response(&[0x90, 0x50, 0x00, 0x01, 0xFF]),
+            None
+        );
+        assert_eq!(parse_version_response(&[0x90, 0x60, 0x02, 0xFF]), None);
+    }
+
+    #[test]
+    fn ack_and_error_replies_are_classified() {
+        assert!(is_ack(&[0x90, 0x41, 0xFF]));
+        assert!(!is_ack(&[0x90, 0x51, 0xFF]));
+        assert_eq!(reply_error(&[0x90, 0x61, 0x41, 0xFF]), Some(0x41));
+        assert_eq!(reply_error(&[0x90, 0x51, 0xFF]), None);
+        // Inquiry replies carry data and are neither
+        assert!(!is_ack(&[0x90, 0x50, 0x02, 0xFF]));
+    }
 }
@@ -0,0 +1,239 @@
+//! JSON messages exchanged with WebSocket control clients.
+
+use crate::ptz::types::PtzPosition;
+use serde::{Deserialize, Serialize};
+
+/// Largest text message accepted; control requests are far smaller.
+pub const MAX_MESSAGE_BYTES: usize = 4096;
+
+/// A PTZ action requested by a control client. Positions and speeds use the
+/// same normalized ranges as the rest of the app.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case", deny_unknown_fields)]
+pub enum ControlCommand {
+    /// Pan and tilt -1.0 to 1.0, zoom 0.0 to 1.0.
+    MoveAbsolute {
+        pan: f64,
+        tilt: f64,
+        zoom: f64,
+    },
+    /// Deltas -1.0 to 1.0.
+    MoveRelative {
+        pan_delta: f64,
+        tilt_delta: f64,
+    },
+    /// Speeds -1.0 to 1.0; the move stops on its own unless repeated or kept alive.
+    ContinuousMove {
+        pan_speed: f64,
+        tilt_speed: f64,
+    },
+    /// Keeps a continuous move running without changing its speed.
+    Keepalive,
+    /// Absolute zoom level, 0.0 to 1.0.
+    Zoom {
+        zoom: f64,
+    },
+    Stop,
+    Home,
+    /// 1-based position of a preset in the active profile.
+    RecallPreset {
+        number: usize,
+    },
+    GetPosition,
+}
+
+impl ControlCommand {
+    /// Reject values outside their normalized ranges.
+    pub fn validate(&self) -> Result<(), String> {
+        let check = |value: f64, min: f64, max: f64, name: &str| {
+            if value.is_finite() && (min..=max).contains(&value) {
+                Ok(())
+            } else {
+                Err(format!("{} must be between {} and {}", name, min, max))
+            }
+        };
+        match *self {
+            ControlCommand::MoveAbsolute { pan, tilt, zoom } => {
+                check(pan, -1.0, 1.0, "pan")?;
+                check(tilt, -1.0, 1.0, "tilt")?;
+                check(zoom, 0.0, 1.0, "zoom")
+            }
+            ControlCommand::MoveRelative {
+                pan_delta,
+                tilt_delta,
+            } => {
+                check(pan_delta, -1.0, 1.0, "pan_delta")?;
+                check(tilt_delta, -1.0, 1.0, "tilt_delta")
+            }
+            ControlCommand::ContinuousMove {
+                pan_speed,
+                tilt_speed,
+            } => {
+                check(pan_speed, -1.0, 1.0, "pan_speed")?;
+                check(tilt_speed, -1.0, 1.0, "tilt_speed")
+            }
+            ControlCommand::Zoom { zoom } => check(zoom, 0.0, 1.0, "zoom"),
+            ControlCommand::RecallPreset { number: 0 } => {
+                Err("Preset numbers start at 1".to_string())
+            }
+            ControlCommand::Keepalive
+            | ControlCommand::Stop
+            | ControlCommand::Home
+            | ControlCommand::RecallPreset { .. }
+            | ControlCommand::GetPosition => Ok(()),
+        }
+    }
+}
+
+/// A command from a client. The optional `id` is echoed in the response so
+/// clients can match replies to requests.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct ControlRequest {
+    #[serde(default)]
+    pub id: Option<u64>,
+    pub command: ControlCommand,
+}
+
+/// A message sent to a client.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum ServerMessage {
+    /// Outcome of a request. `position` is set for position queries.
+    Response {
+        id: Option<u64>,
+        ok: bool,
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        error: Option<String>,
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        position: Option<PtzPosition>,
+    },
+    /// Pushed whenever the camera position changes.
+    Position { position: PtzPosition },
+}
+
+impl ServerMessage {
+    pub fn success(id: Option<u64>, position: Option<PtzPosition>) -> Self {
+        ServerMessage::Response {
+            id,
+            ok: true,
+            error: None,
+            position,
+        }
+    }
+
+    pub fn failure(id: Option<u64>, error: impl Into<String>) -> Self {
+        ServerMessage::Response {
+            id,
+            ok: false,
+            error: Some(error.into()),
+            position: None,
+        }
+    }
+
+    pub fn to_json(&self) -> String {
+        serde_json::to_string(self).expect("server messages always serialize")
+    }
+}
+
+/// Parse and validate a client request.
+pub fn parse_request(text: &str) -> Result<ControlRequest, String> {
+    if text.len() > MAX_MESSAGE_BYTES {
+        return Err(format!(
+            "Message exceeds the {} byte limit",
+            MAX_MESSAGE_BYTES
+        ));
+    }
+    let request: ControlRequest =
+        serde_json::from_str(text).map_err(|e| format!("Invalid request: {}", e))?;
+    request.command.validate()?;
+    Ok(request)
+}
+
+/// Best-effort request id from a message that failed to parse, so the error
+/// reply can still be matched to it.
+pub fn request_id(text: &str) -> Option<u64> {
+    serde_json::from_str::<serde_json::Value>(text)
+        .ok()?
+        .get("id")?
+        .as_u64()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_commands_with_and_without_ids() {
+        let request = parse_request(
+            r#"{"id":7,"command":{"type":"move_absolute","pan":0.5,"tilt":-0.25,"zoom":1.0}}"#,
+        )
+        .unwrap();
+        assert_eq!(request.id, Some(7));
+        assert_eq!(
+            request.command,
+            ControlCommand::MoveAbsolute {
+                pan: 0.5,
+                tilt: -0.25,
+                zoom: 1.0
+            }
+        );
+
+        let request = parse_request(r#"{"command":{"type":"stop"}}"#).unwrap();
+        assert_eq!(request.id, None);
+        assert_eq!(request.command, ControlCommand::Stop);
+
+        let request = parse_request(r#"{"command":{"type":"recall_preset","number":3}}"#).unwrap();
+        assert_eq!(request.command, ControlCommand::RecallPreset { number: 3 });
+    }
+
+    #[test]
+    fn rejects_unknown_malformed_and_out_of_range_requests() {
+        assert!(parse_request("not json").is_err());
+        assert!(parse_request(r#"{"command":{"type":"self_destruct"}}"#).is_err());
+        assert!(parse_request(r#"{"command":{"type":"zoom"}}"#).is_err());
+        assert!(parse_request(r#"{"command":{"type":"zoom","zoom":0.5,"speed":1}}"#).is_err());
+        assert!(parse_request(r#"{"command":{"type":"stop"},"extra":true}"#).is_err());
+        assert!(parse_request(r#"{"command":{"type":"zoom","zoom":1.5}}"#).is_err());
+        assert!(parse_request(
+            r#"{"command":{"type":"continuous_move","pan_speed":-2,"tilt_speed":0}}"#
+        )
+        .is_err());
+        assert!(parse_request(r#"{"command":{"type":"recall_preset","number":0}}"#).is_err());
+        assert!(parse_request(r#"{"command":{"type":"recall_preset","number":-1}}"#).is_err());
+
+        let oversized = format!(
+            r#"{{"command":{{"type":"stop"}},"pad":"{}"}}"#,
+            "x".repeat(MAX_MESSAGE_BYTES)
+        );
+        assert!(parse_request(&oversized).unwrap_err().contains("limit"));
+    }
+
+    #[test]
+    fn failed_requests_keep_their_id() {
+        assert_eq!(request_id(r#"{"id":4,"command":{"type":"nope"}}"#), Some(4));
+        assert_eq!(request_id("garbage"), None);
+    }
+
+    #[test]
+    fn server_messages_serialize_with_type_tags() {
+        let position = PtzPosition {
+            pan: 0.1,
+            tilt: 0.2,
+            zoom: 0.3,
+        };
+        let json = ServerMessage::success(Some(1), Some(position.clone())).to_json();
+        assert_eq!(
+            json,
+            r#"{"type":"response","id":1,"ok":true,"position":{"pan":0.1,"tilt":0.2,"zoom":0.3}}"#
+        );
+        let json = ServerMessage::failure(None, "Not connected").to_json();
+        assert_eq!(
+            json,
+            r#"{"type":"response","id":null,"ok":false,"error":"Not connected"}"#
+        );
+        let pushed = ServerMessage::Position { position };
+        let round_trip: ServerMessage = serde_json::from_str(&pushed.to_json()).unwrap();
+        assert_eq!(round_trip, pushed);
+    }
+}
@@ -0,0 +1,334 @@
+use super::message::{self, ControlCommand, ServerMessage, MAX_MESSAGE_BYTES};
+use crate::ptz::types::PtzPosition;
+use crate::video::mjpeg_server::ServerOptions;
+use axum::{
+    body::Body,
+    extract::ws::{close_code, CloseFrame, Message, WebSocket, WebSocketUpgrade},
+    extract::Request,
+    http::{header, StatusCode},
+    middleware::{self, Next},
+    response::Response,
+    routing::get,
+    Router,
+};
+use std::future::Future;
+use std::net::SocketAddr;
+use std::pin::Pin;
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::sync::{watch, Mutex, Semaphore};
+use tokio::time::Instant;
+
+/// Most clients connected at once; further upgrades are refused.
+pub const MAX_CONNECTIONS: usize = 8;
+
+/// How often each client is sent the camera position, if it changed.
+const POSITION_INTERVAL: Duration = Duration::from_millis(100);
+
+type CommandFuture = Pin<Box<dyn Future<Output = Result<Option<PtzPosition>, String>> + Send>>;
+
+/// Runs a client's command, returning the camera position for position
+/// queries.
+type CommandHandler = Arc<dyn Fn(ControlCommand) -> CommandFuture + Send + Sync>;
+
+struct ControlState {
+    on_command: CommandHandler,
+    position: Arc<Mutex<PtzPosition>>,
+    connections: Arc<Semaphore>,
+    /// Browser origins allowed to connect, e.g. `http://localhost:1420`.
+    allowed_origins: Vec<String>,
+    shutdown: watch::Receiver<bool>,
+}
+
+/// Whether a request's `Origin` header may open a control connection.
+/// Browsers don't apply CORS to WebSockets, so without this any page open
+/// on the operator's machine could drive the camera. Requests without an
+/// `Origin` come from non-browser clients and are allowed; `*` in the list
+/// allows every origin.
+pub fn origin_allowed(origin: Option<&str>, allowed_origins: &[String]) -> bool {
+    let Some(origin) = origin else {
+        return true;
+    };
+    let origin = origin.trim().trim_end_matches('/');
+    allowed_origins.iter().any(|allowed| {
+        let allowed = allowed.trim().trim_end_matches('/');
+        allowed == "*" || allowed.eq_ignore_ascii_case(origin)
+    })
+}
+
+/// Refuse requests from origins not in the allowed list before upgrading.
+async fn check_origin(
+    state: axum::extract::State<Arc<ControlState>>,
+    request: Request,
+    next: Next,
+) -> Response<Body> {
+    let origin = request.headers().get(header::ORIGIN).map(|v| v.to_str());
+    let allowed = match origin {
+        None => origin_allowed(None, &state.allowed_origins),
+        Some(Ok(origin)) => origin_allowed(Some(origin), &state.allowed_origins),
+        Some(Err(_)) => false,
+    };
+    if !allowed {
+        log::warn!("Refused control connection from origin {:?}", origin);
+        return Response::builder()
+            .status(StatusCode::FORBIDDEN)
+            .body(Body::from("Origin not allowed"))
+            .unwrap();
+    }
+    next.run(request).await
+}
+
+/// Parse a text message, run it and build the reply.
+async fn handle_text(on_command: &CommandHandler, text: &str) -> ServerMessage {
+    let request = match message::parse_request(text) {
+        Ok(request) => request,
+        Err(e) => return ServerMessage::failure(message::request_id(text), e),
+    };
+    match on_command(request.command).await {
+        Ok(position) => ServerMessage::success(request.id, position),
+        Err(e) => ServerMessage::failure(request.id, e),
+    }
+}
+
+async fn send(socket: &mut WebSocket, message: &ServerMessage) -> bool {
+    socket
+        .send(Message::Text(message.to_json().into()))
+        .await
+        .is_ok()
+}
+
+/// Serve one client until it disconnects or the server shuts down.
+async fn run_connection(mut socket: WebSocket, state: Arc<ControlState>) {
+    let mut shutdown = state.shutdown.clone();
+    let mut last_position: Option<PtzPosition> = None;
+    let mut next_push = Instant::now();
+    loop {
+        if *shutdown.borrow_and_update() {
+            let _ = socket
+                .send(Message::Close(Some(CloseFrame {
+                    code: close_code::AWAY,
+                    reason: "Server shutting down".into(),
+                })))
+                .await;
+            break;
+        }
+
+        if Instant::now() >= next_push {
+            next_push = Instant::now() + POSITION_INTERVAL;
+            let position = state.position.lock().await.clone();
+            if last_position.as_ref() != Some(&position) {
+                if !send(
+                    &mut socket,
+                    &ServerMessage::Position {
+                        position: position.clone(),
+                    },
+                )
+                .await
+                {
+                    break;
+                }
+                last_position = Some(position);
+            }
+        }
+
+        let received = match tokio::time::timeout_at(next_push, socket.recv()).await {
+            Err(_) => continue,
+            Ok(None) => break,
+            Ok(Some(Err(e))) => {
+                log::debug!("WebSocket control client error: {}", e);
+                break;
+            }
+            Ok(Some(Ok(received))) => received,
+        };
+        let reply = match received {
+            Message::Text(text) => handle_text(&state.on_command, text.as_str()).await,
+            Message::Binary(_) => ServerMessage::failure(None, "Only text messages are accepted"),
+            Message::Close(_) => break,
+            // Pings are answered by the transport
+            Message::Ping(_) | Message::Pong(_) => continue,
+        };
+        if !send(&mut socket, &reply).await {
+            break;
+        }
+    }
+}
+
+/// Upgrade to a WebSocket, unless the connection limit is reached.
+async fn upgrade_handler(
+    state: axum::extract::State<Arc<ControlState>>,
+    ws: WebSocketUpgrade,
+) -> Response<Body> {
+    let Ok(permit) = state.connections.clone().try_acquire_owned() else {
+        return Response::builder()
+            .status(StatusCode::SERVICE_UNAVAILABLE)
+            .body(Body::from("Too many control clients connected"))
+            .unwrap();
+    };
+    let state = state.0.clone();
+    ws.max_message_size(MAX_MESSAGE_BYTES)
+        .on_upgrade(move |socket| async move {
+            run_connection(socket, state).await;
+            drop(permit);
+        })
+}
+
+/// Start the WebSocket control server. Clients connect to `/control`, send
+/// JSON requests and receive replies plus position updates read from
+/// `position`. `on_command` runs each valid request. Browser clients must
+/// connect from one of `allowed_origins`.
+/// Returns the bound port and a shutdown sender.
+pub async fn start_server<F, Fut>(
+    position: Arc<Mutex<PtzPosition>>,
+    options: &ServerOptions,
+    allowed_origins: Vec<String>,
+    on_command: F,
+) -> Result<(u16, watch::Sender<bool>), String>
+where
+    F: Fn(ControlCommand) -> Fut + Send + Sync + 'static,
+    Fut: Future<Output = Result<Option<PtzPosition>, String>> + Send + 'static,
+{
+    let addr = SocketAddr::new(options.bind_address, options.port.unwrap_or(0));
+    let listener = tokio::net::TcpListener::bind(addr)
+        .await
+        .map_err(|e| format!("Failed to bind control server to {}: {}", addr, e))?;
+    let port = listener.local_addr().map_err(|e| e.to_string())?.port();
+
+    let (shutdown_tx, mut shutdown_rx) = watch::channel(false);
+    let state = Arc::new(ControlState {
+        on_command: Arc::new(move |command| Box::pin(on_command(command))),
+        position,
+        connections: Arc::new(Semaphore::new(MAX_CONNECTIONS)),
+        allowed_origins,
+        shutdown: shutdown_tx.subscribe(),
+    });
+    let app = Router::new()
+        .route("/control", get(upgrade_handler))
+        .route_layer(middleware::from_fn_with_state(state.clone(), check_origin))
+        .with_state(state);
+
+    tokio::spawn(async move {
+        if let Err(e) = axum::serve(listener, app)
+            .with_graceful_shutdown(async move {
+                // Wait until shutdown signal is received
+                while !*shutdown_rx.borrow_and_update() {
+                    if shutdown_rx.changed().await.is_err() {
+                        break;
+                    }
+                }
+            })
+            .await
+        {
+            log::error!("Control server error on port {}: {}", port, e);
+        }
+        log::info!("Control server on port {} shut down", port);
+    });
+
+    log::info!(
+        "Control server started on {}:{}",
+        options.bind_address,
+        port
+    );
+    Ok((port, shutdown_tx))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn handler(
+        run: impl Fn(ControlCommand) -> Result<Option<PtzPosition>, String> + Send + Sync + 'static,
+    ) -> CommandHandler {
+        Arc::new(move |command| {
+            let result = run(command);
+            Box::pin(async move { result })
+        })
+    }
+
+    #[tokio::test]
+    async fn replies_echo_ids_and_carry_command_results() {
+        let seen = Arc::new(std::sync::Mutex::new(Vec::new()));
+        let on_command = handler({
+            let seen = seen.clone();
+            move |command| {
+                seen.lock().unwrap().push(command.clone());
+                match command {
+                    ControlCommand::GetPosition => Ok(Some(PtzPosition {
+                        pan: 0.5,
+                        tilt: 0.0,
+                        zoom: 0.2,
+                    })),
+                    ControlCommand::Home => Err("No active endpoint".to_string()),
+                    _ => Ok(None),
+                }
+            }
+        });
+
+        let reply = handle_text(&on_command, r#"{"id":3,"command":{"type":"get_position"}}"#).await;
+        assert!(matches!(
+            reply,
+            ServerMessage::Response { id: Some(3), ok: true, position: Some(ref p), .. } if p.pan == 0.5
+        ));
+
+        let reply = handle_text(&on_command, r#"{"id":4,"command":{"type":"home"}}"#).await;
+        assert_eq!(reply, ServerMessage::failure(Some(4), "No active endpoint"));
+
+        let reply = handle_text(&on_command, r#"{"id":5,"command":{"type":"warp"}}"#).await;
+        assert!(matches!(
+            reply,
+            ServerMessage::Response {
+                id: Some(5),
+                ok: false,
+                ..
+            }
+        ));
+        // Invalid requests never reach the command layer
+        assert_eq!(
+            *seen.lock().unwrap(),
+            [ControlCommand::GetPosition, ControlCommand::Home]
+        );
+    }
+
+    #[test]
+    fn origins_must_be_in_the_allowed_list() {
+        let allowed = vec!["http://localhost:1420/".to_string()];
+        assert!(origin_allowed(None, &allowed));
+        assert!(origin_allowed(Some("http://localhost:1420"), &allowed));
+        assert!(origin_allowed(Some("HTTP://LOCALHOST:1420"), &allowed));
+        assert!(!origin_allowed(Some("https://evil.example"), &allowed));
+        assert!(!origin_allowed(Some("http://localhost:1421"), &allowed));
+        assert!(!origin_allowed(Some("null"), &[]));
+        assert!(origin_allowed(
+            Some("https://evil.example"),
+            &["*".to_string()]
+        ));
+    }
+
+    #[tokio::test]
+    async fn other_origins_are_refused_before_upgrading() {
+        let (port, shutdown) = start_server(
+            Arc::new(Mutex::new(PtzPosition {
+                pan: 0.0,
+                tilt: 0.0,
+                zoom: 0.0,
+            })),
+            &ServerOptions::default(),
+            vec!["http://localhost:1420".to_string()],
+            |_| async { Ok(None) },
+        )
+        .await
+        .unwrap();
+
+        let response = reqwest::Client::new()
+            .get(format!("http://127.0.0.1:{}/control", port))
+            .header("Origin", "https://evil.example")
+            .header("Connection", "Upgrade")
+            .header("Upgrade", "websocket")
+            .header("Sec-WebSocket-Version", "13")
+            .header("Sec-WebSocket-Key", "dGhlIHNhbXBsZSBub25jZQ==")
+            .send()
+            .await
+            .unwrap();
+        assert_eq!(response.status(), reqwest::StatusCode::FORBIDDEN);
+        let _ = shutdown.send(true);
+    }
+}
@@ -10,6 +10,7 @@ import { useAppStore } from "./store/app-store";
 import { usePresets } from "./hooks/usePresets";
 import { useEndpoints } from "./hooks/useEndpoints";
 import { useKeyboardShortcuts } from "./hooks/useKeyboardShortcuts";
+import { usePositionEvents } from "./hooks/usePositionEvents";
 
 export default function App() {
   const sidebarCollapsed = useAppStore((s) => s.sidebarCollapsed);
@@ -18,6 +19,7 @@ export default function App() {
   const { loadEndpoints } = useEndpoints();
 
   useKeyboardShortcuts();
+  usePositionEvents();
 
   useEffect(() => {
     loadPresets();
@@ -1,6 +1,11 @@
 import { useState } from "react";
 import { useEndpoints } from "../hooks/useEndpoints";
-import type { CameraEndpoint, ProtocolConfig, PtzProtocol } from "@shared/types";
+import type {
+  CameraEndpoint,
+  ProtocolConfig,
+  PtzProtocol,
+  RelativeMode,
+} from "@shared/types";
 
 function generateId(): string {
   return crypto.randomUUID?.() ?? Math.random().toString(36).slice(2);
@@ -22,7 +27,10 @@ export function EndpointManager() {
     null
   );
   const [isNew, setIsNew] = useState(false);
-  const [testResult, setTestResult] = useState<string | null>(null);
+  const [testResult, setTestResult] = useState<{
+    ok: boolean;
+    message: string;
+  } | null>(null);
   const [testing, setTesting] = useState(false);
 
   const startNewEndpoint = () => {
@@ -39,13 +47,17 @@ export function EndpointManager() {
   const getConfigForProtocol = (protocol: PtzProtocol): ProtocolConfig => {
     switch (protocol) {
       case "Ndi":
-        return { type: "Ndi" };
+        return { type: "Ndi", source_name: "" };
       case "Visca":
         return { type: "Visca", host: "192.168.1.100", port: 52381 };
       case "PanasonicAw":
         return { type: "PanasonicAw", host: "192.168.1.100", port: 80 };
       case "BirdDogRest":
         return { type: "BirdDogRest", host: "192.168.1.100", port: 8080 };
+      case "PelcoD":
+        return { type: "PelcoD", host: "192.168.1.100", port: 4001, address: 1 };
+      case "Onvif":
+        return { type: "Onvif", host: "192.168.1.100", port: 80 };
       case "Simulated":
         return { type: "Simulated" };
     }
@@ -71,7 +83,7 @@ export function EndpointManager() {
       setEditingEndpoint(null);
       setIsNew(false);
     } catch (err) {
-      setTestResult(`Error saving: ${err}`);
+      setTestResult({ ok: false, message: `Error saving: ${err}` });
     }
   };
 
@@ -81,9 +93,14 @@ export function EndpointManager() {
     setTestResult(null);
     try {
       const result = await testConnection(editingEndpoint.config);
-      setTestResult(result);
+      setTestResult({
+        ok: result.ok,
+        message: result.ok
+          ? `${result.message} in ${result.latency_ms} ms`
+          : result.message,
+      });
     } catch (err) {
-      setTestResult(`Error: ${err}`);
+      setTestResult({ ok: false, message: `Error: ${err}` });
     } finally {
       setTesting(false);
     }
@@ -194,6 +211,8 @@ export function EndpointManager() {
               <option value="Visca">VISCA-over-IP</option>
               <option value="PanasonicAw">Panasonic AW (HTTP)</option>
               <option value="BirdDogRest">BirdDog REST API</option>
+              <option value="PelcoD">Pelco-D (serial gateway)</option>
+              <option value="Onvif">ONVIF</option>
               <option value="Simulated">Simulated (no hardware)</option>
             </select>
           </div>
@@ -246,8 +265,56 @@ export function EndpointManager() {
               </div>
             )}
 
-          {/* Panasonic auth fields */}
-          {editingEndpoint.config.type === "PanasonicAw" && (
+          {/* NDI source name */}
+          {editingEndpoint.config.type === "Ndi" && (
+            <div>
+              <label className="block text-xs text-[var(--color-text-muted)] mb-1">
+                NDI Source Name
+              </label>
+              <input
+                type="text"
+                value={editingEndpoint.config.source_name ?? ""}
+                placeholder="CAMERA (Channel 1)"
+                onChange={(e) =>
+                  setEditingEndpoint({
+                    ...editingEndpoint,
+                    config: { type: "Ndi", source_name: e.target.value },
+                  })
+                }
+                className="w-full px-2 py-1.5 text-sm bg-[var(--color-bg-dark)] border border-[var(--color-border)] rounded text-[var(--color-text)] focus:outline-none focus:border-[var(--color-primary)]"
+              />
+            </div>
+          )}
+
+          {/* Pelco-D receiver address */}
+          {editingEndpoint.config.type === "PelcoD" && (
+            <div>
+              <label className="block text-xs text-[var(--color-text-muted)] mb-1">
+                Camera Address
+              </label>
+              <input
+                type="number"
+                value={editingEndpoint.config.address}
+                onChange={(e) => {
+                  const parsed = parseInt(e.target.value, 10);
+                  if (!isNaN(parsed)) {
+                    setEditingEndpoint({
+                      ...editingEndpoint,
+                      config: {
+                        ...editingEndpoint.config,
+                        address: Math.max(1, Math.min(255, parsed)),
+                      } as ProtocolConfig,
+                    });
+                  }
+                }}
+                className="w-full px-2 py-1.5 text-sm bg-[var(--color-bg-dark)] border border-[var(--color-border)] rounded text-[var(--color-text)] focus:outline-none focus:border-[var(--color-primary)]"
+              />
+            </div>
+          )}
+
+          {/* Panasonic and ONVIF auth fields */}
+          {(editingEndpoint.config.type === "PanasonicAw" ||
+            editingEndpoint.config.type === "Onvif") && (
             <div className="grid grid-cols-2 gap-3">
               <div>
                 <label className="block text-xs text-[var(--color-text-muted)] mb-1">
@@ -290,16 +357,66 @@ export function EndpointManager() {
             </div>
           )}
 
+          {/* Panasonic relative-move strategy */}
+          {editingEndpoint.config.type === "PanasonicAw" && (
+            <div>
+              <label className="block text-xs text-[var(--color-text-muted)] mb-1">
+                Relative Moves
+              </label>
+              <select
+                value={editingEndpoint.config.relative_mode ?? "NativeSpeed"}
+                onChange={(e) =>
+                  setEditingEndpoint({
+                    ...editingEndpoint,
+                    config: {
+                      ...editingEndpoint.config,
+                      relative_mode: e.target.value as RelativeMode,
+                    } as ProtocolConfig,
+                  })
+                }
+                className="w-full px-2 py-1.5 text-sm bg-[var(--color-bg-dark)] border border-[var(--color-border)] rounded text-[var(--color-text)] focus:outline-none focus:border-[var(--color-primary)]"
+              >
+                <option value="NativeSpeed">Speed burst (fast)</option>
+                <option value="ViaAbsolute">Via absolute position (repeatable)</option>
+              </select>
+            </div>
+          )}
+
+          {/* Panasonic APS speed range, which depends on firmware */}
+          {editingEndpoint.config.type === "PanasonicAw" && (
+            <div>
+              <label className="block text-xs text-[var(--color-text-muted)] mb-1">
+                Move Speed Range
+              </label>
+              <select
+                value={editingEndpoint.config.move_speed_max === 0x29 ? "extended" : "standard"}
+                onChange={(e) =>
+                  setEditingEndpoint({
+                    ...editingEndpoint,
+                    config: {
+                      ...editingEndpoint.config,
+                      move_speed_max: e.target.value === "extended" ? 0x29 : null,
+                    } as ProtocolConfig,
+                  })
+                }
+                className="w-full px-2 py-1.5 text-sm bg-[var(--color-bg-dark)] border border-[var(--color-border)] rounded text-[var(--color-text)] focus:outline-none focus:border-[var(--color-primary)]"
+              >
+                <option value="standard">Standard firmware (00-1D)</option>
+                <option value="extended">Extended firmware (00-29)</option>
+              </select>
+            </div>
+          )}
+
           {/* Test result */}
           {testResult && (
             <div
               className={`text-xs p-2 rounded ${
-                testResult.startsWith("Error")
-                  ? "bg-red-900/30 text-red-300"
-                  : "bg-green-900/30 text-green-300"
+                testResult.ok
+                  ? "bg-green-900/30 text-green-300"
+                  : "bg-red-900/30 text-red-300"
               }`}
             >
-              {testResult}
+              {testResult.message}
             </div>
           )}
 
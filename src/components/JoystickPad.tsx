@@ -1,18 +1,26 @@
-import { useCallback, useRef, useState } from "react";
+import { useCallback, useEffect, useRef, useState } from "react";
 import { usePtzControl } from "../hooks/usePtzControl";
 
 const PAD_SIZE = 160;
 const THUMB_RADIUS = 14;
 const OUTER_RADIUS = PAD_SIZE / 2;
 const THROTTLE_MS = 50;
+// Keeps the backend's deadman from stopping a jog held in place
+const KEEPALIVE_MS = 100;
 
 export function JoystickPad() {
-  const { continuousMove, stop } = usePtzControl();
+  const { continuousMove, continuousMoveKeepalive, stop } = usePtzControl();
   const containerRef = useRef<HTMLDivElement>(null);
   const lastSendTime = useRef(0);
   const [thumbPos, setThumbPos] = useState({ x: 0, y: 0 });
   const [active, setActive] = useState(false);
 
+  useEffect(() => {
+    if (!active) return;
+    const timer = setInterval(continuousMoveKeepalive, KEEPALIVE_MS);
+    return () => clearInterval(timer);
+  }, [active, continuousMoveKeepalive]);
+
   const getOffset = useCallback(
     (clientX: number, clientY: number) => {
       if (!containerRef.current) return { x: 0, y: 0 };
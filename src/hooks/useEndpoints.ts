@@ -1,7 +1,11 @@
 import { useCallback } from "react";
 import { invoke } from "@tauri-apps/api/core";
 import { useAppStore } from "../store/app-store";
-import type { CameraEndpoint, ProtocolConfig } from "@shared/types";
+import type {
+  CameraEndpoint,
+  ConnectionTestResult,
+  ProtocolConfig,
+} from "@shared/types";
 
 export function useEndpoints() {
   const endpoints = useAppStore((s) => s.endpoints);
@@ -93,8 +97,10 @@ export function useEndpoints() {
   }, [setActiveEndpointId]);
 
   const testConnection = useCallback(
-    async (config: ProtocolConfig): Promise<string> => {
-      return invoke<string>("test_endpoint_connection", { config });
+    async (config: ProtocolConfig): Promise<ConnectionTestResult> => {
+      return invoke<ConnectionTestResult>("test_endpoint_connection", {
+        config,
+      });
     },
     []
   );
@@ -2,6 +2,17 @@ import { useEffect } from "react";
 import { usePtzControl } from "./usePtzControl";
 import { useAppStore } from "../store/app-store";
 
+/** Describe a key press the way preset hotkeys are written, e.g. "Ctrl+1". */
+function hotkeyName(e: KeyboardEvent): string {
+  const parts: string[] = [];
+  if (e.ctrlKey) parts.push("Ctrl");
+  if (e.altKey) parts.push("Alt");
+  if (e.shiftKey) parts.push("Shift");
+  if (e.metaKey) parts.push("Meta");
+  parts.push(e.key.length === 1 ? e.key.toUpperCase() : e.key);
+  return parts.join("+");
+}
+
 export function useKeyboardShortcuts() {
   const {
     moveRelative,
@@ -22,6 +33,17 @@ export function useKeyboardShortcuts() {
         return;
       }
 
+      // Preset hotkeys take precedence over the built-in shortcuts
+      const name = hotkeyName(e).toLowerCase();
+      const bound = useAppStore
+        .getState()
+        .presets.find((p) => p.hotkey?.toLowerCase() === name);
+      if (bound) {
+        e.preventDefault();
+        recallPreset(bound.id);
+        return;
+      }
+
       const step = e.shiftKey ? 0.01 : e.ctrlKey || e.metaKey ? 0.15 : 0.05;
 
       switch (e.key) {
@@ -0,0 +1,18 @@
+import { useEffect } from "react";
+import { listen } from "@tauri-apps/api/event";
+import { useAppStore } from "../store/app-store";
+import type { PtzPosition } from "@shared/types";
+
+/** Keeps the store's position in sync with the backend position poller. */
+export function usePositionEvents() {
+  const setCurrentPosition = useAppStore((s) => s.setCurrentPosition);
+
+  useEffect(() => {
+    const unlisten = listen<PtzPosition>("ptz-position-changed", (event) => {
+      setCurrentPosition(event.payload);
+    });
+    return () => {
+      unlisten.then((fn) => fn());
+    };
+  }, [setCurrentPosition]);
+}
@@ -1,8 +1,7 @@
 import { useCallback, useRef } from "react";
 import { invoke } from "@tauri-apps/api/core";
 import { useAppStore } from "../store/app-store";
-import { calculateClickVector } from "../utils/ptz-math";
-import type { PtzPosition } from "@shared/types";
+import type { ClickDelta, PtzPosition } from "@shared/types";
 
 const MIN_COMMAND_INTERVAL_MS = 100;
 
@@ -102,6 +101,14 @@ export function usePtzControl() {
     [throttle]
   );
 
+  const continuousMoveKeepalive = useCallback(async () => {
+    try {
+      await invoke("ptz_continuous_move_keepalive");
+    } catch (err) {
+      console.error("PTZ keepalive failed:", err);
+    }
+  }, []);
+
   const stop = useCallback(async () => {
     try {
       await invoke("ptz_stop");
@@ -146,15 +153,26 @@ export function usePtzControl() {
   }, []);
 
   const handleVideoClick = useCallback(
-    (clickX: number, clickY: number, canvasWidth: number, canvasHeight: number) => {
-      const pos = useAppStore.getState().currentPosition;
-      const { panDelta, tiltDelta } = calculateClickVector(
-        clickX, clickY, canvasWidth, canvasHeight,
-        settings.click_sensitivity, pos.zoom
-      );
-      moveRelative(panDelta, tiltDelta);
+    async (clickX: number, clickY: number, canvasWidth: number, canvasHeight: number) => {
+      if (!throttle()) return;
+      try {
+        const delta = await invoke<ClickDelta>("ptz_click_move", {
+          clickX,
+          clickY,
+          frameWidth: canvasWidth,
+          frameHeight: canvasHeight,
+        });
+        const pos = useAppStore.getState().currentPosition;
+        setCurrentPosition({
+          pan: Math.max(-1, Math.min(1, pos.pan + delta.pan_delta)),
+          tilt: Math.max(-1, Math.min(1, pos.tilt + delta.tilt_delta)),
+          zoom: pos.zoom,
+        });
+      } catch (err) {
+        console.error("PTZ click move failed:", err);
+      }
     },
-    [settings.click_sensitivity, moveRelative]
+    [throttle, setCurrentPosition]
   );
 
   const handleVideoScroll = useCallback(
@@ -181,6 +199,7 @@ export function usePtzControl() {
     recallPreset,
     home,
     continuousMove,
+    continuousMoveKeepalive,
     stop,
     focusContinuous,
     focusStop,
@@ -102,6 +102,7 @@ export function useVideoFeed() {
         // Start MJPEG stream from backend and use returned port
         try {
           const port = await invoke<number>("start_mjpeg_stream");
+          await invoke("start_ndi_preview", { sourceName: activeSource.ndiName });
           const mjpegUrl = `http://127.0.0.1:${port}/stream`;
           connectToMjpeg(mjpegUrl);
         } catch (err) {